@@ -0,0 +1,32 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Python bindings for `RustQuant`, built with `PyO3` and `maturin`.
+//!
+//! The module layout follows the Rust crate: `rustquant.trading` holds the
+//! trading bindings.
+
+#![deny(missing_docs)]
+// PyO3 0.22's `#[pymethods]` expansion converts `PyResult` errors into `PyErr`.
+#![allow(clippy::useless_conversion)]
+
+use pyo3::prelude::*;
+
+/// Trading bindings.
+pub mod trading;
+
+/// The `rustquant` Python module.
+#[pymodule]
+fn rustquant(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let trading = PyModule::new_bound(m.py(), "trading")?;
+    trading::register(&trading)?;
+    m.add_submodule(&trading)?;
+
+    Ok(())
+}
@@ -0,0 +1,558 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Python bindings for the backtester.
+//!
+//! Rust traits cannot be subclassed from Python, so strategies subclass
+//! [`PyStrategy`] (`rustquant.trading.Strategy`) and override `on_bar`,
+//! which the backtest calls back into for every bar. Any object with an
+//! `on_bar(bar) -> list[Order]` method works.
+//!
+//! ```python
+//! from rustquant.trading import Backtest, Bar, Order, Strategy
+//!
+//! class BuyAndHold(Strategy):
+//!     def __init__(self):
+//!         self.invested = False
+//!
+//!     def on_bar(self, bar):
+//!         if self.invested:
+//!             return []
+//!         self.invested = True
+//!         return [Order.buy(10)]
+//!
+//! bars = [Bar(p, p, p, p) for p in [100.0, 110.0, 99.0, 120.0]]
+//! result = Backtest(1000.0).run(BuyAndHold(), bars)
+//! print(result.equity_curve, result.sharpe_ratio, result.max_drawdown)
+//! ```
+//!
+//! `run` also takes an optional `progress(completed, total, equity)`
+//! callable, called every `every` bars. Ctrl-C is checked at the same
+//! points; a `KeyboardInterrupt`, or an exception raised by the callback,
+//! stops the backtest and is re-raised.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use time::OffsetDateTime;
+use RustQuant::data::Bar;
+use RustQuant::progress::ProgressHandle;
+use RustQuant::trading::backtest::{Backtest, BacktestOrder, BacktestResult, Strategy};
+use RustQuant::trading::order_side::OrderSide;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// An OHLCV bar (`rustquant.trading.Bar`), with start and end times in
+/// seconds since the Unix epoch.
+#[pyclass(name = "Bar", module = "rustquant.trading")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PyBar {
+    /// First traded price.
+    #[pyo3(get)]
+    pub open: f64,
+    /// Highest traded price.
+    #[pyo3(get)]
+    pub high: f64,
+    /// Lowest traded price.
+    #[pyo3(get)]
+    pub low: f64,
+    /// Last traded price.
+    #[pyo3(get)]
+    pub close: f64,
+    /// Traded volume.
+    #[pyo3(get)]
+    pub volume: f64,
+    /// Start of the bar (Unix seconds).
+    #[pyo3(get)]
+    pub start: i64,
+    /// End of the bar (Unix seconds).
+    #[pyo3(get)]
+    pub end: i64,
+}
+
+/// A market order (`rustquant.trading.Order`).
+#[pyclass(name = "Order", module = "rustquant.trading")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PyOrder {
+    inner: BacktestOrder,
+}
+
+/// Base class for Python strategies (`rustquant.trading.Strategy`).
+///
+/// The default `on_bar` places no orders.
+#[pyclass(name = "Strategy", module = "rustquant.trading", subclass)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PyStrategy;
+
+/// Backtest settings (`rustquant.trading.Backtest`).
+#[pyclass(name = "Backtest", module = "rustquant.trading")]
+#[derive(Debug, Clone, Copy)]
+pub struct PyBacktest {
+    inner: Backtest,
+}
+
+/// Result of a backtest (`rustquant.trading.BacktestResult`).
+#[pyclass(name = "BacktestResult", module = "rustquant.trading")]
+#[derive(Debug, Clone)]
+pub struct PyBacktestResult {
+    inner: BacktestResult,
+}
+
+/// Rust [`Strategy`] calling back into a Python object's `on_bar`.
+///
+/// The first Python exception is kept, and no more calls are made after it.
+struct PythonStrategy {
+    strategy: Py<PyAny>,
+    error: Option<PyErr>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[pymethods]
+impl PyBar {
+    #[new]
+    #[pyo3(signature = (open, high, low, close, volume = 0.0, start = 0, end = 0))]
+    fn new(open: f64, high: f64, low: f64, close: f64, volume: f64, start: i64, end: i64) -> Self {
+        Self {
+            open,
+            high,
+            low,
+            close,
+            volume,
+            start,
+            end,
+        }
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Bar(open={}, high={}, low={}, close={}, volume={}, start={}, end={})",
+            self.open, self.high, self.low, self.close, self.volume, self.start, self.end
+        )
+    }
+}
+
+impl From<&Bar> for PyBar {
+    fn from(bar: &Bar) -> Self {
+        Self {
+            open: bar.open,
+            high: bar.high,
+            low: bar.low,
+            close: bar.close,
+            volume: bar.volume,
+            start: bar.start.unix_timestamp(),
+            end: bar.end.unix_timestamp(),
+        }
+    }
+}
+
+impl TryFrom<&PyBar> for Bar {
+    type Error = PyErr;
+
+    fn try_from(bar: &PyBar) -> PyResult<Self> {
+        let timestamp = |seconds: i64| {
+            OffsetDateTime::from_unix_timestamp(seconds)
+                .map_err(|e| PyValueError::new_err(e.to_string()))
+        };
+
+        Ok(Self {
+            start: timestamp(bar.start)?,
+            end: timestamp(bar.end)?,
+            open: bar.open,
+            high: bar.high,
+            low: bar.low,
+            close: bar.close,
+            volume: bar.volume,
+            dollar_volume: bar.volume * bar.close,
+            trade_count: 0,
+        })
+    }
+}
+
+#[pymethods]
+impl PyOrder {
+    /// New order on `side` (`"buy"` or `"sell"`) for `quantity` units.
+    #[new]
+    fn new(side: &str, quantity: u64) -> PyResult<Self> {
+        match side.to_lowercase().as_str() {
+            "buy" => Ok(Self::buy(quantity)),
+            "sell" => Ok(Self::sell(quantity)),
+            _ => Err(PyValueError::new_err(format!(
+                "Order side must be 'buy' or 'sell', got '{side}'."
+            ))),
+        }
+    }
+
+    /// Buy `quantity` units.
+    #[staticmethod]
+    fn buy(quantity: u64) -> Self {
+        Self {
+            inner: BacktestOrder::buy(quantity),
+        }
+    }
+
+    /// Sell `quantity` units.
+    #[staticmethod]
+    fn sell(quantity: u64) -> Self {
+        Self {
+            inner: BacktestOrder::sell(quantity),
+        }
+    }
+
+    /// `"buy"` or `"sell"`.
+    #[getter]
+    fn side(&self) -> &'static str {
+        match self.inner.side {
+            OrderSide::BID => "buy",
+            OrderSide::ASK => "sell",
+        }
+    }
+
+    /// Number of units.
+    #[getter]
+    fn quantity(&self) -> u64 {
+        self.inner.quantity
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Order('{}', {})", self.side(), self.inner.quantity)
+    }
+}
+
+#[pymethods]
+impl PyStrategy {
+    #[new]
+    #[pyo3(signature = (*_args, **_kwargs))]
+    fn new(_args: &Bound<'_, PyTuple>, _kwargs: Option<&Bound<'_, PyDict>>) -> Self {
+        Self
+    }
+
+    /// Orders to fill at the close of `bar`; override in subclasses.
+    fn on_bar(&self, _bar: PyBar) -> Vec<PyOrder> {
+        Vec::new()
+    }
+}
+
+impl Strategy for PythonStrategy {
+    fn on_bar(&mut self, bar: &Bar) -> Vec<BacktestOrder> {
+        if self.error.is_some() {
+            return Vec::new();
+        }
+
+        Python::with_gil(|py| {
+            let orders = self
+                .strategy
+                .call_method1(py, "on_bar", (PyBar::from(bar),))
+                .and_then(|orders| orders.extract::<Vec<PyOrder>>(py));
+
+            match orders {
+                Ok(orders) => orders.into_iter().map(|order| order.inner).collect(),
+                Err(error) => {
+                    self.error = Some(error);
+                    Vec::new()
+                }
+            }
+        })
+    }
+}
+
+#[pymethods]
+impl PyBacktest {
+    #[new]
+    #[pyo3(signature = (initial_cash, commission = 0.0, periods_per_year = 252.0))]
+    fn new(initial_cash: f64, commission: f64, periods_per_year: f64) -> Self {
+        Self {
+            inner: Backtest::new(initial_cash)
+                .with_commission(commission)
+                .with_periods_per_year(periods_per_year),
+        }
+    }
+
+    /// Run `strategy` over `bars`, calling `strategy.on_bar` for each bar.
+    ///
+    /// Every `every` bars, checks for Ctrl-C and calls
+    /// `progress(completed, total, equity)` if given. Exceptions raised by
+    /// the strategy or the callback, and `KeyboardInterrupt`, are re-raised.
+    #[pyo3(signature = (strategy, bars, progress = None, every = 1))]
+    fn run(
+        &self,
+        strategy: Bound<'_, PyAny>,
+        bars: Vec<PyBar>,
+        progress: Option<Bound<'_, PyAny>>,
+        every: usize,
+    ) -> PyResult<PyBacktestResult> {
+        if every == 0 {
+            return Err(PyValueError::new_err("`every` must be positive."));
+        }
+
+        let bars = bars
+            .iter()
+            .map(Bar::try_from)
+            .collect::<PyResult<Vec<Bar>>>()?;
+
+        let py = strategy.py();
+        let mut strategy = PythonStrategy {
+            strategy: strategy.unbind(),
+            error: None,
+        };
+
+        // The first error from the callback or a signal, which cancels the run.
+        let progress_error = RefCell::new(None);
+        let cancel = Arc::new(AtomicBool::new(false));
+        let token = Arc::clone(&cancel);
+
+        let mut handle = ProgressHandle::new()
+            .with_callback(every, |completed, total, equity| {
+                let status = py.check_signals().and_then(|()| match &progress {
+                    Some(callback) => callback.call1((completed, total, equity)).map(drop),
+                    None => Ok(()),
+                });
+
+                if let Err(error) = status {
+                    progress_error.borrow_mut().get_or_insert(error);
+                    token.store(true, Ordering::Relaxed);
+                }
+            })
+            .with_cancellation(cancel);
+
+        let result = self
+            .inner
+            .run_with_progress(&mut strategy, &bars, &mut handle)
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        drop(handle);
+
+        match strategy.error.or(progress_error.into_inner()) {
+            Some(error) => Err(error),
+            None => Ok(PyBacktestResult {
+                inner: result.into_inner(),
+            }),
+        }
+    }
+}
+
+#[pymethods]
+impl PyBacktestResult {
+    /// Equity after each bar.
+    #[getter]
+    fn equity_curve(&self) -> Vec<f64> {
+        self.inner.equity_curve.clone()
+    }
+
+    /// Annualised Sharpe ratio of the bar returns.
+    #[getter]
+    fn sharpe_ratio(&self) -> f64 {
+        self.inner.sharpe_ratio()
+    }
+
+    /// Maximum drawdown, as a positive fraction of the running peak.
+    #[getter]
+    fn max_drawdown(&self) -> f64 {
+        self.inner.max_drawdown()
+    }
+
+    /// Number of executed orders.
+    #[getter]
+    fn total_trades(&self) -> usize {
+        self.inner.total_trades()
+    }
+
+    /// Total return over the backtest.
+    #[getter]
+    fn total_return(&self) -> f64 {
+        self.inner.total_return()
+    }
+
+    /// Position after the last bar.
+    #[getter]
+    fn final_position(&self) -> i64 {
+        self.inner.final_position
+    }
+}
+
+/// Add the backtest classes to `m`.
+///
+/// # Errors
+/// Errors if a class cannot be added to the module.
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyBar>()?;
+    m.add_class::<PyOrder>()?;
+    m.add_class::<PyStrategy>()?;
+    m.add_class::<PyBacktest>()?;
+    m.add_class::<PyBacktestResult>()?;
+
+    Ok(())
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_backtest_bindings {
+    use super::*;
+
+    /// Run `code` with the backtest classes in scope as `trading`.
+    fn run_python(code: &str) -> PyResult<()> {
+        Python::with_gil(|py| {
+            let trading = PyModule::new_bound(py, "trading")?;
+            register(&trading)?;
+
+            let globals = PyDict::new_bound(py);
+            globals.set_item("trading", trading)?;
+
+            py.run_bound(code, Some(&globals), None)
+        })
+    }
+
+    #[test]
+    fn test_python_buy_and_hold() {
+        run_python(
+            r#"
+class BuyAndHold(trading.Strategy):
+    def __init__(self, quantity):
+        self.quantity = quantity
+        self.invested = False
+        self.seen = []
+
+    def on_bar(self, bar):
+        self.seen.append(bar.close)
+        if self.invested:
+            return []
+        self.invested = True
+        return [trading.Order.buy(self.quantity)]
+
+closes = [100.0, 110.0, 99.0, 120.0]
+bars = [trading.Bar(c, c, c, c, start=86400 * i, end=86400 * (i + 1)) for i, c in enumerate(closes)]
+
+strategy = BuyAndHold(10)
+result = trading.Backtest(1000.0).run(strategy, bars)
+
+assert strategy.seen == closes
+assert result.equity_curve == [1000.0, 1100.0, 990.0, 1200.0], result.equity_curve
+assert result.total_trades == 1
+assert result.final_position == 10
+assert abs(result.max_drawdown - 0.1) < 1e-12
+assert abs(result.total_return - 0.2) < 1e-12
+assert result.sharpe_ratio > 0.0
+"#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_python_round_trip_and_defaults() {
+        run_python(
+            r#"
+class Flip(trading.Strategy):
+    def __init__(self):
+        self.n = 0
+
+    def on_bar(self, bar):
+        self.n += 1
+        if self.n == 1:
+            return [trading.Order("buy", 5)]
+        if self.n == 2:
+            return [trading.Order("sell", 5)]
+        return []
+
+bars = [trading.Bar(c, c, c, c) for c in [10.0, 12.0, 8.0]]
+
+result = trading.Backtest(100.0, commission=0.01).run(Flip(), bars)
+assert result.total_trades == 2
+assert result.final_position == 0
+assert abs(result.equity_curve[-1] - (100.0 + 10.0 - 0.5 - 0.6)) < 1e-12
+
+idle = trading.Backtest(100.0).run(trading.Strategy(), bars)
+assert idle.equity_curve == [100.0] * 3
+assert idle.total_trades == 0
+
+try:
+    trading.Order("hold", 1)
+    raise AssertionError("expected a ValueError")
+except ValueError:
+    pass
+"#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_python_progress_callback() {
+        run_python(
+            r#"
+bars = [trading.Bar(c, c, c, c) for c in range(1, 96)]
+
+calls = []
+result = trading.Backtest(100.0).run(
+    trading.Strategy(), bars, progress=lambda *args: calls.append(args), every=10
+)
+assert len(calls) == 10, calls
+assert calls[0] == (10, 95, 100.0)
+assert calls[-1] == (95, 95, 100.0)
+assert len(result.equity_curve) == 95
+
+class Counter(trading.Strategy):
+    def __init__(self):
+        self.n = 0
+
+    def on_bar(self, bar):
+        self.n += 1
+        return []
+
+def stop(completed, total, equity):
+    if completed == 30:
+        raise KeyboardInterrupt
+
+counter = Counter()
+try:
+    trading.Backtest(100.0).run(counter, bars, progress=stop)
+    raise AssertionError("expected a KeyboardInterrupt")
+except KeyboardInterrupt:
+    pass
+assert counter.n == 30, counter.n
+
+try:
+    trading.Backtest(100.0).run(counter, bars, every=0)
+    raise AssertionError("expected a ValueError")
+except ValueError:
+    pass
+"#,
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_python_exceptions_propagate() {
+        let error = run_python(
+            r#"
+class Broken(trading.Strategy):
+    def on_bar(self, bar):
+        raise RuntimeError("boom")
+
+trading.Backtest(100.0).run(Broken(), [trading.Bar(1.0, 1.0, 1.0, 1.0)])
+"#,
+        )
+        .unwrap_err();
+
+        Python::with_gil(|py| {
+            assert!(error.is_instance_of::<pyo3::exceptions::PyRuntimeError>(py));
+        });
+    }
+}
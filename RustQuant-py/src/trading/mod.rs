@@ -0,0 +1,24 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Trading bindings.
+
+use pyo3::prelude::*;
+
+/// Backtesting: bars, orders, strategies, and results.
+pub mod backtest_bindings;
+pub use backtest_bindings::*;
+
+/// Add the trading classes to `m`.
+///
+/// # Errors
+/// Errors if a class cannot be added to the module.
+pub fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    backtest_bindings::register(m)
+}
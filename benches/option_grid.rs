@@ -0,0 +1,89 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Pricing a 50 strike x 20 expiry grid cell by cell with the scalar
+//! Black-Scholes-Merton pricer, and in one batch with `price_grid` and
+//! `greeks_grid`.
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use time::{macros::datetime, Duration, OffsetDateTime};
+use RustQuant::instruments::options::{BlackScholesMerton, TypeFlag};
+
+fn option_grid(c: &mut Criterion) {
+    let evaluation_date = datetime!(2024-01-02 0:00 UTC);
+    let strikes: Vec<f64> = (0..50).map(|i| 50.0 + 2.0 * f64::from(i)).collect();
+    let expiries: Vec<OffsetDateTime> = (1..=20)
+        .map(|i| evaluation_date + Duration::days(30 * i))
+        .collect();
+
+    let option = BlackScholesMerton::new(
+        0.03.into(),
+        100.0.into(),
+        100.0.into(),
+        0.25.into(),
+        0.05.into(),
+        Some(evaluation_date.date()),
+        evaluation_date.date(),
+        TypeFlag::Call,
+    );
+
+    let mut group = c.benchmark_group("option_grid_50x20");
+
+    group.bench_function("scalar_price", |b| {
+        b.iter(|| {
+            let mut total = 0.0;
+            for expiry in &expiries {
+                for &strike_price in &strikes {
+                    total += BlackScholesMerton {
+                        strike_price,
+                        expiration_date: expiry.date(),
+                        ..option
+                    }
+                    .price();
+                }
+            }
+            black_box(total)
+        });
+    });
+
+    group.bench_function("price_grid", |b| {
+        b.iter(|| black_box(option.price_grid(black_box(&strikes), black_box(&expiries))));
+    });
+
+    group.bench_function("scalar_greeks", |b| {
+        b.iter(|| {
+            let mut total = 0.0;
+            for expiry in &expiries {
+                for &strike_price in &strikes {
+                    let scalar = BlackScholesMerton {
+                        strike_price,
+                        expiration_date: expiry.date(),
+                        ..option
+                    };
+                    total += scalar.price()
+                        + scalar.delta()
+                        + scalar.gamma()
+                        + scalar.vega()
+                        + scalar.theta()
+                        + scalar.rho();
+                }
+            }
+            black_box(total)
+        });
+    });
+
+    group.bench_function("greeks_grid", |b| {
+        b.iter(|| black_box(option.greeks_grid(black_box(&strikes), black_box(&expiries))));
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, option_grid);
+criterion_main!(benches);
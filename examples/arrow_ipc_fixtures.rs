@@ -0,0 +1,68 @@
+// Writes a small path matrix, Monte Carlo result, equity curve, and trade
+// list as Arrow IPC files, for the Python-side tests in
+// `tests/python/test_arrow_ipc.py`.
+//
+// Usage: cargo run --example arrow_ipc_fixtures -- <output directory>
+
+use std::path::PathBuf;
+use time::macros::datetime;
+use RustQuant::data::*;
+use RustQuant::error::RustQuantError;
+use RustQuant::pricer::MonteCarloResult;
+use RustQuant::stochastics::Trajectories;
+use RustQuant::trading::{backtest::BacktestResult, fill::Fill, order_side::OrderSide};
+
+fn main() -> Result<(), RustQuantError> {
+    let dir = PathBuf::from(std::env::args().nth(1).unwrap_or_else(|| String::from(".")));
+    std::fs::create_dir_all(&dir)?;
+
+    // Path j at time t is 100 + j * t, on a grid of 101 times in [0, 1].
+    let times: Vec<f64> = (0..=100).map(|i| f64::from(i) / 100.0).collect();
+    let paths = (0..5)
+        .map(|j| times.iter().map(|t| 100.0 + f64::from(j) * t).collect())
+        .collect();
+    write_trajectories_ipc(dir.join("paths.arrow"), &Trajectories { times, paths }, 32)?;
+
+    let result = MonteCarloResult {
+        price: 10.45,
+        standard_error: 0.02,
+        confidence_interval: (10.41, 10.49),
+        n_paths: 100_000,
+        convergence_trace: vec![(50_000, 10.47), (100_000, 10.45)],
+        skewness: 1.2,
+        kurtosis: 3.4,
+        variance_reduction_factor: None,
+        converged: true,
+    };
+    write_monte_carlo_result_ipc(dir.join("mc_result.arrow"), &result)?;
+
+    let backtest = BacktestResult {
+        equity_curve: vec![10_000.0, 10_012.5, 9_998.75],
+        fills: vec![
+            Fill::new(
+                1,
+                7,
+                OrderSide::BID,
+                101.25,
+                10,
+                datetime!(2024-01-02 14:30 UTC),
+            ),
+            Fill::new(
+                2,
+                7,
+                OrderSide::ASK,
+                102.5,
+                4,
+                datetime!(2024-01-03 15:00 UTC),
+            ),
+        ],
+        final_position: 6,
+        periods_per_year: 252.0,
+    };
+    write_equity_curve_ipc(dir.join("equity.arrow"), &backtest)?;
+    write_fills_ipc(dir.join("fills.arrow"), &backtest.fills)?;
+
+    println!("Wrote Arrow IPC fixtures to {}", dir.display());
+
+    Ok(())
+}
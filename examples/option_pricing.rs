@@ -3,11 +3,11 @@ use RustQuant::instruments::options::*;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let option = BlackScholesMertonBuilder::default()
-        .underlying_price(100.0)
-        .strike_price(100.0)
-        .volatility(0.3)
-        .risk_free_rate(0.03)
-        .cost_of_carry(0.05)
+        .underlying_price(100.0.into())
+        .strike_price(100.0.into())
+        .volatility(0.3.into())
+        .risk_free_rate(0.03.into())
+        .cost_of_carry(0.05.into())
         .expiration_date(date!(2024 - 12 - 31))
         .option_type(TypeFlag::Call)
         .build()?;
@@ -0,0 +1,427 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Fused n-ary operations on the `Graph` tape.
+//!
+//! Built from the elementary operators, the log-sum-exp of $n$ variables
+//! takes about $3n$ vertices, and softmax cross-entropy slightly more. The
+//! fused operations push a single vertex with one edge per input instead,
+//! with the partials computed analytically:
+//!
+//! - [`logsumexp`]: $\log \sum_i e^{x_i}$, with partials
+//!   $\mathrm{softmax}(x)_i$, computed after subtracting $\max_i x_i$ so
+//!   large logits do not overflow.
+//! - [`softmax_cross_entropy`]: $\mathrm{LSE}(x) - x_t$ for the target
+//!   class $t$, with partials $\mathrm{softmax}(x) - e_t$.
+//! - [`softmax_cross_entropy_batch`]: the mean of the cross-entropies of a
+//!   batch of samples, in one vertex for the whole batch.
+//!
+//! A fused vertex keeps its edges in a side table of the graph. Its two
+//! binary parent slots point to itself, like those of an input.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::autodiff::{variables::variable::Variable, Graph, Operation, Vertex};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Edges of a fused vertex.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct FusedEdges {
+    /// Index of the fused vertex.
+    pub(crate) node: usize,
+    /// Indices of its parents.
+    pub(crate) parents: Vec<usize>,
+    /// Partial derivative with respect to each parent.
+    pub(crate) partials: Vec<f64>,
+    /// Target class of each sample, for softmax cross-entropy.
+    pub(crate) labels: Vec<usize>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Graph {
+    /// Pushes a fused vertex, produced by `operation`, with an edge to each
+    /// of the `parents`.
+    pub(crate) fn push_fused(
+        &self,
+        operation: Operation,
+        parents: Vec<usize>,
+        partials: Vec<f64>,
+        labels: Vec<usize>,
+    ) -> usize {
+        assert_eq!(parents.len(), partials.len());
+
+        let mut vertices = self.vertices.borrow_mut();
+        let node = vertices.len();
+
+        vertices.push(Vertex {
+            partials: [0.0; 2],
+            parents: [node; 2],
+        });
+        self.operations.borrow_mut().push(operation);
+        self.fused.borrow_mut().push(FusedEdges {
+            node,
+            parents,
+            partials,
+            labels,
+        });
+
+        node
+    }
+}
+
+/// Log-sum-exp $\log \sum_i e^{x_i}$ of the variables, as one vertex.
+///
+/// ```
+/// use RustQuant::autodiff::*;
+///
+/// let g = Graph::new();
+/// let x = g.vars(&[1.0, 2.0, 3.0]);
+///
+/// let z = logsumexp(&x);
+/// let grad = z.accumulate();
+///
+/// let total: f64 = [1.0_f64, 2.0, 3.0].iter().map(|x| x.exp()).sum();
+/// assert!((z.value - total.ln()).abs() < 1e-15);
+/// assert!((grad.wrt(&x[2]) - 3.0_f64.exp() / total).abs() < 1e-15);
+/// ```
+///
+/// # Panics
+/// Panics if `x` is empty or the variables are on different graphs.
+#[must_use]
+pub fn logsumexp<'v>(x: &[Variable<'v>]) -> Variable<'v> {
+    let graph = same_graph(x);
+    let values: Vec<f64> = x.iter().map(|v| v.value).collect();
+    let (value, partials) = softmax(&values);
+
+    Variable {
+        graph,
+        value,
+        index: graph.push_fused(
+            Operation::LogSumExp,
+            x.iter().map(|v| v.index).collect(),
+            partials,
+            Vec::new(),
+        ),
+    }
+}
+
+/// Softmax cross-entropy $\log \sum_i e^{x_i} - x_t$ of the logits for the
+/// class `target_index`, as one vertex.
+///
+/// # Panics
+/// Panics if `target_index` is out of bounds or the variables are on
+/// different graphs.
+#[must_use]
+pub fn softmax_cross_entropy<'v>(logits: &[Variable<'v>], target_index: usize) -> Variable<'v> {
+    softmax_cross_entropy_batch(&[logits], &[target_index])
+}
+
+/// Mean softmax cross-entropy of a batch of samples, one row of logits and
+/// one target class per sample, as one vertex.
+///
+/// # Panics
+/// Panics if the batch is empty, the rows have different lengths, there is
+/// not one target per row, a target is out of bounds, or the variables are
+/// on different graphs.
+#[must_use]
+pub fn softmax_cross_entropy_batch<'v, L>(logits: &[L], target_indices: &[usize]) -> Variable<'v>
+where
+    L: AsRef<[Variable<'v>]>,
+{
+    assert_eq!(
+        logits.len(),
+        target_indices.len(),
+        "There must be one target per sample."
+    );
+
+    let n_classes = logits.first().map_or(0, |row| row.as_ref().len());
+    assert!(
+        logits.iter().all(|row| row.as_ref().len() == n_classes),
+        "Every row must have the same number of logits."
+    );
+
+    let logits: Vec<Variable<'v>> = logits
+        .iter()
+        .flat_map(|row| row.as_ref().iter().copied())
+        .collect();
+    let graph = same_graph(&logits);
+
+    let values: Vec<f64> = logits.iter().map(|v| v.value).collect();
+    let (value, partials) = cross_entropy(&values, n_classes, target_indices)
+        .expect("Every target must be below the number of classes.");
+
+    Variable {
+        graph,
+        value,
+        index: graph.push_fused(
+            Operation::SoftmaxCrossEntropy(n_classes),
+            logits.iter().map(|v| v.index).collect(),
+            partials,
+            target_indices.to_vec(),
+        ),
+    }
+}
+
+/// Whether a fused `operation` with `n_parents` parents and `labels` is
+/// valid, so that replaying it cannot panic.
+pub(crate) fn valid_fused(operation: Operation, n_parents: usize, labels: &[usize]) -> bool {
+    match operation {
+        Operation::LogSumExp => n_parents > 0 && labels.is_empty(),
+        Operation::SoftmaxCrossEntropy(n_classes) => {
+            !labels.is_empty()
+                && n_classes.checked_mul(labels.len()) == Some(n_parents)
+                && labels.iter().all(|label| *label < n_classes)
+        }
+        _ => false,
+    }
+}
+
+/// Applies the fused `operation` to the `parents`.
+pub(crate) fn apply_fused<'v>(
+    operation: Operation,
+    parents: &[Variable<'v>],
+    labels: &[usize],
+) -> Variable<'v> {
+    match operation {
+        Operation::LogSumExp => logsumexp(parents),
+        Operation::SoftmaxCrossEntropy(n_classes) => {
+            let rows: Vec<&[Variable<'v>]> = parents.chunks(n_classes).collect();
+            softmax_cross_entropy_batch(&rows, labels)
+        }
+        _ => unreachable!("Only fused operations are applied here."),
+    }
+}
+
+/// Reverse sweep over `vertices` and their `fused` edges (sorted by
+/// vertex), adding the contributions to the `adjoints` of the parents.
+pub(crate) fn reverse_sweep(vertices: &[Vertex], fused: &[FusedEdges], adjoints: &mut [f64]) {
+    let mut fused = fused.iter().rev().peekable();
+
+    for (index, vertex) in vertices.iter().enumerate().rev() {
+        let deriv = adjoints[index];
+
+        adjoints[vertex.parents[0]] += vertex.partials[0] * deriv;
+        adjoints[vertex.parents[1]] += vertex.partials[1] * deriv;
+
+        if let Some(edges) = fused.next_if(|edges| edges.node == index) {
+            for (&parent, &partial) in edges.parents.iter().zip(&edges.partials) {
+                adjoints[parent] += partial * deriv;
+            }
+        }
+    }
+}
+
+/// Log-sum-exp of `x` and its gradient, the softmax of `x`.
+fn softmax(x: &[f64]) -> (f64, Vec<f64>) {
+    let max = x.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let exponentials: Vec<f64> = x.iter().map(|x| (x - max).exp()).collect();
+    let total: f64 = exponentials.iter().sum();
+
+    (
+        max + total.ln(),
+        exponentials.iter().map(|e| e / total).collect(),
+    )
+}
+
+/// Mean cross-entropy of the rows of `logits` and its gradient, or `None`
+/// if the shapes or targets are invalid.
+fn cross_entropy(logits: &[f64], n_classes: usize, targets: &[usize]) -> Option<(f64, Vec<f64>)> {
+    if targets.is_empty()
+        || n_classes.checked_mul(targets.len()) != Some(logits.len())
+        || targets.iter().any(|target| *target >= n_classes)
+    {
+        return None;
+    }
+
+    let scale = (targets.len() as f64).recip();
+    let mut loss = 0.0;
+    let mut partials = Vec::with_capacity(logits.len());
+
+    for (row, target) in logits.chunks(n_classes).zip(targets) {
+        let (lse, probabilities) = softmax(row);
+
+        loss += lse - row[*target];
+        partials.extend(probabilities.iter().enumerate().map(|(class, p)| {
+            let one_hot = if class == *target { 1.0 } else { 0.0 };
+            (p - one_hot) * scale
+        }));
+    }
+
+    Some((loss * scale, partials))
+}
+
+/// The graph of the variables.
+fn same_graph<'v>(x: &[Variable<'v>]) -> &'v Graph {
+    let graph = x.first().expect("At least one variable is required.").graph;
+    assert!(
+        x.iter().all(|v| std::ptr::eq(v.graph, graph)),
+        "The variables must be on the same graph."
+    );
+
+    graph
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_fused {
+    use crate::autodiff::*;
+
+    /// Log-sum-exp from the elementary operators.
+    fn unfused_logsumexp<'v>(x: &[Variable<'v>]) -> Variable<'v> {
+        let max = x.iter().map(|v| v.value).fold(f64::NEG_INFINITY, f64::max);
+        let total: Variable<'v> = x.iter().map(|v| (*v - max).exp()).sum();
+
+        total.ln() + max
+    }
+
+    /// Mean cross-entropy from the elementary operators.
+    fn unfused_cross_entropy<'v>(logits: &[Vec<Variable<'v>>], targets: &[usize]) -> Variable<'v> {
+        let losses: Variable<'v> = logits
+            .iter()
+            .zip(targets)
+            .map(|(row, target)| unfused_logsumexp(row) - row[*target])
+            .sum();
+
+        losses / targets.len() as f64
+    }
+
+    /// Deterministic logits, some large enough to overflow `exp`.
+    fn logits(n: usize, seed: usize) -> Vec<f64> {
+        (0..n)
+            .map(|i| 3.0 * ((i * 7 + seed * 13) as f64 * 0.37).sin() + (seed as f64) * 200.0)
+            .collect()
+    }
+
+    fn assert_close(a: &[f64], b: &[f64], tolerance: f64) {
+        for (a, b) in a.iter().zip(b) {
+            assert!((a - b).abs() < tolerance, "{a} != {b}");
+        }
+    }
+
+    #[test]
+    fn test_logsumexp() {
+        let values = logits(128, 4);
+
+        let fused_graph = Graph::new();
+        let x = fused_graph.vars(&values);
+        let fused = logsumexp(&x);
+        let fused_gradient = fused.accumulate().wrt(&x);
+
+        let unfused_graph = Graph::new();
+        let y = unfused_graph.vars(&values);
+        let unfused = unfused_logsumexp(&y);
+        let unfused_gradient = unfused.accumulate().wrt(&y);
+
+        assert!(fused.value.is_finite());
+        assert!((fused.value - unfused.value).abs() < 1e-12);
+        assert_close(&fused_gradient, &unfused_gradient, 1e-12);
+        assert!((fused_gradient.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+
+        assert_eq!(fused_graph.len(), 129);
+        assert!(unfused_graph.len() - 128 >= 10 * (fused_graph.len() - 128));
+    }
+
+    #[test]
+    fn test_softmax_cross_entropy() {
+        let values = logits(128, 1);
+
+        let fused_graph = Graph::new();
+        let x = fused_graph.vars(&values);
+        let fused = softmax_cross_entropy(&x, 17);
+        let fused_gradient = fused.accumulate().wrt(&x);
+
+        let unfused_graph = Graph::new();
+        let y = unfused_graph.vars(&values);
+        let unfused = unfused_cross_entropy(std::slice::from_ref(&y), &[17]);
+        let unfused_gradient = unfused.accumulate().wrt(&y);
+
+        assert!((fused.value - unfused.value).abs() < 1e-12);
+        assert_close(&fused_gradient, &unfused_gradient, 1e-12);
+
+        // Softmax minus the one-hot target.
+        assert!(fused_gradient[17] < 0.0);
+        assert!(fused_gradient.iter().sum::<f64>().abs() < 1e-12);
+
+        // Nodes besides the inputs.
+        let fused_nodes = fused_graph.len() - 128;
+        let unfused_nodes = unfused_graph.len() - 128;
+        assert_eq!(fused_nodes, 1);
+        assert!(unfused_nodes >= 10 * fused_nodes, "{unfused_nodes}");
+    }
+
+    #[test]
+    fn test_softmax_cross_entropy_batch() {
+        let (n_samples, n_classes) = (8, 128);
+        let targets: Vec<usize> = (0..n_samples).map(|i| (i * 37) % n_classes).collect();
+
+        let fused_graph = Graph::new();
+        let x: Vec<Vec<Variable>> = (0..n_samples)
+            .map(|i| fused_graph.vars(&logits(n_classes, i)))
+            .collect();
+        let fused = softmax_cross_entropy_batch(&x, &targets);
+        let fused_gradient = fused.accumulate();
+
+        let unfused_graph = Graph::new();
+        let y: Vec<Vec<Variable>> = (0..n_samples)
+            .map(|i| unfused_graph.vars(&logits(n_classes, i)))
+            .collect();
+        let unfused = unfused_cross_entropy(&y, &targets);
+        let unfused_gradient = unfused.accumulate();
+
+        assert!((fused.value - unfused.value).abs() < 1e-12);
+        for (row_x, row_y) in x.iter().zip(&y) {
+            assert_close(
+                &fused_gradient.wrt(row_x),
+                &unfused_gradient.wrt(row_y),
+                1e-12,
+            );
+        }
+
+        let inputs = n_samples * n_classes;
+        assert_eq!(fused_graph.len(), inputs + 1);
+        assert!(unfused_graph.len() - inputs >= 10 * n_samples);
+    }
+
+    #[test]
+    fn test_fused_vertices_compose() {
+        let g = Graph::new();
+        let x = g.vars(&[0.5, -1.0, 2.0]);
+
+        // A fused vertex feeding binary ones, and the other way round.
+        let z = logsumexp(&[x[0] * x[1], x[2].sin(), x[0]]) * x[2];
+        let gradient = z.accumulate().wrt(&x);
+
+        let h = Graph::new();
+        let y = h.vars(&[0.5, -1.0, 2.0]);
+        let w = unfused_logsumexp(&[y[0] * y[1], y[2].sin(), y[0]]) * y[2];
+
+        assert!((z.value - w.value).abs() < 1e-14);
+        assert_close(&gradient, &w.accumulate().wrt(&y), 1e-14);
+    }
+
+    #[test]
+    #[should_panic(expected = "Every target must be below the number of classes.")]
+    fn test_target_out_of_bounds() {
+        let g = Graph::new();
+        let x = g.vars(&[0.5, -1.0, 2.0]);
+
+        let _ = softmax_cross_entropy(&x, 3);
+    }
+}
@@ -17,7 +17,8 @@
 // IMPORTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::autodiff::{variables::variable::Variable, Arity, Vertex};
+use crate::autodiff::fused::{reverse_sweep, FusedEdges};
+use crate::autodiff::{variables::variable::Variable, Arity, Operation, OptimizedTape, Vertex};
 use std::cell::RefCell;
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -29,6 +30,13 @@ use std::cell::RefCell;
 pub struct Graph {
     /// Vector containing the vertices in the Wengert List.
     pub vertices: RefCell<Vec<Vertex>>,
+    /// Operation that produced each vertex, so the tape can be saved and
+    /// replayed.
+    pub operations: RefCell<Vec<Operation>>,
+    /// Edges of the fused vertices, in vertex order.
+    pub(crate) fused: RefCell<Vec<FusedEdges>>,
+    /// Simplified tape used for accumulation, set by [`Graph::optimize`].
+    pub(crate) optimized: RefCell<Option<OptimizedTape>>,
 }
 // pub struct Graph(RefCell<Rc<[Vertex]>>);
 
@@ -47,6 +55,9 @@ impl Graph {
     pub const fn new() -> Self {
         Self {
             vertices: RefCell::new(Vec::new()),
+            operations: RefCell::new(Vec::new()),
+            fused: RefCell::new(Vec::new()),
+            optimized: RefCell::new(None),
             // vertices: RefCell::new(Rc::new([])),
         }
     }
@@ -57,6 +68,9 @@ impl Graph {
     pub fn with_capacity(capacity: usize) -> Self {
         Graph {
             vertices: RefCell::new(Vec::with_capacity(capacity)),
+            operations: RefCell::new(Vec::with_capacity(capacity)),
+            fused: RefCell::new(Vec::new()),
+            optimized: RefCell::new(None),
             // vertices: RefCell::new(Rc::new([])),
         }
     }
@@ -66,8 +80,19 @@ impl Graph {
     #[inline]
     pub fn join(&self, other: &Self) -> Self {
         let graph = self.clone();
-        let other = other.vertices.borrow_mut().clone();
-        graph.vertices.borrow_mut().extend(other);
+        let offset = graph.len();
+        let vertices = other.vertices.borrow_mut().clone();
+        let operations = other.operations.borrow_mut().clone();
+        let fused = other.fused.borrow().clone();
+        graph.vertices.borrow_mut().extend(vertices);
+        graph.operations.borrow_mut().extend(operations);
+        graph
+            .fused
+            .borrow_mut()
+            .extend(fused.into_iter().map(|edges| FusedEdges {
+                node: edges.node + offset,
+                ..edges
+            }));
         graph
     }
 
@@ -78,7 +103,7 @@ impl Graph {
         Variable {
             graph: self,
             value,
-            index: self.push(Arity::Nullary, &[], &[]),
+            index: self.push(Operation::Input(value), &[], &[]),
         }
     }
 
@@ -105,6 +130,39 @@ impl Graph {
     #[inline]
     pub fn clear(&self) {
         self.vertices.borrow_mut().clear();
+        self.operations.borrow_mut().clear();
+        self.fused.borrow_mut().clear();
+        self.optimized.borrow_mut().take();
+    }
+
+    /// Removes every vertex from the graph, keeping its allocation, so the
+    /// graph can be reused (e.g. between iterations of a calibration loop)
+    /// without its memory growing.
+    ///
+    /// All `Variable`s created on the graph index into its vertices, so a
+    /// reset invalidates them. They borrow the graph, so taking `&mut self`
+    /// lets the borrow checker enforce this: no `Variable` of the graph can
+    /// be alive when `reset` is called. Prefer it to [`Graph::clear`].
+    #[inline]
+    pub fn reset(&mut self) {
+        self.vertices.get_mut().clear();
+        self.operations.get_mut().clear();
+        self.fused.get_mut().clear();
+        self.optimized.get_mut().take();
+    }
+
+    /// Returns the number of vertices (nodes) in the graph.
+    #[inline]
+    pub fn node_count(&self) -> usize {
+        self.len()
+    }
+
+    /// Returns the number of vertices the graph can hold without
+    /// reallocating. The vertices are stored contiguously, so this is the
+    /// size of the graph's arena.
+    #[inline]
+    pub fn capacity(&self) -> usize {
+        self.vertices.borrow().capacity()
     }
 
     /// Zeroes the adjoints in the graph.
@@ -114,19 +172,23 @@ impl Graph {
             .borrow_mut()
             .iter_mut()
             .for_each(|vertex| vertex.partials = [0.0; 2]);
+        self.fused
+            .borrow_mut()
+            .iter_mut()
+            .for_each(|edges| edges.partials.fill(0.0));
     }
 
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
     // Functions to push values to the graph (Wengert List):
     // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-    /// Pushes a vertex to the graph.
+    /// Pushes a vertex, produced by `operation`, to the graph.
     #[inline]
-    pub fn push(&self, arity: Arity, parents: &[usize], partials: &[f64]) -> usize {
+    pub fn push(&self, operation: Operation, parents: &[usize], partials: &[f64]) -> usize {
         let mut vertices = self.vertices.borrow_mut();
         let len = vertices.len();
 
-        let vertex = match arity {
+        let vertex = match operation.arity() {
             // Nullary operator pushback.
             //
             // The vertex pushed to the graph is the result of a **nullary** operation.
@@ -178,12 +240,65 @@ impl Graph {
                     parents: [parents[0], parents[1]],
                 }
             }
+            // Fused vertices keep their edges outside the vertex, see
+            // `crate::autodiff::fused`.
+            Arity::Nary => panic!("Fused operations are pushed by their own functions."),
         };
 
         vertices.push(vertex);
+        self.operations.borrow_mut().push(operation);
 
         len
     }
+
+    /// Reverse accumulates the adjoints of every vertex with respect to
+    /// the vertex at `index`, e.g. to interrogate a loaded tape.
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    #[must_use]
+    #[inline]
+    pub fn accumulate_at(&self, index: usize) -> Vec<f64> {
+        self.accumulate_from(&[index])
+    }
+
+    /// Reverse accumulates the adjoints of every vertex with respect to
+    /// the sum of the vertices at `indices`, in a single reverse pass.
+    ///
+    /// If the seeded vertices depend on disjoint sets of inputs, each
+    /// input's adjoint is its derivative for the one vertex that depends
+    /// on it (see [`crate::autodiff::jacobian_sparse`]).
+    ///
+    /// If the graph has been optimized (see [`Graph::optimize`]) since its
+    /// last vertex was pushed, the pass runs on the optimized tape.
+    ///
+    /// # Panics
+    /// Panics if an index is out of bounds.
+    #[must_use]
+    #[inline]
+    pub fn accumulate_from(&self, indices: &[usize]) -> Vec<f64> {
+        let vertices = self.vertices.borrow();
+
+        if let Some(optimized) = self.optimized.borrow().as_ref() {
+            if optimized.original_len() == vertices.len() {
+                return optimized.accumulate_from(indices);
+            }
+        }
+
+        // Set the seeds.
+        // The seed is the derivative of the output with respect to itself.
+        // dy/dy = 1
+        let mut adjoints = vec![0.0; vertices.len()];
+        for &index in indices {
+            adjoints[index] = 1.0; // SEED
+        }
+
+        // Traverse the graph backwards and update the adjoints for the parent vertices.
+        // This is simply the generalised chain rule.
+        reverse_sweep(&vertices, &self.fused.borrow(), &mut adjoints);
+
+        adjoints
+    }
 }
 
 // /// Nullary operator pushback.
@@ -252,3 +367,61 @@ impl Graph {
 //     len
 // }
 // }
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod test_graph {
+    use crate::assert_approx_equal;
+    use crate::autodiff::{Accumulate, Gradient, Graph};
+
+    #[test]
+    fn test_reset() {
+        let mut graph = Graph::with_capacity(16);
+
+        {
+            let x = graph.var(2.0);
+            let y = graph.var(3.0);
+            let _ = x * y + x;
+        }
+        assert_eq!(graph.node_count(), 4);
+
+        graph.reset();
+        assert_eq!(graph.node_count(), 0);
+        assert!(graph.is_empty());
+        assert!(graph.capacity() >= 16);
+
+        // Variables can be registered again, from index zero.
+        let x = graph.var(5.0);
+        assert_eq!(x.index, 0);
+        let gradient = (x * x).accumulate();
+        assert_approx_equal!(gradient.wrt(&x), 10.0, 1e-12);
+    }
+
+    #[test]
+    fn test_calibration_loop_memory_is_bounded() {
+        let mut graph = Graph::new();
+        let mut parameter = 0.0;
+        let mut capacity = None;
+
+        // Gradient descent on (p - 1)^2, with a fresh tape each iteration.
+        for _ in 0..1_000 {
+            {
+                let p = graph.var(parameter);
+                let loss = (p - 1.0) * (p - 1.0);
+                parameter -= 0.1 * loss.accumulate().wrt(&p);
+            }
+
+            let nodes = graph.node_count();
+            graph.reset();
+
+            assert_eq!(graph.node_count(), 0);
+            assert!(graph.capacity() >= nodes);
+            assert_eq!(*capacity.get_or_insert(graph.capacity()), graph.capacity());
+        }
+
+        assert_approx_equal!(parameter, 1.0, 1e-12);
+    }
+}
@@ -41,6 +41,7 @@ pub fn graphviz(graph: &Graph, vars: &[Variable]) -> String {
     );
 
     let vertices = graph.vertices.borrow();
+    let fused = graph.fused.borrow();
 
     // Initialize a HashSet with variable indices for quick lookup
     let var_indices: std::collections::HashSet<_> = vars.iter().map(|var| var.index).collect();
@@ -73,6 +74,16 @@ pub fn graphviz(graph: &Graph, vars: &[Variable]) -> String {
         }
     }
 
+    // Fused vertices keep their edges outside the vertex.
+    for edges in fused.iter() {
+        for (i, (parent, label)) in edges.parents.iter().zip(&edges.partials).enumerate() {
+            dot.push_str(&format!(
+                "\t{} -> {} [label=\"\u{2202}_{}: {:.2?}\"];\n",
+                parent, edges.node, i, label
+            ));
+        }
+    }
+
     dot.push_str("}\n");
 
     dot
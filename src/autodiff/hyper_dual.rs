@@ -0,0 +1,738 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Hyper-dual numbers: forward-mode second derivatives without a tape.
+//!
+//! A hyper-dual number is $a + b \epsilon_1 + c \epsilon_2 + d \epsilon_1
+//! \epsilon_2$ with $\epsilon_1^2 = \epsilon_2^2 = 0$ (Fike and Alonso,
+//! 2011). For a twice differentiable $f$,
+//!
+//! $$
+//! f(a + b \epsilon_1 + c \epsilon_2 + d \epsilon_1 \epsilon_2) = f(a)
+//!     + f'(a) b \epsilon_1 + f'(a) c \epsilon_2
+//!     + (f'(a) d + f''(a) b c) \epsilon_1 \epsilon_2,
+//! $$
+//!
+//! so seeding $x + \epsilon_1 + \epsilon_2$ gives $f(x)$, $f'(x)$ and
+//! $f''(x)$ in one evaluation, and seeding $x + \epsilon_1$, $y +
+//! \epsilon_2$ gives the cross derivative $\partial^2 f / \partial x
+//! \partial y$. Unlike finite differences there is no step size, so the
+//! derivatives are exact to rounding.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use errorfunctions::RealErrorFunctions;
+use std::f64::consts::{LN_10, LN_2, PI, SQRT_2};
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Hyper-dual number $a + b \epsilon_1 + c \epsilon_2 + d \epsilon_1
+/// \epsilon_2$.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct HyperDual {
+    /// Real part $a$.
+    pub value: f64,
+    /// First tangent $b$ (coefficient of $\epsilon_1$).
+    pub e1: f64,
+    /// Second tangent $c$ (coefficient of $\epsilon_2$).
+    pub e2: f64,
+    /// Cross term $d$ (coefficient of $\epsilon_1 \epsilon_2$).
+    pub e1e2: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Second derivative $f''(x)$, from one evaluation of `f` at $x +
+/// \epsilon_1 + \epsilon_2$.
+///
+/// ```
+/// use RustQuant::assert_approx_equal;
+/// use RustQuant::autodiff::*;
+///
+/// // d^2/dx^2 x^3 = 6x
+/// let f = |x: HyperDual| x.powi(3);
+///
+/// assert_approx_equal!(second_derivative(f, 2.0), 12.0, 1e-15);
+/// ```
+pub fn second_derivative<F>(f: F, x: f64) -> f64
+where
+    F: Fn(HyperDual) -> HyperDual,
+{
+    f(HyperDual::new(x, 1.0, 1.0, 0.0)).e1e2
+}
+
+/// Cross derivative $\partial^2 f / \partial x \partial y$, from one
+/// evaluation of `f` at $(x + \epsilon_1, y + \epsilon_2)$.
+///
+/// ```
+/// use RustQuant::assert_approx_equal;
+/// use RustQuant::autodiff::*;
+///
+/// // d^2/dxdy x^2 y^3 = 6 x y^2
+/// let f = |x: HyperDual, y: HyperDual| x * x * y.powi(3);
+///
+/// assert_approx_equal!(cross_derivative(f, 2.0, 3.0), 108.0, 1e-13);
+/// ```
+pub fn cross_derivative<F>(f: F, x: f64, y: f64) -> f64
+where
+    F: Fn(HyperDual, HyperDual) -> HyperDual,
+{
+    f(
+        HyperDual::new(x, 1.0, 0.0, 0.0),
+        HyperDual::new(y, 0.0, 1.0, 0.0),
+    )
+    .e1e2
+}
+
+impl HyperDual {
+    /// New hyper-dual number from its four components.
+    #[must_use]
+    pub const fn new(value: f64, e1: f64, e2: f64, e1e2: f64) -> Self {
+        Self {
+            value,
+            e1,
+            e2,
+            e1e2,
+        }
+    }
+
+    /// A constant (all derivative parts zero).
+    #[must_use]
+    pub const fn constant(value: f64) -> Self {
+        Self::new(value, 0.0, 0.0, 0.0)
+    }
+
+    /// Applies a scalar function given its value and first two derivatives
+    /// at the real part.
+    #[must_use]
+    #[inline]
+    pub fn chain(self, f: f64, df: f64, d2f: f64) -> Self {
+        Self {
+            value: f,
+            e1: df * self.e1,
+            e2: df * self.e2,
+            e1e2: df * self.e1e2 + d2f * self.e1 * self.e2,
+        }
+    }
+
+    /// Absolute value (derivatives of the branch at the real part).
+    #[must_use]
+    pub fn abs(self) -> Self {
+        if self.value < 0.0 {
+            -self
+        } else {
+            self
+        }
+    }
+
+    /// Reciprocal $1 / x$.
+    #[must_use]
+    pub fn recip(self) -> Self {
+        let r = self.value.recip();
+        self.chain(r, -r * r, 2.0 * r * r * r)
+    }
+
+    /// Square root.
+    #[must_use]
+    pub fn sqrt(self) -> Self {
+        let s = self.value.sqrt();
+        self.chain(s, 0.5 / s, -0.25 / (s * self.value))
+    }
+
+    /// Cube root.
+    #[must_use]
+    pub fn cbrt(self) -> Self {
+        let c = self.value.cbrt();
+        let df = c / (3.0 * self.value);
+        self.chain(c, df, -2.0 * df / (3.0 * self.value))
+    }
+
+    /// Integer power $x^n$.
+    #[must_use]
+    pub fn powi(self, n: i32) -> Self {
+        match n {
+            0 => Self::constant(1.0),
+            1 => self,
+            _ => {
+                let n_f = f64::from(n);
+                self.chain(
+                    self.value.powi(n),
+                    n_f * self.value.powi(n - 1),
+                    n_f * (n_f - 1.0) * self.value.powi(n - 2),
+                )
+            }
+        }
+    }
+
+    /// Real power $x^n$.
+    #[must_use]
+    pub fn powf(self, n: f64) -> Self {
+        self.chain(
+            self.value.powf(n),
+            n * self.value.powf(n - 1.0),
+            n * (n - 1.0) * self.value.powf(n - 2.0),
+        )
+    }
+
+    /// Power $x^y$ with a hyper-dual exponent, $e^{y \ln x}$.
+    #[must_use]
+    pub fn pow(self, exponent: Self) -> Self {
+        (exponent * self.ln()).exp()
+    }
+
+    /// Exponential $e^x$.
+    #[must_use]
+    pub fn exp(self) -> Self {
+        let e = self.value.exp();
+        self.chain(e, e, e)
+    }
+
+    /// $2^x$.
+    #[must_use]
+    pub fn exp2(self) -> Self {
+        let e = self.value.exp2();
+        self.chain(e, e * LN_2, e * LN_2 * LN_2)
+    }
+
+    /// $e^x - 1$, accurate near zero.
+    #[must_use]
+    pub fn exp_m1(self) -> Self {
+        let e = self.value.exp();
+        self.chain(self.value.exp_m1(), e, e)
+    }
+
+    /// Natural logarithm.
+    #[must_use]
+    pub fn ln(self) -> Self {
+        let r = self.value.recip();
+        self.chain(self.value.ln(), r, -r * r)
+    }
+
+    /// $\ln(1 + x)$, accurate near zero.
+    #[must_use]
+    pub fn ln_1p(self) -> Self {
+        let r = (1.0 + self.value).recip();
+        self.chain(self.value.ln_1p(), r, -r * r)
+    }
+
+    /// Base-10 logarithm.
+    #[must_use]
+    pub fn log10(self) -> Self {
+        let r = self.value.recip();
+        self.chain(self.value.log10(), r / LN_10, -r * r / LN_10)
+    }
+
+    /// Base-2 logarithm.
+    #[must_use]
+    pub fn log2(self) -> Self {
+        let r = self.value.recip();
+        self.chain(self.value.log2(), r / LN_2, -r * r / LN_2)
+    }
+
+    /// Sine.
+    #[must_use]
+    pub fn sin(self) -> Self {
+        let (s, c) = self.value.sin_cos();
+        self.chain(s, c, -s)
+    }
+
+    /// Cosine.
+    #[must_use]
+    pub fn cos(self) -> Self {
+        let (s, c) = self.value.sin_cos();
+        self.chain(c, -s, -c)
+    }
+
+    /// Tangent.
+    #[must_use]
+    pub fn tan(self) -> Self {
+        let t = self.value.tan();
+        let sec2 = 1.0 + t * t;
+        self.chain(t, sec2, 2.0 * t * sec2)
+    }
+
+    /// Arcsine.
+    #[must_use]
+    pub fn asin(self) -> Self {
+        let x = self.value;
+        let df = (1.0 - x * x).sqrt().recip();
+        self.chain(x.asin(), df, x * df * df * df)
+    }
+
+    /// Arccosine.
+    #[must_use]
+    pub fn acos(self) -> Self {
+        let x = self.value;
+        let df = (1.0 - x * x).sqrt().recip();
+        self.chain(x.acos(), -df, -x * df * df * df)
+    }
+
+    /// Arctangent.
+    #[must_use]
+    pub fn atan(self) -> Self {
+        let x = self.value;
+        let df = (1.0 + x * x).recip();
+        self.chain(x.atan(), df, -2.0 * x * df * df)
+    }
+
+    /// Hyperbolic sine.
+    #[must_use]
+    pub fn sinh(self) -> Self {
+        let (s, c) = (self.value.sinh(), self.value.cosh());
+        self.chain(s, c, s)
+    }
+
+    /// Hyperbolic cosine.
+    #[must_use]
+    pub fn cosh(self) -> Self {
+        let (s, c) = (self.value.sinh(), self.value.cosh());
+        self.chain(c, s, c)
+    }
+
+    /// Hyperbolic tangent.
+    #[must_use]
+    pub fn tanh(self) -> Self {
+        let t = self.value.tanh();
+        let sech2 = 1.0 - t * t;
+        self.chain(t, sech2, -2.0 * t * sech2)
+    }
+
+    /// Inverse hyperbolic sine.
+    #[must_use]
+    pub fn asinh(self) -> Self {
+        let x = self.value;
+        let df = (x * x + 1.0).sqrt().recip();
+        self.chain(x.asinh(), df, -x * df * df * df)
+    }
+
+    /// Inverse hyperbolic cosine.
+    #[must_use]
+    pub fn acosh(self) -> Self {
+        let x = self.value;
+        let df = (x * x - 1.0).sqrt().recip();
+        self.chain(x.acosh(), df, -x * df * df * df)
+    }
+
+    /// Inverse hyperbolic tangent.
+    #[must_use]
+    pub fn atanh(self) -> Self {
+        let x = self.value;
+        let df = (1.0 - x * x).recip();
+        self.chain(x.atanh(), df, 2.0 * x * df * df)
+    }
+
+    /// Error function.
+    #[must_use]
+    pub fn erf(self) -> Self {
+        let x = self.value;
+        let df = 2.0 * (-x * x).exp() / PI.sqrt();
+        self.chain(RealErrorFunctions::erf(x), df, -2.0 * x * df)
+    }
+
+    /// Complementary error function.
+    #[must_use]
+    pub fn erfc(self) -> Self {
+        let x = self.value;
+        let df = -2.0 * (-x * x).exp() / PI.sqrt();
+        self.chain(RealErrorFunctions::erfc(x), df, -2.0 * x * df)
+    }
+
+    /// Standard normal cumulative distribution function.
+    #[must_use]
+    pub fn norm_cdf(self) -> Self {
+        let x = self.value;
+        let pdf = (-0.5 * x * x).exp() / (2.0 * PI).sqrt();
+        self.chain(0.5 * RealErrorFunctions::erfc(-x / SQRT_2), pdf, -x * pdf)
+    }
+
+    /// Standard normal probability density function.
+    #[must_use]
+    pub fn norm_pdf(self) -> Self {
+        let x = self.value;
+        let pdf = (-0.5 * x * x).exp() / (2.0 * PI).sqrt();
+        self.chain(pdf, -x * pdf, (x * x - 1.0) * pdf)
+    }
+
+    /// Minimum, following the smaller argument.
+    #[must_use]
+    pub fn min(self, other: Self) -> Self {
+        if other.value < self.value {
+            other
+        } else {
+            self
+        }
+    }
+
+    /// Maximum, following the larger argument.
+    #[must_use]
+    pub fn max(self, other: Self) -> Self {
+        if other.value > self.value {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+impl From<f64> for HyperDual {
+    fn from(value: f64) -> Self {
+        Self::constant(value)
+    }
+}
+
+impl Neg for HyperDual {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self::new(-self.value, -self.e1, -self.e2, -self.e1e2)
+    }
+}
+
+impl Add for HyperDual {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self::new(
+            self.value + rhs.value,
+            self.e1 + rhs.e1,
+            self.e2 + rhs.e2,
+            self.e1e2 + rhs.e1e2,
+        )
+    }
+}
+
+impl Sub for HyperDual {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        Self::new(
+            self.value - rhs.value,
+            self.e1 - rhs.e1,
+            self.e2 - rhs.e2,
+            self.e1e2 - rhs.e1e2,
+        )
+    }
+}
+
+impl Mul for HyperDual {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        Self::new(
+            self.value * rhs.value,
+            self.value * rhs.e1 + self.e1 * rhs.value,
+            self.value * rhs.e2 + self.e2 * rhs.value,
+            self.value * rhs.e1e2 + self.e1 * rhs.e2 + self.e2 * rhs.e1 + self.e1e2 * rhs.value,
+        )
+    }
+}
+
+impl Div for HyperDual {
+    type Output = Self;
+
+    #[allow(clippy::suspicious_arithmetic_impl)]
+    fn div(self, rhs: Self) -> Self {
+        self * rhs.recip()
+    }
+}
+
+/// Arithmetic with `f64` on either side, and the assignment operators.
+macro_rules! impl_scalar_ops {
+    ($($trait:ident, $method:ident, $assign_trait:ident, $assign_method:ident);*) => {$(
+        impl $trait<f64> for HyperDual {
+            type Output = Self;
+
+            fn $method(self, rhs: f64) -> Self {
+                self.$method(Self::constant(rhs))
+            }
+        }
+
+        impl $trait<HyperDual> for f64 {
+            type Output = HyperDual;
+
+            fn $method(self, rhs: HyperDual) -> HyperDual {
+                HyperDual::constant(self).$method(rhs)
+            }
+        }
+
+        impl $assign_trait for HyperDual {
+            fn $assign_method(&mut self, rhs: Self) {
+                *self = (*self).$method(rhs);
+            }
+        }
+
+        impl $assign_trait<f64> for HyperDual {
+            fn $assign_method(&mut self, rhs: f64) {
+                *self = (*self).$method(rhs);
+            }
+        }
+    )*};
+}
+
+impl_scalar_ops!(
+    Add, add, AddAssign, add_assign;
+    Sub, sub, SubAssign, sub_assign;
+    Mul, mul, MulAssign, mul_assign;
+    Div, div, DivAssign, div_assign
+);
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_hyper_dual {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    /// Checks `f` against its analytic first and second derivatives.
+    fn check<F>(f: F, df: impl Fn(f64) -> f64, d2f: impl Fn(f64) -> f64, x: f64, value: f64)
+    where
+        F: Fn(HyperDual) -> HyperDual,
+    {
+        let y = f(HyperDual::new(x, 1.0, 1.0, 0.0));
+
+        assert_approx_equal!(y.value, value, 1e-14);
+        assert_approx_equal!(y.e1, df(x), 1e-13);
+        assert_approx_equal!(y.e2, df(x), 1e-13);
+        assert_approx_equal!(y.e1e2, d2f(x), 1e-13);
+    }
+
+    #[test]
+    fn test_elementary_functions() {
+        let x: f64 = 0.3;
+
+        check(|x| x.exp(), f64::exp, f64::exp, x, x.exp());
+        check(|x| x.ln(), |x| 1.0 / x, |x| -1.0 / (x * x), x, x.ln());
+        check(
+            |x| x.sqrt(),
+            |x| 0.5 / x.sqrt(),
+            |x| -0.25 * x.powf(-1.5),
+            x,
+            x.sqrt(),
+        );
+        check(
+            |x| x.cbrt(),
+            |x| x.powf(-2.0 / 3.0) / 3.0,
+            |x| -2.0 / 9.0 * x.powf(-5.0 / 3.0),
+            x,
+            x.cbrt(),
+        );
+        check(
+            |x| x.recip(),
+            |x| -1.0 / (x * x),
+            |x| 2.0 / x.powi(3),
+            x,
+            1.0 / x,
+        );
+        check(
+            |x| x.powi(4),
+            |x| 4.0 * x.powi(3),
+            |x| 12.0 * x * x,
+            x,
+            x.powi(4),
+        );
+        check(
+            |x| x.powf(2.5),
+            |x| 2.5 * x.powf(1.5),
+            |x| 3.75 * x.sqrt(),
+            x,
+            x.powf(2.5),
+        );
+        check(
+            |x| x.pow(x),
+            |x| x.powf(x) * (x.ln() + 1.0),
+            |x| x.powf(x) * ((x.ln() + 1.0).powi(2) + 1.0 / x),
+            x,
+            x.powf(x),
+        );
+        check(
+            |x| x.exp2(),
+            |x| x.exp2() * LN_2,
+            |x| x.exp2() * LN_2 * LN_2,
+            x,
+            x.exp2(),
+        );
+        check(|x| x.exp_m1(), f64::exp, f64::exp, x, x.exp_m1());
+        check(
+            |x| x.ln_1p(),
+            |x| 1.0 / (1.0 + x),
+            |x| -1.0 / (1.0 + x).powi(2),
+            x,
+            x.ln_1p(),
+        );
+        check(
+            |x| x.log10(),
+            |x| 1.0 / (x * LN_10),
+            |x| -1.0 / (x * x * LN_10),
+            x,
+            x.log10(),
+        );
+        check(
+            |x| x.log2(),
+            |x| 1.0 / (x * LN_2),
+            |x| -1.0 / (x * x * LN_2),
+            x,
+            x.log2(),
+        );
+    }
+
+    #[test]
+    fn test_trigonometric_functions() {
+        let x: f64 = 0.3;
+
+        check(|x| x.sin(), f64::cos, |x| -x.sin(), x, x.sin());
+        check(|x| x.cos(), |x| -x.sin(), |x| -x.cos(), x, x.cos());
+        check(
+            |x| x.tan(),
+            |x| x.cos().powi(-2),
+            |x| 2.0 * x.tan() * x.cos().powi(-2),
+            x,
+            x.tan(),
+        );
+        check(
+            |x| x.asin(),
+            |x| (1.0 - x * x).powf(-0.5),
+            |x| x * (1.0 - x * x).powf(-1.5),
+            x,
+            x.asin(),
+        );
+        check(
+            |x| x.acos(),
+            |x| -(1.0 - x * x).powf(-0.5),
+            |x| -x * (1.0 - x * x).powf(-1.5),
+            x,
+            x.acos(),
+        );
+        check(
+            |x| x.atan(),
+            |x| 1.0 / (1.0 + x * x),
+            |x| -2.0 * x / (1.0 + x * x).powi(2),
+            x,
+            x.atan(),
+        );
+        check(|x| x.sinh(), f64::cosh, f64::sinh, x, x.sinh());
+        check(|x| x.cosh(), f64::sinh, f64::cosh, x, x.cosh());
+        check(
+            |x| x.tanh(),
+            |x| x.cosh().powi(-2),
+            |x| -2.0 * x.tanh() * x.cosh().powi(-2),
+            x,
+            x.tanh(),
+        );
+        check(
+            |x| x.asinh(),
+            |x| (x * x + 1.0).powf(-0.5),
+            |x| -x * (x * x + 1.0).powf(-1.5),
+            x,
+            x.asinh(),
+        );
+        check(
+            |x| x.atanh(),
+            |x| 1.0 / (1.0 - x * x),
+            |x| 2.0 * x / (1.0 - x * x).powi(2),
+            x,
+            x.atanh(),
+        );
+        check(
+            |x| x.acosh(),
+            |x| (x * x - 1.0).powf(-0.5),
+            |x| -x * (x * x - 1.0).powf(-1.5),
+            1.3,
+            1.3_f64.acosh(),
+        );
+    }
+
+    #[test]
+    fn test_error_functions() {
+        let x: f64 = 0.7;
+        let d = |x: f64| 2.0 / PI.sqrt() * (-x * x).exp();
+
+        check(
+            |x| x.erf(),
+            d,
+            |x| -2.0 * x * d(x),
+            x,
+            RealErrorFunctions::erf(x),
+        );
+        check(
+            |x| x.erfc(),
+            |x| -d(x),
+            |x| 2.0 * x * d(x),
+            x,
+            RealErrorFunctions::erfc(x),
+        );
+
+        let pdf = |x: f64| (-0.5 * x * x).exp() / (2.0 * PI).sqrt();
+        check(
+            |x| x.norm_cdf(),
+            pdf,
+            |x| -x * pdf(x),
+            x,
+            0.5 * RealErrorFunctions::erfc(-x / SQRT_2),
+        );
+        check(
+            |x| x.norm_pdf(),
+            |x| -x * pdf(x),
+            |x| (x * x - 1.0) * pdf(x),
+            x,
+            pdf(x),
+        );
+    }
+
+    #[test]
+    fn test_arithmetic_and_cross_derivatives() {
+        // The cross derivative is checked against central differences of
+        // the exact f_x.
+        let f = |x: HyperDual, y: HyperDual| (x * y + 2.0) / (x - y) - 3.0 * x;
+        let f_x = |x: f64, y: f64| (y * (x - y) - (x * y + 2.0)) / (x - y).powi(2) - 3.0;
+        let f_xy = |x: f64, y: f64| {
+            let h = 1e-6;
+            (f_x(x, y + h) - f_x(x, y - h)) / (2.0 * h)
+        };
+
+        let (x, y) = (1.5, 0.5);
+        let z = f(
+            HyperDual::new(x, 1.0, 0.0, 0.0),
+            HyperDual::new(y, 0.0, 1.0, 0.0),
+        );
+
+        assert_approx_equal!(z.value, (x * y + 2.0) / (x - y) - 3.0 * x, 1e-15);
+        assert_approx_equal!(z.e1, f_x(x, y), 1e-14);
+        assert_approx_equal!(z.e1e2, f_xy(x, y), 1e-8);
+        assert_approx_equal!(cross_derivative(f, x, y), z.e1e2, 1e-15);
+
+        // Symmetric in the order of the seeds.
+        let g = |x: HyperDual, y: HyperDual| f(y, x);
+        assert_approx_equal!(cross_derivative(g, y, x), z.e1e2, 1e-14);
+
+        let mut w = HyperDual::new(2.0, 1.0, 1.0, 0.0);
+        w *= w;
+        w -= 1.0;
+        w /= 3.0;
+        w += HyperDual::constant(1.0);
+        // (x^2 - 1) / 3 + 1 at x = 2.
+        assert_approx_equal!(w.value, 2.0, 1e-15);
+        assert_approx_equal!(w.e1, 4.0 / 3.0, 1e-15);
+        assert_approx_equal!(w.e2, 4.0 / 3.0, 1e-15);
+        assert_approx_equal!(w.e1e2, 2.0 / 3.0, 1e-15);
+        assert_approx_equal!(
+            second_derivative(|x| (x * x - 1.0) / 3.0 + 1.0, 2.0),
+            2.0 / 3.0,
+            1e-15
+        );
+    }
+}
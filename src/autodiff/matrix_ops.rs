@@ -0,0 +1,178 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Matrix operations on `Variable`s.
+//!
+//! Matrices are row-major `Vec<Vec<Variable>>`s. Each operation is built
+//! from the scalar overloads, so the gradient of any scalar function of the
+//! result (e.g. a loss) with respect to the matrix entries is available via
+//! [`Accumulate`](crate::autodiff::Accumulate).
+
+use crate::autodiff::variables::variable::Variable;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Matrix product $AB$.
+///
+/// # Panics
+/// Panics if the number of columns of `a` differs from the number of rows
+/// of `b`, or the inner dimension is zero.
+#[must_use]
+pub fn matmul_ad<'v>(a: &[Vec<Variable<'v>>], b: &[Vec<Variable<'v>>]) -> Vec<Vec<Variable<'v>>> {
+    assert!(!b.is_empty(), "Inner dimension must be positive.");
+    assert!(
+        a.iter().all(|row| row.len() == b.len()),
+        "Columns of `a` must match rows of `b`."
+    );
+
+    let columns = b[0].len();
+    assert!(
+        b.iter().all(|row| row.len() == columns),
+        "Rows of `b` must have equal lengths."
+    );
+
+    a.iter()
+        .map(|row| {
+            (0..columns)
+                .map(|j| row.iter().zip(b).map(|(&a_ik, b_k)| a_ik * b_k[j]).sum())
+                .collect()
+        })
+        .collect()
+}
+
+/// Element-wise sum $A + B$.
+///
+/// # Panics
+/// Panics if the matrices have different shapes.
+#[must_use]
+pub fn add_matrix<'v>(a: &[Vec<Variable<'v>>], b: &[Vec<Variable<'v>>]) -> Vec<Vec<Variable<'v>>> {
+    elementwise(a, b, |x, y| x + y)
+}
+
+/// Element-wise (Hadamard) product $A \circ B$.
+///
+/// # Panics
+/// Panics if the matrices have different shapes.
+#[must_use]
+pub fn hadamard_product<'v>(
+    a: &[Vec<Variable<'v>>],
+    b: &[Vec<Variable<'v>>],
+) -> Vec<Vec<Variable<'v>>> {
+    elementwise(a, b, |x, y| x * y)
+}
+
+fn elementwise<'v>(
+    a: &[Vec<Variable<'v>>],
+    b: &[Vec<Variable<'v>>],
+    op: impl Fn(Variable<'v>, Variable<'v>) -> Variable<'v>,
+) -> Vec<Vec<Variable<'v>>> {
+    assert!(
+        a.len() == b.len() && a.iter().zip(b).all(|(x, y)| x.len() == y.len()),
+        "Matrices must have the same shape."
+    );
+
+    a.iter()
+        .zip(b)
+        .map(|(x, y)| x.iter().zip(y).map(|(&x, &y)| op(x, y)).collect())
+        .collect()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod test_matrix_ops {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::autodiff::{Accumulate, Gradient, Graph};
+    use std::f64::EPSILON as EPS;
+
+    fn matrix<'v>(g: &'v Graph, rows: &[&[f64]]) -> Vec<Vec<Variable<'v>>> {
+        rows.iter().map(|row| g.vars(row)).collect()
+    }
+
+    #[test]
+    fn test_matmul_values() {
+        let g = Graph::new();
+        let a = matrix(&g, &[&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0]]);
+        let b = matrix(&g, &[&[7.0, 8.0], &[9.0, 10.0], &[11.0, 12.0]]);
+
+        let c = matmul_ad(&a, &b);
+        let expected = [[58.0, 64.0], [139.0, 154.0]];
+
+        assert_eq!(c.len(), 2);
+        for (row, expected) in c.iter().zip(expected) {
+            for (c_ij, e_ij) in row.iter().zip(expected) {
+                assert_approx_equal!(c_ij.value, e_ij, EPS);
+            }
+        }
+    }
+
+    #[test]
+    fn test_least_squares_gradient() {
+        // loss = ||Wx - y||^2, with gradient 2 (Wx - y) x^T.
+        let g = Graph::new();
+        let w = matrix(&g, &[&[0.5, -1.0, 2.0], &[1.5, 0.3, -0.7]]);
+        let x = matrix(&g, &[&[1.0], &[2.0], &[-1.0]]);
+        let minus_y = matrix(&g, &[&[-0.4], &[-1.1]]);
+
+        let residuals = add_matrix(&matmul_ad(&w, &x), &minus_y);
+        let loss: Variable = hadamard_product(&residuals, &residuals)
+            .into_iter()
+            .flatten()
+            .sum();
+
+        let gradient = loss.accumulate();
+        let x_values = [1.0, 2.0, -1.0];
+
+        for (i, row) in w.iter().enumerate() {
+            let residual = residuals[i][0].value;
+            for (j, w_ij) in row.iter().enumerate() {
+                assert_approx_equal!(gradient.wrt(w_ij), 2.0 * residual * x_values[j], 1e-12);
+            }
+        }
+
+        // And with respect to x: 2 W^T (Wx - y).
+        for (j, x_j) in x.iter().enumerate() {
+            let expected: f64 = (0..2)
+                .map(|i| 2.0 * w[i][j].value * residuals[i][0].value)
+                .sum();
+            assert_approx_equal!(gradient.wrt(&x_j[0]), expected, 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_elementwise_gradients() {
+        let g = Graph::new();
+        let a = matrix(&g, &[&[1.0, 2.0], &[3.0, 4.0]]);
+        let b = matrix(&g, &[&[5.0, 6.0], &[7.0, 8.0]]);
+
+        let sum: Variable = add_matrix(&a, &b).into_iter().flatten().sum();
+        let gradient = sum.accumulate();
+        assert_approx_equal!(sum.value, 36.0, EPS);
+        assert_approx_equal!(gradient.wrt(&a[1][0]), 1.0, EPS);
+
+        let product: Variable = hadamard_product(&a, &b).into_iter().flatten().sum();
+        let gradient = product.accumulate();
+        assert_approx_equal!(product.value, 70.0, EPS);
+        assert_approx_equal!(gradient.wrt(&a[1][0]), 7.0, EPS);
+        assert_approx_equal!(gradient.wrt(&b[0][1]), 2.0, EPS);
+    }
+
+    #[test]
+    #[should_panic(expected = "Columns of `a` must match rows of `b`.")]
+    fn test_matmul_shape_mismatch() {
+        let g = Graph::new();
+        let a = matrix(&g, &[&[1.0, 2.0]]);
+        let _ = matmul_ad(&a, &a);
+    }
+}
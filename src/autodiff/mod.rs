@@ -66,9 +66,15 @@ pub mod accumulate;
 pub use accumulate::*;
 
 /// Implements the gradient computation.
+pub mod fused;
+pub use fused::*;
+
 pub mod gradient;
 pub use gradient::*;
 
+pub mod gradient_check;
+pub use gradient_check::*;
+
 /// The Graph (aka. tape or Wengert List).
 pub mod graph;
 pub use graph::*;
@@ -77,6 +83,24 @@ pub use graph::*;
 pub mod graphviz;
 pub use graphviz::*;
 
+pub mod hyper_dual;
+pub use hyper_dual::*;
+
+/// Matrix operations (products, sums) on `Variable`s.
+pub mod matrix_ops;
+pub use matrix_ops::*;
+
+pub mod optimize;
+pub use optimize::*;
+
+/// Jacobians, with sparsity detection and compressed evaluation.
+pub mod sparse_jacobian;
+pub use sparse_jacobian::*;
+
+/// Saving, loading, and replaying the [`Graph`] tape.
+pub mod tape;
+pub use tape::*;
+
 /// Implements [`Vertex`] (nodes) for the `Graph`.
 pub mod vertex;
 pub use vertex::*;
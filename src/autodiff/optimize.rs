@@ -0,0 +1,352 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Simplification of the `Graph` tape before accumulation.
+//!
+//! Reverse accumulation only uses the parents and partials of each vertex,
+//! so vertices can be simplified as long as the adjoints of the inputs are
+//! unchanged. [`Graph::optimize`] builds a smaller tape, in one forward
+//! pass, with:
+//!
+//! - Constant folding: edges with a zero partial (e.g. `x * 0.0`, the
+//!   losing branch of `min`/`max`) are dropped, and so are vertices left
+//!   without a path to an input.
+//! - Identity elimination: vertices with a single edge of partial one
+//!   (e.g. `x + c`, `x * 1.0`, `-(-x)`) are replaced by their parent, and
+//!   chains of single-parent vertices collapse into one edge with the
+//!   product of the partials.
+//! - Common subexpression elimination: vertices with the same parents and
+//!   partials (e.g. the same subexpression built twice in a loop) are
+//!   merged, as are repeated parents of one vertex (`x + x` is `2 x`).
+//!
+//! Every vertex of the original tape maps to a vertex of the optimized one
+//! (or to none, if it is constant), and accumulation on an optimized graph
+//! runs on the smaller tape and maps the adjoints back, so `wrt` keeps
+//! working with the existing `Variable`s.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::autodiff::fused::{reverse_sweep, FusedEdges};
+use crate::autodiff::{Graph, Operation, Vertex};
+use std::collections::HashMap;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Sizes of the tape before and after [`Graph::optimize`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OptimizationStats {
+    /// Number of vertices on the tape.
+    pub nodes_before: usize,
+    /// Number of vertices on the optimized tape.
+    pub nodes_after: usize,
+}
+
+/// Optimized tape of a graph, used by accumulation while the graph has
+/// the same number of vertices as when it was optimized.
+#[derive(Debug, Clone)]
+pub struct OptimizedTape {
+    /// Vertices of the optimized tape.
+    vertices: Vec<Vertex>,
+    /// Edges of the vertices with more than two parents.
+    fused: Vec<FusedEdges>,
+    /// Optimized vertex of each original vertex (`None` if constant).
+    node_of: Vec<Option<usize>>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl OptimizationStats {
+    /// Fraction of the vertices removed.
+    #[must_use]
+    pub fn reduction(&self) -> f64 {
+        if self.nodes_before == 0 {
+            return 0.0;
+        }
+
+        1.0 - self.nodes_after as f64 / self.nodes_before as f64
+    }
+}
+
+impl OptimizedTape {
+    /// Number of original vertices the tape covers.
+    pub(crate) fn original_len(&self) -> usize {
+        self.node_of.len()
+    }
+
+    /// Adjoints of the original vertices with respect to the sum of the
+    /// vertices at `indices`, from one reverse pass over the optimized
+    /// tape.
+    ///
+    /// The adjoints of the inputs are exact. Those of eliminated
+    /// intermediate vertices are the adjoints of the vertices they map to.
+    pub(crate) fn accumulate_from(&self, indices: &[usize]) -> Vec<f64> {
+        let mut adjoints = vec![0.0; self.vertices.len()];
+        for &index in indices {
+            if let Some(node) = self.node_of[index] {
+                adjoints[node] += 1.0;
+            }
+        }
+
+        reverse_sweep(&self.vertices, &self.fused, &mut adjoints);
+
+        self.node_of
+            .iter()
+            .map(|node| node.map_or(0.0, |node| adjoints[node]))
+            .collect()
+    }
+}
+
+impl Graph {
+    /// Simplifies the tape for accumulation (see the
+    /// [module documentation](crate::autodiff::optimize)) and returns the
+    /// number of vertices before and after.
+    ///
+    /// The recorded vertices are unchanged: subsequent accumulations use
+    /// the optimized tape, until vertices are pushed to the graph (optimize
+    /// again after building the next expression) or it is cleared, reset,
+    /// or replayed.
+    pub fn optimize(&self) -> OptimizationStats {
+        let vertices = self.vertices.borrow();
+        let operations = self.operations.borrow();
+        let fused = self.fused.borrow();
+        let mut fused_edges = fused.iter().peekable();
+
+        let mut optimized: Vec<Vertex> = Vec::new();
+        let mut optimized_fused: Vec<FusedEdges> = Vec::new();
+        let mut node_of: Vec<Option<usize>> = Vec::with_capacity(vertices.len());
+        let mut subexpressions: HashMap<Vec<(usize, u64)>, usize> = HashMap::new();
+
+        for (index, (vertex, operation)) in vertices.iter().zip(operations.iter()).enumerate() {
+            if matches!(operation, Operation::Input(_)) {
+                let node = optimized.len();
+                optimized.push(Vertex {
+                    partials: [0.0; 2],
+                    parents: [node; 2],
+                });
+                node_of.push(Some(node));
+                continue;
+            }
+
+            // Edges to the optimized parents, through single-parent vertices.
+            let edges_of_index = fused_edges.next_if(|edges| edges.node == index);
+            let fused_parents = edges_of_index
+                .iter()
+                .flat_map(|e| e.parents.iter().zip(&e.partials));
+
+            let mut edges: Vec<(usize, f64)> = Vec::with_capacity(2);
+            for (&parent, &partial) in vertex
+                .parents
+                .iter()
+                .zip(&vertex.partials)
+                .chain(fused_parents)
+            {
+                if parent == index || partial == 0.0 {
+                    continue;
+                }
+                let Some(node) = node_of[parent] else {
+                    continue;
+                };
+
+                let (node, partial) = match single_edge(&optimized[node], node) {
+                    Some((grandparent, scale)) => (grandparent, partial * scale),
+                    None => (node, partial),
+                };
+
+                match edges.iter_mut().find(|(other, _)| *other == node) {
+                    Some((_, total)) => *total += partial,
+                    None => edges.push((node, partial)),
+                }
+            }
+            edges.retain(|(_, partial)| *partial != 0.0);
+            edges.sort_by_key(|(node, _)| *node);
+
+            let node = match edges.as_slice() {
+                [] => None,
+                [(node, partial)] if *partial == 1.0 => Some(*node),
+                _ => {
+                    let key = edges.iter().map(|(n, p)| (*n, p.to_bits())).collect();
+
+                    Some(*subexpressions.entry(key).or_insert_with(|| {
+                        let node = optimized.len();
+                        optimized.push(match edges.as_slice() {
+                            [(parent, partial)] => Vertex {
+                                partials: [*partial, 0.0],
+                                parents: [*parent, node],
+                            },
+                            [(parent_0, partial_0), (parent_1, partial_1)] => Vertex {
+                                partials: [*partial_0, *partial_1],
+                                parents: [*parent_0, *parent_1],
+                            },
+                            _ => {
+                                optimized_fused.push(FusedEdges {
+                                    node,
+                                    parents: edges.iter().map(|(parent, _)| *parent).collect(),
+                                    partials: edges.iter().map(|(_, partial)| *partial).collect(),
+                                    labels: Vec::new(),
+                                });
+                                Vertex {
+                                    partials: [0.0; 2],
+                                    parents: [node; 2],
+                                }
+                            }
+                        });
+                        node
+                    }))
+                }
+            };
+
+            node_of.push(node);
+        }
+
+        let stats = OptimizationStats {
+            nodes_before: vertices.len(),
+            nodes_after: optimized.len(),
+        };
+
+        *self.optimized.borrow_mut() = Some(OptimizedTape {
+            vertices: optimized,
+            fused: optimized_fused,
+            node_of,
+        });
+
+        stats
+    }
+}
+
+/// The parent and partial of an optimized vertex with a single edge.
+fn single_edge(vertex: &Vertex, index: usize) -> Option<(usize, f64)> {
+    let is_single = vertex.parents[0] != index && vertex.parents[1] == index;
+
+    is_single.then_some((vertex.parents[0], vertex.partials[0]))
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_optimize {
+    use crate::autodiff::*;
+
+    /// Gradients of `f` with and without the optimization pass.
+    fn gradients<F>(inputs: &[f64], f: F) -> (Vec<f64>, Vec<f64>, OptimizationStats)
+    where
+        F: for<'v> Fn(&[Variable<'v>]) -> Variable<'v>,
+    {
+        let graph = Graph::new();
+        let variables = graph.vars(inputs);
+        let output = f(&variables);
+
+        let plain = output.accumulate().wrt(&variables);
+        let stats = graph.optimize();
+        let optimized = output.accumulate().wrt(&variables);
+
+        (plain, optimized, stats)
+    }
+
+    /// Inputs and the function of them to differentiate.
+    type Case = (Vec<f64>, for<'v> fn(&[Variable<'v>]) -> Variable<'v>);
+
+    fn assert_close(a: &[f64], b: &[f64], tolerance: f64) {
+        for (a, b) in a.iter().zip(b) {
+            assert!((a - b).abs() <= tolerance * a.abs().max(1.0), "{a} != {b}");
+        }
+    }
+
+    #[test]
+    fn test_gradient_suite_unchanged() {
+        let cases: Vec<Case> = vec![
+            (vec![1.0, 2.0], |v| v[0] * v[1]),
+            (vec![69.0, 420.0], |v| v[0] * v[1] + v[0].sin()),
+            (vec![1.0, 2.0], |v| v[0] * v[1] + v[0].tan()),
+            (vec![1.0, 2.0], |v| (v[0] * v[1]).cosh()),
+            (vec![1.0, 2.0], |v| {
+                (v[0] * v[1]).cosh() / (v[0].tanh() * v[1].sinh())
+            }),
+            (vec![1.0, 2.0], |v| (v[0].sin() + v[1].tan()).exp()),
+            (vec![3.0, 2.0, 1.0], |v| {
+                v[0].powf(v[1]) + 1.0_f64.sin() - v[2].asinh() / 2.0
+            }),
+            (vec![0.3, -1.2], |v| {
+                (v[0] - v[1]).abs() * Min::min(&v[0], v[1]) + Max::max(&v[0], 0.5)
+                    - (-(-v[1])) * 1.0
+                    + 0.0
+            }),
+            (vec![0.4, 1.7], |v| {
+                v[0].erf() * v[1].ln() + v[0].exp_m1().powi(3) / v[1].sqrt() - v[1].recip()
+            }),
+            (vec![0.3, -1.2, 2.0], |v| logsumexp(v) * v[0] + 1.0),
+            (vec![0.3, -1.2, 2.0, 0.5, 0.5, -0.7], |v| {
+                softmax_cross_entropy_batch(&[&v[..3], &v[3..]], &[2, 0]) + logsumexp(&v[..2])
+            }),
+        ];
+
+        for (inputs, f) in cases {
+            let (plain, optimized, stats) = gradients(&inputs, f);
+
+            assert_close(&plain, &optimized, 1e-15);
+            assert!(stats.nodes_after <= stats.nodes_before);
+        }
+    }
+
+    #[test]
+    fn test_loop_built_expression() {
+        // A loop that rebuilds the same subexpressions and applies
+        // identities, as generated code often does.
+        let (plain, optimized, stats) = gradients(&[0.7, 1.3, -0.4], |v| {
+            let mut total = v[0] * 0.0;
+            for k in 0..50 {
+                let common = v[0] * v[1];
+                let scaled = (common * 1.0 + 0.0) * (k as f64);
+                let again = -(-(v[0] * v[1]));
+                total = total + scaled + again.sin() + v[2] * 0.0;
+            }
+            total
+        });
+
+        // The merged vertices sum the 50 terms in a different order.
+        assert_close(&plain, &optimized, 1e-13);
+        assert!(stats.reduction() > 0.3, "{stats:?}");
+
+        // d/dx0 = sum_k (k x1) + 50 cos(x0 x1) x1.
+        let (x0, x1) = (0.7_f64, 1.3_f64);
+        let expected = 1225.0 * x1 + 50.0 * (x0 * x1).cos() * x1;
+        assert!((optimized[0] - expected).abs() < 1e-10);
+        assert_eq!(optimized[2], 0.0);
+    }
+
+    #[test]
+    fn test_stale_optimization_is_ignored() {
+        let graph = Graph::new();
+        let x = graph.var(2.0);
+        let y = x * 1.0 + 3.0;
+
+        let stats = graph.optimize();
+        assert_eq!(stats.nodes_before, 3);
+        assert_eq!(stats.nodes_after, 1);
+        assert_eq!(y.accumulate().wrt(&x), 1.0);
+
+        // Vertices pushed after optimizing are accumulated on the full tape.
+        let z = y * y;
+        assert_eq!(z.accumulate().wrt(&x), 10.0);
+
+        // A constant output has a zero gradient.
+        let graph = Graph::new();
+        let x = graph.var(2.0);
+        let c = x * 0.0 + 1.0;
+        graph.optimize();
+        assert_eq!(c.accumulate().wrt(&x), 0.0);
+    }
+}
@@ -7,7 +7,7 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::autodiff::{variables::variable::Variable, vertex::Arity};
+use crate::autodiff::{variables::variable::Variable, vertex::Operation};
 use std::ops::{Div, DivAssign};
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -117,7 +117,7 @@ impl<'v> Div<Variable<'v>> for f64 {
             graph: other.graph,
             value: self / other.value,
             index: other.graph.push(
-                Arity::Binary,
+                Operation::ConstantDiv(self),
                 &[other.index, other.index],
                 &[0.0, -self / (other.value * other.value)],
             ),
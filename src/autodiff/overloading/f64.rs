@@ -7,7 +7,8 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::autodiff::{variables::variable::Variable, vertex::Arity};
+use crate::autodiff::{variables::variable::Variable, vertex::Operation};
+use std::f64::consts::{LN_10, LN_2};
 use std::ops::Neg;
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -56,7 +57,7 @@ impl<'v> Variable<'v> {
             value: self.value.abs(),
             index: self
                 .graph
-                .push(Arity::Unary, &[self.index], &[self.value.signum()]),
+                .push(Operation::Abs, &[self.index], &[self.value.signum()]),
         }
     }
 
@@ -81,7 +82,7 @@ impl<'v> Variable<'v> {
             graph: self.graph,
             value: self.value.acos(),
             index: self.graph.push(
-                Arity::Unary,
+                Operation::Acos,
                 &[self.index],
                 &[((1.0 - self.value.powi(2)).sqrt()).recip().neg()],
             ),
@@ -109,7 +110,7 @@ impl<'v> Variable<'v> {
             graph: self.graph,
             value: self.value.acosh(),
             index: self.graph.push(
-                Arity::Unary,
+                Operation::Acosh,
                 &[self.index],
                 &[((self.value - 1.0).sqrt() * (self.value + 1.0).sqrt()).recip()],
             ),
@@ -139,7 +140,7 @@ impl<'v> Variable<'v> {
             graph: self.graph,
             value: self.value.asin(),
             index: self.graph.push(
-                Arity::Unary,
+                Operation::Asin,
                 &[self.index],
                 &[if (self.value > -1.0) && (self.value < 1.0) {
                     ((1.0 - self.value.powi(2)).sqrt()).recip()
@@ -172,7 +173,7 @@ impl<'v> Variable<'v> {
             graph: self.graph,
             value: self.value.asinh(),
             index: self.graph.push(
-                Arity::Unary,
+                Operation::Asinh,
                 &[self.index],
                 &[((1.0 + self.value.powi(2)).sqrt()).recip()],
             ),
@@ -201,7 +202,7 @@ impl<'v> Variable<'v> {
             graph: self.graph,
             value: self.value.atan(),
             index: self.graph.push(
-                Arity::Unary,
+                Operation::Atan,
                 &[self.index],
                 &[((1.0 + self.value.powi(2)).recip())],
             ),
@@ -231,7 +232,7 @@ impl<'v> Variable<'v> {
             graph: self.graph,
             value: self.value.atanh(),
             index: self.graph.push(
-                Arity::Unary,
+                Operation::Atanh,
                 &[self.index],
                 &[((1.0 - self.value.powi(2)).recip())],
             ),
@@ -261,7 +262,7 @@ impl<'v> Variable<'v> {
             graph: self.graph,
             value: self.value.cbrt(),
             index: self.graph.push(
-                Arity::Unary,
+                Operation::Cbrt,
                 &[self.index],
                 &[((3.0 * self.value.powf(2.0 / 3.0)).recip())],
             ),
@@ -293,7 +294,7 @@ impl<'v> Variable<'v> {
             // index: self.graph.push_unary(self.index, self.value.sin().neg()),
             index: self
                 .graph
-                .push(Arity::Unary, &[self.index], &[self.value.sin().neg()]),
+                .push(Operation::Cos, &[self.index], &[self.value.sin().neg()]),
         }
     }
 
@@ -321,7 +322,7 @@ impl<'v> Variable<'v> {
             value: self.value.cosh(),
             index: self
                 .graph
-                .push(Arity::Unary, &[self.index], &[self.value.sinh()]),
+                .push(Operation::Cosh, &[self.index], &[self.value.sinh()]),
         }
     }
 
@@ -348,7 +349,7 @@ impl<'v> Variable<'v> {
             value: self.value.exp(),
             index: self
                 .graph
-                .push(Arity::Unary, &[self.index], &[self.value.exp()]),
+                .push(Operation::Exp, &[self.index], &[self.value.exp()]),
         }
     }
 
@@ -375,7 +376,7 @@ impl<'v> Variable<'v> {
             graph: self.graph,
             value: self.value.exp2(),
             index: self.graph.push(
-                Arity::Unary,
+                Operation::Exp2,
                 &[self.index],
                 &[2_f64.powf(self.value) * 2_f64.ln()],
             ),
@@ -407,7 +408,7 @@ impl<'v> Variable<'v> {
             // index: self.graph.push_unary(self.index, self.value.exp()),
             index: self
                 .graph
-                .push(Arity::Unary, &[self.index], &[self.value.exp()]),
+                .push(Operation::ExpM1, &[self.index], &[self.value.exp()]),
         }
     }
 
@@ -434,7 +435,7 @@ impl<'v> Variable<'v> {
             value: self.value.ln(),
             index: self
                 .graph
-                .push(Arity::Unary, &[self.index], &[self.value.recip()]),
+                .push(Operation::Ln, &[self.index], &[self.value.recip()]),
         }
     }
 
@@ -460,14 +461,16 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.ln_1p(),
-            index: self
-                .graph
-                .push(Arity::Unary, &[self.index], &[(1.0 + self.value).recip()]),
+            index: self.graph.push(
+                Operation::Ln1p,
+                &[self.index],
+                &[(1.0 + self.value).recip()],
+            ),
         }
     }
 
     /// Logarithm (base 10).
-    /// d/dx log_10(x) = 1 / x
+    /// d/dx log_10(x) = 1 / (x ln(10))
     ///
     /// ```
     /// use RustQuant::assert_approx_equal;
@@ -480,7 +483,7 @@ impl<'v> Variable<'v> {
     /// let grad = z.accumulate();
     ///
     /// assert_approx_equal!(z.value,      0.00000000000, 1e-10);
-    /// assert_approx_equal!(grad.wrt(&x), 1.00000000000, 1e-10);
+    /// assert_approx_equal!(grad.wrt(&x), 0.43429448190, 1e-10);
     /// ```
     #[must_use]
     #[inline]
@@ -488,14 +491,16 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.log10(),
-            index: self
-                .graph
-                .push(Arity::Unary, &[self.index], &[self.value.recip()]),
+            index: self.graph.push(
+                Operation::Log10,
+                &[self.index],
+                &[(self.value * LN_10).recip()],
+            ),
         }
     }
 
     /// Logarithm (base 2).
-    /// d/dx log_2(x) = 1 / x
+    /// d/dx log_2(x) = 1 / (x ln(2))
     ///
     /// ```
     /// use RustQuant::assert_approx_equal;
@@ -508,7 +513,7 @@ impl<'v> Variable<'v> {
     /// let grad = z.accumulate();
     ///
     /// assert_approx_equal!(z.value,      0.00000000000, 1e-10);
-    /// assert_approx_equal!(grad.wrt(&x), 1.00000000000, 1e-10);
+    /// assert_approx_equal!(grad.wrt(&x), 1.44269504089, 1e-10);
     /// ```
     #[must_use]
     #[inline]
@@ -516,9 +521,11 @@ impl<'v> Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value.log2(),
-            index: self
-                .graph
-                .push(Arity::Unary, &[self.index], &[self.value.recip()]),
+            index: self.graph.push(
+                Operation::Log2,
+                &[self.index],
+                &[(self.value * LN_2).recip()],
+            ),
         }
     }
 
@@ -556,7 +563,7 @@ impl<'v> Variable<'v> {
             graph: self.graph,
             value: self.value.recip(),
             index: self.graph.push(
-                Arity::Unary,
+                Operation::Recip,
                 &[self.index],
                 &[self.value.powi(2).recip().neg()],
             ),
@@ -587,7 +594,7 @@ impl<'v> Variable<'v> {
             value: self.value.sin(),
             index: self
                 .graph
-                .push(Arity::Unary, &[self.index], &[self.value.cos()]),
+                .push(Operation::Sin, &[self.index], &[self.value.cos()]),
         }
     }
 
@@ -615,7 +622,7 @@ impl<'v> Variable<'v> {
             value: self.value.sinh(),
             index: self
                 .graph
-                .push(Arity::Unary, &[self.index], &[self.value.cosh()]),
+                .push(Operation::Sinh, &[self.index], &[self.value.cosh()]),
         }
     }
 
@@ -641,7 +648,7 @@ impl<'v> Variable<'v> {
             graph: self.graph,
             value: self.value.sqrt(),
             index: self.graph.push(
-                Arity::Unary,
+                Operation::Sqrt,
                 &[self.index],
                 &[(2.0 * self.value.sqrt()).recip()],
             ),
@@ -671,7 +678,7 @@ impl<'v> Variable<'v> {
             graph: self.graph,
             value: self.value.tan(),
             index: self.graph.push(
-                Arity::Unary,
+                Operation::Tan,
                 &[self.index],
                 &[(self.value.cos().powi(2)).recip()],
             ),
@@ -701,7 +708,7 @@ impl<'v> Variable<'v> {
             graph: self.graph,
             value: self.value.tanh(),
             index: self.graph.push(
-                Arity::Unary,
+                Operation::Tanh,
                 &[self.index],
                 &[(self.value.cosh().powi(2)).recip()],
             ),
@@ -717,6 +724,7 @@ impl<'v> Variable<'v> {
 mod test_overloading_f64 {
     use crate::assert_approx_equal;
     use crate::autodiff::*;
+    use num::Complex;
     use std::f64::EPSILON as EPS;
 
     #[test]
@@ -760,14 +768,18 @@ mod test_overloading_f64 {
 
         // GRADIENTS
         assert_approx_equal!((-x).accumulate().wrt(&x), -1.0, EPS);
-        assert_approx_equal!(x.log2().accumulate().wrt(&x), 1.0, EPS);
+        assert_approx_equal!(x.log2().accumulate().wrt(&x), std::f64::consts::LOG2_E, EPS);
         assert_approx_equal!(x.exp2().accumulate().wrt(&x), 1.386_294_361_119_890_6, EPS);
         assert_approx_equal!(x.exp_m1().accumulate().wrt(&x), std::f64::consts::E, EPS);
         assert_approx_equal!(x.ln().accumulate().wrt(&x), 1.0, EPS);
         assert_approx_equal!(x.ln().accumulate().wrt(&x), 1.0, EPS);
         assert_approx_equal!(x.ln_1p().accumulate().wrt(&x), 0.5, EPS);
-        assert_approx_equal!(x.log10().accumulate().wrt(&x), 1.0, EPS);
-        assert_approx_equal!(x.log2().accumulate().wrt(&x), 1.0, EPS);
+        assert_approx_equal!(
+            x.log10().accumulate().wrt(&x),
+            std::f64::consts::LOG10_E,
+            EPS
+        );
+        assert_approx_equal!(x.log2().accumulate().wrt(&x), std::f64::consts::LOG2_E, EPS);
         assert_approx_equal!(x.recip().accumulate().wrt(&x), -1.0, EPS);
         assert_approx_equal!(x.sqrt().accumulate().wrt(&x), 0.5, EPS);
         assert_approx_equal!(x.cbrt().accumulate().wrt(&x), 0.333_333_333_333_333_3, EPS);
@@ -785,4 +797,78 @@ mod test_overloading_f64 {
         assert!(x.asin().accumulate().wrt(&x).is_nan());
         assert!(x.acos().accumulate().wrt(&x).is_nan());
     }
+
+    #[test]
+    fn test_gradients_complex_step() {
+        type Case = (
+            &'static str,
+            for<'v> fn(Variable<'v>) -> Variable<'v>,
+            fn(Complex<f64>) -> Complex<f64>,
+            &'static [f64],
+        );
+
+        const REALS: &[f64] = &[-2.1, -0.4, 0.3, 1.7];
+        const POSITIVE: &[f64] = &[0.05, 0.7, 3.2];
+        const UNIT: &[f64] = &[-0.8, -0.1, 0.6];
+
+        // `num`'s asin, acos, and atan lose the imaginary part of a complex
+        // step, so they are inverted by one Newton step from the real
+        // inverse instead, which gets the imaginary part right.
+        fn newton<F>(z: Complex<f64>, w: f64, f: F) -> Complex<f64>
+        where
+            F: Fn(Complex<f64>) -> (Complex<f64>, Complex<f64>),
+        {
+            let w = Complex::new(w, 0.0);
+            let (value, derivative) = f(w);
+            w - (value - z) / derivative
+        }
+
+        let cases: [Case; 23] = [
+            ("neg", |x| -x, |z| -z, REALS),
+            ("exp", |x| x.exp(), |z| z.exp(), REALS),
+            ("exp2", |x| x.exp2(), |z| z.exp2(), REALS),
+            ("exp_m1", |x| x.exp_m1(), |z| z.exp() - 1.0, REALS),
+            ("ln", |x| x.ln(), |z| z.ln(), POSITIVE),
+            ("ln_1p", |x| x.ln_1p(), |z| (z + 1.0).ln(), POSITIVE),
+            ("log10", |x| x.log10(), |z| z.log10(), POSITIVE),
+            ("log2", |x| x.log2(), |z| z.log2(), POSITIVE),
+            ("recip", |x| x.recip(), |z| z.inv(), REALS),
+            ("sqrt", |x| x.sqrt(), |z| z.sqrt(), POSITIVE),
+            ("cbrt", |x| x.cbrt(), |z| z.cbrt(), POSITIVE),
+            ("sin", |x| x.sin(), |z| z.sin(), REALS),
+            ("cos", |x| x.cos(), |z| z.cos(), REALS),
+            ("tan", |x| x.tan(), |z| z.tan(), REALS),
+            ("sinh", |x| x.sinh(), |z| z.sinh(), REALS),
+            ("cosh", |x| x.cosh(), |z| z.cosh(), REALS),
+            ("tanh", |x| x.tanh(), |z| z.tanh(), REALS),
+            (
+                "asin",
+                |x| x.asin(),
+                |z| newton(z, z.re.asin(), |w| (w.sin(), w.cos())),
+                UNIT,
+            ),
+            (
+                "acos",
+                |x| x.acos(),
+                |z| newton(z, z.re.acos(), |w| (w.cos(), -w.sin())),
+                UNIT,
+            ),
+            (
+                "atan",
+                |x| x.atan(),
+                |z| newton(z, z.re.atan(), |w| (w.tan(), w.cos().powi(-2))),
+                REALS,
+            ),
+            ("asinh", |x| x.asinh(), |z| z.asinh(), REALS),
+            ("acosh", |x| x.acosh(), |z| z.acosh(), &[1.2, 2.5, 10.0]),
+            ("atanh", |x| x.atanh(), |z| z.atanh(), UNIT),
+        ];
+
+        for (name, f_real, f_complex, points) in cases {
+            let points: Vec<Vec<f64>> = points.iter().map(|x| vec![*x]).collect();
+            let report = check_gradient(|x| f_real(x[0]), |z| f_complex(z[0]), &points);
+
+            assert!(report.passed(1e-14), "{name}: {report:?}");
+        }
+    }
 }
@@ -7,7 +7,7 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::autodiff::{variables::variable::Variable, vertex::Arity};
+use crate::autodiff::{variables::variable::Variable, vertex::Operation};
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // OVERLOADING: LOGARITHM
@@ -35,11 +35,11 @@ impl<'v> Log<Variable<'v>> for Variable<'v> {
             graph: self.graph,
             value: f64::log(self.value, base.value),
             index: self.graph.push(
-                Arity::Binary,
+                Operation::Log,
                 &[self.index, base.index],
                 &[
-                    -f64::ln(self.value) / (base.value * f64::ln(base.value).powi(2)),
                     1.0 / (self.value * f64::ln(base.value)),
+                    -f64::ln(self.value) / (base.value * f64::ln(base.value).powi(2)),
                 ],
             ),
         }
@@ -59,7 +59,7 @@ impl<'v> Log<Variable<'v>> for f64 {
             graph: base.graph,
             value: f64::log(*self, base.value),
             index: base.graph.push(
-                Arity::Binary,
+                Operation::ConstantLog(*self),
                 &[base.index, base.index],
                 &[
                     -f64::ln(*self) / (base.value * f64::ln(base.value).powi(2)),
@@ -83,10 +83,33 @@ impl<'v> Log<f64> for Variable<'v> {
             graph: self.graph,
             value: f64::log(self.value, base),
             index: self.graph.push(
-                Arity::Binary,
+                Operation::LogConstant(base),
                 &[self.index, self.index],
                 &[0.0, 1.0 / (f64::ln(base) * self.value)],
             ),
         }
     }
 }
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod test_log {
+    use crate::autodiff::{check_gradient, Log};
+    use num::Complex;
+
+    #[test]
+    fn test_gradients_complex_step() {
+        let report = check_gradient(
+            |x| x[0].log(x[1]) + x[0].log(10.0) + Log::log(&5.0, x[1]),
+            |x: &[Complex<f64>]| {
+                x[0].ln() / x[1].ln() + x[0].ln() / 10_f64.ln() + 5_f64.ln() / x[1].ln()
+            },
+            &[vec![0.4, 1.7], vec![3.0, 0.6], vec![12.0, 2.5]],
+        );
+
+        assert!(report.passed(1e-14), "{report:?}");
+    }
+}
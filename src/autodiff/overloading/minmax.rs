@@ -7,7 +7,7 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::autodiff::{variables::variable::Variable, vertex::Arity};
+use crate::autodiff::{variables::variable::Variable, vertex::Operation};
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // OVERLOADING: MIN
@@ -34,7 +34,7 @@ impl<'v> Min<Variable<'v>> for Variable<'v> {
             graph: self.graph,
             value: self.value.min(rhs.value),
             index: self.graph.push(
-                Arity::Binary,
+                Operation::Min,
                 &[self.index, rhs.index],
                 &[
                     if self.value < rhs.value { 1.0 } else { 0.0 },
@@ -55,7 +55,7 @@ impl<'v> Min<f64> for Variable<'v> {
             graph: self.graph,
             value: self.value.min(rhs),
             index: self.graph.push(
-                Arity::Binary,
+                Operation::MinConstant(rhs),
                 &[self.index, self.index],
                 &[if self.value < rhs { 1.0 } else { 0.0 }, 0.0],
             ),
@@ -73,7 +73,7 @@ impl<'v> Min<Variable<'v>> for f64 {
             graph: rhs.graph,
             value: f64::min(*self, rhs.value),
             index: rhs.graph.push(
-                Arity::Binary,
+                Operation::ConstantMin(*self),
                 &[rhs.index, rhs.index],
                 &[0.0, if self < &rhs.value { 1.0 } else { 0.0 }],
             ),
@@ -106,7 +106,7 @@ impl<'v> Max<Variable<'v>> for Variable<'v> {
             graph: self.graph,
             value: self.value.max(rhs.value),
             index: self.graph.push(
-                Arity::Binary,
+                Operation::Max,
                 &[self.index, rhs.index],
                 &[
                     if self.value > rhs.value { 1.0 } else { 0.0 },
@@ -127,7 +127,7 @@ impl<'v> Max<f64> for Variable<'v> {
             graph: self.graph,
             value: self.value.max(rhs),
             index: self.graph.push(
-                Arity::Binary,
+                Operation::MaxConstant(rhs),
                 &[self.index, self.index],
                 &[if self.value > rhs { 1.0 } else { 0.0 }, 0.0],
             ),
@@ -145,7 +145,7 @@ impl<'v> Max<Variable<'v>> for f64 {
             graph: rhs.graph,
             value: f64::max(*self, rhs.value),
             index: rhs.graph.push(
-                Arity::Binary,
+                Operation::ConstantMax(*self),
                 &[rhs.index, rhs.index],
                 &[0.0, if self > &rhs.value { 1.0 } else { 0.0 }],
             ),
@@ -7,7 +7,7 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::autodiff::{variables::variable::Variable, vertex::Arity};
+use crate::autodiff::{variables::variable::Variable, vertex::Operation};
 use std::ops::{Mul, MulAssign};
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -68,7 +68,7 @@ impl<'v> Mul<Variable<'v>> for Variable<'v> {
             graph: self.graph,
             value: self.value * other.value,
             index: self.graph.push(
-                Arity::Binary,
+                Operation::Mul,
                 &[self.index, other.index],
                 &[other.value, self.value],
             ),
@@ -99,9 +99,11 @@ impl<'v> Mul<f64> for Variable<'v> {
         Variable {
             graph: self.graph,
             value: self.value * other,
-            index: self
-                .graph
-                .push(Arity::Binary, &[self.index, self.index], &[other, 0.0]),
+            index: self.graph.push(
+                Operation::MulConstant(other),
+                &[self.index, self.index],
+                &[other, 0.0],
+            ),
         }
     }
 }
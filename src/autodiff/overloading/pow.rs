@@ -7,7 +7,7 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::autodiff::{variables::variable::Variable, vertex::Arity};
+use crate::autodiff::{variables::variable::Variable, vertex::Operation};
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // OVERLOADING: POWER FUNCTION TRAITS
@@ -33,7 +33,7 @@ impl<'v> Powf<Variable<'v>> for Variable<'v> {
             graph: self.graph,
             value: self.value.powf(other.value),
             index: self.graph.push(
-                Arity::Binary,
+                Operation::Powf,
                 &[self.index, other.index],
                 &[
                     other.value * f64::powf(self.value, other.value - 1.),
@@ -54,7 +54,7 @@ impl<'v> Powf<f64> for Variable<'v> {
             graph: self.graph,
             value: f64::powf(self.value, n),
             index: self.graph.push(
-                Arity::Binary,
+                Operation::PowfConstant(n),
                 &[self.index, self.index],
                 &[n * f64::powf(self.value, n - 1.0), 0.0],
             ),
@@ -72,9 +72,9 @@ impl<'v> Powf<Variable<'v>> for f64 {
             graph: other.graph,
             value: f64::powf(*self, other.value),
             index: other.graph.push(
-                Arity::Binary,
+                Operation::ConstantPowf(*self),
                 &[other.index, other.index],
-                &[0.0, other.value * f64::powf(*self, other.value - 1.0)],
+                &[0.0, f64::powf(*self, other.value) * f64::ln(*self)],
             ),
         }
     }
@@ -101,7 +101,7 @@ impl<'v> Powi<Variable<'v>> for Variable<'v> {
             graph: self.graph,
             value: self.value.powf(other.value),
             index: self.graph.push(
-                Arity::Binary,
+                Operation::Powi,
                 &[self.index, other.index],
                 &[
                     other.value * f64::powf(self.value, other.value - 1.),
@@ -122,7 +122,7 @@ impl<'v> Powi<i32> for Variable<'v> {
             graph: self.graph,
             value: f64::powi(self.value, n),
             index: self.graph.push(
-                Arity::Binary,
+                Operation::PowiConstant(n),
                 &[self.index, self.index],
                 &[f64::from(n) * f64::powi(self.value, n - 1), 0.0],
             ),
@@ -140,9 +140,9 @@ impl<'v> Powi<Variable<'v>> for f64 {
             graph: other.graph,
             value: f64::powf(*self, other.value),
             index: other.graph.push(
-                Arity::Binary,
+                Operation::ConstantPowi(*self),
                 &[other.index, other.index],
-                &[0.0, other.value * f64::powf(*self, other.value - 1.0)],
+                &[0.0, f64::powf(*self, other.value) * f64::ln(*self)],
             ),
         }
     }
@@ -154,8 +154,30 @@ impl<'v> Powi<Variable<'v>> for f64 {
 
 #[cfg(test)]
 mod test_overload {
+    use crate::autodiff::{check_gradient, Powf, Powi};
+    use num::Complex;
+
     // use crate::autodiff::{Graph, Variable};
 
+    #[test]
+    fn test_gradients_complex_step() {
+        let points = vec![vec![0.4, 1.7], vec![1.3, -0.6], vec![2.5, 3.0]];
+
+        let powf = check_gradient(
+            |x| x[0].powf(x[1]) + x[0].powf(2.5) + Powf::powf(&3.0, x[1]),
+            |x: &[Complex<f64>]| x[0].powc(x[1]) + x[0].powf(2.5) + x[1].expf(3.0),
+            &points,
+        );
+        let powi = check_gradient(
+            |x| x[0].powi(x[1]) + x[0].powi(-3) + Powi::powi(&0.5, x[1]),
+            |x: &[Complex<f64>]| x[0].powc(x[1]) + x[0].powi(-3) + x[1].expf(0.5),
+            &points,
+        );
+
+        assert!(powf.passed(1e-14), "{powf:?}");
+        assert!(powi.passed(1e-14), "{powi:?}");
+    }
+
     // #[test]
     // fn test_powf() {
     //     let g = Graph::new();
@@ -9,7 +9,7 @@
 
 //! Overloading functions from `statrs` crate.
 
-use crate::autodiff::{variables::variable::Variable, vertex::Arity};
+use crate::autodiff::{variables::variable::Variable, vertex::Operation};
 use std::f64::consts::PI;
 use std::ops::Neg;
 
@@ -39,7 +39,7 @@ impl<'v> Variable<'v> {
             graph: self.graph,
             value: errorfunctions::RealErrorFunctions::erf(self.value),
             index: self.graph.push(
-                Arity::Unary,
+                Operation::Erf,
                 &[self.index],
                 &[2.0 * self.value.powi(2).neg().exp() / PI.sqrt()],
             ),
@@ -71,7 +71,7 @@ impl<'v> Variable<'v> {
             graph: self.graph,
             value: errorfunctions::RealErrorFunctions::erfc(self.value),
             index: self.graph.push(
-                Arity::Unary,
+                Operation::Erfc,
                 &[self.index],
                 &[((2.0 * self.value.powi(2).neg().exp()).neg() / PI.sqrt())],
             ),
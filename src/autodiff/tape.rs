@@ -0,0 +1,697 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Saving, loading, and replaying the `Graph` tape.
+//!
+//! A saved tape can be loaded and interrogated offline, e.g. to debug a
+//! calibration that produced a strange gradient: [`Graph::replay`]
+//! recomputes every value (and partial) from the inputs, and
+//! [`Graph::accumulate_at`] gives the adjoints with respect to any vertex.
+//!
+//! The format is little-endian binary: a header of the magic bytes
+//! `RQTAPE\0\0`, the format version (`u32`), and the number of vertices
+//! (`u64`), followed for each vertex by its operation code (`u8`), its
+//! constant operand or input value (`f64`, zero if none), its two parent
+//! indices (`u64`), and its value (`f64`). A fused vertex (see
+//! [`crate::autodiff::fused`]) is followed by its number of parents
+//! (`u64`), their indices (`u64`), its number of labels (`u64`), and the
+//! labels (`u64`). The partials are not saved: they are recomputed by
+//! replaying the operations on load.
+//!
+//! Version 2 added the fused vertices, so version 1 tapes are valid
+//! version 2 tapes and still load.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::autodiff::fused::{apply_fused, valid_fused, FusedEdges};
+use crate::autodiff::{variables::variable::Variable, Arity, Graph, Log, Max, Min, Operation};
+use crate::autodiff::{Powf, Powi};
+use crate::error::{RustQuantError, TapeError};
+use std::path::Path;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// CONSTANTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Magic bytes at the start of a saved tape.
+const TAPE_MAGIC: [u8; 8] = *b"RQTAPE\0\0";
+
+/// Version of the tape format. Tapes of later versions fail to load.
+pub const TAPE_FORMAT_VERSION: u32 = 2;
+
+/// Bytes in the header: magic, version, and number of vertices.
+const HEADER_SIZE: usize = 8 + 4 + 8;
+
+/// Bytes per vertex: operation code, operand, parents, and value (fused
+/// vertices have more).
+const VERTEX_SIZE: usize = 1 + 8 + 2 * 8 + 8;
+
+/// Operations without an operand, in code order after the operations with
+/// one.
+const OPERAND_FREE_OPERATIONS: [Operation; 33] = [
+    Operation::Add,
+    Operation::Mul,
+    Operation::Powf,
+    Operation::Powi,
+    Operation::Log,
+    Operation::Min,
+    Operation::Max,
+    Operation::Abs,
+    Operation::Acos,
+    Operation::Acosh,
+    Operation::Asin,
+    Operation::Asinh,
+    Operation::Atan,
+    Operation::Atanh,
+    Operation::Cbrt,
+    Operation::Cos,
+    Operation::Cosh,
+    Operation::Exp,
+    Operation::Exp2,
+    Operation::ExpM1,
+    Operation::Ln,
+    Operation::Ln1p,
+    Operation::Log10,
+    Operation::Log2,
+    Operation::Recip,
+    Operation::Sin,
+    Operation::Sinh,
+    Operation::Sqrt,
+    Operation::Tan,
+    Operation::Tanh,
+    Operation::Erf,
+    Operation::Erfc,
+    Operation::LogSumExp,
+];
+
+/// Code of the first operation without an operand.
+const OPERAND_FREE_OFFSET: u8 = 15;
+
+/// Code of `Operation::SoftmaxCrossEntropy`, after the operations without
+/// an operand.
+const SOFTMAX_CROSS_ENTROPY_CODE: u8 = OPERAND_FREE_OFFSET + OPERAND_FREE_OPERATIONS.len() as u8;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Graph {
+    /// Saves the tape to `path` (see the [module documentation](self) for
+    /// the format).
+    ///
+    /// # Errors
+    /// `IoError` if the file cannot be written.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), RustQuantError> {
+        std::fs::write(path, self.to_bytes())?;
+
+        Ok(())
+    }
+
+    /// Loads a tape saved by [`Graph::save`], replaying it to recompute
+    /// the partials.
+    ///
+    /// # Errors
+    /// - `IoError` if the file cannot be read.
+    /// - `TapeError` if it is not a valid tape of this format version.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, RustQuantError> {
+        Ok(Self::from_bytes(&std::fs::read(path)?)?)
+    }
+
+    /// The tape, encoded as by [`Graph::save`].
+    #[must_use]
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let vertices = self.vertices.borrow();
+        let operations = self.operations.borrow();
+        let fused = self.fused.borrow();
+        let (_, values) = evaluate(&operations, |node| vertices[node].parents, &fused);
+        let mut fused = fused.iter();
+
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + VERTEX_SIZE * vertices.len());
+        bytes.extend_from_slice(&TAPE_MAGIC);
+        bytes.extend_from_slice(&TAPE_FORMAT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(vertices.len() as u64).to_le_bytes());
+
+        for ((vertex, operation), value) in vertices.iter().zip(operations.iter()).zip(values) {
+            let (code, operand) = encode(*operation);
+
+            bytes.push(code);
+            bytes.extend_from_slice(&operand.to_le_bytes());
+            for parent in vertex.parents {
+                bytes.extend_from_slice(&(parent as u64).to_le_bytes());
+            }
+            bytes.extend_from_slice(&value.to_le_bytes());
+
+            if matches!(operation.arity(), Arity::Nary) {
+                let edges = fused.next().expect("Every fused vertex has edges.");
+
+                for list in [&edges.parents, &edges.labels] {
+                    bytes.extend_from_slice(&(list.len() as u64).to_le_bytes());
+                    for index in list {
+                        bytes.extend_from_slice(&(*index as u64).to_le_bytes());
+                    }
+                }
+            }
+        }
+
+        bytes
+    }
+
+    /// Decodes a tape encoded by [`Graph::to_bytes`], replaying it to
+    /// recompute the partials.
+    ///
+    /// # Errors
+    /// A `TapeError` if the header, the format version, the length, an
+    /// operation, or a vertex's parents are invalid, or if replaying the
+    /// operations does not reproduce the saved values.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, TapeError> {
+        let mut reader = Reader { bytes };
+
+        if reader.take(TAPE_MAGIC.len())? != TAPE_MAGIC {
+            return Err(TapeError::InvalidHeader);
+        }
+        let version = reader.u32()?;
+        if version == 0 || version > TAPE_FORMAT_VERSION {
+            return Err(TapeError::VersionMismatch {
+                found: version,
+                expected: TAPE_FORMAT_VERSION,
+            });
+        }
+        let n = usize::try_from(reader.u64()?).map_err(|_| TapeError::Truncated)?;
+        if bytes.len() - HEADER_SIZE < n.saturating_mul(VERTEX_SIZE) {
+            return Err(TapeError::Truncated);
+        }
+
+        let mut operations = Vec::with_capacity(n);
+        let mut parents = Vec::with_capacity(n);
+        let mut values = Vec::with_capacity(n);
+        let mut fused = Vec::new();
+
+        for node in 0..n {
+            let code = reader.u8()?;
+            let operation =
+                decode(code, reader.f64()?).ok_or(TapeError::InvalidOperation { node, code })?;
+
+            let mut vertex_parents = [0; 2];
+            for parent in &mut vertex_parents {
+                *parent = usize::try_from(reader.u64()?)
+                    .map_err(|_| TapeError::InvalidParents { node })?;
+            }
+
+            // The parents must be the ones `Graph::push` records.
+            let valid = match operation.arity() {
+                Arity::Nullary => vertex_parents == [node, node],
+                Arity::Unary => vertex_parents[0] < node && vertex_parents[1] == node,
+                Arity::Binary => vertex_parents[0] < node && vertex_parents[1] < node,
+                Arity::Nary => vertex_parents == [node, node],
+            };
+            if !valid {
+                return Err(TapeError::InvalidParents { node });
+            }
+
+            operations.push(operation);
+            parents.push(vertex_parents);
+            values.push(reader.f64()?);
+
+            if matches!(operation.arity(), Arity::Nary) {
+                let edges = FusedEdges {
+                    node,
+                    parents: reader.indices()?,
+                    partials: Vec::new(),
+                    labels: reader.indices()?,
+                };
+
+                if edges.parents.iter().any(|parent| *parent >= node)
+                    || !valid_fused(operation, edges.parents.len(), &edges.labels)
+                {
+                    return Err(TapeError::InvalidParents { node });
+                }
+                fused.push(edges);
+            }
+        }
+
+        if !reader.bytes.is_empty() {
+            return Err(TapeError::TrailingData);
+        }
+
+        let (graph, replayed) = evaluate(&operations, |node| parents[node], &fused);
+
+        for (node, vertex) in graph.vertices.borrow().iter().enumerate() {
+            if vertex.parents != parents[node] {
+                return Err(TapeError::InvalidParents { node });
+            }
+            if replayed[node].to_bits() != values[node].to_bits() {
+                return Err(TapeError::ValueMismatch { node });
+            }
+        }
+
+        Ok(graph)
+    }
+
+    /// Replays the tape: recomputes the value and partials of every vertex
+    /// from the inputs, in order, and returns the values.
+    ///
+    /// The inputs are the values recorded in the `Operation::Input`
+    /// operations, so they can be changed before replaying to evaluate the
+    /// recorded function (and, with [`Graph::accumulate_at`], its
+    /// gradient) at other points.
+    #[must_use]
+    pub fn replay(&self) -> Vec<f64> {
+        let (graph, values) = {
+            let vertices = self.vertices.borrow();
+            let operations = self.operations.borrow();
+
+            evaluate(
+                &operations,
+                |node| vertices[node].parents,
+                &self.fused.borrow(),
+            )
+        };
+
+        *self.vertices.borrow_mut() = graph.vertices.into_inner();
+        *self.fused.borrow_mut() = graph.fused.into_inner();
+        self.optimized.borrow_mut().take();
+
+        values
+    }
+}
+
+/// Evaluates `operations` on a new graph, with the parents of each vertex
+/// given by `parents` (and by `fused` for fused vertices), by applying the
+/// operators themselves, so the recomputed partials are those the
+/// operators record.
+///
+/// Returns the graph and the value of each vertex.
+fn evaluate<F>(operations: &[Operation], parents: F, fused: &[FusedEdges]) -> (Graph, Vec<f64>)
+where
+    F: Fn(usize) -> [usize; 2],
+{
+    let graph = Graph::with_capacity(operations.len());
+    let mut values: Vec<f64> = Vec::with_capacity(operations.len());
+    let mut fused = fused.iter();
+
+    for (node, operation) in operations.iter().enumerate() {
+        if matches!(operation.arity(), Arity::Nary) {
+            let edges = fused.next().expect("Every fused vertex has edges.");
+            let operands: Vec<Variable> = edges
+                .parents
+                .iter()
+                .map(|&parent| Variable::new(&graph, parent, values[parent]))
+                .collect();
+
+            values.push(apply_fused(*operation, &operands, &edges.labels).value);
+            continue;
+        }
+
+        let value = {
+            let [i, j] = parents(node);
+            let x = Variable::new(&graph, i, values.get(i).copied().unwrap_or_default());
+            let y = Variable::new(&graph, j, values.get(j).copied().unwrap_or_default());
+
+            apply(*operation, &graph, x, y).value
+        };
+
+        values.push(value);
+    }
+
+    (graph, values)
+}
+
+/// Applies `operation` to the operands `x` and `y` (only `x` for unary
+/// operations, and neither for inputs).
+fn apply<'v>(
+    operation: Operation,
+    graph: &'v Graph,
+    x: Variable<'v>,
+    y: Variable<'v>,
+) -> Variable<'v> {
+    match operation {
+        Operation::Input(value) => graph.var(value),
+        Operation::Add => x + y,
+        Operation::AddConstant(c) => x + c,
+        Operation::ConstantSub(c) => c - x,
+        Operation::Mul => x * y,
+        Operation::MulConstant(c) => x * c,
+        Operation::ConstantDiv(c) => c / x,
+        Operation::Powf => x.powf(y),
+        Operation::PowfConstant(c) => x.powf(c),
+        Operation::ConstantPowf(c) => Powf::powf(&c, x),
+        Operation::Powi => x.powi(y),
+        Operation::PowiConstant(n) => x.powi(n),
+        Operation::ConstantPowi(c) => Powi::powi(&c, x),
+        Operation::Log => x.log(y),
+        Operation::LogConstant(c) => x.log(c),
+        Operation::ConstantLog(c) => Log::log(&c, x),
+        Operation::Min => Min::min(&x, y),
+        Operation::MinConstant(c) => Min::min(&x, c),
+        Operation::ConstantMin(c) => Min::min(&c, x),
+        Operation::Max => Max::max(&x, y),
+        Operation::MaxConstant(c) => Max::max(&x, c),
+        Operation::ConstantMax(c) => Max::max(&c, x),
+        Operation::Abs => x.abs(),
+        Operation::Acos => x.acos(),
+        Operation::Acosh => x.acosh(),
+        Operation::Asin => x.asin(),
+        Operation::Asinh => x.asinh(),
+        Operation::Atan => x.atan(),
+        Operation::Atanh => x.atanh(),
+        Operation::Cbrt => x.cbrt(),
+        Operation::Cos => x.cos(),
+        Operation::Cosh => x.cosh(),
+        Operation::Exp => x.exp(),
+        Operation::Exp2 => x.exp2(),
+        Operation::ExpM1 => x.exp_m1(),
+        Operation::Ln => x.ln(),
+        Operation::Ln1p => x.ln_1p(),
+        Operation::Log10 => x.log10(),
+        Operation::Log2 => x.log2(),
+        Operation::Recip => x.recip(),
+        Operation::Sin => x.sin(),
+        Operation::Sinh => x.sinh(),
+        Operation::Sqrt => x.sqrt(),
+        Operation::Tan => x.tan(),
+        Operation::Tanh => x.tanh(),
+        Operation::Erf => x.erf(),
+        Operation::Erfc => x.erfc(),
+        Operation::LogSumExp | Operation::SoftmaxCrossEntropy(_) => {
+            unreachable!("Fused operations are applied by `apply_fused`.")
+        }
+    }
+}
+
+/// Operation code and operand of `operation`.
+fn encode(operation: Operation) -> (u8, f64) {
+    match operation {
+        Operation::Input(value) => (0, value),
+        Operation::AddConstant(c) => (1, c),
+        Operation::ConstantSub(c) => (2, c),
+        Operation::MulConstant(c) => (3, c),
+        Operation::ConstantDiv(c) => (4, c),
+        Operation::PowfConstant(c) => (5, c),
+        Operation::ConstantPowf(c) => (6, c),
+        Operation::PowiConstant(n) => (7, f64::from(n)),
+        Operation::ConstantPowi(c) => (8, c),
+        Operation::LogConstant(c) => (9, c),
+        Operation::ConstantLog(c) => (10, c),
+        Operation::MinConstant(c) => (11, c),
+        Operation::ConstantMin(c) => (12, c),
+        Operation::MaxConstant(c) => (13, c),
+        Operation::ConstantMax(c) => (14, c),
+        Operation::SoftmaxCrossEntropy(n_classes) => (SOFTMAX_CROSS_ENTROPY_CODE, n_classes as f64),
+        operation => {
+            let position = OPERAND_FREE_OPERATIONS
+                .iter()
+                .position(|&o| o == operation)
+                .expect("Every other operation has no operand.");
+
+            (OPERAND_FREE_OFFSET + position as u8, 0.0)
+        }
+    }
+}
+
+/// Operation with code `code` and operand `operand`, if valid.
+fn decode(code: u8, operand: f64) -> Option<Operation> {
+    Some(match code {
+        0 => Operation::Input(operand),
+        1 => Operation::AddConstant(operand),
+        2 => Operation::ConstantSub(operand),
+        3 => Operation::MulConstant(operand),
+        4 => Operation::ConstantDiv(operand),
+        5 => Operation::PowfConstant(operand),
+        6 => Operation::ConstantPowf(operand),
+        7 => {
+            #[allow(clippy::cast_possible_truncation)]
+            let n = operand as i32;
+            if f64::from(n) != operand {
+                return None;
+            }
+            Operation::PowiConstant(n)
+        }
+        8 => Operation::ConstantPowi(operand),
+        9 => Operation::LogConstant(operand),
+        10 => Operation::ConstantLog(operand),
+        11 => Operation::MinConstant(operand),
+        12 => Operation::ConstantMin(operand),
+        13 => Operation::MaxConstant(operand),
+        14 => Operation::ConstantMax(operand),
+        SOFTMAX_CROSS_ENTROPY_CODE => {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let n_classes = operand as usize;
+            if n_classes as f64 != operand {
+                return None;
+            }
+            Operation::SoftmaxCrossEntropy(n_classes)
+        }
+        code => {
+            if operand != 0.0 {
+                return None;
+            }
+            *OPERAND_FREE_OPERATIONS.get(usize::from(code.checked_sub(OPERAND_FREE_OFFSET)?))?
+        }
+    })
+}
+
+/// Cursor over the bytes of a tape.
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], TapeError> {
+        if self.bytes.len() < n {
+            return Err(TapeError::Truncated);
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], TapeError> {
+        Ok(self.take(N)?.try_into().expect("Slice of length N."))
+    }
+
+    fn u8(&mut self) -> Result<u8, TapeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, TapeError> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn u64(&mut self) -> Result<u64, TapeError> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    fn f64(&mut self) -> Result<f64, TapeError> {
+        Ok(f64::from_le_bytes(self.array()?))
+    }
+
+    /// A count followed by that many indices.
+    fn indices(&mut self) -> Result<Vec<usize>, TapeError> {
+        let n = usize::try_from(self.u64()?).map_err(|_| TapeError::Truncated)?;
+        if self.bytes.len() / 8 < n {
+            return Err(TapeError::Truncated);
+        }
+
+        (0..n)
+            .map(|_| usize::try_from(self.u64()?).map_err(|_| TapeError::Truncated))
+            .collect()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_tape {
+    use super::*;
+    use crate::autodiff::Accumulate;
+
+    #[test]
+    fn test_save_load_replay() -> Result<(), RustQuantError> {
+        // The `cosh_xy_div_tanh_x_times_sinh_y` graph.
+        let g = Graph::new();
+        let x = g.var(1.0);
+        let y = g.var(2.0);
+        let z = (x * y).cosh() / (x.tanh() * y.sinh());
+        let gradient = z.accumulate();
+
+        let path = std::env::temp_dir().join(format!("rustquant_tape_{}.bin", std::process::id()));
+        g.save(&path)?;
+        let loaded = Graph::load(&path);
+        std::fs::remove_file(&path)?;
+        let loaded = loaded?;
+
+        assert_eq!(*loaded.vertices.borrow(), *g.vertices.borrow());
+        assert_eq!(*loaded.operations.borrow(), *g.operations.borrow());
+        assert_eq!(loaded.accumulate_at(z.index), gradient);
+
+        let values = loaded.replay();
+        assert_eq!(values.len(), g.len());
+        assert_eq!(values[x.index], 1.0);
+        assert_eq!(values[y.index], 2.0);
+        assert_eq!(values[z.index].to_bits(), z.value.to_bits());
+        assert_eq!(loaded.accumulate_at(z.index), gradient);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_replay_at_new_inputs() {
+        let g = Graph::new();
+        let x = g.var(1.0);
+        let y = g.var(2.0);
+        let z = (x * y).cosh() / (x.tanh() * y.sinh());
+
+        g.operations.borrow_mut()[x.index] = Operation::Input(0.5);
+        let values = g.replay();
+        let gradient = g.accumulate_at(z.index);
+
+        let h = Graph::new();
+        let (a, b) = (h.var(0.5), h.var(2.0));
+        let c = (a * b).cosh() / (a.tanh() * b.sinh());
+        let expected = c.accumulate();
+
+        assert_eq!(values[z.index], c.value);
+        assert_eq!(gradient[x.index], expected[a.index]);
+        assert_eq!(gradient[y.index], expected[b.index]);
+    }
+
+    #[test]
+    fn test_every_operation_round_trips() {
+        let g = Graph::new();
+        let x = g.var(0.3);
+        let y = g.var(1.7);
+
+        let terms = [
+            x + y,
+            x + 2.0,
+            2.0 - x,
+            x - y,
+            x * y,
+            -x,
+            x / 4.0,
+            3.0 / x,
+            x / y,
+            y.powf(x),
+            y.powf(2.5),
+            Powf::powf(&2.0, x),
+            y.powi(x),
+            y.powi(3),
+            Powi::powi(&2.0, x),
+            y.log(y + 1.0),
+            y.log(10.0),
+            Log::log(&5.0, y),
+            Min::min(&x, y),
+            Min::min(&x, 0.1),
+            Min::min(&0.1, x),
+            Max::max(&x, y),
+            Max::max(&x, 0.1),
+            Max::max(&0.1, x),
+            x.abs() + x.acos() + y.acosh() + x.asin() + x.asinh() + x.atan() + x.atanh(),
+            x.cbrt() + x.cos() + x.cosh() + x.exp() + x.exp2() + x.exp_m1() + x.ln(),
+            x.ln_1p() + x.log10() + x.log2() + x.recip() + x.sin() + x.sinh() + x.sqrt(),
+            x.tan() + x.tanh() + x.erf() + x.erfc(),
+        ];
+        let f: Variable = terms.iter().copied().sum();
+        let gradient = f.accumulate();
+
+        let loaded = Graph::from_bytes(&g.to_bytes()).expect("Valid tape.");
+
+        assert_eq!(*loaded.vertices.borrow(), *g.vertices.borrow());
+        assert_eq!(*loaded.operations.borrow(), *g.operations.borrow());
+        assert_eq!(loaded.replay()[f.index].to_bits(), f.value.to_bits());
+        assert_eq!(loaded.accumulate_at(f.index), gradient);
+    }
+
+    #[test]
+    fn test_fused_operations_round_trip() {
+        let g = Graph::new();
+        let x = g.vars(&[0.3, -1.2, 2.0, 0.5, 0.5, -0.7]);
+        let f = crate::autodiff::softmax_cross_entropy_batch(&[&x[..3], &x[3..]], &[2, 0])
+            * crate::autodiff::logsumexp(&x[1..4]);
+        let gradient = f.accumulate();
+
+        let bytes = g.to_bytes();
+        let loaded = Graph::from_bytes(&bytes).expect("Valid tape.");
+
+        assert_eq!(*loaded.operations.borrow(), *g.operations.borrow());
+        assert_eq!(*loaded.fused.borrow(), *g.fused.borrow());
+        assert_eq!(loaded.replay()[f.index].to_bits(), f.value.to_bits());
+        assert_eq!(loaded.accumulate_at(f.index), gradient);
+
+        // The first label of the cross-entropy vertex (node 6), after its
+        // six parents, out of range.
+        let mut corrupted = bytes.clone();
+        corrupted[HEADER_SIZE + 7 * VERTEX_SIZE + 8 * 8] = 3;
+        assert_eq!(
+            Graph::from_bytes(&corrupted).err(),
+            Some(TapeError::InvalidParents { node: 6 })
+        );
+    }
+
+    #[test]
+    fn test_corrupted_tapes() {
+        let g = Graph::new();
+        let x = g.var(1.0);
+        let y = g.var(2.0);
+        let _ = (x * y).sin() + x;
+        let bytes = g.to_bytes();
+        assert!(Graph::from_bytes(&bytes).is_ok());
+
+        let corrupt = |offset: usize, byte: u8| {
+            let mut corrupted = bytes.clone();
+            corrupted[offset] = byte;
+            Graph::from_bytes(&corrupted).err()
+        };
+        let vertex = |node: usize| HEADER_SIZE + node * VERTEX_SIZE;
+
+        assert_eq!(Graph::from_bytes(&[]).err(), Some(TapeError::Truncated));
+        assert_eq!(corrupt(0, b'X'), Some(TapeError::InvalidHeader));
+        assert_eq!(
+            corrupt(8, 3),
+            Some(TapeError::VersionMismatch {
+                found: 3,
+                expected: TAPE_FORMAT_VERSION
+            })
+        );
+        // Version 1 tapes have no fused vertices, so they are still valid.
+        assert!(corrupt(8, 1).is_none());
+        assert_eq!(
+            Graph::from_bytes(&bytes[..bytes.len() - 1]).err(),
+            Some(TapeError::Truncated)
+        );
+        assert_eq!(
+            Graph::from_bytes(&[bytes.as_slice(), &[0]].concat()).err(),
+            Some(TapeError::TrailingData)
+        );
+        assert_eq!(
+            corrupt(vertex(3), 200),
+            Some(TapeError::InvalidOperation { node: 3, code: 200 })
+        );
+        // The `sin` vertex pointing at itself.
+        assert_eq!(
+            corrupt(vertex(3) + 9, 3),
+            Some(TapeError::InvalidParents { node: 3 })
+        );
+        // The first input's value, changed in the saved values only.
+        assert_eq!(
+            corrupt(vertex(0) + 25 + 7, 0x41),
+            Some(TapeError::ValueMismatch { node: 0 })
+        );
+
+        assert!(matches!(
+            Graph::load(std::env::temp_dir().join("rustquant_no_such_tape.bin")),
+            Err(RustQuantError::IoError(_))
+        ));
+    }
+}
@@ -41,6 +41,119 @@ pub enum Arity {
     /// Binary operation (e.g. x + y).
     /// This has two parents.
     Binary,
+    /// Fused operation (e.g. `logsumexp(&x)`).
+    /// This has any number of parents, kept outside the vertex.
+    Nary,
+}
+
+/// Operation that produced a vertex, recorded on the graph so the tape
+/// can be saved and replayed (see [`crate::autodiff::Graph::replay`]).
+///
+/// Operations with an `f64` operand record it, with the prefix `Constant`
+/// when the constant is on the left (e.g. `ConstantSub(c)` is `c - x`).
+/// Subtraction and division of variables are recorded as the negation or
+/// reciprocal followed by an addition or multiplication, as they are
+/// computed.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Operation {
+    /// An input variable (leaf), with its value.
+    Input(f64),
+    /// `x + y`
+    Add,
+    /// `x + c`
+    AddConstant(f64),
+    /// `c - x`
+    ConstantSub(f64),
+    /// `x * y`
+    Mul,
+    /// `x * c` (also `-x` and `x / c`)
+    MulConstant(f64),
+    /// `c / x`
+    ConstantDiv(f64),
+    /// `x.powf(y)`
+    Powf,
+    /// `x.powf(c)`
+    PowfConstant(f64),
+    /// `c.powf(x)`
+    ConstantPowf(f64),
+    /// `x.powi(y)`
+    Powi,
+    /// `x.powi(n)`
+    PowiConstant(i32),
+    /// `c.powi(x)`
+    ConstantPowi(f64),
+    /// `x.log(y)`
+    Log,
+    /// `x.log(c)`
+    LogConstant(f64),
+    /// `c.log(x)`
+    ConstantLog(f64),
+    /// `x.min(y)`
+    Min,
+    /// `x.min(c)`
+    MinConstant(f64),
+    /// `c.min(x)`
+    ConstantMin(f64),
+    /// `x.max(y)`
+    Max,
+    /// `x.max(c)`
+    MaxConstant(f64),
+    /// `c.max(x)`
+    ConstantMax(f64),
+    /// `x.abs()`
+    Abs,
+    /// `x.acos()`
+    Acos,
+    /// `x.acosh()`
+    Acosh,
+    /// `x.asin()`
+    Asin,
+    /// `x.asinh()`
+    Asinh,
+    /// `x.atan()`
+    Atan,
+    /// `x.atanh()`
+    Atanh,
+    /// `x.cbrt()`
+    Cbrt,
+    /// `x.cos()`
+    Cos,
+    /// `x.cosh()`
+    Cosh,
+    /// `x.exp()`
+    Exp,
+    /// `x.exp2()`
+    Exp2,
+    /// `x.exp_m1()`
+    ExpM1,
+    /// `x.ln()`
+    Ln,
+    /// `x.ln_1p()`
+    Ln1p,
+    /// `x.log10()`
+    Log10,
+    /// `x.log2()`
+    Log2,
+    /// `x.recip()`
+    Recip,
+    /// `x.sin()`
+    Sin,
+    /// `x.sinh()`
+    Sinh,
+    /// `x.sqrt()`
+    Sqrt,
+    /// `x.tan()`
+    Tan,
+    /// `x.tanh()`
+    Tanh,
+    /// `x.erf()`
+    Erf,
+    /// `x.erfc()`
+    Erfc,
+    /// `logsumexp(&x)`
+    LogSumExp,
+    /// `softmax_cross_entropy_batch(&x, &targets)` with this many classes
+    SoftmaxCrossEntropy(usize),
 }
 
 impl Vertex {
@@ -89,6 +202,42 @@ impl Vertex {
     }
 }
 
+impl Operation {
+    /// Arity of the vertex the operation pushes to the graph.
+    ///
+    /// Operations with a constant operand push a binary vertex whose
+    /// parents are both the variable operand.
+    #[must_use]
+    pub const fn arity(&self) -> Arity {
+        match self {
+            Self::Input(_) => Arity::Nullary,
+            Self::Add
+            | Self::AddConstant(_)
+            | Self::ConstantSub(_)
+            | Self::Mul
+            | Self::MulConstant(_)
+            | Self::ConstantDiv(_)
+            | Self::Powf
+            | Self::PowfConstant(_)
+            | Self::ConstantPowf(_)
+            | Self::Powi
+            | Self::PowiConstant(_)
+            | Self::ConstantPowi(_)
+            | Self::Log
+            | Self::LogConstant(_)
+            | Self::ConstantLog(_)
+            | Self::Min
+            | Self::MinConstant(_)
+            | Self::ConstantMin(_)
+            | Self::Max
+            | Self::MaxConstant(_)
+            | Self::ConstantMax(_) => Arity::Binary,
+            Self::LogSumExp | Self::SoftmaxCrossEntropy(_) => Arity::Nary,
+            _ => Arity::Unary,
+        }
+    }
+}
+
 impl PartialEq for Vertex {
     fn eq(&self, other: &Self) -> bool {
         self.partials == other.partials && self.parents == other.parents
@@ -0,0 +1,574 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Arrow IPC (Feather v2) files for simulation and backtest output, written
+//! with polars' Arrow writer, so they can be read by `pyarrow` or
+//! `polars` in Python.
+//!
+//! - Path matrices have one row per time point: a `time` column, then one
+//!   column `path_{j}` per path. [`IpcPathWriter`] writes them in record
+//!   batches of time steps, so a simulation can stream its states to disk
+//!   without holding (or copying) the whole matrix.
+//! - A [`MonteCarloResult`] is a single row, with its convergence trace in
+//!   the list columns `trace_paths` and `trace_estimates`.
+//! - A backtest equity curve has columns `bar` and `equity`; a trade list
+//!   has one row per [`Fill`], with the side as `"BID"` or `"ASK"` and the
+//!   timestamp as a nanosecond datetime (UTC).
+//!
+//! Files are written in standard (not polars-specific) Arrow types, for
+//! compatibility with other Arrow readers.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use crate::pricer::MonteCarloResult;
+use crate::stochastics::Trajectories;
+use crate::trading::{backtest::BacktestResult, fill::Fill, order_side::OrderSide};
+use nalgebra::DMatrix;
+use polars::io::ipc::BatchedWriter;
+use polars::prelude::*;
+use std::fs::File;
+use std::path::Path;
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Streaming writer of a path matrix to an Arrow IPC file, one batch of time
+/// steps at a time.
+pub struct IpcPathWriter {
+    /// Underlying batched IPC writer.
+    writer: BatchedWriter<File>,
+    /// Column names of the paths.
+    names: Vec<String>,
+    /// Number of time steps written so far.
+    rows_written: usize,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl IpcPathWriter {
+    /// Creates (or truncates) `file_path` for a matrix of `n_paths` paths.
+    ///
+    /// # Errors
+    /// `RustQuantError::IoError` if the file cannot be created.
+    pub fn create<P: AsRef<Path>>(file_path: P, n_paths: usize) -> Result<Self, RustQuantError> {
+        let names: Vec<String> = (0..n_paths).map(|j| format!("path_{j}")).collect();
+
+        let schema = std::iter::once(Field::new("time", DataType::Float64))
+            .chain(names.iter().map(|name| Field::new(name, DataType::Float64)))
+            .collect::<Schema>();
+
+        let writer = IpcWriter::new(File::create(file_path)?)
+            .with_pl_flavor(false)
+            .batched(&schema)?;
+
+        Ok(Self {
+            writer,
+            names,
+            rows_written: 0,
+        })
+    }
+
+    /// Appends the states at `times`: `states` has one row per time and one
+    /// column per path.
+    ///
+    /// # Errors
+    /// `RustQuantError::InvalidArgument` if the shape of `states` does not
+    /// match, or a polars error from writing the batch.
+    pub fn write_steps(
+        &mut self,
+        times: &[f64],
+        states: &DMatrix<f64>,
+    ) -> Result<(), RustQuantError> {
+        if states.shape() != (times.len(), self.names.len()) {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Expected a {} by {} matrix of states, got {:?}.",
+                times.len(),
+                self.names.len(),
+                states.shape()
+            )));
+        }
+
+        // Columns of a column-major matrix are contiguous.
+        let columns = std::iter::once(Series::new("time", times))
+            .chain(
+                self.names
+                    .iter()
+                    .zip(states.column_iter())
+                    .map(|(name, column)| Series::new(name, column.as_slice())),
+            )
+            .collect();
+
+        self.writer.write_batch(&DataFrame::new(columns)?)?;
+        self.rows_written += times.len();
+
+        Ok(())
+    }
+
+    /// Writes the file footer, and returns the number of time steps written.
+    ///
+    /// # Errors
+    /// A polars error from writing the footer.
+    pub fn finish(mut self) -> Result<usize, RustQuantError> {
+        self.writer.finish()?;
+
+        Ok(self.rows_written)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Writes `trajectories` to `file_path`, `chunk_rows` time steps per record
+/// batch, so at most one chunk is copied at a time.
+///
+/// # Errors
+/// `RustQuantError::InvalidArgument` if `chunk_rows` is zero or a path's
+/// length differs from the time grid's, or any error from
+/// [`IpcPathWriter`].
+pub fn write_trajectories_ipc<P: AsRef<Path>>(
+    file_path: P,
+    trajectories: &Trajectories,
+    chunk_rows: usize,
+) -> Result<(), RustQuantError> {
+    let times = &trajectories.times;
+    let paths = &trajectories.paths;
+
+    if chunk_rows == 0 {
+        return Err(RustQuantError::InvalidArgument(
+            "Chunks must have at least one row.".to_string(),
+        ));
+    }
+    if paths.iter().any(|path| path.len() != times.len()) {
+        return Err(RustQuantError::InvalidArgument(
+            "Every path must have one value per time point.".to_string(),
+        ));
+    }
+
+    let mut writer = IpcPathWriter::create(file_path, paths.len())?;
+
+    for start in (0..times.len()).step_by(chunk_rows) {
+        let end = (start + chunk_rows).min(times.len());
+        let states = DMatrix::from_fn(end - start, paths.len(), |i, j| paths[j][start + i]);
+
+        writer.write_steps(&times[start..end], &states)?;
+    }
+
+    writer.finish()?;
+
+    Ok(())
+}
+
+/// Reads a path matrix written by [`IpcPathWriter`] or
+/// [`write_trajectories_ipc`]: all paths, or only those with the indices in
+/// `paths`, in that order.
+///
+/// # Errors
+/// `RustQuantError::IoError` if the file cannot be opened, or a polars
+/// error if it is not a path matrix.
+pub fn read_trajectories_ipc<P: AsRef<Path>>(
+    file_path: P,
+    paths: Option<&[usize]>,
+) -> Result<Trajectories, RustQuantError> {
+    let columns: Option<Vec<String>> = paths.map(|indices| {
+        std::iter::once("time".to_string())
+            .chain(indices.iter().map(|j| format!("path_{j}")))
+            .collect()
+    });
+
+    let mut df = IpcReader::new(File::open(file_path)?)
+        .with_columns(columns.clone())
+        .finish()?;
+
+    // The projection is read in file order.
+    if let Some(columns) = columns {
+        df = df.select(columns)?;
+    }
+
+    let times = float_column(&df, "time")?;
+    let paths = df
+        .get_columns()
+        .iter()
+        .filter(|series| series.name() != "time")
+        .map(|series| Ok(series.f64()?.into_no_null_iter().collect()))
+        .collect::<Result<Vec<Vec<f64>>, RustQuantError>>()?;
+
+    Ok(Trajectories { times, paths })
+}
+
+/// Writes `result` to `file_path`, as a single row.
+///
+/// # Errors
+/// `RustQuantError::IoError` if the file cannot be created, or a polars
+/// error from writing it.
+pub fn write_monte_carlo_result_ipc<P: AsRef<Path>>(
+    file_path: P,
+    result: &MonteCarloResult,
+) -> Result<(), RustQuantError> {
+    let (trace_paths, trace_estimates): (Vec<u64>, Vec<f64>) = result
+        .convergence_trace
+        .iter()
+        .map(|&(paths, estimate)| (paths as u64, estimate))
+        .unzip();
+
+    let mut df = df!(
+        "price" => [result.price],
+        "standard_error" => [result.standard_error],
+        "ci_lower" => [result.confidence_interval.0],
+        "ci_upper" => [result.confidence_interval.1],
+        "n_paths" => [result.n_paths as u64],
+        "skewness" => [result.skewness],
+        "kurtosis" => [result.kurtosis],
+        "variance_reduction_factor" => [result.variance_reduction_factor],
+        "converged" => [result.converged],
+        "trace_paths" => [Series::new("", trace_paths)],
+        "trace_estimates" => [Series::new("", trace_estimates)],
+    )?;
+
+    write_frame(file_path, &mut df)
+}
+
+/// Reads a [`MonteCarloResult`] written by [`write_monte_carlo_result_ipc`].
+///
+/// # Errors
+/// `RustQuantError::IoError` if the file cannot be opened,
+/// `RustQuantError::MissingInput` if it has no rows or a required value is
+/// null, or a polars error if a column is missing or has the wrong type.
+pub fn read_monte_carlo_result_ipc<P: AsRef<Path>>(
+    file_path: P,
+) -> Result<MonteCarloResult, RustQuantError> {
+    let df = IpcReader::new(File::open(file_path)?).finish()?;
+
+    let value = |name: &str| -> Result<f64, RustQuantError> {
+        df.column(name)?.f64()?.get(0).ok_or_else(|| missing(name))
+    };
+    let list = |name: &str| -> Result<Series, RustQuantError> {
+        df.column(name)?
+            .list()?
+            .get_as_series(0)
+            .ok_or_else(|| missing(name))
+    };
+
+    let trace_paths = list("trace_paths")?;
+    let trace_estimates = list("trace_estimates")?;
+    let convergence_trace = trace_paths
+        .u64()?
+        .into_no_null_iter()
+        .zip(trace_estimates.f64()?.into_no_null_iter())
+        .map(|(paths, estimate)| (paths as usize, estimate))
+        .collect();
+
+    Ok(MonteCarloResult {
+        price: value("price")?,
+        standard_error: value("standard_error")?,
+        confidence_interval: (value("ci_lower")?, value("ci_upper")?),
+        n_paths: df
+            .column("n_paths")?
+            .u64()?
+            .get(0)
+            .ok_or_else(|| missing("n_paths"))? as usize,
+        convergence_trace,
+        skewness: value("skewness")?,
+        kurtosis: value("kurtosis")?,
+        variance_reduction_factor: df.column("variance_reduction_factor")?.f64()?.get(0),
+        converged: df
+            .column("converged")?
+            .bool()?
+            .get(0)
+            .ok_or_else(|| missing("converged"))?,
+    })
+}
+
+/// Writes the equity curve of `result` to `file_path`, with columns `bar`
+/// and `equity`.
+///
+/// # Errors
+/// `RustQuantError::IoError` if the file cannot be created, or a polars
+/// error from writing it.
+pub fn write_equity_curve_ipc<P: AsRef<Path>>(
+    file_path: P,
+    result: &BacktestResult,
+) -> Result<(), RustQuantError> {
+    let mut df = df!(
+        "bar" => (0..result.equity_curve.len() as u64).collect::<Vec<u64>>(),
+        "equity" => &result.equity_curve,
+    )?;
+
+    write_frame(file_path, &mut df)
+}
+
+/// Reads an equity curve written by [`write_equity_curve_ipc`].
+///
+/// # Errors
+/// `RustQuantError::IoError` if the file cannot be opened, or a polars
+/// error if the `equity` column is missing or has the wrong type.
+pub fn read_equity_curve_ipc<P: AsRef<Path>>(file_path: P) -> Result<Vec<f64>, RustQuantError> {
+    let df = IpcReader::new(File::open(file_path)?).finish()?;
+
+    float_column(&df, "equity")
+}
+
+/// Writes `fills` (e.g. the trade list of a backtest) to `file_path`, one row
+/// per fill.
+///
+/// # Errors
+/// `RustQuantError::IoError` if the file cannot be created, or a polars
+/// error from writing it.
+pub fn write_fills_ipc<P: AsRef<Path>>(file_path: P, fills: &[Fill]) -> Result<(), RustQuantError> {
+    let datetime = DataType::Datetime(TimeUnit::Nanoseconds, None);
+    let timestamps: Vec<i64> = fills
+        .iter()
+        .map(|fill| fill.timestamp.unix_timestamp_nanos() as i64)
+        .collect();
+
+    let mut df = df!(
+        "order_id" => fills.iter().map(|fill| fill.order_id).collect::<Vec<u64>>(),
+        "symbol_id" => fills.iter().map(|fill| fill.symbol_id).collect::<Vec<u32>>(),
+        "side" => fills.iter().map(|fill| match fill.side {
+            OrderSide::BID => "BID",
+            OrderSide::ASK => "ASK",
+        }).collect::<Vec<&str>>(),
+        "price" => fills.iter().map(|fill| fill.price).collect::<Vec<f64>>(),
+        "quantity" => fills.iter().map(|fill| fill.quantity).collect::<Vec<u64>>(),
+        "timestamp" => Series::new("timestamp", timestamps).cast(&datetime)?,
+    )?;
+
+    write_frame(file_path, &mut df)
+}
+
+/// Reads fills written by [`write_fills_ipc`].
+///
+/// # Errors
+/// `RustQuantError::IoError` if the file cannot be opened,
+/// `RustQuantError::MissingInput` if a value is null, a side is not `"BID"`
+/// or `"ASK"`, or a timestamp is out of range, or a polars error if a
+/// column is missing or has the wrong type.
+pub fn read_fills_ipc<P: AsRef<Path>>(file_path: P) -> Result<Vec<Fill>, RustQuantError> {
+    let df = IpcReader::new(File::open(file_path)?).finish()?;
+
+    let timestamps = df.column("timestamp")?.cast(&DataType::Int64)?;
+
+    df.column("order_id")?
+        .u64()?
+        .into_iter()
+        .zip(df.column("symbol_id")?.u32()?)
+        .zip(df.column("side")?.str()?)
+        .zip(df.column("price")?.f64()?)
+        .zip(df.column("quantity")?.u64()?)
+        .zip(timestamps.i64()?)
+        .map(
+            |(((((order_id, symbol_id), side), price), quantity), timestamp)| {
+                let (
+                    Some(order_id),
+                    Some(symbol_id),
+                    Some(side),
+                    Some(price),
+                    Some(quantity),
+                    Some(timestamp),
+                ) = (order_id, symbol_id, side, price, quantity, timestamp)
+                else {
+                    return Err(missing("fill"));
+                };
+
+                let side = match side {
+                    "BID" => OrderSide::BID,
+                    "ASK" => OrderSide::ASK,
+                    other => {
+                        return Err(RustQuantError::MissingInput(format!(
+                            "Unknown order side {other:?}."
+                        )))
+                    }
+                };
+                let timestamp = OffsetDateTime::from_unix_timestamp_nanos(i128::from(timestamp))
+                    .map_err(|e| RustQuantError::MissingInput(e.to_string()))?;
+
+                Ok(Fill::new(
+                    order_id, symbol_id, side, price, quantity, timestamp,
+                ))
+            },
+        )
+        .collect()
+}
+
+/// Writes `df` to `file_path` in standard Arrow types.
+fn write_frame<P: AsRef<Path>>(file_path: P, df: &mut DataFrame) -> Result<(), RustQuantError> {
+    let mut file = File::create(file_path)?;
+
+    IpcWriter::new(&mut file).with_pl_flavor(false).finish(df)?;
+
+    Ok(())
+}
+
+/// Values of the `Float64` column `name`.
+fn float_column(df: &DataFrame, name: &str) -> Result<Vec<f64>, RustQuantError> {
+    Ok(df.column(name)?.f64()?.into_no_null_iter().collect())
+}
+
+/// Error for a missing value in column `name`.
+fn missing(name: &str) -> RustQuantError {
+    RustQuantError::MissingInput(format!("Null or missing value in `{name}`."))
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_arrow_ipc {
+    use super::*;
+    use time::macros::datetime;
+
+    fn temp_file(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("rustquant_{}_{name}.arrow", std::process::id()))
+    }
+
+    fn trajectories() -> Trajectories {
+        let times: Vec<f64> = (0..=250).map(|i| f64::from(i) / 250.0).collect();
+        let paths = (0..7)
+            .map(|j| {
+                times
+                    .iter()
+                    .map(|t| 100.0 + f64::from(j) * t - t * t)
+                    .collect()
+            })
+            .collect();
+
+        Trajectories { times, paths }
+    }
+
+    #[test]
+    fn test_trajectories_round_trip() -> Result<(), RustQuantError> {
+        let file = temp_file("paths");
+        let original = trajectories();
+
+        // 251 time steps in chunks of 64, so the last chunk is partial.
+        write_trajectories_ipc(&file, &original, 64)?;
+
+        let read = read_trajectories_ipc(&file, None)?;
+        assert_eq!(read.times, original.times);
+        assert_eq!(read.paths, original.paths);
+
+        let subset = read_trajectories_ipc(&file, Some(&[5, 2]))?;
+        assert_eq!(subset.times, original.times);
+        assert_eq!(
+            subset.paths,
+            vec![original.paths[5].clone(), original.paths[2].clone()]
+        );
+
+        std::fs::remove_file(file)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_streaming_path_writer() -> Result<(), RustQuantError> {
+        let file = temp_file("stream");
+        let mut writer = IpcPathWriter::create(&file, 3)?;
+
+        writer.write_steps(
+            &[0.0, 0.5],
+            &DMatrix::from_row_slice(2, 3, &[1., 2., 3., 4., 5., 6.]),
+        )?;
+        writer.write_steps(&[1.0], &DMatrix::from_row_slice(1, 3, &[7., 8., 9.]))?;
+        assert!(writer.write_steps(&[1.5], &DMatrix::zeros(1, 2)).is_err());
+        assert_eq!(writer.finish()?, 3);
+
+        let read = read_trajectories_ipc(&file, None)?;
+        assert_eq!(read.times, vec![0.0, 0.5, 1.0]);
+        assert_eq!(
+            read.paths,
+            vec![vec![1., 4., 7.], vec![2., 5., 8.], vec![3., 6., 9.]]
+        );
+
+        std::fs::remove_file(file)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_monte_carlo_result_round_trip() -> Result<(), RustQuantError> {
+        let file = temp_file("mc_result");
+
+        for variance_reduction_factor in [Some(3.5), None] {
+            let result = MonteCarloResult {
+                price: 10.45,
+                standard_error: 0.02,
+                confidence_interval: (10.41, 10.49),
+                n_paths: 100_000,
+                convergence_trace: vec![(25_000, 10.5), (50_000, 10.47), (100_000, 10.45)],
+                skewness: 1.2,
+                kurtosis: 3.4,
+                variance_reduction_factor,
+                converged: true,
+            };
+
+            write_monte_carlo_result_ipc(&file, &result)?;
+            assert_eq!(read_monte_carlo_result_ipc(&file)?, result);
+        }
+
+        std::fs::remove_file(file)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_backtest_round_trip() -> Result<(), RustQuantError> {
+        let equity_file = temp_file("equity");
+        let fills_file = temp_file("fills");
+
+        let fills = vec![
+            Fill::new(
+                1,
+                7,
+                OrderSide::BID,
+                101.25,
+                10,
+                datetime!(2024-01-02 14:30:00.123456789 UTC),
+            ),
+            Fill::new(
+                2,
+                7,
+                OrderSide::ASK,
+                102.5,
+                4,
+                datetime!(2024-01-03 15:00:00 UTC),
+            ),
+        ];
+        let result = BacktestResult {
+            equity_curve: vec![10_000.0, 10_012.5, 9_998.75],
+            fills: fills.clone(),
+            final_position: 6,
+            periods_per_year: 252.0,
+        };
+
+        write_equity_curve_ipc(&equity_file, &result)?;
+        write_fills_ipc(&fills_file, &result.fills)?;
+
+        assert_eq!(read_equity_curve_ipc(&equity_file)?, result.equity_curve);
+
+        let read = read_fills_ipc(&fills_file)?;
+        assert_eq!(read.len(), fills.len());
+        for (read, fill) in read.iter().zip(&fills) {
+            assert_eq!(read.order_id, fill.order_id);
+            assert_eq!(read.symbol_id, fill.symbol_id);
+            assert_eq!(read.side, fill.side);
+            assert_eq!(read.price, fill.price);
+            assert_eq!(read.quantity, fill.quantity);
+            assert_eq!(read.timestamp, fill.timestamp);
+        }
+
+        std::fs::remove_file(equity_file)?;
+        std::fs::remove_file(fills_file)?;
+        Ok(())
+    }
+}
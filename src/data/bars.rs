@@ -0,0 +1,718 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! OHLCV bar aggregation from tick (trade) data.
+//!
+//! Besides the usual time bars, the aggregator produces the "alternative bars"
+//! of Lopez de Prado (2018), *Advances in Financial Machine Learning*, ch. 2:
+//! volume bars and dollar bars, which close once a given volume or traded
+//! value has been exchanged, and so sample more often when the market is active.
+//!
+//! Ticks may arrive slightly out of order: they are held in a reorder buffer
+//! until they are older than the newest tick by more than a tolerance, and
+//! then aggregated in timestamp order. Ticks arriving later than that are dropped.
+//!
+//! Volume and dollar bars can optionally split the tick that crosses the
+//! threshold, so each bar holds exactly the threshold; [`aggregate_to_bars`]
+//! does this for a whole batch of ticks.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use crate::trading::{fill::Fill, order_side::OrderSide};
+use polars::prelude::*;
+use time::{Duration, OffsetDateTime};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// A single trade.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tick {
+    /// Trade time.
+    pub timestamp: OffsetDateTime,
+    /// Trade price.
+    pub price: f64,
+    /// Trade quantity.
+    pub quantity: f64,
+    /// Side of the aggressor (bid = buyer initiated), if known.
+    pub side: Option<OrderSide>,
+}
+
+/// When a bar closes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BarType {
+    /// Fixed time intervals, aligned to the Unix epoch.
+    Time(Duration),
+    /// Once the traded volume reaches the threshold.
+    Volume(f64),
+    /// Once the traded value (price times quantity) reaches the threshold.
+    Dollar(f64),
+}
+
+/// What to do with time intervals without any trades.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmptyBars {
+    /// Emit no bar for the interval.
+    Skip,
+    /// Emit a bar at the previous close, with zero volume.
+    ForwardFill,
+}
+
+/// An OHLCV bar.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bar {
+    /// Start of the bar: the interval start for time bars,
+    /// the first trade time otherwise.
+    pub start: OffsetDateTime,
+    /// End of the bar: the (exclusive) interval end for time bars,
+    /// the last trade time otherwise.
+    pub end: OffsetDateTime,
+    /// First traded price.
+    pub open: f64,
+    /// Highest traded price.
+    pub high: f64,
+    /// Lowest traded price.
+    pub low: f64,
+    /// Last traded price.
+    pub close: f64,
+    /// Traded volume.
+    pub volume: f64,
+    /// Traded value (sum of price times quantity).
+    pub dollar_volume: f64,
+    /// Number of trades.
+    pub trade_count: usize,
+}
+
+/// Incremental bar aggregator.
+#[derive(Debug, Clone)]
+pub struct BarAggregator {
+    /// When bars close.
+    pub bar_type: BarType,
+    /// How long a tick may be held back to wait for earlier ticks.
+    pub tolerance: Duration,
+    /// Handling of time intervals without trades.
+    pub empty_bars: EmptyBars,
+    /// Whether volume and dollar bars split the tick crossing the
+    /// threshold, so each bar holds exactly the threshold.
+    pub split_ticks: bool,
+
+    /// Ticks not yet aggregated, sorted by timestamp.
+    buffer: Vec<Tick>,
+    /// Timestamp of the newest tick received.
+    newest: Option<OffsetDateTime>,
+    /// Timestamp of the last tick aggregated.
+    last_released: Option<OffsetDateTime>,
+    /// Bar being built.
+    current: Option<Bar>,
+    /// Close of the last bar emitted, for forward filling.
+    last_close: Option<f64>,
+    dropped: usize,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl From<&Fill> for Tick {
+    fn from(fill: &Fill) -> Self {
+        Self {
+            timestamp: fill.timestamp,
+            price: fill.price,
+            quantity: fill.quantity as f64,
+            side: Some(fill.side),
+        }
+    }
+}
+
+#[cfg(feature = "websocket")]
+impl From<&crate::data::websocket::Trade> for Tick {
+    fn from(trade: &crate::data::websocket::Trade) -> Self {
+        Self {
+            timestamp: trade.timestamp,
+            price: trade.price,
+            quantity: trade.quantity,
+            side: Some(trade.aggressor_side),
+        }
+    }
+}
+
+impl Bar {
+    fn new(start: OffsetDateTime, end: OffsetDateTime, tick: &Tick) -> Self {
+        Self {
+            start,
+            end,
+            open: tick.price,
+            high: tick.price,
+            low: tick.price,
+            close: tick.price,
+            volume: tick.quantity,
+            dollar_volume: tick.price * tick.quantity,
+            trade_count: 1,
+        }
+    }
+
+    fn empty(start: OffsetDateTime, end: OffsetDateTime, price: f64) -> Self {
+        Self {
+            start,
+            end,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: 0.0,
+            dollar_volume: 0.0,
+            trade_count: 0,
+        }
+    }
+
+    fn update(&mut self, tick: &Tick) {
+        self.high = self.high.max(tick.price);
+        self.low = self.low.min(tick.price);
+        self.close = tick.price;
+        self.volume += tick.quantity;
+        self.dollar_volume += tick.price * tick.quantity;
+        self.trade_count += 1;
+    }
+
+    /// Volume-weighted average price of the bar (the close for empty bars).
+    #[must_use]
+    pub fn vwap(&self) -> f64 {
+        if self.volume > 0.0 {
+            self.dollar_volume / self.volume
+        } else {
+            self.close
+        }
+    }
+}
+
+impl BarAggregator {
+    /// Create a new `BarAggregator`, with no out-of-order tolerance,
+    /// which skips empty time intervals.
+    ///
+    /// # Panics
+    /// Panics if the bar interval or threshold is not positive.
+    #[must_use]
+    pub fn new(bar_type: BarType) -> Self {
+        match bar_type {
+            BarType::Time(interval) => {
+                assert!(interval.is_positive(), "Bar interval must be positive.");
+            }
+            BarType::Volume(threshold) | BarType::Dollar(threshold) => {
+                assert!(threshold > 0.0, "Bar threshold must be positive.");
+            }
+        }
+
+        Self {
+            bar_type,
+            tolerance: Duration::ZERO,
+            empty_bars: EmptyBars::Skip,
+            split_ticks: false,
+            buffer: Vec::new(),
+            newest: None,
+            last_released: None,
+            current: None,
+            last_close: None,
+            dropped: 0,
+        }
+    }
+
+    /// Set the out-of-order tolerance.
+    #[must_use]
+    pub fn with_tolerance(mut self, tolerance: Duration) -> Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Set the handling of empty time intervals.
+    #[must_use]
+    pub fn with_empty_bars(mut self, empty_bars: EmptyBars) -> Self {
+        self.empty_bars = empty_bars;
+        self
+    }
+
+    /// Split the tick crossing the threshold of a volume or dollar bar
+    /// between that bar and the next, so each bar holds exactly the
+    /// threshold. A split tick counts as a trade in both bars.
+    #[must_use]
+    pub fn with_split_ticks(mut self, split_ticks: bool) -> Self {
+        self.split_ticks = split_ticks;
+        self
+    }
+
+    /// Number of ticks dropped for arriving too late.
+    #[must_use]
+    pub fn dropped_ticks(&self) -> usize {
+        self.dropped
+    }
+
+    /// Add a tick, returning the bars it closed (possibly none).
+    pub fn push(&mut self, tick: Tick) -> Vec<Bar> {
+        if self.last_released.is_some_and(|last| tick.timestamp < last) {
+            self.dropped += 1;
+            return Vec::new();
+        }
+
+        // Insert after any tick with the same timestamp, to keep arrival order.
+        let index = self
+            .buffer
+            .partition_point(|t| t.timestamp <= tick.timestamp);
+        self.buffer.insert(index, tick);
+
+        let newest = self
+            .newest
+            .map_or(tick.timestamp, |newest| newest.max(tick.timestamp));
+        self.newest = Some(newest);
+
+        let ready = self
+            .buffer
+            .partition_point(|t| t.timestamp <= newest - self.tolerance);
+
+        self.release(ready)
+    }
+
+    /// Aggregate the remaining ticks and close the current (partial) bar.
+    pub fn flush(&mut self) -> Vec<Bar> {
+        let mut bars = self.release(self.buffer.len());
+
+        if let Some(bar) = self.current.take() {
+            self.last_close = Some(bar.close);
+            bars.push(bar);
+        }
+
+        bars
+    }
+
+    /// Aggregate a whole series of ticks, including the final partial bar.
+    pub fn aggregate<I: IntoIterator<Item = Tick>>(&mut self, ticks: I) -> Vec<Bar> {
+        let mut bars: Vec<Bar> = ticks.into_iter().flat_map(|tick| self.push(tick)).collect();
+        bars.extend(self.flush());
+        bars
+    }
+
+    /// Aggregate the first `n` buffered ticks.
+    fn release(&mut self, n: usize) -> Vec<Bar> {
+        let mut bars = Vec::new();
+
+        for tick in self.buffer.drain(..n).collect::<Vec<_>>() {
+            self.last_released = Some(tick.timestamp);
+
+            match self.bar_type {
+                BarType::Time(interval) => self.add_timed(&tick, interval, &mut bars),
+                BarType::Volume(threshold) => {
+                    self.add_threshold(&tick, threshold, |bar| bar.volume, &mut bars);
+                }
+                BarType::Dollar(threshold) => {
+                    self.add_threshold(&tick, threshold, |bar| bar.dollar_volume, &mut bars);
+                }
+            }
+        }
+
+        bars
+    }
+
+    fn add_timed(&mut self, tick: &Tick, interval: Duration, bars: &mut Vec<Bar>) {
+        let width = interval.whole_nanoseconds();
+        let nanos = tick.timestamp.unix_timestamp_nanos();
+        let start = OffsetDateTime::UNIX_EPOCH
+            + Duration::nanoseconds_i128(nanos.div_euclid(width) * width);
+
+        match &mut self.current {
+            Some(bar) if bar.start == start => bar.update(tick),
+            _ => {
+                if let Some(bar) = self.current.take() {
+                    let mut gap_start = bar.end;
+                    bars.push(bar);
+
+                    if self.empty_bars == EmptyBars::ForwardFill {
+                        while gap_start < start {
+                            bars.push(Bar::empty(gap_start, gap_start + interval, bar.close));
+                            gap_start += interval;
+                        }
+                    }
+                    self.last_close = Some(bar.close);
+                }
+
+                self.current = Some(Bar::new(start, start + interval, tick));
+            }
+        }
+    }
+
+    fn add_threshold(
+        &mut self,
+        tick: &Tick,
+        threshold: f64,
+        size: fn(&Bar) -> f64,
+        bars: &mut Vec<Bar>,
+    ) {
+        let mut tick = *tick;
+
+        if self.split_ticks {
+            // Fill the current bar with part of the tick while the tick
+            // overflows it.
+            loop {
+                let remaining = threshold - self.current.as_ref().map_or(0.0, size);
+                let tick_size = size(&Bar::new(tick.timestamp, tick.timestamp, &tick));
+
+                if tick_size <= remaining {
+                    break;
+                }
+
+                let part = Tick {
+                    quantity: tick.quantity * remaining / tick_size,
+                    ..tick
+                };
+                tick.quantity -= part.quantity;
+
+                self.extend_current(&part);
+                self.close_current(bars);
+            }
+
+            if tick.quantity <= 0.0 {
+                return;
+            }
+        }
+
+        self.extend_current(&tick);
+
+        if self
+            .current
+            .as_ref()
+            .is_some_and(|bar| size(bar) >= threshold)
+        {
+            self.close_current(bars);
+        }
+    }
+
+    fn extend_current(&mut self, tick: &Tick) {
+        match &mut self.current {
+            Some(bar) => {
+                bar.update(tick);
+                bar.end = tick.timestamp;
+            }
+            None => self.current = Some(Bar::new(tick.timestamp, tick.timestamp, tick)),
+        }
+    }
+
+    fn close_current(&mut self, bars: &mut Vec<Bar>) {
+        if let Some(bar) = self.current.take() {
+            self.last_close = Some(bar.close);
+            bars.push(bar);
+        }
+    }
+}
+
+/// Downsample a batch of ticks (in any order) to bars.
+///
+/// Volume and dollar bars split the tick crossing the threshold, so every
+/// bar but the last holds exactly the threshold; the last bar is partial.
+///
+/// # Panics
+/// Panics if the bar interval or threshold is not positive.
+#[must_use]
+pub fn aggregate_to_bars(ticks: &[Tick], bar_type: BarType) -> Vec<Bar> {
+    let mut sorted = ticks.to_vec();
+    sorted.sort_by_key(|tick| tick.timestamp);
+
+    BarAggregator::new(bar_type)
+        .with_split_ticks(true)
+        .aggregate(sorted)
+}
+
+/// Read ticks from a `DataFrame` with columns `timestamp` (Unix milliseconds),
+/// `price`, and `quantity`, e.g. as read from a CSV file with [`crate::data::Data`].
+///
+/// # Errors
+/// `RustQuantError::PolarsError` if a column is missing or has the wrong type,
+/// `RustQuantError::MissingInput` if a value is null or a timestamp is out of range.
+pub fn ticks_from_dataframe(df: &DataFrame) -> Result<Vec<Tick>, RustQuantError> {
+    let timestamps = df.column("timestamp")?.cast(&DataType::Int64)?;
+    let prices = df.column("price")?.cast(&DataType::Float64)?;
+    let quantities = df.column("quantity")?.cast(&DataType::Float64)?;
+
+    timestamps
+        .i64()?
+        .into_iter()
+        .zip(prices.f64()?)
+        .zip(quantities.f64()?)
+        .map(|((timestamp, price), quantity)| {
+            let (Some(timestamp), Some(price), Some(quantity)) = (timestamp, price, quantity)
+            else {
+                return Err(RustQuantError::MissingInput(
+                    "Null value in tick data.".to_string(),
+                ));
+            };
+
+            let timestamp =
+                OffsetDateTime::from_unix_timestamp_nanos(i128::from(timestamp) * 1_000_000)
+                    .map_err(|e| RustQuantError::MissingInput(e.to_string()))?;
+
+            Ok(Tick {
+                timestamp,
+                price,
+                quantity,
+                side: None,
+            })
+        })
+        .collect()
+}
+
+/// Collect bars into a `DataFrame`, with `start` and `end` as millisecond datetimes.
+///
+/// # Errors
+/// `RustQuantError::PolarsError` if the `DataFrame` cannot be built.
+pub fn bars_to_dataframe(bars: &[Bar]) -> Result<DataFrame, RustQuantError> {
+    let millis = |t: OffsetDateTime| (t.unix_timestamp_nanos() / 1_000_000) as i64;
+    let datetime = DataType::Datetime(TimeUnit::Milliseconds, None);
+
+    let start: Vec<i64> = bars.iter().map(|bar| millis(bar.start)).collect();
+    let end: Vec<i64> = bars.iter().map(|bar| millis(bar.end)).collect();
+    let column = |f: fn(&Bar) -> f64| bars.iter().map(f).collect::<Vec<f64>>();
+
+    let df = df!(
+        "start" => Series::new("start", start).cast(&datetime)?,
+        "end" => Series::new("end", end).cast(&datetime)?,
+        "open" => column(|bar| bar.open),
+        "high" => column(|bar| bar.high),
+        "low" => column(|bar| bar.low),
+        "close" => column(|bar| bar.close),
+        "volume" => column(|bar| bar.volume),
+        "dollar_volume" => column(|bar| bar.dollar_volume),
+        "trade_count" => bars.iter().map(|bar| bar.trade_count as u64).collect::<Vec<u64>>(),
+    )?;
+
+    Ok(df)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_bars {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::data::{Data, DataFormat, DataReader};
+    use time::macros::datetime;
+
+    // 12 trades over 4 minutes from 2024-01-02 14:30:00 UTC.
+    // The 4th arrives 10 seconds late, and there are no trades from 14:32 to 14:33.
+    fn ticks() -> Result<Vec<Tick>, RustQuantError> {
+        let mut data = Data::new(
+            DataFormat::CSV,
+            String::from("./src/data/examples/ticks.csv"),
+        );
+        data.read()?;
+
+        ticks_from_dataframe(&data.data)
+    }
+
+    fn assert_ohlcv(bar: &Bar, ohlc: [f64; 4], volume: f64, trade_count: usize) {
+        assert_approx_equal!(bar.open, ohlc[0], 1e-12);
+        assert_approx_equal!(bar.high, ohlc[1], 1e-12);
+        assert_approx_equal!(bar.low, ohlc[2], 1e-12);
+        assert_approx_equal!(bar.close, ohlc[3], 1e-12);
+        assert_approx_equal!(bar.volume, volume, 1e-12);
+        assert_eq!(bar.trade_count, trade_count);
+    }
+
+    #[test]
+    fn test_time_bars() -> Result<(), RustQuantError> {
+        let mut aggregator = BarAggregator::new(BarType::Time(Duration::minutes(1)))
+            .with_tolerance(Duration::seconds(15));
+
+        let bars = aggregator.aggregate(ticks()?);
+
+        assert_eq!(bars.len(), 3);
+        assert_eq!(aggregator.dropped_ticks(), 0);
+
+        assert_eq!(bars[0].start, datetime!(2024-01-02 14:30:00 UTC));
+        assert_eq!(bars[0].end, datetime!(2024-01-02 14:31:00 UTC));
+        assert_ohlcv(&bars[0], [100.0, 101.0, 99.5, 100.2], 50.0, 5);
+        assert_approx_equal!(bars[0].dollar_volume, 4_999.5, 1e-9);
+
+        assert_eq!(bars[1].start, datetime!(2024-01-02 14:31:00 UTC));
+        assert_ohlcv(&bars[1], [100.8, 101.5, 100.8, 101.2], 30.0, 3);
+
+        // The empty 14:32 interval is skipped.
+        assert_eq!(bars[2].start, datetime!(2024-01-02 14:33:00 UTC));
+        assert_ohlcv(&bars[2], [102.0, 102.5, 101.8, 102.4], 50.0, 4);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_time_bars_forward_fill() -> Result<(), RustQuantError> {
+        let mut aggregator = BarAggregator::new(BarType::Time(Duration::minutes(1)))
+            .with_tolerance(Duration::seconds(15))
+            .with_empty_bars(EmptyBars::ForwardFill);
+
+        let bars = aggregator.aggregate(ticks()?);
+
+        assert_eq!(bars.len(), 4);
+        assert_eq!(bars[2].start, datetime!(2024-01-02 14:32:00 UTC));
+        assert_eq!(bars[2].end, datetime!(2024-01-02 14:33:00 UTC));
+        assert_ohlcv(&bars[2], [101.2; 4], 0.0, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_volume_bars() -> Result<(), RustQuantError> {
+        let mut aggregator =
+            BarAggregator::new(BarType::Volume(40.0)).with_tolerance(Duration::seconds(15));
+
+        let bars = aggregator.aggregate(ticks()?);
+
+        assert_eq!(bars.len(), 4);
+        assert_ohlcv(&bars[0], [100.0, 101.0, 99.5, 99.5], 40.0, 4);
+        assert_eq!(bars[0].start, datetime!(2024-01-02 14:30:00 UTC));
+        assert_eq!(bars[0].end, datetime!(2024-01-02 14:30:40 UTC));
+        assert_ohlcv(&bars[1], [100.2, 101.5, 100.2, 101.2], 40.0, 4);
+        assert_ohlcv(&bars[2], [102.0, 102.5, 101.8, 102.5], 40.0, 3);
+
+        // Partial bar at the end.
+        assert_ohlcv(&bars[3], [102.4; 4], 10.0, 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_dollar_bars() -> Result<(), RustQuantError> {
+        let mut aggregator =
+            BarAggregator::new(BarType::Dollar(4_000.0)).with_tolerance(Duration::seconds(15));
+
+        let bars = aggregator.aggregate(ticks()?);
+
+        assert_eq!(bars.len(), 3);
+        assert_ohlcv(&bars[0], [100.0, 101.0, 99.5, 100.2], 50.0, 5);
+        assert_ohlcv(&bars[1], [100.8, 102.0, 100.8, 102.0], 40.0, 4);
+        assert_approx_equal!(bars[1].dollar_volume, 4_053.0, 1e-9);
+        assert_ohlcv(&bars[2], [101.8, 102.5, 101.8, 102.4], 40.0, 3);
+        assert_approx_equal!(bars[2].dollar_volume, 4_081.5, 1e-9);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_incremental_emission_and_late_ticks() -> Result<(), RustQuantError> {
+        // Without tolerance, the late tick is dropped.
+        let mut aggregator = BarAggregator::new(BarType::Time(Duration::minutes(1)));
+        let mut emitted = Vec::new();
+
+        for tick in ticks()? {
+            let bars = aggregator.push(tick);
+
+            // A bar is emitted as soon as a tick of the next interval arrives.
+            if tick.timestamp == datetime!(2024-01-02 14:31:05 UTC) {
+                assert_eq!(bars.len(), 1);
+            }
+            emitted.extend(bars);
+        }
+
+        assert_eq!(aggregator.dropped_ticks(), 1);
+        assert_eq!(emitted.len(), 2);
+        assert_ohlcv(&emitted[0], [100.0, 101.0, 99.5, 100.2], 45.0, 4);
+        assert_eq!(aggregator.flush().len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_bars_to_dataframe() -> Result<(), RustQuantError> {
+        let bars = BarAggregator::new(BarType::Volume(40.0))
+            .with_tolerance(Duration::seconds(15))
+            .aggregate(ticks()?);
+        let df = bars_to_dataframe(&bars)?;
+
+        assert_eq!(df.shape(), (bars.len(), 9));
+        assert_eq!(df.column("close")?.f64()?.get(0), Some(bars[0].close));
+        assert_eq!(df.column("trade_count")?.u64()?.get(3), Some(1));
+
+        Ok(())
+    }
+
+    // Ticks of 7 units every 10 seconds from 2024-01-02 14:30:00 UTC, in
+    // reverse order.
+    fn synthetic_ticks(n: i64) -> Vec<Tick> {
+        (0..n)
+            .rev()
+            .map(|i| Tick {
+                timestamp: datetime!(2024-01-02 14:30:00 UTC) + Duration::seconds(10 * i),
+                price: 100.0 + i as f64,
+                quantity: 7.0,
+                side: Some(if i % 2 == 0 {
+                    OrderSide::BID
+                } else {
+                    OrderSide::ASK
+                }),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_aggregate_to_time_bars() {
+        let bars = aggregate_to_bars(&synthetic_ticks(30), BarType::Time(Duration::minutes(1)));
+
+        assert_eq!(bars.len(), 5);
+        for (i, bar) in bars.iter().enumerate() {
+            let start = datetime!(2024-01-02 14:30:00 UTC) + Duration::minutes(i as i64);
+
+            assert_eq!(bar.start, start);
+            assert_eq!(bar.end, start + Duration::minutes(1));
+            assert_ohlcv(
+                bar,
+                [
+                    100.0 + 6.0 * i as f64,
+                    105.0 + 6.0 * i as f64,
+                    100.0 + 6.0 * i as f64,
+                    105.0 + 6.0 * i as f64,
+                ],
+                42.0,
+                6,
+            );
+        }
+    }
+
+    #[test]
+    fn test_aggregate_to_volume_bars_splits_ticks() {
+        let ticks = synthetic_ticks(30);
+        let bars = aggregate_to_bars(&ticks, BarType::Volume(25.0));
+
+        // 210 units: 8 full bars and a partial bar of 10.
+        assert_eq!(bars.len(), 9);
+        for bar in &bars[..8] {
+            assert_approx_equal!(bar.volume, 25.0, 1e-9);
+        }
+        assert_approx_equal!(bars[8].volume, 10.0, 1e-9);
+
+        // The 4th tick is split 4 / 3 between the first two bars.
+        assert_eq!(bars[0].trade_count, 4);
+        assert_eq!(bars[0].close, 103.0);
+        assert_eq!(bars[1].open, 103.0);
+        assert_eq!(bars[1].start, datetime!(2024-01-02 14:30:30 UTC));
+
+        let total: f64 = bars.iter().map(|bar| bar.volume).sum();
+        assert_approx_equal!(total, 210.0, 1e-9);
+    }
+
+    #[test]
+    fn test_aggregate_to_dollar_bars_splits_ticks() {
+        let bars = aggregate_to_bars(&synthetic_ticks(30), BarType::Dollar(5_000.0));
+
+        let (last, full) = bars.split_last().expect("Bars.");
+        for bar in full {
+            assert_approx_equal!(bar.dollar_volume, 5_000.0, 1e-9);
+        }
+        assert!(last.dollar_volume <= 5_000.0 + 1e-9);
+    }
+}
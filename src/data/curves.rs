@@ -356,7 +356,7 @@ macro_rules! impl_specific_curve_cost_function {
 
                 let y_model = x
                     .iter()
-                    .map(|date| nss.$curve_function(*date))
+                    .map(|date| CurveModel::$curve_function(&nss, *date))
                     .collect::<Vec<f64>>();
 
                 let data = zip(y.iter(), y_model.iter());
@@ -463,7 +463,7 @@ macro_rules! impl_specific_curve {
                             self.fitted_curve = Some(Curve::<Date>::new());
                         }
 
-                        let rate = self.nss.$curve_function(date);
+                        let rate = CurveModel::$curve_function(&self.nss, date);
                         self.insert_rate(date, rate);
                         self.fitted_curve.as_mut().unwrap().insert(date, rate);
 
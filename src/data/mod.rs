@@ -109,10 +109,28 @@
 pub mod io;
 pub use io::*;
 
+/// Arrow IPC files for simulated paths, Monte Carlo results, and backtests.
+pub mod arrow_ipc;
+pub use arrow_ipc::*;
+
+/// Time, volume, and dollar bars from tick data.
+pub mod bars;
+pub use bars::*;
+
+/// Point-in-time panels: as-of joins with availability lags and lineage.
+pub mod point_in_time;
+pub use point_in_time::*;
+
 /// Yahoo! Finance data reader.
 pub mod yahoo;
 pub use yahoo::*;
 
+/// WebSocket market data client (requires the `websocket` feature).
+#[cfg(feature = "websocket")]
+pub mod websocket;
+#[cfg(feature = "websocket")]
+pub use websocket::*;
+
 /// Base curve data structure and implementations.
 /// Curves (in the financial sense) are functions that map
 /// a time to a value, such as a yield curve or a swap curve.
@@ -120,6 +138,15 @@ pub use yahoo::*;
 pub mod curves;
 pub use curves::*;
 
+/// Piecewise yield curves, bootstrapped or globally fitted to deposits,
+/// FRAs, futures, and swaps.
+pub mod curve_fitting;
+pub use curve_fitting::*;
+
+/// Multi-curve framework: OIS discounting with per-index forecast curves.
+pub mod multi_curve;
+pub use multi_curve::*;
+
 // /// Base surface data structure and implementations.
 // /// Surfaces are simply [Curve]s with an additional dimension.
 // /// For example, a volatility surface is a function of time and strike/moneyness.
@@ -0,0 +1,668 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Multi-curve framework: OIS discounting with separate forecast curves.
+//!
+//! Collateralised trades are discounted on the OIS curve, while the
+//! floating rates of each IBOR tenor are projected from their own forecast
+//! curve. A [`CurveSet`] holds the curves keyed by [`RateIndex`], and the
+//! pricers take explicit discount and forecast curves.
+//!
+//! The curves are built by a dual-curve bootstrap (see
+//! [`bootstrap_dual_curve`]):
+//!
+//! 1. the OIS curve from OIS deposits and swaps, which discount and project
+//!    on the same curve;
+//! 2. each forecast curve from deposits, FRAs, and IBOR swaps discounted on
+//!    the OIS curve, or from tenor basis swaps against a forecast curve
+//!    built before it.
+//!
+//! Schedules are payment times (year fractions), with the first accrual
+//! period starting today.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{CalibrationInstrument, CurveFitter, CurveInstrument, PiecewiseCurve};
+use crate::error::RustQuantError;
+use crate::instruments::options::{black_76_caplet, black_76_floorlet};
+use std::collections::BTreeMap;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Rate index a curve discounts or projects.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RateIndex {
+    /// Overnight index (discounting of collateralised trades).
+    Ois,
+    /// Three-month IBOR.
+    Ibor3M,
+    /// Six-month IBOR.
+    Ibor6M,
+}
+
+/// Set of curves keyed by index.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CurveSet {
+    curves: BTreeMap<RateIndex, PiecewiseCurve>,
+}
+
+/// Calibration instrument of a forecast curve.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ForecastInstrument {
+    /// Fixing (or deposit) of the index from today to `maturity`.
+    Deposit {
+        /// Maturity (year fraction).
+        maturity: f64,
+        /// Simple rate.
+        rate: f64,
+    },
+    /// FRA on the index from `start` to `end`.
+    Fra {
+        /// Start of the accrual period (year fraction).
+        start: f64,
+        /// End of the accrual period (year fraction).
+        end: f64,
+        /// Simple forward rate.
+        rate: f64,
+    },
+    /// Par swap of a fixed `rate` against the index, discounted on OIS.
+    Swap {
+        /// Fixed leg payment times.
+        fixed_times: Vec<f64>,
+        /// Floating leg payment times.
+        float_times: Vec<f64>,
+        /// Par swap rate.
+        rate: f64,
+    },
+    /// Tenor basis swap: the index against `base_index` plus `spread`,
+    /// discounted on OIS.
+    TenorBasisSwap {
+        /// Index of the other leg, whose curve must be built first.
+        base_index: RateIndex,
+        /// Payment times of the `base_index` leg.
+        base_times: Vec<f64>,
+        /// Payment times of the index leg.
+        float_times: Vec<f64>,
+        /// Par spread paid on the `base_index` leg.
+        spread: f64,
+    },
+}
+
+// A forecast instrument with the curves it is priced on, as calibrated by
+// `CurveFitter`.
+struct Projected<'a> {
+    instrument: &'a ForecastInstrument,
+    discount: &'a PiecewiseCurve,
+    base: Option<&'a PiecewiseCurve>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl CurveSet {
+    /// New empty curve set.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts the curve of `index`, returning the curve it replaces.
+    pub fn insert(&mut self, index: RateIndex, curve: PiecewiseCurve) -> Option<PiecewiseCurve> {
+        self.curves.insert(index, curve)
+    }
+
+    /// Curve of `index`, if present.
+    #[must_use]
+    pub fn get(&self, index: RateIndex) -> Option<&PiecewiseCurve> {
+        self.curves.get(&index)
+    }
+
+    /// Curve of `index`.
+    ///
+    /// # Errors
+    /// `MissingInput` if the set has no curve for `index`.
+    pub fn curve(&self, index: RateIndex) -> Result<&PiecewiseCurve, RustQuantError> {
+        self.get(index)
+            .ok_or_else(|| RustQuantError::MissingInput(format!("No curve for {index:?}.")))
+    }
+
+    /// The OIS discount curve.
+    ///
+    /// # Errors
+    /// `MissingInput` if the set has no OIS curve.
+    pub fn discount_curve(&self) -> Result<&PiecewiseCurve, RustQuantError> {
+        self.curve(RateIndex::Ois)
+    }
+
+    /// Bootstraps the forecast curve of `index` from `instruments`,
+    /// discounted on the set's OIS curve, and inserts it into the set.
+    ///
+    /// # Errors
+    /// - `MissingInput` if the OIS curve, or the base curve of a basis swap,
+    ///   is not in the set.
+    /// - As for [`CurveFitter::bootstrap`].
+    pub fn bootstrap_forecast(
+        &mut self,
+        index: RateIndex,
+        fitter: &CurveFitter,
+        instruments: &[ForecastInstrument],
+    ) -> Result<(), RustQuantError> {
+        let discount = self.discount_curve()?;
+        let projected = instruments
+            .iter()
+            .map(|instrument| {
+                let base = match instrument {
+                    ForecastInstrument::TenorBasisSwap { base_index, .. } => {
+                        Some(self.curve(*base_index)?)
+                    }
+                    _ => None,
+                };
+
+                Ok(Projected {
+                    instrument,
+                    discount,
+                    base,
+                })
+            })
+            .collect::<Result<Vec<_>, RustQuantError>>()?;
+
+        let curve = fitter.bootstrap(&projected)?;
+        self.insert(index, curve);
+
+        Ok(())
+    }
+}
+
+impl ForecastInstrument {
+    /// Market quote: the rate, or the spread of a basis swap.
+    #[must_use]
+    pub fn market_quote(&self) -> f64 {
+        match self {
+            Self::Deposit { rate, .. } | Self::Fra { rate, .. } | Self::Swap { rate, .. } => *rate,
+            Self::TenorBasisSwap { spread, .. } => *spread,
+        }
+    }
+
+    /// Quote implied by the `discount` and `forecast` curves, and the
+    /// `base` forecast curve of a basis swap.
+    ///
+    /// # Panics
+    /// Panics if `base` is `None` for a basis swap.
+    #[must_use]
+    pub fn model_quote(
+        &self,
+        discount: &PiecewiseCurve,
+        forecast: &PiecewiseCurve,
+        base: Option<&PiecewiseCurve>,
+    ) -> f64 {
+        match self {
+            Self::Deposit { maturity, .. } => simple_forward_rate(forecast, 0.0, *maturity),
+            Self::Fra { start, end, .. } => simple_forward_rate(forecast, *start, *end),
+            Self::Swap {
+                fixed_times,
+                float_times,
+                ..
+            } => swap_par_rate(fixed_times, float_times, discount, forecast),
+            Self::TenorBasisSwap {
+                base_times,
+                float_times,
+                ..
+            } => {
+                let base = base.expect("A basis swap needs its base forecast curve.");
+
+                (floating_leg_value(float_times, 0.0, discount, forecast)
+                    - floating_leg_value(base_times, 0.0, discount, base))
+                    / annuity(base_times, discount)
+            }
+        }
+    }
+
+    fn maturity(&self) -> f64 {
+        let last = |times: &[f64]| times.last().copied().unwrap_or(0.0);
+
+        match self {
+            Self::Deposit { maturity, .. } => *maturity,
+            Self::Fra { end, .. } => *end,
+            Self::Swap {
+                fixed_times,
+                float_times,
+                ..
+            } => last(fixed_times).max(last(float_times)),
+            Self::TenorBasisSwap {
+                base_times,
+                float_times,
+                ..
+            } => last(base_times).max(last(float_times)),
+        }
+    }
+
+    fn is_valid(&self) -> bool {
+        let schedule = |times: &[f64]| {
+            !times.is_empty() && times[0] > 0.0 && times.windows(2).all(|w| w[0] < w[1])
+        };
+
+        match self {
+            Self::Deposit { maturity, rate } => *maturity > 0.0 && rate.is_finite(),
+            Self::Fra { start, end, rate } => *start >= 0.0 && start < end && rate.is_finite(),
+            Self::Swap {
+                fixed_times,
+                float_times,
+                rate,
+            } => schedule(fixed_times) && schedule(float_times) && rate.is_finite(),
+            Self::TenorBasisSwap {
+                base_times,
+                float_times,
+                spread,
+                ..
+            } => schedule(base_times) && schedule(float_times) && spread.is_finite(),
+        }
+    }
+}
+
+impl CalibrationInstrument for Projected<'_> {
+    fn maturity(&self) -> f64 {
+        self.instrument.maturity()
+    }
+
+    fn market_rate(&self) -> f64 {
+        self.instrument.market_quote()
+    }
+
+    fn model_rate(&self, curve: &PiecewiseCurve) -> f64 {
+        self.instrument.model_quote(self.discount, curve, self.base)
+    }
+
+    fn validate(&self) -> Result<(), RustQuantError> {
+        if self.instrument.is_valid() {
+            Ok(())
+        } else {
+            Err(RustQuantError::InvalidArgument(format!(
+                "Invalid forecast instrument: {:?}",
+                self.instrument
+            )))
+        }
+    }
+}
+
+/// Dual-curve bootstrap: the OIS curve from `ois_instruments`, then each
+/// forecast curve in turn from its instruments, discounted on OIS.
+///
+/// # Errors
+/// As for [`CurveFitter::bootstrap`] and [`CurveSet::bootstrap_forecast`].
+pub fn bootstrap_dual_curve(
+    fitter: &CurveFitter,
+    ois_instruments: &[CurveInstrument],
+    forecast_instruments: &[(RateIndex, Vec<ForecastInstrument>)],
+) -> Result<CurveSet, RustQuantError> {
+    let mut curves = CurveSet::new();
+    curves.insert(RateIndex::Ois, fitter.bootstrap(ois_instruments)?);
+
+    for (index, instruments) in forecast_instruments {
+        curves.bootstrap_forecast(*index, fitter, instruments)?;
+    }
+
+    Ok(curves)
+}
+
+/// Simple forward rate from `start` to `end` projected by `forecast`.
+#[must_use]
+pub fn simple_forward_rate(forecast: &PiecewiseCurve, start: f64, end: f64) -> f64 {
+    (forecast.discount_factor(start) / forecast.discount_factor(end) - 1.0) / (end - start)
+}
+
+/// Annuity $\sum_i \tau_i P_d(t_i)$ of the payment times `times`.
+#[must_use]
+pub fn annuity(times: &[f64], discount: &PiecewiseCurve) -> f64 {
+    periods(times)
+        .map(|(start, end)| (end - start) * discount.discount_factor(end))
+        .sum()
+}
+
+/// Value of a floating leg paying the projected rate plus `spread`:
+/// $\sum_j \tau_j (F_j + s) P_d(t_j)$.
+#[must_use]
+pub fn floating_leg_value(
+    times: &[f64],
+    spread: f64,
+    discount: &PiecewiseCurve,
+    forecast: &PiecewiseCurve,
+) -> f64 {
+    periods(times)
+        .map(|(start, end)| {
+            (end - start)
+                * (simple_forward_rate(forecast, start, end) + spread)
+                * discount.discount_factor(end)
+        })
+        .sum()
+}
+
+/// Par rate of a swap with fixed payments at `fixed_times` and floating
+/// payments at `float_times`.
+#[must_use]
+pub fn swap_par_rate(
+    fixed_times: &[f64],
+    float_times: &[f64],
+    discount: &PiecewiseCurve,
+    forecast: &PiecewiseCurve,
+) -> f64 {
+    floating_leg_value(float_times, 0.0, discount, forecast) / annuity(fixed_times, discount)
+}
+
+/// Value of a payer swap (pay `fixed_rate`, receive floating) per unit of
+/// notional.
+#[must_use]
+pub fn payer_swap_value(
+    fixed_rate: f64,
+    fixed_times: &[f64],
+    float_times: &[f64],
+    discount: &PiecewiseCurve,
+    forecast: &PiecewiseCurve,
+) -> f64 {
+    floating_leg_value(float_times, 0.0, discount, forecast)
+        - fixed_rate * annuity(fixed_times, discount)
+}
+
+/// Value of a FRA paying $\tau (F - K)$ at `end`, per unit of notional.
+#[must_use]
+pub fn fra_value(
+    start: f64,
+    end: f64,
+    strike: f64,
+    discount: &PiecewiseCurve,
+    forecast: &PiecewiseCurve,
+) -> f64 {
+    (end - start)
+        * (simple_forward_rate(forecast, start, end) - strike)
+        * discount.discount_factor(end)
+}
+
+/// Black-76 value of a cap on the periods ending at `times`, per unit of
+/// notional. The first period fixes today, so its caplet is worth its
+/// intrinsic value.
+#[must_use]
+pub fn cap_value(
+    strike: f64,
+    volatility: f64,
+    times: &[f64],
+    discount: &PiecewiseCurve,
+    forecast: &PiecewiseCurve,
+) -> f64 {
+    periods(times)
+        .map(|(start, end)| {
+            black_76_caplet(
+                simple_forward_rate(forecast, start, end),
+                strike,
+                discount.zero_rate(end),
+                volatility,
+                start,
+                end - start,
+            )
+        })
+        .sum()
+}
+
+/// Black-76 value of a floor on the periods ending at `times`, per unit of
+/// notional (see [`cap_value`]).
+#[must_use]
+pub fn floor_value(
+    strike: f64,
+    volatility: f64,
+    times: &[f64],
+    discount: &PiecewiseCurve,
+    forecast: &PiecewiseCurve,
+) -> f64 {
+    periods(times)
+        .map(|(start, end)| {
+            black_76_floorlet(
+                simple_forward_rate(forecast, start, end),
+                strike,
+                discount.zero_rate(end),
+                volatility,
+                start,
+                end - start,
+            )
+        })
+        .sum()
+}
+
+// Accrual periods (start, end) of the payment times, starting today.
+fn periods(times: &[f64]) -> impl Iterator<Item = (f64, f64)> + '_ {
+    std::iter::once(0.0)
+        .chain(times.iter().copied())
+        .zip(times.iter().copied())
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_multi_curve {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::data::CurveInterpolation;
+
+    fn schedule(years: usize, per_year: usize) -> Vec<f64> {
+        (1..=years * per_year)
+            .map(|i| i as f64 / per_year as f64)
+            .collect()
+    }
+
+    fn ois_instruments() -> Vec<CurveInstrument> {
+        let mut instruments = vec![
+            CurveInstrument::Deposit {
+                maturity: 0.25,
+                rate: 0.0250,
+            },
+            CurveInstrument::Deposit {
+                maturity: 0.5,
+                rate: 0.0255,
+            },
+        ];
+        for (years, rate) in [(1, 0.0260), (2, 0.0270), (3, 0.0278), (5, 0.0290)] {
+            instruments.push(CurveInstrument::Swap {
+                payment_times: schedule(years, 1),
+                rate,
+            });
+        }
+
+        instruments
+    }
+
+    fn ibor_3m_quotes() -> Vec<(usize, f64)> {
+        vec![(1, 0.0290), (2, 0.0302), (3, 0.0311), (5, 0.0325)]
+    }
+
+    fn ibor_3m_instruments() -> Vec<ForecastInstrument> {
+        let mut instruments = vec![
+            ForecastInstrument::Deposit {
+                maturity: 0.25,
+                rate: 0.0280,
+            },
+            ForecastInstrument::Fra {
+                start: 0.25,
+                end: 0.5,
+                rate: 0.0284,
+            },
+            ForecastInstrument::Fra {
+                start: 0.5,
+                end: 0.75,
+                rate: 0.0288,
+            },
+        ];
+        for (years, rate) in ibor_3m_quotes() {
+            instruments.push(ForecastInstrument::Swap {
+                fixed_times: schedule(years, 1),
+                float_times: schedule(years, 4),
+                rate,
+            });
+        }
+
+        instruments
+    }
+
+    fn ibor_6m_instruments() -> Vec<ForecastInstrument> {
+        let mut instruments = vec![ForecastInstrument::Deposit {
+            maturity: 0.5,
+            rate: 0.0295,
+        }];
+        for (years, spread) in [(1, 0.0010), (2, 0.0009), (3, 0.0008), (5, 0.0007)] {
+            instruments.push(ForecastInstrument::TenorBasisSwap {
+                base_index: RateIndex::Ibor3M,
+                base_times: schedule(years, 4),
+                float_times: schedule(years, 2),
+                spread,
+            });
+        }
+
+        instruments
+    }
+
+    fn curves() -> CurveSet {
+        bootstrap_dual_curve(
+            &CurveFitter::new(CurveInterpolation::LogLinear),
+            &ois_instruments(),
+            &[
+                (RateIndex::Ibor3M, ibor_3m_instruments()),
+                (RateIndex::Ibor6M, ibor_6m_instruments()),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_calibration_instruments_reprice() {
+        let curves = curves();
+        let ois = curves.discount_curve().unwrap();
+        let ibor_3m = curves.curve(RateIndex::Ibor3M).unwrap();
+        let ibor_6m = curves.curve(RateIndex::Ibor6M).unwrap();
+
+        for instrument in ois_instruments() {
+            assert_approx_equal!(instrument.model_rate(ois), instrument.market_rate(), 1e-10);
+        }
+        for instrument in ibor_3m_instruments() {
+            assert_approx_equal!(
+                instrument.model_quote(ois, ibor_3m, None),
+                instrument.market_quote(),
+                1e-10
+            );
+        }
+        for instrument in ibor_6m_instruments() {
+            assert_approx_equal!(
+                instrument.model_quote(ois, ibor_6m, Some(ibor_3m)),
+                instrument.market_quote(),
+                1e-10
+            );
+        }
+
+        // The forecast curves project above OIS, the 6M above the 3M.
+        assert!(simple_forward_rate(ibor_3m, 2.0, 2.25) > simple_forward_rate(ois, 2.0, 2.25));
+        assert!(simple_forward_rate(ibor_6m, 2.0, 2.5) > simple_forward_rate(ibor_3m, 2.0, 2.5));
+    }
+
+    #[test]
+    fn test_dual_curve_swap_valuation() {
+        let curves = curves();
+        let ois = curves.discount_curve().unwrap();
+        let dual = curves.curve(RateIndex::Ibor3M).unwrap();
+
+        // Classic single curve: the same 3M quotes, discounted on themselves.
+        let mut single_instruments = vec![
+            CurveInstrument::Deposit {
+                maturity: 0.25,
+                rate: 0.0280,
+            },
+            CurveInstrument::Fra {
+                start: 0.25,
+                end: 0.5,
+                rate: 0.0284,
+            },
+            CurveInstrument::Fra {
+                start: 0.5,
+                end: 0.75,
+                rate: 0.0288,
+            },
+        ];
+        for (years, rate) in ibor_3m_quotes() {
+            single_instruments.push(CurveInstrument::Swap {
+                payment_times: schedule(years, 1),
+                rate,
+            });
+        }
+        let single = CurveFitter::new(CurveInterpolation::LogLinear)
+            .bootstrap(&single_instruments)
+            .unwrap();
+
+        // An off-market 5y payer swap, struck below the 3.25% par rate.
+        let (fixed, float) = (schedule(5, 1), schedule(5, 4));
+        let strike = 0.0300;
+        let dual_value = payer_swap_value(strike, &fixed, &float, ois, dual);
+        let single_value = payer_swap_value(strike, &fixed, &float, &single, &single);
+
+        // Both curves reprice the par swap, so each value is the coupon
+        // difference on its own annuity, and the dual-curve adjustment is
+        // the coupon difference on the OIS-minus-IBOR annuity.
+        let (ois_annuity, ibor_annuity) = (annuity(&fixed, ois), annuity(&fixed, &single));
+        assert_approx_equal!(dual_value, (0.0325 - strike) * ois_annuity, 1e-10);
+        assert_approx_equal!(single_value, (0.0325 - strike) * ibor_annuity, 1e-10);
+        assert_approx_equal!(
+            dual_value - single_value,
+            (0.0325 - strike) * (ois_annuity - ibor_annuity),
+            1e-10
+        );
+        assert!(dual_value > single_value);
+
+        // FRAs at the projected rate are worth nothing on either framework.
+        let forward = simple_forward_rate(dual, 1.0, 1.25);
+        assert_approx_equal!(fra_value(1.0, 1.25, forward, ois, dual), 0.0, 1e-15);
+    }
+
+    #[test]
+    fn test_cap_floor_parity() {
+        let curves = curves();
+        let ois = curves.discount_curve().unwrap();
+        let forecast = curves.curve(RateIndex::Ibor3M).unwrap();
+        let times = schedule(3, 4);
+
+        for strike in [0.025, 0.03, 0.035] {
+            let cap = cap_value(strike, 0.25, &times, ois, forecast);
+            let floor = floor_value(strike, 0.25, &times, ois, forecast);
+            let swap = payer_swap_value(strike, &times, &times, ois, forecast);
+
+            assert!(cap > 0.0 && floor > 0.0);
+            assert_approx_equal!(cap - floor, swap, 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_missing_curves() {
+        let fitter = CurveFitter::new(CurveInterpolation::LogLinear);
+        let mut curves = CurveSet::new();
+
+        assert!(curves
+            .bootstrap_forecast(RateIndex::Ibor3M, &fitter, &ibor_3m_instruments())
+            .is_err());
+
+        curves.insert(
+            RateIndex::Ois,
+            fitter.bootstrap(&ois_instruments()).unwrap(),
+        );
+        assert!(curves
+            .bootstrap_forecast(RateIndex::Ibor6M, &fitter, &ibor_6m_instruments())
+            .is_err());
+        assert!(curves
+            .bootstrap_forecast(RateIndex::Ibor3M, &fitter, &ibor_3m_instruments())
+            .is_ok());
+    }
+}
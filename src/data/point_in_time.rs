@@ -0,0 +1,494 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Point-in-time panels: joining data sources onto observations keyed by
+//! `(date, asset)` using only the values that were known on each date.
+//!
+//! Each source row has a `"date"` (e.g. the end of a reporting period) and
+//! becomes available some time later: either a fixed availability lag after
+//! its date (e.g. fundamentals 45 days after the quarter end), or at the
+//! date in its own availability column (e.g. the filing date). For each
+//! observation, a source contributes its latest row for the same asset that
+//! was available on or before the observation date (a backward as-of join
+//! on the availability date), and nothing if there is none.
+//!
+//! Joining on the source `"date"` instead, as a naive as-of join would, uses
+//! values before they were published, and so leaks future information into
+//! backtests and model training.
+//!
+//! Dates are handled at daily resolution: the `"date"` columns may be
+//! `Date` or `Datetime`, and are truncated to days.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use polars::prelude::*;
+use std::collections::HashMap;
+use time::Duration;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// A data source to join onto a [`PointInTimePanel`].
+#[derive(Debug, Clone)]
+pub struct PointInTimeSource {
+    /// Name of the source, used to prefix its columns in the panel
+    /// (`"{name}_{column}"`) and in the lineage report.
+    pub name: String,
+    /// Source data, with `"date"` and `"asset"` columns.
+    pub frame: DataFrame,
+    /// Columns of `frame` to join.
+    pub columns: Vec<String>,
+    /// Time between a row's date and when it becomes available.
+    pub availability_lag: Duration,
+    /// Column of `frame` with the date each row became available, used
+    /// instead of `availability_lag` if set.
+    pub available_column: Option<String>,
+}
+
+/// Observations keyed by `(date, asset)`, with the sources to join onto
+/// them point-in-time.
+#[derive(Debug, Clone)]
+pub struct PointInTimePanel {
+    /// Observations, with `"date"` and `"asset"` columns.
+    observations: DataFrame,
+    /// Sources to join, in order.
+    sources: Vec<PointInTimeSource>,
+    /// Whether to error if a joined row is dated after the observation.
+    strict: bool,
+}
+
+/// Output of [`PointInTimePanel::build`].
+#[derive(Debug, Clone)]
+pub struct PointInTimeOutput {
+    /// The observations, with the columns of each source appended.
+    pub panel: DataFrame,
+    /// Lineage report: one row per observation and source, with columns
+    /// `"row"` (observation row), `"source"`, `"source_row"` (row of the
+    /// source that fed all of that source's cells in the observation row,
+    /// null if none), `"source_date"`, and `"available_date"`.
+    pub lineage: DataFrame,
+}
+
+/// A source row, as seen by the join.
+#[derive(Debug, Clone, Copy)]
+struct SourceRow {
+    /// Day the row became available.
+    available: i32,
+    /// Day of the row.
+    date: i32,
+    /// Row of the source `DataFrame`.
+    row: IdxSize,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl PointInTimeSource {
+    /// New source joining `columns` of `frame`, available on its dates.
+    #[must_use]
+    pub fn new(name: &str, frame: DataFrame, columns: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            frame,
+            columns: columns.iter().map(|column| (*column).to_string()).collect(),
+            availability_lag: Duration::ZERO,
+            available_column: None,
+        }
+    }
+
+    /// Rows become available `lag` after their date.
+    #[must_use]
+    pub fn with_availability_lag(mut self, lag: Duration) -> Self {
+        self.availability_lag = lag;
+        self
+    }
+
+    /// Rows become available on the date in `column`.
+    #[must_use]
+    pub fn with_available_column(mut self, column: &str) -> Self {
+        self.available_column = Some(column.to_string());
+        self
+    }
+
+    /// Rows of each asset, sorted by availability (then date, then row).
+    fn rows_by_asset(&self) -> Result<HashMap<String, Vec<SourceRow>>, RustQuantError> {
+        let dates = days(&self.frame, "date")?;
+        let available = match &self.available_column {
+            Some(column) => days(&self.frame, column)?,
+            None => {
+                let lag = self.availability_lag.whole_days() as i32;
+                dates
+                    .iter()
+                    .map(|date| date.map(|date| date + lag))
+                    .collect()
+            }
+        };
+
+        let mut rows: HashMap<String, Vec<SourceRow>> = HashMap::new();
+        for (row, ((asset, date), available)) in assets(&self.frame)?
+            .into_iter()
+            .zip(dates)
+            .zip(available)
+            .enumerate()
+        {
+            if let (Some(asset), Some(date), Some(available)) = (asset, date, available) {
+                rows.entry(asset).or_default().push(SourceRow {
+                    available,
+                    date,
+                    row: row as IdxSize,
+                });
+            }
+        }
+
+        for rows in rows.values_mut() {
+            rows.sort_by_key(|row| (row.available, row.date, row.row));
+        }
+
+        Ok(rows)
+    }
+}
+
+impl PointInTimePanel {
+    /// New panel over `observations`, with no sources.
+    #[must_use]
+    pub fn new(observations: DataFrame) -> Self {
+        Self {
+            observations,
+            sources: Vec::new(),
+            strict: false,
+        }
+    }
+
+    /// Adds a source to join.
+    #[must_use]
+    pub fn with_source(mut self, source: PointInTimeSource) -> Self {
+        self.sources.push(source);
+        self
+    }
+
+    /// In strict mode, [`PointInTimePanel::build`] errors if any joined row
+    /// is dated after its observation, e.g. because of a negative lag, or an
+    /// availability date before the row's date.
+    #[must_use]
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Joins the sources onto the observations.
+    ///
+    /// # Errors
+    /// - `PolarsError` if a column is missing or has an unsupported type.
+    /// - `ConditionViolated` in strict mode, if a joined row is dated after
+    ///   its observation.
+    pub fn build(&self) -> Result<PointInTimeOutput, RustQuantError> {
+        let observation_dates = days(&self.observations, "date")?;
+        let observation_assets = assets(&self.observations)?;
+        let n = self.observations.height();
+
+        let mut panel = self.observations.clone();
+        let mut lineage_rows: Vec<u32> = Vec::with_capacity(n * self.sources.len());
+        let mut lineage_sources: Vec<&str> = Vec::with_capacity(n * self.sources.len());
+        let mut lineage_source_rows: Vec<Option<IdxSize>> = Vec::new();
+        let mut lineage_dates: Vec<Option<i32>> = Vec::new();
+        let mut lineage_available: Vec<Option<i32>> = Vec::new();
+
+        for source in &self.sources {
+            let rows = source.rows_by_asset()?;
+
+            let matches: Vec<Option<SourceRow>> = observation_dates
+                .iter()
+                .zip(&observation_assets)
+                .map(|(date, asset)| {
+                    let (date, rows) = ((*date)?, rows.get(asset.as_ref()?)?);
+                    let known = rows.partition_point(|row| row.available <= date);
+
+                    known.checked_sub(1).map(|last| rows[last])
+                })
+                .collect();
+
+            if self.strict {
+                for (row, (date, matched)) in observation_dates.iter().zip(&matches).enumerate() {
+                    if let (Some(date), Some(matched)) = (date, matched) {
+                        if matched.date > *date {
+                            return Err(RustQuantError::ConditionViolated(format!(
+                                "Row {} of source '{}' is dated after observation row {row}.",
+                                matched.row, source.name
+                            )));
+                        }
+                    }
+                }
+            }
+
+            let indices: IdxCa = matches
+                .iter()
+                .map(|matched| matched.map(|m| m.row))
+                .collect();
+            for column in &source.columns {
+                let mut values = source.frame.column(column)?.take(&indices)?;
+                values.rename(&format!("{}_{column}", source.name));
+                panel.with_column(values)?;
+            }
+
+            for (row, matched) in matches.iter().enumerate() {
+                lineage_rows.push(row as u32);
+                lineage_sources.push(&source.name);
+                lineage_source_rows.push(matched.map(|m| m.row));
+                lineage_dates.push(matched.map(|m| m.date));
+                lineage_available.push(matched.map(|m| m.available));
+            }
+        }
+
+        let lineage = df!(
+            "row" => lineage_rows,
+            "source" => lineage_sources,
+            "source_row" => lineage_source_rows,
+            "source_date" => Series::new("source_date", lineage_dates).cast(&DataType::Date)?,
+            "available_date" => Series::new("available_date", lineage_available).cast(&DataType::Date)?,
+        )?;
+
+        Ok(PointInTimeOutput { panel, lineage })
+    }
+}
+
+/// Backward as-of join of the `columns` of `source` onto `observations`,
+/// by `(date, asset)`, with rows of `source` becoming available
+/// `availability_lag` after their date.
+///
+/// The joined columns are named `"{name}_{column}"`. See
+/// [`PointInTimePanel`] to join several sources and get a lineage report.
+///
+/// # Errors
+/// `PolarsError` if a column is missing or has an unsupported type.
+pub fn asof_join(
+    observations: &DataFrame,
+    source: PointInTimeSource,
+) -> Result<DataFrame, RustQuantError> {
+    Ok(PointInTimePanel::new(observations.clone())
+        .with_source(source)
+        .build()?
+        .panel)
+}
+
+/// Days since the Unix epoch of the dates in `column` of `df`.
+fn days(df: &DataFrame, column: &str) -> Result<Vec<Option<i32>>, RustQuantError> {
+    Ok(df
+        .column(column)?
+        .cast(&DataType::Date)?
+        .cast(&DataType::Int32)?
+        .i32()?
+        .into_iter()
+        .collect())
+}
+
+/// Assets of the rows of `df`, as strings.
+fn assets(df: &DataFrame) -> Result<Vec<Option<String>>, RustQuantError> {
+    Ok(df
+        .column("asset")?
+        .cast(&DataType::String)?
+        .str()?
+        .into_iter()
+        .map(|asset| asset.map(str::to_string))
+        .collect())
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_point_in_time {
+    use super::*;
+    use time::{macros::date, Date};
+
+    fn dates(dates: &[Date]) -> Series {
+        let days: Vec<i32> = dates
+            .iter()
+            .map(|date| (*date - date!(1970 - 01 - 01)).whole_days() as i32)
+            .collect();
+
+        Series::new("date", days).cast(&DataType::Date).unwrap()
+    }
+
+    /// Prices of two assets on April 15 and May 20, 2024.
+    fn observations() -> DataFrame {
+        df!(
+            "date" => dates(&[
+                date!(2024 - 04 - 15),
+                date!(2024 - 04 - 15),
+                date!(2024 - 05 - 20),
+                date!(2024 - 05 - 20),
+            ]),
+            "asset" => ["AAA", "BBB", "AAA", "BBB"],
+            "price" => [10.0, 20.0, 11.0, 19.0],
+        )
+        .unwrap()
+    }
+
+    /// Quarterly earnings per share, dated at the quarter end.
+    fn fundamentals() -> DataFrame {
+        df!(
+            "date" => dates(&[
+                date!(2023 - 12 - 31),
+                date!(2024 - 03 - 31),
+                date!(2023 - 12 - 31),
+            ]),
+            "asset" => ["AAA", "AAA", "BBB"],
+            "eps" => [1.0, 2.0, 5.0],
+            "filed" => dates(&[
+                date!(2024 - 02 - 10),
+                date!(2024 - 03 - 20),
+                date!(2024 - 02 - 12),
+            ]),
+        )
+        .unwrap()
+    }
+
+    fn eps(panel: &DataFrame) -> Vec<Option<f64>> {
+        panel
+            .column("fundamentals_eps")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_naive_join_leaks_and_point_in_time_does_not() {
+        // Joining on the quarter end uses Q1 earnings on April 15, before
+        // they were published (45 days after the quarter end, on May 15).
+        let naive = asof_join(
+            &observations(),
+            PointInTimeSource::new("fundamentals", fundamentals(), &["eps"]),
+        )
+        .unwrap();
+        assert_eq!(eps(&naive)[0], Some(2.0));
+
+        let point_in_time = asof_join(
+            &observations(),
+            PointInTimeSource::new("fundamentals", fundamentals(), &["eps"])
+                .with_availability_lag(Duration::days(45)),
+        )
+        .unwrap();
+
+        assert_eq!(
+            eps(&point_in_time),
+            [Some(1.0), Some(5.0), Some(2.0), Some(5.0)]
+        );
+        assert_eq!(point_in_time.height(), 4);
+    }
+
+    #[test]
+    fn test_lineage_report() {
+        let signals = df!(
+            "date" => dates(&[date!(2024 - 04 - 14), date!(2024 - 05 - 19)]),
+            "asset" => ["BBB", "BBB"],
+            "score" => [0.3, -0.1],
+        )
+        .unwrap();
+
+        let output = PointInTimePanel::new(observations())
+            .with_source(
+                PointInTimeSource::new("fundamentals", fundamentals(), &["eps"])
+                    .with_availability_lag(Duration::days(45)),
+            )
+            .with_source(
+                PointInTimeSource::new("signals", signals, &["score"])
+                    .with_availability_lag(Duration::days(1)),
+            )
+            .strict(true)
+            .build()
+            .unwrap();
+
+        let scores: Vec<Option<f64>> = output
+            .panel
+            .column("signals_score")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(scores, [None, Some(0.3), None, Some(-0.1)]);
+
+        let lineage = &output.lineage;
+        assert_eq!(lineage.height(), 8);
+
+        let source_rows: Vec<Option<IdxSize>> = lineage
+            .column("source_row")
+            .unwrap()
+            .idx()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(
+            source_rows,
+            [
+                Some(0),
+                Some(2),
+                Some(1),
+                Some(2),
+                None,
+                Some(0),
+                None,
+                Some(1)
+            ]
+        );
+
+        // Every joined row was available by its observation date.
+        let available = lineage
+            .column("available_date")
+            .unwrap()
+            .cast(&DataType::Int32)
+            .unwrap();
+        let observed = observations()
+            .column("date")
+            .unwrap()
+            .cast(&DataType::Int32)
+            .unwrap();
+        for (i, available) in available.i32().unwrap().into_iter().enumerate() {
+            if let Some(available) = available {
+                assert!(available <= observed.i32().unwrap().get(i % 4).unwrap());
+            }
+        }
+    }
+
+    #[test]
+    fn test_strict_mode_rejects_rows_dated_after_the_observation() {
+        // The Q1 filing date of AAA precedes its quarter end, so the Q1
+        // earnings would be joined on March 25, although dated March 31...
+        let source = || {
+            PointInTimeSource::new("fundamentals", fundamentals(), &["eps"])
+                .with_available_column("filed")
+        };
+        let observations = df!(
+            "date" => dates(&[date!(2024 - 03 - 25)]),
+            "asset" => ["AAA"],
+        )
+        .unwrap();
+
+        let lenient = PointInTimePanel::new(observations.clone())
+            .with_source(source())
+            .build()
+            .unwrap();
+        assert_eq!(eps(&lenient.panel), [Some(2.0)]);
+
+        // ... which strict mode refuses.
+        let strict = PointInTimePanel::new(observations)
+            .with_source(source())
+            .strict(true)
+            .build();
+        assert!(matches!(strict, Err(RustQuantError::ConditionViolated(_))));
+    }
+}
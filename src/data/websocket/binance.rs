@@ -0,0 +1,200 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{FeedProtocol, MarketDataError, MarketDataEvent, OrderBookEvent, PriceLevel, Trade};
+use crate::trading::order_side::OrderSide;
+use serde_json::Value;
+use time::OffsetDateTime;
+
+/// Binance spot public WebSocket endpoint.
+pub const BINANCE_URL: &str = "wss://stream.binance.com:9443/ws";
+
+/// Binance spot public feed: `<symbol>@trade` and `<symbol>@depth@100ms` streams.
+///
+/// Book updates carry the first (`U`) and last (`u`) update IDs they contain.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Binance;
+
+impl Binance {
+    fn streams(symbol: &str, trades: bool) -> Vec<String> {
+        let symbol = symbol.to_lowercase();
+        let mut streams = vec![format!("{symbol}@depth@100ms")];
+
+        if trades {
+            streams.insert(0, format!("{symbol}@trade"));
+        }
+
+        streams
+    }
+
+    fn request(method: &str, params: &[String], id: usize) -> String {
+        format!(
+            r#"{{"method":"{method}","params":{},"id":{id}}}"#,
+            Value::from(params)
+        )
+    }
+}
+
+impl FeedProtocol for Binance {
+    fn subscribe_messages(&self, symbols: &[String]) -> Vec<String> {
+        let params: Vec<String> = symbols
+            .iter()
+            .flat_map(|symbol| Self::streams(symbol, true))
+            .collect();
+
+        vec![Self::request("SUBSCRIBE", &params, 1)]
+    }
+
+    fn resubscribe_messages(&self, symbol: &str) -> Vec<String> {
+        vec![
+            Self::request("UNSUBSCRIBE", &Self::streams(symbol, false), 2),
+            Self::request("SUBSCRIBE", &Self::streams(symbol, false), 3),
+        ]
+    }
+
+    fn normalize(&self, message: &str) -> Result<Vec<MarketDataEvent>, MarketDataError> {
+        let value: Value =
+            serde_json::from_str(message).map_err(|e| MarketDataError::Parse(e.to_string()))?;
+
+        match value.get("e").and_then(Value::as_str) {
+            Some("trade") => Ok(vec![MarketDataEvent::Trade(Trade {
+                symbol: string(&value, "s")?,
+                trade_id: integer(&value, "t")?,
+                price: decimal(&value["p"])?,
+                quantity: decimal(&value["q"])?,
+                // `m`: the buyer is the maker, so the seller is the aggressor.
+                aggressor_side: if value["m"].as_bool() == Some(true) {
+                    OrderSide::ASK
+                } else {
+                    OrderSide::BID
+                },
+                timestamp: timestamp(integer(&value, "T")?)?,
+            })]),
+            Some("depthUpdate") => Ok(vec![MarketDataEvent::OrderBook(OrderBookEvent {
+                symbol: string(&value, "s")?,
+                first_sequence: integer(&value, "U")?,
+                last_sequence: integer(&value, "u")?,
+                bids: levels(&value["b"])?,
+                asks: levels(&value["a"])?,
+                timestamp: timestamp(integer(&value, "E")?)?,
+            })]),
+            // Subscription acknowledgements and other control messages.
+            _ => Ok(Vec::new()),
+        }
+    }
+}
+
+fn missing(key: &str) -> MarketDataError {
+    MarketDataError::Parse(format!("missing or invalid field '{key}'"))
+}
+
+fn string(value: &Value, key: &str) -> Result<String, MarketDataError> {
+    value[key]
+        .as_str()
+        .map(str::to_string)
+        .ok_or_else(|| missing(key))
+}
+
+fn integer(value: &Value, key: &str) -> Result<u64, MarketDataError> {
+    value[key].as_u64().ok_or_else(|| missing(key))
+}
+
+// Binance sends prices and quantities as strings to preserve precision.
+fn decimal(value: &Value) -> Result<f64, MarketDataError> {
+    value
+        .as_str()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| MarketDataError::Parse(format!("invalid decimal {value}")))
+}
+
+fn levels(value: &Value) -> Result<Vec<PriceLevel>, MarketDataError> {
+    value
+        .as_array()
+        .ok_or_else(|| MarketDataError::Parse(format!("invalid levels {value}")))?
+        .iter()
+        .map(|level| {
+            Ok(PriceLevel {
+                price: decimal(&level[0])?,
+                quantity: decimal(&level[1])?,
+            })
+        })
+        .collect()
+}
+
+fn timestamp(millis: u64) -> Result<OffsetDateTime, MarketDataError> {
+    OffsetDateTime::from_unix_timestamp_nanos(i128::from(millis) * 1_000_000)
+        .map_err(|e| MarketDataError::Parse(e.to_string()))
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_binance {
+    use super::*;
+    use time::macros::datetime;
+
+    #[test]
+    fn test_normalize_trade() -> Result<(), MarketDataError> {
+        let message = r#"{"e":"trade","E":1704205800123,"s":"BTCUSDT","t":3345521,"p":"42150.10","q":"0.015","b":88,"a":50,"T":1704205800120,"m":true,"M":true}"#;
+
+        let events = Binance.normalize(message)?;
+
+        assert_eq!(
+            events,
+            vec![MarketDataEvent::Trade(Trade {
+                symbol: "BTCUSDT".to_string(),
+                trade_id: 3_345_521,
+                price: 42_150.1,
+                quantity: 0.015,
+                aggressor_side: OrderSide::ASK,
+                timestamp: datetime!(2024-01-02 14:30:00.120 UTC),
+            })]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_normalize_depth_update() -> Result<(), MarketDataError> {
+        let message = r#"{"e":"depthUpdate","E":1704205800200,"s":"BTCUSDT","U":157,"u":160,"b":[["42150.00","1.5"],["42149.90","0"]],"a":[["42150.20","0.75"]]}"#;
+
+        let MarketDataEvent::OrderBook(update) = &Binance.normalize(message)?[0] else {
+            panic!("Expected a book update.");
+        };
+
+        assert_eq!((update.first_sequence, update.last_sequence), (157, 160));
+        assert_eq!(update.bids.len(), 2);
+        assert_eq!(update.bids[1].quantity, 0.0);
+        assert_eq!(update.asks[0].price, 42_150.2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_control_and_malformed_messages() {
+        assert!(Binance
+            .normalize(r#"{"result":null,"id":1}"#)
+            .unwrap()
+            .is_empty());
+        assert!(Binance.normalize("not json").is_err());
+        assert!(Binance.normalize(r#"{"e":"trade","s":"BTCUSDT"}"#).is_err());
+    }
+
+    #[test]
+    fn test_subscribe_messages() {
+        assert_eq!(
+            Binance.subscribe_messages(&["BTCUSDT".to_string()]),
+            vec![
+                r#"{"method":"SUBSCRIBE","params":["btcusdt@trade","btcusdt@depth@100ms"],"id":1}"#
+            ]
+        );
+    }
+}
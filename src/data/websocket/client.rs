@@ -0,0 +1,404 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{MarketDataError, MarketDataEvent, OrderBookEvent};
+use futures_util::{SinkExt, StreamExt};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio_tungstenite::{connect_async, tungstenite::Message};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Venue-specific part of a WebSocket feed: subscriptions and message normalization.
+pub trait FeedProtocol: Send + Sync + 'static {
+    /// Messages subscribing to the trades and book updates of `symbols`.
+    fn subscribe_messages(&self, symbols: &[String]) -> Vec<String>;
+
+    /// Messages resubscribing to the book updates of `symbol`, after a sequence gap.
+    fn resubscribe_messages(&self, symbol: &str) -> Vec<String>;
+
+    /// Normalize a text message into events.
+    /// Control messages (e.g. subscription acknowledgements) give no events.
+    ///
+    /// # Errors
+    /// `MarketDataError::Parse` if the message is malformed.
+    fn normalize(&self, message: &str) -> Result<Vec<MarketDataEvent>, MarketDataError>;
+}
+
+/// Configuration of a [`MarketDataClient`].
+#[derive(Debug, Clone)]
+pub struct WebSocketConfig {
+    /// WebSocket endpoint.
+    pub url: String,
+    /// Symbols to subscribe to.
+    pub symbols: Vec<String>,
+    /// Capacity of the channel to the consumer. When it is full,
+    /// the client stops reading from the socket until the consumer catches up.
+    pub channel_capacity: usize,
+    /// Delay before the first reconnection attempt.
+    pub initial_backoff: Duration,
+    /// Maximum delay between reconnection attempts.
+    pub max_backoff: Duration,
+    /// Maximum number of reconnections (`None` for unlimited).
+    pub max_reconnects: Option<usize>,
+}
+
+/// WebSocket market data client.
+#[derive(Debug, Clone)]
+pub struct MarketDataClient<P: FeedProtocol> {
+    /// Client configuration.
+    pub config: WebSocketConfig,
+    /// Venue protocol.
+    pub protocol: P,
+}
+
+/// Last book sequence number of each symbol.
+#[derive(Debug, Default)]
+struct SequenceTracker {
+    last_sequence: HashMap<String, u64>,
+}
+
+/// Why a connection ended.
+enum SessionEnd {
+    /// The consumer dropped the receiver.
+    ConsumerClosed,
+    /// The connection was established, then lost.
+    Disconnected(String),
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Delay before reconnection attempt number `attempt` (starting at zero):
+/// `initial * 2^attempt`, capped at `max`.
+#[must_use]
+pub fn backoff_delay(initial: Duration, max: Duration, attempt: u32) -> Duration {
+    initial
+        .saturating_mul(2_u32.saturating_pow(attempt))
+        .min(max)
+}
+
+impl WebSocketConfig {
+    /// Configuration with a channel capacity of 1024, and unlimited
+    /// reconnections with a backoff from 100 milliseconds to 30 seconds.
+    #[must_use]
+    pub fn new(url: &str, symbols: &[&str]) -> Self {
+        Self {
+            url: url.to_string(),
+            symbols: symbols.iter().map(|s| (*s).to_string()).collect(),
+            channel_capacity: 1024,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(30),
+            max_reconnects: None,
+        }
+    }
+}
+
+impl SequenceTracker {
+    /// Record an update, returning the expected first sequence number
+    /// if the update does not follow the previous one.
+    fn check(&mut self, update: &OrderBookEvent) -> Option<u64> {
+        match self.last_sequence.get(&update.symbol) {
+            Some(&last) if update.first_sequence != last + 1 => {
+                // Start afresh after resubscribing.
+                self.last_sequence.remove(&update.symbol);
+                Some(last + 1)
+            }
+            _ => {
+                self.last_sequence
+                    .insert(update.symbol.clone(), update.last_sequence);
+                None
+            }
+        }
+    }
+}
+
+impl<P: FeedProtocol> MarketDataClient<P> {
+    /// Create a new `MarketDataClient`.
+    #[must_use]
+    pub fn new(config: WebSocketConfig, protocol: P) -> Self {
+        Self { config, protocol }
+    }
+
+    /// Run the client on a new task.
+    ///
+    /// Returns the receiving end of the event channel, and the task handle.
+    /// The task ends when the receiver is dropped, or with an error when
+    /// the reconnection attempts are exhausted.
+    ///
+    /// # Panics
+    /// Panics if called outside of a Tokio runtime.
+    #[must_use]
+    pub fn spawn(
+        self,
+    ) -> (
+        mpsc::Receiver<MarketDataEvent>,
+        JoinHandle<Result<(), MarketDataError>>,
+    ) {
+        let (sender, receiver) = mpsc::channel(self.config.channel_capacity);
+
+        (receiver, tokio::spawn(self.run(sender)))
+    }
+
+    /// Run the client, sending events to `sender` until it is closed.
+    ///
+    /// # Errors
+    /// `MarketDataError::Connection` when the reconnection attempts are exhausted.
+    pub async fn run(self, sender: mpsc::Sender<MarketDataEvent>) -> Result<(), MarketDataError> {
+        let mut attempt = 0;
+        let mut reconnects = 0;
+
+        loop {
+            let reason = match self.session(&sender).await {
+                Ok(SessionEnd::ConsumerClosed) => return Ok(()),
+                Ok(SessionEnd::Disconnected(reason)) => {
+                    attempt = 0;
+                    reason
+                }
+                Err(error) => error.to_string(),
+            };
+
+            if sender.is_closed() {
+                return Ok(());
+            }
+            if self
+                .config
+                .max_reconnects
+                .is_some_and(|max| reconnects >= max)
+            {
+                return Err(MarketDataError::Connection(reason));
+            }
+
+            tokio::time::sleep(backoff_delay(
+                self.config.initial_backoff,
+                self.config.max_backoff,
+                attempt,
+            ))
+            .await;
+
+            attempt += 1;
+            reconnects += 1;
+        }
+    }
+
+    /// Connect, subscribe, and forward events until the connection is lost.
+    async fn session(
+        &self,
+        sender: &mpsc::Sender<MarketDataEvent>,
+    ) -> Result<SessionEnd, MarketDataError> {
+        let connection_error =
+            |e: tokio_tungstenite::tungstenite::Error| MarketDataError::Connection(e.to_string());
+
+        let (mut socket, _) = connect_async(self.config.url.as_str())
+            .await
+            .map_err(connection_error)?;
+
+        for message in self.protocol.subscribe_messages(&self.config.symbols) {
+            socket
+                .send(Message::Text(message))
+                .await
+                .map_err(connection_error)?;
+        }
+
+        if sender.send(MarketDataEvent::Connected).await.is_err() {
+            return Ok(SessionEnd::ConsumerClosed);
+        }
+
+        let mut tracker = SequenceTracker::default();
+
+        loop {
+            let message = tokio::select! {
+                message = socket.next() => message,
+                () = sender.closed() => return Ok(SessionEnd::ConsumerClosed),
+            };
+
+            let text = match message {
+                Some(Ok(Message::Text(text))) => text,
+                Some(Ok(Message::Close(_))) | None => {
+                    return Ok(SessionEnd::Disconnected("connection closed".to_string()))
+                }
+                // Pings are answered by the socket itself.
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => return Ok(SessionEnd::Disconnected(e.to_string())),
+            };
+
+            // Malformed messages are skipped.
+            let Ok(events) = self.protocol.normalize(&text) else {
+                continue;
+            };
+
+            for mut event in events {
+                if let MarketDataEvent::OrderBook(update) = &event {
+                    if let Some(expected) = tracker.check(update) {
+                        for message in self.protocol.resubscribe_messages(&update.symbol) {
+                            if let Err(e) = socket.send(Message::Text(message)).await {
+                                return Ok(SessionEnd::Disconnected(e.to_string()));
+                            }
+                        }
+
+                        event = MarketDataEvent::SequenceGap {
+                            symbol: update.symbol.clone(),
+                            expected,
+                            received: update.first_sequence,
+                        };
+                    }
+                }
+
+                if sender.send(event).await.is_err() {
+                    return Ok(SessionEnd::ConsumerClosed);
+                }
+            }
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_client {
+    use super::*;
+    use crate::data::websocket::Binance;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+
+    const ACK: &str = r#"{"result":null,"id":1}"#;
+    const TRADE_1: &str = r#"{"e":"trade","E":1704205800123,"s":"BTCUSDT","t":1,"p":"42150.10","q":"0.015","T":1704205800120,"m":true,"M":true}"#;
+    const TRADE_2: &str = r#"{"e":"trade","E":1704205801123,"s":"BTCUSDT","t":2,"p":"42151.00","q":"0.2","T":1704205801120,"m":false,"M":true}"#;
+    const DEPTH_1: &str = r#"{"e":"depthUpdate","E":1704205800200,"s":"BTCUSDT","U":157,"u":160,"b":[["42150.00","1.5"]],"a":[]}"#;
+    const DEPTH_2: &str = r#"{"e":"depthUpdate","E":1704205800300,"s":"BTCUSDT","U":161,"u":163,"b":[],"a":[["42150.20","0.75"]]}"#;
+    const DEPTH_GAP: &str = r#"{"e":"depthUpdate","E":1704205800400,"s":"BTCUSDT","U":170,"u":172,"b":[["42149.90","0"]],"a":[]}"#;
+
+    /// Local WebSocket server: for each connection, read the subscription,
+    /// send the fixtures, close, and record the text messages received.
+    async fn mock_server(sessions: Vec<Vec<&'static str>>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let handle = tokio::spawn(async move {
+            let mut received = Vec::new();
+
+            for fixtures in sessions {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut socket = accept_async(stream).await.unwrap();
+
+                if let Some(Ok(Message::Text(text))) = socket.next().await {
+                    received.push(text);
+                }
+                for fixture in fixtures {
+                    socket
+                        .send(Message::Text(fixture.to_string()))
+                        .await
+                        .unwrap();
+                }
+                socket.close(None).await.unwrap();
+
+                while let Some(Ok(message)) = socket.next().await {
+                    if let Message::Text(text) = message {
+                        received.push(text);
+                    }
+                }
+            }
+
+            received
+        });
+
+        (url, handle)
+    }
+
+    fn config(url: &str, max_reconnects: usize) -> WebSocketConfig {
+        WebSocketConfig {
+            channel_capacity: 2,
+            initial_backoff: Duration::from_millis(10),
+            max_reconnects: Some(max_reconnects),
+            ..WebSocketConfig::new(url, &["BTCUSDT"])
+        }
+    }
+
+    async fn collect(mut receiver: mpsc::Receiver<MarketDataEvent>) -> Vec<MarketDataEvent> {
+        let mut events = Vec::new();
+        while let Some(event) = receiver.recv().await {
+            events.push(event);
+        }
+        events
+    }
+
+    #[tokio::test]
+    async fn test_normalized_event_stream() {
+        let (url, server) =
+            mock_server(vec![vec![ACK, TRADE_1, DEPTH_1, DEPTH_2, DEPTH_GAP]]).await;
+
+        let (receiver, client) = MarketDataClient::new(config(&url, 0), Binance).spawn();
+        let events = collect(receiver).await;
+
+        let expected = [TRADE_1, DEPTH_1, DEPTH_2]
+            .iter()
+            .flat_map(|fixture| Binance.normalize(fixture).unwrap());
+
+        assert_eq!(events[0], MarketDataEvent::Connected);
+        assert_eq!(events[1..4], expected.collect::<Vec<_>>()[..]);
+        assert_eq!(
+            events[4],
+            MarketDataEvent::SequenceGap {
+                symbol: "BTCUSDT".to_string(),
+                expected: 164,
+                received: 170
+            }
+        );
+        assert_eq!(events.len(), 5);
+
+        // No reconnection allowed.
+        assert!(matches!(
+            client.await.unwrap(),
+            Err(MarketDataError::Connection(_))
+        ));
+
+        // Subscription, then resubscription after the gap.
+        let received = server.await.unwrap();
+        assert_eq!(received, {
+            let mut messages = Binance.subscribe_messages(&["BTCUSDT".to_string()]);
+            messages.extend(Binance.resubscribe_messages("BTCUSDT"));
+            messages
+        });
+    }
+
+    #[tokio::test]
+    async fn test_reconnection() {
+        let (url, server) = mock_server(vec![vec![TRADE_1], vec![TRADE_2]]).await;
+
+        let (receiver, client) = MarketDataClient::new(config(&url, 1), Binance).spawn();
+        let events = collect(receiver).await;
+
+        assert_eq!(events.len(), 4);
+        assert_eq!(events[0], MarketDataEvent::Connected);
+        assert_eq!(events[1], Binance.normalize(TRADE_1).unwrap()[0]);
+        assert_eq!(events[2], MarketDataEvent::Connected);
+        assert_eq!(events[3], Binance.normalize(TRADE_2).unwrap()[0]);
+
+        assert!(client.await.unwrap().is_err());
+        assert_eq!(server.await.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_backoff_delay() {
+        let initial = Duration::from_millis(100);
+        let max = Duration::from_secs(1);
+
+        assert_eq!(backoff_delay(initial, max, 0), initial);
+        assert_eq!(backoff_delay(initial, max, 3), Duration::from_millis(800));
+        assert_eq!(backoff_delay(initial, max, 4), max);
+        assert_eq!(backoff_delay(initial, max, 100), max);
+    }
+}
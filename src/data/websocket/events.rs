@@ -0,0 +1,93 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::trading::order_side::OrderSide;
+use thiserror::Error;
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// A trade printed on the venue.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trade {
+    /// Venue symbol.
+    pub symbol: String,
+    /// Venue trade identifier.
+    pub trade_id: u64,
+    /// Trade price.
+    pub price: f64,
+    /// Trade quantity.
+    pub quantity: f64,
+    /// Side of the aggressor (bid = buyer initiated).
+    pub aggressor_side: OrderSide,
+    /// Trade time.
+    pub timestamp: OffsetDateTime,
+}
+
+/// A price level of the book: the total quantity at a price.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceLevel {
+    /// Price of the level.
+    pub price: f64,
+    /// New total quantity at the level (zero when the level is removed).
+    pub quantity: f64,
+}
+
+/// An incremental update of the order book.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderBookEvent {
+    /// Venue symbol.
+    pub symbol: String,
+    /// Sequence number of the first change in the update.
+    pub first_sequence: u64,
+    /// Sequence number of the last change in the update.
+    pub last_sequence: u64,
+    /// Changed bid levels.
+    pub bids: Vec<PriceLevel>,
+    /// Changed ask levels.
+    pub asks: Vec<PriceLevel>,
+    /// Event time.
+    pub timestamp: OffsetDateTime,
+}
+
+/// Normalized market data event.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MarketDataEvent {
+    /// The client (re)connected and subscribed.
+    /// Books built from previous updates should be discarded.
+    Connected,
+    /// A trade.
+    Trade(Trade),
+    /// An order book update.
+    OrderBook(OrderBookEvent),
+    /// Book updates were missed for a symbol. The update that revealed
+    /// the gap is dropped, and the symbol's book channel is resubscribed.
+    SequenceGap {
+        /// Venue symbol.
+        symbol: String,
+        /// Expected first sequence number.
+        expected: u64,
+        /// Received first sequence number.
+        received: u64,
+    },
+}
+
+/// Error type for the market data client.
+#[derive(Debug, Error)]
+pub enum MarketDataError {
+    /// A message could not be normalized.
+    #[error("Failed to parse market data message: {0}")]
+    Parse(String),
+
+    /// The connection failed and the reconnection attempts were exhausted.
+    #[error("WebSocket connection failed: {0}")]
+    Connection(String),
+}
@@ -0,0 +1,41 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Streaming market data over WebSocket.
+//!
+//! Requires the `websocket` feature.
+//!
+//! The [`MarketDataClient`] connects to a venue's public feed, subscribes
+//! to trades and order book updates, and normalizes the venue's messages
+//! into [`MarketDataEvent`]s, which are sent over a bounded channel.
+//! It reconnects with exponential backoff, and resubscribes to a symbol's
+//! book updates when a sequence gap is detected.
+//!
+//! ```ignore
+//! use RustQuant::data::*;
+//!
+//! let config = WebSocketConfig::new(BINANCE_URL, &["btcusdt"]);
+//! let (mut events, _handle) = MarketDataClient::new(config, Binance).spawn();
+//!
+//! while let Some(event) = events.recv().await {
+//!     println!("{event:?}");
+//! }
+//! ```
+
+/// Binance public feed.
+pub mod binance;
+pub use binance::*;
+
+/// WebSocket client.
+pub mod client;
+pub use client::*;
+
+/// Normalized market data events.
+pub mod events;
+pub use events::*;
@@ -78,6 +78,10 @@ pub enum RustQuantError {
     #[error("{0}")]
     Gaussian(#[from] rand_distr::NormalError),
 
+    /// Error variant from constructing InverseGaussian distribution.
+    #[error("{0}")]
+    InverseGaussian(#[from] rand_distr::InverseGaussianError),
+
     /// Error variant from constructing Poisson distribution.
     #[error("{0}")]
     Poisson(#[from] rand_distr::PoissonError),
@@ -111,6 +115,138 @@ pub enum RustQuantError {
     /// Outside of interpolation range.
     #[error("Outside of interpolation range.")]
     OutsideOfRange,
+
+    // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Pricing related errors
+    // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    /// Error variant arising from pricing an instrument.
+    #[error("Pricing error: {0}")]
+    PricingError(#[from] PricingError),
+
+    // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Calibration related errors
+    // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    /// Error variant arising from calibrating a model to market data.
+    #[error("Calibration error: {0}")]
+    CalibrationError(#[from] CalibrationError),
+
+    // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Automatic differentiation related errors
+    // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    /// Error variant arising from reading a saved `Graph` tape.
+    #[error("Tape error: {0}")]
+    TapeError(#[from] TapeError),
+
+    // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    // Machine learning related errors
+    // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+    /// Error variant arising from reading a saved model.
+    #[error("Model error: {0}")]
+    ModelError(#[from] ModelError),
+}
+
+/// Pricing error enum.
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum PricingError {
+    /// The instrument expired before the valuation date, by the given
+    /// number of years.
+    #[error("Instrument expired {0} years before the valuation date.")]
+    Expired(f64),
+
+    /// The instrument's dates are inconsistent.
+    #[error("Invalid dates: {0}")]
+    InvalidDates(&'static str),
+}
+
+/// Calibration error enum.
+#[derive(Debug, Clone, Copy, PartialEq, Error)]
+pub enum CalibrationError {
+    /// The market data cannot be fitted (e.g. too few or invalid points).
+    #[error("Invalid market data: {0}")]
+    InvalidData(&'static str),
+
+    /// The optimizer failed before reaching a fit.
+    #[error("The optimizer did not converge.")]
+    NotConverged,
+
+    /// The fitted parameters violate the model's constraints.
+    #[error("The fitted parameters are degenerate: {0}")]
+    DegenerateParameters(&'static str),
+}
+
+/// Error reading a saved `Graph` tape.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum TapeError {
+    /// The data does not start with the tape header.
+    #[error("Not a tape: invalid header.")]
+    InvalidHeader,
+
+    /// The tape was written in another version of the format.
+    #[error("Tape format version {found} is not supported (expected {expected}).")]
+    VersionMismatch {
+        /// Version of the tape.
+        found: u32,
+        /// Version this build reads and writes.
+        expected: u32,
+    },
+
+    /// The data ends before the last vertex.
+    #[error("Tape is truncated.")]
+    Truncated,
+
+    /// There is data after the last vertex.
+    #[error("Tape has trailing data after the last vertex.")]
+    TrailingData,
+
+    /// A vertex has an unknown operation code, or an invalid operand.
+    #[error("Vertex {node} has an invalid operation (code {code}).")]
+    InvalidOperation {
+        /// Index of the vertex.
+        node: usize,
+        /// Operation code.
+        code: u8,
+    },
+
+    /// A vertex's parents are inconsistent with its operation, or do not
+    /// precede it.
+    #[error("Vertex {node} has invalid parents.")]
+    InvalidParents {
+        /// Index of the vertex.
+        node: usize,
+    },
+
+    /// Replaying the tape does not reproduce a saved value.
+    #[error("Replaying vertex {node} does not reproduce its saved value.")]
+    ValueMismatch {
+        /// Index of the vertex.
+        node: usize,
+    },
+}
+
+/// Error reading or writing a saved model.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ModelError {
+    /// The model was saved by another version of the format.
+    #[error("Model schema version {found} is not supported (expected {expected}).")]
+    VersionMismatch {
+        /// Version of the saved model.
+        found: u32,
+        /// Version this build reads and writes.
+        expected: u32,
+    },
+
+    /// The saved model is of another kind.
+    #[error("Saved model is a {found}, not a {expected}.")]
+    KindMismatch {
+        /// Kind of the saved model.
+        found: String,
+        /// Kind being loaded.
+        expected: &'static str,
+    },
+
+    /// The data is not a valid saved model.
+    #[error("Malformed model: {0}")]
+    Malformed(String),
 }
 
 /// Curve error enum.
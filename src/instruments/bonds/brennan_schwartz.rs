@@ -0,0 +1,249 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Two-factor Brennan-Schwartz model of the short rate and the long rate.
+//!
+//! The state is the short rate $r$ and the long rate (consol yield) $l$,
+//! which capture the level and the slope of the yield curve:
+//!
+//! $$
+//! dr = \kappa_1 (\theta_1 - r) dt + \sigma_1 dW_1 \\\\
+//! dl = \kappa_2 (\theta_2 - l) dt + \sigma_2 l dW_2 \\\\
+//! dW_1 dW_2 = \rho dt
+//! $$
+//!
+//! As in Brennan and Schwartz (1979), the long rate has proportional
+//! volatility, so it stays positive. Here both factors are mean-reverting
+//! to their own levels, and are linked through the correlation $\rho$,
+//! so the short rate on its own is a Vasicek process.
+//!
+//! Reference: Brennan and Schwartz (1979), *A Continuous Time Approach
+//! to the Pricing of Bonds*.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use rand::Rng;
+use rand_distr::StandardNormal;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Brennan-Schwartz two-factor interest rate model (risk-neutral parameters).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BrennanSchwartz {
+    /// Mean-reversion speed of the short rate.
+    pub kappa1: f64,
+    /// Mean-reversion speed of the long rate.
+    pub kappa2: f64,
+    /// Long-run level of the short rate.
+    pub theta1: f64,
+    /// Long-run level of the long rate.
+    pub theta2: f64,
+    /// Volatility of the short rate (absolute).
+    pub sigma1: f64,
+    /// Volatility of the long rate (proportional).
+    pub sigma2: f64,
+    /// Correlation between the short and long rate shocks.
+    pub rho: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl BrennanSchwartz {
+    /// Number of time steps per year used by [`Self::bond_price_mc`].
+    pub const STEPS_PER_YEAR: f64 = 252.0;
+
+    /// Simulate paths of `(short rate, long rate)` over `[0, t]`.
+    ///
+    /// Each path has `n_steps + 1` points, starting at `(r0, l0)`.
+    /// The short rate is sampled exactly from its Gaussian transition density,
+    /// and the long rate with a log-Euler step, which keeps it positive
+    /// (for $\kappa_2 \Delta t < 1$).
+    ///
+    /// # Panics
+    /// Panics if `n_steps` is zero or `rho` is not in $[-1, 1]$.
+    #[must_use]
+    pub fn simulate(
+        &self,
+        r0: f64,
+        l0: f64,
+        t: f64,
+        n_steps: usize,
+        n_paths: usize,
+        rng: &mut impl Rng,
+    ) -> Vec<Vec<(f64, f64)>> {
+        assert!(n_steps > 0, "Number of steps must be positive.");
+        assert!(
+            (-1.0..=1.0).contains(&self.rho),
+            "Correlation must be in [-1, 1]."
+        );
+
+        let dt = t / n_steps as f64;
+
+        // Exact Ornstein-Uhlenbeck transition of the short rate.
+        let decay = (-self.kappa1 * dt).exp();
+        let r_std_dev = if self.kappa1 == 0.0 {
+            self.sigma1 * dt.sqrt()
+        } else {
+            self.sigma1 * ((1.0 - decay * decay) / (2.0 * self.kappa1)).sqrt()
+        };
+
+        let rho_perp = (1.0 - self.rho * self.rho).sqrt();
+
+        (0..n_paths)
+            .map(|_| {
+                let mut path = Vec::with_capacity(n_steps + 1);
+                let (mut r, mut l) = (r0, l0);
+                path.push((r, l));
+
+                for _ in 0..n_steps {
+                    let z1: f64 = rng.sample(StandardNormal);
+                    let z2: f64 = self.rho * z1 + rho_perp * rng.sample::<f64, _>(StandardNormal);
+
+                    r = self.theta1 + (r - self.theta1) * decay + r_std_dev * z1;
+                    l = (l + self.kappa2 * (self.theta2 - l) * dt)
+                        * (-0.5 * self.sigma2 * self.sigma2 * dt + self.sigma2 * dt.sqrt() * z2)
+                            .exp();
+
+                    path.push((r, l));
+                }
+
+                path
+            })
+            .collect()
+    }
+
+    /// Monte Carlo price of a zero-coupon bond paying 1 at `maturity`:
+    /// $E[\exp(-\int_0^T r_t dt)]$, with the integral computed by the
+    /// trapezoidal rule on [`Self::STEPS_PER_YEAR`] steps per year.
+    ///
+    /// # Panics
+    /// Panics if `n_paths` is zero or `rho` is not in $[-1, 1]$.
+    #[must_use]
+    pub fn bond_price_mc(&self, r0: f64, l0: f64, maturity: f64, n_paths: usize) -> f64 {
+        assert!(n_paths > 0, "Number of paths must be positive.");
+
+        let n_steps = ((maturity * Self::STEPS_PER_YEAR).ceil() as usize).max(1);
+        let dt = maturity / n_steps as f64;
+
+        let paths = self.simulate(r0, l0, maturity, n_steps, n_paths, &mut rand::thread_rng());
+
+        paths
+            .iter()
+            .map(|path| {
+                let integral: f64 = path.windows(2).map(|w| 0.5 * (w[0].0 + w[1].0) * dt).sum();
+
+                (-integral).exp()
+            })
+            .sum::<f64>()
+            / n_paths as f64
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_brennan_schwartz {
+    use super::*;
+    use crate::assert_approx_equal;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn model(rho: f64) -> BrennanSchwartz {
+        BrennanSchwartz {
+            kappa1: 0.3,
+            kappa2: 0.1,
+            theta1: 0.05,
+            theta2: 0.06,
+            sigma1: 0.02,
+            sigma2: 0.1,
+            rho,
+        }
+    }
+
+    // Vasicek zero-coupon bond price.
+    fn vasicek_bond(r0: f64, k: f64, theta: f64, sigma: f64, tau: f64) -> f64 {
+        let B = (1.0 - (-k * tau).exp()) / k;
+        let A = (B - tau) * (k * k * theta - 0.5 * sigma * sigma) / (k * k)
+            - sigma * sigma * B * B / (4.0 * k);
+
+        (A - B * r0).exp()
+    }
+
+    #[test]
+    fn test_short_rate_marginal_is_vasicek() {
+        let model = model(0.0);
+        let (r0, t, n_paths) = (0.02, 2.0, 20_000);
+        let mut rng = StdRng::seed_from_u64(1);
+
+        let paths = model.simulate(r0, 0.06, t, 8, n_paths, &mut rng);
+        let r_t: Vec<f64> = paths.iter().map(|path| path[8].0).collect();
+
+        let mean = r_t.iter().sum::<f64>() / n_paths as f64;
+        let variance = r_t.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n_paths - 1) as f64;
+
+        let k = model.kappa1;
+        let vasicek_mean = model.theta1 + (r0 - model.theta1) * (-k * t).exp();
+        let vasicek_variance = model.sigma1.powi(2) * (1.0 - (-2.0 * k * t).exp()) / (2.0 * k);
+
+        // Standard errors: ~1.7e-4 for the mean, 1% for the variance.
+        assert_approx_equal!(mean, vasicek_mean, 5e-4);
+        assert_approx_equal!(variance / vasicek_variance, 1.0, 0.04);
+    }
+
+    #[test]
+    fn test_bond_price_matches_vasicek() {
+        let model = model(0.0);
+        let (r0, maturity) = (0.03, 2.0);
+
+        let mc = model.bond_price_mc(r0, 0.06, maturity, 5_000);
+        let exact = vasicek_bond(r0, model.kappa1, model.theta1, model.sigma1, maturity);
+
+        // Standard error ~4e-4.
+        assert_approx_equal!(mc, exact, 2e-3);
+    }
+
+    #[test]
+    fn test_long_rate_and_correlation() {
+        let model = model(0.6);
+        let mut rng = StdRng::seed_from_u64(2);
+
+        let paths = model.simulate(0.04, 0.06, 1.0, 50, 2_000, &mut rng);
+
+        assert!(paths.iter().all(|path| path.len() == 51));
+        assert!(paths.iter().flatten().all(|&(_, l)| l > 0.0));
+
+        // Correlation of the first increments.
+        let increments: Vec<(f64, f64)> = paths
+            .iter()
+            .map(|path| (path[1].0 - path[0].0, path[1].1 - path[0].1))
+            .collect();
+        let n = increments.len() as f64;
+        let (mx, my) = increments
+            .iter()
+            .fold((0.0, 0.0), |(x, y), (dx, dy)| (x + dx / n, y + dy / n));
+        let (sxy, sxx, syy) = increments
+            .iter()
+            .fold((0.0, 0.0, 0.0), |(a, b, c), (dx, dy)| {
+                (
+                    a + (dx - mx) * (dy - my),
+                    b + (dx - mx).powi(2),
+                    c + (dy - my).powi(2),
+                )
+            });
+
+        assert_approx_equal!(sxy / (sxx * syy).sqrt(), 0.6, 0.05);
+    }
+}
@@ -0,0 +1,455 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Callable bonds and option-adjusted spreads.
+//!
+//! A callable bond is priced by backward induction on a Hull-White
+//! trinomial tree for the short rate (Hull and White, 1994),
+//! $$
+//! dr = (\theta(t) - a r) dt + \sigma dW,
+//! $$
+//! with $\theta(t)$ fitted to the discount curve of the bond, so that
+//! without calls the tree reprices the straight bond. On each call date the
+//! issuer calls the bond when its continuation value (after the coupon then
+//! due) exceeds the call price.
+//!
+//! The option-adjusted spread (OAS) is the constant, continuously
+//! compounded spread over a risk-free curve at which the tree price equals
+//! the market price: the spread left once the value of the call option is
+//! accounted for.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::CouponBond;
+use crate::data::DiscountCurve;
+use crate::math::rootfinding::{
+    brent::Brent,
+    rootfinder::{Rootfinder, RootfinderData},
+};
+use crate::models::HullWhite;
+use crate::time::{Calendar, DayCountConvention};
+use time::{Date, OffsetDateTime};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Bond that the issuer may redeem early.
+pub struct CallableBond<C: Calendar> {
+    /// The straight (non-callable) bond, with its coupons constructed
+    /// (see [`CouponBond::construct_coupons`]).
+    pub bond: CouponBond<C>,
+    /// Call dates and the prices the issuer pays to call the bond on them
+    /// (in the units of the face value).
+    pub call_schedule: Vec<(OffsetDateTime, f64)>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Time steps of the tree per year.
+const STEPS_PER_YEAR: f64 = 50.0;
+
+impl<C: Calendar> CallableBond<C> {
+    /// New callable bond.
+    #[must_use]
+    pub fn new(bond: CouponBond<C>, call_schedule: Vec<(OffsetDateTime, f64)>) -> Self {
+        Self {
+            bond,
+            call_schedule,
+        }
+    }
+
+    /// Price on a Hull-White trinomial tree fitted to the discount curve of
+    /// the bond.
+    ///
+    /// The tree uses the mean reversion $a$ (`hw.alpha`) and volatility
+    /// (`hw.sigma`) at time zero; `hw.theta` is replaced by the drift that
+    /// fits the curve. Cash flows and calls are moved to the nearest of 50
+    /// time steps per year, the cash flows discounted along the curve.
+    ///
+    /// # Panics
+    /// Panics if the mean reversion is not positive, or the discount curve
+    /// has no node after the evaluation date.
+    #[must_use]
+    pub fn price_hull_white(&self, hw: &HullWhite) -> f64 {
+        self.spread_price(hw, 0.0, &self.bond.discount_curve)
+    }
+
+    /// Tree price with the continuously compounded `spread` added to the
+    /// zero rates of `discount_curve`.
+    fn spread_price(
+        &self,
+        hw: &HullWhite,
+        spread: f64,
+        discount_curve: &DiscountCurve<Date, C>,
+    ) -> f64 {
+        let a = hw.alpha.0(0.0);
+        let sigma = hw.sigma.0(0.0);
+        assert!(a > 0.0, "The mean reversion must be positive.");
+
+        let evaluation_date = self.bond.evaluation_date;
+        let year_fraction =
+            |date: Date| DayCountConvention::default().day_count_factor(evaluation_date, date);
+
+        let maturity = year_fraction(self.bond.expiration_date);
+        if maturity <= 0.0 {
+            return 0.0;
+        }
+
+        let n_steps = (maturity * STEPS_PER_YEAR).ceil().max(1.0) as usize;
+        let dt = maturity / n_steps as f64;
+        let step = |t: f64| ((t / dt).round() as usize).min(n_steps);
+
+        // Zero rates of the curve at its nodes (discount factors, as in
+        // `CouponBond::price`), linearly interpolated in time between them
+        // and flat beyond.
+        let knots: Vec<(f64, f64)> = discount_curve
+            .curve
+            .nodes
+            .iter()
+            .map(|(date, df)| (year_fraction(*date), *df))
+            .filter(|(t, _)| *t > 0.0)
+            .map(|(t, df)| (t, -df.ln() / t + spread))
+            .collect();
+        assert!(
+            !knots.is_empty(),
+            "The discount curve has no node after the evaluation date."
+        );
+
+        let zero_rate = |t: f64| match knots.iter().position(|(knot, _)| *knot >= t) {
+            Some(0) => knots[0].1,
+            Some(i) => {
+                let ((t0, z0), (t1, z1)) = (knots[i - 1], knots[i]);
+                z0 + (z1 - z0) * (t - t0) / (t1 - t0)
+            }
+            None => knots[knots.len() - 1].1,
+        };
+
+        // Cash flows off the time grid are discounted along the curve to
+        // their step, so that without calls the tree reprices the bond.
+        let cash_flows: Vec<(usize, f64)> = self
+            .bond
+            .coupons
+            .iter()
+            .filter(|(date, _)| **date > evaluation_date)
+            .map(|(date, amount)| {
+                let t = year_fraction(*date);
+                let step = step(t);
+                let t_step = step as f64 * dt;
+
+                (
+                    step,
+                    amount * (zero_rate(t_step) * t_step - zero_rate(t) * t).exp(),
+                )
+            })
+            .collect();
+        let calls: Vec<(usize, f64)> = self
+            .call_schedule
+            .iter()
+            .map(|(date, price)| (date.date(), *price))
+            .filter(|(date, _)| *date > evaluation_date && *date <= self.bond.expiration_date)
+            .map(|(date, price)| (step(year_fraction(date)), price))
+            .collect();
+
+        tree_price(a, sigma, dt, n_steps, &zero_rate, &cash_flows, &calls)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Option-adjusted spread of `callable_bond`: the constant, continuously
+/// compounded spread over `risk_free_curve` at which its Hull-White tree
+/// price (see [`CallableBond::price_hull_white`]) is `market_price`,
+/// found with Brent's method on $[-50\%, 100\%]$.
+///
+/// # Panics
+/// Panics if the mean reversion of `hw` is not positive, or the curve has
+/// no node after the evaluation date.
+#[must_use]
+pub fn option_adjusted_spread<C: Calendar>(
+    callable_bond: &CallableBond<C>,
+    market_price: f64,
+    hw: &HullWhite,
+    risk_free_curve: &DiscountCurve<Date, C>,
+) -> f64 {
+    let f = |s: f64| callable_bond.spread_price(hw, s, risk_free_curve) - market_price;
+    let data = RootfinderData::new(1e-10, 0.01, -0.5, 1.0, true);
+
+    Brent::new(f, 0.01, data).solve()
+}
+
+/// Price on a Hull-White trinomial tree of `n_steps` steps of `dt`, fitted
+/// to the zero rates `zero_rate(t)`, of `cash_flows` paid at the given
+/// steps, callable at the given steps and prices.
+fn tree_price(
+    a: f64,
+    sigma: f64,
+    dt: f64,
+    n_steps: usize,
+    zero_rate: &dyn Fn(f64) -> f64,
+    cash_flows: &[(usize, f64)],
+    calls: &[(usize, f64)],
+) -> f64 {
+    let dx = sigma * (3.0 * dt).sqrt();
+    let m = (-a * dt).exp_m1();
+    let j_max = ((0.184 / (a * dt)).ceil() as usize).max(1);
+    let width = 2 * j_max + 1;
+
+    // Branching of node j (Hull and White, 1994): the lowest node reached,
+    // and the probabilities of moving to it and the two nodes above.
+    let branch = |j: i64| -> (i64, [f64; 3]) {
+        let jm = j as f64 * m;
+        let jm2 = jm * jm;
+
+        if j == j_max as i64 {
+            (
+                j - 2,
+                [
+                    1.0 / 6.0 + (jm2 + jm) / 2.0,
+                    -1.0 / 3.0 - jm2 - 2.0 * jm,
+                    7.0 / 6.0 + (jm2 + 3.0 * jm) / 2.0,
+                ],
+            )
+        } else if j == -(j_max as i64) {
+            (
+                j,
+                [
+                    7.0 / 6.0 + (jm2 - 3.0 * jm) / 2.0,
+                    -1.0 / 3.0 - jm2 + 2.0 * jm,
+                    1.0 / 6.0 + (jm2 - jm) / 2.0,
+                ],
+            )
+        } else {
+            (
+                j - 1,
+                [
+                    1.0 / 6.0 + (jm2 - jm) / 2.0,
+                    2.0 / 3.0 - jm2,
+                    1.0 / 6.0 + (jm2 + jm) / 2.0,
+                ],
+            )
+        }
+    };
+    let levels = |step: usize| step.min(j_max) as i64;
+    let index = |j: i64| (j + j_max as i64) as usize;
+
+    // Forward induction of the Arrow-Debreu prices, fitting the drift of
+    // each step to the discount factors of the curve.
+    let mut shifts = Vec::with_capacity(n_steps);
+    let mut arrow_debreu = vec![0.0; width];
+    arrow_debreu[index(0)] = 1.0;
+
+    for step in 0..n_steps {
+        let t_next = (step + 1) as f64 * dt;
+        let target = (-zero_rate(t_next) * t_next).exp();
+        let jl = levels(step);
+        let sum: f64 = (-jl..=jl)
+            .map(|j| arrow_debreu[index(j)] * (-(j as f64) * dx * dt).exp())
+            .sum();
+        let shift = (sum / target).ln() / dt;
+        shifts.push(shift);
+
+        let mut next = vec![0.0; width];
+        for j in -jl..=jl {
+            let discounted = arrow_debreu[index(j)] * (-(shift + j as f64 * dx) * dt).exp();
+            let (low, p) = branch(j);
+            for (k, pk) in p.iter().enumerate() {
+                next[index(low + k as i64)] += discounted * pk;
+            }
+        }
+        arrow_debreu = next;
+    }
+
+    // Backward induction of the bond value.
+    let flows_at = |step: usize| -> f64 {
+        cash_flows
+            .iter()
+            .filter(|(s, _)| *s == step)
+            .map(|(_, amount)| amount)
+            .sum()
+    };
+    let call_at = |step: usize| -> Option<f64> {
+        calls
+            .iter()
+            .filter(|(s, _)| *s == step)
+            .map(|(_, price)| *price)
+            .reduce(f64::min)
+    };
+
+    let mut values = vec![0.0; width];
+    let jl = levels(n_steps);
+    for j in -jl..=jl {
+        values[index(j)] = flows_at(n_steps);
+    }
+
+    for step in (0..n_steps).rev() {
+        let jl = levels(step);
+        let mut current = vec![0.0; width];
+        for j in -jl..=jl {
+            let (low, p) = branch(j);
+            let expected: f64 = p
+                .iter()
+                .enumerate()
+                .map(|(k, pk)| pk * values[index(low + k as i64)])
+                .sum();
+            let mut value = expected * (-(shifts[step] + j as f64 * dx) * dt).exp();
+            if let Some(call) = call_at(step) {
+                value = value.min(call);
+            }
+            current[index(j)] = value + flows_at(step);
+        }
+        values = current;
+    }
+
+    values[index(0)]
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_callable_bond {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::data::Curve;
+    use crate::instruments::Instrument;
+    use crate::models::NelsonSiegelSvensson;
+    use crate::time::north_america::united_states::UnitedStatesCalendar;
+    use crate::time::{DateRollingConvention, Frequency, Schedule};
+    use std::collections::BTreeMap;
+    use time::macros::date;
+
+    const EVALUATION_DATE: Date = date!(2024 - 01 - 01);
+
+    /// Ten year bond with annual 6% coupons.
+    fn coupon_bond(rate: f64) -> CouponBond<UnitedStatesCalendar> {
+        let mut bond = CouponBond {
+            face_value: 100.0,
+            schedule: Schedule {
+                dates: Vec::new(),
+                day_count_factors: Vec::new(),
+                day_counting_convention: DayCountConvention::default(),
+                date_rolling_convention: DateRollingConvention::Actual,
+            },
+            calendar: UnitedStatesCalendar,
+            evaluation_date: EVALUATION_DATE,
+            expiration_date: date!(2034 - 01 - 01),
+            currency: None,
+            coupon_rate: 0.06,
+            coupon_frequency: Frequency::Annually,
+            settlement_convention: DateRollingConvention::Actual,
+            discount_curve: flat_curve(rate, &[]),
+            coupons: BTreeMap::new(),
+        };
+        bond.construct_coupons();
+
+        let dates: Vec<Date> = bond.coupons.keys().copied().collect();
+        bond.discount_curve = flat_curve(rate, &dates);
+
+        bond
+    }
+
+    // Discount factors of a flat continuously compounded rate.
+    fn flat_curve(rate: f64, dates: &[Date]) -> DiscountCurve<Date, UnitedStatesCalendar> {
+        let curve = Curve::<Date>::new_from_function(
+            |date| {
+                let t = DayCountConvention::default().day_count_factor(EVALUATION_DATE, date);
+                (-rate * t).exp()
+            },
+            dates,
+        );
+
+        DiscountCurve {
+            curve,
+            calendar: None,
+            day_count_convention: None,
+            date_rolling_convention: None,
+            nss: NelsonSiegelSvensson::default(),
+            fitted: false,
+            fitted_curve: None,
+        }
+    }
+
+    /// The coupon bond, callable at par on each coupon date from the third.
+    fn callable_bond(rate: f64) -> CallableBond<UnitedStatesCalendar> {
+        let bond = coupon_bond(rate);
+        let calls = bond
+            .coupons
+            .keys()
+            .skip(2)
+            .filter(|date| **date < bond.expiration_date)
+            .map(|date| (date.midnight().assume_utc(), 100.0))
+            .collect();
+
+        CallableBond::new(bond, calls)
+    }
+
+    #[test]
+    fn test_without_calls_reprices_the_straight_bond() {
+        let hw = HullWhite::new(0.1, 0.01, 0.0);
+
+        for rate in [0.03, 0.05, 0.08] {
+            let straight = CallableBond::new(coupon_bond(rate), Vec::new());
+
+            assert_approx_equal!(straight.price_hull_white(&hw), straight.bond.price(), 1e-8);
+        }
+    }
+
+    #[test]
+    fn test_call_option_lowers_the_price() {
+        let callable = callable_bond(0.05);
+
+        let straight = callable.bond.price();
+        let low_vol = callable.price_hull_white(&HullWhite::new(0.1, 0.01, 0.0));
+        let high_vol = callable.price_hull_white(&HullWhite::new(0.1, 0.02, 0.0));
+
+        assert!(low_vol < straight);
+        // Worth at most the bond called on the first call date.
+        let called_at_first_date: f64 = [6.0, 6.0, 106.0]
+            .iter()
+            .zip(callable.bond.discount_curve.curve.values())
+            .map(|(amount, df)| amount * df)
+            .sum();
+        assert!(high_vol < low_vol && low_vol <= called_at_first_date + 1e-9);
+    }
+
+    #[test]
+    fn test_option_adjusted_spread() {
+        let callable = callable_bond(0.05);
+        let curve = &callable.bond.discount_curve;
+        let hw = HullWhite::new(0.1, 0.01, 0.0);
+
+        // At the model price there is no spread.
+        let model_price = callable.price_hull_white(&hw);
+        assert_approx_equal!(
+            option_adjusted_spread(&callable, model_price, &hw, curve),
+            0.0,
+            1e-8
+        );
+
+        // A bond trading cheap to its straight bond equivalent has a
+        // positive OAS, at which the tree reprices it.
+        let market_price = model_price - 1.0;
+        let oas = option_adjusted_spread(&callable, market_price, &hw, curve);
+        assert!(oas > 0.0);
+        assert_approx_equal!(callable.spread_price(&hw, oas, curve), market_price, 1e-8);
+
+        // The spread of the straight bond at the same price (its Z-spread)
+        // ignores the call option, so it is wider by the option cost.
+        let straight = CallableBond::new(coupon_bond(0.05), Vec::new());
+        assert!(option_adjusted_spread(&straight, market_price, &hw, curve) > oas);
+    }
+}
@@ -0,0 +1,283 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! CIR++ model (Brigo-Mercurio): a Cox-Ingersoll-Ross factor plus a
+//! deterministic shift fitted to the market curve,
+//!
+//! $$
+//! r_t = x_t + \varphi(t), \qquad
+//! dx_t = \kappa (\bar{x} - x_t) dt + \sigma \sqrt{x_t} dW_t.
+//! $$
+//!
+//! With $\varphi(t) = f^M(0,t) - f^{CIR}(0,t; x_0)$ the model reproduces the
+//! market discount factors exactly, and the short rate stays positive as
+//! long as the shift does and the Feller condition $2 \kappa \bar{x} >
+//! \sigma^2$ holds.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::data::PiecewiseCurve;
+use crate::models::CoxIngersollRoss;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// CIR++ short rate model.
+///
+/// The CIR factor uses the long-run mean `mu` ($\bar{x}$), volatility
+/// `sigma`, and mean reversion speed `theta` ($\kappa$) of the underlying
+/// [`CoxIngersollRoss`] process, evaluated at time zero: the shift is what
+/// makes the model time-dependent.
+pub struct CIRPlusPlus {
+    /// The CIR factor $x_t$.
+    pub cir: CoxIngersollRoss,
+    /// Initial value $x_0$ of the CIR factor.
+    pub x0: f64,
+    /// The deterministic shift $\varphi(t)$.
+    pub shift_fn: Box<dyn Fn(f64) -> f64 + Send + Sync>,
+    market_curve: PiecewiseCurve,
+}
+
+/// Constant CIR parameters, as used in the closed-form bond prices.
+#[derive(Debug, Clone, Copy)]
+struct CirParameters {
+    kappa: f64,
+    mean: f64,
+    sigma: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl CirParameters {
+    fn new(cir: &CoxIngersollRoss) -> Self {
+        Self {
+            kappa: cir.theta.0(0.0),
+            mean: cir.mu.0(0.0),
+            sigma: cir.sigma.0(0.0),
+        }
+    }
+
+    /// $h = \sqrt{\kappa^2 + 2 \sigma^2}$, and the common denominator
+    /// $2h + (\kappa + h)(e^{h \tau} - 1)$.
+    fn h_and_denominator(&self, tau: f64) -> (f64, f64) {
+        let h = (self.kappa * self.kappa + 2.0 * self.sigma * self.sigma).sqrt();
+        let denominator = 2.0 * h + (self.kappa + h) * (h * tau).exp_m1();
+
+        (h, denominator)
+    }
+
+    /// CIR discount factor $A(\tau) e^{-B(\tau) x}$ over a period `tau`.
+    fn discount_factor(&self, x: f64, tau: f64) -> f64 {
+        let (h, denominator) = self.h_and_denominator(tau);
+
+        let a = (2.0 * h * (0.5 * (self.kappa + h) * tau).exp() / denominator)
+            .powf(2.0 * self.kappa * self.mean / (self.sigma * self.sigma));
+        let b = 2.0 * (h * tau).exp_m1() / denominator;
+
+        a * (-b * x).exp()
+    }
+
+    /// CIR instantaneous forward rate $f^{CIR}(0, t; x_0)$.
+    fn forward_rate(&self, x0: f64, t: f64) -> f64 {
+        let (h, denominator) = self.h_and_denominator(t);
+
+        2.0 * self.kappa * self.mean * (h * t).exp_m1() / denominator
+            + x0 * 4.0 * h * h * (h * t).exp() / (denominator * denominator)
+    }
+}
+
+impl CIRPlusPlus {
+    /// Fit the shift $\varphi(t) = f^M(0,t) - f^{CIR}(0,t; x_0)$ to the
+    /// instantaneous forwards of the market curve.
+    ///
+    /// Jumps in the market curve are not part of its forwards, so they are
+    /// not in the shift either; [`CIRPlusPlus::bond_price`] still reproduces
+    /// them, as it uses the market discount factors directly.
+    #[must_use]
+    pub fn calibrate_shift(cir: CoxIngersollRoss, x0: f64, market_curve: &PiecewiseCurve) -> Self {
+        let parameters = CirParameters::new(&cir);
+        let curve = market_curve.clone();
+
+        Self {
+            cir,
+            x0,
+            shift_fn: Box::new(move |t| curve.forward_rate(t) - parameters.forward_rate(x0, t)),
+            market_curve: market_curve.clone(),
+        }
+    }
+
+    /// The deterministic shift $\varphi(t)$.
+    #[must_use]
+    pub fn shift(&self, t: f64) -> f64 {
+        (self.shift_fn)(t)
+    }
+
+    /// Initial short rate $r_0 = x_0 + \varphi(0)$.
+    #[must_use]
+    pub fn initial_short_rate(&self) -> f64 {
+        self.x0 + self.shift(0.0)
+    }
+
+    /// Price at time `t` of the zero-coupon bond maturing at `maturity`,
+    /// given the short rate `r_t` at `t`:
+    ///
+    /// $$
+    /// P(t,T) = \frac{P^M(0,T) P^{CIR}(0,t; x_0)}{P^M(0,t) P^{CIR}(0,T; x_0)}
+    ///     P^{CIR}(t,T; r_t - \varphi(t)).
+    /// $$
+    #[must_use]
+    pub fn bond_price(&self, r_t: f64, t: f64, maturity: f64) -> f64 {
+        let parameters = CirParameters::new(&self.cir);
+
+        let market_df = |s: f64| {
+            if s <= 0.0 {
+                1.0
+            } else {
+                self.market_curve.discount_factor(s)
+            }
+        };
+
+        let correction = market_df(maturity) * parameters.discount_factor(self.x0, t)
+            / (market_df(t) * parameters.discount_factor(self.x0, maturity));
+
+        correction * parameters.discount_factor(r_t - self.shift(t), maturity - t)
+    }
+
+    /// Whether the Feller condition $2 \kappa \bar{x} > \sigma^2$ holds, so
+    /// the CIR factor stays strictly positive.
+    #[must_use]
+    pub fn feller_condition(&self) -> bool {
+        let parameters = CirParameters::new(&self.cir);
+
+        2.0 * parameters.kappa * parameters.mean > parameters.sigma * parameters.sigma
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_cir_plus_plus {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::data::CurveInterpolation;
+
+    const PILLARS: [f64; 6] = [0.5, 1.0, 2.0, 5.0, 10.0, 30.0];
+
+    fn market_curve() -> PiecewiseCurve {
+        let zero_rates = [0.021, 0.024, 0.027, 0.031, 0.034, 0.036];
+        let log_discounts: Vec<f64> = PILLARS
+            .iter()
+            .zip(zero_rates)
+            .map(|(t, z)| -z * t)
+            .collect();
+
+        PiecewiseCurve::new(
+            &PILLARS,
+            &log_discounts,
+            CurveInterpolation::MonotoneConvex,
+            vec![],
+        )
+        .unwrap()
+    }
+
+    fn model(curve: &PiecewiseCurve) -> CIRPlusPlus {
+        CIRPlusPlus::calibrate_shift(CoxIngersollRoss::new(0.03, 0.08, 0.4), 0.015, curve)
+    }
+
+    #[test]
+    fn test_reproduces_market_discount_factors() {
+        let curve = market_curve();
+        let model = model(&curve);
+        let r0 = model.initial_short_rate();
+
+        for t in PILLARS.iter().chain(&[0.25, 3.0, 7.5, 20.0]) {
+            assert_approx_equal!(
+                model.bond_price(r0, 0.0, *t),
+                curve.discount_factor(*t),
+                1e-12
+            );
+        }
+        assert_approx_equal!(model.bond_price(r0, 0.0, 0.0), 1.0, 1e-15);
+        assert!(model.feller_condition());
+    }
+
+    #[test]
+    fn test_shift_integrates_to_curve_difference() {
+        // int_0^T phi = ln P^CIR(0, T; x0) - ln P^M(0, T).
+        let curve = market_curve();
+        let model = model(&curve);
+        let parameters = CirParameters::new(&model.cir);
+
+        for t in [1.0, 5.0, 10.0] {
+            let integral: f64 = (0..(4.0 * t) as usize)
+                .map(|i| {
+                    let a = 0.25 * i as f64;
+                    crate::math::integrate(|s| model.shift(s), a, a + 0.25)
+                })
+                .sum();
+
+            assert_approx_equal!(
+                integral,
+                parameters.discount_factor(model.x0, t).ln() - curve.discount_factor(t).ln(),
+                1e-8
+            );
+        }
+    }
+
+    #[test]
+    fn test_shift_vanishes_on_a_cir_curve() {
+        let cir = CoxIngersollRoss::new(0.03, 0.08, 0.4);
+        let parameters = CirParameters::new(&cir);
+
+        let knots: Vec<f64> = (1..=80).map(|i| 0.125 * i as f64).collect();
+        let log_discounts: Vec<f64> = knots
+            .iter()
+            .map(|t| parameters.discount_factor(0.015, *t).ln())
+            .collect();
+        let curve = PiecewiseCurve::new(
+            &knots,
+            &log_discounts,
+            CurveInterpolation::MonotoneConvex,
+            vec![],
+        )
+        .unwrap();
+
+        let model = CIRPlusPlus::calibrate_shift(cir, 0.015, &curve);
+        for t in [0.3, 1.0, 2.7, 6.1, 9.5] {
+            assert!(model.shift(t).abs() < 1e-4, "{}", model.shift(t));
+        }
+    }
+
+    #[test]
+    fn test_bond_price_in_the_future() {
+        let curve = market_curve();
+        let model = model(&curve);
+        let (t, maturity) = (2.0, 7.0);
+        let x = 0.02;
+
+        let price = model.bond_price(x + model.shift(t), t, maturity);
+        assert_approx_equal!(model.bond_price(0.05, t, t), 1.0, 1e-12);
+
+        // Higher rates mean lower prices, and the price is between zero and one.
+        assert!(model.bond_price(x + 0.01 + model.shift(t), t, maturity) < price);
+        assert!(price > 0.0 && price < 1.0);
+
+        // With the factor near its mean, the price is close to the market
+        // forward discount factor.
+        let forward = curve.discount_factor(maturity) / curve.discount_factor(t);
+        assert_approx_equal!(price, forward, 0.02);
+    }
+}
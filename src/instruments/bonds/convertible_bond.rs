@@ -0,0 +1,357 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Convertible bonds, priced with the Tsiveriotis-Fernandes model.
+//!
+//! A convertible bond pays coupons and its face value at maturity, but can
+//! be converted by the holder into `conversion_ratio` shares at any time.
+//! The issuer may be able to call it back, and the holder to put it back,
+//! at given prices.
+//!
+//! Tsiveriotis and Fernandes (1998) split the value on a Cox-Ross-Rubinstein
+//! tree into an equity component, paid in shares and discounted at the
+//! risk-free rate, and a debt (cash-only) component, exposed to the
+//! issuer's default and discounted at the risk-free rate plus the credit
+//! spread.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::time::Schedule;
+use crate::units::{Price, Rate, TimeFraction};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Issuer call provision: the bond can be called at `price` (plus any
+/// coupon due) at times in `[start, end]`.
+///
+/// A soft call can only be exercised once the share price has risen above
+/// `trigger`. Soft calls usually require the share price to stay above the
+/// trigger for, say, 20 of the last 30 trading days; this path dependence
+/// is approximated by checking the share price at the tree node.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CallProvision {
+    /// Start of the call period, in years.
+    pub start: f64,
+    /// End of the call period, in years.
+    pub end: f64,
+    /// Call price.
+    pub price: f64,
+    /// Share price above which the bond is callable (soft call), if any.
+    pub trigger: Option<f64>,
+}
+
+/// Holder put provision: the bond can be put back at `price` (plus any
+/// coupon due) at `time`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PutProvision {
+    /// Exercise time, in years.
+    pub time: f64,
+    /// Put price.
+    pub price: f64,
+}
+
+/// Convertible bond.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConvertibleBond {
+    /// Face value, redeemed at maturity.
+    pub face_value: f64,
+    /// Maturity, in years.
+    pub maturity: f64,
+    /// Coupons, as `(time, amount)` pairs, with times in years.
+    pub coupons: Vec<(f64, f64)>,
+    /// Number of shares received on conversion.
+    pub conversion_ratio: f64,
+    /// Issuer call provisions.
+    pub call_schedule: Vec<CallProvision>,
+    /// Holder put provisions.
+    pub put_schedule: Vec<PutProvision>,
+    /// Issuer's credit spread over the risk-free rate.
+    pub credit_spread: f64,
+}
+
+/// Value of a convertible bond, split into its equity and debt components.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConvertibleBondValue {
+    /// Value of the bond.
+    pub price: f64,
+    /// Value of the payoffs received in shares.
+    pub equity_component: f64,
+    /// Value of the payoffs received in cash.
+    pub debt_component: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl CallProvision {
+    fn is_active(&self, time: f64, underlying_price: f64) -> bool {
+        (self.start..=self.end).contains(&time)
+            && self
+                .trigger
+                .is_none_or(|trigger| underlying_price >= trigger)
+    }
+}
+
+impl ConvertibleBond {
+    /// New convertible bond without coupons or call and put provisions.
+    #[must_use]
+    pub fn new(
+        face_value: Price,
+        maturity: TimeFraction,
+        conversion_ratio: f64,
+        credit_spread: Rate,
+    ) -> Self {
+        Self {
+            face_value: face_value.value(),
+            maturity: maturity.value(),
+            coupons: Vec::new(),
+            conversion_ratio,
+            call_schedule: Vec::new(),
+            put_schedule: Vec::new(),
+            credit_spread: credit_spread.value(),
+        }
+    }
+
+    /// Adds coupons at rate `coupon_rate` on the periods of `schedule`.
+    ///
+    /// The schedule's day count factors are the accrual periods, starting
+    /// from the valuation date, so the coupon times are their running sums.
+    #[must_use]
+    pub fn with_coupon_schedule(mut self, schedule: &Schedule, coupon_rate: f64) -> Self {
+        let mut time = 0.0;
+
+        for accrual in &schedule.day_count_factors {
+            time += accrual;
+            self.coupons
+                .push((time, self.face_value * coupon_rate * accrual));
+        }
+
+        self
+    }
+
+    /// Adds an issuer call provision.
+    #[must_use]
+    pub fn with_call(mut self, call: CallProvision) -> Self {
+        self.call_schedule.push(call);
+        self
+    }
+
+    /// Adds a holder put provision.
+    #[must_use]
+    pub fn with_put(mut self, put: PutProvision) -> Self {
+        self.put_schedule.push(put);
+        self
+    }
+
+    /// Tsiveriotis-Fernandes value on a Cox-Ross-Rubinstein tree with
+    /// `steps` time steps.
+    ///
+    /// The equity parameters follow [`BlackScholesMerton`](crate::instruments::BlackScholesMerton):
+    /// `cost_of_carry` is $b = r - q$ for a dividend yield $q$. Coupon, call,
+    /// and put times are rounded to the nearest time step. At each node the
+    /// value is $\max(\kappa S, \max(P, \min(V, C)))$ for conversion value
+    /// $\kappa S$, put price $P$, call price $C$, and continuation value $V$,
+    /// and at maturity the holder converts if the shares are worth more than
+    /// the redemption amount.
+    ///
+    /// # Panics
+    /// Panics if `steps` is zero or the maturity is not positive.
+    #[must_use]
+    pub fn price(
+        &self,
+        underlying_price: f64,
+        volatility: f64,
+        risk_free_rate: f64,
+        cost_of_carry: f64,
+        steps: usize,
+    ) -> ConvertibleBondValue {
+        assert!(steps > 0, "At least one time step is required.");
+        assert!(self.maturity > 0.0, "Maturity must be positive.");
+
+        let dt = self.maturity / steps as f64;
+        let u = (volatility * dt.sqrt()).exp();
+        let d = 1.0 / u;
+        let p = ((cost_of_carry * dt).exp() - d) / (u - d);
+
+        let equity_discount = (-risk_free_rate * dt).exp();
+        let debt_discount = (-(risk_free_rate + self.credit_spread) * dt).exp();
+
+        let step = |time: f64| (time / dt).round() as usize;
+        let spot = |k: usize, j: usize| underlying_price * u.powi(2 * j as i32 - k as i32);
+
+        let mut coupons = vec![0.0; steps + 1];
+        for &(time, amount) in &self.coupons {
+            if time > 0.0 && step(time) <= steps {
+                coupons[step(time)] += amount;
+            }
+        }
+
+        // At maturity: convert, or redeem.
+        let (mut equity, mut debt): (Vec<f64>, Vec<f64>) = (0..=steps)
+            .map(|j| {
+                let conversion = self.conversion_ratio * spot(steps, j);
+                let redemption = self.face_value + coupons[steps];
+
+                if conversion > redemption {
+                    (conversion, 0.0)
+                } else {
+                    (0.0, redemption)
+                }
+            })
+            .unzip();
+
+        for k in (0..steps).rev() {
+            let time = k as f64 * dt;
+            let put = self.put_schedule.iter().find(|put| step(put.time) == k);
+
+            for j in 0..=k {
+                let s = spot(k, j);
+                let mut e = equity_discount * (p * equity[j + 1] + (1.0 - p) * equity[j]);
+                let mut b = debt_discount * (p * debt[j + 1] + (1.0 - p) * debt[j]) + coupons[k];
+
+                if let Some(call) = self
+                    .call_schedule
+                    .iter()
+                    .find(|call| call.is_active(time, s))
+                {
+                    if e + b > call.price + coupons[k] {
+                        (e, b) = (0.0, call.price + coupons[k]);
+                    }
+                }
+
+                if let Some(put) = put {
+                    if put.price + coupons[k] > e + b {
+                        (e, b) = (0.0, put.price + coupons[k]);
+                    }
+                }
+
+                let conversion = self.conversion_ratio * s;
+                if conversion > e + b {
+                    (e, b) = (conversion, 0.0);
+                }
+
+                equity[j] = e;
+                debt[j] = b;
+            }
+        }
+
+        ConvertibleBondValue {
+            price: equity[0] + debt[0],
+            equity_component: equity[0],
+            debt_component: debt[0],
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_convertible_bond {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::options::black_scholes;
+    use crate::time::{DateRollingConvention, DayCountConvention};
+    use time::macros::date;
+
+    #[test]
+    fn test_zero_conversion_ratio_is_straight_bond() {
+        // 5% annual coupons over five years.
+        let schedule = Schedule {
+            dates: vec![
+                date!(2025 - 01 - 01),
+                date!(2026 - 01 - 01),
+                date!(2027 - 01 - 01),
+                date!(2028 - 01 - 01),
+                date!(2029 - 01 - 01),
+            ],
+            day_count_factors: vec![1.0; 5],
+            day_counting_convention: DayCountConvention::One_One,
+            date_rolling_convention: DateRollingConvention::Actual,
+        };
+        let bond = ConvertibleBond::new(100.0.into(), 5.0.into(), 0.0, 0.02.into())
+            .with_coupon_schedule(&schedule, 0.05);
+
+        let value = bond.price(50.0, 0.3, 0.04, 0.04, 500);
+        let expected = (1..=5)
+            .map(|t| 5.0 * (-0.06 * f64::from(t)).exp())
+            .sum::<f64>()
+            + 100.0 * (-0.3_f64).exp();
+
+        assert_approx_equal!(value.price, expected, 1e-10);
+        assert_approx_equal!(value.equity_component, 0.0, 1e-15);
+    }
+
+    #[test]
+    fn test_deep_in_the_money_is_conversion_value() {
+        let bond = ConvertibleBond::new(100.0.into(), 5.0.into(), 1.0, 0.03.into());
+        let value = bond.price(1_000.0, 0.2, 0.05, 0.05, 500);
+
+        assert_approx_equal!(value.price, 1_000.0, 1e-3);
+        assert!(value.equity_component > 0.999_99 * value.price);
+    }
+
+    #[test]
+    fn test_risk_free_bond_plus_call() {
+        // With no credit spread, coupons, or dividends, early conversion is
+        // never optimal, so the convertible is a zero-coupon bond plus
+        // `conversion_ratio` European calls struck at the conversion price.
+        let (s, k, t, r, v): (f64, f64, f64, f64, f64) = (50.0, 50.0, 1.0, 0.05, 0.3);
+        let bond = ConvertibleBond::new(100.0.into(), t.into(), 2.0, 0.0.into());
+
+        let call = black_scholes::call_price(s, k, r, 0.0, v, t);
+
+        let value = bond.price(s, v, r, r, 2_000);
+        assert_approx_equal!(value.price, 100.0 * (-r * t).exp() + 2.0 * call, 0.02);
+    }
+
+    #[test]
+    fn test_call_and_put_provisions() {
+        let bond = ConvertibleBond::new(100.0.into(), 5.0.into(), 1.0, 0.02.into());
+        let price = |bond: &ConvertibleBond| bond.price(90.0, 0.25, 0.04, 0.03, 500).price;
+
+        let hard_call = CallProvision {
+            start: 2.0,
+            end: 5.0,
+            price: 105.0,
+            trigger: None,
+        };
+        let soft_call = CallProvision {
+            trigger: Some(130.0),
+            ..hard_call
+        };
+        let put = PutProvision {
+            time: 3.0,
+            price: 102.0,
+        };
+
+        let straight = price(&bond);
+        let hard = price(&bond.clone().with_call(hard_call));
+        let soft = price(&bond.clone().with_call(soft_call));
+        let putable = price(&bond.clone().with_put(put));
+
+        assert!(hard < soft && soft < straight);
+        assert!(putable > straight);
+
+        // Splitting the value doesn't change the total.
+        let value = bond.price(90.0, 0.25, 0.04, 0.03, 500);
+        assert_approx_equal!(
+            value.equity_component + value.debt_component,
+            value.price,
+            1e-12
+        );
+        assert!(value.equity_component > 0.0 && value.debt_component > 0.0);
+    }
+}
@@ -0,0 +1,212 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Convexity corrections between interest rate futures and forward rates.
+//!
+//! Futures are marked to market daily, so with stochastic interest rates the
+//! futures rate is higher than the forward rate for the same period.
+//! The corrections below assume the Hull-White (extended Vasicek) model
+//!
+//! $$
+//! dr = (\theta(t) - a r) dt + \sigma dW
+//! $$
+//!
+//! with $B(t, T) = (1 - e^{-a(T - t)}) / a$. For $a \to 0$ they reduce to
+//! the Ho-Lee corrections.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Below this mean reversion speed, the Ho-Lee limits are used.
+const MIN_MEAN_REVERSION: f64 = 1e-8;
+
+/// Hull-White $B(t, T) = (1 - e^{-a(T - t)}) / a$, which tends to $T - t$ as $a \to 0$.
+fn B(t: f64, T: f64, a: f64) -> f64 {
+    if a.abs() < MIN_MEAN_REVERSION {
+        T - t
+    } else {
+        (1.0 - (-a * (T - t)).exp()) / a
+    }
+}
+
+/// $(1 - e^{-2 a t}) / (2 a)$, the variance factor of the short rate at time $t$.
+fn variance_factor(t: f64, a: f64) -> f64 {
+    B(0.0, t, 2.0 * a)
+}
+
+/// Convexity adjustment of a Eurodollar-style futures contract under Hull-White:
+/// the continuously compounded futures rate minus the forward rate for
+/// the period $[t_1, t_2]$.
+///
+/// $$
+/// \frac{B(t_1, t_2)}{t_2 - t_1} \left[ B(t_1, t_2)(1 - e^{-2 a t_1}) + 2 a B(0, t_1)^2 \right] \frac{\sigma^2}{4 a}
+/// $$
+///
+/// For $a \to 0$ this is the Ho-Lee adjustment $\sigma^2 t_1 t_2 / 2$.
+///
+/// # Arguments
+/// - `t1`: futures expiry (start of the rate period), in years.
+/// - `t2`: end of the rate period, in years.
+/// - `sigma`: short rate volatility.
+/// - `a`: mean reversion speed.
+///
+/// # Panics
+/// Panics if `t1 < 0` or `t2 <= t1`.
+#[must_use]
+pub fn eurodollar_futures_convexity_adjustment(t1: f64, t2: f64, sigma: f64, a: f64) -> f64 {
+    assert!(t1 >= 0.0 && t2 > t1, "Require 0 <= t1 < t2.");
+
+    let b = B(t1, t2, a);
+    let b0 = B(0.0, t1, a);
+
+    // [B (1 - e^{-2 a t1}) + 2 a B0^2] / (4 a), written to be stable as a -> 0.
+    let bracket = 0.5 * (b * variance_factor(t1, a) + b0 * b0);
+
+    b / (t2 - t1) * bracket * sigma * sigma
+}
+
+/// Convexity exponent $\Gamma$ under Hull-White, such that
+///
+/// $$
+/// E^{Q}\left[\frac{1}{P(t_1, t_2)}\right] = \frac{P(0, t_1)}{P(0, t_2)} e^{\Gamma}
+/// $$
+///
+/// where the expectation is under the risk-neutral (futures) measure:
+///
+/// $$
+/// \Gamma = \sigma^2 B(t_1, t_2) \int_0^{t_1} e^{-a(t_1 - s)} B(s, t_2) ds
+///        = \frac{\sigma^2}{a} B(t_1, t_2) \left[ B(0, t_1) - e^{-a(t_2 - t_1)} \frac{1 - e^{-2 a t_1}}{2 a} \right].
+/// $$
+///
+/// # Panics
+/// Panics if `t1 < 0` or `t2 <= t1`.
+#[must_use]
+pub fn hull_white_convexity_exponent(t1: f64, t2: f64, sigma: f64, a: f64) -> f64 {
+    assert!(t1 >= 0.0 && t2 > t1, "Require 0 <= t1 < t2.");
+
+    let b = B(t1, t2, a);
+
+    let integral = if a.abs() < MIN_MEAN_REVERSION {
+        // Ho-Lee: the integral of (t2 - s) over [0, t1].
+        t1 * (t2 - 0.5 * t1)
+    } else {
+        (B(0.0, t1, a) - (-a * (t2 - t1)).exp() * variance_factor(t1, a)) / a
+    };
+
+    sigma * sigma * b * integral
+}
+
+/// Convexity adjustment for a futures contract on a simply compounded rate
+/// of any tenor (e.g. 1M SOFR, 3M Euribor): the futures rate minus the
+/// forward rate $F$ for the period $[t, t + \delta]$.
+///
+/// Since $1 + \delta L(t, t + \delta) = 1 / P(t, t + \delta)$, the futures rate is
+/// $E^Q[L] = ((1 + \delta F) e^{\Gamma} - 1) / \delta$, hence the adjustment
+///
+/// $$
+/// \left(\frac{1}{\delta} + F\right) \left(e^{\Gamma} - 1\right)
+/// $$
+///
+/// with $\Gamma$ from [`hull_white_convexity_exponent`].
+///
+/// # Arguments
+/// - `forward_rate`: simply compounded forward rate $F$ for the period.
+/// - `expiry`: start $t$ of the rate period, in years.
+/// - `tenor`: accrual period $\delta$, in years.
+/// - `sigma`: short rate volatility.
+/// - `a`: mean reversion speed.
+///
+/// # Panics
+/// Panics if `expiry < 0` or `tenor <= 0`.
+#[must_use]
+pub fn futures_convexity_adjustment(
+    forward_rate: f64,
+    expiry: f64,
+    tenor: f64,
+    sigma: f64,
+    a: f64,
+) -> f64 {
+    let gamma = hull_white_convexity_exponent(expiry, expiry + tenor, sigma, a);
+
+    (1.0 / tenor + forward_rate) * gamma.exp_m1()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_convexity_correction {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    #[test]
+    fn test_ho_lee_limit() {
+        // Hull, Options, Futures, and Other Derivatives:
+        // 8-year Eurodollar futures, sigma = 1.2%, adjustment = 0.5 * 0.012^2 * 8 * 8.25.
+        let adjustment = eurodollar_futures_convexity_adjustment(8.0, 8.25, 0.012, 0.0);
+
+        assert_approx_equal!(adjustment, 0.004_752, 1e-12);
+        assert_approx_equal!(
+            eurodollar_futures_convexity_adjustment(8.0, 8.25, 0.012, 1e-6),
+            adjustment,
+            1e-7
+        );
+    }
+
+    #[test]
+    fn test_adjustment_positive_and_increasing() {
+        let sigma = 0.01;
+        let a = 0.05;
+
+        let mut previous = 0.0;
+        for quarter in 1..=40 {
+            let t1 = f64::from(quarter) * 0.25;
+            let adjustment = eurodollar_futures_convexity_adjustment(t1, t1 + 0.25, sigma, a);
+            let simple = futures_convexity_adjustment(0.04, t1, 0.25, sigma, a);
+
+            assert!(adjustment > previous);
+            assert!(simple > 0.0);
+            previous = adjustment;
+        }
+
+        let mut previous = 0.0;
+        for sigma in [0.005, 0.01, 0.015, 0.02] {
+            let adjustment = eurodollar_futures_convexity_adjustment(5.0, 5.25, sigma, a);
+            let simple = futures_convexity_adjustment(0.04, 5.0, 0.25, sigma, a);
+
+            assert!(adjustment > previous);
+            assert!(simple > adjustment);
+            previous = adjustment;
+        }
+
+        // Mean reversion damps the adjustment.
+        assert!(
+            eurodollar_futures_convexity_adjustment(5.0, 5.25, 0.01, 0.1)
+                < eurodollar_futures_convexity_adjustment(5.0, 5.25, 0.01, 0.01)
+        );
+    }
+
+    #[test]
+    fn test_continuous_and_simple_adjustments_agree() {
+        // For Gaussian rates, the continuously compounded adjustment is
+        // (Gamma - Var[ln P(t1, t2)] / 2) / (t2 - t1).
+        let (t1, t2, sigma, a) = (3.0, 3.5, 0.012, 0.08);
+
+        let gamma = hull_white_convexity_exponent(t1, t2, sigma, a);
+        let variance = sigma * sigma * B(t1, t2, a).powi(2) * variance_factor(t1, a);
+
+        assert_approx_equal!(
+            eurodollar_futures_convexity_adjustment(t1, t2, sigma, a),
+            (gamma - 0.5 * variance) / (t2 - t1),
+            1e-14
+        );
+    }
+}
@@ -8,7 +8,7 @@
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 use super::zero_coupon_bond::ZeroCouponBond;
-use crate::data::{Curve, DiscountCurve};
+use crate::data::DiscountCurve;
 use crate::instruments::fx::currency::Currency;
 use crate::instruments::Instrument;
 use crate::time::{Calendar, DateRollingConvention, Frequency, Schedule};
@@ -144,7 +144,7 @@ where
         // Compute the present value of the coupons and face value, and sum them.
         self.coupons
             .values()
-            .zip(self.discount_curve.curve.nodes.values().into_iter())
+            .zip(self.discount_curve.curve.nodes.values())
             .map(|(coupon, df)| coupon * df)
             .sum::<f64>()
     }
@@ -193,10 +193,15 @@ impl CouponBond2 {
 #[cfg(test)]
 mod tests_bond {
     use super::*;
-    use crate::{data::Curve, iso::USD, time::today};
+    use crate::{
+        data::Curve,
+        instruments::fx::currency::USD,
+        models::NelsonSiegelSvensson,
+        time::{north_america::united_states::UnitedStatesCalendar, today, DayCountConvention},
+    };
 
     #[allow(clippy::similar_names)]
-    fn create_test_discount_curve(t0: Date) -> YieldCurve {
+    fn create_test_discount_curve(t0: Date) -> DiscountCurve<Date, UnitedStatesCalendar> {
         // Create a treasury discount curve with 8 points (3m, 6m, 1y, 2y, 5y, 10y, 30y).
         // Values from Bloomberg: <https://www.bloomberg.com/markets/rates-bonds/government-bonds/us>
         let rate_vec = vec![0.0544, 0.0556, 0.0546, 0.0514, 0.0481, 0.0481, 0.0494];
@@ -210,7 +215,15 @@ mod tests_bond {
             t0 + Duration::days(30 * 365),
         ];
 
-        DiscountCurve::from_dates_and_rates(&date_vec, &rate_vec)
+        DiscountCurve {
+            curve: Curve::<Date>::new_from_slice(&date_vec, &rate_vec),
+            calendar: None,
+            day_count_convention: None,
+            date_rolling_convention: None,
+            nss: NelsonSiegelSvensson::default(),
+            fitted: false,
+            fitted_curve: None,
+        }
     }
 
     #[test]
@@ -218,6 +231,13 @@ mod tests_bond {
         let today = today();
 
         let mut bond = CouponBond {
+            schedule: Schedule {
+                dates: Vec::new(),
+                day_count_factors: Vec::new(),
+                day_counting_convention: DayCountConvention::default(),
+                date_rolling_convention: DateRollingConvention::Actual,
+            },
+            calendar: UnitedStatesCalendar,
             evaluation_date: today,
             expiration_date: today + Duration::days(365 * 2),
             currency: Some(USD),
@@ -0,0 +1,213 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Credit spread risk of bonds.
+//!
+//! A credit-risky bond is priced off the risk-free discount curve plus a
+//! constant, continuously compounded credit spread $s$:
+//! $$
+//! P(s) = \sum_i c_i \, DF(t_i) \, e^{-s t_i}.
+//! $$
+//! The CS01 is the loss in value when the spread widens by one basis
+//! point, the spread duration is $-\frac{1}{P} \frac{dP}{ds}$, and the
+//! Z-spread is the spread at which $P(s)$ equals the market price.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::StepUpBond;
+use crate::data::CurveModel;
+use crate::math::rootfinding::{
+    brent::Brent,
+    rootfinder::{Rootfinder, RootfinderData},
+};
+use crate::time::DayCountConvention;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// One basis point.
+const BASIS_POINT: f64 = 1e-4;
+
+/// Price of `bond` off `discount_curve` plus the continuously compounded
+/// `credit_spread`.
+#[must_use]
+pub fn spread_price(bond: &StepUpBond, credit_spread: f64, discount_curve: &dyn CurveModel) -> f64 {
+    spread_moments(bond, credit_spread, discount_curve).0
+}
+
+/// CS01: the loss in value of `bond` when `credit_spread` widens by one
+/// basis point, $P(s) - P(s + 0.0001)$.
+#[must_use]
+pub fn cs01(bond: &StepUpBond, credit_spread: f64, discount_curve: &dyn CurveModel) -> f64 {
+    spread_price(bond, credit_spread, discount_curve)
+        - spread_price(bond, credit_spread + BASIS_POINT, discount_curve)
+}
+
+/// Spread duration of `bond`, $-\frac{1}{P} \frac{dP}{ds}$: the present
+/// value weighted average time to the cash flows, discounted at the curve
+/// plus `credit_spread`.
+#[must_use]
+pub fn credit_spread_duration(
+    bond: &StepUpBond,
+    credit_spread: f64,
+    discount_curve: &dyn CurveModel,
+) -> f64 {
+    let (price, weighted_time) = spread_moments(bond, credit_spread, discount_curve);
+
+    weighted_time / price
+}
+
+/// Z-spread of `bond`: the constant spread over `discount_curve` at which
+/// it is worth `market_price`, found with Brent's method on
+/// $[-50\%, 100\%]$.
+#[must_use]
+pub fn z_spread(bond: &StepUpBond, market_price: f64, discount_curve: &dyn CurveModel) -> f64 {
+    let f = |s: f64| spread_price(bond, s, discount_curve) - market_price;
+    let data = RootfinderData::new(1e-12, 0.01, -0.5, 1.0, true);
+
+    Brent::new(f, 0.01, data).solve()
+}
+
+/// Price, and the sum of the present values weighted by their times.
+fn spread_moments(
+    bond: &StepUpBond,
+    credit_spread: f64,
+    discount_curve: &dyn CurveModel,
+) -> (f64, f64) {
+    let evaluation_date = bond.evaluation_date().date();
+    let convention = DayCountConvention::default();
+
+    bond.cash_flows()
+        .iter()
+        .fold((0.0, 0.0), |(price, weighted_time), (date, amount)| {
+            let t = convention.day_count_factor(evaluation_date, date.date());
+            let present_value =
+                amount * discount_curve.discount_factor(date.date()) * (-credit_spread * t).exp();
+
+            (price + present_value, weighted_time + t * present_value)
+        })
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_credit_spread {
+    use super::*;
+    use crate::assert_approx_equal;
+    use time::macros::{date, datetime};
+    use time::Date;
+
+    // Flat continuously compounded curve.
+    struct FlatCurve {
+        rate: f64,
+        reference_date: Date,
+    }
+
+    impl CurveModel for FlatCurve {
+        fn forward_rate(&self, _: Date) -> f64 {
+            self.rate
+        }
+
+        fn spot_rate(&self, _: Date) -> f64 {
+            self.rate
+        }
+
+        fn discount_factor(&self, date: Date) -> f64 {
+            let t = DayCountConvention::default().day_count_factor(self.reference_date, date);
+            (-self.rate * t).exp()
+        }
+    }
+
+    fn curve(rate: f64) -> FlatCurve {
+        FlatCurve {
+            rate,
+            reference_date: date!(2024 - 01 - 01),
+        }
+    }
+
+    /// Five year bond with annual 5% coupons.
+    fn bond() -> StepUpBond {
+        let dates = [
+            datetime!(2025-01-01 0:00 UTC),
+            datetime!(2026-01-01 0:00 UTC),
+            datetime!(2027-01-01 0:00 UTC),
+            datetime!(2028-01-01 0:00 UTC),
+            datetime!(2029-01-01 0:00 UTC),
+        ];
+
+        StepUpBond {
+            evaluation_date: Some(datetime!(2024-01-01 0:00 UTC)),
+            ..StepUpBond::new(
+                100.0.into(),
+                dates.into_iter().map(|d| (d, 0.05)).collect(),
+                dates[4],
+            )
+        }
+    }
+
+    #[test]
+    fn test_spread_is_a_yield_shift_on_a_flat_curve() {
+        // On a flat curve, the spread adds to the yield.
+        let bond = bond();
+        let (rate, spread) = (0.03, 0.02);
+
+        assert_approx_equal!(
+            spread_price(&bond, spread, &curve(rate)),
+            bond.price_from_yield(rate + spread),
+            1e-10
+        );
+        assert_approx_equal!(
+            credit_spread_duration(&bond, spread, &curve(rate)),
+            bond.duration(rate + spread),
+            1e-12
+        );
+        assert_approx_equal!(
+            spread_price(&bond, 0.0, &curve(rate)),
+            bond.price(&curve(rate)),
+            1e-12
+        );
+    }
+
+    #[test]
+    fn test_cs01_matches_spread_duration() {
+        let bond = bond();
+        let (curve, spread) = (curve(0.03), 0.015);
+
+        let price = spread_price(&bond, spread, &curve);
+        let duration = credit_spread_duration(&bond, spread, &curve);
+        let cs01 = cs01(&bond, spread, &curve);
+
+        assert!(cs01 > 0.0);
+        // Up to the convexity term, of order 1e-5.
+        assert_approx_equal!(cs01, price * duration * BASIS_POINT, 1e-4);
+    }
+
+    #[test]
+    fn test_z_spread() {
+        let bond = bond();
+        let curve = curve(0.04);
+
+        // Round trip.
+        for spread in [-0.005, 0.0, 0.01, 0.035] {
+            let price = spread_price(&bond, spread, &curve);
+            assert_approx_equal!(z_spread(&bond, price, &curve), spread, 1e-10);
+        }
+
+        // A 5% bond on a 4% curve is worth more than par without a spread,
+        // so a price below par needs a positive spread.
+        assert!(spread_price(&bond, 0.0, &curve) > 100.0);
+        assert!(z_spread(&bond, 95.0, &curve) > 0.0);
+        assert!(z_spread(&bond, 90.0, &curve) > z_spread(&bond, 95.0, &curve));
+    }
+}
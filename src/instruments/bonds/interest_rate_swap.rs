@@ -0,0 +1,171 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Vanilla (fixed for floating) interest rate swaps, valued off a single
+//! discount curve.
+//!
+//! With fixed leg payment dates $T_1, \dots, T_n$, accrual fractions
+//! $\tau_i$, and discount factors $P(0, T)$, the annuity is
+//! $A = \sum_i \tau_i P(0, T_i)$, the floating leg is worth
+//! $P(0, T_0) - P(0, T_n)$ per unit of notional, and the forward (par)
+//! swap rate is $S = [P(0, T_0) - P(0, T_n)] / A$.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::data::CurveModel;
+use crate::time::DayCountConvention;
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Vanilla interest rate swap, exchanging a fixed rate for a floating
+/// rate on the same notional.
+#[derive(Debug, Clone)]
+pub struct VanillaInterestRateSwap {
+    /// Notional.
+    pub notional: f64,
+    /// Annual fixed rate.
+    pub fixed_rate: f64,
+    /// Start of the first accrual period, $T_0$.
+    pub start_date: OffsetDateTime,
+    /// Fixed leg payment dates, $T_1, \dots, T_n$, in date order.
+    pub payment_dates: Vec<OffsetDateTime>,
+    /// Day count convention of the fixed leg accrual fractions.
+    pub day_count_convention: DayCountConvention,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl VanillaInterestRateSwap {
+    /// New swap, with the default day count convention.
+    #[must_use]
+    pub fn new(
+        notional: f64,
+        fixed_rate: f64,
+        start_date: OffsetDateTime,
+        payment_dates: Vec<OffsetDateTime>,
+    ) -> Self {
+        Self {
+            notional,
+            fixed_rate,
+            start_date,
+            payment_dates,
+            day_count_convention: DayCountConvention::default(),
+        }
+    }
+
+    /// Accrual fraction $\tau_i$ of each fixed leg period.
+    #[must_use]
+    pub fn accrual_fractions(&self) -> Vec<f64> {
+        std::iter::once(&self.start_date)
+            .chain(&self.payment_dates)
+            .zip(&self.payment_dates)
+            .map(|(start, end)| {
+                self.day_count_convention
+                    .day_count_factor(start.date(), end.date())
+            })
+            .collect()
+    }
+
+    /// Annuity $A = \sum_i \tau_i P(0, T_i)$, per unit of notional.
+    #[must_use]
+    pub fn annuity(&self, discount_curve: &dyn CurveModel) -> f64 {
+        self.accrual_fractions()
+            .iter()
+            .zip(&self.payment_dates)
+            .map(|(tau, date)| tau * discount_curve.discount_factor(date.date()))
+            .sum()
+    }
+
+    /// Forward (par) swap rate $S$, at which the swap is worth zero.
+    ///
+    /// # Panics
+    /// Panics if the swap has no payment dates.
+    #[must_use]
+    pub fn forward_swap_rate(&self, discount_curve: &dyn CurveModel) -> f64 {
+        let maturity = self
+            .payment_dates
+            .last()
+            .expect("The swap has no payments.");
+        let floating_leg = discount_curve.discount_factor(self.start_date.date())
+            - discount_curve.discount_factor(maturity.date());
+
+        floating_leg / self.annuity(discount_curve)
+    }
+
+    /// Value to the payer of the fixed rate, $N A (S - K)$.
+    #[must_use]
+    pub fn payer_npv(&self, discount_curve: &dyn CurveModel) -> f64 {
+        let annuity = self.annuity(discount_curve);
+        let swap_rate = self.forward_swap_rate(discount_curve);
+
+        self.notional * annuity * (swap_rate - self.fixed_rate)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_interest_rate_swap {
+    use super::*;
+    use crate::assert_approx_equal;
+    use time::macros::{date, datetime};
+    use time::Date;
+
+    // Flat continuously compounded curve.
+    struct FlatCurve(f64);
+
+    impl CurveModel for FlatCurve {
+        fn forward_rate(&self, _: Date) -> f64 {
+            self.0
+        }
+
+        fn spot_rate(&self, _: Date) -> f64 {
+            self.0
+        }
+
+        fn discount_factor(&self, date: Date) -> f64 {
+            let t = DayCountConvention::default().day_count_factor(date!(2024 - 01 - 01), date);
+            (-self.0 * t).exp()
+        }
+    }
+
+    #[test]
+    fn test_swap_at_par_rate_is_worth_zero() {
+        let dates = vec![
+            datetime!(2026-01-01 0:00 UTC),
+            datetime!(2027-01-01 0:00 UTC),
+            datetime!(2028-01-01 0:00 UTC),
+        ];
+        let curve = FlatCurve(0.03);
+        let mut swap =
+            VanillaInterestRateSwap::new(1_000_000.0, 0.0, datetime!(2025-01-01 0:00 UTC), dates);
+
+        // Annual periods of one year (Actual/Actual), at 1.0, 2.0, 3.0, 4.0.
+        let discount = |t: f64| (-0.03 * t).exp();
+        let annuity = discount(2.0) + discount(3.0) + discount(4.0);
+        assert_approx_equal!(swap.annuity(&curve), annuity, 1e-12);
+        assert_approx_equal!(
+            swap.forward_swap_rate(&curve),
+            (discount(1.0) - discount(4.0)) / annuity,
+            1e-12
+        );
+
+        swap.fixed_rate = swap.forward_swap_rate(&curve);
+        assert_approx_equal!(swap.payer_npv(&curve), 0.0, 1e-6);
+    }
+}
@@ -6,3 +6,51 @@
 //      - LICENSE-APACHE.md
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Brennan-Schwartz two-factor short rate/long rate model.
+pub mod brennan_schwartz;
+pub use brennan_schwartz::*;
+
+/// CIR++ (shifted Cox-Ingersoll-Ross) short rate model.
+pub mod cir_plus_plus;
+pub use cir_plus_plus::*;
+
+/// Futures convexity corrections.
+pub mod convexity_correction;
+pub use convexity_correction::*;
+
+/// Vanilla interest rate swaps.
+pub mod interest_rate_swap;
+pub use interest_rate_swap::*;
+
+/// Credit spread sensitivities (CS01, spread duration) and Z-spreads.
+pub mod credit_spread;
+pub use credit_spread::*;
+
+/// Callable bonds (Hull-White trinomial tree) and option-adjusted spreads.
+pub mod callable_bond;
+pub use callable_bond::*;
+
+/// Convertible bonds (Tsiveriotis-Fernandes).
+pub mod convertible_bond;
+pub use convertible_bond::*;
+
+/// Step-up bonds.
+pub mod step_up_bond;
+pub use step_up_bond::*;
+
+/// European swaptions (Black model).
+pub mod swaption;
+pub use swaption::*;
+
+/// Bond total returns and total return indices.
+pub mod total_return;
+pub use total_return::*;
+
+/// Coupon bonds.
+pub mod coupon_bond;
+pub use coupon_bond::*;
+
+/// Zero-coupon bonds.
+pub mod zero_coupon_bond;
+pub use zero_coupon_bond::*;
@@ -0,0 +1,292 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Step-up bonds: fixed rate bonds whose coupon rate increases over time
+//! according to a schedule.
+//!
+//! Yields are continuously compounded, so for cash flows $c_i$ at times
+//! $t_i$ the price at yield $y$ is $P = \sum_i c_i e^{-y t_i}$, the
+//! (Macaulay and modified) duration is $D = \sum_i t_i c_i e^{-y t_i} / P$,
+//! and the convexity is $C = \sum_i t_i^2 c_i e^{-y t_i} / P$.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::data::CurveModel;
+use crate::math::rootfinding::{
+    brent::Brent,
+    rootfinder::{Rootfinder, RootfinderData},
+};
+use crate::time::DayCountConvention;
+use crate::units::Price;
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Step-up bond.
+///
+/// Each entry `(date, coupon_rate)` of the coupon schedule is a coupon
+/// paid on `date` at the annual `coupon_rate`, accrued since the previous
+/// coupon date. The first coupon period is assumed to be as long as the
+/// second (a regular schedule), or a year if there is a single coupon.
+#[derive(Debug, Clone)]
+pub struct StepUpBond {
+    /// Face value, repaid at maturity.
+    pub face: f64,
+    /// Coupon payment dates and annual coupon rates, in date order.
+    pub coupon_schedule: Vec<(OffsetDateTime, f64)>,
+    /// Maturity date.
+    pub maturity: OffsetDateTime,
+    /// Evaluation date (optional, defaults to now).
+    pub evaluation_date: Option<OffsetDateTime>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl StepUpBond {
+    /// New step-up bond, evaluated now.
+    #[must_use]
+    pub fn new(
+        face: Price,
+        coupon_schedule: Vec<(OffsetDateTime, f64)>,
+        maturity: OffsetDateTime,
+    ) -> Self {
+        Self {
+            face: face.value(),
+            coupon_schedule,
+            maturity,
+            evaluation_date: None,
+        }
+    }
+
+    /// Remaining cash flows, as `(date, amount)` pairs: the coupons and the
+    /// face value paid after the evaluation date.
+    #[must_use]
+    pub fn cash_flows(&self) -> Vec<(OffsetDateTime, f64)> {
+        let convention = DayCountConvention::default();
+        let accrual = |start: OffsetDateTime, end: OffsetDateTime| {
+            convention.day_count_factor(start.date(), end.date())
+        };
+
+        let mut cash_flows: Vec<(OffsetDateTime, f64)> = self
+            .coupon_schedule
+            .iter()
+            .enumerate()
+            .map(|(i, &(date, rate))| {
+                let period = match (i, self.coupon_schedule.get(1)) {
+                    (0, Some(&(next, _))) => accrual(date, next),
+                    (0, None) => 1.0,
+                    _ => accrual(self.coupon_schedule[i - 1].0, date),
+                };
+
+                (date, self.face * rate * period)
+            })
+            .collect();
+        cash_flows.push((self.maturity, self.face));
+
+        let evaluation_date = self.evaluation_date();
+        cash_flows.retain(|(date, _)| *date > evaluation_date);
+        cash_flows
+    }
+
+    /// Price (net present value) off `discount_curve`, which discounts each
+    /// cash flow with its (interpolated) rate for the payment date.
+    #[must_use]
+    pub fn price(&self, discount_curve: &dyn CurveModel) -> f64 {
+        self.cash_flows()
+            .iter()
+            .map(|(date, amount)| amount * discount_curve.discount_factor(date.date()))
+            .sum()
+    }
+
+    /// Price at the continuously compounded yield `ytm`.
+    #[must_use]
+    pub fn price_from_yield(&self, ytm: f64) -> f64 {
+        self.timed_cash_flows()
+            .iter()
+            .map(|(t, amount)| amount * (-ytm * t).exp())
+            .sum()
+    }
+
+    /// Continuously compounded yield to maturity at which the bond is
+    /// worth `price`, found with Brent's method on $[-50\%, 100\%]$.
+    #[must_use]
+    pub fn yield_to_maturity(&self, price: f64) -> f64 {
+        let f = |y: f64| self.price_from_yield(y) - price;
+        let data = RootfinderData::new(1e-12, 0.01, -0.5, 1.0, true);
+
+        Brent::new(f, 0.05, data).solve()
+    }
+
+    /// Duration at the yield `ytm`: the present value weighted average
+    /// time to the cash flows, in years.
+    #[must_use]
+    pub fn duration(&self, ytm: f64) -> f64 {
+        self.yield_moment(ytm, 1)
+    }
+
+    /// Convexity at the yield `ytm`: $\frac{1}{P} \frac{d^2 P}{d y^2}$.
+    #[must_use]
+    pub fn convexity(&self, ytm: f64) -> f64 {
+        self.yield_moment(ytm, 2)
+    }
+
+    pub(crate) fn evaluation_date(&self) -> OffsetDateTime {
+        self.evaluation_date.unwrap_or(OffsetDateTime::now_utc())
+    }
+
+    fn timed_cash_flows(&self) -> Vec<(f64, f64)> {
+        let evaluation_date = self.evaluation_date().date();
+
+        self.cash_flows()
+            .iter()
+            .map(|(date, amount)| {
+                let t =
+                    DayCountConvention::default().day_count_factor(evaluation_date, date.date());
+                (t, *amount)
+            })
+            .collect()
+    }
+
+    // Present value weighted average of t^k.
+    fn yield_moment(&self, ytm: f64, k: i32) -> f64 {
+        let cash_flows = self.timed_cash_flows();
+        let present_values = cash_flows
+            .iter()
+            .map(|(t, amount)| amount * (-ytm * t).exp());
+
+        let price: f64 = present_values.clone().sum();
+        let moment: f64 = present_values
+            .zip(&cash_flows)
+            .map(|(present_value, (t, _))| t.powi(k) * present_value)
+            .sum();
+
+        moment / price
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_step_up_bond {
+    use super::*;
+    use crate::assert_approx_equal;
+    use time::macros::datetime;
+    use time::Date;
+
+    // Flat continuously compounded curve.
+    struct FlatCurve {
+        rate: f64,
+        reference_date: Date,
+    }
+
+    impl CurveModel for FlatCurve {
+        fn forward_rate(&self, _: Date) -> f64 {
+            self.rate
+        }
+
+        fn spot_rate(&self, _: Date) -> f64 {
+            self.rate
+        }
+
+        fn discount_factor(&self, date: Date) -> f64 {
+            let t = DayCountConvention::default().day_count_factor(self.reference_date, date);
+            (-self.rate * t).exp()
+        }
+    }
+
+    fn step_up_bond(rates: [f64; 5]) -> StepUpBond {
+        let dates = [
+            datetime!(2025-01-01 0:00 UTC),
+            datetime!(2026-01-01 0:00 UTC),
+            datetime!(2027-01-01 0:00 UTC),
+            datetime!(2028-01-01 0:00 UTC),
+            datetime!(2029-01-01 0:00 UTC),
+        ];
+
+        StepUpBond {
+            evaluation_date: Some(datetime!(2024-01-01 0:00 UTC)),
+            ..StepUpBond::new(
+                100.0.into(),
+                dates.into_iter().zip(rates).collect(),
+                dates[4],
+            )
+        }
+    }
+
+    #[test]
+    fn test_constant_coupon_special_case() {
+        let bond = step_up_bond([0.05; 5]);
+        let curve = FlatCurve {
+            rate: 0.04,
+            reference_date: Date::from_calendar_date(2024, time::Month::January, 1).unwrap(),
+        };
+
+        // Plain annual 5% bond, with Actual/Actual year fractions.
+        let times: [f64; 5] = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let mut expected: f64 = times.iter().map(|t| 5.0 * (-0.04 * t).exp()).sum();
+        expected += 100.0 * (-0.04 * 5.0_f64).exp();
+
+        assert_approx_equal!(bond.price(&curve), expected, 1e-9);
+        assert_approx_equal!(bond.price_from_yield(0.04), expected, 1e-9);
+    }
+
+    #[test]
+    fn test_step_up_coupons() {
+        let step_up = step_up_bond([0.03, 0.04, 0.05, 0.06, 0.07]);
+        let flat = step_up_bond([0.05; 5]);
+
+        let cash_flows = step_up.cash_flows();
+        assert_eq!(cash_flows.len(), 6);
+        assert_approx_equal!(cash_flows[0].1, 3.0, 1e-12);
+        assert_approx_equal!(cash_flows[4].1, 7.0, 1e-12);
+
+        // Same total coupons, paid later: cheaper, and longer duration.
+        assert!(step_up.price_from_yield(0.04) < flat.price_from_yield(0.04));
+        assert!(step_up.duration(0.04) > flat.duration(0.04));
+    }
+
+    #[test]
+    fn test_yield_to_maturity_inverts_price() {
+        let bond = step_up_bond([0.03, 0.04, 0.05, 0.06, 0.07]);
+
+        for ytm in [-0.01, 0.0, 0.02, 0.05, 0.12] {
+            let price = bond.price_from_yield(ytm);
+            assert_approx_equal!(bond.yield_to_maturity(price), ytm, 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_duration_and_convexity() {
+        let bond = step_up_bond([0.03, 0.04, 0.05, 0.06, 0.07]);
+        let (y, h) = (0.04, 1e-4);
+
+        let price = bond.price_from_yield(y);
+        let (up, down) = (bond.price_from_yield(y + h), bond.price_from_yield(y - h));
+
+        assert_approx_equal!(bond.duration(y), (down - up) / (2.0 * h * price), 1e-6);
+        assert_approx_equal!(
+            bond.convexity(y),
+            (up - 2.0 * price + down) / (h * h * price),
+            1e-4
+        );
+
+        // A zero-coupon bond's duration is its maturity.
+        let zero = step_up_bond([0.0; 5]);
+        assert_approx_equal!(zero.duration(0.04), 5.0, 1e-12);
+        assert_approx_equal!(zero.convexity(0.04), 25.0, 1e-12);
+    }
+}
@@ -0,0 +1,216 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! European swaptions under the Black model.
+//!
+//! With the annuity $A$ of the underlying swap as numeraire, the forward
+//! swap rate $S$ is a martingale, assumed lognormal with volatility
+//! $\sigma$ until the expiry $T$. A payer swaption (the right to pay the
+//! fixed rate $K$) is then a call on $S$, and a receiver swaption a put:
+//!
+//! $$
+//! V_{pay} = N A [S N(d_1) - K N(d_2)], \quad
+//! V_{rec} = N A [K N(-d_2) - S N(-d_1)]
+//! $$
+//!
+//! with $d_{1,2} = [\ln(S/K) \pm \sigma^2 T / 2] / (\sigma \sqrt{T})$, so
+//! $V_{pay} - V_{rec} = N A (S - K)$, the value of the forward payer swap.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::VanillaInterestRateSwap;
+use crate::data::CurveModel;
+use crate::instruments::options::{black_76::black_76_undiscounted, TypeFlag};
+use crate::math::rootfinding::{
+    brent::Brent,
+    rootfinder::{Rootfinder, RootfinderData},
+};
+use crate::time::DayCountConvention;
+use crate::units::Volatility;
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// European swaption: the right to enter the `swap` at its fixed rate on
+/// the `expiry`. `TypeFlag::Call` prices the payer swaption and
+/// `TypeFlag::Put` the receiver swaption.
+#[derive(Debug, Clone)]
+pub struct Swaption {
+    /// Expiry date of the option.
+    pub expiry: OffsetDateTime,
+    /// Underlying (forward starting) swap.
+    pub swap: VanillaInterestRateSwap,
+    /// Black volatility of the forward swap rate.
+    pub vol: f64,
+    /// Evaluation date (optional, defaults to now).
+    pub evaluation_date: Option<OffsetDateTime>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Swaption {
+    /// New swaption, evaluated now.
+    #[must_use]
+    pub fn new(expiry: OffsetDateTime, swap: VanillaInterestRateSwap, vol: Volatility) -> Self {
+        Self {
+            expiry,
+            swap,
+            vol: vol.value(),
+            evaluation_date: None,
+        }
+    }
+
+    /// Time to expiry, in years.
+    #[must_use]
+    pub fn year_fraction(&self) -> f64 {
+        let evaluation_date = self.evaluation_date.unwrap_or(OffsetDateTime::now_utc());
+
+        DayCountConvention::default().day_count_factor(evaluation_date.date(), self.expiry.date())
+    }
+
+    /// Black price off `discount_curve`, which gives the annuity and the
+    /// forward swap rate.
+    #[must_use]
+    pub fn price(&self, discount_curve: &dyn CurveModel, flag: TypeFlag) -> f64 {
+        self.price_with_vol(discount_curve, flag, self.vol)
+    }
+
+    fn price_with_vol(&self, discount_curve: &dyn CurveModel, flag: TypeFlag, vol: f64) -> f64 {
+        let annuity = self.swap.annuity(discount_curve);
+        let swap_rate = self.swap.forward_swap_rate(discount_curve);
+        let T = self.year_fraction();
+
+        self.swap.notional
+            * annuity
+            * black_76_undiscounted(swap_rate, self.swap.fixed_rate, vol, T, flag)
+    }
+}
+
+/// Black volatility at which the `swaption` is worth `price`, found with
+/// Brent's method on $[10^{-6}, 5]$ (its `vol` field is ignored).
+#[must_use]
+pub fn swaption_vol_from_price(
+    price: f64,
+    swaption: &Swaption,
+    discount_curve: &dyn CurveModel,
+    flag: TypeFlag,
+) -> f64 {
+    let f = |vol: f64| swaption.price_with_vol(discount_curve, flag, vol) - price;
+    let data = RootfinderData::new(1e-12, 0.01, 1e-6, 5.0, true);
+
+    Brent::new(f, 0.2, data).solve()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_swaption {
+    use super::*;
+    use crate::assert_approx_equal;
+    use time::macros::{date, datetime};
+    use time::Date;
+
+    // Upward sloping continuously compounded curve, r(t) = 2% + 0.5% t.
+    struct SlopedCurve;
+
+    impl CurveModel for SlopedCurve {
+        fn forward_rate(&self, _: Date) -> f64 {
+            unimplemented!()
+        }
+
+        fn spot_rate(&self, date: Date) -> f64 {
+            let t = DayCountConvention::default().day_count_factor(date!(2024 - 01 - 01), date);
+            0.02 + 0.005 * t
+        }
+
+        fn discount_factor(&self, date: Date) -> f64 {
+            let t = DayCountConvention::default().day_count_factor(date!(2024 - 01 - 01), date);
+            (-self.spot_rate(date) * t).exp()
+        }
+    }
+
+    // A 1y x 4y swaption, evaluated on 2024-01-01.
+    fn swaption(fixed_rate: f64, vol: f64) -> Swaption {
+        let payment_dates = vec![
+            datetime!(2026-01-01 0:00 UTC),
+            datetime!(2027-01-01 0:00 UTC),
+            datetime!(2028-01-01 0:00 UTC),
+            datetime!(2029-01-01 0:00 UTC),
+        ];
+        let swap = VanillaInterestRateSwap::new(
+            1_000_000.0,
+            fixed_rate,
+            datetime!(2025-01-01 0:00 UTC),
+            payment_dates,
+        );
+
+        Swaption {
+            evaluation_date: Some(datetime!(2024-01-01 0:00 UTC)),
+            ..Swaption::new(datetime!(2025-01-01 0:00 UTC), swap, vol.into())
+        }
+    }
+
+    #[test]
+    fn test_receiver_payer_parity() {
+        for fixed_rate in [0.03, 0.05, 0.07] {
+            for vol in [0.05, 0.2, 0.6] {
+                let swaption = swaption(fixed_rate, vol);
+                let payer = swaption.price(&SlopedCurve, TypeFlag::Call);
+                let receiver = swaption.price(&SlopedCurve, TypeFlag::Put);
+
+                assert!(payer > 0.0 && receiver > 0.0);
+                assert_approx_equal!(
+                    payer - receiver,
+                    swaption.swap.payer_npv(&SlopedCurve),
+                    1e-6
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_vol_from_price_inverts_price() {
+        // Strikes close enough to the forward swap rate (about 5.06%) for
+        // the price to depend on the volatility.
+        for fixed_rate in [0.04, 0.05, 0.06] {
+            for vol in [0.1, 0.2, 0.6] {
+                let swaption = swaption(fixed_rate, vol);
+
+                for flag in [TypeFlag::Call, TypeFlag::Put] {
+                    let price = swaption.price(&SlopedCurve, flag);
+                    let implied = swaption_vol_from_price(price, &swaption, &SlopedCurve, flag);
+
+                    assert_approx_equal!(implied, vol, 1e-8);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_zero_vol_is_intrinsic() {
+        let swaption = swaption(0.03, 0.0);
+        let intrinsic = swaption.swap.payer_npv(&SlopedCurve);
+
+        assert!(intrinsic > 0.0);
+        assert_approx_equal!(
+            swaption.price(&SlopedCurve, TypeFlag::Call),
+            intrinsic,
+            1e-6
+        );
+        assert_eq!(swaption.price(&SlopedCurve, TypeFlag::Put), 0.0);
+    }
+}
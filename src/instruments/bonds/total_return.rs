@@ -0,0 +1,229 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Bond total returns over a holding period, with coupon reinvestment, and
+//! total return indices.
+//!
+//! A bond bought at its evaluation date for $P_0$ and held to the horizon
+//! $h$ returns
+//! $$
+//! R = \frac{\sum_{t_i \le h} c_i e^{\rho (h - t_i)} + P_h}{P_0} - 1,
+//! $$
+//! where the cash flows $c_i$ received by the horizon are reinvested at the
+//! continuously compounded rate $\rho$, and the horizon price $P_h$ of the
+//! remaining cash flows is taken at the yield to maturity implied by $P_0$
+//! (an unchanged yield).
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::StepUpBond;
+use crate::time::DayCountConvention;
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Total return of `bond`, bought at its evaluation date for
+/// `purchase_price` and held to `horizon`.
+///
+/// Cash flows received up to and including the horizon are reinvested at
+/// the continuously compounded `reinvestment_rate`, and the bond is sold at
+/// the horizon at its purchase yield to maturity. A fixed coupon bond is a
+/// [`StepUpBond`] with a constant coupon rate.
+///
+/// # Panics
+/// Panics if `purchase_price` is not positive or the horizon is before the
+/// evaluation date.
+#[must_use]
+pub fn total_return(
+    bond: &StepUpBond,
+    purchase_price: f64,
+    horizon: OffsetDateTime,
+    reinvestment_rate: f64,
+) -> f64 {
+    assert!(purchase_price > 0.0, "Purchase price must be positive.");
+
+    let purchase_date = bond.evaluation_date.unwrap_or(OffsetDateTime::now_utc());
+    assert!(
+        horizon >= purchase_date,
+        "Horizon must not be before the purchase date."
+    );
+
+    let convention = DayCountConvention::default();
+    let (received, remaining): (Vec<_>, Vec<_>) = bond
+        .cash_flows()
+        .into_iter()
+        .partition(|(date, _)| *date <= horizon);
+
+    let reinvested: f64 = received
+        .iter()
+        .map(|(date, amount)| {
+            let t = convention.day_count_factor(date.date(), horizon.date());
+            amount * (reinvestment_rate * t).exp()
+        })
+        .sum();
+
+    let horizon_price = if remaining.is_empty() {
+        0.0
+    } else {
+        let ytm = bond.yield_to_maturity(purchase_price);
+
+        StepUpBond {
+            evaluation_date: Some(horizon),
+            ..bond.clone()
+        }
+        .price_from_yield(ytm)
+    };
+
+    (reinvested + horizon_price) / purchase_price - 1.0
+}
+
+/// Total return index of a bond (or bond portfolio), starting at 100.
+///
+/// `prices[i]` is the (dirty) price on `dates[i]`, and `coupons[i]` the
+/// cash paid between `dates[i - 1]` and `dates[i]` (`coupons[0]` is
+/// ignored), reinvested in the bond:
+/// $I_i = I_{i-1} (P_i + C_i) / P_{i-1}$.
+///
+/// # Panics
+/// Panics if the inputs differ in length, the dates are not increasing, or
+/// a price is not positive.
+#[must_use]
+pub fn total_return_index(prices: &[f64], coupons: &[f64], dates: &[OffsetDateTime]) -> Vec<f64> {
+    assert!(
+        prices.len() == coupons.len() && prices.len() == dates.len(),
+        "Prices, coupons, and dates must have the same length."
+    );
+    assert!(
+        dates.windows(2).all(|pair| pair[0] < pair[1]),
+        "Dates must be increasing."
+    );
+    assert!(
+        prices.iter().all(|&price| price > 0.0),
+        "Prices must be positive."
+    );
+
+    let mut index = 100.0;
+
+    prices
+        .iter()
+        .enumerate()
+        .map(|(i, price)| {
+            if i > 0 {
+                index *= (price + coupons[i]) / prices[i - 1];
+            }
+            index
+        })
+        .collect()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_total_return {
+    use super::*;
+    use crate::assert_approx_equal;
+    use time::macros::datetime;
+
+    const PURCHASE: OffsetDateTime = datetime!(2024-01-01 0:00 UTC);
+
+    // Annual coupons from 2025 to 2029.
+    fn bond(coupon_rate: f64) -> StepUpBond {
+        let dates: Vec<OffsetDateTime> = (2025..=2029)
+            .map(|year| PURCHASE.replace_year(year).expect("Valid year."))
+            .collect();
+
+        StepUpBond {
+            evaluation_date: Some(PURCHASE),
+            ..StepUpBond::new(
+                100.0.into(),
+                dates.iter().map(|&date| (date, coupon_rate)).collect(),
+                dates[4],
+            )
+        }
+    }
+
+    #[test]
+    fn test_zero_coupon_total_return() {
+        let zero = bond(0.0);
+        let maturity = zero.maturity;
+
+        for reinvestment_rate in [0.0, 0.03, 0.1] {
+            assert_approx_equal!(
+                total_return(&zero, 80.0, maturity, reinvestment_rate),
+                100.0 / 80.0 - 1.0,
+                1e-12
+            );
+        }
+
+        // Before maturity, a zero-coupon bond earns its yield.
+        let ytm = zero.yield_to_maturity(80.0);
+        let horizon = PURCHASE.replace_year(2026).expect("Valid year.");
+        assert_approx_equal!(
+            total_return(&zero, 80.0, horizon, 0.1),
+            (2.0 * ytm).exp() - 1.0,
+            1e-9
+        );
+    }
+
+    #[test]
+    fn test_coupon_reinvestment() {
+        let bond = bond(0.05);
+        let price = bond.price_from_yield(0.04);
+        let horizon = bond.maturity;
+
+        // Held to maturity, reinvesting at the yield earns the yield.
+        assert_approx_equal!(
+            total_return(&bond, price, horizon, 0.04),
+            (0.04 * 5.0_f64).exp() - 1.0,
+            1e-9
+        );
+
+        // Reinvesting at a higher rate earns more.
+        assert!(
+            total_return(&bond, price, horizon, 0.06) > total_return(&bond, price, horizon, 0.04)
+        );
+
+        // Without reinvestment: coupons plus face.
+        assert_approx_equal!(
+            total_return(&bond, price, horizon, 0.0),
+            125.0 / price - 1.0,
+            1e-12
+        );
+
+        // Horizon on the purchase date: no return.
+        assert_approx_equal!(total_return(&bond, price, PURCHASE, 0.04), 0.0, 1e-9);
+    }
+
+    #[test]
+    fn test_total_return_index() {
+        let dates: Vec<OffsetDateTime> = (0..4)
+            .map(|month| PURCHASE + time::Duration::days(30 * month))
+            .collect();
+        let prices = [100.0, 101.0, 99.0, 102.0];
+        let coupons = [0.0, 0.0, 2.0, 0.0];
+
+        let index = total_return_index(&prices, &coupons, &dates);
+
+        assert_approx_equal!(index[0], 100.0, 1e-12);
+        assert_approx_equal!(index[1], 101.0, 1e-12);
+        // The price drop is offset by the coupon.
+        assert_approx_equal!(index[2], 101.0, 1e-12);
+        assert_approx_equal!(index[3], 101.0 * 102.0 / 99.0, 1e-12);
+
+        // Without coupons the index tracks the price.
+        let index = total_return_index(&prices, &[0.0; 4], &dates);
+        assert_approx_equal!(index[3], 102.0, 1e-12);
+    }
+}
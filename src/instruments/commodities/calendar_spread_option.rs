@@ -0,0 +1,238 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Calendar spread options.
+//!
+//! A calendar spread call pays $\max(F_2 - F_1 - K, 0)$ at expiry, where
+//! $F_1$ and $F_2$ are the prices of a near and a far futures contract,
+//! modelled as correlated driftless geometric Brownian motions.
+//!
+//! There is no closed form for $K \neq 0$. Kirk's approximation treats
+//! $F_1 + K$ as lognormal, which gives a Black-76 style formula with
+//!
+//! $$
+//! \sigma = \sqrt{\sigma_2^2 - 2 \rho \sigma_1 \sigma_2 \frac{F_1}{F_1 + K}
+//!     + \left(\sigma_1 \frac{F_1}{F_1 + K}\right)^2}
+//! $$
+//!
+//! and is exact (Margrabe's exchange option) for $K = 0$.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::FuturesCurve;
+use crate::instruments::options::black_scholes;
+use crate::instruments::TypeFlag;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand_distr::StandardNormal;
+use time::Date;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// European option on the spread between a far and a near futures price.
+#[derive(Debug, Clone, Copy)]
+pub struct CalendarSpreadOption {
+    /// F1 - The near futures price.
+    pub near_price: f64,
+    /// F2 - The far futures price.
+    pub far_price: f64,
+    /// K - The strike on the spread $F_2 - F_1$.
+    pub strike: f64,
+    /// sigma_1 - Volatility of the near futures price.
+    pub near_volatility: f64,
+    /// sigma_2 - Volatility of the far futures price.
+    pub far_volatility: f64,
+    /// rho - Correlation between the two futures prices.
+    pub correlation: f64,
+    /// r - The risk-free rate used for discounting.
+    pub risk_free_rate: f64,
+    /// T - Time to expiry, in years.
+    pub time_to_expiry: f64,
+    /// Call or put on the spread.
+    pub option_type: TypeFlag,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl CalendarSpreadOption {
+    /// Spread option between the contracts delivering on `near` and `far`,
+    /// with prices read off `curve`.
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn from_curve(
+        curve: &FuturesCurve,
+        near: Date,
+        far: Date,
+        strike: f64,
+        volatilities: (f64, f64),
+        correlation: f64,
+        risk_free_rate: f64,
+        expiry: Date,
+        option_type: TypeFlag,
+    ) -> Self {
+        Self {
+            near_price: curve.price(near),
+            far_price: curve.price(far),
+            strike,
+            near_volatility: volatilities.0,
+            far_volatility: volatilities.1,
+            correlation,
+            risk_free_rate,
+            time_to_expiry: curve.year_fraction(expiry),
+            option_type,
+        }
+    }
+
+    /// Price with Kirk's approximation.
+    ///
+    /// # Panics
+    /// Panics if $F_1 + K$ is not positive.
+    #[must_use]
+    pub fn price_kirk(&self) -> f64 {
+        let (f1, f2, k, t) = (
+            self.near_price,
+            self.far_price,
+            self.strike,
+            self.time_to_expiry,
+        );
+        assert!(f1 + k > 0.0, "Kirk's approximation requires F1 + K > 0.");
+
+        let (s1, s2, rho) = (self.near_volatility, self.far_volatility, self.correlation);
+        let weight = f1 / (f1 + k);
+        let v = (s2 * s2 - 2.0 * rho * s1 * s2 * weight + (s1 * weight).powi(2)).sqrt();
+
+        // Black (1976) on the far future with strike F1 + K.
+        let r = self.risk_free_rate;
+        black_scholes::price(f2, f1 + k, r, r, v, t, self.option_type)
+    }
+
+    /// Monte Carlo price from `n_paths` joint draws of the two futures
+    /// prices at expiry, and its standard error.
+    ///
+    /// # Panics
+    /// Panics if `n_paths` is less than two.
+    #[must_use]
+    pub fn price_monte_carlo(&self, n_paths: usize, seed: u64) -> (f64, f64) {
+        assert!(n_paths > 1, "At least two paths are required.");
+
+        let mut rng = StdRng::seed_from_u64(seed);
+        let t = self.time_to_expiry;
+        let (s1, s2, rho) = (self.near_volatility, self.far_volatility, self.correlation);
+        let df = (-self.risk_free_rate * t).exp();
+
+        let payoffs: Vec<f64> = (0..n_paths)
+            .map(|_| {
+                let z1: f64 = rng.sample(StandardNormal);
+                let z2 = rho * z1 + (1.0 - rho * rho).sqrt() * rng.sample::<f64, _>(StandardNormal);
+
+                let f1 = self.near_price * (s1 * t.sqrt() * z1 - 0.5 * s1 * s1 * t).exp();
+                let f2 = self.far_price * (s2 * t.sqrt() * z2 - 0.5 * s2 * s2 * t).exp();
+                let spread = f2 - f1 - self.strike;
+
+                df * match self.option_type {
+                    TypeFlag::Call => spread.max(0.0),
+                    TypeFlag::Put => (-spread).max(0.0),
+                }
+            })
+            .collect();
+
+        let n = n_paths as f64;
+        let mean = payoffs.iter().sum::<f64>() / n;
+        let variance = payoffs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+
+        (mean, (variance / n).sqrt())
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_calendar_spread_option {
+    use super::*;
+    use crate::assert_approx_equal;
+    use time::macros::date;
+
+    fn option(strike: f64, risk_free_rate: f64, option_type: TypeFlag) -> CalendarSpreadOption {
+        CalendarSpreadOption {
+            near_price: 80.0,
+            far_price: 84.0,
+            strike,
+            near_volatility: 0.35,
+            far_volatility: 0.3,
+            correlation: 0.9,
+            risk_free_rate,
+            time_to_expiry: 0.5,
+            option_type,
+        }
+    }
+
+    #[test]
+    fn test_zero_strike_is_exchange_option() {
+        let spread = option(0.0, 0.0, TypeFlag::Call);
+
+        // Margrabe's formula.
+        let (f1, f2, t) = (80.0_f64, 84.0_f64, 0.5_f64);
+        let v = (0.35_f64.powi(2) + 0.3_f64.powi(2) - 2.0 * 0.9 * 0.35 * 0.3).sqrt();
+        let margrabe = black_scholes::call_price(f2, f1, 0.0, 0.0, v, t);
+
+        assert_approx_equal!(spread.price_kirk(), margrabe, 1e-12);
+
+        let (mc, error) = spread.price_monte_carlo(200_000, 176);
+        assert!((mc - margrabe).abs() < 3.0 * error, "{mc} vs {margrabe}");
+    }
+
+    #[test]
+    fn test_kirk_against_monte_carlo() {
+        for option_type in [TypeFlag::Call, TypeFlag::Put] {
+            let spread = option(3.0, 0.04, option_type);
+            let (mc, error) = spread.price_monte_carlo(200_000, 7);
+
+            assert!((spread.price_kirk() - mc).abs() < 4.0 * error + 0.01);
+        }
+
+        // Put-call parity on the spread.
+        let (call, put) = (
+            option(3.0, 0.04, TypeFlag::Call).price_kirk(),
+            option(3.0, 0.04, TypeFlag::Put).price_kirk(),
+        );
+        assert_approx_equal!(call - put, (-0.02_f64).exp() * (84.0 - 80.0 - 3.0), 1e-12);
+    }
+
+    #[test]
+    fn test_from_curve() {
+        let curve = FuturesCurve::new(
+            date!(2024 - 01 - 02),
+            &[(date!(2024 - 07 - 15), 80.0), (date!(2024 - 12 - 15), 84.0)],
+        )
+        .unwrap();
+
+        let spread = CalendarSpreadOption::from_curve(
+            &curve,
+            date!(2024 - 07 - 15),
+            date!(2024 - 12 - 15),
+            3.0,
+            (0.35, 0.3),
+            0.9,
+            0.04,
+            date!(2024 - 07 - 01),
+            TypeFlag::Call,
+        );
+
+        assert_approx_equal!(spread.near_price, 80.0, 1e-12);
+        assert_approx_equal!(spread.far_price, 84.0, 1e-12);
+        assert_approx_equal!(spread.time_to_expiry, 181.0 / 365.0, 1e-12);
+    }
+}
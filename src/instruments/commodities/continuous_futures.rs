@@ -0,0 +1,251 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Continuous futures series for backtests.
+//!
+//! A continuous series follows the front contract and rolls into the next
+//! one according to a roll rule. Splicing raw prices introduces artificial
+//! jumps at each roll (the spread between the two contracts), so the
+//! history before each roll is usually back-adjusted: shifted by the
+//! difference, or scaled by the ratio, of the two contracts' prices on the
+//! roll date. Ratio adjustment keeps returns unchanged (and prices
+//! positive), so it is the natural choice for return-based backtests.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use std::collections::{BTreeMap, BTreeSet};
+use time::{Date, Duration};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Daily history of a single futures contract.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuturesContractHistory {
+    /// Last trading date of the contract.
+    pub last_trade_date: Date,
+    /// `(price, open interest)` on each trading date.
+    pub observations: BTreeMap<Date, (f64, f64)>,
+}
+
+/// When to roll from the front contract into the next one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RollRule {
+    /// Roll the given number of calendar days before the front contract's
+    /// last trading date.
+    DaysBeforeLastTrade(i64),
+    /// Roll once the next contract's open interest exceeds the front's.
+    OpenInterest,
+}
+
+/// Back-adjustment of the history before each roll.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RollAdjustment {
+    /// Splice raw prices.
+    None,
+    /// Shift earlier prices by the price difference on the roll date.
+    Difference,
+    /// Scale earlier prices by the price ratio on the roll date.
+    Ratio,
+}
+
+/// Continuous futures series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ContinuousFuturesSeries {
+    /// Trading dates.
+    pub dates: Vec<Date>,
+    /// Adjusted prices.
+    pub prices: Vec<f64>,
+    /// Index of the contract followed on each date.
+    pub contracts: Vec<usize>,
+    /// Dates on which the series rolled into the next contract.
+    pub roll_dates: Vec<Date>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Continuous series through `contracts` (ordered by last trading date).
+///
+/// The series rolls into the next contract on the first date the roll rule
+/// is met (or the front contract has expired) and the next contract
+/// trades. Dates on which the followed contract doesn't trade are skipped.
+#[must_use]
+pub fn continuous_futures_series(
+    contracts: &[FuturesContractHistory],
+    rule: RollRule,
+    adjustment: RollAdjustment,
+) -> ContinuousFuturesSeries {
+    let dates: BTreeSet<Date> = contracts
+        .iter()
+        .flat_map(|contract| contract.observations.keys().copied())
+        .collect();
+
+    let mut series = ContinuousFuturesSeries {
+        dates: Vec::new(),
+        prices: Vec::new(),
+        contracts: Vec::new(),
+        roll_dates: Vec::new(),
+    };
+    // Adjustment at each roll: (index of the first date after it, factor).
+    let mut rolls = Vec::new();
+    let mut front = 0;
+
+    for date in dates {
+        while let (Some(current), Some(next)) = (contracts.get(front), contracts.get(front + 1)) {
+            let Some(&(next_price, next_open_interest)) = next.observations.get(&date) else {
+                break;
+            };
+            let current = current.observations.get(&date).copied();
+
+            let roll = date > contracts[front].last_trade_date
+                || match rule {
+                    RollRule::DaysBeforeLastTrade(days) => {
+                        date >= contracts[front].last_trade_date - Duration::days(days)
+                    }
+                    RollRule::OpenInterest => {
+                        current.is_none_or(|(_, open_interest)| next_open_interest > open_interest)
+                    }
+                };
+            if !roll {
+                break;
+            }
+
+            let factor = match (adjustment, current) {
+                (RollAdjustment::Difference, Some((price, _))) => next_price - price,
+                (RollAdjustment::Ratio, Some((price, _))) => next_price / price,
+                (RollAdjustment::Ratio, None) => 1.0,
+                _ => 0.0,
+            };
+            rolls.push((series.dates.len(), factor));
+            series.roll_dates.push(date);
+            front += 1;
+        }
+
+        if let Some(&(price, _)) = contracts
+            .get(front)
+            .and_then(|contract| contract.observations.get(&date))
+        {
+            series.dates.push(date);
+            series.prices.push(price);
+            series.contracts.push(front);
+        }
+    }
+
+    // Back-adjust the history before each roll.
+    for (end, factor) in rolls {
+        for price in &mut series.prices[..end] {
+            match adjustment {
+                RollAdjustment::None => {}
+                RollAdjustment::Difference => *price += factor,
+                RollAdjustment::Ratio => *price *= factor,
+            }
+        }
+    }
+
+    series
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_continuous_futures {
+    use super::*;
+    use crate::assert_approx_equal;
+    use time::macros::date;
+
+    fn spot(day: i64) -> f64 {
+        100.0 * (1.0 + 0.01 * (day as f64 * 0.7).sin())
+    }
+
+    // Two contracts on a common spot path, the second at a 5% premium,
+    // with open interest migrating from the first to the second.
+    fn contracts() -> Vec<FuturesContractHistory> {
+        let start = date!(2024 - 01 - 01);
+        let history = |premium: f64, first: i64, last: i64, open_interest: fn(i64) -> f64| {
+            (first..=last)
+                .map(|day| {
+                    (
+                        start + Duration::days(day),
+                        (premium * spot(day), open_interest(day)),
+                    )
+                })
+                .collect()
+        };
+
+        vec![
+            FuturesContractHistory {
+                last_trade_date: start + Duration::days(40),
+                observations: history(1.0, 0, 40, |day| (1_000 - 20 * day) as f64),
+            },
+            FuturesContractHistory {
+                last_trade_date: start + Duration::days(80),
+                observations: history(1.05, 10, 80, |day| (100 + 20 * day) as f64),
+            },
+        ]
+    }
+
+    fn returns(prices: &[f64]) -> Vec<f64> {
+        prices.windows(2).map(|pair| pair[1] / pair[0]).collect()
+    }
+
+    #[test]
+    fn test_ratio_adjustment_has_no_jump() {
+        let contracts = contracts();
+        let series = continuous_futures_series(
+            &contracts,
+            RollRule::DaysBeforeLastTrade(5),
+            RollAdjustment::Ratio,
+        );
+
+        assert_eq!(series.roll_dates, vec![date!(2024 - 02 - 05)]);
+        assert_eq!(series.dates.len(), 81);
+
+        // Every return is a return of the underlying spot path.
+        let spot: Vec<f64> = (0..=80).map(spot).collect();
+        for (adjusted, raw) in returns(&series.prices).iter().zip(returns(&spot)) {
+            assert_approx_equal!(*adjusted, raw, 1e-12);
+        }
+
+        // Without adjustment the roll shows up as a 5% jump.
+        let raw = continuous_futures_series(
+            &contracts,
+            RollRule::DaysBeforeLastTrade(5),
+            RollAdjustment::None,
+        );
+        let roll = raw.contracts.iter().position(|&c| c == 1).unwrap();
+        let jump = raw.prices[roll] / raw.prices[roll - 1];
+        assert!(jump > 1.03);
+        assert_approx_equal!(series.prices[80], raw.prices[80], 1e-12);
+    }
+
+    #[test]
+    fn test_open_interest_roll() {
+        let series = continuous_futures_series(
+            &contracts(),
+            RollRule::OpenInterest,
+            RollAdjustment::Difference,
+        );
+
+        // Open interest crosses on day 23: 1000 - 460 < 100 + 460.
+        assert_eq!(series.roll_dates, vec![date!(2024 - 01 - 24)]);
+
+        // The difference adjustment removes the price gap on the roll date.
+        let roll = series.contracts.iter().position(|&c| c == 1).unwrap();
+        let gap = series.prices[roll] - series.prices[roll - 1];
+        let spot_move = 100.0 * 0.01 * ((23.0_f64 * 0.7).sin() - (22.0_f64 * 0.7).sin());
+        assert_approx_equal!(gap, spot_move, 1e-9);
+    }
+}
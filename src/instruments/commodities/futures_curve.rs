@@ -0,0 +1,218 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Commodity futures curves.
+//!
+//! A futures curve is built from a strip of futures quotes, one per
+//! contract (delivery) month. Prices between contracts are interpolated
+//! linearly in log price against time to delivery, and held flat beyond
+//! the first and last contracts, giving a continuous forward curve.
+//!
+//! Many commodities (natural gas, power, agricultural products) have
+//! seasonal forward curves. With monthly seasonal factors, the
+//! deseasonalised prices $F(T) / s_{m(T)}$ are interpolated instead, so the
+//! seasonal shape is kept between contracts.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use time::{Date, Duration};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Futures curve built from a strip of futures quotes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuturesCurve {
+    valuation_date: Date,
+    /// `(delivery date, price)` of each contract, ordered by delivery.
+    contracts: Vec<(Date, f64)>,
+    /// Multiplicative seasonal factor of each calendar month.
+    seasonality: Option<[f64; 12]>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl FuturesCurve {
+    /// New futures curve from `(delivery date, price)` quotes.
+    ///
+    /// # Errors
+    /// - `MissingInput` if there are no quotes.
+    /// - `InvalidArgument` if a price is not positive, a delivery date is
+    ///   before the valuation date, or two quotes have the same delivery.
+    pub fn new(valuation_date: Date, quotes: &[(Date, f64)]) -> Result<Self, RustQuantError> {
+        if quotes.is_empty() {
+            return Err(RustQuantError::MissingInput(
+                "At least one futures quote is required.".to_string(),
+            ));
+        }
+
+        let mut contracts = quotes.to_vec();
+        contracts.sort_by_key(|(delivery, _)| *delivery);
+
+        if contracts
+            .iter()
+            .any(|&(delivery, price)| price <= 0.0 || delivery < valuation_date)
+        {
+            return Err(RustQuantError::InvalidArgument(
+                "Futures prices must be positive, with delivery on or after the valuation date."
+                    .to_string(),
+            ));
+        }
+        if contracts.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+            return Err(RustQuantError::InvalidArgument(
+                "Each contract must have a distinct delivery date.".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            valuation_date,
+            contracts,
+            seasonality: None,
+        })
+    }
+
+    /// Interpolates with the (positive) multiplicative seasonal factors of
+    /// each calendar month, January first.
+    #[must_use]
+    pub fn with_seasonality(mut self, factors: [f64; 12]) -> Self {
+        self.seasonality = Some(factors);
+        self
+    }
+
+    /// Valuation date of the curve.
+    #[must_use]
+    pub fn valuation_date(&self) -> Date {
+        self.valuation_date
+    }
+
+    /// `(delivery date, price)` of each contract, ordered by delivery.
+    #[must_use]
+    pub fn contracts(&self) -> &[(Date, f64)] {
+        &self.contracts
+    }
+
+    /// Time from the valuation date to `date`, in years (Actual/365).
+    #[must_use]
+    pub fn year_fraction(&self, date: Date) -> f64 {
+        (date - self.valuation_date).whole_days() as f64 / 365.0
+    }
+
+    /// Futures price for delivery on `date`.
+    #[must_use]
+    pub fn price(&self, date: Date) -> f64 {
+        let log_price =
+            |(delivery, price): (Date, f64)| (price / self.seasonal_factor(delivery)).ln();
+
+        let index = self
+            .contracts
+            .partition_point(|(delivery, _)| *delivery <= date);
+        let deseasonalised = if index == 0 {
+            log_price(self.contracts[0])
+        } else if index == self.contracts.len() {
+            log_price(self.contracts[index - 1])
+        } else {
+            let (left, right) = (self.contracts[index - 1], self.contracts[index]);
+            let weight =
+                (date - left.0).whole_days() as f64 / (right.0 - left.0).whole_days() as f64;
+
+            (1.0 - weight) * log_price(left) + weight * log_price(right)
+        };
+
+        deseasonalised.exp() * self.seasonal_factor(date)
+    }
+
+    /// Forward price for delivery at time `t` (in years).
+    #[must_use]
+    pub fn forward(&self, t: f64) -> f64 {
+        self.price(self.valuation_date + Duration::days((t * 365.0).round() as i64))
+    }
+
+    fn seasonal_factor(&self, date: Date) -> f64 {
+        self.seasonality
+            .map_or(1.0, |factors| factors[date.month() as usize - 1])
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_futures_curve {
+    use super::*;
+    use crate::assert_approx_equal;
+    use time::macros::date;
+
+    fn quotes() -> Vec<(Date, f64)> {
+        vec![
+            (date!(2024 - 03 - 15), 82.0),
+            (date!(2024 - 01 - 15), 80.0),
+            (date!(2024 - 06 - 15), 85.0),
+        ]
+    }
+
+    #[test]
+    fn test_interpolation_by_contract() {
+        let curve = FuturesCurve::new(date!(2024 - 01 - 02), &quotes()).unwrap();
+
+        assert_eq!(curve.contracts()[0].1, 80.0);
+        assert_approx_equal!(curve.price(date!(2024 - 03 - 15)), 82.0, 1e-12);
+
+        // Flat before the first and after the last contract.
+        assert_approx_equal!(curve.price(date!(2024 - 01 - 05)), 80.0, 1e-12);
+        assert_approx_equal!(curve.price(date!(2025 - 01 - 01)), 85.0, 1e-12);
+
+        // Log-linear in between.
+        let midpoint = curve.price(date!(2024 - 02 - 14));
+        assert_approx_equal!(midpoint, (80.0_f64 * 82.0).sqrt(), 1e-9);
+
+        assert_approx_equal!(
+            curve.forward(curve.year_fraction(date!(2024 - 06 - 15))),
+            85.0,
+            1e-12
+        );
+    }
+
+    #[test]
+    fn test_seasonal_interpolation() {
+        // Winter premium for natural gas.
+        let mut factors = [1.0; 12];
+        factors[1] = 1.2;
+
+        let quotes = [(date!(2024 - 01 - 15), 3.0), (date!(2024 - 03 - 15), 3.0)];
+        let plain = FuturesCurve::new(date!(2024 - 01 - 02), &quotes).unwrap();
+        let seasonal = plain.clone().with_seasonality(factors);
+
+        assert_approx_equal!(plain.price(date!(2024 - 02 - 15)), 3.0, 1e-12);
+        assert_approx_equal!(seasonal.price(date!(2024 - 02 - 15)), 3.6, 1e-12);
+
+        // Quoted contracts are still repriced.
+        assert_approx_equal!(seasonal.price(date!(2024 - 03 - 15)), 3.0, 1e-12);
+    }
+
+    #[test]
+    fn test_invalid_quotes() {
+        let valuation_date = date!(2024 - 01 - 02);
+
+        assert!(FuturesCurve::new(valuation_date, &[]).is_err());
+        assert!(FuturesCurve::new(valuation_date, &[(date!(2024 - 03 - 15), -1.0)]).is_err());
+        assert!(FuturesCurve::new(valuation_date, &[(date!(2023 - 12 - 15), 80.0)]).is_err());
+        assert!(FuturesCurve::new(
+            valuation_date,
+            &[(date!(2024 - 03 - 15), 80.0), (date!(2024 - 03 - 15), 81.0)]
+        )
+        .is_err());
+    }
+}
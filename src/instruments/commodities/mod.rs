@@ -6,3 +6,15 @@
 //      - LICENSE-APACHE.md
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Calendar spread options on futures.
+pub mod calendar_spread_option;
+pub use calendar_spread_option::*;
+
+/// Continuous futures series with roll rules.
+pub mod continuous_futures;
+pub use continuous_futures::*;
+
+/// Commodity futures curves.
+pub mod futures_curve;
+pub use futures_curve::*;
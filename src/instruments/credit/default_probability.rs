@@ -0,0 +1,255 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Default probability term structures bootstrapped from CDS spreads.
+//!
+//! The hazard rate is piecewise constant between the CDS maturities
+//! $T_1 < \dots < T_n$, so the survival probability is
+//! $Q(t) = \exp(-\sum_i \lambda_i \, |(T_{i-1}, T_i] \cap (0, t]|)$.
+//! The bootstrap solves for $\lambda_1, \lambda_2, \dots$ in turn, each one
+//! making the CDS of that maturity price at its quoted par spread given the
+//! hazard rates already found.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{CreditDefaultSwap, HazardRateCurve};
+use crate::data::PiecewiseCurve;
+use crate::error::RustQuantError;
+use crate::math::rootfinding::{
+    brent::Brent,
+    rootfinder::{Rootfinder, RootfinderData},
+};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Hazard rate curve, constant between its knots and flat after the last
+/// one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PiecewiseConstantHazardCurve {
+    /// Right end of each segment (year fractions), increasing.
+    times: Vec<f64>,
+    /// Hazard rate on each segment.
+    hazard_rates: Vec<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl PiecewiseConstantHazardCurve {
+    /// New curve with hazard rate `hazard_rates[i]` up to `times[i]`.
+    ///
+    /// # Errors
+    /// `InvalidArgument` if the times are not positive and strictly
+    /// increasing, the lengths differ, or a hazard rate is negative.
+    pub fn new(times: &[f64], hazard_rates: &[f64]) -> Result<Self, RustQuantError> {
+        if times.is_empty() || times.len() != hazard_rates.len() {
+            return Err(RustQuantError::InvalidArgument(
+                "Times and hazard rates must be non-empty and of equal length.".to_string(),
+            ));
+        }
+        if times[0] <= 0.0 || times.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(RustQuantError::InvalidArgument(
+                "Times must be positive and strictly increasing.".to_string(),
+            ));
+        }
+        if hazard_rates.iter().any(|h| h.is_nan() || *h < 0.0) {
+            return Err(RustQuantError::InvalidArgument(
+                "Hazard rates must be non-negative.".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            times: times.to_vec(),
+            hazard_rates: hazard_rates.to_vec(),
+        })
+    }
+
+    /// Right end of each segment.
+    #[must_use]
+    pub fn times(&self) -> &[f64] {
+        &self.times
+    }
+
+    /// Hazard rate on each segment.
+    #[must_use]
+    pub fn hazard_rates(&self) -> &[f64] {
+        &self.hazard_rates
+    }
+
+    /// Cumulative hazard $\int_0^t \lambda(u) \, du$.
+    fn cumulative_hazard(&self, t: f64) -> f64 {
+        let mut start = 0.0;
+        let mut total = 0.0;
+
+        for (end, rate) in self.times.iter().zip(&self.hazard_rates) {
+            if t <= *end {
+                return total + rate * (t - start).max(0.0);
+            }
+            total += rate * (end - start);
+            start = *end;
+        }
+
+        total + self.hazard_rates[self.hazard_rates.len() - 1] * (t - start)
+    }
+}
+
+impl HazardRateCurve for PiecewiseConstantHazardCurve {
+    fn hazard_rate(&self, t: f64) -> f64 {
+        let i = self.times.partition_point(|end| *end < t);
+
+        self.hazard_rates[i.min(self.hazard_rates.len() - 1)]
+    }
+
+    fn survival_probability(&self, t: f64) -> f64 {
+        (-self.cumulative_hazard(t)).exp()
+    }
+}
+
+/// Bootstraps a [`PiecewiseConstantHazardCurve`] from par spreads of
+/// quarterly-paying CDS with the given maturities (year fractions) and
+/// recovery rate, discounting off `discount_curve`. Each hazard rate is
+/// found with Brent's method on $[0, 10]$.
+///
+/// # Panics
+/// Panics if the maturities are not positive and strictly increasing,
+/// there is not one spread per maturity, a spread is negative, or the
+/// recovery rate is not in $[0, 1)$.
+#[must_use]
+pub fn bootstrap_hazard_rates(
+    maturities: &[f64],
+    cds_spreads: &[f64],
+    recovery_rate: f64,
+    discount_curve: &PiecewiseCurve,
+) -> PiecewiseConstantHazardCurve {
+    assert_eq!(
+        maturities.len(),
+        cds_spreads.len(),
+        "There must be one CDS spread per maturity."
+    );
+    assert!(
+        cds_spreads.iter().all(|s| *s >= 0.0),
+        "CDS spreads must be non-negative."
+    );
+
+    let mut hazard_rates = Vec::with_capacity(maturities.len());
+
+    for (i, (maturity, spread)) in maturities.iter().zip(cds_spreads).enumerate() {
+        let cds = CreditDefaultSwap::new(*maturity, recovery_rate);
+
+        let curve_with = |hazard_rate: f64| {
+            let rates: Vec<f64> = hazard_rates
+                .iter()
+                .copied()
+                .chain(std::iter::once(hazard_rate))
+                .collect();
+
+            PiecewiseConstantHazardCurve::new(&maturities[..=i], &rates)
+                .expect("CDS maturities must be positive and strictly increasing.")
+        };
+
+        // The NPV per unit annuity is monotone in the hazard rate.
+        let f = |hazard_rate: f64| cds.npv(*spread, &curve_with(hazard_rate), discount_curve);
+        let data = RootfinderData::new(1e-14, 0.01, 0.0, 10.0, true);
+        let guess = spread / (1.0 - recovery_rate);
+
+        hazard_rates.push(Brent::new(f, guess, data).solve().max(0.0));
+    }
+
+    PiecewiseConstantHazardCurve {
+        times: maturities.to_vec(),
+        hazard_rates,
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_default_probability {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::data::CurveInterpolation;
+
+    const BASIS_POINT: f64 = 1e-4;
+
+    fn discount_curve() -> PiecewiseCurve {
+        PiecewiseCurve::new(
+            &[1.0, 3.0, 10.0],
+            &[-0.03, -0.035 * 3.0, -0.04 * 10.0],
+            CurveInterpolation::LogLinear,
+            vec![],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_survival_probability() {
+        let curve = PiecewiseConstantHazardCurve::new(&[1.0, 3.0], &[0.01, 0.03]).unwrap();
+
+        assert_eq!(curve.survival_probability(0.0), 1.0);
+        assert_approx_equal!(curve.survival_probability(0.5), (-0.005_f64).exp(), 1e-15);
+        assert_approx_equal!(curve.survival_probability(2.0), (-0.04_f64).exp(), 1e-15);
+        assert_approx_equal!(curve.survival_probability(4.0), (-0.10_f64).exp(), 1e-15);
+        assert_approx_equal!(
+            curve.default_probability(2.0),
+            1.0 - (-0.04_f64).exp(),
+            1e-15
+        );
+
+        assert_eq!(curve.hazard_rate(1.0), 0.01);
+        assert_eq!(curve.hazard_rate(1.5), 0.03);
+        assert_eq!(curve.hazard_rate(5.0), 0.03);
+
+        assert!(PiecewiseConstantHazardCurve::new(&[1.0, 1.0], &[0.01, 0.02]).is_err());
+        assert!(PiecewiseConstantHazardCurve::new(&[1.0], &[-0.01]).is_err());
+    }
+
+    #[test]
+    fn test_bootstrap_reprices_spreads() {
+        let maturities = [0.5, 1.0, 2.0, 3.0, 5.0, 7.0, 10.0];
+        let spreads = [45.0, 52.0, 68.0, 85.0, 110.0, 130.0, 150.0].map(|s| s * BASIS_POINT);
+        let discount_curve = discount_curve();
+
+        let curve = bootstrap_hazard_rates(&maturities, &spreads, 0.4, &discount_curve);
+
+        for (maturity, spread) in maturities.iter().zip(spreads) {
+            let cds = CreditDefaultSwap::new(*maturity, 0.4);
+            let residual = cds.par_spread(&curve, &discount_curve) - spread;
+
+            assert!(
+                residual.abs() < 0.1 * BASIS_POINT,
+                "{maturity}y CDS reprices {residual} away from its spread."
+            );
+        }
+
+        // Steepening spreads imply increasing forward hazard rates.
+        assert!(curve.hazard_rates().windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[test]
+    fn test_flat_spreads_give_flat_hazard_rate() {
+        let maturities = [1.0, 3.0, 5.0];
+        let spreads = [0.012; 3];
+
+        let curve = bootstrap_hazard_rates(&maturities, &spreads, 0.4, &discount_curve());
+
+        for hazard_rate in curve.hazard_rates() {
+            // Credit triangle: lambda = s / (1 - R), up to the quarterly
+            // timing of the premiums.
+            assert_approx_equal!(*hazard_rate, 0.02, 2e-4);
+            assert_approx_equal!(*hazard_rate, curve.hazard_rates()[0], 5e-5);
+        }
+    }
+}
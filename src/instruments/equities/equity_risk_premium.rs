@@ -0,0 +1,234 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Equity risk premium estimators: implied by dividend discount models, or
+//! historical excess returns.
+//!
+//! References:
+//!     - Damodaran, A. (2024). Equity Risk Premiums (ERP): Determinants,
+//!       Estimation, and Implications.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::math::rootfinding::{
+    brent::Brent,
+    rootfinder::{Rootfinder, RootfinderData},
+};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Number of years the cash flows grow at the expected earnings growth rate
+/// in [`implied_equity_premium`], before growing at the risk-free rate.
+pub const HIGH_GROWTH_YEARS: i32 = 5;
+
+/// Equity risk premium estimated from historical returns.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistoricalEquityPremium {
+    /// Arithmetic mean of the excess returns.
+    pub arithmetic: f64,
+    /// Geometric mean equity return less the geometric mean risk-free return.
+    pub geometric: f64,
+    /// Standard error of the arithmetic mean.
+    pub standard_error: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Cost of equity implied by the Gordon growth model, $D_1 / P + g$, from
+/// next year's `dividend` $D_1$, its perpetual `growth_rate` $g$, and the
+/// `price` $P$.
+#[must_use]
+pub fn gordon_growth_model(dividend: f64, growth_rate: f64, price: f64) -> f64 {
+    dividend / price + growth_rate
+}
+
+/// Price in the Gordon growth model, $D_1 / (k - g)$, from next year's
+/// `dividend` $D_1$, its perpetual `growth_rate` $g$, and the
+/// `cost_of_equity` $k > g$.
+#[must_use]
+pub fn gordon_growth_price(dividend: f64, growth_rate: f64, cost_of_equity: f64) -> f64 {
+    dividend / (cost_of_equity - growth_rate)
+}
+
+/// Implied equity risk premium of an index (Damodaran's two-stage model).
+///
+/// The cash returned to investors is the average over the given years of
+/// the `dividends` plus the `buybacks` (e.g. trailing twelve months, or
+/// the last ten years to normalize it). It grows at `earnings_growth` for
+/// [`HIGH_GROWTH_YEARS`] years, and at the risk-free rate afterwards. The
+/// expected return $r$ that discounts these cash flows to `index_price` is
+/// found with Brent's method, and the premium is $r$ less `risk_free`.
+///
+/// # Panics
+/// Panics if `dividends` is empty, or `buybacks` has another length.
+#[must_use]
+pub fn implied_equity_premium(
+    index_price: f64,
+    dividends: Vec<f64>,
+    buybacks: Vec<f64>,
+    earnings_growth: f64,
+    risk_free: f64,
+) -> f64 {
+    assert!(
+        !dividends.is_empty() && dividends.len() == buybacks.len(),
+        "Expected one buyback amount per dividend."
+    );
+
+    let cash_yield = dividends
+        .iter()
+        .zip(&buybacks)
+        .map(|(d, b)| d + b)
+        .sum::<f64>()
+        / dividends.len() as f64;
+
+    let present_value = |r: f64| {
+        let mut cash_flow = cash_yield;
+        let mut value = 0.0;
+
+        for t in 1..=HIGH_GROWTH_YEARS {
+            cash_flow *= 1.0 + earnings_growth;
+            value += cash_flow / (1.0 + r).powi(t);
+        }
+
+        let terminal_value = cash_flow * (1.0 + risk_free) / (r - risk_free);
+
+        value + terminal_value / (1.0 + r).powi(HIGH_GROWTH_YEARS)
+    };
+
+    let f = |r: f64| present_value(r) - index_price;
+    let data = RootfinderData::new(1e-12, 0.01, risk_free + 1e-6, risk_free + 1.0, true);
+    let expected_return = Brent::new(f, risk_free + 0.05, data).solve();
+
+    expected_return - risk_free
+}
+
+/// Historical equity risk premium from periodic `equity_returns` and
+/// `risk_free_returns` over the same periods.
+///
+/// # Panics
+/// Panics if there are fewer than two periods, or the returns have
+/// different lengths.
+#[must_use]
+pub fn historical_equity_premium(
+    equity_returns: &[f64],
+    risk_free_returns: &[f64],
+) -> HistoricalEquityPremium {
+    let n = equity_returns.len();
+    assert!(
+        n > 1 && risk_free_returns.len() == n,
+        "Expected at least two periods of equity and risk-free returns."
+    );
+
+    let excess: Vec<f64> = equity_returns
+        .iter()
+        .zip(risk_free_returns)
+        .map(|(equity, risk_free)| equity - risk_free)
+        .collect();
+    let arithmetic = excess.iter().sum::<f64>() / n as f64;
+    let variance = excess.iter().map(|x| (x - arithmetic).powi(2)).sum::<f64>() / (n - 1) as f64;
+
+    let geometric_mean =
+        |returns: &[f64]| returns.iter().map(|r| (1.0 + r).ln()).sum::<f64>() / n as f64;
+    let geometric = geometric_mean(equity_returns).exp() - geometric_mean(risk_free_returns).exp();
+
+    HistoricalEquityPremium {
+        arithmetic,
+        geometric,
+        standard_error: (variance / n as f64).sqrt(),
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_equity_risk_premium {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    #[test]
+    fn test_gordon_growth_round_trip() {
+        for (dividend, growth_rate, cost_of_equity) in
+            [(2.0, 0.03, 0.08), (0.5, -0.01, 0.06), (10.0, 0.05, 0.051)]
+        {
+            let price = gordon_growth_price(dividend, growth_rate, cost_of_equity);
+
+            assert_approx_equal!(
+                gordon_growth_model(dividend, growth_rate, price),
+                cost_of_equity,
+                1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn test_implied_premium_reduces_to_gordon() {
+        // With earnings growing at the risk-free rate throughout, the model
+        // is a Gordon growth model.
+        let (index_price, cash_flow, risk_free) = (4_000.0, 150.0, 0.04);
+        let premium = implied_equity_premium(
+            index_price,
+            vec![100.0],
+            vec![cash_flow - 100.0],
+            risk_free,
+            risk_free,
+        );
+        let cost_of_equity =
+            gordon_growth_model(cash_flow * (1.0 + risk_free), risk_free, index_price);
+
+        assert_approx_equal!(premium, cost_of_equity - risk_free, 1e-10);
+    }
+
+    #[test]
+    fn test_implied_premium() {
+        // Damodaran's implied ERP at the start of 2024: the S&P 500 at
+        // 4769.83, with a normalized cash yield of about 4.03% (dividends
+        // plus buybacks), 5.88% expected earnings growth, and a 3.88%
+        // ten-year Treasury rate, for a premium of about 4.6%.
+        let index_price = 4_769.83;
+        let cash_flow = 0.0403 * index_price;
+        let premium = implied_equity_premium(
+            index_price,
+            vec![0.4 * cash_flow],
+            vec![0.6 * cash_flow],
+            0.0588,
+            0.0388,
+        );
+
+        assert!((premium - 0.046).abs() < 0.003, "{premium}");
+
+        // A higher price implies a lower premium.
+        let richer = implied_equity_premium(
+            1.1 * index_price,
+            vec![0.4 * cash_flow],
+            vec![0.6 * cash_flow],
+            0.0588,
+            0.0388,
+        );
+        assert!(richer < premium);
+    }
+
+    #[test]
+    fn test_historical_premium() {
+        let equity = [0.10, -0.05, 0.20, 0.08];
+        let risk_free = [0.02, 0.02, 0.03, 0.01];
+        let premium = historical_equity_premium(&equity, &risk_free);
+
+        assert_approx_equal!(premium.arithmetic, 0.0625, 1e-12);
+        assert!(premium.geometric < premium.arithmetic);
+        assert!(premium.standard_error > 0.0);
+    }
+}
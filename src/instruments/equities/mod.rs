@@ -7,6 +7,10 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+/// Equity risk premium estimators.
+pub mod equity_risk_premium;
+pub use equity_risk_premium::*;
+
 use super::{currency::Currency, Ticker};
 use crate::iso::isin::ISIN;
 
@@ -0,0 +1,756 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Garman-Kohlhagen (1983) FX options, the FX delta and ATM conventions,
+//! and smiles built from ATM, risk reversal, and butterfly quotes.
+//!
+//! An FX option is an option on one unit of the foreign currency, with
+//! spot $S$ quoted in domestic currency per unit of foreign. The foreign
+//! rate $r_f$ plays the part of a dividend yield, so the price is the
+//! Black-Scholes-Merton price with $q = r_f$ and forward
+//! $F = S e^{(r_d - r_f) t}$.
+//!
+//! FX deltas are quoted in four conventions (Reiswich and Wystup, 2010),
+//! where $\phi$ is $+1$ for calls and $-1$ for puts:
+//!
+//! | Convention                      | Delta                                        |
+//! |---------------------------------|----------------------------------------------|
+//! | Spot                            | $\phi e^{-r_f t} N(\phi d_1)$                |
+//! | Forward                         | $\phi N(\phi d_1)$                           |
+//! | Premium-adjusted spot           | $\phi e^{-r_f t} \frac{K}{F} N(\phi d_2)$    |
+//! | Premium-adjusted forward        | $\phi \frac{K}{F} N(\phi d_2)$               |
+//!
+//! The premium-adjusted deltas are used when the premium is paid in the
+//! foreign currency (e.g. USD/JPY): the hedge is reduced by the premium
+//! received, $\Delta_{pa} = \Delta - V / S$.
+//!
+//! Smiles are quoted at the ATM strike and at the strikes of given deltas
+//! (usually 25 and 10): the risk reversal $RR = \sigma_{C} - \sigma_{P}$
+//! and the butterfly $BF = (\sigma_{C} + \sigma_{P}) / 2 - \sigma_{ATM}$.
+//! [`FxSmile`] treats the butterfly as the smile strangle, so the wing
+//! volatilities are $\sigma_{ATM} + BF \pm RR / 2$.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use crate::instruments::options::{black_scholes, TypeFlag};
+use crate::math::distributions::{Distribution, Gaussian};
+use crate::math::rootfinding::{
+    brent::Brent,
+    rootfinder::{Rootfinder, RootfinderData},
+};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Garman-Kohlhagen market for FX options of one expiry.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GarmanKohlhagen {
+    /// Spot exchange rate, in domestic currency per unit of foreign.
+    pub spot: f64,
+    /// Continuously compounded domestic interest rate.
+    pub domestic_rate: f64,
+    /// Continuously compounded foreign interest rate.
+    pub foreign_rate: f64,
+    /// Time to expiry, in years.
+    pub time: f64,
+}
+
+/// FX delta quoting convention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FxDeltaConvention {
+    /// Spot delta, $\partial V / \partial S$.
+    Spot,
+    /// Forward delta, the spot delta without the foreign discount factor.
+    Forward,
+    /// Spot delta less the premium in foreign currency, $V / S$.
+    PremiumAdjustedSpot,
+    /// Forward delta less the forward premium in foreign currency.
+    PremiumAdjustedForward,
+}
+
+/// FX at-the-money strike convention.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FxAtmConvention {
+    /// The ATM strike is the forward.
+    Forward,
+    /// Delta-neutral straddle: the call and put deltas at the ATM strike
+    /// sum to zero.
+    DeltaNeutral,
+}
+
+/// Risk reversal and butterfly quoted at one delta.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FxWingQuote {
+    /// Absolute delta of the wing options, e.g. 0.25 for the 25-delta
+    /// quotes.
+    pub delta: f64,
+    /// Call volatility less put volatility.
+    pub risk_reversal: f64,
+    /// Average of the call and put volatilities less the ATM volatility.
+    pub butterfly: f64,
+}
+
+/// Volatility quotes of one expiry.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FxVolQuotes {
+    /// ATM volatility.
+    pub atm: f64,
+    /// Risk reversals and butterflies, e.g. at 25 and 10 delta.
+    pub wings: Vec<FxWingQuote>,
+    /// ATM strike convention.
+    pub atm_convention: FxAtmConvention,
+    /// Delta convention of the ATM and wing quotes.
+    pub delta_convention: FxDeltaConvention,
+}
+
+/// Implied volatility smile of one expiry: the quoted pillars, joined by a
+/// natural cubic spline in log-moneyness $\ln(K / F)$ and held flat beyond
+/// the outermost pillars.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FxSmile {
+    forward: f64,
+    strikes: Vec<f64>,
+    vols: Vec<f64>,
+    log_moneyness: Vec<f64>,
+    second_derivatives: Vec<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl FxDeltaConvention {
+    /// Whether the delta is net of the premium.
+    #[must_use]
+    pub fn is_premium_adjusted(self) -> bool {
+        matches!(
+            self,
+            Self::PremiumAdjustedSpot | Self::PremiumAdjustedForward
+        )
+    }
+
+    /// Whether the delta is a spot (rather than forward) delta.
+    #[must_use]
+    pub fn is_spot(self) -> bool {
+        matches!(self, Self::Spot | Self::PremiumAdjustedSpot)
+    }
+}
+
+impl GarmanKohlhagen {
+    /// New Garman-Kohlhagen market.
+    #[must_use]
+    pub fn new(spot: f64, domestic_rate: f64, foreign_rate: f64, time: f64) -> Self {
+        Self {
+            spot,
+            domestic_rate,
+            foreign_rate,
+            time,
+        }
+    }
+
+    /// Forward exchange rate, $S e^{(r_d - r_f) t}$.
+    #[must_use]
+    pub fn forward(&self) -> f64 {
+        self.spot * ((self.domestic_rate - self.foreign_rate) * self.time).exp()
+    }
+
+    /// Price in domestic currency of an option on one unit of foreign
+    /// currency.
+    #[must_use]
+    pub fn price(&self, strike: f64, volatility: f64, flag: TypeFlag) -> f64 {
+        let (s, rd, rf, t) = self.unpack();
+
+        black_scholes::price(s, strike, rd, rf, volatility, t, flag)
+    }
+
+    /// Delta in the given convention.
+    #[must_use]
+    pub fn delta(
+        &self,
+        strike: f64,
+        volatility: f64,
+        flag: TypeFlag,
+        convention: FxDeltaConvention,
+    ) -> f64 {
+        let (s, rd, rf, t) = self.unpack();
+
+        let mut delta = black_scholes::delta(s, strike, rd, rf, volatility, t, flag);
+
+        if convention.is_premium_adjusted() {
+            delta -= self.price(strike, volatility, flag) / s;
+        }
+        if !convention.is_spot() {
+            delta /= self.foreign_discount_factor();
+        }
+
+        delta
+    }
+
+    /// Gamma, $\partial^2 V / \partial S^2$.
+    #[must_use]
+    pub fn gamma(&self, strike: f64, volatility: f64) -> f64 {
+        let (s, rd, rf, t) = self.unpack();
+
+        black_scholes::gamma(s, strike, rd, rf, volatility, t)
+    }
+
+    /// Vega, $\partial V / \partial \sigma$.
+    #[must_use]
+    pub fn vega(&self, strike: f64, volatility: f64) -> f64 {
+        let (s, rd, rf, t) = self.unpack();
+
+        black_scholes::vega(s, strike, rd, rf, volatility, t)
+    }
+
+    /// Theta, $\partial V / \partial t$ per year of calendar time.
+    #[must_use]
+    pub fn theta(&self, strike: f64, volatility: f64, flag: TypeFlag) -> f64 {
+        let (s, rd, rf, t) = self.unpack();
+
+        black_scholes::theta(s, strike, rd, rf, volatility, t, flag)
+    }
+
+    /// Sensitivity to the domestic rate, $\partial V / \partial r_d$.
+    #[must_use]
+    pub fn domestic_rho(&self, strike: f64, volatility: f64, flag: TypeFlag) -> f64 {
+        let (s, rd, rf, t) = self.unpack();
+
+        black_scholes::rho(s, strike, rd, rf, volatility, t, flag)
+    }
+
+    /// Sensitivity to the foreign rate, $\partial V / \partial r_f$.
+    #[must_use]
+    pub fn foreign_rho(&self, strike: f64, volatility: f64, flag: TypeFlag) -> f64 {
+        let (s, rd, rf, t) = self.unpack();
+
+        black_scholes::dividend_rho(s, strike, rd, rf, volatility, t, flag)
+    }
+
+    /// Strike at which the option has the given delta in the given
+    /// convention.
+    ///
+    /// Unadjusted deltas are inverted in closed form. The premium-adjusted
+    /// call delta is not monotonic in the strike: it rises from zero to a
+    /// maximum and falls back to zero. The strike returned is the one above
+    /// the maximum, as quoted in the market (Reiswich and Wystup, 2010).
+    ///
+    /// # Errors
+    /// `InvalidArgument` if the volatility or time to expiry is not
+    /// positive, or the delta does not have the sign of the option type or
+    /// is larger in absolute value than any strike gives.
+    pub fn strike_from_delta(
+        &self,
+        delta: f64,
+        volatility: f64,
+        flag: TypeFlag,
+        convention: FxDeltaConvention,
+    ) -> Result<f64, RustQuantError> {
+        if !(volatility > 0.0 && self.time > 0.0) {
+            return Err(RustQuantError::InvalidArgument(
+                "The volatility and time to expiry must be positive.".to_string(),
+            ));
+        }
+
+        let phi = flag.sign();
+        let forward = self.forward();
+        let std_dev = volatility * self.time.sqrt();
+
+        // Absolute forward delta.
+        let target = if convention.is_spot() {
+            phi * delta / self.foreign_discount_factor()
+        } else {
+            phi * delta
+        };
+
+        if !(target > 0.0 && target < 1.0) {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "No {flag} strike has a delta of {delta}."
+            )));
+        }
+
+        // Strike of the unadjusted forward delta: N(phi d1) = target.
+        let n = Gaussian::default();
+        let d1 = phi * n.inv_cdf(target);
+        let unadjusted = forward * (-d1 * std_dev + 0.5 * std_dev * std_dev).exp();
+
+        if !convention.is_premium_adjusted() {
+            return Ok(unadjusted);
+        }
+
+        // Absolute premium-adjusted forward delta, (K / F) N(phi d2).
+        let adjusted = |k: f64| {
+            let d2 = ((forward / k).ln() - 0.5 * std_dev * std_dev) / std_dev;
+
+            k / forward * n.cdf(phi * d2)
+        };
+
+        // The adjustment increases the absolute delta of a put and reduces
+        // that of a call, so the put strike is below the unadjusted one and
+        // the call strike between the maximum and the unadjusted one.
+        let (lower, upper) = match flag {
+            TypeFlag::Put => {
+                let mut lower = 0.5 * unadjusted;
+                while adjusted(lower) > target {
+                    lower *= 0.5;
+                }
+
+                (lower, unadjusted)
+            }
+            TypeFlag::Call => {
+                // The maximum is where sigma sqrt(t) N(d2) = n(d2).
+                let f = |d2: f64| std_dev * n.cdf(d2) - n.pdf(d2);
+                let data = RootfinderData::new(1e-14, 0.1, -40.0, 40.0, true);
+                let d2 = Brent::new(f, 0.0, data).solve();
+                let maximum = forward * (-d2 * std_dev - 0.5 * std_dev * std_dev).exp();
+
+                if adjusted(maximum) < target {
+                    return Err(RustQuantError::InvalidArgument(format!(
+                        "The premium-adjusted call delta is at most {}.",
+                        phi * adjusted(maximum)
+                    )));
+                }
+
+                (maximum, unadjusted)
+            }
+        };
+
+        let data = RootfinderData::new(1e-14 * forward, 1e-3 * forward, lower, upper, true);
+
+        Ok(Brent::new(|k| adjusted(k) - target, 0.5 * (lower + upper), data).solve())
+    }
+
+    /// Convert a delta between conventions for the same option.
+    ///
+    /// # Errors
+    /// As [`Self::strike_from_delta`] for the delta in the `from`
+    /// convention.
+    pub fn convert_delta(
+        &self,
+        delta: f64,
+        volatility: f64,
+        flag: TypeFlag,
+        from: FxDeltaConvention,
+        to: FxDeltaConvention,
+    ) -> Result<f64, RustQuantError> {
+        let strike = self.strike_from_delta(delta, volatility, flag, from)?;
+
+        Ok(self.delta(strike, volatility, flag, to))
+    }
+
+    /// ATM strike in the given conventions.
+    ///
+    /// The delta-neutral straddle strike is $F e^{\sigma^2 t / 2}$ for
+    /// unadjusted deltas and $F e^{-\sigma^2 t / 2}$ for premium-adjusted
+    /// ones.
+    #[must_use]
+    pub fn atm_strike(
+        &self,
+        volatility: f64,
+        atm_convention: FxAtmConvention,
+        delta_convention: FxDeltaConvention,
+    ) -> f64 {
+        let variance = volatility * volatility * self.time;
+
+        match (atm_convention, delta_convention.is_premium_adjusted()) {
+            (FxAtmConvention::Forward, _) => self.forward(),
+            (FxAtmConvention::DeltaNeutral, false) => self.forward() * (0.5 * variance).exp(),
+            (FxAtmConvention::DeltaNeutral, true) => self.forward() * (-0.5 * variance).exp(),
+        }
+    }
+
+    fn foreign_discount_factor(&self) -> f64 {
+        (-self.foreign_rate * self.time).exp()
+    }
+
+    fn unpack(&self) -> (f64, f64, f64, f64) {
+        (self.spot, self.domestic_rate, self.foreign_rate, self.time)
+    }
+}
+
+impl FxSmile {
+    /// Smile through the ATM strike and the call and put strikes of each
+    /// wing delta.
+    ///
+    /// # Errors
+    /// - `InvalidArgument` if a pillar volatility is not positive, or the
+    ///   pillar strikes are not strictly increasing from the put wings
+    ///   through the ATM strike to the call wings.
+    /// - As [`GarmanKohlhagen::strike_from_delta`] for the wing strikes.
+    pub fn from_quotes(
+        market: &GarmanKohlhagen,
+        quotes: &FxVolQuotes,
+    ) -> Result<Self, RustQuantError> {
+        let mut wings = quotes.wings.clone();
+        wings.sort_by(|a, b| a.delta.total_cmp(&b.delta));
+
+        let mut pillars = Vec::with_capacity(2 * wings.len() + 1);
+
+        for wing in &wings {
+            let vol = quotes.atm + wing.butterfly - 0.5 * wing.risk_reversal;
+            let strike = market.strike_from_delta(
+                -wing.delta,
+                vol,
+                TypeFlag::Put,
+                quotes.delta_convention,
+            )?;
+            pillars.push((strike, vol));
+        }
+
+        pillars.push((
+            market.atm_strike(quotes.atm, quotes.atm_convention, quotes.delta_convention),
+            quotes.atm,
+        ));
+
+        for wing in wings.iter().rev() {
+            let vol = quotes.atm + wing.butterfly + 0.5 * wing.risk_reversal;
+            let strike = market.strike_from_delta(
+                wing.delta,
+                vol,
+                TypeFlag::Call,
+                quotes.delta_convention,
+            )?;
+            pillars.push((strike, vol));
+        }
+
+        if pillars.iter().any(|&(_, vol)| vol.is_nan() || vol <= 0.0) {
+            return Err(RustQuantError::InvalidArgument(
+                "The quoted volatilities must be positive.".to_string(),
+            ));
+        }
+        if pillars.windows(2).any(|w| w[0].0 >= w[1].0) {
+            return Err(RustQuantError::InvalidArgument(
+                "The pillar strikes must be strictly increasing.".to_string(),
+            ));
+        }
+
+        let forward = market.forward();
+        let (strikes, vols): (Vec<f64>, Vec<f64>) = pillars.into_iter().unzip();
+        let log_moneyness: Vec<f64> = strikes.iter().map(|k| (k / forward).ln()).collect();
+        let second_derivatives = natural_spline(&log_moneyness, &vols);
+
+        Ok(Self {
+            forward,
+            strikes,
+            vols,
+            log_moneyness,
+            second_derivatives,
+        })
+    }
+
+    /// Pillar strikes, in increasing order.
+    #[must_use]
+    pub fn strikes(&self) -> &[f64] {
+        &self.strikes
+    }
+
+    /// Pillar volatilities.
+    #[must_use]
+    pub fn vols(&self) -> &[f64] {
+        &self.vols
+    }
+
+    /// Forward exchange rate of the expiry.
+    #[must_use]
+    pub fn forward(&self) -> f64 {
+        self.forward
+    }
+
+    /// Implied volatility at `strike`.
+    #[must_use]
+    pub fn volatility(&self, strike: f64) -> f64 {
+        let x = (strike / self.forward).ln();
+        let (xs, ys, m) = (&self.log_moneyness, &self.vols, &self.second_derivatives);
+        let last = xs.len() - 1;
+
+        if x <= xs[0] {
+            return ys[0];
+        }
+        if x >= xs[last] {
+            return ys[last];
+        }
+
+        let i = xs.partition_point(|&xi| xi <= x) - 1;
+        let h = xs[i + 1] - xs[i];
+        let (a, b) = ((xs[i + 1] - x) / h, (x - xs[i]) / h);
+
+        a * ys[i]
+            + b * ys[i + 1]
+            + ((a.powi(3) - a) * m[i] + (b.powi(3) - b) * m[i + 1]) * h * h / 6.0
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// Second derivatives of the natural cubic spline through the points, by the
+// tridiagonal (Thomas) algorithm.
+fn natural_spline(xs: &[f64], ys: &[f64]) -> Vec<f64> {
+    let n = xs.len();
+    let mut m = vec![0.0; n];
+
+    if n < 3 {
+        return m;
+    }
+
+    let mut diagonal = vec![0.0; n];
+    let mut rhs = vec![0.0; n];
+
+    for i in 1..n - 1 {
+        let (h0, h1) = (xs[i] - xs[i - 1], xs[i + 1] - xs[i]);
+
+        diagonal[i] = 2.0 * (h0 + h1);
+        rhs[i] = 6.0 * ((ys[i + 1] - ys[i]) / h1 - (ys[i] - ys[i - 1]) / h0);
+
+        if i > 1 {
+            let w = h0 / diagonal[i - 1];
+            diagonal[i] -= w * h0;
+            rhs[i] -= w * rhs[i - 1];
+        }
+    }
+
+    for i in (1..n - 1).rev() {
+        let h1 = xs[i + 1] - xs[i];
+        m[i] = (rhs[i] - h1 * m[i + 1]) / diagonal[i];
+    }
+
+    m
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_garman_kohlhagen {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    const CONVENTIONS: [FxDeltaConvention; 4] = [
+        FxDeltaConvention::Spot,
+        FxDeltaConvention::Forward,
+        FxDeltaConvention::PremiumAdjustedSpot,
+        FxDeltaConvention::PremiumAdjustedForward,
+    ];
+
+    // EUR/USD-like market, one year.
+    fn market() -> GarmanKohlhagen {
+        GarmanKohlhagen::new(1.35, 0.03, 0.01, 1.0)
+    }
+
+    #[test]
+    fn test_textbook_price() {
+        // Haug (2007): six-month call, S = 1.56, K = 1.60, r_d = 6%,
+        // r_f = 8%, sigma = 12%.
+        let market = GarmanKohlhagen::new(1.56, 0.06, 0.08, 0.5);
+
+        assert_approx_equal!(market.price(1.60, 0.12, TypeFlag::Call), 0.0291, 1e-4);
+    }
+
+    #[test]
+    fn test_deltas() {
+        let market = market();
+        let (k, v) = (1.4, 0.1);
+        let df = (-market.foreign_rate * market.time).exp();
+
+        for flag in [TypeFlag::Call, TypeFlag::Put] {
+            let spot = market.delta(k, v, flag, FxDeltaConvention::Spot);
+            let premium = market.price(k, v, flag) / market.spot;
+
+            // Finite-difference spot delta.
+            let h = 1e-6;
+            let bump = |s: f64| GarmanKohlhagen { spot: s, ..market }.price(k, v, flag);
+            let fd = (bump(market.spot + h) - bump(market.spot - h)) / (2.0 * h);
+            assert_approx_equal!(spot, fd, 1e-8);
+
+            assert_approx_equal!(
+                market.delta(k, v, flag, FxDeltaConvention::Forward),
+                spot / df,
+                1e-14
+            );
+            assert_approx_equal!(
+                market.delta(k, v, flag, FxDeltaConvention::PremiumAdjustedSpot),
+                spot - premium,
+                1e-14
+            );
+            assert_approx_equal!(
+                market.delta(k, v, flag, FxDeltaConvention::PremiumAdjustedForward),
+                (spot - premium) / df,
+                1e-14
+            );
+        }
+
+        // Forward put-call parity of the deltas.
+        let call = market.delta(k, v, TypeFlag::Call, FxDeltaConvention::Forward);
+        let put = market.delta(k, v, TypeFlag::Put, FxDeltaConvention::Forward);
+        assert_approx_equal!(call - put, 1.0, 1e-14);
+    }
+
+    #[test]
+    fn test_strike_from_delta_round_trips() -> Result<(), RustQuantError> {
+        let market = market();
+
+        for convention in CONVENTIONS {
+            for vol in [0.05, 0.12, 0.3] {
+                for delta in [0.1, 0.25, 0.4] {
+                    for (flag, delta) in [(TypeFlag::Call, delta), (TypeFlag::Put, -delta)] {
+                        let strike = market.strike_from_delta(delta, vol, flag, convention)?;
+
+                        assert_approx_equal!(
+                            market.delta(strike, vol, flag, convention),
+                            delta,
+                            1e-10
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_premium_adjusted_call_strike_is_above_the_maximum() -> Result<(), RustQuantError> {
+        let market = market();
+        let (vol, convention) = (0.3, FxDeltaConvention::PremiumAdjustedForward);
+        let strike = market.strike_from_delta(0.25, vol, TypeFlag::Call, convention)?;
+
+        // The delta is falling at the strike returned.
+        let delta = |k: f64| market.delta(k, vol, TypeFlag::Call, convention);
+        assert!(delta(strike * 1.01) < delta(strike));
+
+        // Premium-adjusted call deltas are bounded away from one.
+        assert!(market
+            .strike_from_delta(0.95, vol, TypeFlag::Call, convention)
+            .is_err());
+        // Deltas with the wrong sign have no strike.
+        assert!(market
+            .strike_from_delta(0.25, vol, TypeFlag::Put, FxDeltaConvention::Spot)
+            .is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_convert_delta() -> Result<(), RustQuantError> {
+        let market = market();
+        let df = (-market.foreign_rate * market.time).exp();
+
+        let forward = market.convert_delta(
+            0.25,
+            0.1,
+            TypeFlag::Call,
+            FxDeltaConvention::Spot,
+            FxDeltaConvention::Forward,
+        )?;
+        assert_approx_equal!(forward, 0.25 / df, 1e-12);
+
+        // There and back again.
+        for from in CONVENTIONS {
+            for to in CONVENTIONS {
+                let converted = market.convert_delta(-0.25, 0.1, TypeFlag::Put, from, to)?;
+                let back = market.convert_delta(converted, 0.1, TypeFlag::Put, to, from)?;
+
+                assert_approx_equal!(back, -0.25, 1e-10);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_delta_neutral_atm_strike() {
+        let market = market();
+        let vol = 0.15;
+
+        for convention in CONVENTIONS {
+            let strike = market.atm_strike(vol, FxAtmConvention::DeltaNeutral, convention);
+            let call = market.delta(strike, vol, TypeFlag::Call, convention);
+            let put = market.delta(strike, vol, TypeFlag::Put, convention);
+
+            assert_approx_equal!(call + put, 0.0, 1e-12);
+        }
+
+        assert_eq!(
+            market.atm_strike(vol, FxAtmConvention::Forward, FxDeltaConvention::Spot),
+            market.forward()
+        );
+    }
+
+    #[test]
+    fn test_smile_from_quotes() -> Result<(), RustQuantError> {
+        let market = market();
+
+        for convention in CONVENTIONS {
+            let quotes = FxVolQuotes {
+                atm: 0.1,
+                wings: vec![
+                    FxWingQuote {
+                        delta: 0.1,
+                        risk_reversal: -0.012,
+                        butterfly: 0.01,
+                    },
+                    FxWingQuote {
+                        delta: 0.25,
+                        risk_reversal: -0.005,
+                        butterfly: 0.003,
+                    },
+                ],
+                atm_convention: FxAtmConvention::DeltaNeutral,
+                delta_convention: convention,
+            };
+            let smile = FxSmile::from_quotes(&market, &quotes)?;
+
+            assert_eq!(smile.strikes().len(), 5);
+            for (vol, quoted) in smile.vols().iter().zip([0.116, 0.1055, 0.1, 0.1005, 0.104]) {
+                assert_approx_equal!(vol, quoted, 1e-15);
+            }
+
+            // The pillars are recovered, at the strikes of their deltas.
+            for (&strike, &vol) in smile.strikes().iter().zip(smile.vols()) {
+                assert_approx_equal!(smile.volatility(strike), vol, 1e-14);
+            }
+
+            let put_25 = smile.strikes()[1];
+            let call_25 = smile.strikes()[3];
+            assert_approx_equal!(
+                market.delta(put_25, smile.volatility(put_25), TypeFlag::Put, convention),
+                -0.25,
+                1e-10
+            );
+            assert_approx_equal!(
+                market.delta(
+                    call_25,
+                    smile.volatility(call_25),
+                    TypeFlag::Call,
+                    convention
+                ),
+                0.25,
+                1e-10
+            );
+
+            // Risk reversal and butterfly of the smile.
+            let (put_vol, call_vol) = (smile.volatility(put_25), smile.volatility(call_25));
+            assert_approx_equal!(call_vol - put_vol, -0.005, 1e-14);
+            assert_approx_equal!(0.5 * (call_vol + put_vol) - 0.1, 0.003, 1e-14);
+
+            // Flat beyond the wings, smooth in between.
+            assert_eq!(smile.volatility(0.5), smile.vols()[0]);
+            assert_eq!(smile.volatility(3.0), smile.vols()[4]);
+            let between = smile.volatility(0.5 * (put_25 + smile.strikes()[2]));
+            assert!(between > 0.1 && between < 0.1055);
+        }
+
+        Ok(())
+    }
+}
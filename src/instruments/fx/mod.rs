@@ -15,3 +15,7 @@ pub use exchange::*;
 
 pub mod money;
 pub use money::*;
+
+/// Garman-Kohlhagen FX options, delta conventions, and smiles.
+pub mod garman_kohlhagen;
+pub use garman_kohlhagen::*;
@@ -47,6 +47,8 @@
 //! ### Equities
 //!
 //! ### Commodities
+//!
+//! ### Structured Products
 
 /// Base trait for all instruments.
 pub mod instrument;
@@ -54,6 +56,14 @@ pub use instrument::*;
 
 /// Bond pricing models.
 pub mod bonds;
+
+/// Commodity futures curves and spread options.
+pub mod commodities;
+pub use commodities::*;
+
+/// Credit default swaps and default probability curves.
+pub mod credit;
+pub use credit::*;
 // pub use bonds::*;
 
 /// Option pricers and sensitivity functions.
@@ -68,6 +78,15 @@ pub use fx::*;
 pub mod equities;
 pub use equities::*;
 
+/// Pricing kernels (stochastic discount factors) of consumption-based
+/// asset pricing models.
+pub mod pricing_kernel;
+pub use pricing_kernel::*;
+
+/// Retail structured products (autocallables, reverse convertibles).
+pub mod structured_products;
+pub use structured_products::*;
+
 /// Ticker symbol.
 pub mod ticker;
 pub use ticker::*;
@@ -0,0 +1,204 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Static no-arbitrage checks of European-style (call, put) price pairs.
+//!
+//! A call pays $(X - Y)^+$ and a put $(Y - X)^+$ at expiry, where $X$ is the
+//! underlying (the spot, or the average for Asian options) and $Y$ the
+//! strike. With $F$ and $K$ the present values of $X$ and $Y$, any model
+//! must give:
+//! - put-call parity: $C - P = F - K$,
+//! - lower bounds: $C \ge (F - K)^+$ and $P \ge (K - F)^+$,
+//! - upper bounds: $C \le F$ and $P \le K$.
+//!
+//! These checks are diagnostics only: they never change a price, they
+//! report how far it is from the no-arbitrage region.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::TypeFlag;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Relative tolerance of the checks, as a fraction of $F + K$.
+pub const ARBITRAGE_TOLERANCE: f64 = 1e-10;
+
+/// A violation of a static no-arbitrage relation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArbitrageViolation {
+    /// The price is NaN or infinite.
+    NonFinitePrice {
+        /// Call or put.
+        type_flag: TypeFlag,
+        /// The price.
+        price: f64,
+    },
+    /// $C - P$ differs from $F - K$.
+    PutCallParity {
+        /// $(C - P) - (F - K)$.
+        gap: f64,
+    },
+    /// The price is below the intrinsic value of the forward.
+    LowerBound {
+        /// Call or put.
+        type_flag: TypeFlag,
+        /// Amount by which the price is below the bound.
+        shortfall: f64,
+    },
+    /// The price exceeds the present value of what it can pay.
+    UpperBound {
+        /// Call or put.
+        type_flag: TypeFlag,
+        /// Amount by which the price is above the bound.
+        excess: f64,
+    },
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl ArbitrageViolation {
+    /// Size of the violation, in price units (infinite for non-finite
+    /// prices).
+    #[must_use]
+    pub fn magnitude(&self) -> f64 {
+        match *self {
+            Self::NonFinitePrice { .. } => f64::INFINITY,
+            Self::PutCallParity { gap } => gap.abs(),
+            Self::LowerBound { shortfall, .. } => shortfall,
+            Self::UpperBound { excess, .. } => excess,
+        }
+    }
+}
+
+/// Check a (call, put) price pair against put-call parity and the price
+/// bounds, given the present values of the underlying (`forward`) and of
+/// the strike (`strike`) paid at expiry.
+///
+/// Returns every violation larger than [`ARBITRAGE_TOLERANCE`] of
+/// `forward + strike`, or nothing if the prices are consistent.
+#[must_use]
+pub fn check_no_arbitrage(
+    call: f64,
+    put: f64,
+    forward: f64,
+    strike: f64,
+) -> Vec<ArbitrageViolation> {
+    let mut violations = Vec::new();
+
+    for (type_flag, price) in [(TypeFlag::Call, call), (TypeFlag::Put, put)] {
+        if !price.is_finite() {
+            violations.push(ArbitrageViolation::NonFinitePrice { type_flag, price });
+        }
+    }
+    if !violations.is_empty() {
+        return violations;
+    }
+
+    let tolerance = ARBITRAGE_TOLERANCE * (forward.abs() + strike.abs()).max(1.0);
+
+    let gap = (call - put) - (forward - strike);
+    if gap.abs() > tolerance {
+        violations.push(ArbitrageViolation::PutCallParity { gap });
+    }
+
+    let bounds = [
+        (TypeFlag::Call, call, (forward - strike).max(0.0), forward),
+        (TypeFlag::Put, put, (strike - forward).max(0.0), strike),
+    ];
+
+    for (type_flag, price, lower, upper) in bounds {
+        if price < lower - tolerance {
+            violations.push(ArbitrageViolation::LowerBound {
+                type_flag,
+                shortfall: lower - price,
+            });
+        }
+        if price > upper + tolerance {
+            violations.push(ArbitrageViolation::UpperBound {
+                type_flag,
+                excess: price - upper,
+            });
+        }
+    }
+
+    violations
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_arbitrage {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    #[test]
+    fn test_consistent_prices_pass() {
+        // Call 10.45, put 5.57 for S = K = 100, r = 5%, T = 1.
+        let strike = 100.0 * (-0.05_f64).exp();
+        let call = 10.450_583_572_185_565;
+
+        assert!(check_no_arbitrage(call, call - (100.0 - strike), 100.0, strike).is_empty());
+
+        // Worthless and maximal prices are on the bounds.
+        assert!(check_no_arbitrage(0.0, 5.0, 95.0, 100.0).is_empty());
+        assert!(check_no_arbitrage(100.0, strike, 100.0, strike).is_empty());
+    }
+
+    #[test]
+    fn test_violations_are_reported() {
+        // Parity broken by 1.
+        let violations = check_no_arbitrage(10.0, 4.0, 100.0, 95.0);
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(
+            violations[0],
+            ArbitrageViolation::PutCallParity { gap } if (gap - 1.0).abs() < 1e-12
+        ));
+
+        // Call below intrinsic, and a negative put.
+        let violations = check_no_arbitrage(3.0, -2.0, 100.0, 95.0);
+        assert_eq!(
+            violations,
+            vec![
+                ArbitrageViolation::LowerBound {
+                    type_flag: TypeFlag::Call,
+                    shortfall: 2.0
+                },
+                ArbitrageViolation::LowerBound {
+                    type_flag: TypeFlag::Put,
+                    shortfall: 2.0
+                },
+            ]
+        );
+        assert_approx_equal!(violations[0].magnitude(), 2.0, 1e-12);
+
+        // Call worth more than the underlying.
+        let violations = check_no_arbitrage(110.0, 105.0, 100.0, 95.0);
+        assert!(violations.contains(&ArbitrageViolation::UpperBound {
+            type_flag: TypeFlag::Call,
+            excess: 10.0
+        }));
+        assert!(violations.contains(&ArbitrageViolation::UpperBound {
+            type_flag: TypeFlag::Put,
+            excess: 10.0
+        }));
+
+        // NaN prices fail every comparison, so are reported on their own.
+        let violations = check_no_arbitrage(f64::NAN, 1.0, 100.0, 95.0);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].magnitude(), f64::INFINITY);
+    }
+}
@@ -0,0 +1,302 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! The Black (1976) model for options on futures and forwards,
+//! such as commodity options and interest rate caplets.
+//!
+//! The forward price $F$ is lognormal with volatility $\sigma$, and:
+//!
+//! $$
+//! C = e^{-rT} [F N(d_1) - K N(d_2)], \quad
+//! P = e^{-rT} [K N(-d_2) - F N(-d_1)]
+//! $$
+//!
+//! with $d_{1,2} = [\ln(F/K) \pm \sigma^2 T / 2] / (\sigma \sqrt{T})$. This is
+//! the Black-Scholes-Merton formula with the forward as the underlying and a
+//! dividend yield equal to the rate, and is priced with [`black_scholes`].
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::PricingError;
+use crate::instruments::options::{black_scholes, TypeFlag};
+use crate::time::{DayCountConvention, ExpiryConvention};
+use crate::units::{Price, Rate, Volatility};
+
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// European option on a forward (or futures) price, under the Black model.
+#[derive(Debug, Clone, Copy)]
+pub struct Black76Option {
+    /// F - The forward (or futures) price.
+    pub forward: f64,
+    /// K - The strike price.
+    pub strike: f64,
+    /// r - The risk-free rate used for discounting.
+    pub r: f64,
+    /// sigma - The volatility of the forward price.
+    pub v: f64,
+    /// Evaluation date (optional, defaults to now).
+    pub evaluation_date: Option<OffsetDateTime>,
+    /// The option's expiry.
+    pub expiry: OffsetDateTime,
+    /// Local time of day at which the option expires (optional; without
+    /// one, time to expiry is counted in whole days).
+    pub expiry_convention: Option<ExpiryConvention>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Black76Option {
+    /// New Black-76 option, evaluated now.
+    #[must_use]
+    pub fn new(
+        forward: Price,
+        strike: Price,
+        r: Rate,
+        v: Volatility,
+        expiry: OffsetDateTime,
+    ) -> Self {
+        Self {
+            forward: forward.value(),
+            strike: strike.value(),
+            r: r.value(),
+            v: v.value(),
+            evaluation_date: None,
+            expiry,
+            expiry_convention: None,
+        }
+    }
+
+    /// Time to expiry, in years.
+    #[must_use]
+    pub fn year_fraction(&self) -> f64 {
+        let evaluation_date = self.evaluation_date.unwrap_or(OffsetDateTime::now_utc());
+
+        match self.expiry_convention {
+            Some(convention) => convention.year_fraction(evaluation_date, self.expiry.date()),
+            None => DayCountConvention::default()
+                .day_count_factor(evaluation_date.date(), self.expiry.date()),
+        }
+    }
+
+    /// Black-76 option price.
+    #[must_use]
+    pub fn price(&self, flag: TypeFlag) -> f64 {
+        let T = self.year_fraction();
+
+        black_scholes::price(self.forward, self.strike, self.r, self.r, self.v, T, flag)
+    }
+
+    /// Black-76 option price, rejecting options that expired before the
+    /// evaluation date.
+    ///
+    /// An option expiring on the evaluation date is worth its intrinsic
+    /// value; without an expiry convention only dates are counted, so this
+    /// includes expiry later that day.
+    ///
+    /// # Errors
+    /// [`PricingError::Expired`] if the expiry is before the evaluation date.
+    pub fn try_price(&self, flag: TypeFlag) -> Result<f64, PricingError> {
+        let T = self.year_fraction();
+        if T < 0.0 {
+            return Err(PricingError::Expired(-T));
+        }
+
+        Ok(self.price(flag))
+    }
+}
+
+/// Undiscounted Black-76 price: $E[(F_T - K)^+]$ or $E[(K - F_T)^+]$.
+///
+/// At (or past) expiry, or without volatility, this is the payoff.
+pub(crate) fn black_76_undiscounted(F: f64, K: f64, v: f64, T: f64, flag: TypeFlag) -> f64 {
+    black_scholes::price(F, K, 0.0, 0.0, v, T, flag)
+}
+
+/// Black-76 price of an interest rate caplet, per unit of notional.
+///
+/// The caplet pays $\tau (L - K)^+$ at time $t + \tau$, where $L$ is the
+/// rate fixed at time $t$ for the period $[t, t + \tau]$.
+///
+/// # Arguments
+/// - `f`: forward rate for the period.
+/// - `k`: cap rate (strike).
+/// - `r`: continuously compounded rate used to discount from $t + \tau$.
+/// - `v`: volatility of the forward rate.
+/// - `t`: time to the fixing, in years.
+/// - `tau`: accrual period, in years.
+#[must_use]
+pub fn black_76_caplet(f: f64, k: f64, r: f64, v: f64, t: f64, tau: f64) -> f64 {
+    tau * (-r * (t + tau)).exp() * black_76_undiscounted(f, k, v, t, TypeFlag::Call)
+}
+
+/// Black-76 price of an interest rate floorlet, per unit of notional.
+///
+/// The floorlet pays $\tau (K - L)^+$ at time $t + \tau$; the arguments are
+/// as for [`black_76_caplet`].
+#[must_use]
+pub fn black_76_floorlet(f: f64, k: f64, r: f64, v: f64, t: f64, tau: f64) -> f64 {
+    tau * (-r * (t + tau)).exp() * black_76_undiscounted(f, k, v, t, TypeFlag::Put)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_black_76 {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::options::BlackScholesMerton;
+    use crate::RUSTQUANT_EPSILON;
+    use time::macros::datetime;
+    use time::Duration;
+
+    #[test]
+    fn test_black_76_recovers_black_scholes() {
+        let evaluation_date = datetime!(2024-01-02 0:00 UTC);
+        let expiry = evaluation_date + Duration::days(182);
+        let (S, K, r, v) = (100.0, 95.0, 0.05, 0.2);
+
+        for flag in [TypeFlag::Call, TypeFlag::Put] {
+            let bs = BlackScholesMerton::new(
+                r.into(),
+                S.into(),
+                K.into(),
+                v.into(),
+                r.into(),
+                Some(evaluation_date.date()),
+                expiry.date(),
+                flag,
+            );
+
+            let mut black = Black76Option::new(0.0.into(), K.into(), r.into(), v.into(), expiry);
+            black.evaluation_date = Some(evaluation_date);
+            black.forward = S * (r * black.year_fraction()).exp();
+
+            assert_approx_equal!(black.price(flag), bs.price(), RUSTQUANT_EPSILON);
+        }
+    }
+
+    #[test]
+    fn test_black_76_put_call_parity() {
+        let evaluation_date = datetime!(2024-01-02 0:00 UTC);
+        let option = Black76Option {
+            forward: 20.0,
+            strike: 22.0,
+            r: 0.03,
+            v: 0.35,
+            evaluation_date: Some(evaluation_date),
+            expiry: evaluation_date + Duration::days(365),
+            expiry_convention: None,
+        };
+
+        let T = option.year_fraction();
+        let parity = (-option.r * T).exp() * (option.forward - option.strike);
+
+        assert_approx_equal!(
+            option.price(TypeFlag::Call) - option.price(TypeFlag::Put),
+            parity,
+            RUSTQUANT_EPSILON
+        );
+    }
+
+    #[test]
+    fn test_black_76_caplet() {
+        // Hull, Options, Futures, and Other Derivatives, caplet example:
+        // 8% cap on a 10,000 loan for 3 months starting in 1 year,
+        // 7% forward rate, 6.5% 15-month rate, 20% volatility.
+        // Hull reports 5.16 using rounded intermediate values.
+        let caplet = 10_000.0 * black_76_caplet(0.07, 0.08, 0.065, 0.2, 1.0, 0.25);
+
+        assert_approx_equal!(caplet, 5.190_045_917_440_498, 1e-8);
+
+        // Expired caplet: intrinsic value.
+        assert_approx_equal!(
+            black_76_caplet(0.09, 0.08, 0.0, 0.2, 0.0, 0.25),
+            0.25 * 0.01,
+            RUSTQUANT_EPSILON
+        );
+    }
+
+    #[test]
+    fn test_black_76_expiry_edge_cases() {
+        let evaluation_date = datetime!(2024-01-02 12:00 UTC);
+        let option = |expiry| Black76Option {
+            forward: 105.0,
+            strike: 100.0,
+            r: 0.03,
+            v: 0.35,
+            evaluation_date: Some(evaluation_date),
+            expiry,
+            expiry_convention: None,
+        };
+
+        // Expiring at valuation, or a second later on the same day: the
+        // intrinsic value.
+        for expiry in [evaluation_date, evaluation_date + Duration::seconds(1)] {
+            assert_eq!(option(expiry).try_price(TypeFlag::Call), Ok(5.0));
+            assert_eq!(option(expiry).try_price(TypeFlag::Put), Ok(0.0));
+        }
+
+        assert!(matches!(
+            option(evaluation_date - Duration::days(1)).try_price(TypeFlag::Call),
+            Err(PricingError::Expired(years)) if years > 0.0
+        ));
+    }
+
+    #[test]
+    fn test_black_76_expiry_convention() {
+        let expiry = datetime!(2024-07-19 0:00 UTC);
+        let noon_new_york = datetime!(2024-07-19 12:00 -4);
+        let option = |evaluation_date| Black76Option {
+            evaluation_date: Some(evaluation_date),
+            expiry_convention: Some(ExpiryConvention::nyse_close()),
+            ..Black76Option::new(100.0.into(), 100.0.into(), 0.05.into(), 0.2.into(), expiry)
+        };
+
+        // Four hours to the close, wherever the evaluation time is expressed.
+        let in_new_york = option(noon_new_york);
+        let in_utc = option(noon_new_york.to_offset(time::UtcOffset::UTC));
+
+        assert_approx_equal!(
+            in_new_york.year_fraction(),
+            4.0 / (365.0 * 24.0),
+            RUSTQUANT_EPSILON
+        );
+        assert_eq!(in_utc.year_fraction(), in_new_york.year_fraction());
+        assert_eq!(
+            in_utc.price(TypeFlag::Call),
+            in_new_york.price(TypeFlag::Call)
+        );
+
+        // Without the convention the option is already at expiry, and worth
+        // nothing at the money; with it, the last hours still have value.
+        let whole_days = Black76Option {
+            expiry_convention: None,
+            ..in_new_york
+        };
+        assert_eq!(whole_days.price(TypeFlag::Call), 0.0);
+        assert!(in_new_york.price(TypeFlag::Call) > 0.1);
+
+        // After the close it has expired.
+        assert!(matches!(
+            option(datetime!(2024-07-19 16:30 -4)).try_price(TypeFlag::Call),
+            Err(PricingError::Expired(_))
+        ));
+    }
+}
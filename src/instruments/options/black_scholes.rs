@@ -0,0 +1,922 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Black-Scholes-Merton prices and Greeks of European options as free
+//! functions of plain `f64`s:
+//! `(spot, strike, rate, dividend_yield, volatility, time)`, with the
+//! [`TypeFlag`] last where the formula depends on it.
+//!
+//! These are the canonical vanilla formulas of the crate:
+//! [`BlackScholesMerton`](crate::instruments::options::BlackScholesMerton),
+//! the spreads, and the calendar spreads price through them.
+//! [`BlackScholesInputs`] takes the same inputs as the typed units of
+//! [`crate::units`].
+//!
+//! The formulas stay finite at the limits:
+//! - At or past expiry ($t \le 0$) and without volatility ($\sigma = 0$)
+//!   the price is the discounted intrinsic value of the forward,
+//!   $e^{-rt} (\phi (F - K))^+$. The first-order Greeks are those of this
+//!   payoff (with half the weight exactly at the money), and the
+//!   second-order Greeks are zero.
+//! - Far in or out of the money the densities underflow to zero rather
+//!   than producing `NaN`.
+//!
+//! Time Greeks are with respect to calendar time, so a long option
+//! usually has a negative theta.
+//!
+//! ```
+//! # use RustQuant::instruments::options::black_scholes;
+//! // Haug (2007), the Black-Scholes (1973) call.
+//! let call = black_scholes::call_price(60.0, 65.0, 0.08, 0.0, 0.3, 0.25);
+//!
+//! assert!((call - 2.1334).abs() < 1e-4);
+//! ```
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::autodiff::{HyperDual, Variable};
+use crate::instruments::options::TypeFlag;
+use crate::math::distributions::{Distribution, Gaussian};
+use crate::units::{DividendYield, Price, Rate, TimeFraction, Volatility};
+use std::f64::consts::SQRT_2;
+use std::ops::{Add, Div, Mul, Sub};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Inputs of the Black-Scholes-Merton formulas in units of measure.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BlackScholesInputs {
+    /// Spot price of the underlying.
+    pub spot: Price,
+    /// Strike price.
+    pub strike: Price,
+    /// Continuously compounded risk-free rate.
+    pub rate: Rate,
+    /// Continuous dividend yield.
+    pub dividend_yield: DividendYield,
+    /// Volatility of the underlying.
+    pub volatility: Volatility,
+    /// Time to expiry in years.
+    pub time: TimeFraction,
+}
+
+/// Terms shared by the formulas, in the generalised cost of carry form
+/// $b = r - q$ used by Haug (2007).
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Terms {
+    s: f64,
+    k: f64,
+    r: f64,
+    b: f64,
+    v: f64,
+    t: f64,
+    sqrt_t: f64,
+    carry: f64,
+    discount: f64,
+    d1: f64,
+    d2: f64,
+    degenerate: bool,
+}
+
+/// Number types of the automatic differentiation backends, in which
+/// [`autodiff_price`] is evaluated.
+///
+/// [`Variable`] and [`HyperDual`] share the arithmetic operators but not
+/// a trait for `ln` and the normal CDF, so this supplies the two.
+pub(crate) trait AutodiffScalar:
+    Copy
+    + Add<Output = Self>
+    + Add<f64, Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Mul<f64, Output = Self>
+    + Div<Output = Self>
+    + Div<f64, Output = Self>
+{
+    fn ln(self) -> Self;
+
+    fn norm_cdf(self) -> Self;
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl BlackScholesInputs {
+    fn unpack(&self) -> (f64, f64, f64, f64, f64, f64) {
+        (
+            self.spot.value(),
+            self.strike.value(),
+            self.rate.value(),
+            self.dividend_yield.value(),
+            self.volatility.value(),
+            self.time.value(),
+        )
+    }
+
+    /// See [`d1`].
+    #[must_use]
+    pub fn d1(&self) -> f64 {
+        let (s, k, r, q, v, t) = self.unpack();
+        d1(s, k, r, q, v, t)
+    }
+
+    /// See [`d2`].
+    #[must_use]
+    pub fn d2(&self) -> f64 {
+        let (s, k, r, q, v, t) = self.unpack();
+        d2(s, k, r, q, v, t)
+    }
+
+    /// See [`price`].
+    #[must_use]
+    pub fn price(&self, flag: TypeFlag) -> Price {
+        let (s, k, r, q, v, t) = self.unpack();
+        Price::new_unchecked(price(s, k, r, q, v, t, flag))
+    }
+
+    /// See [`delta`].
+    #[must_use]
+    pub fn delta(&self, flag: TypeFlag) -> f64 {
+        let (s, k, r, q, v, t) = self.unpack();
+        delta(s, k, r, q, v, t, flag)
+    }
+
+    /// See [`gamma`].
+    #[must_use]
+    pub fn gamma(&self) -> f64 {
+        let (s, k, r, q, v, t) = self.unpack();
+        gamma(s, k, r, q, v, t)
+    }
+
+    /// See [`vega`].
+    #[must_use]
+    pub fn vega(&self) -> f64 {
+        let (s, k, r, q, v, t) = self.unpack();
+        vega(s, k, r, q, v, t)
+    }
+
+    /// See [`theta`].
+    #[must_use]
+    pub fn theta(&self, flag: TypeFlag) -> f64 {
+        let (s, k, r, q, v, t) = self.unpack();
+        theta(s, k, r, q, v, t, flag)
+    }
+
+    /// See [`rho`].
+    #[must_use]
+    pub fn rho(&self, flag: TypeFlag) -> f64 {
+        let (s, k, r, q, v, t) = self.unpack();
+        rho(s, k, r, q, v, t, flag)
+    }
+
+    /// See [`dividend_rho`].
+    #[must_use]
+    pub fn dividend_rho(&self, flag: TypeFlag) -> f64 {
+        let (s, k, r, q, v, t) = self.unpack();
+        dividend_rho(s, k, r, q, v, t, flag)
+    }
+
+    /// See [`vanna`].
+    #[must_use]
+    pub fn vanna(&self) -> f64 {
+        let (s, k, r, q, v, t) = self.unpack();
+        vanna(s, k, r, q, v, t)
+    }
+
+    /// See [`volga`].
+    #[must_use]
+    pub fn volga(&self) -> f64 {
+        let (s, k, r, q, v, t) = self.unpack();
+        volga(s, k, r, q, v, t)
+    }
+
+    /// See [`charm`].
+    #[must_use]
+    pub fn charm(&self, flag: TypeFlag) -> f64 {
+        let (s, k, r, q, v, t) = self.unpack();
+        charm(s, k, r, q, v, t, flag)
+    }
+
+    /// See [`veta`].
+    #[must_use]
+    pub fn veta(&self) -> f64 {
+        let (s, k, r, q, v, t) = self.unpack();
+        veta(s, k, r, q, v, t)
+    }
+}
+
+impl Terms {
+    /// Terms for spot `s`, strike `k`, rate `r`, cost of carry `b`,
+    /// volatility `v`, and time to expiry `t` (floored at zero).
+    pub(crate) fn with_carry(s: f64, k: f64, r: f64, b: f64, v: f64, t: f64) -> Self {
+        let t = t.max(0.0);
+        let sqrt_t = t.sqrt();
+        let degenerate = t == 0.0 || v == 0.0;
+        let (d1, d2) = d1_d2(s, k, b, v, t, sqrt_t, degenerate);
+
+        Self {
+            s,
+            k,
+            r,
+            b,
+            v,
+            t,
+            sqrt_t,
+            carry: ((b - r) * t).exp(),
+            discount: (-r * t).exp(),
+            d1,
+            d2,
+            degenerate,
+        }
+    }
+
+    /// The same terms for strike `k`, reusing the strike-independent ones.
+    /// Equal to [`Self::with_carry`] with strike `k`, bit for bit.
+    pub(crate) fn with_strike(&self, k: f64) -> Self {
+        let Self {
+            s,
+            b,
+            v,
+            t,
+            sqrt_t,
+            degenerate,
+            ..
+        } = *self;
+        let (d1, d2) = d1_d2(s, k, b, v, t, sqrt_t, degenerate);
+
+        Self { k, d1, d2, ..*self }
+    }
+
+    pub(crate) fn d1_d2(&self) -> (f64, f64) {
+        (self.d1, self.d2)
+    }
+
+    pub(crate) fn price(&self, flag: TypeFlag) -> f64 {
+        let n = Gaussian::default();
+        let Self { s, k, .. } = *self;
+
+        if self.degenerate {
+            return (flag.sign() * (s * self.carry - k * self.discount)).max(0.0);
+        }
+
+        match flag {
+            TypeFlag::Call => s * self.carry * n.cdf(self.d1) - k * self.discount * n.cdf(self.d2),
+            TypeFlag::Put => {
+                -s * self.carry * n.cdf(-self.d1) + k * self.discount * n.cdf(-self.d2)
+            }
+        }
+    }
+
+    pub(crate) fn delta(&self, flag: TypeFlag) -> f64 {
+        let n = Gaussian::default();
+
+        match flag {
+            TypeFlag::Call => self.carry * n.cdf(self.d1),
+            TypeFlag::Put => self.carry * (n.cdf(self.d1) - 1.0),
+        }
+    }
+
+    pub(crate) fn gamma(&self) -> f64 {
+        if self.degenerate {
+            return 0.0;
+        }
+
+        self.carry * Gaussian::default().pdf(self.d1) / (self.s * self.v * self.sqrt_t)
+    }
+
+    pub(crate) fn vega(&self) -> f64 {
+        self.s * self.carry * Gaussian::default().pdf(self.d1) * self.sqrt_t
+    }
+
+    pub(crate) fn theta(&self, flag: TypeFlag) -> f64 {
+        let n = Gaussian::default();
+        let Self { s, k, r, b, .. } = *self;
+
+        let diffusion = if self.degenerate {
+            0.0
+        } else {
+            -s * self.carry * n.pdf(self.d1) * self.v / (2.0 * self.sqrt_t)
+        };
+
+        match flag {
+            TypeFlag::Call => {
+                diffusion
+                    - (b - r) * s * self.carry * n.cdf(self.d1)
+                    - r * k * self.discount * n.cdf(self.d2)
+            }
+            TypeFlag::Put => {
+                diffusion
+                    + (b - r) * s * self.carry * n.cdf(-self.d1)
+                    + r * k * self.discount * n.cdf(-self.d2)
+            }
+        }
+    }
+
+    pub(crate) fn rho(&self, flag: TypeFlag) -> f64 {
+        let n = Gaussian::default();
+
+        match flag {
+            TypeFlag::Call => self.k * self.t * self.discount * n.cdf(self.d2),
+            TypeFlag::Put => -self.k * self.t * self.discount * n.cdf(-self.d2),
+        }
+    }
+
+    pub(crate) fn dividend_rho(&self, flag: TypeFlag) -> f64 {
+        let n = Gaussian::default();
+
+        match flag {
+            TypeFlag::Call => -self.t * self.s * self.carry * n.cdf(self.d1),
+            TypeFlag::Put => self.t * self.s * self.carry * n.cdf(-self.d1),
+        }
+    }
+
+    pub(crate) fn vanna(&self) -> f64 {
+        if self.degenerate {
+            return 0.0;
+        }
+
+        -self.carry * Gaussian::default().pdf(self.d1) * self.d2 / self.v
+    }
+
+    pub(crate) fn volga(&self) -> f64 {
+        if self.degenerate {
+            return 0.0;
+        }
+
+        self.vega() * self.d1 * self.d2 / self.v
+    }
+
+    pub(crate) fn charm(&self, flag: TypeFlag) -> f64 {
+        let n = Gaussian::default();
+        let Self { r, b, v, t, .. } = *self;
+
+        let diffusion = if self.degenerate {
+            0.0
+        } else {
+            n.pdf(self.d1) * (b / (v * self.sqrt_t) - self.d2 / (2.0 * t))
+        };
+
+        match flag {
+            TypeFlag::Call => -self.carry * (diffusion + (b - r) * n.cdf(self.d1)),
+            TypeFlag::Put => -self.carry * (diffusion - (b - r) * n.cdf(-self.d1)),
+        }
+    }
+
+    pub(crate) fn veta(&self) -> f64 {
+        if self.degenerate {
+            return 0.0;
+        }
+
+        let Self {
+            r, b, v, t, d1, d2, ..
+        } = *self;
+
+        self.vega() * (r - b + b * d1 / (v * self.sqrt_t) - (d1 * d2 + 1.0) / (2.0 * t))
+    }
+}
+
+impl<'v> AutodiffScalar for Variable<'v> {
+    fn ln(self) -> Self {
+        Variable::ln(self)
+    }
+
+    fn norm_cdf(self) -> Self {
+        0.5 * (-self / SQRT_2).erfc()
+    }
+}
+
+impl AutodiffScalar for HyperDual {
+    fn ln(self) -> Self {
+        HyperDual::ln(self)
+    }
+
+    fn norm_cdf(self) -> Self {
+        HyperDual::norm_cdf(self)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+fn d1_d2(s: f64, k: f64, b: f64, v: f64, t: f64, sqrt_t: f64, degenerate: bool) -> (f64, f64) {
+    if degenerate {
+        // The limit of d1 and d2 as the variance vanishes: the sign of
+        // the forward moneyness.
+        let moneyness = (s / k).ln() + b * t;
+        let d = if moneyness > 0.0 {
+            f64::INFINITY
+        } else if moneyness < 0.0 {
+            f64::NEG_INFINITY
+        } else {
+            0.0
+        };
+
+        (d, d)
+    } else {
+        let d1 = (1.0 / (v * sqrt_t)) * ((s / k).ln() + (b + 0.5 * v.powi(2)) * t);
+
+        (d1, d1 - v * sqrt_t)
+    }
+}
+
+/// [`price`] with the spot and volatility in an automatic differentiation
+/// type and the other inputs as in [`Terms::with_carry`], for $t > 0$.
+///
+/// The same formula as [`Terms`], which stays the `f64` path: the limits
+/// at $\sigma \sqrt{t} = 0$ are not differentiable, so the callers handle
+/// expiry themselves.
+pub(crate) fn autodiff_price<T: AutodiffScalar>(
+    spot: T,
+    strike: f64,
+    rate: f64,
+    cost_of_carry: f64,
+    volatility: T,
+    time: f64,
+    flag: TypeFlag,
+) -> T {
+    let (k, r, b, t) = (strike, rate, cost_of_carry, time);
+    let sqrt_t = t.sqrt();
+    let sign = flag.sign();
+
+    let d1 = ((spot / k).ln() + (volatility * volatility * 0.5 + b) * t) / (volatility * sqrt_t);
+    let d2 = d1 - volatility * sqrt_t;
+
+    (spot * ((b - r) * t).exp() * (d1 * sign).norm_cdf()
+        - (d2 * sign).norm_cdf() * (k * (-r * t).exp()))
+        * sign
+}
+
+fn terms(s: f64, k: f64, r: f64, q: f64, v: f64, t: f64) -> Terms {
+    Terms::with_carry(s, k, r, r - q, v, t)
+}
+
+/// $d_1 = \frac{\ln(S/K) + (r - q + \sigma^2/2) t}{\sigma \sqrt{t}}$,
+/// or $\pm\infty$ (0 at the forward) when $\sigma \sqrt{t}$ vanishes.
+#[must_use]
+pub fn d1(
+    spot: f64,
+    strike: f64,
+    rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    time: f64,
+) -> f64 {
+    terms(spot, strike, rate, dividend_yield, volatility, time).d1
+}
+
+/// $d_2 = d_1 - \sigma \sqrt{t}$.
+#[must_use]
+pub fn d2(
+    spot: f64,
+    strike: f64,
+    rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    time: f64,
+) -> f64 {
+    terms(spot, strike, rate, dividend_yield, volatility, time).d2
+}
+
+/// Price of a European call.
+#[must_use]
+pub fn call_price(
+    spot: f64,
+    strike: f64,
+    rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    time: f64,
+) -> f64 {
+    terms(spot, strike, rate, dividend_yield, volatility, time).price(TypeFlag::Call)
+}
+
+/// Price of a European put.
+#[must_use]
+pub fn put_price(
+    spot: f64,
+    strike: f64,
+    rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    time: f64,
+) -> f64 {
+    terms(spot, strike, rate, dividend_yield, volatility, time).price(TypeFlag::Put)
+}
+
+/// Price of a European call or put.
+#[must_use]
+pub fn price(
+    spot: f64,
+    strike: f64,
+    rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    time: f64,
+    flag: TypeFlag,
+) -> f64 {
+    terms(spot, strike, rate, dividend_yield, volatility, time).price(flag)
+}
+
+/// Delta, $\partial V / \partial S$.
+#[must_use]
+pub fn delta(
+    spot: f64,
+    strike: f64,
+    rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    time: f64,
+    flag: TypeFlag,
+) -> f64 {
+    terms(spot, strike, rate, dividend_yield, volatility, time).delta(flag)
+}
+
+/// Gamma, $\partial^2 V / \partial S^2$ (the same for calls and puts).
+#[must_use]
+pub fn gamma(
+    spot: f64,
+    strike: f64,
+    rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    time: f64,
+) -> f64 {
+    terms(spot, strike, rate, dividend_yield, volatility, time).gamma()
+}
+
+/// Vega, $\partial V / \partial \sigma$ (the same for calls and puts).
+#[must_use]
+pub fn vega(
+    spot: f64,
+    strike: f64,
+    rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    time: f64,
+) -> f64 {
+    terms(spot, strike, rate, dividend_yield, volatility, time).vega()
+}
+
+/// Theta, $\partial V / \partial t$ per year of calendar time.
+#[must_use]
+pub fn theta(
+    spot: f64,
+    strike: f64,
+    rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    time: f64,
+    flag: TypeFlag,
+) -> f64 {
+    terms(spot, strike, rate, dividend_yield, volatility, time).theta(flag)
+}
+
+/// Rho, $\partial V / \partial r$.
+#[must_use]
+pub fn rho(
+    spot: f64,
+    strike: f64,
+    rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    time: f64,
+    flag: TypeFlag,
+) -> f64 {
+    terms(spot, strike, rate, dividend_yield, volatility, time).rho(flag)
+}
+
+/// Sensitivity to the dividend yield, $\partial V / \partial q$.
+/// Also known as epsilon, psi, or phi.
+#[must_use]
+pub fn dividend_rho(
+    spot: f64,
+    strike: f64,
+    rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    time: f64,
+    flag: TypeFlag,
+) -> f64 {
+    terms(spot, strike, rate, dividend_yield, volatility, time).dividend_rho(flag)
+}
+
+/// Vanna, $\partial^2 V / \partial S \partial \sigma$.
+#[must_use]
+pub fn vanna(
+    spot: f64,
+    strike: f64,
+    rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    time: f64,
+) -> f64 {
+    terms(spot, strike, rate, dividend_yield, volatility, time).vanna()
+}
+
+/// Volga, $\partial^2 V / \partial \sigma^2$. Also known as vomma.
+#[must_use]
+pub fn volga(
+    spot: f64,
+    strike: f64,
+    rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    time: f64,
+) -> f64 {
+    terms(spot, strike, rate, dividend_yield, volatility, time).volga()
+}
+
+/// Charm, $\partial \Delta / \partial t$ per year of calendar time.
+/// Also known as delta decay.
+#[must_use]
+pub fn charm(
+    spot: f64,
+    strike: f64,
+    rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    time: f64,
+    flag: TypeFlag,
+) -> f64 {
+    terms(spot, strike, rate, dividend_yield, volatility, time).charm(flag)
+}
+
+/// Veta, $\partial \mathcal{V} / \partial t$ per year of calendar time.
+/// Also known as vega decay or vega bleed.
+#[must_use]
+pub fn veta(
+    spot: f64,
+    strike: f64,
+    rate: f64,
+    dividend_yield: f64,
+    volatility: f64,
+    time: f64,
+) -> f64 {
+    terms(spot, strike, rate, dividend_yield, volatility, time).veta()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_black_scholes {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::error::RustQuantError;
+
+    const FLAGS: [TypeFlag; 2] = [TypeFlag::Call, TypeFlag::Put];
+
+    // (spot, strike, rate, dividend yield, volatility, time)
+    const CASES: [(f64, f64, f64, f64, f64, f64); 5] = [
+        (100.0, 100.0, 0.05, 0.0, 0.2, 1.0),
+        (100.0, 80.0, 0.03, 0.02, 0.35, 0.5),
+        (100.0, 130.0, 0.01, 0.04, 0.15, 2.0),
+        (50.0, 45.0, -0.005, 0.0, 0.6, 0.1),
+        (1.2, 1.25, 0.04, 0.06, 0.1, 0.75),
+    ];
+
+    #[test]
+    fn test_published_prices() {
+        // Haug (2007), The Complete Guide to Option Pricing Formulas:
+        // Black-Scholes (1973) and Merton (1973).
+        assert_approx_equal!(call_price(60.0, 65.0, 0.08, 0.0, 0.3, 0.25), 2.1334, 1e-4);
+        assert_approx_equal!(put_price(100.0, 95.0, 0.1, 0.05, 0.2, 0.5), 2.4648, 1e-4);
+
+        // Hull, Options, Futures, and Other Derivatives: S = 42, K = 40.
+        assert_approx_equal!(call_price(42.0, 40.0, 0.1, 0.0, 0.2, 0.5), 4.76, 5e-3);
+        assert_approx_equal!(put_price(42.0, 40.0, 0.1, 0.0, 0.2, 0.5), 0.81, 5e-3);
+    }
+
+    #[test]
+    fn test_published_greeks() {
+        // Haug (2007), chapter 2: the futures option delta (q = r), the
+        // gamma, the put theta, and the call rho examples.
+        let (call, put) = (TypeFlag::Call, TypeFlag::Put);
+
+        assert_approx_equal!(delta(105.0, 100.0, 0.1, 0.1, 0.36, 0.5, call), 0.5946, 1e-4);
+        assert_approx_equal!(delta(105.0, 100.0, 0.1, 0.1, 0.36, 0.5, put), -0.3566, 1e-4);
+        assert_approx_equal!(gamma(55.0, 60.0, 0.1, 0.0, 0.3, 0.75), 0.0278, 1e-4);
+        assert_approx_equal!(
+            theta(430.0, 405.0, 0.07, 0.05, 0.2, 0.0833, put),
+            -31.1924,
+            1e-4
+        );
+        assert_approx_equal!(rho(72.0, 75.0, 0.09, 0.0, 0.19, 1.0, call), 38.7325, 1e-4);
+    }
+
+    #[test]
+    fn test_put_call_parity() {
+        for (s, k, r, q, v, t) in CASES {
+            let forward = s * (-q * t).exp() - k * (-r * t).exp();
+
+            assert_approx_equal!(
+                call_price(s, k, r, q, v, t) - put_price(s, k, r, q, v, t),
+                forward,
+                1e-12
+            );
+            assert_approx_equal!(
+                delta(s, k, r, q, v, t, TypeFlag::Call) - delta(s, k, r, q, v, t, TypeFlag::Put),
+                (-q * t).exp(),
+                1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn test_greeks_match_finite_differences() {
+        let h = 1e-4;
+        let close = |a: f64, b: f64| (a - b).abs() <= 1e-5 * (1.0 + b.abs());
+
+        for (s, k, r, q, v, t) in CASES {
+            for flag in FLAGS {
+                let p = |s: f64, r: f64, q: f64, v: f64, t: f64| price(s, k, r, q, v, t, flag);
+                let d = |s: f64, v: f64, t: f64| delta(s, k, r, q, v, t, flag);
+                let central = |f: &dyn Fn(f64) -> f64, x: f64| (f(x + h) - f(x - h)) / (2.0 * h);
+
+                let fd_delta = central(&|x| p(x, r, q, v, t), s);
+                let fd_gamma = central(&|x| d(x, v, t), s);
+                let fd_vega = central(&|x| p(s, r, q, x, t), v);
+                // Calendar time runs against the time to expiry.
+                let fd_theta = -central(&|x| p(s, r, q, v, x), t);
+                let fd_rho = central(&|x| p(s, x, q, v, t), r);
+                let fd_dividend_rho = central(&|x| p(s, r, x, v, t), q);
+                let fd_vanna = central(&|x| d(s, x, t), v);
+                let fd_volga = central(&|x| vega(s, k, r, q, x, t), v);
+                let fd_charm = -central(&|x| d(s, v, x), t);
+                let fd_veta = -central(&|x| vega(s, k, r, q, v, x), t);
+
+                assert!(close(delta(s, k, r, q, v, t, flag), fd_delta));
+                assert!(close(gamma(s, k, r, q, v, t), fd_gamma));
+                assert!(close(vega(s, k, r, q, v, t), fd_vega));
+                assert!(close(theta(s, k, r, q, v, t, flag), fd_theta));
+                assert!(close(rho(s, k, r, q, v, t, flag), fd_rho));
+                assert!(close(dividend_rho(s, k, r, q, v, t, flag), fd_dividend_rho));
+                assert!(close(vanna(s, k, r, q, v, t), fd_vanna));
+                assert!(close(volga(s, k, r, q, v, t), fd_volga));
+                assert!(close(charm(s, k, r, q, v, t, flag), fd_charm));
+                assert!(close(veta(s, k, r, q, v, t), fd_veta));
+            }
+        }
+    }
+
+    #[test]
+    fn test_expiry_is_intrinsic_value() {
+        for flag in FLAGS {
+            for (s, k) in [(110.0, 100.0), (90.0, 100.0), (100.0, 100.0)] {
+                for t in [0.0, -0.5] {
+                    assert_eq!(price(s, k, 0.05, 0.02, 0.2, t, flag), flag.payoff(s, k));
+                    assert_eq!(gamma(s, k, 0.05, 0.02, 0.2, t), 0.0);
+                    assert_eq!(vega(s, k, 0.05, 0.02, 0.2, t), 0.0);
+                    assert_eq!(rho(s, k, 0.05, 0.02, 0.2, t, flag), 0.0);
+                }
+            }
+
+            // In the money the delta is the sign of the payoff, out of the
+            // money zero, and at the money a half.
+            assert_eq!(
+                delta(110.0, 100.0, 0.05, 0.0, 0.2, 0.0, flag),
+                f64::from(flag == TypeFlag::Call) * flag.sign()
+            );
+            assert_eq!(
+                delta(90.0, 100.0, 0.05, 0.0, 0.2, 0.0, flag),
+                f64::from(flag == TypeFlag::Put) * flag.sign()
+            );
+            assert_eq!(
+                delta(100.0, 100.0, 0.05, 0.0, 0.2, 0.0, flag),
+                0.5 * flag.sign()
+            );
+        }
+    }
+
+    #[test]
+    fn test_converges_to_expiry() {
+        for (s, k, r, q, v, _) in CASES {
+            for flag in FLAGS {
+                let near = price(s, k, r, q, v, 1e-14, flag);
+
+                assert_approx_equal!(near, flag.payoff(s, k), 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_zero_volatility_is_discounted_forward_intrinsic() {
+        for (s, k, r, q, _, t) in CASES {
+            let forward = s * ((r - q) * t).exp();
+
+            for flag in FLAGS {
+                let deterministic = (-r * t).exp() * flag.payoff(forward, k);
+                let zero = price(s, k, r, q, 0.0, t, flag);
+
+                assert_approx_equal!(zero, deterministic, 1e-12);
+                assert_approx_equal!(price(s, k, r, q, 1e-9, t, flag), zero, 1e-9);
+                assert_approx_equal!(
+                    delta(s, k, r, q, 1e-9, t, flag),
+                    delta(s, k, r, q, 0.0, t, flag),
+                    1e-9
+                );
+                assert_approx_equal!(
+                    theta(s, k, r, q, 1e-9, t, flag),
+                    theta(s, k, r, q, 0.0, t, flag),
+                    1e-6
+                );
+                assert_eq!(gamma(s, k, r, q, 0.0, t), 0.0);
+                assert_eq!(vanna(s, k, r, q, 0.0, t), 0.0);
+                assert_eq!(volga(s, k, r, q, 0.0, t), 0.0);
+                assert_eq!(veta(s, k, r, q, 0.0, t), 0.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_extreme_moneyness_is_finite() {
+        let greeks = |s: f64, k: f64, flag: TypeFlag| {
+            let (r, q, v, t) = (0.05, 0.01, 0.25, 1.0);
+
+            [
+                price(s, k, r, q, v, t, flag),
+                delta(s, k, r, q, v, t, flag),
+                gamma(s, k, r, q, v, t),
+                vega(s, k, r, q, v, t),
+                theta(s, k, r, q, v, t, flag),
+                rho(s, k, r, q, v, t, flag),
+                dividend_rho(s, k, r, q, v, t, flag),
+                vanna(s, k, r, q, v, t),
+                volga(s, k, r, q, v, t),
+                charm(s, k, r, q, v, t, flag),
+                veta(s, k, r, q, v, t),
+            ]
+        };
+
+        for flag in FLAGS {
+            for (s, k) in [
+                (1e-300, 100.0),
+                (1e300, 100.0),
+                (100.0, 1e-300),
+                (100.0, 1e300),
+            ] {
+                for value in greeks(s, k, flag) {
+                    assert!(value.is_finite(), "{flag} S = {s:e}, K = {k:e}: {value}");
+                }
+            }
+
+            // Deep out of the money the option is worthless, deep in the
+            // money it is the discounted forward intrinsic value.
+            let (deep_itm, deep_otm) = match flag {
+                TypeFlag::Call => ((1e6, 1.0), (1.0, 1e6)),
+                TypeFlag::Put => ((1.0, 1e6), (1e6, 1.0)),
+            };
+            assert_eq!(greeks(deep_otm.0, deep_otm.1, flag)[0], 0.0);
+            assert_approx_equal!(
+                greeks(deep_itm.0, deep_itm.1, flag)[0],
+                price(deep_itm.0, deep_itm.1, 0.05, 0.01, 0.0, 1.0, flag),
+                1e-6
+            );
+        }
+    }
+
+    #[test]
+    fn test_typed_inputs_match_plain_functions() -> Result<(), RustQuantError> {
+        let inputs = BlackScholesInputs {
+            spot: Price::from(100.0),
+            strike: Price::from(95.0),
+            rate: Rate::from_percent(10.0)?,
+            dividend_yield: DividendYield::from_percent(5.0)?,
+            volatility: Volatility::from_percent(20.0)?,
+            time: TimeFraction::from(0.5),
+        };
+
+        for flag in FLAGS {
+            assert_eq!(
+                inputs.price(flag).value(),
+                price(100.0, 95.0, 0.1, 0.05, 0.2, 0.5, flag)
+            );
+            assert_eq!(
+                inputs.delta(flag),
+                delta(100.0, 95.0, 0.1, 0.05, 0.2, 0.5, flag)
+            );
+            assert_eq!(
+                inputs.theta(flag),
+                theta(100.0, 95.0, 0.1, 0.05, 0.2, 0.5, flag)
+            );
+        }
+        assert_eq!(inputs.gamma(), gamma(100.0, 95.0, 0.1, 0.05, 0.2, 0.5));
+        assert_eq!(inputs.d2(), d2(100.0, 95.0, 0.1, 0.05, 0.2, 0.5));
+        assert_approx_equal!(inputs.price(TypeFlag::Put).value(), 2.4648, 1e-4);
+
+        Ok(())
+    }
+}
@@ -25,12 +25,20 @@
 // IMPORTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::instruments::options::TypeFlag;
+use crate::autodiff::{HyperDual, Max, Variable};
+use crate::error::{PricingError, RustQuantError};
+use crate::instruments::options::black_scholes::{autodiff_price, Terms};
+use crate::instruments::options::{check_no_arbitrage, ArbitrageViolation, TypeFlag};
 use crate::instruments::Instrument;
 use crate::math::distributions::{Distribution, Gaussian};
+use crate::pricer::{AuditCollector, AuditRecord};
 use crate::time::{today, DayCountConvention};
+use crate::trading::risk::{AutodiffGreeks, GreeksBackend, Pricer};
+use crate::units::{Price, Rate, Volatility};
 
-use time::Date;
+use nalgebra::DMatrix;
+use rayon::prelude::*;
+use time::{Date, OffsetDateTime};
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // STRUCTS, ENUMS, AND TRAITS
@@ -51,14 +59,19 @@ pub struct BlackScholesMerton {
     ///     - Asay 1982 margined futures option model.
     /// - b = r_d - r_f
     ///     - Garman and Kohlhagen 1983 currency option model.
+    #[builder(setter(custom))]
     pub cost_of_carry: f64,
     /// S - The underlying asset price.
+    #[builder(setter(custom))]
     pub underlying_price: f64,
     /// K - The options strike price.
+    #[builder(setter(custom))]
     pub strike_price: f64,
     /// sigma - The underlying asset's volatility.
+    #[builder(setter(custom))]
     pub volatility: f64,
     /// r - The risk-free interest rate.
+    #[builder(setter(custom))]
     pub risk_free_rate: f64,
 
     /// Evaluation date (optional, defaults to today t = 0).
@@ -71,6 +84,24 @@ pub struct BlackScholesMerton {
     pub option_type: TypeFlag,
 }
 
+/// Prices and Greeks over a strike/expiry grid, one row per strike and one
+/// column per expiry (see [`BlackScholesMerton::greeks_grid`]).
+#[derive(Debug, Clone, PartialEq)]
+pub struct GreeksGrid {
+    /// Prices.
+    pub price: DMatrix<f64>,
+    /// Deltas.
+    pub delta: DMatrix<f64>,
+    /// Gammas.
+    pub gamma: DMatrix<f64>,
+    /// Vegas.
+    pub vega: DMatrix<f64>,
+    /// Thetas.
+    pub theta: DMatrix<f64>,
+    /// Rhos.
+    pub rho: DMatrix<f64>,
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -98,26 +129,61 @@ impl Instrument for BlackScholesMerton {
     }
 }
 
+impl BlackScholesMertonBuilder {
+    /// The cost of carry factor.
+    pub fn cost_of_carry(&mut self, value: Rate) -> &mut Self {
+        self.cost_of_carry = Some(value.value());
+        self
+    }
+
+    /// S - The underlying asset price.
+    pub fn underlying_price(&mut self, value: Price) -> &mut Self {
+        self.underlying_price = Some(value.value());
+        self
+    }
+
+    /// K - The options strike price.
+    pub fn strike_price(&mut self, value: Price) -> &mut Self {
+        self.strike_price = Some(value.value());
+        self
+    }
+
+    /// sigma - The underlying asset's volatility.
+    pub fn volatility(&mut self, value: Volatility) -> &mut Self {
+        self.volatility = Some(value.value());
+        self
+    }
+
+    /// r - The risk-free interest rate.
+    pub fn risk_free_rate(&mut self, value: Rate) -> &mut Self {
+        self.risk_free_rate = Some(value.value());
+        self
+    }
+}
+
 impl BlackScholesMerton {
+    /// Model name of the audit records.
+    pub const AUDIT_MODEL: &'static str = "BlackScholesMerton";
+
     /// New European Option
     #[allow(clippy::too_many_arguments)]
     #[must_use]
     pub fn new(
-        cost_of_carry: f64,
-        underlying_price: f64,
-        strike_price: f64,
-        volatility: f64,
-        risk_free_rate: f64,
+        cost_of_carry: Rate,
+        underlying_price: Price,
+        strike_price: Price,
+        volatility: Volatility,
+        risk_free_rate: Rate,
         evaluation_date: Option<Date>,
         expiration_date: Date,
         option_type: TypeFlag,
     ) -> Self {
         Self {
-            cost_of_carry,
-            underlying_price,
-            strike_price,
-            volatility,
-            risk_free_rate,
+            cost_of_carry: cost_of_carry.value(),
+            underlying_price: underlying_price.value(),
+            strike_price: strike_price.value(),
+            volatility: volatility.value(),
+            risk_free_rate: risk_free_rate.value(),
             evaluation_date,
             expiration_date,
             option_type,
@@ -125,19 +191,149 @@ impl BlackScholesMerton {
     }
 
     /// Generalised Black-Scholes European Option Price.
+    ///
+    /// On the expiration date ($T = 0$) this is the intrinsic value, and
+    /// without volatility the discounted intrinsic value of the forward
+    /// (see [`crate::instruments::options::black_scholes`]).
     #[must_use]
     pub fn price(&self) -> f64 {
-        let (S, K, _, r, b) = self.unpack();
+        let terms = self.terms();
+        let price = terms.price(self.option_type);
+
+        #[cfg(feature = "tracing")]
+        tracing::debug!(
+            target: "rustquant::pricing",
+            model = Self::AUDIT_MODEL,
+            underlying_price = self.underlying_price,
+            strike_price = self.strike_price,
+            volatility = self.volatility,
+            risk_free_rate = self.risk_free_rate,
+            cost_of_carry = self.cost_of_carry,
+            year_fraction = self.year_fraction(),
+            option_type = %self.option_type,
+            d1 = terms.d1_d2().0,
+            d2 = terms.d1_d2().1,
+            price,
+            "priced"
+        );
+
+        price
+    }
+
+    /// Price, recording the inputs, intermediate quantities, and result in
+    /// `collector` (see [`Self::audit_record`]).
+    pub fn price_audited(&self, collector: &mut AuditCollector) -> f64 {
+        let record = self.audit_record();
+        let price = record.result;
+        collector.record(record);
+
+        price
+    }
+
+    /// Price and Greeks by automatic differentiation (see [`Self::greeks`]),
+    /// recording the inputs, the backend, and the Greeks in `collector`.
+    pub fn greeks_audited(
+        &self,
+        backend: GreeksBackend,
+        collector: &mut AuditCollector,
+    ) -> AutodiffGreeks {
+        let greeks = self.greeks(backend);
+        collector.record(
+            self.audit_record()
+                .with_label("greeks_backend", format!("{backend:?}"))
+                .with_greeks(&greeks),
+        );
+
+        greeks
+    }
+
+    /// Audit record of the price: the parameters, the evaluation and
+    /// expiration dates, the year fraction, the forward $S e^{bT}$, the
+    /// discount factor $e^{-rT}$, $d_1$ and $d_2$ (before expiry), and the
+    /// price.
+    ///
+    /// [`Self::from_audit_record`] rebuilds an option that reprices to
+    /// exactly the recorded result.
+    #[must_use]
+    pub fn audit_record(&self) -> AuditRecord {
+        let (S, K, v, r, b) = self.unpack();
         let T = self.year_fraction();
+
+        let record = AuditRecord::new(Self::AUDIT_MODEL)
+            .with_parameter("cost_of_carry", b)
+            .with_parameter("underlying_price", S)
+            .with_parameter("strike_price", K)
+            .with_parameter("volatility", v)
+            .with_parameter("risk_free_rate", r)
+            .with_date("evaluation_date", self.evaluation_date.unwrap_or(today()))
+            .with_date("expiration_date", self.expiration_date)
+            .with_label("option_type", self.option_type)
+            .with_intermediate("year_fraction", T)
+            .with_intermediate("forward", S * (b * T).exp())
+            .with_intermediate("discount_factor", (-r * T).exp())
+            .with_result(self.price());
+
+        // At expiry the price is intrinsic and d1, d2 are undefined.
+        if T == 0.0 {
+            return record;
+        }
+
         let (d1, d2) = self.d1_d2();
-        let n = Gaussian::default();
+        record
+            .with_intermediate("d1", d1)
+            .with_intermediate("d2", d2)
+    }
 
-        match self.option_type {
-            TypeFlag::Call => S * ((b - r) * T).exp() * n.cdf(d1) - K * (-r * T).exp() * n.cdf(d2),
-            TypeFlag::Put => {
-                -S * ((b - r) * T).exp() * n.cdf(-d1) + K * (-r * T).exp() * n.cdf(-d2)
-            }
+    /// Option with the inputs of a record from [`Self::audit_record`].
+    ///
+    /// # Errors
+    /// - `InvalidArgument` if the record is of another model, or has an
+    ///   invalid option type or date.
+    /// - `MissingInput` if an input is missing.
+    pub fn from_audit_record(record: &AuditRecord) -> Result<Self, RustQuantError> {
+        if record.model != Self::AUDIT_MODEL {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Expected a {} record, got '{}'.",
+                Self::AUDIT_MODEL,
+                record.model
+            )));
         }
+
+        let option_type = match record.label("option_type")? {
+            "Call" => TypeFlag::Call,
+            "Put" => TypeFlag::Put,
+            other => {
+                return Err(RustQuantError::InvalidArgument(format!(
+                    "Invalid option type '{other}'."
+                )))
+            }
+        };
+
+        Ok(Self::new(
+            record.parameter("cost_of_carry")?.into(),
+            record.parameter("underlying_price")?.into(),
+            record.parameter("strike_price")?.into(),
+            record.parameter("volatility")?.into(),
+            record.parameter("risk_free_rate")?.into(),
+            Some(record.date("evaluation_date")?),
+            record.date("expiration_date")?,
+            option_type,
+        ))
+    }
+
+    /// Generalised Black-Scholes European Option Price, rejecting options
+    /// that expired before the evaluation date.
+    ///
+    /// # Errors
+    /// [`PricingError::Expired`] if the expiration date is before the
+    /// evaluation date.
+    pub fn try_price(&self) -> Result<f64, PricingError> {
+        DayCountConvention::default().time_to_expiry(
+            self.evaluation_date.unwrap_or(today()),
+            self.expiration_date,
+        )?;
+
+        Ok(self.price())
     }
 
     /// Implied volatility.
@@ -161,18 +357,17 @@ impl BlackScholesMerton {
         )
     }
 
+    // Terms of the closed-form formulas in `black_scholes`.
+    fn terms(&self) -> Terms {
+        let (S, K, v, r, b) = self.unpack();
+
+        Terms::with_carry(S, K, r, b, v, self.year_fraction())
+    }
+
     // Compute d1 and d2.
     #[must_use]
     fn d1_d2(&self) -> (f64, f64) {
-        let (S, K, v, _, b) = self.unpack();
-
-        // Compute time to maturity.
-        let T = self.year_fraction();
-
-        let d1 = (1.0 / (v * T.sqrt())) * ((S / K).ln() + (b + 0.5 * v.powi(2)) * T);
-        let d2 = d1 - v * T.sqrt();
-
-        (d1, d2)
+        self.terms().d1_d2()
     }
 
     // Unpack struct to get option parameters.
@@ -190,48 +385,24 @@ impl BlackScholesMerton {
     /// Delta of generalised Black-Scholes European Option.
     #[must_use]
     pub fn delta(&self) -> f64 {
-        let (_, _, _, r, b) = self.unpack();
-        let T = self.year_fraction();
-        let d1 = self.d1_d2().0;
-        let n = Gaussian::default();
-
-        match self.option_type {
-            TypeFlag::Call => ((b - r) * T).exp() * n.cdf(d1),
-            TypeFlag::Put => ((b - r) * T).exp() * (n.cdf(d1) - 1.0),
-        }
+        self.terms().delta(self.option_type)
     }
 
     /// Vanna of generalised Black-Scholes European Option.
     /// Also known as DdeltaDvol.
     #[must_use]
     pub fn vanna(&self) -> f64 {
-        let (_, _, v, r, b) = self.unpack();
-        let T = self.year_fraction();
-        let (d1, d2) = self.d1_d2();
-        let n = Gaussian::default();
-
-        -((b - r) * T).exp() * n.pdf(d1) * d2 / v
+        self.terms().vanna()
     }
 
     /// Charm of generalised Black-Scholes European Option.
     /// Also known as DdeltaDtime, delta decay or delta bleed.
+    ///
+    /// Per year of calendar time, with the sign of Haug (2007) and of
+    /// [`Self::theta`].
     #[must_use]
     pub fn charm(&self) -> f64 {
-        let (_, _, v, r, b) = self.unpack();
-        let T = self.year_fraction();
-        let (d1, d2) = self.d1_d2();
-        let n = Gaussian::default();
-
-        match self.option_type {
-            TypeFlag::Call => {
-                ((b - r) * T).exp()
-                    * (n.pdf(d1) * ((b / (v * T.sqrt())) - (d2 / (2.0 * T))) + (b - r) * n.cdf(d1))
-            }
-            TypeFlag::Put => {
-                ((b - r) * T).exp()
-                    * (n.pdf(d1) * ((b / (v * T.sqrt())) - (d2 / (2.0 * T))) - (b - r) * n.cdf(-d1))
-            }
-        }
+        self.terms().charm(self.option_type)
     }
 
     /// Lambda of generalised Black-Scholes European Option.
@@ -245,12 +416,7 @@ impl BlackScholesMerton {
     /// Also known as convexity.
     #[must_use]
     pub fn gamma(&self) -> f64 {
-        let n = Gaussian::default();
-        let (S, _, v, r, b) = self.unpack();
-        let T = self.year_fraction();
-        let (d1, _) = self.d1_d2();
-
-        ((b - r) * T).exp() * n.pdf(d1) / (S * v * T.sqrt())
+        self.terms().gamma()
     }
 
     /// Gamma percent of generalised Black-Scholes European Option.
@@ -259,6 +425,14 @@ impl BlackScholesMerton {
         self.gamma() * self.underlying_price / 100.0
     }
 
+    /// Price, delta, gamma, vega, and vanna by automatic differentiation
+    /// with the given backend. With [`GreeksBackend::HyperDual`] they agree
+    /// with the closed-form Greeks to rounding.
+    #[must_use]
+    pub fn greeks(&self, backend: GreeksBackend) -> AutodiffGreeks {
+        AutodiffGreeks::compute_with_backend(self, self.underlying_price, self.volatility, backend)
+    }
+
     /// Zomma of generalised Black-Scholes European Option.
     /// Also known as DgammaDvol.
     #[must_use]
@@ -303,22 +477,14 @@ impl BlackScholesMerton {
     /// Also known as zeta.
     #[must_use]
     pub fn vega(&self) -> f64 {
-        let (S, _, _, r, b) = self.unpack();
-        let T = self.year_fraction();
-        let (d1, _) = self.d1_d2();
-
-        let n = Gaussian::default();
-
-        S * ((b - r) * T).exp() * n.pdf(d1) * T.sqrt()
+        self.terms().vega()
     }
 
     /// Vomma of generalised Black-Scholes European Option.
     /// Also known as DvegaDvol.
     #[must_use]
     pub fn vomma(&self) -> f64 {
-        let (d1, d2) = self.d1_d2();
-
-        self.vega() * d1 * d2 / self.volatility
+        self.terms().volga()
     }
 
     /// Ultima of generalised Black-Scholes European Option.
@@ -334,71 +500,27 @@ impl BlackScholesMerton {
     /// Also known as DvegaDtime.
     #[must_use]
     pub fn vega_bleed(&self) -> f64 {
-        let (_, _, v, r, b) = self.unpack();
-        let T = self.year_fraction();
-        let (d1, d2) = self.d1_d2();
-
-        self.vega() * (r - b + b * d1 / (v * T.sqrt()) - (d1 * d2 + 1.0) / (2.0 * T))
+        self.terms().veta()
     }
 
     /// Theta of the generalised Black-Scholes European option.
     /// Also known as Expected Bleed.
     #[must_use]
     pub fn theta(&self) -> f64 {
-        let (S, K, v, r, b) = self.unpack();
-        let T = self.year_fraction();
-        let (d1, d2) = self.d1_d2();
-
-        let n = Gaussian::default();
-
-        match self.option_type {
-            TypeFlag::Call => {
-                -S * ((b - r) * T).exp() * n.pdf(d1) * v / (2.0 * T.sqrt())
-                    - (b - r) * S * ((b - r) * T).exp() * n.cdf(d1)
-                    - r * K * (-r * T).exp() * n.cdf(d2)
-            }
-            TypeFlag::Put => {
-                -S * ((b - r) * T).exp() * n.pdf(d1) * v / (2.0 * T.sqrt())
-                    + (b - r) * S * ((b - r) * T).exp() * n.cdf(-d1)
-                    + r * K * (-r * T).exp() * n.cdf(-d2)
-            }
-        }
+        self.terms().theta(self.option_type)
     }
 
     /// Rho of the generalised Black-Scholes European option.
     #[must_use]
     pub fn rho(&self) -> f64 {
-        let T = self.year_fraction();
-
-        match self.option_type {
-            TypeFlag::Call => {
-                self.strike_price
-                    * T
-                    * (-self.risk_free_rate * T).exp()
-                    * Gaussian::default().cdf(self.d1_d2().1)
-            }
-            TypeFlag::Put => {
-                -self.strike_price
-                    * T
-                    * (-self.risk_free_rate * T).exp()
-                    * Gaussian::default().cdf(-self.d1_d2().1)
-            }
-        }
+        self.terms().rho(self.option_type)
     }
 
     /// Phi of the generalised Black-Scholes European option.
     /// Also known as Rho-2.
     #[must_use]
     pub fn phi(&self) -> f64 {
-        let (S, _, _, r, b) = self.unpack();
-        let T = self.year_fraction();
-
-        let (d1, _) = self.d1_d2();
-
-        match self.option_type {
-            TypeFlag::Call => -T * S * ((b - r) * T).exp() * Gaussian::default().cdf(d1),
-            TypeFlag::Put => T * S * ((b - r) * T).exp() * Gaussian::default().cdf(-d1),
-        }
+        self.terms().dividend_rho(self.option_type)
     }
 
     /// Zeta of the generalised Black-Scholes European option.
@@ -436,6 +558,159 @@ impl BlackScholesMerton {
         n.pdf(self.d1_d2().1) * (-self.risk_free_rate * T).exp()
             / (self.strike_price * self.volatility * T.sqrt())
     }
+
+    /// Check the call and put prices against put-call parity and the price
+    /// bounds, e.g. to catch inconsistent inputs such as a negative
+    /// volatility. The prices themselves are unchanged.
+    ///
+    /// See [`crate::instruments::options::arbitrage`].
+    #[must_use]
+    pub fn validate(&self) -> Vec<ArbitrageViolation> {
+        let (S, K, _, r, b) = self.unpack();
+        let T = self.year_fraction();
+
+        let call = Self {
+            option_type: TypeFlag::Call,
+            ..*self
+        };
+        let put = Self {
+            option_type: TypeFlag::Put,
+            ..*self
+        };
+
+        check_no_arbitrage(
+            call.price(),
+            put.price(),
+            S * ((b - r) * T).exp(),
+            K * (-r * T).exp(),
+        )
+    }
+}
+
+impl BlackScholesMerton {
+    /// Prices over a grid of strikes (rows) and expiries (columns), in
+    /// place of `strike_price` and `expiration_date`.
+    ///
+    /// Year fractions, discount factors, and the other strike-independent
+    /// terms are computed once per expiry, and the expiries are priced in
+    /// parallel. Each cell equals [`Self::price`] for that strike and
+    /// expiry exactly.
+    #[must_use]
+    pub fn price_grid(&self, strikes: &[f64], expiries: &[OffsetDateTime]) -> DMatrix<f64> {
+        let columns: Vec<Vec<f64>> = expiries
+            .par_iter()
+            .map(|expiry| {
+                let terms = self.expiry_terms(expiry);
+
+                strikes
+                    .iter()
+                    .map(|&K| terms.with_strike(K).price(self.option_type))
+                    .collect()
+            })
+            .collect();
+
+        DMatrix::from_vec(strikes.len(), expiries.len(), columns.concat())
+    }
+
+    /// Prices, deltas, gammas, vegas, thetas, and rhos over a grid of
+    /// strikes (rows) and expiries (columns), computed as in
+    /// [`Self::price_grid`]. Each cell equals the scalar Greek exactly.
+    #[must_use]
+    pub fn greeks_grid(&self, strikes: &[f64], expiries: &[OffsetDateTime]) -> GreeksGrid {
+        let flag = self.option_type;
+
+        let columns: Vec<Vec<[f64; 6]>> = expiries
+            .par_iter()
+            .map(|expiry| {
+                let terms = self.expiry_terms(expiry);
+
+                strikes
+                    .iter()
+                    .map(|&K| {
+                        let cell = terms.with_strike(K);
+
+                        [
+                            cell.price(flag),
+                            cell.delta(flag),
+                            cell.gamma(),
+                            cell.vega(),
+                            cell.theta(flag),
+                            cell.rho(flag),
+                        ]
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let cells = columns.concat();
+        let matrix = |i: usize| {
+            DMatrix::from_iterator(
+                strikes.len(),
+                expiries.len(),
+                cells.iter().map(|cell| cell[i]),
+            )
+        };
+
+        GreeksGrid {
+            price: matrix(0),
+            delta: matrix(1),
+            gamma: matrix(2),
+            vega: matrix(3),
+            theta: matrix(4),
+            rho: matrix(5),
+        }
+    }
+
+    // Terms of one expiry, to be moved to each strike with `with_strike`.
+    fn expiry_terms(&self, expiry: &OffsetDateTime) -> Terms {
+        let (S, K, v, r, b) = self.unpack();
+        let T = DayCountConvention::default()
+            .day_count_factor(self.evaluation_date.unwrap_or(today()), expiry.date());
+
+        Terms::with_carry(S, K, r, b, v, T)
+    }
+}
+
+impl Pricer for BlackScholesMerton {
+    fn price<'v>(&self, spot: Variable<'v>, volatility: Variable<'v>) -> Variable<'v> {
+        let (_, K, _, r, b) = self.unpack();
+        let T = self.year_fraction();
+        let flag = self.option_type;
+
+        if T == 0.0 {
+            return Max::max(&(flag.sign() * (spot - K)), 0.0);
+        }
+
+        autodiff_price(spot, K, r, b, volatility, T, flag)
+    }
+
+    fn audit_record(&self, spot: f64, volatility: f64) -> Option<AuditRecord> {
+        let (_, K, _, r, b) = self.unpack();
+        let option = Self::new(
+            b.into(),
+            spot.into(),
+            K.into(),
+            volatility.into(),
+            r.into(),
+            self.evaluation_date,
+            self.expiration_date,
+            self.option_type,
+        );
+
+        Some(BlackScholesMerton::audit_record(&option))
+    }
+
+    fn price_hyper_dual(&self, spot: HyperDual, volatility: HyperDual) -> Option<HyperDual> {
+        let (_, K, _, r, b) = self.unpack();
+        let T = self.year_fraction();
+        let flag = self.option_type;
+
+        if T == 0.0 {
+            return Some((flag.sign() * (spot - K)).max(HyperDual::constant(0.0)));
+        }
+
+        Some(autodiff_price(spot, K, r, b, volatility, T, flag))
+    }
 }
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -453,11 +728,11 @@ mod tests_black_scholes_merton {
     fn black_scholes_1973() {
         // Values from Haug
         let bsm = BlackScholesMerton::new(
-            0.08,
-            60.0,
-            65.0,
-            0.3,
-            0.08,
+            0.08.into(),
+            60.0.into(),
+            65.0.into(),
+            0.3.into(),
+            0.08.into(),
             None,
             today() + Duration::days(91),
             TypeFlag::Call,
@@ -469,15 +744,288 @@ mod tests_black_scholes_merton {
     fn merton_1973() {
         // Values from Haug
         let bsm = BlackScholesMerton::new(
-            0.1 - 0.05,
-            100.0,
-            95.0,
-            0.2,
-            0.1,
+            (0.1 - 0.05).into(),
+            100.0.into(),
+            95.0.into(),
+            0.2.into(),
+            0.1.into(),
             None,
             today() + Duration::days(182),
             TypeFlag::Put,
         );
         assert_approx_equal!(bsm.price(), 2.456571166461579, RUSTQUANT_EPSILON);
     }
+
+    #[test]
+    fn merton_1973_from_parsed_units() -> Result<(), RustQuantError> {
+        use crate::units::DividendYield;
+
+        let risk_free_rate: Rate = "10%".parse()?;
+        let dividend_yield: DividendYield = "500bp".parse()?;
+
+        let bsm = BlackScholesMertonBuilder::default()
+            .cost_of_carry(risk_free_rate - dividend_yield)
+            .underlying_price("100".parse()?)
+            .strike_price(Price::new(95.0)?)
+            .volatility("20%".parse()?)
+            .risk_free_rate(risk_free_rate)
+            .expiration_date(today() + Duration::days(182))
+            .option_type(TypeFlag::Put)
+            .build()
+            .unwrap();
+        let expected = BlackScholesMerton::new(
+            (0.1 - 0.05).into(),
+            100.0.into(),
+            95.0.into(),
+            0.2.into(),
+            0.1.into(),
+            None,
+            today() + Duration::days(182),
+            TypeFlag::Put,
+        );
+        assert_approx_equal!(bsm.price(), expected.price(), RUSTQUANT_EPSILON);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_audit_record_replays_bit_for_bit() {
+        let evaluation_date = time::macros::date!(2024 - 01 - 02);
+        let option = BlackScholesMerton::new(
+            0.03.into(),
+            101.3.into(),
+            97.0.into(),
+            0.237.into(),
+            0.045.into(),
+            Some(evaluation_date),
+            evaluation_date + Duration::days(137),
+            TypeFlag::Put,
+        );
+
+        let mut collector = AuditCollector::new();
+        let price = option.price_audited(&mut collector);
+        let greeks = option.greeks_audited(GreeksBackend::HyperDual, &mut collector);
+
+        let records = collector.into_records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(price.to_bits(), option.price().to_bits());
+        assert!(records[0].intermediates.contains_key("d1"));
+        assert_eq!(records[1].label("greeks_backend").unwrap(), "HyperDual");
+
+        for record in &records {
+            let stored = AuditRecord::from_json(&record.to_json().unwrap()).unwrap();
+            let replayed = BlackScholesMerton::from_audit_record(&stored).unwrap();
+
+            assert_eq!(replayed.price().to_bits(), record.result.to_bits());
+            assert_eq!(replayed.audit_record(), records[0]);
+        }
+
+        let replayed = BlackScholesMerton::from_audit_record(&records[1]).unwrap();
+        assert_eq!(replayed.greeks(GreeksBackend::HyperDual), greeks);
+        assert_eq!(
+            records[1].outputs["delta"].to_bits(),
+            greeks.delta.to_bits()
+        );
+
+        // At expiry there is no d1 or d2, and the record still serializes.
+        let expired = BlackScholesMerton::new(
+            0.03.into(),
+            101.3.into(),
+            97.0.into(),
+            0.237.into(),
+            0.045.into(),
+            Some(evaluation_date),
+            evaluation_date,
+            TypeFlag::Put,
+        );
+        let record = AuditRecord::from_json(&expired.audit_record().to_json().unwrap()).unwrap();
+        assert!(!record.intermediates.contains_key("d1"));
+        assert_eq!(record.result, 0.0);
+
+        assert!(BlackScholesMerton::from_audit_record(&AuditRecord::new("Other")).is_err());
+    }
+
+    #[test]
+    fn test_validate() {
+        let evaluation_date = time::macros::date!(2024 - 01 - 02);
+        let option = |s: f64, v: f64| {
+            BlackScholesMerton::new(
+                0.05.into(),
+                s.into(),
+                100.0.into(),
+                v.into(),
+                0.08.into(),
+                Some(evaluation_date),
+                evaluation_date + Duration::days(182),
+                TypeFlag::Call,
+            )
+        };
+
+        for s in [50.0, 100.0, 200.0] {
+            for v in [0.01, 0.3, 2.0] {
+                assert!(option(s, v).validate().is_empty());
+            }
+        }
+
+        // A negative volatility prices the deep in-the-money call near zero
+        // and the put below zero.
+        let violations = option(150.0, -0.3).validate();
+        assert_eq!(violations.len(), 2);
+        assert!(matches!(
+            violations[0],
+            ArbitrageViolation::LowerBound {
+                type_flag: TypeFlag::Call,
+                ..
+            }
+        ));
+        assert!(matches!(
+            violations[1],
+            ArbitrageViolation::LowerBound {
+                type_flag: TypeFlag::Put,
+                ..
+            }
+        ));
+        assert!(violations
+            .iter()
+            .all(|violation| violation.magnitude() > 40.0));
+    }
+
+    #[test]
+    fn test_grids_match_scalar_pricer() {
+        let evaluation_date = time::macros::datetime!(2024-01-02 0:00 UTC);
+        let strikes: Vec<f64> = (0..50).map(|i| 50.0 + 2.0 * f64::from(i)).collect();
+        let expiries: Vec<OffsetDateTime> = (1..=20)
+            .map(|i| evaluation_date + Duration::days(30 * i))
+            .collect();
+
+        for option_type in [TypeFlag::Call, TypeFlag::Put] {
+            let option = BlackScholesMerton::new(
+                0.03.into(),
+                100.0.into(),
+                100.0.into(),
+                0.25.into(),
+                0.05.into(),
+                Some(evaluation_date.date()),
+                evaluation_date.date(),
+                option_type,
+            );
+
+            let prices = option.price_grid(&strikes, &expiries);
+            let greeks = option.greeks_grid(&strikes, &expiries);
+
+            assert_eq!(prices.shape(), (50, 20));
+            assert_eq!(greeks.price, prices);
+
+            for (i, &strike_price) in strikes.iter().enumerate() {
+                for (j, expiry) in expiries.iter().enumerate() {
+                    let scalar = BlackScholesMerton {
+                        strike_price,
+                        expiration_date: expiry.date(),
+                        ..option
+                    };
+
+                    assert_eq!(prices[(i, j)], scalar.price());
+                    assert_eq!(greeks.delta[(i, j)], scalar.delta());
+                    assert_eq!(greeks.gamma[(i, j)], scalar.gamma());
+                    assert_eq!(greeks.vega[(i, j)], scalar.vega());
+                    assert_eq!(greeks.theta[(i, j)], scalar.theta());
+                    assert_eq!(greeks.rho[(i, j)], scalar.rho());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_expiry_edge_cases() {
+        let evaluation_date = time::macros::date!(2024 - 01 - 02);
+        let option = |expiration_date, option_type| {
+            BlackScholesMerton::new(
+                0.05.into(),
+                110.0.into(),
+                100.0.into(),
+                0.3.into(),
+                0.05.into(),
+                Some(evaluation_date),
+                expiration_date,
+                option_type,
+            )
+        };
+
+        // Expiring today (the dates carry no time of day, so this also covers
+        // expiry a second after valuation): the intrinsic value.
+        for (option_type, intrinsic) in [(TypeFlag::Call, 10.0), (TypeFlag::Put, 0.0)] {
+            let expiring = option(evaluation_date, option_type);
+
+            assert_eq!(expiring.price(), intrinsic);
+            assert_eq!(expiring.try_price(), Ok(intrinsic));
+        }
+
+        // Expired yesterday.
+        let expired = option(evaluation_date - Duration::days(1), TypeFlag::Call);
+        assert!(matches!(
+            expired.try_price(),
+            Err(PricingError::Expired(years)) if years > 0.0
+        ));
+
+        // Expiring tomorrow: the usual price.
+        let live = option(evaluation_date + Duration::days(1), TypeFlag::Call);
+        assert_eq!(live.try_price(), Ok(live.price()));
+        assert!(live.price() > 10.0);
+    }
+
+    #[test]
+    fn test_hyper_dual_greeks_match_closed_form() {
+        let evaluation_date = time::macros::date!(2024 - 01 - 02);
+
+        for option_type in [TypeFlag::Call, TypeFlag::Put] {
+            for days in [1, 30, 365, 730] {
+                for underlying_price in [40.0, 95.0, 100.0, 160.0] {
+                    for volatility in [0.1, 0.45] {
+                        let bsm = BlackScholesMerton::new(
+                            (0.05 - 0.02).into(),
+                            underlying_price.into(),
+                            100.0.into(),
+                            volatility.into(),
+                            0.05.into(),
+                            Some(evaluation_date),
+                            evaluation_date + Duration::days(days),
+                            option_type,
+                        );
+                        let greeks = bsm.greeks(GreeksBackend::HyperDual);
+
+                        let case =
+                            format!("{option_type:?} S={underlying_price} v={volatility} {days}d");
+                        let close = |a: f64, b: f64| (a - b).abs() <= 1e-12 * b.abs().max(1.0);
+
+                        assert!(close(greeks.price, bsm.price()), "price {case}");
+                        assert!(close(greeks.delta, bsm.delta()), "delta {case}");
+                        assert!(close(greeks.gamma, bsm.gamma()), "gamma {case}");
+                        assert!(close(greeks.vega, bsm.vega()), "vega {case}");
+                        assert!(close(greeks.vanna, bsm.vanna()), "vanna {case}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_tape_greeks_approximate_closed_form() {
+        let bsm = BlackScholesMerton::new(
+            0.03.into(),
+            105.0.into(),
+            100.0.into(),
+            0.25.into(),
+            0.05.into(),
+            Some(time::macros::date!(2024 - 01 - 02)),
+            time::macros::date!(2024 - 07 - 02),
+            TypeFlag::Put,
+        );
+        let greeks = bsm.greeks(GreeksBackend::Tape);
+
+        assert_approx_equal!(greeks.price, bsm.price(), 1e-12);
+        assert_approx_equal!(greeks.delta, bsm.delta(), 1e-12);
+        assert_approx_equal!(greeks.vega, bsm.vega(), 1e-10);
+        assert_approx_equal!(greeks.gamma, bsm.gamma(), 1e-6);
+        assert_approx_equal!(greeks.vanna, bsm.vanna(), 1e-6);
+    }
 }
@@ -0,0 +1,386 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Calendar (time) spreads: long a far-expiry option and short a
+//! near-expiry option with the same strike, each priced with the
+//! Black-Scholes-Merton formula at its own point of the volatility term
+//! structure.
+//!
+//! The spread is long vega in the far option and short vega in the near
+//! one, and at the money it earns time decay: the near option loses value
+//! faster than the far one.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::PricingError;
+use crate::instruments::options::{black_scholes, TypeFlag};
+use crate::math::rootfinding::{
+    brent::Brent,
+    rootfinder::{Rootfinder, RootfinderData},
+};
+use crate::time::{DayCountConvention, ExpiryConvention};
+
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Calendar spread: long the far-expiry option, short the near-expiry one.
+#[derive(Debug, Clone, Copy)]
+pub struct CalendarSpread {
+    /// Expiry of the short (near) option.
+    pub near_expiry: OffsetDateTime,
+    /// Expiry of the long (far) option.
+    pub far_expiry: OffsetDateTime,
+    /// K - The common strike.
+    pub strike: f64,
+    /// S - The underlying price.
+    pub s: f64,
+    /// r - The risk-free rate.
+    pub r: f64,
+    /// q - The continuous dividend yield.
+    pub q: f64,
+    /// Implied volatility to the near expiry.
+    pub near_vol: f64,
+    /// Implied volatility to the far expiry.
+    pub far_vol: f64,
+    /// Evaluation date (optional, defaults to now).
+    pub evaluation_date: Option<OffsetDateTime>,
+    /// Local time of day at which both options expire (optional; without
+    /// one, time to expiry is counted in whole days).
+    pub expiry_convention: Option<ExpiryConvention>,
+}
+
+/// Black-Scholes-Merton price, vega, and theta of one leg.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Leg {
+    pub(crate) price: f64,
+    pub(crate) vega: f64,
+    pub(crate) theta: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl CalendarSpread {
+    fn year_fraction(&self, expiry: OffsetDateTime) -> f64 {
+        let evaluation_date = self.evaluation_date.unwrap_or(OffsetDateTime::now_utc());
+
+        match self.expiry_convention {
+            Some(convention) => convention.year_fraction(evaluation_date, expiry.date()),
+            None => DayCountConvention::default()
+                .day_count_factor(evaluation_date.date(), expiry.date()),
+        }
+    }
+
+    /// Time to the near expiry, in years.
+    #[must_use]
+    pub fn near_year_fraction(&self) -> f64 {
+        self.year_fraction(self.near_expiry)
+    }
+
+    /// Time to the far expiry, in years.
+    #[must_use]
+    pub fn far_year_fraction(&self) -> f64 {
+        self.year_fraction(self.far_expiry)
+    }
+
+    fn legs(&self, flag: TypeFlag) -> (Leg, Leg) {
+        let leg =
+            |v: f64, t: f64| black_scholes_merton(self.s, self.strike, self.r, self.q, v, t, flag);
+
+        (
+            leg(self.near_vol, self.near_year_fraction()),
+            leg(self.far_vol, self.far_year_fraction()),
+        )
+    }
+
+    /// Spread price: the far option less the near option.
+    #[must_use]
+    pub fn price(&self, flag: TypeFlag) -> f64 {
+        let (near, far) = self.legs(flag);
+
+        far.price - near.price
+    }
+
+    /// Spread price (see [`Self::price`]), checking the dates.
+    ///
+    /// A near option expiring on the evaluation date is worth its intrinsic
+    /// value; without an expiry convention only dates are counted, so this
+    /// includes expiry later that day.
+    ///
+    /// # Errors
+    /// - [`PricingError::Expired`] if the near option expired before the
+    ///   evaluation date.
+    /// - [`PricingError::InvalidDates`] if the near expiry is after the far
+    ///   expiry.
+    pub fn try_price(&self, flag: TypeFlag) -> Result<f64, PricingError> {
+        let T = self.near_year_fraction();
+        if T < 0.0 {
+            return Err(PricingError::Expired(-T));
+        }
+
+        if self.near_expiry > self.far_expiry {
+            return Err(PricingError::InvalidDates(
+                "Near expiry is after the far expiry.",
+            ));
+        }
+
+        Ok(self.price(flag))
+    }
+
+    /// Sensitivity of the spread to the near volatility (the negative of
+    /// the near option's vega, the same for calls and puts).
+    #[must_use]
+    pub fn vega_near(&self) -> f64 {
+        -self.legs(TypeFlag::Call).0.vega
+    }
+
+    /// Sensitivity of the spread to the far volatility (the far option's
+    /// vega, the same for calls and puts).
+    #[must_use]
+    pub fn vega_far(&self) -> f64 {
+        self.legs(TypeFlag::Call).1.vega
+    }
+
+    /// Theta of the spread: the change in value per year as time passes,
+    /// all else equal.
+    #[must_use]
+    pub fn theta(&self, flag: TypeFlag) -> f64 {
+        let (near, far) = self.legs(flag);
+
+        far.theta - near.theta
+    }
+}
+
+/// Black-Scholes-Merton price, vega, and theta ($\partial V / \partial t$,
+/// per year) of a European option with time to expiry `t`, from
+/// [`black_scholes`].
+pub(crate) fn black_scholes_merton(
+    s: f64,
+    k: f64,
+    r: f64,
+    q: f64,
+    v: f64,
+    t: f64,
+    flag: TypeFlag,
+) -> Leg {
+    Leg {
+        price: black_scholes::price(s, k, r, q, v, t, flag),
+        vega: black_scholes::vega(s, k, r, q, v, t),
+        theta: black_scholes::theta(s, k, r, q, v, t, flag),
+    }
+}
+
+/// Increase in the near-term implied volatility that makes a calendar
+/// spread worthless, i.e. lifts the near option's price to `far_price`.
+///
+/// The near option's current implied volatility is backed out of
+/// `near_price` (with expiry `near_t` in years). Returns `NaN` if either
+/// price is outside the no-arbitrage bounds of the near option.
+#[allow(clippy::too_many_arguments)]
+#[must_use]
+pub fn breakeven_vol_move(
+    near_price: f64,
+    far_price: f64,
+    s: f64,
+    k: f64,
+    r: f64,
+    q: f64,
+    near_t: f64,
+    flag: TypeFlag,
+) -> f64 {
+    let (lower, upper) = (1e-6, 5.0);
+    let price = |v: f64| black_scholes_merton(s, k, r, q, v, near_t, flag).price;
+
+    let implied = |target: f64| {
+        if !(price(lower) <= target && target <= price(upper)) {
+            return f64::NAN;
+        }
+
+        let f = |v: f64| price(v) - target;
+        let data = RootfinderData::new(1e-12, 0.01, lower, upper, true);
+
+        Brent::new(f, 0.2, data).solve()
+    };
+
+    implied(far_price) - implied(near_price)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_calendar_spread {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::options::BlackScholesMerton;
+    use time::macros::datetime;
+    use time::Duration;
+
+    fn spread(strike: f64) -> CalendarSpread {
+        let evaluation_date = datetime!(2024-01-02 0:00 UTC);
+
+        CalendarSpread {
+            near_expiry: evaluation_date + Duration::days(30),
+            far_expiry: evaluation_date + Duration::days(120),
+            strike,
+            s: 100.0,
+            r: 0.04,
+            q: 0.01,
+            near_vol: 0.25,
+            far_vol: 0.22,
+            evaluation_date: Some(evaluation_date),
+            expiry_convention: None,
+        }
+    }
+
+    #[test]
+    fn test_legs_match_black_scholes_merton() {
+        let spread = spread(105.0);
+
+        for flag in [TypeFlag::Call, TypeFlag::Put] {
+            let bsm = |v: f64, expiry: OffsetDateTime| {
+                BlackScholesMerton::new(
+                    (spread.r - spread.q).into(),
+                    spread.s.into(),
+                    spread.strike.into(),
+                    v.into(),
+                    spread.r.into(),
+                    spread.evaluation_date.map(|d| d.date()),
+                    expiry.date(),
+                    flag,
+                )
+            };
+            let near = bsm(spread.near_vol, spread.near_expiry);
+            let far = bsm(spread.far_vol, spread.far_expiry);
+
+            assert_approx_equal!(spread.price(flag), far.price() - near.price(), 1e-10);
+            assert_approx_equal!(spread.vega_near(), -near.vega(), 1e-10);
+            assert_approx_equal!(spread.vega_far(), far.vega(), 1e-10);
+            assert_approx_equal!(spread.theta(flag), far.theta() - near.theta(), 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_at_the_money_spread_earns_time_decay() {
+        for flag in [TypeFlag::Call, TypeFlag::Put] {
+            let atm = spread(100.0);
+            assert!(atm.price(flag) > 0.0);
+            assert!(atm.theta(flag) > 0.0, "{:?}", flag);
+
+            // Rolling the valuation forward a week increases the value.
+            let mut later = atm;
+            later.evaluation_date = atm.evaluation_date.map(|d| d + Duration::days(7));
+            assert!(later.price(flag) > atm.price(flag));
+
+            // Long far vega, short near vega.
+            assert!(atm.vega_far() > 0.0);
+            assert!(atm.vega_near() < 0.0);
+        }
+    }
+
+    #[test]
+    fn test_theta_matches_finite_difference() {
+        let spread = spread(100.0);
+        let (near_t, far_t) = (spread.near_year_fraction(), spread.far_year_fraction());
+        let h = 1e-5;
+
+        let price = |dt: f64| {
+            let leg = |v, t| black_scholes_merton(100.0, 100.0, 0.04, 0.01, v, t, TypeFlag::Call);
+            leg(0.22, far_t - dt).price - leg(0.25, near_t - dt).price
+        };
+
+        assert_approx_equal!(
+            spread.theta(TypeFlag::Call),
+            (price(h) - price(-h)) / (2.0 * h),
+            1e-5
+        );
+    }
+
+    #[test]
+    fn test_breakeven_vol_move() {
+        let atm = spread(100.0);
+        let t = atm.near_year_fraction();
+
+        for flag in [TypeFlag::Call, TypeFlag::Put] {
+            let (near, far) = atm.legs(flag);
+            let dv = breakeven_vol_move(near.price, far.price, 100.0, 100.0, 0.04, 0.01, t, flag);
+
+            assert!(dv > 0.0);
+
+            // At the shifted near volatility, the spread is worth nothing.
+            let mut shifted = atm;
+            shifted.near_vol += dv;
+            assert_approx_equal!(shifted.price(flag), 0.0, 1e-9);
+        }
+
+        // Beyond the upper bound of the near call, there is no breakeven.
+        let dv = breakeven_vol_move(2.0, 150.0, 100.0, 100.0, 0.04, 0.01, t, TypeFlag::Call);
+        assert!(dv.is_nan());
+    }
+
+    #[test]
+    fn test_calendar_spread_expiry_edge_cases() {
+        let spread = spread(95.0);
+        let evaluation_date = spread.evaluation_date.expect("Evaluation date is set.");
+
+        // Near option expiring at valuation, or a second later on the same
+        // day: the far option less the near option's intrinsic value.
+        for near_expiry in [evaluation_date, evaluation_date + Duration::seconds(1)] {
+            let expiring = CalendarSpread {
+                near_expiry,
+                ..spread
+            };
+
+            for (flag, intrinsic) in [(TypeFlag::Call, 5.0), (TypeFlag::Put, 0.0)] {
+                let far = BlackScholesMerton::new(
+                    (spread.r - spread.q).into(),
+                    spread.s.into(),
+                    spread.strike.into(),
+                    spread.far_vol.into(),
+                    spread.r.into(),
+                    Some(evaluation_date.date()),
+                    spread.far_expiry.date(),
+                    flag,
+                );
+
+                assert_approx_equal!(
+                    expiring.try_price(flag).expect("Near option is live."),
+                    far.price() - intrinsic,
+                    1e-10
+                );
+            }
+        }
+
+        let expired = CalendarSpread {
+            near_expiry: evaluation_date - Duration::days(1),
+            ..spread
+        };
+        assert!(matches!(
+            expired.try_price(TypeFlag::Call),
+            Err(PricingError::Expired(years)) if years > 0.0
+        ));
+
+        let inverted = CalendarSpread {
+            near_expiry: spread.far_expiry,
+            far_expiry: spread.near_expiry,
+            ..spread
+        };
+        assert!(matches!(
+            inverted.try_price(TypeFlag::Call),
+            Err(PricingError::InvalidDates(_))
+        ));
+    }
+}
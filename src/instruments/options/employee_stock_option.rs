@@ -0,0 +1,256 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Employee stock options (ESOs), valued with the Hull-White (2004) lattice
+//! model used for expensing under IFRS 2 and ASC 718.
+//!
+//! The stock follows a Cox-Ross-Rubinstein tree. On top of it:
+//!
+//! - the option cannot be exercised during the vesting period,
+//! - after vesting, the employee exercises once the stock price reaches
+//!   $M K$, a multiple $M$ of the strike,
+//! - the employee leaves the company at an annual rate $w$, so over each
+//!   time step the option ends with probability $1 - e^{-w \Delta t}$: it
+//!   is forfeited if unvested, and exercised (if in the money) otherwise.
+//!
+//! As for an American option, the option is also exercised after vesting
+//! wherever that is worth more than holding it. With $M \to \infty$ and
+//! $w = 0$ the value is that of an American call with a vesting blackout.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Employee stock option (a call on the company's stock).
+#[derive(Debug, Clone, Copy)]
+pub struct EmployeeStockOption {
+    /// S - Initial stock price.
+    pub initial_price: f64,
+    /// K - Strike price.
+    pub strike_price: f64,
+    /// T - Time to expiry, in years.
+    pub time_to_expiry: f64,
+    /// r - Risk-free rate.
+    pub risk_free_rate: f64,
+    /// q - Dividend yield.
+    pub dividend_yield: f64,
+    /// v - Volatility.
+    pub volatility: f64,
+    /// Vesting period, in years, during which the option cannot be exercised.
+    pub vesting_period: f64,
+    /// M - Exercise multiple: the option is exercised after vesting once the
+    /// stock price reaches `M * K` (`f64::INFINITY` to switch this off).
+    pub exercise_multiple: f64,
+    /// w - Annual employee exit rate.
+    pub exit_rate: f64,
+}
+
+/// Output of the employee stock option lattice.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EmployeeStockOptionValue {
+    /// Fair value of the option.
+    pub value: f64,
+    /// Expected life of the option, in years: the expected time until it is
+    /// exercised, forfeited, or expires, under the tree's (risk-neutral)
+    /// probabilities.
+    pub expected_life: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl EmployeeStockOption {
+    /// Fair value and expected life, on a tree with `n` steps.
+    ///
+    /// # Panics
+    /// Panics if `n` is zero.
+    #[must_use]
+    pub fn value(&self, n: usize) -> EmployeeStockOptionValue {
+        assert!(n > 0, "The tree needs at least one step.");
+
+        let (s0, k, t) = (self.initial_price, self.strike_price, self.time_to_expiry);
+        let b = self.risk_free_rate - self.dividend_yield;
+
+        let dt = t / n as f64;
+        let u = (self.volatility * dt.sqrt()).exp();
+        let d = 1.0 / u;
+        let p = ((b * dt).exp() - d) / (u - d);
+        let df = (-self.risk_free_rate * dt).exp();
+        let stay = (-self.exit_rate * dt).exp();
+
+        let stock_price = |i: usize, j: usize| s0 * u.powi(j as i32) * d.powi((i - j) as i32);
+
+        // Values and expected lives at expiry, indexed by the number of up moves.
+        let mut values: Vec<f64> = (0..=n).map(|j| (stock_price(n, j) - k).max(0.0)).collect();
+        let mut lives = vec![t; n + 1];
+
+        for i in (0..n).rev() {
+            let time = i as f64 * dt;
+            // Small tolerance so a vesting date on a node counts as vested.
+            let vested = time >= self.vesting_period - 1e-12 * t;
+
+            for j in 0..=i {
+                let s = stock_price(i, j);
+                let intrinsic = (s - k).max(0.0);
+
+                let hold = df * (p * values[j + 1] + (1.0 - p) * values[j]);
+                let hold_life = p * lives[j + 1] + (1.0 - p) * lives[j];
+
+                if vested
+                    && (s >= self.exercise_multiple * k || (intrinsic > 0.0 && intrinsic >= hold))
+                {
+                    values[j] = intrinsic;
+                    lives[j] = time;
+                } else {
+                    let exit_value = if vested { intrinsic } else { 0.0 };
+
+                    values[j] = stay * hold + (1.0 - stay) * exit_value;
+                    lives[j] = stay * hold_life + (1.0 - stay) * time;
+                }
+            }
+        }
+
+        EmployeeStockOptionValue {
+            value: values[0],
+            expected_life: lives[0],
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_employee_stock_option {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::options::black_scholes;
+
+    fn option(vesting_period: f64, exercise_multiple: f64, exit_rate: f64) -> EmployeeStockOption {
+        EmployeeStockOption {
+            initial_price: 40.0,
+            strike_price: 40.0,
+            time_to_expiry: 10.0,
+            risk_free_rate: 0.05,
+            dividend_yield: 0.0,
+            volatility: 0.3,
+            vesting_period,
+            exercise_multiple,
+            exit_rate,
+        }
+    }
+
+    fn black_scholes_call(option: &EmployeeStockOption) -> f64 {
+        let (s, k, t) = (
+            option.initial_price,
+            option.strike_price,
+            option.time_to_expiry,
+        );
+        let (r, q, v) = (
+            option.risk_free_rate,
+            option.dividend_yield,
+            option.volatility,
+        );
+
+        black_scholes::call_price(s, k, r, q, v, t)
+    }
+
+    #[test]
+    fn test_two_step_tree_by_hand() {
+        // S = K = 10, T = 2 with yearly steps, vesting after one year,
+        // M = 1.2 and a 10% exit rate.
+        let eso = EmployeeStockOption {
+            initial_price: 10.0,
+            strike_price: 10.0,
+            time_to_expiry: 2.0,
+            risk_free_rate: 0.05,
+            dividend_yield: 0.0,
+            volatility: 0.2,
+            vesting_period: 1.0,
+            exercise_multiple: 1.2,
+            exit_rate: 0.1,
+        };
+
+        let u = 0.2_f64.exp();
+        let d = 1.0 / u;
+        let p = (0.05_f64.exp() - d) / (u - d);
+        let df = (-0.05_f64).exp();
+        let stay = (-0.1_f64).exp();
+
+        // After one year: the up node (12.21 >= 12) is exercised, and the
+        // down node is out of the money with nothing to gain at expiry.
+        let (up_value, up_life) = (10.0 * u - 10.0, 1.0);
+        let (down_value, down_life) = (0.0, stay * 2.0 + (1.0 - stay) * 1.0);
+
+        // At inception the option is unvested, so exits forfeit it.
+        let value = stay * df * (p * up_value + (1.0 - p) * down_value);
+        let life = stay * (p * up_life + (1.0 - p) * down_life);
+
+        let result = eso.value(2);
+        assert_approx_equal!(result.value, value, 1e-12);
+        assert_approx_equal!(result.expected_life, life, 1e-12);
+    }
+
+    #[test]
+    fn test_limit_is_american_with_vesting_blackout() {
+        // Without dividends an American call is never exercised early, so
+        // with M -> infinity and no exits this is the Black-Scholes price.
+        let limit = option(3.0, f64::INFINITY, 0.0);
+        let result = limit.value(1000);
+
+        assert_approx_equal!(result.value, black_scholes_call(&limit), 0.02);
+        assert_approx_equal!(result.expected_life, 10.0, 1e-12);
+
+        // With dividends: between the European price (vesting at expiry)
+        // and the American price (no vesting), and decreasing in vesting.
+        let dividends = |vesting_period| EmployeeStockOption {
+            dividend_yield: 0.03,
+            ..option(vesting_period, f64::INFINITY, 0.0)
+        };
+        let european = dividends(10.0).value(1000).value;
+        let blackout = dividends(3.0).value(1000).value;
+        let american = dividends(0.0).value(1000).value;
+
+        assert_approx_equal!(european, black_scholes_call(&dividends(10.0)), 0.02);
+        assert!(european < blackout && blackout < american);
+        assert!(dividends(3.0).value(1000).expected_life < 10.0);
+    }
+
+    #[test]
+    fn test_exercise_multiple_and_exit_rate_reduce_value() {
+        let plain = option(3.0, f64::INFINITY, 0.0).value(500);
+        let multiple = option(3.0, 2.0, 0.0).value(500);
+        let exits = option(3.0, 2.0, 0.1).value(500);
+
+        assert!(multiple.value < plain.value);
+        assert!(exits.value < multiple.value);
+        assert!(multiple.expected_life < plain.expected_life);
+        assert!(exits.expected_life < multiple.expected_life);
+
+        // A lower multiple means earlier exercise.
+        let early = option(3.0, 1.5, 0.1).value(500);
+        assert!(early.expected_life < exits.expected_life);
+    }
+
+    #[test]
+    fn test_expected_life_with_exits_only() {
+        // Without dividends, the option only ends early through exits, so
+        // its expected life is that of an exit time capped at expiry.
+        let w = 0.1;
+        let result = option(0.0, f64::INFINITY, w).value(2000);
+
+        assert_approx_equal!(
+            result.expected_life,
+            (1.0 - (-w * 10.0_f64).exp()) / w,
+            1e-2
+        );
+    }
+}
@@ -183,10 +183,7 @@ impl FiniteDifferencePricer {
     }
 
     fn payoff(&self, s: f64) -> f64 {
-        match self.type_flag {
-            TypeFlag::Call => (s - self.strike_price).max(0.0),
-            TypeFlag::Put => (self.strike_price - s).max(0.0),
-        }
+        self.type_flag.payoff(s, self.strike_price)
     }
 
     fn american_time_stop_step(&self, v: Vec<f64>, tau: f64, x_min: f64, delta_x: f64) -> Vec<f64> {
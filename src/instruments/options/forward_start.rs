@@ -7,15 +7,244 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-/// Forward start option.
-#[derive(Debug, Clone)]
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FORWARD START OPTION STRUCT
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use time::Date;
+
+use super::{black_scholes, check_no_arbitrage, ArbitrageViolation, TypeFlag};
+use crate::{
+    error::PricingError,
+    time::{today, DayCountConvention},
+};
+
+/// Forward Start Option parameters struct
+#[allow(clippy::module_name_repetitions)]
+#[derive(derive_builder::Builder, Debug)]
 pub struct ForwardStartOption {
-    /// The option contract.
-    pub contract: OptionContract,
+    /// `S` - Initial price of the underlying.
+    pub initial_price: f64,
+    /// `alpha` - The proportion of S to set the strike price.
+    /// Three possibilities:
+    ///     - alpha < 1: call (put) will start (1 - alpha)% in-the-money (out-of-the-money).
+    ///     - alpha = 1: the option starts at-the-money.
+    ///     - alpha > 1: call (put) will start (alpha - 1)% out-of-the-money (in-the-money).
+    pub alpha: f64,
+    /// `r` - Risk-free rate parameter.
+    pub risk_free_rate: f64,
+    /// `v` - Volatility parameter.
+    pub volatility: f64,
+    /// `q` - Dividend rate.
+    pub dividend_rate: f64,
+
+    /// `valuation_date` - Valuation date.
+    #[builder(default = "None")]
+    pub valuation_date: Option<Date>,
+
+    /// `start` - Time until the start of the option (`T` in most literature).
+    pub start: Date,
+    /// `end` - Time until the end of the option (`t` in most literature).
+    pub end: Date,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FORWARD START OPTION IMPLEMENTATION
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl ForwardStartOption {
+    /// Rubinstein (1990) Forward Start Option Price formula.
+    /// Returns a tuple: `(call_price, put_price)`
+    ///
+    /// At the start the option is $S_t / S$ options struck at $\alpha$ on a
+    /// unit spot, so its price is
+    /// $S e^{(b - r) t} \, BS(1, \alpha, r, q, \sigma, T - t)$.
+    ///
+    /// # Note:
+    /// * `b = r - q` - The cost of carry.
+    #[must_use]
+    pub fn price(&self) -> (f64, f64) {
+        let S = self.initial_price;
+        let a = self.alpha;
+
+        let r = self.risk_free_rate;
+        let v = self.volatility;
+        let q = self.dividend_rate;
+
+        let T = DayCountConvention::default()
+            .day_count_factor(self.valuation_date.unwrap_or(today()), self.end);
+
+        let t = DayCountConvention::default()
+            .day_count_factor(self.valuation_date.unwrap_or(today()), self.start);
+
+        let b = r - q;
+
+        // Starting and ending together, this is the intrinsic value against
+        // the strike set at the start.
+        let S_t = S * ((b - r) * t).exp();
+        let price = |flag| S_t * black_scholes::price(1.0, a, r, q, v, T - t, flag);
+
+        (price(TypeFlag::Call), price(TypeFlag::Put))
+    }
+
+    /// Rubinstein (1990) price (see [`Self::price`]), checking the dates.
+    ///
+    /// # Errors
+    /// - [`PricingError::Expired`] if the option ends before the valuation
+    ///   date.
+    /// - [`PricingError::InvalidDates`] if it starts after it ends, or has
+    ///   already started (its strike is then known, and it is a plain
+    ///   European option).
+    pub fn try_price(&self) -> Result<(f64, f64), PricingError> {
+        let valuation_date = self.valuation_date.unwrap_or(today());
+
+        DayCountConvention::default().time_to_expiry(valuation_date, self.end)?;
+
+        if self.start > self.end {
+            return Err(PricingError::InvalidDates(
+                "Forward start option starts after it ends.",
+            ));
+        }
+        if self.start < valuation_date {
+            return Err(PricingError::InvalidDates(
+                "Forward start option started before the valuation date.",
+            ));
+        }
+
+        Ok(self.price())
+    }
+
+    /// Check the `(call, put)` prices against put-call parity and the price
+    /// bounds, with the spot at the end and the strike $\alpha S_t$ set at
+    /// the start.
+    ///
+    /// See [`crate::instruments::options::arbitrage`].
+    #[must_use]
+    pub fn validate(&self) -> Vec<ArbitrageViolation> {
+        let (call, put) = self.price();
+
+        let valuation_date = self.valuation_date.unwrap_or(today());
+        let T = DayCountConvention::default().day_count_factor(valuation_date, self.end);
+        let t = DayCountConvention::default().day_count_factor(valuation_date, self.start);
+
+        let (S, r, q) = (self.initial_price, self.risk_free_rate, self.dividend_rate);
+        let forward = S * (-q * T).exp();
+        let strike = self.alpha * S * (-q * t).exp() * (-r * (T - t)).exp();
+
+        check_no_arbitrage(call, put, forward, strike)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_forward_start {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    #[test]
+    fn TEST_forward_start_option() {
+        let start = today() + time::Duration::days(91);
+        let end = today() + time::Duration::days(365);
+
+        let ForwardStart = ForwardStartOption {
+            initial_price: 60.0,
+            alpha: 1.1,
+            risk_free_rate: 0.08,
+            volatility: 0.3,
+            dividend_rate: 0.04,
+            valuation_date: None,
+            start,
+            end,
+        };
+
+        let prices = ForwardStart.price();
+
+        // Call price example from Haug's book.
+        assert_approx_equal!(prices.0, 4.402888269001168, 1e-2);
+        assert!(ForwardStart.validate().is_empty());
+    }
+
+    #[test]
+    fn test_forward_start_validate() {
+        let valuation_date = time::macros::date!(2024 - 01 - 02);
+
+        let mut option = ForwardStartOption {
+            initial_price: 60.0,
+            alpha: 1.1,
+            risk_free_rate: 0.08,
+            volatility: 0.3,
+            dividend_rate: 0.04,
+            valuation_date: Some(valuation_date),
+            start: valuation_date + time::Duration::days(91),
+            end: valuation_date + time::Duration::days(365),
+        };
+
+        for alpha in [0.5, 1.0, 1.5] {
+            option.alpha = alpha;
+            assert!(option.validate().is_empty());
+        }
+
+        // A negative volatility flips the sign of d1 and d2, pushing the
+        // in-the-money call below its intrinsic value.
+        option.alpha = 0.5;
+        option.volatility = -0.3;
+
+        let violations = option.validate();
+        assert!(violations.iter().any(|violation| matches!(
+            violation,
+            ArbitrageViolation::LowerBound {
+                type_flag: crate::instruments::options::TypeFlag::Call,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn test_forward_start_expiry_edge_cases() {
+        let valuation_date = time::macros::date!(2024 - 01 - 02);
+        let option = |start, end| ForwardStartOption {
+            initial_price: 60.0,
+            alpha: 0.9,
+            risk_free_rate: 0.08,
+            volatility: 0.3,
+            dividend_rate: 0.04,
+            valuation_date: Some(valuation_date),
+            start,
+            end,
+        };
+
+        // Starting and expiring at valuation (the dates carry no time of
+        // day, so this also covers expiry a second after valuation): the
+        // intrinsic value against the strike 0.9 S.
+        let expiring = option(valuation_date, valuation_date);
+        let (call, put) = expiring.price();
+        assert_approx_equal!(call, 6.0, 1e-12);
+        assert_eq!(put, 0.0);
+        assert_eq!(expiring.try_price(), Ok((call, put)));
 
-    /// Strike price of the option.
-    pub strike: f64,
+        // Starting and ending on the same future date: the intrinsic value
+        // of the forward.
+        let start = valuation_date + time::Duration::days(91);
+        let (call, put) = option(start, start).price();
+        assert!(call.is_finite() && call > 0.0);
+        assert_eq!(put, 0.0);
 
-    /// Forward start date.
-    pub start_date: Date,
+        // Expired, or with inconsistent dates.
+        let yesterday = valuation_date - time::Duration::days(1);
+        assert!(matches!(
+            option(yesterday, yesterday).try_price(),
+            Err(PricingError::Expired(years)) if years > 0.0
+        ));
+        assert!(matches!(
+            option(start, valuation_date).try_price(),
+            Err(PricingError::InvalidDates(_))
+        ));
+        assert!(matches!(
+            option(yesterday, start).try_price(),
+            Err(PricingError::InvalidDates(_))
+        ));
+    }
 }
@@ -0,0 +1,311 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! European and American options on futures (equity index, FX, and
+//! commodity futures).
+//!
+//! A futures price has zero cost of carry: it is a martingale under the
+//! risk-neutral measure, while the option premium is still discounted at
+//! the risk-free rate. European options are priced with Black (1976), that
+//! is the Black-Scholes-Merton formulas with the futures price as the
+//! underlying and a "dividend yield" equal to the rate.
+//!
+//! American options are priced on a Cox-Ross-Rubinstein tree with zero
+//! drift. Unlike options on a non-dividend paying stock, both calls and
+//! puts on futures may be exercised early: exercising a deep in the money
+//! call receives $F - K$ now instead of its discounted expectation.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::instruments::options::{black_scholes, TypeFlag};
+use crate::units::{DividendYield, Price, Rate, TimeFraction, Volatility};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Option on a futures contract.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FuturesOption {
+    /// F - Futures price.
+    pub futures_price: f64,
+    /// K - Strike price.
+    pub strike_price: f64,
+    /// r - Risk-free rate used for discounting.
+    pub risk_free_rate: f64,
+    /// v - Volatility of the futures price.
+    pub volatility: f64,
+    /// T - Time to the option's expiry, in years.
+    pub time_to_expiry: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl FuturesOption {
+    /// New option on a quoted futures price.
+    #[must_use]
+    pub fn new(
+        futures_price: Price,
+        strike_price: Price,
+        risk_free_rate: Rate,
+        volatility: Volatility,
+        time_to_expiry: TimeFraction,
+    ) -> Self {
+        Self {
+            futures_price: futures_price.value(),
+            strike_price: strike_price.value(),
+            risk_free_rate: risk_free_rate.value(),
+            volatility: volatility.value(),
+            time_to_expiry: time_to_expiry.value(),
+        }
+    }
+
+    /// New option on a futures contract delivering at `futures_maturity`,
+    /// with the futures price derived from the spot by cost of carry,
+    /// $F = S e^{(r - q) T_F}$.
+    ///
+    /// For an equity index `dividend_yield` is the index yield; for FX
+    /// futures it is the foreign interest rate.
+    #[must_use]
+    pub fn from_spot(
+        spot: Price,
+        strike_price: Price,
+        risk_free_rate: Rate,
+        dividend_yield: DividendYield,
+        volatility: Volatility,
+        time_to_expiry: TimeFraction,
+        futures_maturity: TimeFraction,
+    ) -> Self {
+        let carry = risk_free_rate.value() - dividend_yield.value();
+        let futures_price = spot.value() * (carry * futures_maturity.value()).exp();
+
+        Self::new(
+            futures_price.into(),
+            strike_price,
+            risk_free_rate,
+            volatility,
+            time_to_expiry,
+        )
+    }
+
+    /// European price, from Black (1976).
+    #[must_use]
+    pub fn european_price(&self, flag: TypeFlag) -> f64 {
+        let r = self.risk_free_rate;
+
+        black_scholes::price(
+            self.futures_price,
+            self.strike_price,
+            r,
+            r,
+            self.volatility,
+            self.time_to_expiry,
+            flag,
+        )
+    }
+
+    /// European delta with respect to the futures price,
+    /// $\pm e^{-rT} N(\pm d_1)$.
+    #[must_use]
+    pub fn european_delta(&self, flag: TypeFlag) -> f64 {
+        let r = self.risk_free_rate;
+
+        black_scholes::delta(
+            self.futures_price,
+            self.strike_price,
+            r,
+            r,
+            self.volatility,
+            self.time_to_expiry,
+            flag,
+        )
+    }
+
+    /// American price, on a Cox-Ross-Rubinstein tree with `n` steps.
+    ///
+    /// # Panics
+    /// Panics if `n` is zero.
+    #[must_use]
+    pub fn american_price(&self, flag: TypeFlag, n: usize) -> f64 {
+        assert!(n > 0, "The tree needs at least one step.");
+
+        let (f0, k) = (self.futures_price, self.strike_price);
+
+        let dt = self.time_to_expiry.max(0.0) / n as f64;
+        let u = (self.volatility * dt.sqrt()).exp();
+        let d = 1.0 / u;
+        // Zero cost of carry: the futures price is a martingale.
+        let p = (1.0 - d) / (u - d);
+        let df = (-self.risk_free_rate * dt).exp();
+
+        let futures_price = |i: usize, j: usize| f0 * u.powi(j as i32) * d.powi((i - j) as i32);
+
+        // Degenerate tree (no time or no volatility): exercise now or at expiry.
+        if !(p > 0.0 && p < 1.0) {
+            let discount = (-self.risk_free_rate * self.time_to_expiry.max(0.0)).exp();
+
+            return flag.payoff(f0, k).max(discount * flag.payoff(f0, k));
+        }
+
+        // Option values at expiry, indexed by the number of up moves.
+        let mut values: Vec<f64> = (0..=n)
+            .map(|j| flag.payoff(futures_price(n, j), k))
+            .collect();
+
+        for i in (0..n).rev() {
+            for j in 0..=i {
+                let hold = df * (p * values[j + 1] + (1.0 - p) * values[j]);
+
+                values[j] = hold.max(flag.payoff(futures_price(i, j), k));
+            }
+        }
+
+        values[0]
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_futures_option {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::options::black_76::black_76_undiscounted;
+    use crate::RUSTQUANT_EPSILON;
+
+    fn option(futures_price: f64, strike_price: f64, risk_free_rate: f64) -> FuturesOption {
+        FuturesOption {
+            futures_price,
+            strike_price,
+            risk_free_rate,
+            volatility: 0.25,
+            time_to_expiry: 0.5,
+        }
+    }
+
+    #[test]
+    fn test_haug_black_76() {
+        // Haug (2007), the Black (1976) option on Brent blend futures:
+        // F = K = 19, T = 0.75, r = 10%, v = 28%. Call and put are 1.7011.
+        let brent = FuturesOption::new(
+            19.0.into(),
+            19.0.into(),
+            0.1.into(),
+            0.28.into(),
+            0.75.into(),
+        );
+
+        assert_approx_equal!(brent.european_price(TypeFlag::Call), 1.7011, 1e-4);
+        assert_approx_equal!(brent.european_price(TypeFlag::Put), 1.7011, 1e-4);
+    }
+
+    #[test]
+    fn test_european_matches_black_76() {
+        let o = option(105.0, 100.0, 0.04);
+        let discount = (-o.risk_free_rate * o.time_to_expiry).exp();
+
+        for flag in [TypeFlag::Call, TypeFlag::Put] {
+            let black = black_76_undiscounted(o.futures_price, o.strike_price, 0.25, 0.5, flag);
+
+            assert_approx_equal!(o.european_price(flag), discount * black, 1e-12);
+        }
+
+        // Delta of a call less a put is the discount factor.
+        assert_approx_equal!(
+            o.european_delta(TypeFlag::Call) - o.european_delta(TypeFlag::Put),
+            discount,
+            RUSTQUANT_EPSILON
+        );
+    }
+
+    #[test]
+    fn test_from_spot() {
+        // FX futures: 1.25 spot, 5% domestic and 3% foreign rates, futures
+        // delivering in one year.
+        let fx = FuturesOption::from_spot(
+            1.25.into(),
+            1.25.into(),
+            0.05.into(),
+            0.03.into(),
+            0.1.into(),
+            0.5.into(),
+            1.0.into(),
+        );
+
+        assert_approx_equal!(fx.futures_price, 1.25 * 0.02_f64.exp(), RUSTQUANT_EPSILON);
+
+        // With the futures delivering at the option's expiry the European
+        // price is the Black-Scholes-Merton price on the spot.
+        let equity = FuturesOption::from_spot(
+            100.0.into(),
+            95.0.into(),
+            0.05.into(),
+            0.02.into(),
+            0.2.into(),
+            0.5.into(),
+            0.5.into(),
+        );
+
+        assert_approx_equal!(
+            equity.european_price(TypeFlag::Put),
+            black_scholes::put_price(100.0, 95.0, 0.05, 0.02, 0.2, 0.5),
+            1e-12
+        );
+    }
+
+    #[test]
+    fn test_american_call_and_put_are_symmetric_at_the_money() {
+        // With zero carry the tree satisfies put-call symmetry,
+        // C(F, K) = P(K, F), so at K = F the call and put are equal even
+        // with early exercise.
+        let atm = option(100.0, 100.0, 0.08);
+
+        assert_approx_equal!(
+            atm.american_price(TypeFlag::Call, 500),
+            atm.american_price(TypeFlag::Put, 500),
+            1e-10
+        );
+        assert_approx_equal!(
+            option(90.0, 110.0, 0.08).american_price(TypeFlag::Call, 300),
+            option(110.0, 90.0, 0.08).american_price(TypeFlag::Put, 300),
+            1e-10
+        );
+    }
+
+    #[test]
+    fn test_american_early_exercise() {
+        for flag in [TypeFlag::Call, TypeFlag::Put] {
+            // Deep in the money, both calls and puts are exercised now.
+            let (f, k) = match flag {
+                TypeFlag::Call => (150.0, 100.0),
+                TypeFlag::Put => (100.0, 150.0),
+            };
+            let deep = option(f, k, 0.08);
+
+            assert!(deep.american_price(flag, 500) > deep.european_price(flag) + 0.5);
+            assert_approx_equal!(deep.american_price(flag, 500), 50.0, RUSTQUANT_EPSILON);
+
+            // Without discounting there is nothing to gain from exercising
+            // early, and the tree converges to Black (1976).
+            let undiscounted = option(105.0, 100.0, 0.0);
+
+            assert_approx_equal!(
+                undiscounted.american_price(flag, 2_000),
+                undiscounted.european_price(flag),
+                1e-2
+            );
+        }
+    }
+}
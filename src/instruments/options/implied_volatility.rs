@@ -96,10 +96,7 @@ pub fn implied_volatility(price: f64, S: f64, K: f64, T: f64, r: f64, flag: Type
 
     let F = S * rate;
 
-    let q = match flag {
-        TypeFlag::Call => 1.0,
-        TypeFlag::Put => -1.0,
-    };
+    let q = flag.sign();
 
     implied_volatility_from_a_transformed_rational_guess_with_limited_iterations(
         undiscounted_option_price,
@@ -1075,21 +1072,18 @@ mod test_lets_be_rational {
         assert!(slider >= 1.0);
 
         let mut bs = BlackScholesMerton::new(
-            risk_free_rate,
-            underlying_price,
-            strike_price,
-            0.0,
-            risk_free_rate,
+            risk_free_rate.into(),
+            underlying_price.into(),
+            strike_price.into(),
+            0.0.into(),
+            risk_free_rate.into(),
             None,
             today() + Duration::days(days),
             option_type,
         );
         let T = bs.year_fraction();
         let F = bs.underlying_price * (bs.risk_free_rate * T).exp();
-        let q = match option_type {
-            TypeFlag::Call => 1.0,
-            TypeFlag::Put => -1.0,
-        };
+        let q = option_type.sign();
         let x = (F / bs.strike_price).ln();
         // for
         // normalised_black_call, normalised_vega
@@ -1175,11 +1169,11 @@ mod test_lets_be_rational {
         // test OTM cases
         // these are unrealistic
         let bs = BlackScholesMerton::new(
-            0.05,
-            100.0,
-            150.0,
-            0.04,
-            0.05,
+            0.05.into(),
+            100.0.into(),
+            150.0.into(),
+            0.04.into(),
+            0.05.into(),
             None,
             today() + Duration::days(365),
             TypeFlag::Call,
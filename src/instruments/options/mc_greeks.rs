@@ -0,0 +1,207 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Monte Carlo estimators of the Greeks of European options under
+//! geometric Brownian motion, $S_T = S_0 \exp((r - \sigma^2 / 2) T + \sigma \sqrt{T} Z)$.
+//!
+//! - The pathwise estimator differentiates the payoff along each path.
+//!   For a vanilla payoff, $\partial (S_T - K)^+ / \partial S_0 = 1_{S_T > K} S_T / S_0$,
+//!   which is unbiased because the payoff is Lipschitz in $S_0$.
+//! - The likelihood ratio estimator differentiates the density of $S_T$
+//!   instead, weighting each payoff by the score
+//!   $\partial \log p / \partial \sigma = (Z^2 - 1) / \sigma - Z \sqrt{T}$.
+//!   It needs no smoothness of the payoff, at the cost of a higher variance.
+//!
+//! See Glasserman (2003), *Monte Carlo Methods in Financial Engineering*,
+//! Chapter 7.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Pathwise estimate of the delta of a European call or put with strike `k`
+/// and expiry `t`, from the undiscounted payoffs and terminal spot prices of
+/// paths started at `s` with risk-free rate `r`.
+///
+/// The payoff derivative on a path is $S_T / S_0$ where a call is in the
+/// money ($S_T > K$) and $-S_T / S_0$ where a put is in the money.
+///
+/// # Panics
+/// Panics if the slices have different lengths.
+#[must_use]
+pub fn pathwise_delta(
+    payoff_paths: &[f64],
+    spot_paths: &[f64],
+    s: f64,
+    k: f64,
+    r: f64,
+    t: f64,
+) -> f64 {
+    mean(&pathwise_delta_samples(
+        payoff_paths,
+        spot_paths,
+        s,
+        k,
+        r,
+        t,
+    ))
+}
+
+/// Likelihood ratio estimate of the vega of an option with expiry `t`, from
+/// the discounted payoffs of paths with volatility `v` and the standard
+/// normal draws `z_paths` that generated their terminal prices.
+///
+/// # Panics
+/// Panics if the slices have different lengths.
+#[must_use]
+pub fn lr_vega(payoff_paths: &[f64], z_paths: &[f64], v: f64, t: f64) -> f64 {
+    mean(&lr_vega_samples(payoff_paths, z_paths, v, t))
+}
+
+/// Per-path pathwise delta estimates.
+fn pathwise_delta_samples(
+    payoff_paths: &[f64],
+    spot_paths: &[f64],
+    s: f64,
+    k: f64,
+    r: f64,
+    t: f64,
+) -> Vec<f64> {
+    assert_eq!(
+        payoff_paths.len(),
+        spot_paths.len(),
+        "There must be one terminal spot price per payoff."
+    );
+
+    let discount = (-r * t).exp();
+
+    payoff_paths
+        .iter()
+        .zip(spot_paths)
+        .map(|(payoff, spot)| {
+            if *payoff > 0.0 {
+                let sign = if *spot > k { 1.0 } else { -1.0 };
+                discount * sign * spot / s
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+/// Per-path likelihood ratio vega estimates.
+fn lr_vega_samples(payoff_paths: &[f64], z_paths: &[f64], v: f64, t: f64) -> Vec<f64> {
+    assert_eq!(
+        payoff_paths.len(),
+        z_paths.len(),
+        "There must be one normal draw per payoff."
+    );
+
+    let sqrt_t = t.sqrt();
+
+    payoff_paths
+        .iter()
+        .zip(z_paths)
+        .map(|(payoff, z)| payoff * ((z * z - 1.0) / v - z * sqrt_t))
+        .collect()
+}
+
+fn mean(samples: &[f64]) -> f64 {
+    samples.iter().sum::<f64>() / samples.len() as f64
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_mc_greeks {
+    use super::*;
+    use crate::instruments::options::{black_scholes, TypeFlag};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::StandardNormal;
+
+    const N_PATHS: usize = 1_000_000;
+
+    const S: f64 = 100.0;
+    const R: f64 = 0.05;
+    const V: f64 = 0.2;
+    const T: f64 = 1.0;
+
+    /// Normal draws and terminal prices of GBM paths.
+    fn simulate(seed: u64) -> (Vec<f64>, Vec<f64>) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let z: Vec<f64> = (0..N_PATHS).map(|_| rng.sample(StandardNormal)).collect();
+        let spots = z
+            .iter()
+            .map(|z| S * ((R - 0.5 * V * V) * T + V * T.sqrt() * z).exp())
+            .collect();
+
+        (z, spots)
+    }
+
+    fn standard_error(samples: &[f64]) -> f64 {
+        let n = samples.len() as f64;
+        let mean = mean(samples);
+        let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+
+        (variance / n).sqrt()
+    }
+
+    fn assert_within_three_standard_errors(samples: &[f64], exact: f64) {
+        let estimate = mean(samples);
+        let error = standard_error(samples);
+
+        assert!(
+            (estimate - exact).abs() < 3.0 * error,
+            "Estimate {estimate} is more than 3 standard errors ({error}) from {exact}."
+        );
+    }
+
+    #[test]
+    fn test_pathwise_delta() {
+        let (_, spots) = simulate(1);
+
+        for k in [80.0, 100.0, 120.0] {
+            let calls: Vec<f64> = spots.iter().map(|s| (s - k).max(0.0)).collect();
+            let puts: Vec<f64> = spots.iter().map(|s| (k - s).max(0.0)).collect();
+
+            let call_samples = pathwise_delta_samples(&calls, &spots, S, k, R, T);
+            let put_samples = pathwise_delta_samples(&puts, &spots, S, k, R, T);
+
+            assert_eq!(
+                pathwise_delta(&calls, &spots, S, k, R, T),
+                mean(&call_samples)
+            );
+            assert_within_three_standard_errors(
+                &call_samples,
+                black_scholes::delta(S, k, R, 0.0, V, T, TypeFlag::Call),
+            );
+            assert_within_three_standard_errors(
+                &put_samples,
+                black_scholes::delta(S, k, R, 0.0, V, T, TypeFlag::Put),
+            );
+        }
+    }
+
+    #[test]
+    fn test_lr_vega() {
+        let (z, spots) = simulate(2);
+        let discount = (-R * T).exp();
+
+        for k in [80.0, 100.0, 120.0] {
+            let calls: Vec<f64> = spots.iter().map(|s| discount * (s - k).max(0.0)).collect();
+
+            let samples = lr_vega_samples(&calls, &z, V, T);
+
+            assert_eq!(lr_vega(&calls, &z, V, T), mean(&samples));
+            assert_within_three_standard_errors(&samples, black_scholes::vega(S, k, R, 0.0, V, T));
+        }
+    }
+}
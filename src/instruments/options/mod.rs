@@ -7,6 +7,10 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+/// Static no-arbitrage checks of option prices.
+pub mod arbitrage;
+pub use arbitrage::*;
+
 /// Asian option pricers.
 pub mod asian;
 pub use asian::*;
@@ -26,12 +30,37 @@ pub use binary::*;
 // /// Binomial option pricers.
 // pub mod binomial;
 
+/// Black (1976) model for options on forwards and futures.
+pub mod black_76;
+pub use black_76::*;
+
+/// Black-Scholes-Merton prices and Greeks as free functions.
+pub mod black_scholes;
+pub use black_scholes::BlackScholesInputs;
+
 /// Generalised Black-Scholes-Merton option pricer.
 pub mod black_scholes_merton;
 pub use black_scholes_merton::*;
 
-// /// Forward start options pricers.
-// pub mod forward_start;
+/// Calendar (time) spreads.
+pub mod calendar_spread;
+pub use calendar_spread::*;
+
+/// Butterfly, condor, ratio, and diagonal spreads.
+pub mod spreads;
+pub use spreads::*;
+
+/// Property tests of the closed-form pricers.
+#[cfg(test)]
+mod properties;
+
+/// Forward start option pricer.
+pub mod forward_start;
+pub use forward_start::*;
+
+/// European and American options on futures.
+pub mod futures_option;
+pub use futures_option::*;
 
 // /// Heston model option pricer.
 // pub mod heston;
@@ -45,6 +74,10 @@ pub use implied_volatility::*;
 pub mod lookback;
 pub use lookback::*;
 
+/// Monte Carlo Greeks (pathwise and likelihood ratio estimators).
+pub mod mc_greeks;
+pub use mc_greeks::*;
+
 // /// Merton (1976) jump diffusion model.
 // pub mod merton_jump_diffusion;
 // pub use merton_jump_diffusion::*;
@@ -53,6 +86,14 @@ pub use lookback::*;
 pub mod option_contract;
 pub use option_contract::*;
 
+/// Parisian option pricers.
+pub mod parisian;
+pub use parisian::*;
+
+/// Payoff and profit diagrams of option strategies.
+pub mod payoff_diagram;
+pub use payoff_diagram::*;
+
 /// Power options and contracts.
 pub mod power;
 pub use power::*;
@@ -64,10 +105,26 @@ pub mod finite_difference_pricer;
 pub mod option_flags;
 pub use option_flags::*;
 
+/// Call/put flag.
+pub mod type_flag;
+pub use type_flag::*;
+
 /// Vanilla option.
 pub mod vanilla;
 pub use vanilla::*;
 
+/// Employee stock options (Hull-White lattice model).
+pub mod employee_stock_option;
+pub use employee_stock_option::*;
+
+/// Static replication of exotic payoffs with vanilla options.
+pub mod static_replication;
+pub use static_replication::*;
+
+/// Variance swaps and VIX-style volatility indices.
+pub mod variance_swap;
+pub use variance_swap::*;
+
 /// Supershare options.
 pub mod supershare;
 pub use supershare::*;
@@ -75,3 +132,7 @@ pub use supershare::*;
 /// Log contracts and options.
 pub mod log;
 pub use log::*;
+
+/// Term structure of implied volatility.
+pub mod term_structure;
+pub use term_structure::*;
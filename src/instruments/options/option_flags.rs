@@ -9,15 +9,8 @@
 
 use time::Date;
 
-/// Option type enum.
-#[derive(Debug, Clone, Copy)]
-pub enum TypeFlag {
-    /// Call option (right to BUY the underlying asset).
-    Call = 1,
-
-    /// Put option (right to SELL the underlying asset).
-    Put = -1,
-}
+/// Re-exported so `option_flags::*` still brings the call/put flag in.
+pub use super::type_flag::TypeFlag;
 
 /// American/European option type enum.
 #[derive(Debug, Clone)]
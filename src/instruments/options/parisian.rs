@@ -0,0 +1,375 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Parisian options.
+//!
+//! A Parisian option is a barrier option which is only knocked in (or out)
+//! once the underlying has stayed beyond the barrier for a *consecutive*
+//! period of at least `window`, rather than on first touch.
+//!
+//! With a zero window it is a standard (discretely monitored) barrier option,
+//! and with a window as long as the option's life it can never be triggered
+//! by a path starting inside the barrier, so the knock-out option is a vanilla.
+//!
+//! Reference: Chesney, Jeanblanc-Picqué and Yor (1997),
+//! *Brownian Excursions and Parisian Barrier Options*.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::PricingError;
+use crate::instruments::options::{BarrierType, TypeFlag};
+use crate::time::{DayCountConvention, ExpiryConvention};
+
+use rand::Rng;
+use rand_distr::StandardNormal;
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// European Parisian option on an underlying following geometric Brownian motion.
+#[derive(Debug, Clone, Copy)]
+pub struct ParisianOption {
+    /// S - The underlying price.
+    pub s: f64,
+    /// K - The strike price.
+    pub k: f64,
+    /// r - The risk-free rate.
+    pub r: f64,
+    /// sigma - The volatility of the underlying.
+    pub v: f64,
+    /// Barrier level.
+    pub barrier: f64,
+    /// Consecutive time (in years) the underlying must spend beyond
+    /// the barrier to knock the option in or out.
+    pub window: f64,
+    /// Evaluation date (optional, defaults to now).
+    pub evaluation_date: Option<OffsetDateTime>,
+    /// The option's expiry.
+    pub expiry: OffsetDateTime,
+    /// Barrier type: "up" means above the barrier, "down" below it.
+    pub barrier_type: BarrierType,
+    /// Local time of day at which the option expires (optional; without
+    /// one, time to expiry is counted in whole days).
+    pub expiry_convention: Option<ExpiryConvention>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl ParisianOption {
+    /// Time to expiry, in years.
+    #[must_use]
+    pub fn year_fraction(&self) -> f64 {
+        let evaluation_date = self.evaluation_date.unwrap_or(OffsetDateTime::now_utc());
+
+        match self.expiry_convention {
+            Some(convention) => convention.year_fraction(evaluation_date, self.expiry.date()),
+            None => DayCountConvention::default()
+                .day_count_factor(evaluation_date.date(), self.expiry.date()),
+        }
+    }
+
+    /// Monte Carlo price of the option.
+    ///
+    /// Each path is simulated exactly on `n_steps` equal time steps, and the
+    /// barrier is monitored at every step (including today). The time spent
+    /// beyond the barrier during an excursion is the time between its first
+    /// and last monitoring dates, so the window is effectively rounded up
+    /// to a whole number of steps.
+    ///
+    /// # Panics
+    /// Panics if `n_paths` or `n_steps` is zero.
+    #[must_use]
+    pub fn price(&self, flag: TypeFlag, n_paths: usize, n_steps: usize, rng: &mut impl Rng) -> f64 {
+        assert!(n_paths > 0, "Number of paths must be positive.");
+        assert!(n_steps > 0, "Number of steps must be positive.");
+
+        let T = self.year_fraction();
+        let dt = T / n_steps as f64;
+        let drift = (self.r - 0.5 * self.v * self.v) * dt;
+        let diffusion = self.v * dt.sqrt();
+
+        // Number of steps an excursion must last to trigger the barrier.
+        let required_steps = if dt > 0.0 {
+            (self.window / dt - 1e-9).ceil().max(0.0) as usize
+        } else {
+            0
+        };
+
+        let (up, knock_in) = match self.barrier_type {
+            BarrierType::UpAndOut => (true, false),
+            BarrierType::DownAndOut => (false, false),
+            BarrierType::UpAndIn => (true, true),
+            BarrierType::DownAndIn => (false, true),
+        };
+        let beyond = |s: f64| {
+            if up {
+                s >= self.barrier
+            } else {
+                s <= self.barrier
+            }
+        };
+
+        let mut total = 0.0;
+
+        for _ in 0..n_paths {
+            let mut s = self.s;
+            let mut run = usize::from(beyond(s));
+            let mut longest_run = run;
+
+            for _ in 0..n_steps {
+                let z: f64 = rng.sample(StandardNormal);
+                s *= (drift + diffusion * z).exp();
+
+                run = if beyond(s) { run + 1 } else { 0 };
+                longest_run = longest_run.max(run);
+            }
+
+            // An excursion over `n` monitoring dates lasts `n - 1` steps.
+            let triggered = longest_run > required_steps;
+
+            if triggered == knock_in {
+                total += match flag {
+                    TypeFlag::Call => (s - self.k).max(0.0),
+                    TypeFlag::Put => (self.k - s).max(0.0),
+                };
+            }
+        }
+
+        (-self.r * T).exp() * total / n_paths as f64
+    }
+
+    /// Monte Carlo price of the option (see [`Self::price`]), rejecting
+    /// options that expired before the evaluation date.
+    ///
+    /// An option expiring on the evaluation date is worth its payoff at the
+    /// current price; without an expiry convention only dates are counted,
+    /// so this includes expiry later that day.
+    ///
+    /// # Errors
+    /// [`PricingError::Expired`] if the expiry is before the evaluation date.
+    ///
+    /// # Panics
+    /// Panics if `n_paths` or `n_steps` is zero.
+    pub fn try_price(
+        &self,
+        flag: TypeFlag,
+        n_paths: usize,
+        n_steps: usize,
+        rng: &mut impl Rng,
+    ) -> Result<f64, PricingError> {
+        let T = self.year_fraction();
+        if T < 0.0 {
+            return Err(PricingError::Expired(-T));
+        }
+
+        Ok(self.price(flag, n_paths, n_steps, rng))
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_parisian {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::options::{
+        BarrierOption, BlackScholesMerton, ExerciseFlag, OptionContract,
+    };
+    use crate::instruments::Payoff;
+    use rand::{rngs::StdRng, SeedableRng};
+    use time::macros::datetime;
+    use time::Duration;
+
+    const SEED: u64 = 42;
+    const N_PATHS: usize = 10_000;
+    const N_STEPS: usize = 100;
+
+    fn option(barrier_type: BarrierType, window: f64) -> ParisianOption {
+        let evaluation_date = datetime!(2024-01-02 0:00 UTC);
+
+        ParisianOption {
+            s: 100.0,
+            k: 100.0,
+            r: 0.05,
+            v: 0.2,
+            barrier: 115.0,
+            window,
+            evaluation_date: Some(evaluation_date),
+            expiry: evaluation_date + Duration::days(365),
+            barrier_type,
+            expiry_convention: None,
+        }
+    }
+
+    fn price(barrier_type: BarrierType, window: f64) -> f64 {
+        option(barrier_type, window).price(
+            TypeFlag::Call,
+            N_PATHS,
+            N_STEPS,
+            &mut StdRng::seed_from_u64(SEED),
+        )
+    }
+
+    // Standard barrier and vanilla prices on the same paths as `ParisianOption::price`.
+    fn barrier_and_vanilla_prices(barrier_type: BarrierType) -> (f64, f64) {
+        let parisian = option(barrier_type, 0.0);
+        let T = parisian.year_fraction();
+        let dt = T / N_STEPS as f64;
+        let drift = (parisian.r - 0.5 * parisian.v * parisian.v) * dt;
+        let diffusion = parisian.v * dt.sqrt();
+        let mut rng = StdRng::seed_from_u64(SEED);
+
+        let barrier = BarrierOption {
+            contract: OptionContract {
+                type_flag: TypeFlag::Call,
+                exercise_flag: ExerciseFlag::European {
+                    expiry: parisian.expiry.date(),
+                },
+                strike_flag: None,
+                settlement_flag: None,
+            },
+            barrier_type,
+            barrier: parisian.barrier,
+            strike: parisian.k,
+            rebate: None,
+        };
+
+        let (mut barrier_total, mut vanilla_total) = (0.0, 0.0);
+
+        for _ in 0..N_PATHS {
+            let mut path = vec![parisian.s];
+            for _ in 0..N_STEPS {
+                let z: f64 = rng.sample(StandardNormal);
+                let s = path[path.len() - 1];
+                path.push(s * (drift + diffusion * z).exp());
+            }
+
+            vanilla_total += (path[N_STEPS] - parisian.k).max(0.0);
+            barrier_total += barrier.payoff(path);
+        }
+
+        let discount = (-parisian.r * T).exp() / N_PATHS as f64;
+
+        (discount * barrier_total, discount * vanilla_total)
+    }
+
+    #[test]
+    fn test_parisian_converges_to_barrier_as_window_vanishes() {
+        let (barrier_price, _) = barrier_and_vanilla_prices(BarrierType::UpAndOut);
+
+        assert_approx_equal!(price(BarrierType::UpAndOut, 0.0), barrier_price, 1e-10);
+
+        // Shorter windows knock out more paths, down to the barrier price.
+        let windows = [0.2, 0.1, 0.05, 0.02, 0.0];
+        let prices: Vec<f64> = windows
+            .iter()
+            .map(|&window| price(BarrierType::UpAndOut, window))
+            .collect();
+
+        assert!(prices.windows(2).all(|p| p[0] >= p[1]));
+        assert!(prices[3] - barrier_price < 0.25 * (prices[0] - barrier_price));
+    }
+
+    #[test]
+    fn test_parisian_converges_to_vanilla_as_window_reaches_expiry() {
+        let (_, vanilla_price) = barrier_and_vanilla_prices(BarrierType::UpAndOut);
+        let T = option(BarrierType::UpAndOut, 0.0).year_fraction();
+
+        assert_approx_equal!(price(BarrierType::UpAndOut, T), vanilla_price, 1e-10);
+        assert_approx_equal!(price(BarrierType::UpAndIn, T), 0.0, 1e-10);
+
+        // Longer windows knock out fewer paths, up to the vanilla price.
+        let prices: Vec<f64> = [0.5, 0.75, 0.9, 1.0]
+            .iter()
+            .map(|&fraction| price(BarrierType::UpAndOut, fraction * T))
+            .collect();
+
+        assert!(prices.windows(2).all(|p| p[0] <= p[1]));
+        assert!(vanilla_price - prices[2] < 0.1 * (vanilla_price - prices[0]));
+
+        // The Monte Carlo vanilla agrees with Black-Scholes (standard error ~0.1).
+        let bsm = BlackScholesMerton::new(
+            0.05.into(),
+            100.0.into(),
+            100.0.into(),
+            0.2.into(),
+            0.05.into(),
+            Some(datetime!(2024-01-02 0:00 UTC).date()),
+            datetime!(2025-01-01 0:00 UTC).date(),
+            TypeFlag::Call,
+        );
+        assert_approx_equal!(vanilla_price, bsm.price(), 0.3);
+    }
+
+    #[test]
+    fn test_parisian_in_out_parity() {
+        let (_, vanilla_price) = barrier_and_vanilla_prices(BarrierType::DownAndOut);
+
+        for window in [0.0, 0.05, 0.25] {
+            let up = price(BarrierType::UpAndIn, window) + price(BarrierType::UpAndOut, window);
+            assert_approx_equal!(up, vanilla_price, 1e-10);
+
+            let mut down = option(BarrierType::DownAndIn, window);
+            down.barrier = 90.0;
+            let down_in = down.price(
+                TypeFlag::Call,
+                N_PATHS,
+                N_STEPS,
+                &mut StdRng::seed_from_u64(SEED),
+            );
+            down.barrier_type = BarrierType::DownAndOut;
+            let down_out = down.price(
+                TypeFlag::Call,
+                N_PATHS,
+                N_STEPS,
+                &mut StdRng::seed_from_u64(SEED),
+            );
+            assert_approx_equal!(down_in + down_out, vanilla_price, 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_expiry_edge_cases() {
+        let evaluation_date = datetime!(2024-01-02 12:00 UTC);
+        let expiring = |expiry, s| ParisianOption {
+            s,
+            evaluation_date: Some(evaluation_date),
+            expiry,
+            ..option(BarrierType::UpAndOut, 0.0)
+        };
+        let try_price = |option: ParisianOption| {
+            option.try_price(
+                TypeFlag::Call,
+                10,
+                N_STEPS,
+                &mut StdRng::seed_from_u64(SEED),
+            )
+        };
+
+        // Expiring at valuation, or a second later on the same day: the
+        // payoff at the current price, unless already knocked out.
+        for expiry in [evaluation_date, evaluation_date + Duration::seconds(1)] {
+            assert_eq!(try_price(expiring(expiry, 110.0)), Ok(10.0));
+            assert_eq!(try_price(expiring(expiry, 120.0)), Ok(0.0));
+        }
+
+        assert!(matches!(
+            try_price(expiring(evaluation_date - Duration::days(1), 110.0)),
+            Err(PricingError::Expired(years)) if years > 0.0
+        ));
+    }
+}
@@ -0,0 +1,179 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Payoff and profit diagrams of option strategies at expiry, as
+//! `(spot, value)` points for plotting.
+//!
+//! A strategy is a list of [`OptionPosition`]s. A position in the
+//! underlying itself is a call struck at zero, whose payoff is the spot.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::instruments::options::TypeFlag;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// A position in a European option.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OptionPosition {
+    /// Call or put.
+    pub type_flag: TypeFlag,
+    /// Strike price.
+    pub strike: f64,
+    /// Number of options: positive if long, negative if short.
+    pub quantity: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl OptionPosition {
+    /// New position of `quantity` options.
+    #[must_use]
+    pub fn new(type_flag: TypeFlag, strike: f64, quantity: f64) -> Self {
+        Self {
+            type_flag,
+            strike,
+            quantity,
+        }
+    }
+
+    /// Payoff of the position at expiry with the underlying at `spot`.
+    #[must_use]
+    pub fn payoff(&self, spot: f64) -> f64 {
+        self.quantity * self.type_flag.payoff(spot, self.strike)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Net payoff at expiry of `positions` at `n_points` equally spaced spots
+/// from `spot_range.0` to `spot_range.1`, as `(spot, payoff)` pairs.
+///
+/// # Panics
+/// Panics if `n_points` is less than 2, or the range is not increasing
+/// with a non-negative lower end.
+#[must_use]
+pub fn payoff_diagram(
+    positions: &[OptionPosition],
+    spot_range: (f64, f64),
+    n_points: usize,
+) -> Vec<(f64, f64)> {
+    profit_diagram(positions, 0.0, spot_range, n_points)
+}
+
+/// Net profit at expiry of `positions`: the payoff less `total_premium`,
+/// the net premium paid (negative if received), at `n_points` equally
+/// spaced spots from `spot_range.0` to `spot_range.1`.
+///
+/// # Panics
+/// Panics if `n_points` is less than 2, or the range is not increasing
+/// with a non-negative lower end.
+#[must_use]
+pub fn profit_diagram(
+    positions: &[OptionPosition],
+    total_premium: f64,
+    spot_range: (f64, f64),
+    n_points: usize,
+) -> Vec<(f64, f64)> {
+    let (low, high) = spot_range;
+    assert!(n_points >= 2, "Need at least two points.");
+    assert!(
+        0.0 <= low && low < high,
+        "The spot range must be increasing and non-negative."
+    );
+
+    let step = (high - low) / (n_points - 1) as f64;
+
+    (0..n_points)
+        .map(|i| {
+            let spot = if i == n_points - 1 {
+                high
+            } else {
+                low + i as f64 * step
+            };
+            let payoff: f64 = positions.iter().map(|p| p.payoff(spot)).sum();
+
+            (spot, payoff - total_premium)
+        })
+        .collect()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_payoff_diagram {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    #[test]
+    fn test_long_call() {
+        let call = [OptionPosition::new(TypeFlag::Call, 100.0, 1.0)];
+        let diagram = payoff_diagram(&call, (50.0, 150.0), 101);
+
+        assert_eq!(diagram.len(), 101);
+        assert_eq!((diagram[0].0, diagram[100].0), (50.0, 150.0));
+
+        for (spot, payoff) in &diagram {
+            if *spot <= 100.0 {
+                assert_eq!(*payoff, 0.0);
+            } else {
+                assert_approx_equal!(*payoff, spot - 100.0, 1e-12);
+            }
+        }
+
+        // Profit is the payoff shifted down by the premium.
+        let profit = profit_diagram(&call, 4.0, (50.0, 150.0), 101);
+        for ((_, payoff), (_, profit)) in diagram.iter().zip(&profit) {
+            assert_approx_equal!(*profit, payoff - 4.0, 1e-12);
+        }
+        assert_approx_equal!(profit[54].1, 0.0, 1e-12);
+    }
+
+    #[test]
+    fn test_covered_call_is_capped() {
+        // Long the underlying, short a call struck at 110.
+        let covered_call = [
+            OptionPosition::new(TypeFlag::Call, 0.0, 1.0),
+            OptionPosition::new(TypeFlag::Call, 110.0, -1.0),
+        ];
+
+        let diagram = payoff_diagram(&covered_call, (0.0, 200.0), 201);
+
+        for (spot, payoff) in &diagram {
+            assert_approx_equal!(*payoff, spot.min(110.0), 1e-12);
+        }
+        assert!(diagram.iter().all(|(_, payoff)| *payoff <= 110.0));
+
+        // Premium received raises the profit.
+        let profit = profit_diagram(&covered_call, -3.0, (0.0, 200.0), 201);
+        assert_approx_equal!(profit[200].1, 113.0, 1e-12);
+    }
+
+    #[test]
+    fn test_straddle() {
+        let straddle = [
+            OptionPosition::new(TypeFlag::Call, 100.0, 1.0),
+            OptionPosition::new(TypeFlag::Put, 100.0, 1.0),
+        ];
+
+        for (spot, payoff) in payoff_diagram(&straddle, (80.0, 120.0), 9) {
+            assert_approx_equal!(payoff, (spot - 100.0).abs(), 1e-12);
+        }
+    }
+}
@@ -7,7 +7,7 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use super::{OptionContract, TypeFlag};
+use super::OptionContract;
 use crate::instruments::Payoff;
 
 /// Power Option.
@@ -78,10 +78,9 @@ impl Payoff for PowerOption {
     type Underlying = f64;
 
     fn payoff(&self, underlying: Self::Underlying) -> f64 {
-        match self.contract.type_flag {
-            TypeFlag::Call => (underlying.powf(self.power) - self.strike).max(0.0),
-            TypeFlag::Put => (self.strike - underlying.powf(self.power)).max(0.0),
-        }
+        self.contract
+            .type_flag
+            .payoff(underlying.powf(self.power), self.strike)
     }
 }
 
@@ -89,10 +88,10 @@ impl Payoff for CappedPowerOption {
     type Underlying = f64;
 
     fn payoff(&self, underlying: Self::Underlying) -> f64 {
-        let payoff = match self.contract.type_flag {
-            TypeFlag::Call => (underlying.powf(self.power) - self.strike).max(0.0),
-            TypeFlag::Put => (self.strike - underlying.powf(self.power)).max(0.0),
-        };
+        let payoff = self
+            .contract
+            .type_flag
+            .payoff(underlying.powf(self.power), self.strike);
 
         payoff.min(self.cap)
     }
@@ -102,10 +101,7 @@ impl Payoff for PoweredOption {
     type Underlying = f64;
 
     fn payoff(&self, underlying: Self::Underlying) -> f64 {
-        let payoff = match self.contract.type_flag {
-            TypeFlag::Call => (underlying - self.strike).max(0.0),
-            TypeFlag::Put => (self.strike - underlying).max(0.0),
-        };
+        let payoff = self.contract.type_flag.payoff(underlying, self.strike);
 
         payoff.powf(self.power)
     }
@@ -0,0 +1,160 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Property tests of the closed-form European option pricers: put-call
+//! parity, no-arbitrage bounds, monotonicity in the spot, and convexity in
+//! the strike, over random parameter sets.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_properties {
+    use crate::instruments::options::calendar_spread::black_scholes_merton;
+    use crate::instruments::options::{Black76Option, BlackScholesMerton, TypeFlag};
+    use crate::testing::*;
+    use proptest::prelude::*;
+    use time::{Date, Duration, OffsetDateTime, Time};
+
+    /// Price of a call and a put under one of the closed-form pricers.
+    type Pricer = fn(&OptionParameters, TypeFlag) -> f64;
+
+    /// Parameter sets per property.
+    const CASES: u32 = 2_000;
+
+    /// Evaluation date of the date-based pricers.
+    const EVALUATION_DATE: Date = time::macros::date!(2024 - 01 - 02);
+
+    /// Expiry a whole number of days after the evaluation date, and the
+    /// parameters with the time to expiry those pricers will use.
+    fn dated(p: &OptionParameters) -> (Date, OptionParameters) {
+        let days = (p.t * 365.0).round().max(1.0) as i64;
+        let expiry = EVALUATION_DATE + Duration::days(days);
+        let t =
+            crate::time::DayCountConvention::default().day_count_factor(EVALUATION_DATE, expiry);
+
+        (expiry, OptionParameters { t, ..*p })
+    }
+
+    fn black_scholes_merton_struct(p: &OptionParameters, flag: TypeFlag) -> f64 {
+        let (expiry, _) = dated(p);
+
+        BlackScholesMerton::new(
+            (p.r - p.q).into(),
+            p.s.into(),
+            p.k.into(),
+            p.v.into(),
+            p.r.into(),
+            Some(EVALUATION_DATE),
+            expiry,
+            flag,
+        )
+        .price()
+    }
+
+    fn black_76(p: &OptionParameters, flag: TypeFlag) -> f64 {
+        let (expiry, dated) = dated(p);
+        let at = |date: Date| OffsetDateTime::new_utc(date, Time::MIDNIGHT);
+
+        Black76Option {
+            evaluation_date: Some(at(EVALUATION_DATE)),
+            ..Black76Option::new(
+                dated.forward().into(),
+                p.k.into(),
+                p.r.into(),
+                p.v.into(),
+                at(expiry),
+            )
+        }
+        .price(flag)
+    }
+
+    fn black_scholes_merton_fn(p: &OptionParameters, flag: TypeFlag) -> f64 {
+        black_scholes_merton(p.s, p.k, p.r, p.q, p.v, p.t, flag).price
+    }
+
+    /// Each pricer with the parameters it effectively prices.
+    fn pricers(p: &OptionParameters) -> [(&'static str, Pricer, OptionParameters); 3] {
+        let (_, dated) = dated(p);
+
+        [
+            ("BlackScholesMerton", black_scholes_merton_struct, dated),
+            ("Black76Option", black_76, dated),
+            ("black_scholes_merton", black_scholes_merton_fn, *p),
+        ]
+    }
+
+    /// Points from 0.5x to 2x of `x`.
+    fn around(x: f64) -> Vec<f64> {
+        (0..=30)
+            .map(|i| x * 0.5 * 4_f64.powf(f64::from(i) / 30.0))
+            .collect()
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+        #[test]
+        fn test_put_call_parity(p in option_parameters()) {
+            for (name, price, q) in pricers(&p) {
+                let (call, put) = (price(&p, TypeFlag::Call), price(&p, TypeFlag::Put));
+
+                prop_assert!(
+                    check_put_call_parity(call, put, &q, 1e-10).is_ok(),
+                    "{}: {:?}", name, check_put_call_parity(call, put, &q, 1e-10)
+                );
+            }
+        }
+
+        #[test]
+        fn test_no_arbitrage_bounds(p in option_parameters()) {
+            for (name, price, q) in pricers(&p) {
+                let (dq, dr) = ((-q.q * q.t).exp(), (-q.r * q.t).exp());
+                let tolerance = 1e-10 * q.s;
+                let call = price(&p, TypeFlag::Call);
+                let put = price(&p, TypeFlag::Put);
+
+                prop_assert!(call >= (q.s * dq - q.k * dr).max(0.0) - tolerance, "{}: call {}", name, call);
+                prop_assert!(call <= q.s * dq + tolerance, "{}: call {}", name, call);
+                prop_assert!(put >= (q.k * dr - q.s * dq).max(0.0) - tolerance, "{}: put {}", name, put);
+                prop_assert!(put <= q.k * dr + tolerance, "{}: put {}", name, put);
+            }
+        }
+
+        #[test]
+        fn test_monotone_in_spot(p in option_parameters()) {
+            for (name, price, _) in pricers(&p) {
+                let with_spot = |flag| move |s| price(&OptionParameters { s, ..p }, flag);
+                let tolerance = 1e-10 * p.s;
+
+                let call = check_non_decreasing(with_spot(TypeFlag::Call), &around(p.s), tolerance);
+                let put = check_non_increasing(with_spot(TypeFlag::Put), &around(p.s), tolerance);
+                prop_assert!(call.is_ok(), "{}: {:?}", name, call);
+                prop_assert!(put.is_ok(), "{}: {:?}", name, put);
+            }
+        }
+
+        #[test]
+        fn test_convex_in_strike(p in option_parameters()) {
+            for (name, price, _) in pricers(&p) {
+                let with_strike = |flag| move |k| price(&OptionParameters { k, ..p }, flag);
+                let tolerance = 1e-10 * p.s;
+
+                for flag in [TypeFlag::Call, TypeFlag::Put] {
+                    let convex = check_convex(with_strike(flag), &around(p.k), tolerance);
+                    prop_assert!(convex.is_ok(), "{}: {:?}", name, convex);
+                }
+
+                let call = check_non_increasing(with_strike(TypeFlag::Call), &around(p.k), tolerance);
+                prop_assert!(call.is_ok(), "{}: {:?}", name, call);
+            }
+        }
+    }
+}
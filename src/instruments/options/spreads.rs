@@ -0,0 +1,208 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Option spread strategies: butterflies, condors, ratio spreads, and
+//! diagonal spreads.
+//!
+//! Each strategy is priced leg by leg with the Black-Scholes formula (no
+//! dividends), with the time to expiry `t` in years.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::calendar_spread::black_scholes_merton;
+use crate::instruments::options::TypeFlag;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Black-Scholes price of a European call.
+fn call(s: f64, k: f64, r: f64, v: f64, t: f64) -> f64 {
+    black_scholes_merton(s, k, r, 0.0, v, t, TypeFlag::Call).price
+}
+
+/// Butterfly spread: long one call at `k1`, short two calls at `k2`, and
+/// long one call at `k3`, for $k_2 = (k_1 + k_3) / 2$.
+///
+/// For a broken-wing butterfly ($k_2$ off centre) the wings are weighted so
+/// the payoff is zero outside $[k_1, k_3]$ and peaks at $k_2 - k_1$:
+/// $C(k_1) - \frac{k_3 - k_1}{k_3 - k_2} C(k_2) + \frac{k_2 - k_1}{k_3 - k_2} C(k_3)$.
+///
+/// # Panics
+/// Panics unless $k_1 < k_2 < k_3$.
+#[must_use]
+pub fn butterfly_price(s: f64, k1: f64, k2: f64, k3: f64, r: f64, v: f64, t: f64) -> f64 {
+    assert!(k1 < k2 && k2 < k3, "Strikes must be increasing.");
+
+    let width = k3 - k2;
+
+    call(s, k1, r, v, t) - (k3 - k1) / width * call(s, k2, r, v, t)
+        + (k2 - k1) / width * call(s, k3, r, v, t)
+}
+
+/// Condor spread: long one call at `k1`, short one call at each of `k2` and
+/// `k3`, and long one call at `k4`, for $k_2 - k_1 = k_4 - k_3$.
+///
+/// # Panics
+/// Panics unless $k_1 < k_2 \le k_3 < k_4$.
+#[allow(clippy::too_many_arguments)]
+#[must_use]
+pub fn condor_price(s: f64, k1: f64, k2: f64, k3: f64, k4: f64, r: f64, v: f64, t: f64) -> f64 {
+    assert!(
+        k1 < k2 && k2 <= k3 && k3 < k4,
+        "Strikes must be increasing."
+    );
+
+    call(s, k1, r, v, t) - call(s, k2, r, v, t) - call(s, k3, r, v, t) + call(s, k4, r, v, t)
+}
+
+/// Ratio call spread: long one call at `k1` and short `ratio` calls at
+/// `k2`.
+///
+/// # Panics
+/// Panics unless $k_1 < k_2$ and `ratio` is non-negative.
+#[must_use]
+pub fn ratio_spread_price(s: f64, k1: f64, k2: f64, ratio: f64, r: f64, v: f64, t: f64) -> f64 {
+    assert!(k1 < k2, "Strikes must be increasing.");
+    assert!(ratio >= 0.0, "Ratio must be non-negative.");
+
+    call(s, k1, r, v, t) - ratio * call(s, k2, r, v, t)
+}
+
+/// Diagonal spread: short the near-dated option at `near_strike` and long
+/// the far-dated option at `far_strike`, each with its own volatility.
+///
+/// With equal strikes this is a calendar spread.
+///
+/// # Panics
+/// Panics unless $t_{near} < t_{far}$.
+#[allow(clippy::too_many_arguments)]
+#[must_use]
+pub fn diagonal_spread_price(
+    s: f64,
+    near_strike: f64,
+    far_strike: f64,
+    r: f64,
+    near_vol: f64,
+    far_vol: f64,
+    near_t: f64,
+    far_t: f64,
+    flag: TypeFlag,
+) -> f64 {
+    assert!(
+        near_t < far_t,
+        "The far leg must expire after the near leg."
+    );
+
+    black_scholes_merton(s, far_strike, r, 0.0, far_vol, far_t, flag).price
+        - black_scholes_merton(s, near_strike, r, 0.0, near_vol, near_t, flag).price
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_spreads {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    const R: f64 = 0.02;
+    const V: f64 = 0.2;
+    const T: f64 = 0.25;
+
+    #[test]
+    fn test_butterfly_is_non_negative() {
+        for s in (50..=150).step_by(5).map(f64::from) {
+            for v in [0.05, 0.2, 0.8] {
+                for t in [0.01, 0.5, 3.0] {
+                    assert!(butterfly_price(s, 90.0, 100.0, 110.0, R, v, t) >= -1e-12);
+                    assert!(butterfly_price(s, 90.0, 95.0, 110.0, R, v, t) >= -1e-12);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_butterfly_peaks_at_the_money() {
+        let s = 100.0;
+
+        // Slide a butterfly of fixed width across strikes: it is worth most
+        // when centred on the spot.
+        let (best_centre, best_price) = (60..=140)
+            .map(f64::from)
+            .map(|k2| (k2, butterfly_price(s, k2 - 10.0, k2, k2 + 10.0, R, V, T)))
+            .fold((0.0, f64::NEG_INFINITY), |best, candidate| {
+                if candidate.1 > best.1 {
+                    candidate
+                } else {
+                    best
+                }
+            });
+
+        assert!((best_centre - s).abs() <= 2.0);
+
+        // Never worth more than its discounted maximum payoff.
+        assert!(best_price < 10.0 * (-R * T).exp());
+        assert!(best_price > butterfly_price(s, 110.0, 120.0, 130.0, R, V, T));
+    }
+
+    #[test]
+    fn test_condor_is_two_butterflies() {
+        let s = 103.0;
+
+        assert_approx_equal!(
+            condor_price(s, 90.0, 100.0, 110.0, 120.0, R, V, T),
+            butterfly_price(s, 90.0, 100.0, 110.0, R, V, T)
+                + butterfly_price(s, 100.0, 110.0, 120.0, R, V, T),
+            1e-10
+        );
+
+        // With the inner strikes together, the condor is a butterfly.
+        assert_approx_equal!(
+            condor_price(s, 90.0, 100.0, 100.0, 110.0, R, V, T),
+            butterfly_price(s, 90.0, 100.0, 110.0, R, V, T),
+            1e-10
+        );
+    }
+
+    #[test]
+    fn test_ratio_and_diagonal_spreads() {
+        let s = 100.0;
+
+        // A 1:1 ratio spread is a bull call spread, worth less than the
+        // strike width, and a 2:1 spread is cheaper still.
+        let one = ratio_spread_price(s, 100.0, 110.0, 1.0, R, V, T);
+        let two = ratio_spread_price(s, 100.0, 110.0, 2.0, R, V, T);
+        assert!(one > 0.0 && one < 10.0);
+        assert_approx_equal!(one - two, call(s, 110.0, R, V, T), 1e-10);
+        assert_approx_equal!(
+            ratio_spread_price(s, 100.0, 110.0, 0.0, R, V, T),
+            call(s, 100.0, R, V, T),
+            1e-12
+        );
+
+        // Equal strikes and volatilities: a long calendar spread, worth
+        // more than zero.
+        let calendar = diagonal_spread_price(s, 100.0, 100.0, R, V, V, T, 2.0 * T, TypeFlag::Call);
+        assert_approx_equal!(
+            calendar,
+            call(s, 100.0, R, V, 2.0 * T) - call(s, 100.0, R, V, T),
+            1e-12
+        );
+        assert!(calendar > 0.0);
+
+        // Moving the far strike up cheapens the diagonal.
+        assert!(
+            diagonal_spread_price(s, 100.0, 105.0, R, V, V, T, 2.0 * T, TypeFlag::Call) < calendar
+        );
+    }
+}
@@ -0,0 +1,249 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Static replication of European payoffs with vanilla options.
+//!
+//! A twice-differentiable payoff $f(S_T)$ can be written (Carr and Madan)
+//! as a position in bonds and forwards plus a strip of out-of-the-money
+//! options:
+//!
+//! $$
+//! f(S) = f(F) + f'(F)(S - F)
+//!      + \int_0^F f''(K) (K - S)^+ \\, dK
+//!      + \int_F^\infty f''(K) (S - K)^+ \\, dK,
+//! $$
+//!
+//! and discontinuous payoffs are approximated by spreads of vanillas
+//! (Carr and Chou). On a finite strike grid the replica is exact away
+//! from the strikes closest to the discontinuities.
+//!
+//! References:
+//!     - Carr, P. and Chou, A. (1997), Breaking barriers.
+//!     - Demeterfi, K., Derman, E., Kamal, M. and Zou, J. (1999),
+//!       More than you ever wanted to know about volatility swaps.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Weights on vanilla calls, one per strike in `strikes`, replicating a
+/// digital (cash-or-nothing) call paying 1 if $S_T > k$.
+///
+/// The digital is the limit of a call spread: with the closest strikes
+/// $K_- < k < K_+$ on the grid, the replica is long $1 / (K_+ - K_-)$
+/// calls at $K_-$ and short as many at $K_+$. Its payoff is exact outside
+/// $(K_-, K_+)$ and ramps linearly from 0 to 1 across it: this is the
+/// tightest call spread available on the grid.
+///
+/// # Panics
+/// Panics if `strikes` is not strictly increasing, or does not contain
+/// strikes on both sides of `k`.
+#[must_use]
+pub fn replicate_digital_call(k: f64, strikes: &[f64]) -> Vec<f64> {
+    assert!(
+        strikes.windows(2).all(|w| w[0] < w[1]),
+        "Strikes must be strictly increasing."
+    );
+
+    let upper = strikes.partition_point(|&strike| strike <= k);
+    let lower = strikes[..upper].partition_point(|&strike| strike < k);
+
+    assert!(
+        lower > 0 && upper < strikes.len(),
+        "Strikes must bracket the digital's strike."
+    );
+
+    let (lower, upper) = (lower - 1, upper);
+    let leverage = 1.0 / (strikes[upper] - strikes[lower]);
+
+    let mut weights = vec![0.0; strikes.len()];
+    weights[lower] = leverage;
+    weights[upper] = -leverage;
+
+    weights
+}
+
+/// `(strike, weight)` pairs of out-of-the-money options replicating the
+/// payoff of a variance swap's log contract,
+///
+/// $$
+/// f(S_T) = 2 \left( \frac{S_T - F}{F} - \ln \frac{S_T}{F} \right),
+/// $$
+///
+/// whose risk-neutral expectation is the expected realized variance
+/// $\sigma^2 T$ (for continuous monitoring and no jumps). Strikes below
+/// the forward `forward` are puts, the others calls; no forward or bond
+/// position is needed, since $f(F) = f'(F) = 0$.
+///
+/// Each strike carries the weight $2 / K^2$ integrated over its cell:
+/// the interval between the midpoints to its neighbours, with the forward
+/// closing the cells next to it and half a strike spacing closing the
+/// outermost ones. The replica is accurate to second order in the strike
+/// spacing inside the strike range.
+///
+/// # Panics
+/// Panics if `forward` is not positive, or `strikes` is not positive and
+/// strictly increasing with at least two strikes.
+#[must_use]
+pub fn replicate_variance_swap(forward: f64, strikes: &[f64]) -> Vec<(f64, f64)> {
+    assert!(forward > 0.0, "Forward must be positive.");
+    assert!(strikes.len() >= 2, "At least two strikes are required.");
+    assert!(
+        strikes[0] > 0.0 && strikes.windows(2).all(|w| w[0] < w[1]),
+        "Strikes must be positive and strictly increasing."
+    );
+
+    let n = strikes.len();
+    let split = strikes.partition_point(|&strike| strike < forward);
+
+    // Cell boundaries: midpoints between strikes, except at the forward.
+    let boundary = |i: usize| {
+        if i == 0 {
+            (strikes[0] - 0.5 * (strikes[1] - strikes[0])).max(0.0)
+        } else if i == n {
+            strikes[n - 1] + 0.5 * (strikes[n - 1] - strikes[n - 2])
+        } else if i == split {
+            forward
+        } else {
+            0.5 * (strikes[i - 1] + strikes[i])
+        }
+    };
+
+    // Integral of 2 / K^2 over [a, b].
+    let integral = |a: f64, b: f64| {
+        if a == 0.0 {
+            f64::INFINITY
+        } else {
+            2.0 * (1.0 / a - 1.0 / b)
+        }
+    };
+
+    strikes
+        .iter()
+        .enumerate()
+        .map(|(i, &strike)| (strike, integral(boundary(i), boundary(i + 1))))
+        .collect()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_static_replication {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    fn strike_grid(low: f64, high: f64, step: f64) -> Vec<f64> {
+        let n = ((high - low) / step).round() as usize;
+        (0..=n).map(|i| low + i as f64 * step).collect()
+    }
+
+    // 1000 terminal prices evenly spread over [low, high].
+    fn scenarios(low: f64, high: f64) -> Vec<f64> {
+        (0..1_000)
+            .map(|i| low + (high - low) * (f64::from(i) + 0.5) / 1_000.0)
+            .collect()
+    }
+
+    #[test]
+    fn test_digital_call_replication() {
+        let strikes = strike_grid(50.0, 150.0, 1.0);
+        let k = 100.5;
+        let weights = replicate_digital_call(k, &strikes);
+
+        let replica = |s: f64| -> f64 {
+            strikes
+                .iter()
+                .zip(&weights)
+                .map(|(strike, w)| w * (s - strike).max(0.0))
+                .sum()
+        };
+
+        let mut total_error = 0.0;
+        for s in scenarios(50.0, 150.0) {
+            let target = if s > k { 1.0 } else { 0.0 };
+            let error = (replica(s) - target).abs();
+
+            // Exact outside the call spread.
+            if !(100.0..=101.0).contains(&s) {
+                assert_approx_equal!(error, 0.0, 1e-12);
+            }
+            total_error += error;
+        }
+
+        assert!(total_error / 1_000.0 < 0.005);
+        assert_eq!(weights.iter().filter(|w| **w != 0.0).count(), 2);
+    }
+
+    #[test]
+    fn test_digital_call_on_a_grid_strike() {
+        let strikes = strike_grid(90.0, 110.0, 2.0);
+        let weights = replicate_digital_call(100.0, &strikes);
+
+        // Spread between 98 and 102, centred on the strike.
+        assert_approx_equal!(weights[4], 0.25, 1e-12);
+        assert_approx_equal!(weights[6], -0.25, 1e-12);
+        assert_approx_equal!(weights.iter().sum::<f64>(), 0.0, 1e-12);
+    }
+
+    #[test]
+    fn test_variance_swap_replication() {
+        for forward in [100.0, 103.7] {
+            let strikes = strike_grid(10.0, 400.0, 1.0);
+            let replica = replicate_variance_swap(forward, &strikes);
+
+            let payoff = |s: f64| -> f64 {
+                replica
+                    .iter()
+                    .map(|&(strike, w)| {
+                        if strike < forward {
+                            w * (strike - s).max(0.0)
+                        } else {
+                            w * (s - strike).max(0.0)
+                        }
+                    })
+                    .sum()
+            };
+
+            for s in scenarios(50.0, 200.0) {
+                let target = 2.0 * ((s - forward) / forward - (s / forward).ln());
+                assert_approx_equal!(payoff(s), target, 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn test_variance_swap_weights() {
+        let strikes = strike_grid(80.0, 120.0, 5.0);
+        let replica = replicate_variance_swap(100.0, &strikes);
+
+        // Away from the ends and the forward: 2 / K^2 dK, to second order.
+        for &(strike, weight) in &replica[1..replica.len() - 1] {
+            if strike != 95.0 && strike != 100.0 {
+                assert_approx_equal!(weight, 2.0 * 5.0 / (strike * strike), 1e-5);
+            }
+        }
+
+        // The forward closes the cells next to it: [92.5, 100] for the put
+        // at 95 and [100, 102.5] for the call at 100.
+        assert_approx_equal!(replica[3].1, 2.0 * (1.0 / 92.5 - 1.0 / 100.0), 1e-12);
+        assert_approx_equal!(replica[4].1, 2.0 * (1.0 / 100.0 - 1.0 / 102.5), 1e-12);
+
+        // The cells partition [77.5, 122.5].
+        let total: f64 = replica.iter().map(|(_, w)| w).sum();
+        assert_approx_equal!(total, 2.0 * (1.0 / 77.5 - 1.0 / 122.5), 1e-12);
+    }
+
+    #[test]
+    #[should_panic(expected = "bracket")]
+    fn test_digital_call_outside_grid() {
+        let _ = replicate_digital_call(200.0, &strike_grid(50.0, 150.0, 1.0));
+    }
+}
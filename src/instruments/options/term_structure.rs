@@ -0,0 +1,208 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Term structure of implied volatility.
+//!
+//! Implied volatilities $\sigma_i$ quoted at maturities $t_i$ are
+//! interpolated in total variance $w(t) = \sigma^2(t) t$, linearly between
+//! the quotes (so the forward variance is constant between them). Before
+//! the first and after the last quote the volatility is held flat.
+//!
+//! The forward volatility between $t_1$ and $t_2$ is
+//! $$
+//! \sigma_{1,2} = \sqrt{\frac{w(t_2) - w(t_1)}{t_2 - t_1}},
+//! $$
+//! which only exists if the total variance does not decrease: calendar
+//! spreads would otherwise have negative value.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Implied volatilities by maturity.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VolatilityTermStructure {
+    /// Strictly increasing, positive maturities, in years.
+    pub maturities: Vec<f64>,
+    /// Implied volatility at each maturity.
+    pub vols: Vec<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl VolatilityTermStructure {
+    /// New volatility term structure.
+    ///
+    /// # Errors
+    /// `InvalidArgument` if there is not one volatility per maturity, there
+    /// are no maturities, the maturities are not positive and strictly
+    /// increasing, or a volatility is negative.
+    pub fn new(maturities: Vec<f64>, vols: Vec<f64>) -> Result<Self, RustQuantError> {
+        if maturities.len() != vols.len() || maturities.is_empty() {
+            return Err(RustQuantError::InvalidArgument(
+                "There must be one volatility for each of at least one maturity.".to_string(),
+            ));
+        }
+        if maturities[0] <= 0.0 || maturities.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(RustQuantError::InvalidArgument(
+                "Maturities must be positive and strictly increasing.".to_string(),
+            ));
+        }
+        if vols.iter().any(|&v| v < 0.0) {
+            return Err(RustQuantError::InvalidArgument(
+                "Volatilities must not be negative.".to_string(),
+            ));
+        }
+
+        Ok(Self { maturities, vols })
+    }
+
+    /// Total implied variance $w(t) = \sigma^2(t) t$ to maturity `t`
+    /// (zero for `t <= 0`).
+    #[must_use]
+    pub fn total_variance(&self, t: f64) -> f64 {
+        if t <= 0.0 {
+            return 0.0;
+        }
+
+        let (first, last) = (0, self.maturities.len() - 1);
+
+        if t <= self.maturities[first] {
+            return self.vols[first].powi(2) * t;
+        }
+        if t >= self.maturities[last] {
+            return self.vols[last].powi(2) * t;
+        }
+
+        let i = self.maturities.partition_point(|&m| m <= t);
+        let (t1, t2) = (self.maturities[i - 1], self.maturities[i]);
+        let (w1, w2) = (self.node_variance(i - 1), self.node_variance(i));
+
+        w1 + (w2 - w1) * (t - t1) / (t2 - t1)
+    }
+
+    /// Implied volatility to maturity `t`, $\sqrt{w(t) / t}$ (the first
+    /// quoted volatility for `t <= 0`).
+    #[must_use]
+    pub fn interpolate_vol(&self, t: f64) -> f64 {
+        if t <= 0.0 {
+            return self.vols[0];
+        }
+
+        (self.total_variance(t) / t).sqrt()
+    }
+
+    /// Forward volatility between maturities `t1` and `t2`.
+    ///
+    /// NaN if the total variance decreases between them (see
+    /// [`Self::is_arbitrage_free`]).
+    ///
+    /// # Panics
+    /// Panics if `t2 <= t1`.
+    #[must_use]
+    pub fn forward_vol(&self, t1: f64, t2: f64) -> f64 {
+        assert!(t2 > t1, "The second maturity must be after the first.");
+
+        ((self.total_variance(t2) - self.total_variance(t1)) / (t2 - t1)).sqrt()
+    }
+
+    /// Whether the total variance is non-decreasing in maturity, so every
+    /// forward variance is non-negative and calendar spreads have no
+    /// arbitrage.
+    #[must_use]
+    pub fn is_arbitrage_free(&self) -> bool {
+        (1..self.maturities.len()).all(|i| self.node_variance(i) >= self.node_variance(i - 1))
+    }
+
+    fn node_variance(&self, i: usize) -> f64 {
+        self.vols[i].powi(2) * self.maturities[i]
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_term_structure {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    fn term_structure(vols: Vec<f64>) -> VolatilityTermStructure {
+        VolatilityTermStructure::new(vec![0.25, 0.5, 1.0, 2.0], vols).expect("Valid quotes.")
+    }
+
+    #[test]
+    fn test_flat_term_structure() {
+        let flat = term_structure(vec![0.2; 4]);
+
+        for (t1, t2) in [(0.0, 0.1), (0.1, 0.3), (0.3, 0.75), (0.6, 1.7), (1.5, 5.0)] {
+            assert_approx_equal!(flat.forward_vol(t1, t2), 0.2, 1e-12);
+            assert_approx_equal!(flat.interpolate_vol(t2), 0.2, 1e-12);
+            assert_approx_equal!(flat.total_variance(t2), 0.04 * t2, 1e-12);
+        }
+
+        assert!(flat.is_arbitrage_free());
+    }
+
+    #[test]
+    fn test_interpolation() {
+        let upward = term_structure(vec![0.15, 0.18, 0.2, 0.22]);
+
+        // Quotes are recovered at their maturities.
+        for (t, v) in upward.maturities.iter().zip(&upward.vols) {
+            assert_approx_equal!(upward.interpolate_vol(*t), *v, 1e-12);
+        }
+
+        // Linear in total variance between quotes, so the forward vol is
+        // constant between them.
+        let forward = ((0.2_f64.powi(2) - 0.18_f64.powi(2) * 0.5) / 0.5).sqrt();
+        assert_approx_equal!(upward.forward_vol(0.5, 1.0), forward, 1e-12);
+        assert_approx_equal!(upward.forward_vol(0.6, 0.9), forward, 1e-12);
+        assert_approx_equal!(
+            upward.total_variance(0.75),
+            0.5 * (0.18_f64.powi(2) * 0.5 + 0.2_f64.powi(2)),
+            1e-12
+        );
+
+        // Flat extrapolation.
+        assert_approx_equal!(upward.interpolate_vol(0.1), 0.15, 1e-12);
+        assert_approx_equal!(upward.interpolate_vol(10.0), 0.22, 1e-12);
+        assert!(upward.is_arbitrage_free());
+    }
+
+    #[test]
+    fn test_calendar_arbitrage() {
+        // Total variance 0.09 at one year but 0.08 at two.
+        let inverted = term_structure(vec![0.3, 0.3, 0.3, 0.2]);
+
+        assert!(!inverted.is_arbitrage_free());
+        assert!(inverted.forward_vol(1.0, 2.0).is_nan());
+
+        // A steeply falling but arbitrage-free structure.
+        assert!(term_structure(vec![0.4, 0.3, 0.22, 0.16]).is_arbitrage_free());
+    }
+
+    #[test]
+    fn test_invalid_quotes() {
+        assert!(VolatilityTermStructure::new(vec![], vec![]).is_err());
+        assert!(VolatilityTermStructure::new(vec![1.0, 2.0], vec![0.2]).is_err());
+        assert!(VolatilityTermStructure::new(vec![1.0, 1.0], vec![0.2, 0.2]).is_err());
+        assert!(VolatilityTermStructure::new(vec![0.0, 1.0], vec![0.2, 0.2]).is_err());
+        assert!(VolatilityTermStructure::new(vec![1.0], vec![-0.2]).is_err());
+    }
+}
@@ -0,0 +1,89 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Call/put flag shared by the option pricers.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Option type enum.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeFlag {
+    /// Call option (right to BUY the underlying asset).
+    Call = 1,
+
+    /// Put option (right to SELL the underlying asset).
+    Put = -1,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl TypeFlag {
+    /// Exercise value $\max(S - K, 0)$ of a call or $\max(K - S, 0)$ of a
+    /// put.
+    #[must_use]
+    pub fn payoff(&self, spot: f64, strike: f64) -> f64 {
+        (self.sign() * (spot - strike)).max(0.0)
+    }
+
+    /// `1.0` for a call and `-1.0` for a put.
+    #[must_use]
+    pub fn sign(&self) -> f64 {
+        match self {
+            TypeFlag::Call => 1.0,
+            TypeFlag::Put => -1.0,
+        }
+    }
+}
+
+impl std::fmt::Display for TypeFlag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TypeFlag::Call => write!(f, "Call"),
+            TypeFlag::Put => write!(f, "Put"),
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_type_flag {
+    use super::*;
+
+    #[test]
+    fn test_payoff() {
+        assert_eq!(TypeFlag::Call.payoff(110.0, 100.0), 10.0);
+        assert_eq!(TypeFlag::Put.payoff(110.0, 100.0), 0.0);
+        assert_eq!(TypeFlag::Call.payoff(90.0, 100.0), 0.0);
+        assert_eq!(TypeFlag::Put.payoff(90.0, 100.0), 10.0);
+
+        assert_eq!(TypeFlag::Call.sign(), 1.0);
+        assert_eq!(TypeFlag::Put.sign(), -1.0);
+
+        assert_eq!(TypeFlag::Call.to_string(), "Call");
+        assert_eq!(TypeFlag::Put.to_string(), "Put");
+    }
+
+    #[test]
+    fn test_call_plus_put_is_straddle() {
+        for s in (0..=40).map(|i| 2.5 * f64::from(i)) {
+            for k in (0..=20).map(|i| 5.0 * f64::from(i)) {
+                let straddle = TypeFlag::Call.payoff(s, k) + TypeFlag::Put.payoff(s, k);
+
+                assert_eq!(straddle, (s - k).abs(), "S = {s}, K = {k}");
+            }
+        }
+    }
+}
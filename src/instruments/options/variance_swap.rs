@@ -0,0 +1,374 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Variance swaps and VIX-style volatility indices.
+//!
+//! The fair variance strike is replicated by a strip of out-of-the-money
+//! options (the log contract), discretised as in the CBOE VIX white paper:
+//!
+//! $$
+//! \sigma^2 = \frac{2}{T} \sum_i \frac{\Delta K_i}{K_i^2} e^{RT} Q(K_i)
+//!     - \frac{1}{T} \left( \frac{F}{K_0} - 1 \right)^2,
+//! $$
+//!
+//! where $K_0$ is the first strike at or below the forward $F$, $Q(K_i)$ is
+//! the put price below $K_0$, the call price above it, and the average of
+//! the two at $K_0$, and $\Delta K_i$ is half the distance between the
+//! strikes either side of $K_i$ (one-sided at the ends of the strip). The
+//! last term corrects for $K_0$ not being at the forward.
+//!
+//! The volatility index interpolates the variances of the two expiries
+//! bracketing a (30 day) horizon, linearly in total variance.
+//!
+//! References:
+//!     - Cboe Volatility Index (VIX) white paper.
+//!     - Demeterfi, K., Derman, E., Kamal, M. and Zou, J. (1999),
+//!       More than you ever wanted to know about volatility swaps.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use polars::prelude::*;
+use std::collections::BTreeMap;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Call and put prices of a single expiry, on a common strike grid.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionStrip {
+    /// Strictly increasing strikes.
+    pub strikes: Vec<f64>,
+    /// Call price at each strike.
+    pub calls: Vec<f64>,
+    /// Put price at each strike.
+    pub puts: Vec<f64>,
+}
+
+/// Variance swap: pays `variance_notional` times the difference between
+/// the annualized realized variance and `variance_strike` at maturity.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VarianceSwap {
+    /// Variance strike $K_{\text{var}}$ (annualized, e.g. 0.04 for 20% vol).
+    pub variance_strike: f64,
+    /// Notional per unit of variance.
+    pub variance_notional: f64,
+    /// Maturity, in years.
+    pub maturity: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl OptionStrip {
+    /// New option strip.
+    ///
+    /// # Errors
+    /// `InvalidArgument` if the prices don't match the strikes, there are
+    /// fewer than two strikes, or the strikes are not positive and strictly
+    /// increasing.
+    pub fn new(strikes: Vec<f64>, calls: Vec<f64>, puts: Vec<f64>) -> Result<Self, RustQuantError> {
+        if calls.len() != strikes.len() || puts.len() != strikes.len() {
+            return Err(RustQuantError::InvalidArgument(
+                "There must be a call and a put price for each strike.".to_string(),
+            ));
+        }
+        if strikes.len() < 2 || strikes[0] <= 0.0 || strikes.windows(2).any(|w| w[0] >= w[1]) {
+            return Err(RustQuantError::InvalidArgument(
+                "At least two positive, strictly increasing strikes are required.".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            strikes,
+            calls,
+            puts,
+        })
+    }
+
+    /// Option strip from an options chain `DataFrame` of a single expiry,
+    /// laid out as [`YahooFinanceData::options_chain`](crate::data::YahooFinanceData):
+    /// `contract` (OCC symbols, e.g. `AAPL230526C00250000`), `strike`,
+    /// `bid`, `ask`, and `last_price` columns. Options are priced at the
+    /// bid/ask midpoint when both are quoted, and at the last price
+    /// otherwise; strikes without both a call and a put are dropped.
+    ///
+    /// # Errors
+    /// - `RustQuantError::PolarsError` if a column is missing or has the wrong type.
+    /// - `RustQuantError::MissingInput` if a value is null.
+    /// - `RustQuantError::InvalidArgument` if a contract symbol is malformed,
+    ///   or fewer than two strikes have both a call and a put.
+    pub fn from_chain(chain: &DataFrame) -> Result<Self, RustQuantError> {
+        let column = |name: &str| -> Result<Vec<f64>, RustQuantError> {
+            chain
+                .column(name)?
+                .cast(&DataType::Float64)?
+                .f64()?
+                .into_iter()
+                .map(|value| {
+                    value.ok_or_else(|| {
+                        RustQuantError::MissingInput(format!("Null value in '{name}'."))
+                    })
+                })
+                .collect()
+        };
+
+        let (strikes, bids, asks, last_prices) = (
+            column("strike")?,
+            column("bid")?,
+            column("ask")?,
+            column("last_price")?,
+        );
+
+        // Strikes are positive, so their bit patterns sort like the strikes.
+        let mut quotes: BTreeMap<u64, (Option<f64>, Option<f64>)> = BTreeMap::new();
+
+        for (i, contract) in chain.column("contract")?.str()?.into_iter().enumerate() {
+            let contract = contract
+                .ok_or_else(|| RustQuantError::MissingInput("Null value in 'contract'.".into()))?;
+            let price = if bids[i] > 0.0 && asks[i] > 0.0 {
+                0.5 * (bids[i] + asks[i])
+            } else {
+                last_prices[i]
+            };
+
+            let entry = quotes.entry(strikes[i].to_bits()).or_default();
+            match contract
+                .len()
+                .checked_sub(9)
+                .and_then(|j| contract.get(j..=j))
+            {
+                Some("C") => entry.0 = Some(price),
+                Some("P") => entry.1 = Some(price),
+                _ => {
+                    return Err(RustQuantError::InvalidArgument(format!(
+                        "Malformed contract symbol '{contract}'."
+                    )))
+                }
+            }
+        }
+
+        let (mut strikes, mut calls, mut puts) = (Vec::new(), Vec::new(), Vec::new());
+        for (strike, (call, put)) in quotes {
+            if let (Some(call), Some(put)) = (call, put) {
+                strikes.push(f64::from_bits(strike));
+                calls.push(call);
+                puts.push(put);
+            }
+        }
+
+        Self::new(strikes, calls, puts)
+    }
+
+    /// Forward implied by put-call parity at the strike where the call and
+    /// put prices are closest: $F = K + e^{RT}(C - P)$.
+    #[must_use]
+    pub fn implied_forward(&self, risk_free_rate: f64, time_to_expiry: f64) -> f64 {
+        let i = (0..self.strikes.len())
+            .min_by(|&a, &b| {
+                let gap = |i: usize| (self.calls[i] - self.puts[i]).abs();
+                gap(a).total_cmp(&gap(b))
+            })
+            .unwrap_or(0);
+
+        self.strikes[i] + (risk_free_rate * time_to_expiry).exp() * (self.calls[i] - self.puts[i])
+    }
+
+    /// Fair (annualized) variance strike for the expiry, by the CBOE
+    /// formula, with the forward `forward`.
+    ///
+    /// # Panics
+    /// Panics if `forward` is below the lowest strike.
+    #[must_use]
+    pub fn variance(&self, forward: f64, risk_free_rate: f64, time_to_expiry: f64) -> f64 {
+        let strikes = &self.strikes;
+        let n = strikes.len();
+
+        let k0 = strikes.partition_point(|&strike| strike <= forward);
+        assert!(k0 > 0, "The forward must not be below the lowest strike.");
+        let k0 = k0 - 1;
+
+        let growth = (risk_free_rate * time_to_expiry).exp();
+        let contributions: f64 = (0..n)
+            .map(|i| {
+                let delta_k = match i {
+                    0 => strikes[1] - strikes[0],
+                    _ if i == n - 1 => strikes[n - 1] - strikes[n - 2],
+                    _ => 0.5 * (strikes[i + 1] - strikes[i - 1]),
+                };
+                let q = match i.cmp(&k0) {
+                    std::cmp::Ordering::Less => self.puts[i],
+                    std::cmp::Ordering::Equal => 0.5 * (self.puts[i] + self.calls[i]),
+                    std::cmp::Ordering::Greater => self.calls[i],
+                };
+
+                delta_k / (strikes[i] * strikes[i]) * growth * q
+            })
+            .sum();
+
+        (2.0 * contributions - (forward / strikes[k0] - 1.0).powi(2)) / time_to_expiry
+    }
+}
+
+impl VarianceSwap {
+    /// Payoff at maturity for the annualized realized variance
+    /// `realized_variance`.
+    #[must_use]
+    pub fn payoff(&self, realized_variance: f64) -> f64 {
+        self.variance_notional * (realized_variance - self.variance_strike)
+    }
+
+    /// Mark-to-market value at time `elapsed` (in years), given the
+    /// annualized variance realized so far, the fair variance for the
+    /// remaining life (e.g. [`OptionStrip::variance`] on options expiring
+    /// at maturity), and the risk-free rate for discounting.
+    ///
+    /// Expected realized variance is the time-weighted average of the two:
+    /// $\frac{t}{T} \sigma^2_{\text{realized}} + \frac{T - t}{T} \sigma^2_{\text{implied}}$.
+    #[must_use]
+    pub fn mark_to_market(
+        &self,
+        realized_variance: f64,
+        elapsed: f64,
+        implied_variance: f64,
+        risk_free_rate: f64,
+    ) -> f64 {
+        let remaining = self.maturity - elapsed;
+        let expected_variance =
+            (elapsed * realized_variance + remaining * implied_variance) / self.maturity;
+
+        (-risk_free_rate * remaining).exp() * self.payoff(expected_variance)
+    }
+}
+
+/// Volatility index (in volatility points, e.g. 20 for 20%) at `horizon`
+/// years (30 / 365 for the VIX), interpolating the `(time to expiry,
+/// variance)` of the near and next expiries linearly in total variance.
+///
+/// # Panics
+/// Panics if the expiries are not distinct.
+#[must_use]
+pub fn volatility_index(near: (f64, f64), next: (f64, f64), horizon: f64) -> f64 {
+    let ((t1, variance1), (t2, variance2)) = (near, next);
+    assert!(t1 != t2, "The two expiries must be distinct.");
+
+    let weight = (t2 - horizon) / (t2 - t1);
+    let total_variance = weight * t1 * variance1 + (1.0 - weight) * t2 * variance2;
+
+    100.0 * (total_variance / horizon).sqrt()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_variance_swap {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::options::black_scholes;
+
+    const SPOT: f64 = 100.0;
+    const RATE: f64 = 0.03;
+    const VOL: f64 = 0.2;
+
+    // Black-Scholes call and put prices.
+    fn black_scholes(strike: f64, t: f64) -> (f64, f64) {
+        (
+            black_scholes::call_price(SPOT, strike, RATE, 0.0, VOL, t),
+            black_scholes::put_price(SPOT, strike, RATE, 0.0, VOL, t),
+        )
+    }
+
+    fn strip(spacing: f64, t: f64) -> OptionStrip {
+        let strikes: Vec<f64> = (0..)
+            .map(|i| 30.0 + spacing * f64::from(i))
+            .take_while(|&strike| strike <= 300.0)
+            .collect();
+        let (calls, puts) = strikes
+            .iter()
+            .map(|&strike| black_scholes(strike, t))
+            .unzip();
+
+        OptionStrip::new(strikes, calls, puts).unwrap()
+    }
+
+    #[test]
+    fn test_flat_volatility_fair_strike() {
+        let t = 0.5;
+        let forward = SPOT * (RATE * t).exp();
+
+        let coarse = strip(5.0, t);
+        let fine = strip(1.0, t);
+        assert_approx_equal!(fine.implied_forward(RATE, t), forward, 1e-9);
+
+        let coarse_error = (coarse.variance(forward, RATE, t) - VOL * VOL).abs();
+        let fine_error = (fine.variance(forward, RATE, t) - VOL * VOL).abs();
+
+        assert!(coarse_error < 1e-3);
+        assert!(fine_error < 1e-4);
+        assert!(fine_error < coarse_error);
+    }
+
+    #[test]
+    fn test_volatility_index() {
+        // Flat volatility gives the same index at any horizon.
+        let (t1, t2) = (23.0 / 365.0, 37.0 / 365.0);
+        let index = volatility_index((t1, VOL * VOL), (t2, VOL * VOL), 30.0 / 365.0);
+        assert_approx_equal!(index, 20.0, 1e-12);
+
+        // From the option strips of both expiries.
+        let variance = |t: f64| strip(0.25, t).variance(SPOT * (RATE * t).exp(), RATE, t);
+        let index = volatility_index((t1, variance(t1)), (t2, variance(t2)), 30.0 / 365.0);
+        assert_approx_equal!(index, 20.0, 0.05);
+
+        // Upward sloping term structure: the index lies in between.
+        let index = volatility_index((t1, 0.04), (t2, 0.09), 30.0 / 365.0);
+        assert!(index > 20.0 && index < 30.0);
+    }
+
+    #[test]
+    fn test_variance_swap_mark_to_market() {
+        let swap = VarianceSwap {
+            variance_strike: 0.04,
+            variance_notional: 1e6,
+            maturity: 1.0,
+        };
+
+        assert_approx_equal!(swap.payoff(0.05), 1e4, 1e-6);
+        assert_approx_equal!(swap.mark_to_market(0.0, 0.0, 0.04, RATE), 0.0, 1e-9);
+
+        // Half way through, realized 30% vol, implied 20% vol.
+        let value = swap.mark_to_market(0.09, 0.5, 0.04, RATE);
+        assert_approx_equal!(value, (-RATE * 0.5).exp() * 1e6 * 0.025, 1e-6);
+    }
+
+    #[test]
+    fn test_strip_from_chain() {
+        let chain = df!(
+            "contract" => ["X240119C00090000", "X240119P00090000", "X240119C00100000", "X240119P00100000", "X240119C00110000"],
+            "strike" => [90.0, 90.0, 100.0, 100.0, 110.0],
+            "bid" => [10.0, 0.9, 3.0, 2.9, 0.0],
+            "ask" => [10.4, 1.1, 3.2, 3.1, 0.0],
+            "last_price" => [10.1, 1.0, 3.0, 3.0, 0.5]
+        )
+        .unwrap();
+
+        let strip = OptionStrip::from_chain(&chain).unwrap();
+
+        assert_eq!(strip.strikes, vec![90.0, 100.0]);
+        assert_approx_equal!(strip.calls[0], 10.2, 1e-12);
+        assert_approx_equal!(strip.puts[1], 3.0, 1e-12);
+        assert!(OptionStrip::new(vec![1.0], vec![1.0], vec![1.0]).is_err());
+    }
+}
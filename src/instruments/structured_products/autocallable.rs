@@ -0,0 +1,241 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Autocallable notes.
+//!
+//! On each observation date, if the (worst) performance is at or above the
+//! autocall barrier, the note is redeemed at par plus the coupons accrued
+//! since issue, $N (1 + c \, t_i)$. If the note survives to maturity, the
+//! investor is exposed to the downside through a short put.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{
+    simulate, MonteCarloConfig, PathOutcome, Redemption, StructuredProductMarket,
+    StructuredProductValue,
+};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Downside exposure at maturity, with levels as fractions of the initial
+/// fixing.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Downside {
+    /// Short put: below `strike`, the note redeems $N P_T / K$.
+    Put {
+        /// Strike of the put.
+        strike: f64,
+    },
+    /// Short down-and-in put: as [`Downside::Put`], but only if the
+    /// performance touched `barrier` during the life of the note.
+    BarrierPut {
+        /// Strike of the put.
+        strike: f64,
+        /// Knock-in barrier, monitored at every simulation step.
+        barrier: f64,
+    },
+}
+
+/// Autocallable note on one underlying, or worst-of on several.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Autocallable {
+    /// Notional.
+    pub notional: f64,
+    /// Observation times in years, increasing; the last is maturity.
+    pub observation_times: Vec<f64>,
+    /// Autocall barrier, as a fraction of the initial fixing.
+    pub autocall_barrier: f64,
+    /// Annual coupon rate, paid on redemption for the time since issue.
+    pub coupon_rate: f64,
+    /// Downside exposure at maturity.
+    pub downside: Downside,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Autocallable {
+    /// Monte Carlo price, expected life, and redemption probabilities.
+    ///
+    /// # Panics
+    /// Panics if there are no observation times, or they are not positive
+    /// and increasing.
+    #[must_use]
+    pub fn price(
+        &self,
+        market: &StructuredProductMarket,
+        config: &MonteCarloConfig,
+    ) -> StructuredProductValue {
+        let maturity = self.observation_times[self.observation_times.len() - 1];
+
+        simulate(
+            market,
+            &self.observation_times,
+            config,
+            |worst, observations| {
+                for (i, (&t, &index)) in self.observation_times.iter().zip(observations).enumerate()
+                {
+                    if worst[index] >= self.autocall_barrier {
+                        return PathOutcome {
+                            value: self.notional
+                                * (1.0 + self.coupon_rate * t)
+                                * market.discount_factor(t),
+                            life: t,
+                            redemption: Redemption::Autocall(i),
+                        };
+                    }
+                }
+
+                let final_performance = worst[worst.len() - 1];
+                let (strike, knocked_in) = match self.downside {
+                    Downside::Put { strike } => (strike, true),
+                    Downside::BarrierPut { strike, barrier } => {
+                        (strike, worst.iter().any(|p| *p <= barrier))
+                    }
+                };
+
+                let (redemption, scenario) = if knocked_in && final_performance < strike {
+                    (
+                        self.notional * final_performance / strike,
+                        Redemption::CapitalLoss,
+                    )
+                } else {
+                    (self.notional, Redemption::Par)
+                };
+
+                PathOutcome {
+                    value: redemption * market.discount_factor(maturity),
+                    life: maturity,
+                    redemption: scenario,
+                }
+            },
+        )
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_autocallable {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::options::black_scholes;
+    use crate::math::distributions::{Distribution, Gaussian};
+
+    fn note(autocall_barrier: f64, downside: Downside) -> Autocallable {
+        Autocallable {
+            notional: 1000.0,
+            observation_times: vec![0.5, 1.0, 1.5, 2.0],
+            autocall_barrier,
+            coupon_rate: 0.08,
+            downside,
+        }
+    }
+
+    fn config(n_paths: usize) -> MonteCarloConfig {
+        MonteCarloConfig {
+            n_paths,
+            steps_per_year: 52,
+            ..MonteCarloConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_zero_barrier_calls_on_first_date() {
+        let market = StructuredProductMarket::single(0.25, 0.02, 0.04);
+        let value = note(0.0, Downside::Put { strike: 1.0 }).price(&market, &config(1000));
+
+        assert_approx_equal!(
+            value.price,
+            1000.0 * (1.0 + 0.08 * 0.5) * (-0.04_f64 * 0.5).exp(),
+            1e-9
+        );
+        assert_approx_equal!(value.standard_error, 0.0, 1e-9);
+        assert_approx_equal!(value.expected_life, 0.5, 1e-12);
+        assert_approx_equal!(
+            value.redemption_probabilities[&Redemption::Autocall(0)],
+            1.0,
+            1e-12
+        );
+    }
+
+    #[test]
+    fn test_never_called_is_bond_minus_put() {
+        // Without autocalls, the note is a zero-coupon bond less N / K puts.
+        let (v, q, r, t, k) = (0.25_f64, 0.02_f64, 0.04_f64, 2.0_f64, 0.9_f64);
+        let market = StructuredProductMarket::single(v, q, r);
+        let value =
+            note(f64::INFINITY, Downside::Put { strike: k }).price(&market, &config(20_000));
+
+        let d2 = black_scholes::d2(1.0, k, r, q, v, t);
+        let n = Gaussian::default();
+        let put = black_scholes::put_price(1.0, k, r, q, v, t);
+        let expected = 1000.0 * (-r * t).exp() - 1000.0 / k * put;
+
+        assert!((value.price - expected).abs() < 4.0 * value.standard_error);
+        assert_approx_equal!(value.expected_life, 2.0, 1e-9);
+        assert_approx_equal!(
+            value.redemption_probabilities[&Redemption::CapitalLoss],
+            n.cdf(-d2),
+            0.02
+        );
+    }
+
+    #[test]
+    fn test_scenario_probabilities_and_life() {
+        let market = StructuredProductMarket::single(0.25, 0.02, 0.04);
+        let downside = Downside::BarrierPut {
+            strike: 1.0,
+            barrier: 0.6,
+        };
+        let value = note(1.0, downside).price(&market, &config(5000));
+
+        let total: f64 = value.redemption_probabilities.values().sum();
+        assert_approx_equal!(total, 1.0, 1e-9);
+
+        // Roughly half the notes call on the first date.
+        let first = value.redemption_probabilities[&Redemption::Autocall(0)];
+        assert!(first > 0.4 && first < 0.6);
+        assert!(value.expected_life > 0.5 && value.expected_life < 2.0);
+
+        // A knock-in barrier makes the note safer than a plain put.
+        let put = note(1.0, Downside::Put { strike: 1.0 }).price(&market, &config(5000));
+        assert!(value.price > put.price);
+    }
+
+    #[test]
+    fn test_worst_of_two_underlyings() {
+        let downside = Downside::BarrierPut {
+            strike: 1.0,
+            barrier: 0.6,
+        };
+        let price = |market: &StructuredProductMarket| {
+            note(1.0, downside).price(market, &config(5000)).price
+        };
+
+        let single = price(&StructuredProductMarket::single(0.25, 0.02, 0.04));
+        let high = price(
+            &StructuredProductMarket::two_assets([0.25, 0.25], [0.02, 0.02], 0.9, 0.04).unwrap(),
+        );
+        let low = price(
+            &StructuredProductMarket::two_assets([0.25, 0.25], [0.02, 0.02], 0.2, 0.04).unwrap(),
+        );
+
+        // The worst-of is worth less, and less still with lower correlation.
+        assert!(high < single);
+        assert!(low < high);
+    }
+}
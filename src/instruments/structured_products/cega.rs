@@ -0,0 +1,492 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Correlation sensitivities (cega) of multi-asset payoffs.
+//!
+//! The terminal performances are $P_k = \exp((r - q_k - \sigma_k^2 / 2) T +
+//! \sigma_k \sqrt{T} (L Z)_k)$, with $L$ the Cholesky factor of the
+//! correlation matrix. The sensitivity to each pairwise correlation
+//! $\rho_{ij} = \rho_{ji}$ is estimated either
+//!
+//! - by central finite differences, re-simulating with the bumped Cholesky
+//!   factor and, by default, the same normals $Z$ (common random numbers),
+//!   so that the noise largely cancels in the difference; or
+//! - pathwise, differentiating through the Cholesky factor:
+//!   $\partial L = L \, \Phi(L^{-1} \partial \Sigma L^{-\top})$, where
+//!   $\Phi$ keeps the lower triangle and halves the diagonal.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{MonteCarloConfig, StructuredProductMarket};
+use crate::error::RustQuantError;
+use nalgebra::{DMatrix, DVector};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand_distr::StandardNormal;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Estimator of the correlation sensitivities.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CegaMethod {
+    /// Central finite differences with correlation bump `bump`.
+    FiniteDifference {
+        /// Size of the correlation bump.
+        bump: f64,
+        /// Whether the bumped runs reuse the base normals. Independent
+        /// reruns are only useful to show how noisy they are.
+        common_random_numbers: bool,
+    },
+    /// Pathwise derivative through the Cholesky factor. The payoff is
+    /// differentiated numerically on each path, so it must be Lipschitz
+    /// (calls and puts are, digitals are not).
+    Pathwise,
+}
+
+/// Price and correlation sensitivities of a multi-asset payoff.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cega {
+    /// Present value.
+    pub price: f64,
+    /// Standard error of the price.
+    pub standard_error: f64,
+    /// Symmetric matrix of $\partial V / \partial \rho_{ij}$, with a zero
+    /// diagonal.
+    pub sensitivities: DMatrix<f64>,
+    /// Standard errors of the sensitivities.
+    pub standard_errors: DMatrix<f64>,
+}
+
+/// Terminal performance sampler for one market and maturity.
+struct TerminalSampler<'a> {
+    drifts: Vec<f64>,
+    diffusions: Vec<f64>,
+    signs: &'a [f64],
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Default for CegaMethod {
+    fn default() -> Self {
+        Self::FiniteDifference {
+            bump: 0.01,
+            common_random_numbers: true,
+        }
+    }
+}
+
+impl TerminalSampler<'_> {
+    /// Terminal performances for correlated normals `w = sign * L z`.
+    fn performances(&self, w: &DVector<f64>) -> Vec<f64> {
+        self.drifts
+            .iter()
+            .zip(&self.diffusions)
+            .zip(w.iter())
+            .map(|((drift, diffusion), w)| (drift + diffusion * w).exp())
+            .collect()
+    }
+
+    /// Discounted payoff of each draw, averaged over antithetic pairs.
+    fn samples<F>(&self, cholesky: &DMatrix<f64>, normals: &[DVector<f64>], payoff: &F) -> Vec<f64>
+    where
+        F: Fn(&[f64]) -> f64,
+    {
+        normals
+            .iter()
+            .map(|z| {
+                let w = cholesky * z;
+
+                self.signs
+                    .iter()
+                    .map(|sign| payoff(&self.performances(&(&w * *sign))))
+                    .sum::<f64>()
+                    / self.signs.len() as f64
+            })
+            .collect()
+    }
+}
+
+/// Mean and standard error of the mean.
+fn mean_and_standard_error(samples: &[f64]) -> (f64, f64) {
+    let n = samples.len() as f64;
+    let mean = samples.iter().sum::<f64>() / n;
+    let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+
+    (mean, (variance / n).sqrt())
+}
+
+fn draw_normals(n_paths: usize, n_assets: usize, seed: u64) -> Vec<DVector<f64>> {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    (0..n_paths)
+        .map(|_| DVector::from_fn(n_assets, |_, _| rng.sample(StandardNormal)))
+        .collect()
+}
+
+/// Lower Cholesky factor of `correlation`.
+fn cholesky_factor(correlation: DMatrix<f64>) -> Result<DMatrix<f64>, RustQuantError> {
+    correlation.cholesky().map(|c| c.l()).ok_or_else(|| {
+        RustQuantError::InvalidArgument(
+            "Bumped correlation matrix is not positive definite; use a smaller bump.".to_string(),
+        )
+    })
+}
+
+/// Derivative of the Cholesky factor `l` with respect to $\rho_{ij}$.
+fn cholesky_derivative(l: &DMatrix<f64>, i: usize, j: usize) -> DMatrix<f64> {
+    let n = l.nrows();
+    let l_inv = l
+        .clone()
+        .try_inverse()
+        .expect("A Cholesky factor is invertible.");
+
+    let mut d_sigma = DMatrix::zeros(n, n);
+    d_sigma[(i, j)] = 1.0;
+    d_sigma[(j, i)] = 1.0;
+
+    let mut phi = &l_inv * d_sigma * l_inv.transpose();
+    for r in 0..n {
+        phi[(r, r)] *= 0.5;
+        for c in (r + 1)..n {
+            phi[(r, c)] = 0.0;
+        }
+    }
+
+    l * phi
+}
+
+/// Price and correlation sensitivities of a European payoff of the
+/// terminal performances $S_T / S_0$ of the underlyings.
+///
+/// `payoff` is undiscounted; the time grid of `config` is not used, as the
+/// terminal performances are simulated exactly.
+///
+/// # Errors
+/// - `InvalidArgument` if a bumped correlation matrix is not positive
+///   definite.
+///
+/// # Panics
+/// Panics if `maturity` is not positive or there are fewer than two paths.
+pub fn cega<F>(
+    market: &StructuredProductMarket,
+    maturity: f64,
+    payoff: F,
+    config: &MonteCarloConfig,
+    method: CegaMethod,
+) -> Result<Cega, RustQuantError>
+where
+    F: Fn(&[f64]) -> f64,
+{
+    assert!(maturity > 0.0, "Maturity must be positive.");
+    assert!(config.n_paths > 1, "At least two paths are required.");
+
+    let n_assets = market.n_assets();
+    let df = market.discount_factor(maturity);
+    let discounted = |p: &[f64]| df * payoff(p);
+
+    let sampler = TerminalSampler {
+        drifts: market
+            .volatilities()
+            .iter()
+            .zip(market.dividend_yields())
+            .map(|(v, q)| (market.risk_free_rate() - q - 0.5 * v * v) * maturity)
+            .collect(),
+        diffusions: market
+            .volatilities()
+            .iter()
+            .map(|v| v * maturity.sqrt())
+            .collect(),
+        signs: if config.antithetic {
+            &[1.0, -1.0]
+        } else {
+            &[1.0]
+        },
+    };
+
+    let correlation = market.correlation();
+    let cholesky = cholesky_factor(correlation.clone())?;
+    let normals = draw_normals(config.n_paths, n_assets, config.seed);
+    let (price, standard_error) =
+        mean_and_standard_error(&sampler.samples(&cholesky, &normals, &discounted));
+
+    let mut sensitivities = DMatrix::zeros(n_assets, n_assets);
+    let mut standard_errors = DMatrix::zeros(n_assets, n_assets);
+
+    for i in 0..n_assets {
+        for j in (i + 1)..n_assets {
+            let (estimate, error) = match method {
+                CegaMethod::FiniteDifference {
+                    bump,
+                    common_random_numbers,
+                } => {
+                    let bumped = |h: f64| {
+                        let mut c = correlation.clone();
+                        c[(i, j)] += h;
+                        c[(j, i)] += h;
+                        cholesky_factor(c)
+                    };
+                    let (up, down) = (bumped(bump)?, bumped(-bump)?);
+
+                    if common_random_numbers {
+                        let up = sampler.samples(&up, &normals, &discounted);
+                        let down = sampler.samples(&down, &normals, &discounted);
+                        let differences: Vec<f64> = up
+                            .iter()
+                            .zip(&down)
+                            .map(|(u, d)| (u - d) / (2.0 * bump))
+                            .collect();
+
+                        mean_and_standard_error(&differences)
+                    } else {
+                        let seed = config.seed.wrapping_add(2 * (i * n_assets + j) as u64);
+                        let (up, up_error) = mean_and_standard_error(&sampler.samples(
+                            &up,
+                            &draw_normals(config.n_paths, n_assets, seed.wrapping_add(1)),
+                            &discounted,
+                        ));
+                        let (down, down_error) = mean_and_standard_error(&sampler.samples(
+                            &down,
+                            &draw_normals(config.n_paths, n_assets, seed.wrapping_add(2)),
+                            &discounted,
+                        ));
+
+                        (
+                            (up - down) / (2.0 * bump),
+                            up_error.hypot(down_error) / (2.0 * bump),
+                        )
+                    }
+                }
+                CegaMethod::Pathwise => {
+                    let d_cholesky = cholesky_derivative(&cholesky, i, j);
+                    let derivatives: Vec<f64> = normals
+                        .iter()
+                        .map(|z| {
+                            let (w, dw) = (&cholesky * z, &d_cholesky * z);
+
+                            sampler
+                                .signs
+                                .iter()
+                                .map(|sign| {
+                                    let p = sampler.performances(&(&w * *sign));
+                                    pathwise_derivative(&discounted, &p, |k| {
+                                        p[k] * sampler.diffusions[k] * sign * dw[k]
+                                    })
+                                })
+                                .sum::<f64>()
+                                / sampler.signs.len() as f64
+                        })
+                        .collect();
+
+                    mean_and_standard_error(&derivatives)
+                }
+            };
+
+            sensitivities[(i, j)] = estimate;
+            sensitivities[(j, i)] = estimate;
+            standard_errors[(i, j)] = error;
+            standard_errors[(j, i)] = error;
+        }
+    }
+
+    Ok(Cega {
+        price,
+        standard_error,
+        sensitivities,
+        standard_errors,
+    })
+}
+
+/// Directional derivative of `payoff` at `p` along `dp(k)`, by central
+/// differences with a relative step.
+fn pathwise_derivative<F, D>(payoff: &F, p: &[f64], dp: D) -> f64
+where
+    F: Fn(&[f64]) -> f64,
+    D: Fn(usize) -> f64,
+{
+    let step = 1e-6;
+    let direction: Vec<f64> = (0..p.len()).map(dp).collect();
+    let scale = direction.iter().fold(0.0_f64, |m, d| m.max(d.abs()));
+
+    if scale == 0.0 {
+        return 0.0;
+    }
+
+    let h = step / scale;
+    let shifted =
+        |h: f64| -> Vec<f64> { p.iter().zip(&direction).map(|(p, d)| p + h * d).collect() };
+
+    (payoff(&shifted(h)) - payoff(&shifted(-h))) / (2.0 * h)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_cega {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::math::distributions::{Distribution, Gaussian};
+
+    const V1: f64 = 0.3;
+    const V2: f64 = 0.2;
+    const Q1: f64 = 0.01;
+    const Q2: f64 = 0.03;
+    const T: f64 = 1.0;
+    const RHO: f64 = 0.4;
+
+    fn market() -> StructuredProductMarket {
+        StructuredProductMarket::two_assets([V1, V2], [Q1, Q2], RHO, 0.05).unwrap()
+    }
+
+    fn config(n_paths: usize) -> MonteCarloConfig {
+        MonteCarloConfig {
+            n_paths,
+            ..MonteCarloConfig::default()
+        }
+    }
+
+    fn best_of_call(p: &[f64]) -> f64 {
+        (p[0].max(p[1]) - 1.0).max(0.0)
+    }
+
+    fn exchange(p: &[f64]) -> f64 {
+        (p[0] - p[1]).max(0.0)
+    }
+
+    /// Margrabe exchange option price and its derivative in the correlation.
+    fn margrabe(rho: f64) -> (f64, f64) {
+        let n = Gaussian::default();
+        let v = (V1 * V1 + V2 * V2 - 2.0 * rho * V1 * V2).sqrt();
+        let d1 = ((Q2 - Q1) * T + 0.5 * v * v * T) / (v * T.sqrt());
+        let d2 = d1 - v * T.sqrt();
+
+        let price = (-Q1 * T).exp() * n.cdf(d1) - (-Q2 * T).exp() * n.cdf(d2);
+        let vega = (-Q1 * T).exp() * n.pdf(d1) * T.sqrt();
+
+        (price, vega * (-V1 * V2 / v))
+    }
+
+    #[test]
+    fn test_crn_cega_against_margrabe() {
+        let (price, exact) = margrabe(RHO);
+        let result = cega(
+            &market(),
+            T,
+            exchange,
+            &config(50_000),
+            CegaMethod::default(),
+        )
+        .unwrap();
+
+        assert!((result.price - price).abs() < 4.0 * result.standard_error);
+
+        let estimate = result.sensitivities[(0, 1)];
+        assert!(
+            (estimate - exact).abs() < 4.0 * result.standard_errors[(0, 1)] + 1e-3,
+            "{estimate} vs {exact}"
+        );
+        assert_approx_equal!(result.sensitivities[(1, 0)], estimate, 1e-15);
+        assert_approx_equal!(result.sensitivities[(0, 0)], 0.0, 1e-15);
+    }
+
+    #[test]
+    fn test_pathwise_cega_against_margrabe() {
+        let (_, exact) = margrabe(RHO);
+        let result = cega(
+            &market(),
+            T,
+            exchange,
+            &config(50_000),
+            CegaMethod::Pathwise,
+        )
+        .unwrap();
+
+        let estimate = result.sensitivities[(0, 1)];
+        assert!(
+            (estimate - exact).abs() < 4.0 * result.standard_errors[(0, 1)],
+            "{estimate} vs {exact}"
+        );
+    }
+
+    #[test]
+    fn test_common_random_numbers_reduce_variance() {
+        let crn = cega(
+            &market(),
+            T,
+            best_of_call,
+            &config(20_000),
+            CegaMethod::default(),
+        )
+        .unwrap();
+        let independent = cega(
+            &market(),
+            T,
+            best_of_call,
+            &config(20_000),
+            CegaMethod::FiniteDifference {
+                bump: 0.01,
+                common_random_numbers: false,
+            },
+        )
+        .unwrap();
+
+        // Best-of calls lose value as correlation rises.
+        assert!(crn.sensitivities[(0, 1)] < 0.0);
+
+        // Independent reruns are an order of magnitude noisier or worse.
+        let ratio = independent.standard_errors[(0, 1)] / crn.standard_errors[(0, 1)];
+        assert!(ratio > 10.0, "{ratio}");
+
+        // The two unbiased estimators agree within their noise.
+        assert!(
+            (crn.sensitivities[(0, 1)] - independent.sensitivities[(0, 1)]).abs()
+                < 4.0 * independent.standard_errors[(0, 1)]
+        );
+
+        let pathwise = cega(
+            &market(),
+            T,
+            best_of_call,
+            &config(20_000),
+            CegaMethod::Pathwise,
+        )
+        .unwrap();
+        assert!(
+            (crn.sensitivities[(0, 1)] - pathwise.sensitivities[(0, 1)]).abs()
+                < 4.0 * crn.standard_errors[(0, 1)].hypot(pathwise.standard_errors[(0, 1)])
+        );
+    }
+
+    #[test]
+    fn test_cholesky_derivative() {
+        let correlation =
+            DMatrix::from_row_slice(3, 3, &[1.0, 0.3, 0.2, 0.3, 1.0, -0.1, 0.2, -0.1, 1.0]);
+        let l = cholesky_factor(correlation.clone()).unwrap();
+        let (i, j, h) = (0, 2, 1e-6);
+
+        let mut bumped = correlation;
+        bumped[(i, j)] += h;
+        bumped[(j, i)] += h;
+        let numerical = (cholesky_factor(bumped).unwrap() - &l) / h;
+
+        assert!((cholesky_derivative(&l, i, j) - numerical).amax() < 1e-5);
+    }
+
+    #[test]
+    fn test_bump_out_of_range() {
+        let market = StructuredProductMarket::two_assets([V1, V2], [Q1, Q2], 0.995, 0.05).unwrap();
+        assert!(cega(&market, T, exchange, &config(100), CegaMethod::default()).is_err());
+    }
+}
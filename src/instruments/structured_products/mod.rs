@@ -0,0 +1,24 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Autocallable notes.
+pub mod autocallable;
+pub use autocallable::*;
+
+/// Correlation sensitivities of multi-asset payoffs.
+pub mod cega;
+pub use cega::*;
+
+/// Barrier reverse convertibles.
+pub mod reverse_convertible;
+pub use reverse_convertible::*;
+
+/// Monte Carlo engine for structured products.
+pub mod simulation;
+pub use simulation::*;
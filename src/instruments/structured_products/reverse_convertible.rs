@@ -0,0 +1,279 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Barrier reverse convertibles.
+//!
+//! The note pays fixed coupons whatever happens. At maturity it redeems at
+//! par, unless the (worst) performance touched the knock-in barrier during
+//! its life and ends below the strike, in which case it redeems $N P_T / K$.
+//! That is, the note is a coupon bond less $N / K$ down-and-in puts on the
+//! performance.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{
+    simulate, MonteCarloConfig, PathOutcome, Redemption, StructuredProductMarket,
+    StructuredProductValue,
+};
+use crate::math::distributions::{Distribution, Gaussian};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Barrier reverse convertible on one underlying, or worst-of on several.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReverseConvertible {
+    /// Notional.
+    pub notional: f64,
+    /// Coupon payment times in years, increasing; the last is maturity.
+    pub coupon_times: Vec<f64>,
+    /// Annual coupon rate, accrued between coupon times.
+    pub coupon_rate: f64,
+    /// Strike, as a fraction of the initial fixing.
+    pub strike: f64,
+    /// Knock-in barrier, as a fraction of the initial fixing.
+    pub knock_in_barrier: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl ReverseConvertible {
+    /// Maturity, in years.
+    #[must_use]
+    pub fn maturity(&self) -> f64 {
+        self.coupon_times[self.coupon_times.len() - 1]
+    }
+
+    /// Present value of the coupons and the notional: the bond component.
+    #[must_use]
+    pub fn bond_value(&self, market: &StructuredProductMarket) -> f64 {
+        let mut previous = 0.0;
+        let mut value = self.notional * market.discount_factor(self.maturity());
+
+        for &t in &self.coupon_times {
+            value += self.notional * self.coupon_rate * (t - previous) * market.discount_factor(t);
+            previous = t;
+        }
+
+        value
+    }
+
+    /// Monte Carlo price, expected life, and redemption probabilities.
+    ///
+    /// The barrier is monitored at every simulation step.
+    ///
+    /// # Panics
+    /// Panics if there are no coupon times, or they are not positive and
+    /// increasing.
+    #[must_use]
+    pub fn price(
+        &self,
+        market: &StructuredProductMarket,
+        config: &MonteCarloConfig,
+    ) -> StructuredProductValue {
+        let maturity = self.maturity();
+        let bond = self.bond_value(market);
+        let df = market.discount_factor(maturity);
+
+        simulate(market, &[maturity], config, |worst, _| {
+            let final_performance = worst[worst.len() - 1];
+            let knocked_in = worst.iter().any(|p| *p <= self.knock_in_barrier);
+
+            let (loss, redemption) = if knocked_in && final_performance < self.strike {
+                (
+                    self.notional * (1.0 - final_performance / self.strike),
+                    Redemption::CapitalLoss,
+                )
+            } else {
+                (0.0, Redemption::Par)
+            };
+
+            PathOutcome {
+                value: bond - loss * df,
+                life: maturity,
+                redemption,
+            }
+        })
+    }
+
+    /// Closed-form price on a single underlying with a continuously
+    /// monitored barrier: the bond value less $N / K$ down-and-in puts,
+    /// priced with the Reiner-Rubinstein formula (see Haug's *Complete Guide
+    /// to Option Pricing Formulas*).
+    ///
+    /// # Panics
+    /// Panics if the market has more than one underlying.
+    #[must_use]
+    pub fn price_closed_form(&self, market: &StructuredProductMarket) -> f64 {
+        assert_eq!(
+            market.n_assets(),
+            1,
+            "The closed form needs a single underlying."
+        );
+
+        let put = down_and_in_put(
+            self.strike,
+            self.knock_in_barrier,
+            self.maturity(),
+            market.risk_free_rate(),
+            market.dividend_yields()[0],
+            market.volatilities()[0],
+        );
+
+        self.bond_value(market) - self.notional / self.strike * put
+    }
+}
+
+/// Down-and-in put on a unit spot, with strike `k` and barrier `h` below
+/// the spot.
+fn down_and_in_put(k: f64, h: f64, t: f64, r: f64, q: f64, v: f64) -> f64 {
+    if h <= 0.0 {
+        return 0.0;
+    }
+
+    let n = Gaussian::default();
+    let b = r - q;
+    let vt = v * t.sqrt();
+    let mu = (b - 0.5 * v * v) / (v * v);
+
+    // Vanilla put with d1 = x, and its reflection in the barrier with d1 = y.
+    let put = |x: f64| (k * (-r * t).exp() * n.cdf(-x + vt)) - ((-q * t).exp() * n.cdf(-x));
+    let reflected = |y: f64| {
+        h.powf(2.0 * (mu + 1.0)) * (-q * t).exp() * n.cdf(y)
+            - k * (-r * t).exp() * h.powf(2.0 * mu) * n.cdf(y - vt)
+    };
+
+    let x1 = -k.ln() / vt + (1.0 + mu) * vt;
+
+    if k <= h {
+        return put(x1);
+    }
+
+    let x2 = -h.ln() / vt + (1.0 + mu) * vt;
+    let y1 = (h * h / k).ln() / vt + (1.0 + mu) * vt;
+    let y2 = h.ln() / vt + (1.0 + mu) * vt;
+
+    // B - C + D in Haug's notation, with phi = -1 and eta = 1.
+    put(x2) + reflected(y1) - reflected(y2)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_reverse_convertible {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    fn note(knock_in_barrier: f64) -> ReverseConvertible {
+        ReverseConvertible {
+            notional: 1000.0,
+            coupon_times: vec![0.25, 0.5, 0.75, 1.0],
+            coupon_rate: 0.1,
+            strike: 1.0,
+            knock_in_barrier,
+        }
+    }
+
+    #[test]
+    fn test_down_and_in_put() {
+        let (k, t, r, q, v) = (1.0, 1.0, 0.05, 0.02, 0.3);
+
+        // Knocking in at or above the strike gives the vanilla put.
+        let vanilla = down_and_in_put(k, 1.0, t, r, q, v);
+        assert_approx_equal!(down_and_in_put(k, 1.2, t, r, q, v), vanilla, 1e-12);
+
+        // Continuity in the barrier at the strike, and no value for a
+        // barrier far below the spot.
+        assert_approx_equal!(down_and_in_put(k, 0.999_999, t, r, q, v), vanilla, 1e-5);
+        assert!(down_and_in_put(k, 0.7, t, r, q, v) < vanilla);
+        assert_approx_equal!(down_and_in_put(k, 1e-3, t, r, q, v), 0.0, 1e-12);
+    }
+
+    #[test]
+    fn test_decomposition_against_closed_form() {
+        let market = StructuredProductMarket::single(0.3, 0.02, 0.05);
+        let steps_per_year = 252;
+        let config = MonteCarloConfig {
+            n_paths: 20_000,
+            steps_per_year,
+            ..MonteCarloConfig::default()
+        };
+
+        let value = note(0.7).price(&market, &config);
+
+        // Broadie-Glasserman-Kou: daily monitoring of a barrier H is close to
+        // continuous monitoring of H exp(-0.5826 v sqrt(dt)).
+        let shift = (-0.5826 * 0.3 * (1.0 / steps_per_year as f64).sqrt()).exp();
+        let closed_form = note(0.7 * shift).price_closed_form(&market);
+
+        assert!(
+            (value.price - closed_form).abs() < 4.0 * value.standard_error,
+            "{} vs {closed_form}",
+            value.price
+        );
+        assert!(value.price < note(0.7).bond_value(&market));
+        assert_approx_equal!(value.expected_life, 1.0, 1e-9);
+
+        let loss = value.redemption_probabilities[&Redemption::CapitalLoss];
+        assert_approx_equal!(
+            loss + value.redemption_probabilities[&Redemption::Par],
+            1.0,
+            1e-9
+        );
+    }
+
+    #[test]
+    fn test_unreachable_barrier_is_a_bond() {
+        let market = StructuredProductMarket::single(0.3, 0.02, 0.05);
+        let config = MonteCarloConfig {
+            n_paths: 100,
+            ..MonteCarloConfig::default()
+        };
+        let rc = note(0.0);
+
+        let value = rc.price(&market, &config);
+        assert_approx_equal!(value.price, rc.bond_value(&market), 1e-9);
+        assert_approx_equal!(rc.price_closed_form(&market), rc.bond_value(&market), 1e-9);
+
+        // Coupons of 25 paid quarterly, and the notional.
+        let bond: f64 = [0.25, 0.5, 0.75, 1.0]
+            .iter()
+            .map(|t: &f64| 25.0 * (-0.05 * t).exp())
+            .sum::<f64>()
+            + 1000.0 * (-0.05_f64).exp();
+        assert_approx_equal!(rc.bond_value(&market), bond, 1e-9);
+    }
+
+    #[test]
+    fn test_worst_of_reverse_convertible() {
+        let config = MonteCarloConfig {
+            n_paths: 5000,
+            steps_per_year: 52,
+            ..MonteCarloConfig::default()
+        };
+        let single = note(0.7).price(&StructuredProductMarket::single(0.3, 0.02, 0.05), &config);
+        let worst_of = note(0.7).price(
+            &StructuredProductMarket::two_assets([0.3, 0.3], [0.02, 0.02], 0.5, 0.05).unwrap(),
+            &config,
+        );
+
+        assert!(worst_of.price < single.price);
+        assert!(
+            worst_of.redemption_probabilities[&Redemption::CapitalLoss]
+                > single.redemption_probabilities[&Redemption::CapitalLoss]
+        );
+    }
+}
@@ -0,0 +1,402 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Monte Carlo engine shared by the structured products.
+//!
+//! The underlyings follow correlated geometric Brownian motions, simulated
+//! exactly on a time grid containing the product's key dates. Products are
+//! valued at inception, on the performance $S_t / S_0$ of each underlying,
+//! and worst-of products look at the lowest performance at each date.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use crate::math::{distributions::CholeskyCache, SimulationConfig};
+use nalgebra::DMatrix;
+use rand::{rngs::StdRng, SeedableRng};
+use std::collections::BTreeMap;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Market data for the underlyings of a structured product.
+#[derive(Debug, Clone)]
+pub struct StructuredProductMarket {
+    /// Volatility of each underlying.
+    volatilities: Vec<f64>,
+    /// Continuous dividend yield of each underlying.
+    dividend_yields: Vec<f64>,
+    /// Correlation matrix of the underlyings, with its Cholesky factor.
+    correlation: CholeskyCache,
+    /// Continuously compounded risk-free rate.
+    risk_free_rate: f64,
+}
+
+/// Settings for the Monte Carlo valuation of a structured product.
+#[derive(Debug, Clone, Copy)]
+pub struct MonteCarloConfig {
+    /// Number of independent draws (pairs of paths with antithetic variates).
+    pub n_paths: usize,
+    /// Simulation steps per year. Barriers are monitored at every step, so
+    /// 252 gives daily monitoring.
+    pub steps_per_year: usize,
+    /// Whether to pair each path with its antithetic path.
+    pub antithetic: bool,
+    /// Seed of the random number generator.
+    pub seed: u64,
+}
+
+/// How a structured product was redeemed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Redemption {
+    /// Called early on the observation date with the given index.
+    Autocall(usize),
+    /// Redeemed at par at maturity.
+    Par,
+    /// Redeemed at maturity with a loss of capital.
+    CapitalLoss,
+}
+
+/// Monte Carlo valuation of a structured product.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructuredProductValue {
+    /// Present value.
+    pub price: f64,
+    /// Standard error of the price.
+    pub standard_error: f64,
+    /// Expected time to redemption, in years.
+    pub expected_life: f64,
+    /// Probability of each redemption scenario.
+    pub redemption_probabilities: BTreeMap<Redemption, f64>,
+}
+
+/// Value of one simulated path.
+pub(crate) struct PathOutcome {
+    /// Present value of the cash flows.
+    pub(crate) value: f64,
+    /// Time of redemption, in years.
+    pub(crate) life: f64,
+    /// Redemption scenario.
+    pub(crate) redemption: Redemption,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl StructuredProductMarket {
+    /// Market for a product on a single underlying.
+    #[must_use]
+    pub fn single(volatility: f64, dividend_yield: f64, risk_free_rate: f64) -> Self {
+        Self {
+            volatilities: vec![volatility],
+            dividend_yields: vec![dividend_yield],
+            correlation: CholeskyCache::new(DMatrix::identity(1, 1)),
+            risk_free_rate,
+        }
+    }
+
+    /// Market for a product on several underlyings.
+    ///
+    /// # Errors
+    /// - `InvalidArgument` if the inputs have different lengths, or the
+    ///   correlation matrix is not positive definite.
+    pub fn new(
+        volatilities: Vec<f64>,
+        dividend_yields: Vec<f64>,
+        correlation: DMatrix<f64>,
+        risk_free_rate: f64,
+    ) -> Result<Self, RustQuantError> {
+        let n = volatilities.len();
+
+        if n == 0 || dividend_yields.len() != n || correlation.shape() != (n, n) {
+            return Err(RustQuantError::InvalidArgument(
+                "Expected one volatility, dividend yield, and correlation row per underlying."
+                    .to_string(),
+            ));
+        }
+
+        let correlation = CholeskyCache::new(correlation);
+        correlation.cholesky().map_err(|_| {
+            RustQuantError::InvalidArgument(
+                "Correlation matrix must be positive definite.".to_string(),
+            )
+        })?;
+
+        Ok(Self {
+            volatilities,
+            dividend_yields,
+            correlation,
+            risk_free_rate,
+        })
+    }
+
+    /// Market for a worst-of product on two underlyings with correlation `rho`.
+    ///
+    /// # Errors
+    /// - `InvalidArgument` if `rho` is not strictly between -1 and 1.
+    pub fn two_assets(
+        volatilities: [f64; 2],
+        dividend_yields: [f64; 2],
+        rho: f64,
+        risk_free_rate: f64,
+    ) -> Result<Self, RustQuantError> {
+        Self::new(
+            volatilities.to_vec(),
+            dividend_yields.to_vec(),
+            DMatrix::from_row_slice(2, 2, &[1.0, rho, rho, 1.0]),
+            risk_free_rate,
+        )
+    }
+
+    /// Number of underlyings.
+    #[must_use]
+    pub fn n_assets(&self) -> usize {
+        self.volatilities.len()
+    }
+
+    /// Continuously compounded risk-free rate.
+    #[must_use]
+    pub fn risk_free_rate(&self) -> f64 {
+        self.risk_free_rate
+    }
+
+    /// Volatility of each underlying.
+    #[must_use]
+    pub fn volatilities(&self) -> &[f64] {
+        &self.volatilities
+    }
+
+    /// Continuous dividend yield of each underlying.
+    #[must_use]
+    pub fn dividend_yields(&self) -> &[f64] {
+        &self.dividend_yields
+    }
+
+    /// Correlation matrix of the underlyings.
+    #[must_use]
+    pub fn correlation(&self) -> &DMatrix<f64> {
+        self.correlation.covariance()
+    }
+
+    /// Discount factor to time `t`.
+    pub(crate) fn discount_factor(&self, t: f64) -> f64 {
+        (-self.risk_free_rate * t).exp()
+    }
+}
+
+impl MonteCarloConfig {
+    /// Settings from a [`SimulationConfig`], with `config.n_steps` read as
+    /// the number of steps per year, and the seed the config's child seed
+    /// for `"instruments.structured_products"`.
+    ///
+    /// The paths are always pseudo-random.
+    #[must_use]
+    pub fn from_simulation_config(config: &SimulationConfig) -> Self {
+        Self {
+            n_paths: config.n_paths,
+            steps_per_year: config.n_steps,
+            antithetic: config.antithetic,
+            seed: config.child_seed("instruments.structured_products"),
+        }
+    }
+}
+
+impl Default for MonteCarloConfig {
+    fn default() -> Self {
+        Self {
+            n_paths: 50_000,
+            steps_per_year: 252,
+            antithetic: true,
+            seed: 42,
+        }
+    }
+}
+
+/// Time grid from zero through the (increasing, positive) `key_times`, with
+/// about `steps_per_year` steps per year, and the index of each key time.
+fn time_grid(key_times: &[f64], steps_per_year: usize) -> (Vec<f64>, Vec<usize>) {
+    let mut grid = vec![0.0];
+    let mut key_indices = Vec::with_capacity(key_times.len());
+
+    for &t in key_times {
+        let start = grid[grid.len() - 1];
+        let steps = (((t - start) * steps_per_year as f64).ceil() as usize).max(1);
+
+        grid.extend((1..=steps).map(|i| start + (t - start) * i as f64 / steps as f64));
+        key_indices.push(grid.len() - 1);
+    }
+
+    (grid, key_indices)
+}
+
+/// Values a structured product by Monte Carlo.
+///
+/// `evaluate` is given the worst performance across the underlyings at each
+/// point of the time grid, and the grid index of each key time.
+pub(crate) fn simulate<F>(
+    market: &StructuredProductMarket,
+    key_times: &[f64],
+    config: &MonteCarloConfig,
+    evaluate: F,
+) -> StructuredProductValue
+where
+    F: Fn(&[f64], &[usize]) -> PathOutcome,
+{
+    assert!(config.n_paths > 1, "At least two paths are required.");
+    assert!(
+        config.steps_per_year > 0,
+        "Steps per year must be positive."
+    );
+    assert!(
+        !key_times.is_empty()
+            && key_times[0] > 0.0
+            && key_times.windows(2).all(|pair| pair[0] < pair[1]),
+        "Key times must be positive and increasing."
+    );
+
+    let (grid, key_indices) = time_grid(key_times, config.steps_per_year);
+    let n_assets = market.n_assets();
+    let n_steps = grid.len() - 1;
+
+    // Drift and diffusion of the log performance over each step.
+    let steps: Vec<(Vec<f64>, Vec<f64>)> = grid
+        .windows(2)
+        .map(|pair| {
+            let dt = pair[1] - pair[0];
+
+            market
+                .volatilities
+                .iter()
+                .zip(&market.dividend_yields)
+                .map(|(v, q)| {
+                    (
+                        (market.risk_free_rate - q - 0.5 * v * v) * dt,
+                        v * dt.sqrt(),
+                    )
+                })
+                .unzip()
+        })
+        .collect();
+
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut worst = vec![1.0; grid.len()];
+
+    let signs: &[f64] = if config.antithetic {
+        &[1.0, -1.0]
+    } else {
+        &[1.0]
+    };
+    let n_simulated = (config.n_paths * signs.len()) as f64;
+
+    let mut samples = Vec::with_capacity(config.n_paths);
+    let mut total_life = 0.0;
+    let mut redemption_counts = BTreeMap::new();
+
+    for _ in 0..config.n_paths {
+        // Correlated normal increments.
+        let normals = market.correlation.sample(n_steps, &mut rng);
+
+        let mut sample = 0.0;
+
+        for sign in signs {
+            let mut log_performance = vec![0.0; n_assets];
+
+            for (i, ((drift, diffusion), z)) in steps.iter().zip(&normals).enumerate() {
+                for asset in 0..n_assets {
+                    log_performance[asset] += drift[asset] + diffusion[asset] * sign * z[asset];
+                }
+
+                worst[i + 1] = log_performance
+                    .iter()
+                    .fold(f64::INFINITY, |min, x| min.min(x.exp()));
+            }
+
+            let outcome = evaluate(&worst, &key_indices);
+
+            sample += outcome.value / signs.len() as f64;
+            total_life += outcome.life;
+            *redemption_counts
+                .entry(outcome.redemption)
+                .or_insert(0_usize) += 1;
+        }
+
+        samples.push(sample);
+    }
+
+    let n = samples.len() as f64;
+    let price = samples.iter().sum::<f64>() / n;
+    let variance = samples.iter().map(|x| (x - price).powi(2)).sum::<f64>() / (n - 1.0);
+
+    StructuredProductValue {
+        price,
+        standard_error: (variance / n).sqrt(),
+        expected_life: total_life / n_simulated,
+        redemption_probabilities: redemption_counts
+            .into_iter()
+            .map(|(redemption, count)| (redemption, count as f64 / n_simulated))
+            .collect(),
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_simulation {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    #[test]
+    fn test_time_grid_contains_key_times() {
+        let (grid, key_indices) = time_grid(&[0.25, 1.0], 4);
+
+        assert_eq!(grid.len(), 5);
+        assert_eq!(key_indices, vec![1, 4]);
+        assert_approx_equal!(grid[key_indices[0]], 0.25, 1e-15);
+        assert_approx_equal!(grid[key_indices[1]], 1.0, 1e-15);
+    }
+
+    #[test]
+    fn test_performance_is_a_martingale() {
+        // E[S_T / S_0] = exp((r - q) T) for each underlying.
+        let market = StructuredProductMarket::single(0.3, 0.02, 0.05);
+        let config = MonteCarloConfig {
+            n_paths: 20_000,
+            steps_per_year: 12,
+            ..MonteCarloConfig::default()
+        };
+
+        let value = simulate(&market, &[2.0], &config, |worst, keys| PathOutcome {
+            value: worst[keys[0]],
+            life: 2.0,
+            redemption: Redemption::Par,
+        });
+
+        assert!((value.price - (0.03_f64 * 2.0).exp()).abs() < 4.0 * value.standard_error);
+        assert_approx_equal!(value.expected_life, 2.0, 1e-9);
+        assert_approx_equal!(value.redemption_probabilities[&Redemption::Par], 1.0, 1e-12);
+    }
+
+    #[test]
+    fn test_invalid_market() {
+        assert!(StructuredProductMarket::two_assets([0.2, 0.3], [0.0, 0.0], 1.5, 0.03).is_err());
+        assert!(StructuredProductMarket::new(
+            vec![0.2, 0.3],
+            vec![0.0],
+            DMatrix::identity(2, 2),
+            0.03
+        )
+        .is_err());
+    }
+}
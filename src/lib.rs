@@ -72,9 +72,13 @@ pub mod ml;
 pub mod models;
 pub mod portfolio;
 pub mod pricer;
+pub mod progress;
 pub mod stochastics;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod time;
 pub mod trading;
+pub mod units;
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // Epsilon constant for use in testing.
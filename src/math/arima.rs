@@ -0,0 +1,611 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! ARIMA(p, d, q) models.
+//!
+//! The series is differenced $d$ times, and the differenced series $w_t$
+//! follows the ARMA(p, q) model
+//!
+//! $$
+//! w_t = c + \sum_{i=1}^p \phi_i w_{t-i} + \varepsilon_t + \sum_{j=1}^q \theta_j \varepsilon_{t-j},
+//! \quad \varepsilon_t \sim N(0, \sigma^2).
+//! $$
+//!
+//! The coefficients are estimated by conditional maximum likelihood: the
+//! first $p$ observations and the pre-sample errors (set to zero) are
+//! taken as given, and the Gaussian likelihood of the rest is maximised.
+//! With $\sigma^2$ concentrated out this minimises the conditional sum of
+//! squared residuals, which is done by BFGS with the gradient from the
+//! `autodiff` module.
+//!
+//! Stationarity and invertibility are not imposed on the estimates.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::autodiff::{variables::variable::Variable, Accumulate, Gradient, Graph};
+use crate::math::distributions::{ChiSquared, Distribution};
+use crate::ml::model_selection::{aic, bic, hqic, select_lag_order, InformationCriterion};
+use nalgebra::{DMatrix, DVector};
+use std::f64::consts::PI;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Maximum number of BFGS iterations in [`minimize`].
+const MAX_ITERATIONS: usize = 500;
+
+/// Gradient norm at which [`minimize`] stops.
+const GRADIENT_TOLERANCE: f64 = 1e-10;
+
+/// ARIMA(p, d, q) model.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ARIMA {
+    /// Autoregressive order.
+    pub p: usize,
+    /// Order of differencing.
+    pub d: usize,
+    /// Moving average order.
+    pub q: usize,
+    /// Autoregressive coefficients $\phi_1, \ldots, \phi_p$.
+    pub ar_coefs: Vec<f64>,
+    /// Moving average coefficients $\theta_1, \ldots, \theta_q$.
+    pub ma_coefs: Vec<f64>,
+    /// Constant $c$ of the differenced series.
+    pub constant: f64,
+}
+
+/// Residual diagnostics of a fitted [`ARIMA`] model.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArimaDiagnostics {
+    /// Number of residuals.
+    pub n_residuals: usize,
+    /// Maximum likelihood estimate of the innovation variance.
+    pub residual_variance: f64,
+    /// Conditional Gaussian log-likelihood.
+    pub log_likelihood: f64,
+    /// Akaike information criterion.
+    pub aic: f64,
+    /// Bayesian information criterion.
+    pub bic: f64,
+    /// Hannan-Quinn information criterion.
+    pub hqic: f64,
+    /// Ljung-Box statistic of the residual autocorrelations.
+    pub ljung_box_statistic: f64,
+    /// p-value of the Ljung-Box statistic, with $m - p - q$ degrees of
+    /// freedom (at least one).
+    pub ljung_box_p_value: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl ARIMA {
+    /// New ARIMA(p, d, q) model with all coefficients zero.
+    #[must_use]
+    pub fn new(p: usize, d: usize, q: usize) -> Self {
+        Self {
+            p,
+            d,
+            q,
+            ar_coefs: vec![0.0; p],
+            ma_coefs: vec![0.0; q],
+            constant: 0.0,
+        }
+    }
+
+    /// Fit a model of the same order to `series` by conditional maximum
+    /// likelihood, starting from the mean of the differenced series and
+    /// zero coefficients.
+    ///
+    /// # Panics
+    /// Panics if, after differencing, there are no more observations than
+    /// parameters to estimate.
+    #[must_use]
+    pub fn fit(&self, series: &[f64]) -> Self {
+        let w = difference(series, self.d);
+        let (p, q) = (self.p, self.q);
+
+        assert!(
+            w.len() > 2 * p + q + 1,
+            "Too few observations to fit an ARIMA({p}, {}, {q}) model.",
+            self.d
+        );
+
+        let mean = w.iter().sum::<f64>() / w.len() as f64;
+        let mut x0 = vec![0.0; 1 + p + q];
+        x0[0] = mean;
+
+        let x = minimize(
+            |params| conditional_sum_of_squares(params, &w, p),
+            |params| Self::from_parameters(self.d, p, params).sum_of_squares(&w),
+            x0,
+        );
+
+        Self::from_parameters(self.d, p, &x)
+    }
+
+    /// Fit an ARIMA(p, d, 0) model to `series`, with the autoregressive
+    /// order $p \le$ `max_p` chosen by `criterion` (see
+    /// [`select_lag_order`]).
+    ///
+    /// # Panics
+    /// Panics if, after differencing, there are too few observations to fit
+    /// an AR(`max_p`) model.
+    #[must_use]
+    pub fn select_order(
+        series: &[f64],
+        d: usize,
+        max_p: usize,
+        criterion: InformationCriterion,
+    ) -> Self {
+        let w = difference(series, d);
+
+        assert!(
+            w.len() > 3 * max_p + 1,
+            "Too few observations to select the order of an ARIMA(p, {d}, 0) model."
+        );
+
+        let p = select_lag_order(
+            &w,
+            max_p,
+            |sample, p| {
+                let residuals = Self::new(p, 0, 0).fit(sample).arma_residuals(sample);
+                log_likelihood(&residuals).1
+            },
+            criterion,
+        );
+
+        Self::new(p, d, 0).fit(series)
+    }
+
+    /// Residuals $\varepsilon_t$ of the model on `series`, one for each
+    /// differenced observation after the first $p$.
+    ///
+    /// # Panics
+    /// Panics if, after differencing, there are no more than $p$
+    /// observations.
+    #[must_use]
+    pub fn residuals(&self, series: &[f64]) -> Vec<f64> {
+        let w = difference(series, self.d);
+
+        assert!(
+            w.len() > self.p,
+            "Too few observations for the residuals of the model."
+        );
+
+        self.arma_residuals(&w)
+    }
+
+    /// Forecasts of the next `h` values of the (undifferenced) series
+    /// following `history`.
+    ///
+    /// Future innovations are set to zero, and the in-sample innovations
+    /// are the residuals of the model on `history`.
+    ///
+    /// # Panics
+    /// Panics if, after differencing, `history` has no more than $p$
+    /// observations.
+    #[must_use]
+    pub fn forecast(&self, history: &[f64], h: usize) -> Vec<f64> {
+        let mut levels = vec![history.to_vec()];
+        for k in 0..self.d {
+            levels.push(difference(&levels[k], 1));
+        }
+
+        let mut w = levels.pop().expect("At least the history.");
+        assert!(
+            w.len() > self.p,
+            "Too few observations to forecast from the model."
+        );
+
+        let mut errors = vec![0.0; self.p];
+        errors.extend(self.arma_residuals(&w));
+
+        let n = w.len();
+        for t in n..n + h {
+            let w_t = self.constant
+                + self
+                    .ar_coefs
+                    .iter()
+                    .enumerate()
+                    .map(|(i, phi)| phi * w[t - 1 - i])
+                    .sum::<f64>()
+                + self
+                    .ma_coefs
+                    .iter()
+                    .enumerate()
+                    .filter(|(j, _)| t > *j)
+                    .map(|(j, theta)| theta * errors[t - 1 - j])
+                    .sum::<f64>();
+
+            w.push(w_t);
+            errors.push(0.0);
+        }
+
+        // Undo the differencing, one level at a time.
+        let mut forecasts = w.split_off(n);
+        while let Some(level) = levels.pop() {
+            let mut last = *level.last().expect("Non-empty history.");
+            for f in &mut forecasts {
+                last += *f;
+                *f = last;
+            }
+        }
+
+        forecasts
+    }
+
+    /// Residual diagnostics of the model on `series`, with the Ljung-Box
+    /// test over the first `lags` residual autocorrelations.
+    ///
+    /// The Ljung-Box statistic is
+    /// $$
+    /// Q = n (n + 2) \sum_{k=1}^m \frac{\hat{\rho}_k^2}{n - k},
+    /// $$
+    /// approximately $\chi^2_{m - p - q}$ if the residuals are white noise,
+    /// so a small p-value means the model leaves autocorrelation behind.
+    ///
+    /// # Panics
+    /// Panics if `lags` is zero or not less than the number of residuals.
+    #[must_use]
+    pub fn diagnostics(&self, series: &[f64], lags: usize) -> ArimaDiagnostics {
+        let residuals = self.residuals(series);
+        let n = residuals.len();
+
+        assert!(
+            lags > 0 && lags < n,
+            "The number of lags must be positive and less than the number of residuals."
+        );
+
+        let (residual_variance, log_likelihood) = log_likelihood(&residuals);
+
+        // The coefficients, the constant, and the innovation variance.
+        let k = self.p + self.q + 2;
+
+        let mean = residuals.iter().sum::<f64>() / n as f64;
+        let autocovariance = |lag: usize| {
+            residuals
+                .iter()
+                .zip(&residuals[lag..])
+                .map(|(a, b)| (a - mean) * (b - mean))
+                .sum::<f64>()
+        };
+        let gamma_0 = autocovariance(0);
+
+        let ljung_box_statistic = n as f64
+            * (n as f64 + 2.0)
+            * (1..=lags)
+                .map(|k| (autocovariance(k) / gamma_0).powi(2) / (n - k) as f64)
+                .sum::<f64>();
+        let degrees_of_freedom = lags.saturating_sub(self.p + self.q).max(1);
+        let ljung_box_p_value = 1.0 - ChiSquared::new(degrees_of_freedom).cdf(ljung_box_statistic);
+
+        ArimaDiagnostics {
+            n_residuals: n,
+            residual_variance,
+            log_likelihood,
+            aic: aic(log_likelihood, k),
+            bic: bic(log_likelihood, k, n),
+            hqic: hqic(log_likelihood, k, n),
+            ljung_box_statistic,
+            ljung_box_p_value,
+        }
+    }
+
+    /// Model from the parameter vector $(c, \phi_1, \ldots, \phi_p,
+    /// \theta_1, \ldots, \theta_q)$.
+    fn from_parameters(d: usize, p: usize, params: &[f64]) -> Self {
+        Self {
+            p,
+            d,
+            q: params.len() - 1 - p,
+            ar_coefs: params[1..=p].to_vec(),
+            ma_coefs: params[p + 1..].to_vec(),
+            constant: params[0],
+        }
+    }
+
+    /// Conditional residuals of the ARMA model on the differenced series.
+    fn arma_residuals(&self, w: &[f64]) -> Vec<f64> {
+        let p = self.p;
+        let mut errors: Vec<f64> = Vec::with_capacity(w.len() - p);
+
+        for t in p..w.len() {
+            let mut e_t = w[t] - self.constant;
+            for (i, phi) in self.ar_coefs.iter().enumerate() {
+                e_t -= phi * w[t - 1 - i];
+            }
+            for (j, theta) in self.ma_coefs.iter().enumerate() {
+                if let Some(k) = (t - p).checked_sub(j + 1) {
+                    e_t -= theta * errors[k];
+                }
+            }
+            errors.push(e_t);
+        }
+
+        errors
+    }
+
+    /// Mean squared conditional residual on the differenced series.
+    fn sum_of_squares(&self, w: &[f64]) -> f64 {
+        let errors = self.arma_residuals(w);
+
+        errors.iter().map(|e| e * e).sum::<f64>() / errors.len() as f64
+    }
+}
+
+/// Maximum likelihood innovation variance and conditional Gaussian
+/// log-likelihood of `residuals`.
+fn log_likelihood(residuals: &[f64]) -> (f64, f64) {
+    let n = residuals.len() as f64;
+    let variance = residuals.iter().map(|e| e * e).sum::<f64>() / n;
+
+    (variance, -0.5 * n * ((2.0 * PI * variance).ln() + 1.0))
+}
+
+/// `series` differenced `d` times.
+fn difference(series: &[f64], d: usize) -> Vec<f64> {
+    let mut w = series.to_vec();
+    for _ in 0..d {
+        w = w.windows(2).map(|x| x[1] - x[0]).collect();
+    }
+
+    w
+}
+
+/// Mean squared conditional residual as a function of the parameters
+/// $(c, \phi_1, \ldots, \phi_p, \theta_1, \ldots, \theta_q)$, on the graph.
+fn conditional_sum_of_squares<'v>(params: &[Variable<'v>], w: &[f64], p: usize) -> Variable<'v> {
+    let (constant, ar, ma) = (params[0], &params[1..=p], &params[p + 1..]);
+    let mut errors: Vec<Variable<'v>> = Vec::with_capacity(w.len() - p);
+
+    for t in p..w.len() {
+        let mut e_t = w[t] - constant;
+        for (i, phi) in ar.iter().enumerate() {
+            e_t -= *phi * w[t - 1 - i];
+        }
+        for (j, theta) in ma.iter().enumerate() {
+            if let Some(k) = (t - p).checked_sub(j + 1) {
+                e_t -= *theta * errors[k];
+            }
+        }
+        errors.push(e_t);
+    }
+
+    let n = errors.len() as f64;
+
+    errors.iter().map(|e| *e * *e).sum::<Variable<'v>>() / n
+}
+
+/// Minimise `f` by BFGS with a backtracking line search, with the gradient
+/// from reverse-mode differentiation of `f` and `value` to evaluate it off
+/// the graph.
+pub(crate) fn minimize<F, V>(f: F, value: V, x0: Vec<f64>) -> Vec<f64>
+where
+    F: for<'v> Fn(&[Variable<'v>]) -> Variable<'v>,
+    V: Fn(&[f64]) -> f64,
+{
+    let value_and_gradient = |x: &DVector<f64>| {
+        let graph = Graph::new();
+        let location = graph.vars(x.as_slice());
+        let function = f(&location);
+
+        (
+            function.value,
+            DVector::from_vec(function.accumulate().wrt(&location)),
+        )
+    };
+
+    let n = x0.len();
+    let mut x = DVector::from_vec(x0);
+    let (mut fx, mut gradient) = value_and_gradient(&x);
+    let mut inverse_hessian = DMatrix::<f64>::identity(n, n);
+
+    for _ in 0..MAX_ITERATIONS {
+        if gradient.norm() < GRADIENT_TOLERANCE {
+            break;
+        }
+
+        let mut direction = -(&inverse_hessian * &gradient);
+        if direction.dot(&gradient) >= 0.0 {
+            inverse_hessian = DMatrix::identity(n, n);
+            direction = -gradient.clone();
+        }
+
+        // Backtrack until the Armijo condition holds.
+        let slope = direction.dot(&gradient);
+        let mut step = 1.0;
+        while value((&x + step * &direction).as_slice()) > fx + 1e-4 * step * slope {
+            step *= 0.5;
+            if step < 1e-12 {
+                return x.data.into();
+            }
+        }
+
+        let x_new = &x + step * &direction;
+        let (fx_new, gradient_new) = value_and_gradient(&x_new);
+
+        let s = &x_new - &x;
+        let y = &gradient_new - &gradient;
+        let sy = s.dot(&y);
+        if sy > f64::EPSILON {
+            let rho = 1.0 / sy;
+            let identity = DMatrix::<f64>::identity(n, n);
+            let left = &identity - rho * &s * y.transpose();
+            let right = &identity - rho * &y * s.transpose();
+            inverse_hessian = left * &inverse_hessian * right + rho * &s * s.transpose();
+        }
+
+        x = x_new;
+        fx = fx_new;
+        gradient = gradient_new;
+    }
+
+    x.data.into()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_arima {
+    use super::*;
+    use crate::assert_approx_equal;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::StandardNormal;
+
+    /// ARMA(1, 1) sample path after a burn-in.
+    fn arma_1_1(n: usize, c: f64, phi: f64, theta: f64, seed: u64) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let (mut x, mut e) = (c / (1.0 - phi), 0.0);
+
+        (0..n + 200)
+            .map(|_| {
+                let e_t: f64 = rng.sample(StandardNormal);
+                x = c + phi * x + e_t + theta * e;
+                e = e_t;
+                x
+            })
+            .skip(200)
+            .collect()
+    }
+
+    #[test]
+    fn test_fit_ar_1() {
+        let phi = 0.6;
+        let series = arma_1_1(2000, 0.5, phi, 0.0, 189);
+        let model = ARIMA::new(1, 0, 0).fit(&series);
+
+        assert!(
+            (model.ar_coefs[0] - phi).abs() < 0.05 * phi,
+            "phi = {}",
+            model.ar_coefs[0]
+        );
+        assert!((model.constant - 0.5).abs() < 0.1);
+
+        // Conditional least squares for an AR(1) is ordinary least squares
+        // of x_t on x_{t-1}.
+        let (x, y) = (&series[..series.len() - 1], &series[1..]);
+        let (mx, my) = (
+            x.iter().sum::<f64>() / x.len() as f64,
+            y.iter().sum::<f64>() / y.len() as f64,
+        );
+        let slope = x
+            .iter()
+            .zip(y)
+            .map(|(a, b)| (a - mx) * (b - my))
+            .sum::<f64>()
+            / x.iter().map(|a| (a - mx).powi(2)).sum::<f64>();
+        assert_approx_equal!(model.ar_coefs[0], slope, 1e-6);
+        assert_approx_equal!(model.constant, my - slope * mx, 1e-6);
+    }
+
+    #[test]
+    fn test_fit_arima_1_1_1() {
+        let increments = arma_1_1(3000, 0.0, 0.5, 0.3, 1890);
+        let series: Vec<f64> = increments
+            .iter()
+            .scan(100.0, |level, dx| {
+                *level += dx;
+                Some(*level)
+            })
+            .collect();
+
+        let model = ARIMA::new(1, 1, 1).fit(&series);
+
+        assert!((model.ar_coefs[0] - 0.5).abs() < 0.1);
+        assert!((model.ma_coefs[0] - 0.3).abs() < 0.1);
+        assert!(model.constant.abs() < 0.1);
+    }
+
+    #[test]
+    fn test_forecast() {
+        let mut model = ARIMA::new(1, 0, 0);
+        model.ar_coefs = vec![0.5];
+        model.constant = 1.0;
+
+        // Mean reverts geometrically to c / (1 - phi) = 2.
+        let forecasts = model.forecast(&[0.0, 1.0, 4.0], 3);
+        assert_approx_equal!(forecasts[0], 3.0, 1e-12);
+        assert_approx_equal!(forecasts[1], 2.5, 1e-12);
+        assert_approx_equal!(forecasts[2], 2.25, 1e-12);
+
+        // A random walk with drift forecasts a straight line.
+        let mut drift = ARIMA::new(0, 1, 0);
+        drift.constant = 0.5;
+        assert_eq!(drift.forecast(&[1.0, 3.0, 2.0], 3), vec![2.5, 3.0, 3.5]);
+
+        // Twice integrated: constant second differences.
+        let mut accelerating = ARIMA::new(0, 2, 0);
+        accelerating.constant = 1.0;
+        assert_eq!(accelerating.forecast(&[0.0, 1.0, 3.0], 2), vec![6.0, 10.0]);
+
+        // The MA term uses the last in-sample residual, then dies out.
+        let mut ma = ARIMA::new(0, 0, 1);
+        ma.ma_coefs = vec![0.5];
+        let residuals = ma.residuals(&[1.0, 2.0]);
+        assert_eq!(residuals, vec![1.0, 1.5]);
+        assert_eq!(ma.forecast(&[1.0, 2.0], 2), vec![0.75, 0.0]);
+    }
+
+    #[test]
+    fn test_residual_diagnostics() {
+        let series = arma_1_1(2000, 0.0, 0.7, 0.0, 7);
+
+        // The correct model leaves white noise behind.
+        let ar = ARIMA::new(1, 0, 0).fit(&series);
+        let good = ar.diagnostics(&series, 10);
+        assert_eq!(good.n_residuals, 1999);
+        assert!((good.residual_variance - 1.0).abs() < 0.1);
+        assert!(good.ljung_box_p_value > 0.01, "{good:?}");
+
+        // A white noise model does not.
+        let noise = ARIMA::new(0, 0, 0).fit(&series);
+        let bad = noise.diagnostics(&series, 10);
+        assert!(bad.ljung_box_p_value < 1e-6);
+        assert!(good.aic < bad.aic);
+        assert!(good.bic < bad.bic);
+        assert!(good.hqic < bad.hqic);
+
+        // The criteria are those of the model selection module, with the
+        // coefficients, the constant, and the variance as parameters.
+        assert_approx_equal!(good.aic, aic(good.log_likelihood, 3), 1e-12);
+        assert_approx_equal!(good.bic, bic(good.log_likelihood, 3, 1999), 1e-12);
+    }
+
+    #[test]
+    fn test_select_order() {
+        let series = arma_1_1(1000, 0.5, 0.6, 0.0, 1891);
+
+        for criterion in [InformationCriterion::Bic, InformationCriterion::Hqic] {
+            let model = ARIMA::select_order(&series, 0, 4, criterion);
+
+            assert_eq!((model.p, model.d, model.q), (1, 0, 0), "{criterion:?}");
+            assert!((model.ar_coefs[0] - 0.6).abs() < 0.1);
+        }
+
+        // The order of an integrated series is that of its differences.
+        let integrated: Vec<f64> = series
+            .iter()
+            .scan(0.0, |level, dx| {
+                *level += dx;
+                Some(*level)
+            })
+            .collect();
+        let model = ARIMA::select_order(&integrated, 1, 4, InformationCriterion::Bic);
+
+        assert_eq!((model.p, model.d), (1, 1));
+    }
+}
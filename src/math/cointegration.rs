@@ -0,0 +1,584 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Unit root and cointegration tests.
+//!
+//! - [`adf_test`]: augmented Dickey-Fuller test for a unit root.
+//! - [`engle_granger`]: Engle-Granger two-step cointegration test of two series.
+//! - [`johansen`]: Johansen trace test for the cointegration rank of up to
+//!   10 series.
+//!
+//! Critical values are from MacKinnon (2010), *Critical Values for
+//! Cointegration Tests*, and Osterwald-Lenum (1992).
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use nalgebra::{DMatrix, DVector};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Significance level of a test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignificanceLevel {
+    /// 1% significance (99% confidence).
+    OnePercent,
+    /// 5% significance (95% confidence).
+    FivePercent,
+    /// 10% significance (90% confidence).
+    TenPercent,
+}
+
+/// Critical values of a test statistic.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CriticalValues {
+    /// 1% critical value.
+    pub one_percent: f64,
+    /// 5% critical value.
+    pub five_percent: f64,
+    /// 10% critical value.
+    pub ten_percent: f64,
+}
+
+/// Augmented Dickey-Fuller test result.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdfTest {
+    /// t-statistic of the lagged level coefficient.
+    pub statistic: f64,
+    /// Number of lagged differences in the regression.
+    pub lags: usize,
+    /// Number of observations in the regression.
+    pub n_observations: usize,
+    /// Critical values (the null is rejected below them).
+    pub critical_values: CriticalValues,
+}
+
+/// Engle-Granger cointegration test result.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EngleGranger {
+    /// Hedge ratio $\beta$ in $y_t = \alpha + \beta x_t + e_t$.
+    pub hedge_ratio: f64,
+    /// Intercept $\alpha$.
+    pub intercept: f64,
+    /// Residuals $e_t$ (the spread, minus its mean).
+    pub residuals: Vec<f64>,
+    /// ADF test of the residuals, with cointegration critical values.
+    pub adf: AdfTest,
+}
+
+/// Johansen trace test result.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Johansen {
+    /// Eigenvalues, in decreasing order.
+    pub eigenvalues: Vec<f64>,
+    /// Cointegrating vectors (columns), in the order of the eigenvalues,
+    /// with the coefficient of the constant in the last row.
+    pub eigenvectors: DMatrix<f64>,
+    /// Trace statistics for the null hypotheses rank <= 0, 1, ..., n - 1.
+    pub trace_statistics: Vec<f64>,
+    /// Critical values of the trace statistics (the null is rejected above them).
+    pub critical_values: Vec<CriticalValues>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl CriticalValues {
+    /// Critical value at the given significance level.
+    #[must_use]
+    pub fn get(&self, level: SignificanceLevel) -> f64 {
+        match level {
+            SignificanceLevel::OnePercent => self.one_percent,
+            SignificanceLevel::FivePercent => self.five_percent,
+            SignificanceLevel::TenPercent => self.ten_percent,
+        }
+    }
+
+    /// MacKinnon (2010) response surface, for `n_variables` integrated
+    /// variables (1 for a unit root test) and a regression with a constant.
+    fn mackinnon(n_variables: usize, n_observations: usize) -> Self {
+        // Coefficients (tau_inf, tau_1, tau_2) at 1%, 5% and 10%.
+        const UNIT_ROOT: [[f64; 3]; 3] = [
+            [-3.43035, -6.5393, -16.786],
+            [-2.86154, -2.8903, -4.234],
+            [-2.56677, -1.5384, -2.809],
+        ];
+        const COINTEGRATION: [[f64; 3]; 3] = [
+            [-3.89644, -10.9519, -22.527],
+            [-3.33613, -6.1101, -6.823],
+            [-3.04445, -4.2412, -2.720],
+        ];
+
+        let table = if n_variables == 1 {
+            UNIT_ROOT
+        } else {
+            COINTEGRATION
+        };
+        let T = n_observations as f64;
+        let value = |[tau_inf, tau_1, tau_2]: [f64; 3]| tau_inf + tau_1 / T + tau_2 / (T * T);
+
+        Self {
+            one_percent: value(table[0]),
+            five_percent: value(table[1]),
+            ten_percent: value(table[2]),
+        }
+    }
+}
+
+impl AdfTest {
+    /// Whether the unit root null hypothesis is rejected at the given level.
+    #[must_use]
+    pub fn rejects_unit_root(&self, level: SignificanceLevel) -> bool {
+        self.statistic < self.critical_values.get(level)
+    }
+}
+
+impl EngleGranger {
+    /// Whether the series are cointegrated at the given level
+    /// (i.e. the residuals have no unit root).
+    #[must_use]
+    pub fn is_cointegrated(&self, level: SignificanceLevel) -> bool {
+        self.adf.rejects_unit_root(level)
+    }
+}
+
+impl Johansen {
+    /// Cointegration rank at the given level: the first $r$ for which the
+    /// null hypothesis rank <= $r$ is not rejected.
+    #[must_use]
+    pub fn rank(&self, level: SignificanceLevel) -> usize {
+        self.trace_statistics
+            .iter()
+            .zip(&self.critical_values)
+            .take_while(|(statistic, critical)| **statistic > critical.get(level))
+            .count()
+    }
+}
+
+/// Augmented Dickey-Fuller test, with a constant:
+///
+/// $$
+/// \Delta y_t = \alpha + \gamma y_{t-1} + \sum_{i=1}^p \phi_i \Delta y_{t-i} + \epsilon_t
+/// $$
+///
+/// The statistic is the t-statistic of $\gamma$, and the null hypothesis
+/// of a unit root ($\gamma = 0$) is rejected for large negative values.
+///
+/// # Errors
+/// - `RustQuantError::InvalidArgument` if the series is too short for the lags.
+/// - `RustQuantError::MatrixInversionFailed` if the regression is singular.
+pub fn adf_test(series: &[f64], lags: usize) -> Result<AdfTest, RustQuantError> {
+    adf(series, lags, 1)
+}
+
+/// Engle-Granger two-step cointegration test of `y` and `x`.
+///
+/// 1. Estimate the hedge ratio by OLS: $y_t = \alpha + \beta x_t + e_t$.
+/// 2. ADF test (with `lags` lagged differences) of the residuals $e_t$.
+///
+/// The residuals are estimated, so the critical values are those for
+/// cointegration of two variables, which are more negative than for a
+/// unit root test.
+///
+/// # Errors
+/// - `RustQuantError::InvalidArgument` if the series have different lengths,
+///   or are too short for the lags.
+/// - `RustQuantError::MatrixInversionFailed` if a regression is singular.
+pub fn engle_granger(y: &[f64], x: &[f64], lags: usize) -> Result<EngleGranger, RustQuantError> {
+    if y.len() != x.len() {
+        return Err(RustQuantError::InvalidArgument(
+            "Series must have the same length.".to_string(),
+        ));
+    }
+
+    let n = y.len() as f64;
+    let (x_mean, y_mean) = (x.iter().sum::<f64>() / n, y.iter().sum::<f64>() / n);
+    let sxx: f64 = x.iter().map(|x| (x - x_mean).powi(2)).sum();
+    let sxy: f64 = x
+        .iter()
+        .zip(y)
+        .map(|(x, y)| (x - x_mean) * (y - y_mean))
+        .sum();
+
+    if sxx == 0.0 {
+        return Err(RustQuantError::InvalidArgument(
+            "Series `x` is constant.".to_string(),
+        ));
+    }
+
+    let hedge_ratio = sxy / sxx;
+    let intercept = y_mean - hedge_ratio * x_mean;
+    let residuals: Vec<f64> = y
+        .iter()
+        .zip(x)
+        .map(|(y, x)| y - intercept - hedge_ratio * x)
+        .collect();
+
+    let adf = adf(&residuals, lags, 2)?;
+
+    Ok(EngleGranger {
+        hedge_ratio,
+        intercept,
+        residuals,
+        adf,
+    })
+}
+
+/// Johansen trace test for the cointegration rank of the columns of
+/// `series` (one row per observation), with `lags` lagged differences in
+/// the vector error correction model
+///
+/// $$
+/// \Delta x_t = \alpha (\beta^T x_{t-1} + c) + \sum_{i=1}^p \Gamma_i \Delta x_{t-i} + \epsilon_t
+/// $$
+///
+/// where the constant is restricted to the cointegrating relations, as is
+/// appropriate for series without drift (e.g. prices of a pair, whose
+/// spread has a non-zero mean).
+///
+/// The eigenvalues $\lambda_1 \geq \ldots \geq \lambda_n$ solve
+/// $|\lambda S_{kk} - S_{k0} S_{00}^{-1} S_{0k}| = 0$, where $S$ are the
+/// moment matrices of the residuals of the differences (0) and the lagged
+/// levels and constant (k), regressed on the lagged differences. The trace
+/// statistic for rank <= $r$ is $-T \sum_{i > r} \ln(1 - \lambda_i)$.
+/// Critical values are from Osterwald-Lenum (1992), Table 1*.
+///
+/// # Errors
+/// - `RustQuantError::InvalidArgument` if there are more than 10 series,
+///   or too few observations.
+/// - `RustQuantError::MatrixInversionFailed` if a moment matrix is singular.
+pub fn johansen(series: &DMatrix<f64>, lags: usize) -> Result<Johansen, RustQuantError> {
+    // Trace test critical values (90%, 95%, 99%) with a restricted
+    // constant, for n - r = 1, ..., 10.
+    const TRACE: [[f64; 3]; 10] = [
+        [7.52, 9.24, 12.97],
+        [17.85, 19.96, 24.60],
+        [32.00, 34.91, 41.07],
+        [49.65, 53.12, 60.16],
+        [71.86, 76.07, 84.45],
+        [97.18, 102.14, 111.01],
+        [126.58, 131.70, 143.09],
+        [159.48, 165.58, 177.20],
+        [196.37, 202.92, 215.74],
+        [236.54, 244.15, 257.68],
+    ];
+
+    let (n_obs, n) = series.shape();
+
+    if n == 0 || n > TRACE.len() {
+        return Err(RustQuantError::InvalidArgument(format!(
+            "Johansen test supports 1 to {} series.",
+            TRACE.len()
+        )));
+    }
+    if n_obs < lags + n * (lags + 1) + 3 {
+        return Err(RustQuantError::InvalidArgument(
+            "Too few observations for the Johansen test.".to_string(),
+        ));
+    }
+
+    let differences = series.rows(1, n_obs - 1) - series.rows(0, n_obs - 1);
+
+    // Observations t = lags + 1, ..., n_obs - 1 (T of them).
+    let T = n_obs - 1 - lags;
+    let z0 = differences.rows(lags, T).into_owned();
+    let zk = series.rows(lags, T).into_owned().insert_column(n, 1.0);
+
+    let (r0, rk) = if lags == 0 {
+        (z0, zk)
+    } else {
+        let mut z1 = DMatrix::zeros(T, n * lags);
+        for lag in 1..=lags {
+            z1.columns_mut((lag - 1) * n, n)
+                .copy_from(&differences.rows(lags - lag, T));
+        }
+
+        (residuals(&z0, &z1)?, residuals(&zk, &z1)?)
+    };
+
+    let T_f = T as f64;
+    let s00 = r0.transpose() * &r0 / T_f;
+    let s0k = r0.transpose() * &rk / T_f;
+    let skk = rk.transpose() * &rk / T_f;
+
+    let s00_inverse = s00
+        .try_inverse()
+        .ok_or(RustQuantError::MatrixInversionFailed)?;
+    let l = skk
+        .cholesky()
+        .ok_or(RustQuantError::MatrixInversionFailed)?
+        .l();
+    let l_inverse = l
+        .try_inverse()
+        .ok_or(RustQuantError::MatrixInversionFailed)?;
+
+    // Symmetric form of the generalised eigenvalue problem. It has n + 1
+    // eigenvalues, the smallest of which is zero.
+    let m = &l_inverse * s0k.transpose() * s00_inverse * &s0k * l_inverse.transpose();
+    let m = (&m + m.transpose()) / 2.0;
+    let eigen = m.symmetric_eigen();
+
+    let mut order: Vec<usize> = (0..=n).collect();
+    order.sort_by(|&a, &b| eigen.eigenvalues[b].total_cmp(&eigen.eigenvalues[a]));
+    order.truncate(n);
+
+    let eigenvalues: Vec<f64> = order.iter().map(|&i| eigen.eigenvalues[i]).collect();
+    let vectors = l_inverse.transpose() * &eigen.eigenvectors;
+    let eigenvectors = DMatrix::from_fn(n + 1, n, |row, col| vectors[(row, order[col])]);
+
+    let trace_statistics: Vec<f64> = (0..n)
+        .map(|r| {
+            -T_f * eigenvalues[r..]
+                .iter()
+                .map(|lambda| (1.0 - lambda).ln())
+                .sum::<f64>()
+        })
+        .collect();
+
+    let critical_values = (0..n)
+        .map(|r| {
+            let [ten_percent, five_percent, one_percent] = TRACE[n - r - 1];
+
+            CriticalValues {
+                one_percent,
+                five_percent,
+                ten_percent,
+            }
+        })
+        .collect();
+
+    Ok(Johansen {
+        eigenvalues,
+        eigenvectors,
+        trace_statistics,
+        critical_values,
+    })
+}
+
+/// ADF regression, with critical values for `n_variables` variables.
+fn adf(series: &[f64], lags: usize, n_variables: usize) -> Result<AdfTest, RustQuantError> {
+    let differences: Vec<f64> = series.windows(2).map(|w| w[1] - w[0]).collect();
+
+    // Observations t = lags + 1, ..., n - 1; regressors: constant,
+    // y_{t-1}, and Delta y_{t-1}, ..., Delta y_{t-lags}.
+    let n_obs = differences.len().saturating_sub(lags);
+    let k = 2 + lags;
+
+    if n_obs < k + 3 {
+        return Err(RustQuantError::InvalidArgument(
+            "Series is too short for the ADF regression.".to_string(),
+        ));
+    }
+
+    let X = DMatrix::from_fn(n_obs, k, |row, col| {
+        let t = row + lags;
+        match col {
+            0 => 1.0,
+            1 => series[t],
+            _ => differences[t - (col - 1)],
+        }
+    });
+    let y = DVector::from_iterator(n_obs, differences[lags..].iter().copied());
+
+    let xtx_inverse = (X.transpose() * &X)
+        .try_inverse()
+        .ok_or(RustQuantError::MatrixInversionFailed)?;
+    let coefficients = &xtx_inverse * X.transpose() * &y;
+    let residuals = &y - &X * &coefficients;
+    let s2 = residuals.norm_squared() / (n_obs - k) as f64;
+
+    Ok(AdfTest {
+        statistic: coefficients[1] / (s2 * xtx_inverse[(1, 1)]).sqrt(),
+        lags,
+        n_observations: n_obs,
+        critical_values: CriticalValues::mackinnon(n_variables, n_obs),
+    })
+}
+
+/// Residuals of the least squares regression of the columns of `y` on `x`.
+fn residuals(y: &DMatrix<f64>, x: &DMatrix<f64>) -> Result<DMatrix<f64>, RustQuantError> {
+    let xtx_inverse = (x.transpose() * x)
+        .try_inverse()
+        .ok_or(RustQuantError::MatrixInversionFailed)?;
+
+    Ok(y - x * (xtx_inverse * x.transpose() * y))
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_cointegration {
+    use super::*;
+    use crate::assert_approx_equal;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::StandardNormal;
+
+    const N: usize = 500;
+
+    fn random_walk(rng: &mut StdRng) -> Vec<f64> {
+        let mut x = 0.0;
+
+        (0..N)
+            .map(|_| {
+                x += rng.sample::<f64, _>(StandardNormal);
+                x
+            })
+            .collect()
+    }
+
+    // y = 2 + 1.5 x + AR(1) noise with phi = 0.8.
+    fn cointegrated_pair(seed: u64) -> (Vec<f64>, Vec<f64>) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let x = random_walk(&mut rng);
+
+        let mut e = 0.0;
+        let y = x
+            .iter()
+            .map(|x| {
+                e = 0.8 * e + rng.sample::<f64, _>(StandardNormal);
+                2.0 + 1.5 * x + e
+            })
+            .collect();
+
+        (y, x)
+    }
+
+    fn independent_walks(seed: u64) -> (Vec<f64>, Vec<f64>) {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        (random_walk(&mut rng), random_walk(&mut rng))
+    }
+
+    fn to_matrix(columns: &[&[f64]]) -> DMatrix<f64> {
+        DMatrix::from_fn(N, columns.len(), |row, col| columns[col][row])
+    }
+
+    #[test]
+    fn test_adf_test() {
+        let mut rng = StdRng::seed_from_u64(1);
+
+        let walk = random_walk(&mut rng);
+        let noise: Vec<f64> = (0..N).map(|_| rng.sample(StandardNormal)).collect();
+
+        assert!(!adf_test(&walk, 1)
+            .unwrap()
+            .rejects_unit_root(SignificanceLevel::FivePercent));
+        assert!(adf_test(&noise, 1)
+            .unwrap()
+            .rejects_unit_root(SignificanceLevel::OnePercent));
+
+        // Asymptotic 5% critical value of the unit root test: -2.86.
+        let test = adf_test(&noise, 2).unwrap();
+        assert_eq!(test.n_observations, N - 3);
+        assert_approx_equal!(test.critical_values.five_percent, -2.86, 0.01);
+    }
+
+    #[test]
+    fn test_engle_granger() {
+        let mut false_positives = 0;
+
+        for seed in 0..20 {
+            let (y, x) = cointegrated_pair(seed);
+            let test = engle_granger(&y, &x, 1).unwrap();
+
+            assert!(test.is_cointegrated(SignificanceLevel::FivePercent));
+            assert_approx_equal!(test.hedge_ratio, 1.5, 0.1);
+
+            let (y, x) = independent_walks(100 + seed);
+            if engle_granger(&y, &x, 1)
+                .unwrap()
+                .is_cointegrated(SignificanceLevel::FivePercent)
+            {
+                false_positives += 1;
+            }
+        }
+
+        // Expected number of false positives: 1.
+        assert!(false_positives <= 3);
+    }
+
+    #[test]
+    fn test_johansen_pair() {
+        let (mut overestimated, mut false_positives) = (0, 0);
+
+        for seed in 0..20 {
+            let (y, x) = cointegrated_pair(seed);
+            let test = johansen(&to_matrix(&[&y, &x]), 1).unwrap();
+
+            // Rank 2 (both series stationary) is a type I error of the
+            // second test, expected once.
+            match test.rank(SignificanceLevel::FivePercent) {
+                0 => panic!("Cointegration not detected."),
+                1 => {}
+                _ => overestimated += 1,
+            }
+
+            // The first eigenvector is proportional to (1, -1.5, -2).
+            let vector = test.eigenvectors.column(0);
+            assert_approx_equal!(vector[1] / vector[0], -1.5, 0.1);
+
+            let (y, x) = independent_walks(100 + seed);
+            if johansen(&to_matrix(&[&y, &x]), 1)
+                .unwrap()
+                .rank(SignificanceLevel::FivePercent)
+                > 0
+            {
+                false_positives += 1;
+            }
+        }
+
+        assert!(overestimated <= 3);
+        assert!(false_positives <= 3);
+    }
+
+    #[test]
+    fn test_johansen_rank_two() {
+        let mut correct = 0;
+
+        for seed in 0..10 {
+            // Three series driven by one common stochastic trend.
+            let mut rng = StdRng::seed_from_u64(seed);
+            let trend = random_walk(&mut rng);
+
+            let mut noisy = |scale: f64| -> Vec<f64> {
+                trend
+                    .iter()
+                    .map(|x| scale * x + rng.sample::<f64, _>(StandardNormal))
+                    .collect()
+            };
+            let (a, b, c) = (noisy(1.0), noisy(2.0), noisy(-0.5));
+
+            let test = johansen(&to_matrix(&[&a, &b, &c]), 1).unwrap();
+
+            assert_eq!(test.trace_statistics.len(), 3);
+            assert_eq!(test.eigenvectors.shape(), (4, 3));
+            assert!(test.eigenvalues.windows(2).all(|w| w[0] >= w[1]));
+
+            if test.rank(SignificanceLevel::FivePercent) == 2 {
+                correct += 1;
+            }
+        }
+
+        assert!(correct >= 8);
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        assert!(engle_granger(&[1.0, 2.0], &[1.0], 0).is_err());
+        assert!(adf_test(&[1.0, 2.0, 3.0], 1).is_err());
+        assert!(johansen(&DMatrix::zeros(100, 11), 1).is_err());
+    }
+}
@@ -0,0 +1,404 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Incremental covariance estimators.
+//!
+//! Both estimators keep running (weighted) sums of the observations and of
+//! their outer products, so adding or removing an observation costs
+//! $O(d^2)$ rather than the $O(n d^2)$ of a full recomputation.
+//!
+//! - [`EwmaCovariance`]: exponentially weighted (RiskMetrics-style) covariance.
+//! - [`RollingCovariance`]: equally weighted covariance over a rolling window.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use nalgebra::{DMatrix, DVector};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Trait for covariance estimators that can be updated one observation at a time.
+pub trait IncrementalCovariance {
+    /// Add a new observation (e.g. the latest vector of asset returns).
+    fn update(&mut self, observation: &DVector<f64>);
+
+    /// Remove the *oldest* observation currently held by the estimator.
+    ///
+    /// The estimator does not store the observations themselves,
+    /// so the caller must pass the same vector that was originally added.
+    fn remove(&mut self, observation: &DVector<f64>);
+
+    /// Number of observations currently held by the estimator.
+    fn count(&self) -> usize;
+
+    /// Current estimate of the mean vector.
+    fn mean(&self) -> DVector<f64>;
+
+    /// Current estimate of the covariance matrix.
+    fn covariance(&self) -> DMatrix<f64>;
+
+    /// Current estimate of the correlation matrix.
+    fn correlation(&self) -> DMatrix<f64> {
+        covariance_to_correlation(&self.covariance())
+    }
+}
+
+/// Exponentially weighted moving average (EWMA) covariance estimator.
+///
+/// The most recent observation has weight $1$, the one before it $\lambda$,
+/// then $\lambda^2$, and so on. The estimate is the (bias-corrected)
+/// weighted covariance:
+///
+/// $$
+/// \Sigma = \frac{\sum_i w_i (x_i - \mu)(x_i - \mu)^T}{W - W_2 / W}
+/// $$
+///
+/// where $W = \sum_i w_i$, $W_2 = \sum_i w_i^2$ and $\mu = \sum_i w_i x_i / W$.
+/// As $\lambda \to 1$ this converges to the usual sample covariance.
+///
+/// RiskMetrics recommends $\lambda = 0.94$ for daily and $\lambda = 0.97$
+/// for monthly returns.
+#[derive(Clone, Debug)]
+pub struct EwmaCovariance {
+    /// Decay factor, in $(0, 1]$.
+    pub lambda: f64,
+
+    /// Number of observations currently held.
+    n: usize,
+    /// Sum of the weights.
+    w: f64,
+    /// Sum of the squared weights.
+    w2: f64,
+    /// Weighted sum of the observations.
+    sum: DVector<f64>,
+    /// Weighted sum of the outer products of the observations.
+    sum_outer: DMatrix<f64>,
+}
+
+/// Equally weighted (sample) covariance estimator over a rolling window.
+///
+/// Call [`IncrementalCovariance::update`] with each new observation and
+/// [`IncrementalCovariance::remove`] with the observation leaving the window.
+#[derive(Clone, Debug)]
+pub struct RollingCovariance {
+    /// Number of observations currently held.
+    n: usize,
+    /// Sum of the observations.
+    sum: DVector<f64>,
+    /// Sum of the outer products of the observations.
+    sum_outer: DMatrix<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Convert a covariance matrix to a correlation matrix.
+///
+/// Entries involving a variable with zero variance are set to zero
+/// (and the diagonal to one).
+#[must_use]
+pub fn covariance_to_correlation(covariance: &DMatrix<f64>) -> DMatrix<f64> {
+    let d = covariance.nrows();
+    let std_devs = covariance.diagonal().map(f64::sqrt);
+
+    DMatrix::from_fn(d, d, |i, j| {
+        if i == j {
+            1.0
+        } else if std_devs[i] == 0.0 || std_devs[j] == 0.0 {
+            0.0
+        } else {
+            covariance[(i, j)] / (std_devs[i] * std_devs[j])
+        }
+    })
+}
+
+impl EwmaCovariance {
+    /// Create a new, empty EWMA covariance estimator for `dimension` variables.
+    ///
+    /// # Panics
+    /// Panics if `lambda` is not in $(0, 1]$.
+    #[must_use]
+    pub fn new(dimension: usize, lambda: f64) -> Self {
+        assert!(
+            lambda > 0.0 && lambda <= 1.0,
+            "Lambda must be in the interval (0, 1]."
+        );
+
+        Self {
+            lambda,
+            n: 0,
+            w: 0.0,
+            w2: 0.0,
+            sum: DVector::zeros(dimension),
+            sum_outer: DMatrix::zeros(dimension, dimension),
+        }
+    }
+
+    /// Rebuild the estimator from scratch, from observations ordered oldest first.
+    ///
+    /// Useful to periodically discard any floating point drift
+    /// accumulated by the incremental updates.
+    pub fn rebuild(&mut self, observations: &[DVector<f64>]) {
+        *self = Self::new(self.sum.len(), self.lambda);
+
+        for observation in observations {
+            self.update(observation);
+        }
+    }
+}
+
+impl IncrementalCovariance for EwmaCovariance {
+    fn update(&mut self, observation: &DVector<f64>) {
+        assert_eq!(observation.len(), self.sum.len(), "Dimension mismatch.");
+
+        let lambda = self.lambda;
+
+        self.n += 1;
+        self.w = lambda * self.w + 1.0;
+        self.w2 = lambda * lambda * self.w2 + 1.0;
+        self.sum.axpy(1.0, observation, lambda);
+        self.sum_outer.ger(1.0, observation, observation, lambda);
+    }
+
+    fn remove(&mut self, observation: &DVector<f64>) {
+        assert_eq!(observation.len(), self.sum.len(), "Dimension mismatch.");
+        assert!(self.n > 0, "No observations to remove.");
+
+        // The oldest observation has been decayed (n - 1) times.
+        let weight = self.lambda.powi(self.n as i32 - 1);
+
+        self.n -= 1;
+        self.w -= weight;
+        self.w2 -= weight * weight;
+        self.sum.axpy(-weight, observation, 1.0);
+        self.sum_outer.ger(-weight, observation, observation, 1.0);
+    }
+
+    fn count(&self) -> usize {
+        self.n
+    }
+
+    fn mean(&self) -> DVector<f64> {
+        assert!(self.n > 0, "Estimator must have at least one observation.");
+
+        &self.sum / self.w
+    }
+
+    fn covariance(&self) -> DMatrix<f64> {
+        assert!(self.n > 1, "Estimator must have at least two observations.");
+
+        let mu = self.mean();
+        let scatter = &self.sum_outer - self.w * &mu * mu.transpose();
+
+        scatter / (self.w - self.w2 / self.w)
+    }
+}
+
+impl RollingCovariance {
+    /// Create a new, empty rolling covariance estimator for `dimension` variables.
+    #[must_use]
+    pub fn new(dimension: usize) -> Self {
+        Self {
+            n: 0,
+            sum: DVector::zeros(dimension),
+            sum_outer: DMatrix::zeros(dimension, dimension),
+        }
+    }
+
+    /// Rebuild the estimator from scratch from the observations in the window.
+    ///
+    /// Useful to periodically discard any floating point drift
+    /// accumulated by the incremental updates.
+    pub fn rebuild(&mut self, observations: &[DVector<f64>]) {
+        *self = Self::new(self.sum.len());
+
+        for observation in observations {
+            self.update(observation);
+        }
+    }
+}
+
+impl IncrementalCovariance for RollingCovariance {
+    fn update(&mut self, observation: &DVector<f64>) {
+        assert_eq!(observation.len(), self.sum.len(), "Dimension mismatch.");
+
+        self.n += 1;
+        self.sum += observation;
+        self.sum_outer.ger(1.0, observation, observation, 1.0);
+    }
+
+    fn remove(&mut self, observation: &DVector<f64>) {
+        assert_eq!(observation.len(), self.sum.len(), "Dimension mismatch.");
+        assert!(self.n > 0, "No observations to remove.");
+
+        self.n -= 1;
+        self.sum -= observation;
+        self.sum_outer.ger(-1.0, observation, observation, 1.0);
+    }
+
+    fn count(&self) -> usize {
+        self.n
+    }
+
+    fn mean(&self) -> DVector<f64> {
+        assert!(self.n > 0, "Estimator must have at least one observation.");
+
+        &self.sum / self.n as f64
+    }
+
+    fn covariance(&self) -> DMatrix<f64> {
+        assert!(self.n > 1, "Estimator must have at least two observations.");
+
+        let n = self.n as f64;
+        let mu = self.mean();
+
+        (&self.sum_outer - n * &mu * mu.transpose()) / (n - 1.0)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_covariance {
+    use super::*;
+    use crate::math::Statistic;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rand_distr::{Distribution, StandardNormal};
+
+    fn returns(n: usize, d: usize) -> Vec<DVector<f64>> {
+        let mut rng = StdRng::seed_from_u64(2024);
+
+        (0..n)
+            .map(|_| {
+                DVector::from_fn(d, |i, _| {
+                    let z: f64 = StandardNormal.sample(&mut rng);
+                    0.01 * (i as f64 + 1.0) * z
+                })
+            })
+            .collect()
+    }
+
+    fn max_abs_diff(a: &DMatrix<f64>, b: &DMatrix<f64>) -> f64 {
+        (a - b).abs().max()
+    }
+
+    #[test]
+    fn test_rolling_matches_sample_covariance() {
+        let data = returns(50, 3);
+        let mut estimator = RollingCovariance::new(3);
+
+        for x in &data {
+            estimator.update(x);
+        }
+
+        let cov = estimator.covariance();
+
+        for i in 0..3 {
+            for j in 0..3 {
+                let xi: Vec<f64> = data.iter().map(|x| x[i]).collect();
+                let xj: Vec<f64> = data.iter().map(|x| x[j]).collect();
+
+                assert_approx_equal!(cov[(i, j)], xi.covariance(&xj), 1e-14);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rolling_drift_is_bounded() {
+        let data = returns(5_000, 4);
+        let window = 250;
+        let mut estimator = RollingCovariance::new(4);
+
+        for (t, x) in data.iter().enumerate() {
+            estimator.update(x);
+
+            if t >= window {
+                estimator.remove(&data[t - window]);
+            }
+
+            if t % 1_000 == 999 {
+                let mut rebuilt = RollingCovariance::new(4);
+                rebuilt.rebuild(&data[t + 1 - window..=t]);
+
+                assert_eq!(estimator.count(), window);
+                assert!(max_abs_diff(&estimator.covariance(), &rebuilt.covariance()) < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ewma_drift_is_bounded() {
+        let data = returns(5_000, 4);
+        let window = 250;
+        let mut estimator = EwmaCovariance::new(4, 0.97);
+
+        for (t, x) in data.iter().enumerate() {
+            estimator.update(x);
+
+            if t >= window {
+                estimator.remove(&data[t - window]);
+            }
+
+            if t % 1_000 == 999 {
+                let mut rebuilt = EwmaCovariance::new(4, 0.97);
+                rebuilt.rebuild(&data[t + 1 - window..=t]);
+
+                assert!(max_abs_diff(&estimator.covariance(), &rebuilt.covariance()) < 1e-10);
+                assert!(max_abs_diff(&estimator.correlation(), &rebuilt.correlation()) < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ewma_converges_to_equally_weighted() {
+        let data = returns(200, 3);
+
+        let mut rolling = RollingCovariance::new(3);
+        data.iter().for_each(|x| rolling.update(x));
+        let target = rolling.covariance();
+
+        let mut previous = f64::INFINITY;
+
+        for lambda in [0.9, 0.99, 0.999, 0.999_99] {
+            let mut ewma = EwmaCovariance::new(3, lambda);
+            data.iter().for_each(|x| ewma.update(x));
+
+            let error = max_abs_diff(&ewma.covariance(), &target);
+            assert!(error < previous);
+            previous = error;
+        }
+
+        let mut ewma = EwmaCovariance::new(3, 1.0);
+        data.iter().for_each(|x| ewma.update(x));
+        assert!(max_abs_diff(&ewma.covariance(), &target) < 1e-15);
+    }
+
+    #[test]
+    fn test_correlation_matrix() {
+        let data = returns(100, 3);
+        let mut estimator = RollingCovariance::new(3);
+        data.iter().for_each(|x| estimator.update(x));
+
+        let corr = estimator.correlation();
+        let xs: Vec<f64> = data.iter().map(|x| x[0]).collect();
+        let ys: Vec<f64> = data.iter().map(|x| x[1]).collect();
+
+        for i in 0..3 {
+            assert_approx_equal!(corr[(i, i)], 1.0, f64::EPSILON);
+        }
+        assert_approx_equal!(corr[(0, 1)], corr[(1, 0)], f64::EPSILON);
+        assert_approx_equal!(corr[(0, 1)], xs.correlation(&ys), 1e-12);
+    }
+}
@@ -112,11 +112,13 @@ impl Distribution for Binomial {
         assert!(k as usize <= self.n);
         assert!((0.0..=1.0).contains(&self.p));
 
-        let n_C_k = |n: u32, k: u32| -> u32 {
-            (1..=n).product::<u32>() / ((1..=k).product::<u32>() * (1..=(n - k)).product::<u32>())
+        // Multiplicative form, so the coefficient doesn't overflow for
+        // n > 12 as the factorials would.
+        let n_C_k = |n: usize, k: usize| -> f64 {
+            (1..=k).fold(1.0, |c, i| c * (n - k + i) as f64 / i as f64)
         };
 
-        n_C_k(self.n as u32, k as u32) as f64
+        n_C_k(self.n, k as usize)
             * self.p.powi(k as i32)
             * (1.0 - self.p).powi((self.n - k as usize) as i32)
     }
@@ -132,7 +134,16 @@ impl Distribution for Binomial {
     /// assert_approx_equal!(binomial.cdf(3.0), 0.9129600, 1e-7);
     /// ```
     fn cdf(&self, k: f64) -> f64 {
-        statrs::function::beta::beta_reg((self.n - k as usize) as f64, 1_f64 + k, 1_f64 - self.p)
+        if k < 0.0 {
+            return 0.0;
+        }
+        if k >= self.n as f64 {
+            return 1.0;
+        }
+
+        let k = k.floor();
+
+        statrs::function::beta::beta_reg(self.n as f64 - k, 1_f64 + k, 1_f64 - self.p)
     }
 
     /// Inverse distribution (quantile) function of the Binomial distribution.
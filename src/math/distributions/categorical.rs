@@ -0,0 +1,253 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::Distribution;
+use crate::error::RustQuantError;
+use num::Complex;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Categorical distribution: X ~ Cat(p), with P(X = k) = p_k for
+/// k = 0, ..., K - 1.
+pub struct Categorical {
+    /// Probability of each category.
+    probabilities: Vec<f64>,
+    /// Cumulative probabilities, P(X <= k).
+    cumulative: Vec<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Categorical {
+    /// New instance of a Categorical distribution.
+    /// # Examples
+    /// ```
+    /// # use RustQuant::assert_approx_equal;
+    /// # use RustQuant::math::distributions::*;
+    ///
+    /// let categorical = Categorical::new(&[0.2, 0.3, 0.5]);
+    ///
+    /// assert_approx_equal!(categorical.mean(), 1.3, 1e-12);
+    /// assert_approx_equal!(categorical.cdf(1.0), 0.5, 1e-12);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no categories, a probability is negative, or the
+    /// probabilities do not sum to one.
+    #[must_use]
+    pub fn new(probabilities: &[f64]) -> Categorical {
+        assert!(!probabilities.is_empty());
+        assert!(probabilities.iter().all(|p| *p >= 0.0));
+
+        let cumulative: Vec<f64> = probabilities
+            .iter()
+            .scan(0.0, |total, p| {
+                *total += p;
+                Some(*total)
+            })
+            .collect();
+
+        assert!(
+            (cumulative[cumulative.len() - 1] - 1.0).abs() < 1e-9,
+            "Probabilities must sum to one."
+        );
+
+        Categorical {
+            probabilities: probabilities.to_vec(),
+            cumulative,
+        }
+    }
+
+    /// Probability of each category.
+    #[must_use]
+    pub fn probabilities(&self) -> &[f64] {
+        &self.probabilities
+    }
+
+    /// Central moment $\mathbb{E}[(X - \mu)^k]$.
+    fn central_moment(&self, k: i32) -> f64 {
+        let mean = self.mean();
+
+        self.probabilities
+            .iter()
+            .enumerate()
+            .map(|(i, p)| p * (i as f64 - mean).powi(k))
+            .sum()
+    }
+}
+
+impl Distribution for Categorical {
+    fn cf(&self, t: f64) -> Complex<f64> {
+        let i: Complex<f64> = Complex::i();
+
+        self.probabilities
+            .iter()
+            .enumerate()
+            .map(|(k, p)| p * (i * t * k as f64).exp())
+            .sum()
+    }
+
+    /// Using this method will call `self.pmf()` instead.
+    fn pdf(&self, x: f64) -> f64 {
+        self.pmf(x)
+    }
+
+    fn pmf(&self, k: f64) -> f64 {
+        if k >= 0.0 && k.fract() == 0.0 {
+            self.probabilities.get(k as usize).copied().unwrap_or(0.0)
+        } else {
+            0.0
+        }
+    }
+
+    fn cdf(&self, k: f64) -> f64 {
+        if k < 0.0 {
+            0.0
+        } else {
+            let index = (k.floor() as usize).min(self.cumulative.len() - 1);
+            self.cumulative[index]
+        }
+    }
+
+    /// Smallest category `k` with `cdf(k) >= p`.
+    fn inv_cdf(&self, p: f64) -> f64 {
+        let index = self.cumulative.partition_point(|c| *c < p);
+
+        index.min(self.cumulative.len() - 1) as f64
+    }
+
+    fn mean(&self) -> f64 {
+        self.probabilities
+            .iter()
+            .enumerate()
+            .map(|(k, p)| k as f64 * p)
+            .sum()
+    }
+
+    fn median(&self) -> f64 {
+        self.inv_cdf(0.5)
+    }
+
+    /// Most likely category (the first, in case of ties).
+    fn mode(&self) -> f64 {
+        let mut mode = 0;
+
+        for (k, p) in self.probabilities.iter().enumerate() {
+            if *p > self.probabilities[mode] {
+                mode = k;
+            }
+        }
+
+        mode as f64
+    }
+
+    fn variance(&self) -> f64 {
+        self.central_moment(2)
+    }
+
+    fn skewness(&self) -> f64 {
+        self.central_moment(3) / self.variance().powf(1.5)
+    }
+
+    /// Excess kurtosis.
+    fn kurtosis(&self) -> f64 {
+        self.central_moment(4) / self.variance().powi(2) - 3.0
+    }
+
+    fn entropy(&self) -> f64 {
+        -self
+            .probabilities
+            .iter()
+            .filter(|p| **p > 0.0)
+            .map(|p| p * p.ln())
+            .sum::<f64>()
+    }
+
+    fn mgf(&self, t: f64) -> f64 {
+        self.probabilities
+            .iter()
+            .enumerate()
+            .map(|(k, p)| p * (t * k as f64).exp())
+            .sum()
+    }
+
+    /// Samples category indices by inversion.
+    fn sample(&self, n: usize) -> Result<Vec<f64>, RustQuantError> {
+        use rand::{thread_rng, Rng};
+
+        assert!(n > 0);
+
+        let mut rng = thread_rng();
+
+        Ok((0..n).map(|_| self.inv_cdf(rng.gen::<f64>())).collect())
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_categorical {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::math::distributions::Bernoulli;
+
+    #[test]
+    fn test_two_categories_are_bernoulli() {
+        let categorical = Categorical::new(&[0.7, 0.3]);
+        let bernoulli = Bernoulli::new(0.3);
+
+        assert_approx_equal!(categorical.pmf(1.0), bernoulli.pmf(1.0), 1e-12);
+        assert_approx_equal!(categorical.cdf(0.0), bernoulli.cdf(0.0), 1e-12);
+        assert_approx_equal!(categorical.mean(), bernoulli.mean(), 1e-12);
+        assert_approx_equal!(categorical.variance(), bernoulli.variance(), 1e-12);
+        assert_approx_equal!(categorical.skewness(), bernoulli.skewness(), 1e-12);
+        assert_approx_equal!(categorical.entropy(), bernoulli.entropy(), 1e-12);
+        assert_approx_equal!(categorical.cf(1.5).re, bernoulli.cf(1.5).re, 1e-12);
+        assert_approx_equal!(categorical.mgf(0.5), bernoulli.mgf(0.5), 1e-12);
+    }
+
+    #[test]
+    fn test_quantiles() {
+        let categorical = Categorical::new(&[0.2, 0.3, 0.5]);
+
+        assert_eq!(categorical.inv_cdf(0.1), 0.0);
+        assert_eq!(categorical.inv_cdf(0.2), 0.0);
+        assert_eq!(categorical.inv_cdf(0.45), 1.0);
+        assert_eq!(categorical.inv_cdf(0.99), 2.0);
+        assert_eq!(categorical.median(), 1.0);
+        assert_eq!(categorical.mode(), 2.0);
+        assert_eq!(categorical.pmf(1.5), 0.0);
+        assert_eq!(categorical.cdf(7.0), 1.0);
+    }
+
+    #[test]
+    fn test_sample_frequencies() {
+        let categorical = Categorical::new(&[0.2, 0.3, 0.5]);
+        let sample = categorical.sample(20_000).unwrap();
+
+        for (k, p) in categorical.probabilities().iter().enumerate() {
+            let frequency =
+                sample.iter().filter(|x| **x == k as f64).count() as f64 / sample.len() as f64;
+            assert!((frequency - p).abs() < 0.02);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Probabilities must sum to one.")]
+    fn test_invalid_probabilities() {
+        let _ = Categorical::new(&[0.2, 0.3]);
+    }
+}
@@ -7,9 +7,12 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::{error::RustQuantError, math::distributions::Distribution};
+use crate::{
+    error::RustQuantError,
+    math::distributions::{Distribution, Gamma},
+};
 use num::Complex;
-use statrs::function::gamma::{digamma, gamma, gamma_li};
+use statrs::function::gamma::{digamma, gamma};
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // STRUCTS
@@ -52,6 +55,11 @@ impl ChiSquared {
 
         Self { k }
     }
+
+    /// The equivalent Gamma distribution: ChiSq(k) = Gamma(k / 2, 1 / 2).
+    fn as_gamma(&self) -> Gamma {
+        Gamma::new(self.k as f64 / 2.0, 0.5)
+    }
 }
 
 impl Distribution for ChiSquared {
@@ -86,7 +94,11 @@ impl Distribution for ChiSquared {
     /// assert_approx_equal!(chi.pdf(1.0), 0.2419707, 1e-7);
     /// ```
     fn pdf(&self, x: f64) -> f64 {
-        assert!(if self.k == 1 { x > 0.0 } else { x >= 0.0 });
+        // Zero below the support. With one degree of freedom the density is
+        // unbounded at zero, so report zero there too.
+        if x < 0.0 || (x == 0.0 && self.k == 1) {
+            return 0.0;
+        }
 
         let k = self.k;
 
@@ -121,14 +133,15 @@ impl Distribution for ChiSquared {
     /// assert_approx_equal!(chi.cdf(1.0), 0.6826895, 1e-7);
     ///
     fn cdf(&self, x: f64) -> f64 {
-        assert!(if self.k == 1 { x > 0.0 } else { x >= 0.0 });
-
-        let k = self.k;
+        if x <= 0.0 {
+            return 0.0;
+        }
 
-        gamma_li(k as f64 / 2.0, x / 2.0) / gamma(k as f64 / 2.0)
+        self.as_gamma().cdf(x)
     }
 
-    /// Inverse (quantile) distribution function of the Chi-Squared distribution.
+    /// Inverse (quantile) distribution function of the Chi-Squared distribution,
+    /// computed as a Gamma(k / 2, 1 / 2) quantile.
     /// # Examples
     /// ```
     /// # use RustQuant::assert_approx_equal;
@@ -139,22 +152,7 @@ impl Distribution for ChiSquared {
     /// assert_approx_equal!(chi.inv_cdf(0.6826895), 1.0, 1e-7);
     /// ```
     fn inv_cdf(&self, p: f64) -> f64 {
-        assert!((0.0..=1.0).contains(&p));
-
-        let k = self.k as f64;
-        let mut x = 0.5 * k;
-        let mut delta = 0.5 * k;
-
-        while delta > 1e-10 {
-            let cdf = self.cdf(x);
-            if cdf < p {
-                x += delta;
-            } else {
-                x -= delta;
-            }
-            delta *= 0.5;
-        }
-        x
+        self.as_gamma().inv_cdf(p)
     }
 
     /// Mean of the Chi-Squared distribution.
@@ -344,4 +342,27 @@ mod tests {
         assert_approx_equal!(dist.cdf(4.0), 0.954_499_7, 1e-7);
         assert_approx_equal!(dist.cdf(5.0), 0.974_652_7, 1e-7);
     }
+
+    #[test]
+    fn test_chi_squared_quantile_function() {
+        for k in [1, 2, 5, 30] {
+            let dist: ChiSquared = ChiSquared::new(k);
+
+            for p in [1e-9, 0.05, 0.5, 0.95, 1.0 - 1e-9] {
+                assert_approx_equal!(dist.cdf(dist.inv_cdf(p)), p, 1e-10);
+            }
+        }
+
+        // Values computed using R: qchisq(0.95, k)
+        assert_approx_equal!(
+            ChiSquared::new(1).inv_cdf(0.95),
+            3.841_458_820_694_124,
+            1e-10
+        );
+        assert_approx_equal!(
+            ChiSquared::new(5).inv_cdf(0.95),
+            11.070_497_693_516_352,
+            1e-10
+        );
+    }
 }
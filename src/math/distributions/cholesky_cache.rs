@@ -0,0 +1,175 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Cached Cholesky factor of a covariance matrix, for drawing correlated
+//! normals repeatedly without factorizing the matrix at every draw.
+
+use crate::error::RustQuantError;
+use nalgebra::{DMatrix, DVector};
+use rand::Rng;
+use rand_distr::StandardNormal;
+use std::sync::OnceLock;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Covariance matrix with its lower Cholesky factor $L$ ($\Sigma = L L^T$),
+/// computed on first use and kept for later draws.
+#[derive(Debug, Clone)]
+pub struct CholeskyCache {
+    /// Covariance matrix.
+    covariance: DMatrix<f64>,
+    /// Lower Cholesky factor, or `None` if the covariance matrix is not
+    /// positive definite. Unset until first used.
+    cholesky: OnceLock<Option<DMatrix<f64>>>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl CholeskyCache {
+    /// New cache for `covariance`. The factor is computed on first use.
+    ///
+    /// # Panics
+    /// Panics if `covariance` is not square.
+    #[must_use]
+    pub fn new(covariance: DMatrix<f64>) -> Self {
+        assert!(covariance.is_square(), "Covariance matrix must be square.");
+
+        Self {
+            covariance,
+            cholesky: OnceLock::new(),
+        }
+    }
+
+    /// Covariance matrix.
+    #[must_use]
+    pub fn covariance(&self) -> &DMatrix<f64> {
+        &self.covariance
+    }
+
+    /// Number of variables.
+    #[must_use]
+    pub fn dimension(&self) -> usize {
+        self.covariance.nrows()
+    }
+
+    /// Whether the factor has been computed.
+    #[must_use]
+    pub fn is_factorized(&self) -> bool {
+        self.cholesky.get().is_some()
+    }
+
+    /// Lower Cholesky factor, computed on the first call.
+    ///
+    /// # Errors
+    /// `InvalidArgument` if the covariance matrix is not positive definite.
+    pub fn cholesky(&self) -> Result<&DMatrix<f64>, RustQuantError> {
+        self.cholesky
+            .get_or_init(|| self.covariance.clone().cholesky().map(|c| c.l()))
+            .as_ref()
+            .ok_or_else(|| {
+                RustQuantError::InvalidArgument(
+                    "Covariance matrix must be positive definite.".to_string(),
+                )
+            })
+    }
+
+    /// Correlates a vector `z` of independent standard normals, returning
+    /// $L z$.
+    ///
+    /// # Panics
+    /// Panics if the covariance matrix is not positive definite, or `z`
+    /// has the wrong dimension.
+    #[must_use]
+    pub fn correlate(&self, z: &DVector<f64>) -> DVector<f64> {
+        self.cholesky()
+            .expect("Covariance matrix must be positive definite.")
+            * z
+    }
+
+    /// `n` draws from the multivariate normal distribution with zero mean
+    /// and this covariance.
+    ///
+    /// # Panics
+    /// Panics if the covariance matrix is not positive definite.
+    pub fn sample(&self, n: usize, rng: &mut impl Rng) -> Vec<DVector<f64>> {
+        let dimension = self.dimension();
+
+        (0..n)
+            .map(|_| {
+                let z = DVector::from_fn(dimension, |_, _| rng.sample(StandardNormal));
+                self.correlate(&z)
+            })
+            .collect()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_cholesky_cache {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn covariance() -> DMatrix<f64> {
+        DMatrix::from_row_slice(3, 3, &[4.0, 1.2, -0.6, 1.2, 1.0, 0.3, -0.6, 0.3, 2.25])
+    }
+
+    #[test]
+    fn test_factor_is_computed_once() {
+        let cache = CholeskyCache::new(covariance());
+        let mut rng = StdRng::seed_from_u64(1);
+        assert!(!cache.is_factorized());
+
+        let _ = cache.sample(10, &mut rng);
+        assert!(cache.is_factorized());
+        let first: *const DMatrix<f64> = cache.cholesky().unwrap();
+
+        let _ = cache.sample(10, &mut rng);
+        let second: *const DMatrix<f64> = cache.cholesky().unwrap();
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn test_factor_reproduces_covariance() {
+        let cache = CholeskyCache::new(covariance());
+        let l = cache.cholesky().unwrap();
+
+        assert!((l * l.transpose() - covariance()).amax() < 1e-12);
+    }
+
+    #[test]
+    fn test_sample_covariance() {
+        let cache = CholeskyCache::new(covariance());
+        let mut rng = StdRng::seed_from_u64(2);
+        let n = 100_000;
+
+        let draws = cache.sample(n, &mut rng);
+        let sample_covariance = draws
+            .iter()
+            .fold(DMatrix::zeros(3, 3), |sum, x| sum + x * x.transpose())
+            / n as f64;
+
+        assert!((sample_covariance - covariance()).amax() < 0.1);
+    }
+
+    #[test]
+    fn test_not_positive_definite() {
+        let cache = CholeskyCache::new(DMatrix::from_row_slice(2, 2, &[1.0, 2.0, 2.0, 1.0]));
+
+        assert!(cache.cholesky().is_err());
+        assert!(cache.is_factorized());
+    }
+}
@@ -0,0 +1,186 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Copulas: the dependence structure of a random vector, separated from
+//! its marginal distributions.
+//!
+//! By Sklar's theorem a random vector with marginal distribution functions
+//! $F_i$ can be written $X_i = F_i^{-1}(U_i)$, where the uniforms $U_i$
+//! are drawn from a copula. Copulas here are sampled through `dyn RngCore`
+//! so they can be used as trait objects.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use crate::math::distributions::{CholeskyCache, Distribution, Gaussian};
+use nalgebra::{DMatrix, DVector};
+use rand::{Rng, RngCore};
+use rand_distr::{Open01, StandardNormal};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Copula: a joint distribution of uniforms on $(0, 1)$.
+pub trait Copula {
+    /// Number of variables.
+    fn dimension(&self) -> usize;
+
+    /// One draw of [`Self::dimension`] dependent uniforms on $(0, 1)$.
+    fn sample(&self, rng: &mut dyn RngCore) -> Vec<f64>;
+}
+
+/// Independence copula: independent uniforms.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndependenceCopula {
+    /// Number of variables.
+    pub dimension: usize,
+}
+
+/// Comonotonic copula: perfect positive dependence, every variable is the
+/// same uniform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComonotonicCopula {
+    /// Number of variables.
+    pub dimension: usize,
+}
+
+/// Gaussian copula: the dependence of a multivariate normal with the given
+/// correlation matrix.
+#[derive(Debug, Clone)]
+pub struct GaussianCopula {
+    correlation: CholeskyCache,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Copula for IndependenceCopula {
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn sample(&self, rng: &mut dyn RngCore) -> Vec<f64> {
+        (0..self.dimension).map(|_| rng.sample(Open01)).collect()
+    }
+}
+
+impl Copula for ComonotonicCopula {
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn sample(&self, rng: &mut dyn RngCore) -> Vec<f64> {
+        vec![rng.sample(Open01); self.dimension]
+    }
+}
+
+impl GaussianCopula {
+    /// New Gaussian copula.
+    ///
+    /// # Errors
+    /// `InvalidArgument` if `correlation` is not square with a unit
+    /// diagonal, or not positive definite.
+    pub fn new(correlation: DMatrix<f64>) -> Result<Self, RustQuantError> {
+        if !correlation.is_square() || correlation.diagonal().iter().any(|&d| d != 1.0) {
+            return Err(RustQuantError::InvalidArgument(
+                "The correlation matrix must be square with a unit diagonal.".to_string(),
+            ));
+        }
+
+        let correlation = CholeskyCache::new(correlation);
+        correlation.cholesky()?;
+
+        Ok(Self { correlation })
+    }
+
+    /// Correlation matrix.
+    #[must_use]
+    pub fn correlation(&self) -> &DMatrix<f64> {
+        self.correlation.covariance()
+    }
+}
+
+impl Copula for GaussianCopula {
+    fn dimension(&self) -> usize {
+        self.correlation.dimension()
+    }
+
+    fn sample(&self, rng: &mut dyn RngCore) -> Vec<f64> {
+        let n = Gaussian::default();
+        let z = DVector::from_fn(self.dimension(), |_, _| rng.sample(StandardNormal));
+
+        self.correlation
+            .correlate(&z)
+            .iter()
+            .map(|&x| n.cdf(x))
+            .collect()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_copula {
+    use super::*;
+    use crate::assert_approx_equal;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    // Sample correlation of the normal scores of the draws.
+    fn normal_score_correlation(copula: &dyn Copula, n: usize) -> f64 {
+        let mut rng = StdRng::seed_from_u64(7);
+        let gaussian = Gaussian::default();
+        let (mut sxy, mut sxx, mut syy) = (0.0, 0.0, 0.0);
+
+        for _ in 0..n {
+            let u = copula.sample(&mut rng);
+            let (x, y) = (gaussian.inv_cdf(u[0]), gaussian.inv_cdf(u[1]));
+
+            assert!(u.iter().all(|&u| u > 0.0 && u < 1.0));
+            sxy += x * y;
+            sxx += x * x;
+            syy += y * y;
+        }
+
+        sxy / (sxx * syy).sqrt()
+    }
+
+    #[test]
+    fn test_dependence() -> Result<(), RustQuantError> {
+        let gaussian = GaussianCopula::new(DMatrix::from_row_slice(2, 2, &[1.0, 0.6, 0.6, 1.0]))?;
+
+        assert_approx_equal!(normal_score_correlation(&gaussian, 50_000), 0.6, 0.01);
+        assert_approx_equal!(
+            normal_score_correlation(&IndependenceCopula { dimension: 2 }, 50_000),
+            0.0,
+            0.01
+        );
+        assert_approx_equal!(
+            normal_score_correlation(&ComonotonicCopula { dimension: 2 }, 1_000),
+            1.0,
+            1e-12
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_correlation() {
+        let not_unit = DMatrix::from_row_slice(2, 2, &[2.0, 0.5, 0.5, 1.0]);
+        let not_positive_definite = DMatrix::from_row_slice(2, 2, &[1.0, 1.5, 1.5, 1.0]);
+
+        assert!(GaussianCopula::new(not_unit).is_err());
+        assert!(GaussianCopula::new(not_positive_definite).is_err());
+    }
+}
@@ -61,7 +61,9 @@ impl Distribution for Exponential {
     /// assert_approx_equal!(exp.pdf(1.0), 0.3678794, 1e-7);
     /// ```
     fn pdf(&self, x: f64) -> f64 {
-        assert!(x >= 0.0);
+        if x < 0.0 {
+            return 0.0;
+        }
 
         self.lambda * (-self.lambda * x).exp()
     }
@@ -87,7 +89,9 @@ impl Distribution for Exponential {
     /// assert_approx_equal!(exp.cdf(1.0), 0.6321206, 1e-7);
     /// ```
     fn cdf(&self, x: f64) -> f64 {
-        assert!(x >= 0.0);
+        if x < 0.0 {
+            return 0.0;
+        }
 
         1.0 - (-self.lambda * x).exp()
     }
@@ -7,9 +7,15 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::{error::RustQuantError, math::distributions::Distribution};
+use crate::{
+    error::RustQuantError,
+    math::{
+        distributions::{Distribution, Gaussian},
+        rootfinding::{brent::Brent, rootfinder::Rootfinder, rootfinder::RootfinderData},
+    },
+};
 use num::Complex;
-use statrs::function::gamma::{gamma, gamma_li};
+use statrs::function::gamma::{gamma, gamma_lr};
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // STRUCTS
@@ -48,6 +54,57 @@ impl Gamma {
 
         Self { alpha, beta }
     }
+
+    /// Maximum number of Newton steps in `inv_cdf` before falling back to
+    /// Brent's method.
+    const NEWTON_MAX_ITERATIONS: usize = 100;
+
+    /// Quantile by Newton's method, started from the Wilson-Hilferty
+    /// approximation. Returns `None` if it does not converge.
+    fn inv_cdf_newton(&self, p: f64) -> Option<f64> {
+        let (alpha, beta) = (self.alpha, self.beta);
+
+        // Wilson-Hilferty: (beta X / alpha)^(1/3) is approximately normal.
+        // In the left tail it can go negative, in which case we use the
+        // small-x expansion of the CDF: F(x) ~ (beta x)^alpha / Gamma(alpha + 1).
+        let z = Gaussian::default().inv_cdf(p);
+        let c = 1.0 / (9.0 * alpha);
+        let wilson_hilferty = alpha * (1.0 - c + z * c.sqrt()).powi(3) / beta;
+        let mut x = if wilson_hilferty > 0.0 {
+            wilson_hilferty
+        } else {
+            (p * gamma(alpha + 1.0)).powf(1.0 / alpha) / beta
+        };
+
+        for _ in 0..Self::NEWTON_MAX_ITERATIONS {
+            let step = (self.cdf(x) - p) / self.pdf(x);
+
+            if !step.is_finite() {
+                return None;
+            }
+
+            // Stay in the support: at most halve x on a step to the left.
+            let next = if x - step > 0.0 { x - step } else { 0.5 * x };
+
+            if (next - x).abs() <= 1e-14 * x {
+                return Some(next);
+            }
+
+            x = next;
+        }
+
+        None
+    }
+
+    /// Quantile by Brent's method on $[0, 1000 / \beta]$.
+    fn inv_cdf_brent(&self, p: f64) -> f64 {
+        let upper = 1000.0 / self.beta;
+        let f = |x: f64| if x > 0.0 { self.cdf(x) - p } else { -p };
+
+        let data = RootfinderData::new(f64::EPSILON, 1e-3 * upper, 0.0, upper, true);
+
+        Brent::new(f, self.mean().min(upper), data).solve()
+    }
 }
 
 impl Distribution for Gamma {
@@ -60,7 +117,9 @@ impl Distribution for Gamma {
     }
 
     fn pdf(&self, x: f64) -> f64 {
-        assert!(x > 0.0);
+        if x <= 0.0 {
+            return 0.0;
+        }
 
         let alpha = self.alpha;
         let beta = self.beta;
@@ -73,16 +132,43 @@ impl Distribution for Gamma {
     }
 
     fn cdf(&self, x: f64) -> f64 {
-        assert!(x > 0.0);
+        if x <= 0.0 {
+            return 0.0;
+        }
 
         let alpha = self.alpha;
-        let beta = self.beta;
+        let z = self.beta * x;
+
+        // `gamma_lr` rounds to zero below ~1e-15, which would make the
+        // quantiles of small shapes unreachable, so use the leading terms
+        // of its series there: z^alpha e^{-z} / Gamma(alpha + 1) (1 + z / (alpha + 1)).
+        if z < 1e-10 {
+            return z.powf(alpha) * (-z).exp() / gamma(alpha + 1.0) * (1.0 + z / (alpha + 1.0));
+        }
 
-        gamma_li(alpha, beta * x) / gamma(alpha)
+        gamma_lr(alpha, z)
     }
 
-    fn inv_cdf(&self, _p: f64) -> f64 {
-        unimplemented!()
+    /// Quantile function, by Newton's method on the CDF.
+    ///
+    /// Newton's method can fail to converge for extreme probabilities, in
+    /// which case Brent's method is used on $[0, 1000 / \beta]$.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `p` is not in $[0, 1]$.
+    fn inv_cdf(&self, p: f64) -> f64 {
+        assert!((0.0..=1.0).contains(&p));
+
+        if p == 0.0 {
+            return 0.0;
+        }
+        if p == 1.0 {
+            return f64::INFINITY;
+        }
+
+        self.inv_cdf_newton(p)
+            .unwrap_or_else(|| self.inv_cdf_brent(p))
     }
 
     fn mean(&self) -> f64 {
@@ -189,4 +275,42 @@ mod tests {
         assert_approx_equal!(dist.cdf(3.0), 0.950_212_931_632_136, EPS);
         assert_approx_equal!(dist.cdf(4.0), 0.981_684_361_111_265_8, EPS);
     }
+
+    #[test]
+    fn test_gamma_quantile_function() {
+        // Gamma(1,1) is equivalent to Exp(1).
+        let dist: Gamma = Gamma::new(1.0, 1.0);
+        assert_approx_equal!(dist.inv_cdf(0.5), std::f64::consts::LN_2, 1e-12);
+
+        // Reference values: qgamma(p, shape = 2.5, rate = 2).
+        let dist: Gamma = Gamma::new(2.5, 2.0);
+        assert_approx_equal!(dist.inv_cdf(0.05), 0.286_369_056_515_442_3, 1e-10);
+        assert_approx_equal!(dist.inv_cdf(0.95), 2.767_624_423_379_088, 1e-10);
+    }
+
+    #[test]
+    fn test_gamma_quantile_function_tails() {
+        for &alpha in &[0.1, 0.5, 1.0, 2.5, 10.0, 50.0] {
+            for &beta in &[0.5, 1.0, 3.0] {
+                let dist = Gamma::new(alpha, beta);
+
+                for &p in &[1e-9, 1e-3, 0.5, 1.0 - 1e-3, 1.0 - 1e-9] {
+                    let x = dist.inv_cdf(p);
+                    assert_approx_equal!(dist.cdf(x), p, 1e-10);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_gamma_quantile_function_brent_fallback() {
+        for &alpha in &[0.5, 2.5, 10.0] {
+            let dist = Gamma::new(alpha, 2.0);
+
+            for &p in &[1e-3, 0.5, 1.0 - 1e-9] {
+                let x = dist.inv_cdf_brent(p);
+                assert_approx_equal!(dist.cdf(x), p, 1e-10);
+            }
+        }
+    }
 }
@@ -0,0 +1,446 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{Categorical, Distribution, Gaussian};
+use crate::error::RustQuantError;
+use crate::math::integrate;
+use crate::math::rootfinding::{
+    brent::Brent,
+    rootfinder::{Rootfinder, RootfinderData},
+};
+use num::Complex;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Finite mixture of distributions, with density $f(x) = \sum_i w_i f_i(x)$.
+///
+/// Mixtures capture multi-modal data, such as returns that switch between
+/// a calm and a turbulent regime.
+pub struct MixtureDistribution {
+    /// Component distributions.
+    components: Vec<Box<dyn Distribution>>,
+    /// Mixing weights, summing to one.
+    weights: Vec<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl MixtureDistribution {
+    /// New mixture of `components` with mixing `weights`.
+    /// # Examples
+    /// ```
+    /// # use RustQuant::assert_approx_equal;
+    /// # use RustQuant::math::distributions::*;
+    ///
+    /// let mixture = MixtureDistribution::new(
+    ///     vec![Box::new(Gaussian::new(-1.0, 1.0)), Box::new(Gaussian::new(1.0, 1.0))],
+    ///     vec![0.5, 0.5],
+    /// );
+    ///
+    /// assert_approx_equal!(mixture.mean(), 0.0, 1e-12);
+    /// assert_approx_equal!(mixture.variance(), 2.0, 1e-12);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no components, the number of weights differs from
+    /// the number of components, a weight is negative, or the weights do not
+    /// sum to one.
+    #[must_use]
+    pub fn new(components: Vec<Box<dyn Distribution>>, weights: Vec<f64>) -> Self {
+        assert!(!components.is_empty());
+        assert_eq!(components.len(), weights.len());
+        assert!(weights.iter().all(|w| *w >= 0.0));
+        assert!(
+            (weights.iter().sum::<f64>() - 1.0).abs() < 1e-9,
+            "Weights must sum to one."
+        );
+
+        Self {
+            components,
+            weights,
+        }
+    }
+
+    /// Component distributions.
+    pub fn components(&self) -> &[Box<dyn Distribution>] {
+        &self.components
+    }
+
+    /// Mixing weights.
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// $\sum_i w_i g(f_i)$.
+    fn weighted_sum<G>(&self, g: G) -> f64
+    where
+        G: Fn(&dyn Distribution) -> f64,
+    {
+        self.components
+            .iter()
+            .zip(&self.weights)
+            .map(|(component, w)| w * g(component.as_ref()))
+            .sum()
+    }
+
+    /// Central moment $\mathbb{E}[(X - \mu)^k]$ for k = 2, 3, 4, from the
+    /// central moments of the components about their own means.
+    fn central_moment(&self, k: i32) -> f64 {
+        let mean = self.mean();
+
+        self.weighted_sum(|component| {
+            let delta = component.mean() - mean;
+            let variance = component.variance();
+            let m3 = component.skewness() * variance.powf(1.5);
+            let m4 = (component.kurtosis() + 3.0) * variance * variance;
+
+            match k {
+                2 => variance + delta * delta,
+                3 => m3 + 3.0 * variance * delta + delta.powi(3),
+                4 => m4 + 4.0 * m3 * delta + 6.0 * variance * delta * delta + delta.powi(4),
+                _ => unreachable!(),
+            }
+        })
+    }
+}
+
+impl Distribution for MixtureDistribution {
+    fn cf(&self, t: f64) -> Complex<f64> {
+        self.components
+            .iter()
+            .zip(&self.weights)
+            .map(|(component, w)| *w * component.cf(t))
+            .sum()
+    }
+
+    fn pdf(&self, x: f64) -> f64 {
+        self.weighted_sum(|component| component.pdf(x))
+    }
+
+    fn pmf(&self, x: f64) -> f64 {
+        self.weighted_sum(|component| component.pmf(x))
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        self.weighted_sum(|component| component.cdf(x))
+    }
+
+    /// Quantile, by Brent's method between the smallest and largest
+    /// component quantiles, which bracket it.
+    fn inv_cdf(&self, p: f64) -> f64 {
+        let quantiles = self.components.iter().map(|component| component.inv_cdf(p));
+        let lower = quantiles.clone().fold(f64::INFINITY, f64::min);
+        let upper = quantiles.fold(f64::NEG_INFINITY, f64::max);
+
+        if lower == upper {
+            return lower;
+        }
+
+        let f = |x: f64| self.cdf(x) - p;
+        let data = RootfinderData::new(1e-12, 0.01, lower, upper, true);
+
+        Brent::new(f, 0.5 * (lower + upper), data).solve()
+    }
+
+    fn mean(&self) -> f64 {
+        self.weighted_sum(|component| component.mean())
+    }
+
+    fn median(&self) -> f64 {
+        self.inv_cdf(0.5)
+    }
+
+    /// Component mode with the highest mixture density. This is exact for
+    /// well separated components, and approximate when they overlap.
+    fn mode(&self) -> f64 {
+        self.components
+            .iter()
+            .map(|component| component.mode())
+            .max_by(|a, b| self.pdf(*a).total_cmp(&self.pdf(*b)))
+            .unwrap_or(f64::NAN)
+    }
+
+    fn variance(&self) -> f64 {
+        self.central_moment(2)
+    }
+
+    fn skewness(&self) -> f64 {
+        self.central_moment(3) / self.variance().powf(1.5)
+    }
+
+    /// Excess kurtosis.
+    fn kurtosis(&self) -> f64 {
+        self.central_moment(4) / self.variance().powi(2) - 3.0
+    }
+
+    /// Entropy $-\sum_i w_i \mathbb{E}_i[\ln f(X)]$, integrating over the
+    /// quantiles of each component.
+    fn entropy(&self) -> f64 {
+        -self.weighted_sum(|component| integrate(|u| self.pdf(component.inv_cdf(u)).ln(), 0.0, 1.0))
+    }
+
+    fn mgf(&self, t: f64) -> f64 {
+        self.weighted_sum(|component| component.mgf(t))
+    }
+
+    /// Samples a component index for each draw from the `Categorical`
+    /// distribution of the weights, then samples from that component.
+    fn sample(&self, n: usize) -> Result<Vec<f64>, RustQuantError> {
+        assert!(n > 0);
+
+        let indices = Categorical::new(&self.weights).sample(n)?;
+        let mut variates = vec![0.0; n];
+
+        for (k, component) in self.components.iter().enumerate() {
+            let draws: Vec<usize> = (0..n).filter(|i| indices[*i] == k as f64).collect();
+
+            if !draws.is_empty() {
+                for (i, x) in draws.iter().zip(component.sample(draws.len())?) {
+                    variates[*i] = x;
+                }
+            }
+        }
+
+        Ok(variates)
+    }
+}
+
+/// Fits a mixture of `n_components` Gaussians to `data` by maximum likelihood,
+/// with the expectation-maximisation (EM) algorithm.
+///
+/// The components are initialised on consecutive blocks of the sorted data,
+/// so the fit is deterministic and the components are ordered by mean.
+///
+/// # Panics
+///
+/// Panics if `n_components` is zero, or there are fewer than two data points
+/// per component.
+#[must_use]
+pub fn em_fit_gaussian_mixture(data: &[f64], n_components: usize) -> MixtureDistribution {
+    const MAX_ITERATIONS: usize = 1000;
+    const TOLERANCE: f64 = 1e-10;
+
+    assert!(n_components > 0);
+    assert!(data.len() >= 2 * n_components);
+
+    let n = data.len() as f64;
+
+    let mut sorted = data.to_vec();
+    sorted.sort_by(f64::total_cmp);
+
+    // Floor on the variances, so no component collapses onto a single point.
+    let data_mean = data.iter().sum::<f64>() / n;
+    let data_variance = data.iter().map(|x| (x - data_mean).powi(2)).sum::<f64>() / n;
+    let variance_floor = 1e-6 * data_variance.max(f64::MIN_POSITIVE);
+
+    let mut weights = vec![1.0 / n_components as f64; n_components];
+    let (mut means, mut variances): (Vec<f64>, Vec<f64>) = (0..n_components)
+        .map(|k| {
+            let block = &sorted[k * data.len() / n_components..(k + 1) * data.len() / n_components];
+            let mean = block.iter().sum::<f64>() / block.len() as f64;
+            let variance =
+                block.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / block.len() as f64;
+
+            (mean, variance.max(variance_floor))
+        })
+        .unzip();
+
+    let mut responsibilities = vec![vec![0.0; n_components]; data.len()];
+    let mut log_likelihood = f64::NEG_INFINITY;
+
+    for _ in 0..MAX_ITERATIONS {
+        // E-step: posterior probability of each component for each point.
+        let mut new_log_likelihood = 0.0;
+
+        for (x, r) in data.iter().zip(responsibilities.iter_mut()) {
+            for k in 0..n_components {
+                r[k] = weights[k] * Gaussian::new(means[k], variances[k]).pdf(*x);
+            }
+
+            let total: f64 = r.iter().sum::<f64>().max(f64::MIN_POSITIVE);
+            r.iter_mut().for_each(|r_k| *r_k /= total);
+            new_log_likelihood += total.ln();
+        }
+
+        // M-step: weighted maximum likelihood estimates.
+        for k in 0..n_components {
+            let n_k = responsibilities
+                .iter()
+                .map(|r| r[k])
+                .sum::<f64>()
+                .max(f64::MIN_POSITIVE);
+
+            means[k] = data
+                .iter()
+                .zip(&responsibilities)
+                .map(|(x, r)| r[k] * x)
+                .sum::<f64>()
+                / n_k;
+            variances[k] = (data
+                .iter()
+                .zip(&responsibilities)
+                .map(|(x, r)| r[k] * (x - means[k]).powi(2))
+                .sum::<f64>()
+                / n_k)
+                .max(variance_floor);
+            weights[k] = n_k / n;
+        }
+
+        let converged = (new_log_likelihood - log_likelihood).abs() < TOLERANCE * n;
+        log_likelihood = new_log_likelihood;
+
+        if converged {
+            break;
+        }
+    }
+
+    let total_weight: f64 = weights.iter().sum();
+
+    MixtureDistribution::new(
+        means
+            .into_iter()
+            .zip(variances)
+            .map(|(mean, variance)| {
+                Box::new(Gaussian::new(mean, variance)) as Box<dyn Distribution>
+            })
+            .collect(),
+        weights.iter().map(|w| w / total_weight).collect(),
+    )
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_mixture {
+    use super::*;
+    use crate::assert_approx_equal;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::StandardNormal;
+
+    // Integral over [a, b] in panels of width 0.25, narrow enough to
+    // resolve the component densities.
+    fn integrate_panels<F: Fn(f64) -> f64>(f: F, a: f64, b: f64) -> f64 {
+        let panels = ((b - a) / 0.25).round() as usize;
+
+        (0..panels)
+            .map(|i| integrate(&f, a + 0.25 * i as f64, a + 0.25 * (i + 1) as f64))
+            .sum()
+    }
+
+    // Calm regime N(0.05, 0.01) with weight 0.7, turbulent N(-0.1, 0.09).
+    fn regimes() -> MixtureDistribution {
+        MixtureDistribution::new(
+            vec![
+                Box::new(Gaussian::new(0.05, 0.01)),
+                Box::new(Gaussian::new(-0.1, 0.09)),
+            ],
+            vec![0.7, 0.3],
+        )
+    }
+
+    #[test]
+    fn test_density_and_distribution() {
+        let mixture = regimes();
+        let (calm, turbulent) = (Gaussian::new(0.05, 0.01), Gaussian::new(-0.1, 0.09));
+
+        assert_approx_equal!(
+            mixture.pdf(0.2),
+            0.7 * calm.pdf(0.2) + 0.3 * turbulent.pdf(0.2),
+            1e-12
+        );
+        assert_approx_equal!(integrate_panels(|x| mixture.pdf(x), -2.5, 2.5), 1.0, 1e-9);
+        assert_approx_equal!(
+            mixture.cdf(0.0),
+            integrate_panels(|x| mixture.pdf(x), -2.5, 0.0),
+            1e-9
+        );
+
+        for p in [0.01, 0.25, 0.5, 0.9] {
+            assert_approx_equal!(mixture.cdf(mixture.inv_cdf(p)), p, 1e-9);
+        }
+
+        assert_approx_equal!(mixture.mgf(0.0), 1.0, 1e-12);
+        assert_approx_equal!(mixture.mode(), 0.05, 1e-12);
+    }
+
+    #[test]
+    fn test_moments() {
+        let mixture = regimes();
+        let mean = mixture.mean();
+
+        assert_approx_equal!(mean, 0.005, 1e-12);
+
+        // Central moments by direct integration.
+        let moment = |k: i32| integrate_panels(|x| (x - mean).powi(k) * mixture.pdf(x), -2.5, 2.5);
+
+        assert_approx_equal!(mixture.variance(), moment(2), 1e-9);
+        assert_approx_equal!(mixture.skewness(), moment(3) / moment(2).powf(1.5), 1e-6);
+        assert_approx_equal!(
+            mixture.kurtosis(),
+            moment(4) / moment(2).powi(2) - 3.0,
+            1e-6
+        );
+        assert_approx_equal!(
+            mixture.entropy(),
+            -integrate_panels(|x| mixture.pdf(x) * mixture.pdf(x).ln(), -2.5, 2.5),
+            1e-6
+        );
+    }
+
+    #[test]
+    fn test_sample_moments() {
+        let mixture = regimes();
+        let sample = mixture.sample(20_000).unwrap();
+
+        let mean = sample.iter().sum::<f64>() / sample.len() as f64;
+        let error = (mixture.variance() / sample.len() as f64).sqrt();
+
+        assert!((mean - mixture.mean()).abs() < 5.0 * error);
+    }
+
+    #[test]
+    fn test_em_recovers_gaussian_mixture() {
+        let mut rng = StdRng::seed_from_u64(178);
+
+        // 40% from N(-2, 0.25) and 60% from N(3, 1).
+        let data: Vec<f64> = (0..5000)
+            .map(|_| {
+                let z: f64 = rng.sample(StandardNormal);
+
+                if rng.gen::<f64>() < 0.4 {
+                    -2.0 + 0.5 * z
+                } else {
+                    3.0 + z
+                }
+            })
+            .collect();
+
+        let fit = em_fit_gaussian_mixture(&data, 2);
+        let components = fit.components();
+
+        assert_approx_equal!(fit.weights()[0], 0.4, 0.03);
+        assert_approx_equal!(components[0].mean(), -2.0, 0.05);
+        assert_approx_equal!(components[0].variance(), 0.25, 0.03);
+        assert_approx_equal!(components[1].mean(), 3.0, 0.05);
+        assert_approx_equal!(components[1].variance(), 1.0, 0.08);
+    }
+
+    #[test]
+    #[should_panic(expected = "Weights must sum to one.")]
+    fn test_invalid_weights() {
+        let _ = MixtureDistribution::new(vec![Box::new(Gaussian::default())], vec![0.5]);
+    }
+}
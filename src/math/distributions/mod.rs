@@ -15,14 +15,30 @@ pub use distribution::*;
 pub mod bernoulli;
 pub use bernoulli::*;
 
+/// Bivariate normal distribution.
+pub mod bivariate_normal;
+pub use bivariate_normal::*;
+
 /// Binomial distribution.
 pub mod binomial;
 pub use binomial::*;
 
+/// Categorical distribution.
+pub mod categorical;
+pub use categorical::*;
+
+/// Cached Cholesky factor for sampling correlated normals.
+pub mod cholesky_cache;
+pub use cholesky_cache::*;
+
 /// Chi-squared distribution.
 pub mod chi_squared;
 pub use chi_squared::*;
 
+/// Copulas for sampling dependent uniforms.
+pub mod copula;
+pub use copula::*;
+
 /// Exponential distribution.
 pub mod exponential;
 pub use exponential::*;
@@ -35,10 +51,26 @@ pub use gamma::*;
 pub mod gaussian;
 pub use gaussian::*;
 
+/// Mixture distributions.
+pub mod mixture;
+pub use mixture::*;
+
+/// Normal inverse Gaussian distribution.
+pub mod nig;
+pub use nig::*;
+
 /// Poisson distribution.
 pub mod poisson;
 pub use poisson::*;
 
+/// Sampling into `nalgebra` vectors and matrices.
+pub mod sample_into;
+pub use sample_into::*;
+
+/// Truncated distributions.
+pub mod truncated;
+pub use truncated::*;
+
 /// Uniform distribution.
 pub mod uniform;
 pub use uniform::*;
@@ -0,0 +1,499 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::{
+    error::RustQuantError,
+    math::{distributions::Distribution, StreamingStats},
+};
+use num::Complex;
+use rand::Rng;
+use std::f64::consts::PI;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Normal inverse Gaussian distribution: X ~ NIG(alpha, beta, delta, mu)
+/// <https://en.wikipedia.org/wiki/Normal-inverse_Gaussian_distribution>
+///
+/// X is a normal variance-mean mixture: X = mu + beta * V + sqrt(V) * Z,
+/// where Z ~ N(0, 1) and V is inverse Gaussian, with mean delta / gamma
+/// and shape delta^2, and gamma = sqrt(alpha^2 - beta^2).
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NIG {
+    /// Alpha: tail heaviness (alpha > |beta|).
+    alpha: f64,
+    /// Beta: asymmetry.
+    beta: f64,
+    /// Delta: scale (delta > 0).
+    delta: f64,
+    /// Mu: location.
+    mu: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl NIG {
+    /// New instance of a NIG distribution.
+    ///
+    /// # Panics
+    ///
+    /// Panics if alpha <= |beta| or delta is not positive.
+    #[must_use]
+    pub fn new(alpha: f64, beta: f64, delta: f64, mu: f64) -> Self {
+        assert!(alpha > beta.abs() && delta > 0.0);
+
+        Self {
+            alpha,
+            beta,
+            delta,
+            mu,
+        }
+    }
+
+    /// Fit a NIG distribution to data by the method of moments.
+    ///
+    /// With sample skewness s and excess kurtosis k, the moment equations
+    /// have a solution if and only if 3k > 5s^2.
+    ///
+    /// # Errors
+    ///
+    /// `RustQuantError::InvalidArgument` if there are fewer than four
+    /// observations, or the sample moments are outside the NIG range.
+    pub fn fit(data: &[f64]) -> Result<Self, RustQuantError> {
+        if data.len() < 4 {
+            return Err(RustQuantError::InvalidArgument(
+                "At least four observations are needed to fit a NIG distribution.".to_string(),
+            ));
+        }
+
+        let stats: StreamingStats = data.iter().copied().collect();
+        let (mean, variance) = (stats.mean(), stats.variance());
+        let (s, k) = (stats.skewness(), stats.kurtosis());
+
+        if 3.0 * k <= 5.0 * s * s {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Sample skewness ({s}) and excess kurtosis ({k}) must satisfy 3k > 5s^2."
+            )));
+        }
+
+        // skewness = 3 b / sqrt(zeta), kurtosis = 3 (1 + 4 b^2) / zeta,
+        // with b = beta / alpha and zeta = delta * gamma.
+        let zeta = 3.0 / (k - 4.0 * s * s / 3.0);
+        let b = s * zeta.sqrt() / 3.0;
+
+        // variance = zeta / (gamma^2 (1 - b^2)).
+        let gamma = (zeta / (variance * (1.0 - b * b))).sqrt();
+        let alpha = gamma / (1.0 - b * b).sqrt();
+        let beta = b * alpha;
+        let delta = zeta / gamma;
+
+        Ok(Self::new(alpha, beta, delta, mean - delta * beta / gamma))
+    }
+
+    /// Generate `n` random variates, using the given random number generator.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the inverse Gaussian mixing distribution
+    /// cannot be constructed.
+    pub fn sample_with_rng<R: Rng + ?Sized>(
+        &self,
+        n: usize,
+        rng: &mut R,
+    ) -> Result<Vec<f64>, RustQuantError> {
+        use rand_distr::{Distribution, InverseGaussian, StandardNormal};
+
+        let mixing = InverseGaussian::new(self.delta / self.gamma(), self.delta * self.delta)?;
+
+        Ok((0..n)
+            .map(|_| {
+                let v: f64 = mixing.sample(rng);
+                let z: f64 = rng.sample(StandardNormal);
+
+                self.mu + self.beta * v + v.sqrt() * z
+            })
+            .collect())
+    }
+
+    fn gamma(&self) -> f64 {
+        (self.alpha * self.alpha - self.beta * self.beta).sqrt()
+    }
+
+    /// Integral of the density from `x` to infinity (`upper = true`)
+    /// or from minus infinity to `x`.
+    fn tail(&self, x: f64, upper: bool) -> f64 {
+        self.half_line_integral(|y| self.pdf(y), x, upper)
+    }
+
+    /// Integral of `f` from `x` to infinity (`upper = true`) or from
+    /// minus infinity to `x`, by composite Gauss-Legendre quadrature
+    /// after the change of variables s = c t / (1 - t).
+    fn half_line_integral(&self, f: impl Fn(f64) -> f64, x: f64, upper: bool) -> f64 {
+        const PANELS: usize = 32;
+
+        let (nodes, weights) = gauss_legendre(16);
+        let c = self.variance().sqrt();
+        let sign = if upper { 1.0 } else { -1.0 };
+
+        let mut integral = 0.0;
+
+        for panel in 0..PANELS {
+            let (a, b) = (
+                panel as f64 / PANELS as f64,
+                (panel + 1) as f64 / PANELS as f64,
+            );
+
+            for (node, weight) in nodes.iter().zip(&weights) {
+                let t = 0.5 * (b - a) * node + 0.5 * (a + b);
+                let s = c * t / (1.0 - t);
+                let jacobian = c / (1.0 - t).powi(2);
+
+                let value = f(x + sign * s) * jacobian;
+                if value.is_finite() {
+                    integral += 0.5 * (b - a) * weight * value;
+                }
+            }
+        }
+
+        integral
+    }
+}
+
+impl Distribution for NIG {
+    fn cf(&self, t: f64) -> Complex<f64> {
+        let i: Complex<f64> = Complex::i();
+        let (alpha, beta, delta, mu) = (self.alpha, self.beta, self.delta, self.mu);
+
+        let root = (alpha * alpha - (beta + i * t).powi(2)).sqrt();
+
+        (i * mu * t + delta * (self.gamma() - root)).exp()
+    }
+
+    fn pdf(&self, x: f64) -> f64 {
+        let (alpha, beta, delta, mu) = (self.alpha, self.beta, self.delta, self.mu);
+
+        let q = delta.hypot(x - mu);
+
+        // K_1(z) = exp(-z) * scaled K_1(z), combined in one exponent.
+        alpha * delta * bessel_k1_scaled(alpha * q) / (PI * q)
+            * (delta * self.gamma() + beta * (x - mu) - alpha * q).exp()
+    }
+
+    fn pmf(&self, x: f64) -> f64 {
+        self.pdf(x)
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        if x <= self.mean() {
+            self.tail(x, false)
+        } else {
+            1.0 - self.tail(x, true)
+        }
+    }
+
+    fn inv_cdf(&self, p: f64) -> f64 {
+        assert!(p > 0.0 && p < 1.0);
+
+        let (mean, sd) = (self.mean(), self.variance().sqrt());
+
+        let mut lower = mean - sd;
+        while self.cdf(lower) > p {
+            lower -= 2.0 * (mean - lower);
+        }
+        let mut upper = mean + sd;
+        while self.cdf(upper) < p {
+            upper += 2.0 * (upper - mean);
+        }
+
+        for _ in 0..100 {
+            let middle = 0.5 * (lower + upper);
+
+            if self.cdf(middle) < p {
+                lower = middle;
+            } else {
+                upper = middle;
+            }
+        }
+
+        0.5 * (lower + upper)
+    }
+
+    fn mean(&self) -> f64 {
+        self.mu + self.delta * self.beta / self.gamma()
+    }
+
+    fn median(&self) -> f64 {
+        self.inv_cdf(0.5)
+    }
+
+    fn mode(&self) -> f64 {
+        // The density is unimodal: golden-section search.
+        let (mean, sd) = (self.mean(), self.variance().sqrt());
+        let ratio = (5_f64.sqrt() - 1.0) / 2.0;
+
+        let (mut a, mut b) = (mean - 3.0 * sd, mean + 3.0 * sd);
+
+        while b - a > 1e-10 * sd {
+            let (x1, x2) = (b - ratio * (b - a), a + ratio * (b - a));
+
+            if self.pdf(x1) < self.pdf(x2) {
+                a = x1;
+            } else {
+                b = x2;
+            }
+        }
+
+        0.5 * (a + b)
+    }
+
+    fn variance(&self) -> f64 {
+        self.delta * self.alpha * self.alpha / self.gamma().powi(3)
+    }
+
+    fn skewness(&self) -> f64 {
+        3.0 * self.beta / (self.alpha * (self.delta * self.gamma()).sqrt())
+    }
+
+    fn kurtosis(&self) -> f64 {
+        3.0 * (1.0 + 4.0 * (self.beta / self.alpha).powi(2)) / (self.delta * self.gamma())
+    }
+
+    fn entropy(&self) -> f64 {
+        // No closed form: -integral of f ln f, split at the mean.
+        let f_ln_f = |x: f64| {
+            let f = self.pdf(x);
+
+            if f > 0.0 {
+                f * f.ln()
+            } else {
+                0.0
+            }
+        };
+        let mean = self.mean();
+
+        -(self.half_line_integral(f_ln_f, mean, false)
+            + self.half_line_integral(f_ln_f, mean, true))
+    }
+
+    fn mgf(&self, t: f64) -> f64 {
+        assert!((self.beta + t).abs() < self.alpha);
+
+        let (alpha, beta, delta, mu) = (self.alpha, self.beta, self.delta, self.mu);
+
+        (mu * t + delta * (self.gamma() - (alpha * alpha - (beta + t).powi(2)).sqrt())).exp()
+    }
+
+    fn sample(&self, n: usize) -> Result<Vec<f64>, RustQuantError> {
+        assert!(n > 0);
+
+        self.sample_with_rng(n, &mut rand::thread_rng())
+    }
+}
+
+/// Exponentially scaled modified Bessel function of the second kind,
+/// $e^x K_1(x)$, for $x > 0$.
+///
+/// Uses the integral representation
+/// $e^x K_1(x) = \int_0^\infty e^{-x (\cosh t - 1)} \cosh t \, dt$,
+/// for which the trapezoidal rule converges exponentially fast. The
+/// integrand has width of order $1 / \sqrt{x}$, so the step shrinks with it.
+fn bessel_k1_scaled(x: f64) -> f64 {
+    let h = 0.1_f64.min(0.5 / x.sqrt());
+
+    let mut sum = 0.5;
+
+    for k in 1.. {
+        let t = k as f64 * h;
+        let term = (-x * (t.cosh() - 1.0)).exp() * t.cosh();
+        sum += term;
+
+        if term < 1e-17 * sum {
+            break;
+        }
+    }
+
+    h * sum
+}
+
+/// Gauss-Legendre nodes and weights on [-1, 1], by Newton's method on
+/// the Legendre polynomial $P_n$.
+fn gauss_legendre(n: usize) -> (Vec<f64>, Vec<f64>) {
+    let mut nodes = vec![0.0; n];
+    let mut weights = vec![0.0; n];
+
+    for k in 0..n {
+        // Initial guess for the k-th root.
+        let mut x = (PI * (k as f64 + 0.75) / (n as f64 + 0.5)).cos();
+        let mut derivative = 0.0;
+
+        for _ in 0..100 {
+            // P_n(x) and P_n'(x) by the three-term recurrence.
+            let (mut p0, mut p1) = (1.0, x);
+            for j in 2..=n {
+                let j = j as f64;
+                (p0, p1) = (p1, ((2.0 * j - 1.0) * x * p1 - (j - 1.0) * p0) / j);
+            }
+            derivative = n as f64 * (x * p1 - p0) / (x * x - 1.0);
+
+            let step = p1 / derivative;
+            x -= step;
+
+            if step.abs() < 1e-15 {
+                break;
+            }
+        }
+
+        nodes[k] = x;
+        weights[k] = 2.0 / ((1.0 - x * x) * derivative * derivative);
+    }
+
+    (nodes, weights)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_nig {
+    use super::*;
+    use crate::assert_approx_equal;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_bessel_k1() {
+        // Values from Abramowitz and Stegun, Table 9.8.
+        let k1 = |x: f64| bessel_k1_scaled(x) * (-x).exp();
+
+        assert_approx_equal!(k1(0.1), 9.853_844_780_870_606, 1e-12);
+        assert_approx_equal!(k1(1.0), 0.601_907_230_197_234_6, 1e-14);
+        assert_approx_equal!(k1(5.0), 0.004_044_613_445_452_164, 1e-15);
+
+        // Large arguments: the asymptotic expansion
+        // e^x K_1(x) ~ sqrt(pi / 2x) (1 + 3 / 8x - 15 / 128x^2).
+        let x: f64 = 1e4;
+        let asymptotic = (PI / (2.0 * x)).sqrt() * (1.0 + 3.0 / (8.0 * x) - 15.0 / (128.0 * x * x));
+        assert_approx_equal!(bessel_k1_scaled(x), asymptotic, 1e-14);
+    }
+
+    #[test]
+    fn test_gauss_legendre() {
+        let (nodes, weights) = gauss_legendre(16);
+
+        // Exact for polynomials of degree up to 31.
+        let integral: f64 = nodes
+            .iter()
+            .zip(&weights)
+            .map(|(x, w)| w * x.powi(30))
+            .sum();
+
+        assert_approx_equal!(weights.iter().sum::<f64>(), 2.0, 1e-14);
+        assert_approx_equal!(integral, 2.0 / 31.0, 1e-14);
+    }
+
+    #[test]
+    fn test_nig_density_and_distribution() {
+        let dist = NIG::new(2.0, 0.5, 1.0, 0.1);
+
+        // Symmetric case at the centre: alpha K_1(alpha) e^alpha / pi.
+        let symmetric = NIG::new(1.0, 0.0, 1.0, 0.0);
+        assert_approx_equal!(
+            symmetric.pdf(0.0),
+            0.601_907_230_197_234_6 * 1_f64.exp() / PI,
+            1e-14
+        );
+        assert_approx_equal!(symmetric.cdf(0.0), 0.5, 1e-10);
+
+        // The distribution function is the integral of the density.
+        for x in [-3.0, -0.5, 0.2, 0.8, 4.0] {
+            let h = 1e-4;
+            let derivative = (dist.cdf(x + h) - dist.cdf(x - h)) / (2.0 * h);
+            assert_approx_equal!(derivative, dist.pdf(x), 1e-7);
+        }
+
+        assert!(dist.cdf(-30.0) < 1e-12);
+        assert!(1.0 - dist.cdf(30.0) < 1e-12);
+        assert_approx_equal!(dist.cdf(dist.inv_cdf(0.99)), 0.99, 1e-10);
+        assert!(dist.mode() < dist.median() && dist.median() < dist.mean());
+    }
+
+    #[test]
+    fn test_nig_entropy() {
+        // Near-Gaussian limit: with beta = 0 and delta / alpha = 1 fixed, the
+        // NIG tends to N(0, 1) as alpha grows.
+        let gaussian_entropy = 0.5 * (2.0 * PI * 1_f64.exp()).ln();
+        let near_gaussian = NIG::new(100.0, 0.0, 100.0, 0.0);
+
+        assert_approx_equal!(near_gaussian.entropy(), gaussian_entropy, 1e-6);
+
+        // Heavier tails at the same unit variance: less entropy than the
+        // Gaussian, which maximises it. The location does not matter.
+        let heavy = NIG::new(1.0, 0.5, (0.75_f64).powf(1.5), 2.0);
+
+        assert_approx_equal!(heavy.variance(), 1.0, 1e-12);
+        assert!(heavy.entropy() < gaussian_entropy - 0.01);
+        assert_approx_equal!(
+            heavy.entropy(),
+            NIG::new(1.0, 0.5, (0.75_f64).powf(1.5), -3.0).entropy(),
+            1e-10
+        );
+    }
+
+    #[test]
+    fn test_nig_characteristic_function() {
+        let dist = NIG::new(2.0, 0.5, 1.0, 0.1);
+
+        assert_approx_equal!(dist.cf(0.0).re, 1.0, 1e-15);
+        assert_approx_equal!(dist.mgf(0.0), 1.0, 1e-15);
+
+        // cf'(0) = i E[X].
+        let h = 1e-6;
+        let derivative = (dist.cf(h) - dist.cf(-h)) / (2.0 * h);
+        assert_approx_equal!(derivative.im, dist.mean(), 1e-8);
+    }
+
+    #[test]
+    fn test_nig_fit_recovers_moments() {
+        let dist = NIG::new(2.0, 0.5, 1.0, 0.1);
+        let mut rng = StdRng::seed_from_u64(168);
+
+        let data = dist.sample_with_rng(500_000, &mut rng).unwrap();
+        let stats: StreamingStats = data.iter().copied().collect();
+
+        // Sample moments match the theoretical ones.
+        assert_approx_equal!(stats.mean(), dist.mean(), 0.01);
+        assert_approx_equal!(stats.variance(), dist.variance(), 0.01);
+        assert_approx_equal!(stats.skewness(), dist.skewness(), 0.05);
+        assert_approx_equal!(stats.kurtosis(), dist.kurtosis(), 0.2);
+
+        // The fitted distribution recovers the moments and the parameters.
+        let fitted = NIG::fit(&data).unwrap();
+
+        assert_approx_equal!(fitted.mean(), stats.mean(), 1e-10);
+        assert_approx_equal!(fitted.variance(), stats.variance(), 1e-10);
+        assert_approx_equal!(fitted.skewness(), stats.skewness(), 1e-10);
+        assert_approx_equal!(fitted.kurtosis(), stats.kurtosis(), 1e-10);
+
+        assert_approx_equal!(fitted.alpha, 2.0, 0.2);
+        assert_approx_equal!(fitted.beta, 0.5, 0.1);
+        assert_approx_equal!(fitted.delta, 1.0, 0.1);
+        assert_approx_equal!(fitted.mu, 0.1, 0.05);
+    }
+
+    #[test]
+    fn test_nig_fit_rejects_thin_tails() {
+        let data = [-1.0, 1.0, -1.0, 1.0, -1.0, 1.0];
+
+        assert!(NIG::fit(&data).is_err());
+    }
+}
@@ -9,7 +9,7 @@
 
 use crate::{error::RustQuantError, math::distributions::Distribution};
 use num::Complex;
-use statrs::function::gamma::{gamma_li, gamma_ui};
+use statrs::function::gamma::{gamma_ur, ln_gamma};
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // STRUCTS
@@ -92,8 +92,12 @@ impl Distribution for Poisson {
     /// assert_approx_equal!(poisson.pmf(1.0), poisson.pdf(1.0), 1e-7);
     /// ```
     fn pmf(&self, x: f64) -> f64 {
-        (self.lambda).powi(x as i32) * (-(self.lambda)).exp()
-            / ((1..=x as usize).product::<usize>() as f64)
+        if x < 0.0 || x.fract() != 0.0 {
+            return 0.0;
+        }
+
+        // In logs, so the factorial doesn't overflow.
+        (x * self.lambda.ln() - self.lambda - ln_gamma(x + 1.0)).exp()
     }
 
     /// Cumulative distribution function of the Poisson distribution.
@@ -104,11 +108,16 @@ impl Distribution for Poisson {
     ///
     /// let poisson = Poisson::new(1.0);
     ///
-    /// assert_approx_equal!(poisson.cdf(1.0), 0.6408591, 1e-7);
-    /// assert_approx_equal!(poisson.cdf(2.0), 0.9126873, 1e-7);
+    /// assert_approx_equal!(poisson.cdf(1.0), 0.7357589, 1e-7);
+    /// assert_approx_equal!(poisson.cdf(2.0), 0.9196986, 1e-7);
     /// ```
     fn cdf(&self, x: f64) -> f64 {
-        1.0 - gamma_li(x + 1., self.lambda) / gamma_ui(x + 1., self.lambda)
+        if x < 0.0 {
+            return 0.0;
+        }
+
+        // P(X <= k) = Q(k + 1, lambda), the regularized upper incomplete gamma.
+        gamma_ur(x.floor() + 1.0, self.lambda)
     }
 
     /// Inverse cumulative distribution function of the Poisson distribution.
@@ -302,7 +311,7 @@ mod tests {
 
         // Distribution function
         let cdf = dist.cdf(1.);
-        assert_approx_equal!(cdf, 0.640_859_085_770_477_5, EPS);
+        assert_approx_equal!(cdf, 0.735_758_882_342_884_6, EPS);
 
         // Inverse distribution function
         let icdf = dist.inv_cdf(0.5);
@@ -0,0 +1,89 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Sampling from a distribution directly into `nalgebra` vectors and
+//! matrices, with a caller-supplied random number generator.
+
+use super::Distribution;
+use nalgebra::{DMatrix, DVector};
+use rand::Rng;
+use rand_distr::Open01;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Draws samples into a container `T`.
+///
+/// Implemented for every [`Distribution`] with `T = DVector<f64>`, by
+/// inverse transform sampling: each draw is `inv_cdf(u)` for a uniform `u`
+/// in $(0, 1)$, so the draws are reproducible from a seeded `rng`.
+pub trait SampleInto<T> {
+    /// `n` independent draws.
+    fn sample_into(&self, n: usize, rng: &mut impl Rng) -> T;
+
+    /// A `rows` by `cols` matrix of independent draws.
+    fn sample_into_matrix(&self, rows: usize, cols: usize, rng: &mut impl Rng) -> DMatrix<f64>;
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl<D: Distribution> SampleInto<DVector<f64>> for D {
+    fn sample_into(&self, n: usize, rng: &mut impl Rng) -> DVector<f64> {
+        DVector::from_fn(n, |_, _| self.inv_cdf(rng.sample(Open01)))
+    }
+
+    fn sample_into_matrix(&self, rows: usize, cols: usize, rng: &mut impl Rng) -> DMatrix<f64> {
+        DMatrix::from_fn(rows, cols, |_, _| self.inv_cdf(rng.sample(Open01)))
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_sample_into {
+    use super::*;
+    use crate::math::distributions::{Bernoulli, DistributionClass, Gaussian, Uniform};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn test_gaussian_into_vector() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let n = 1_000;
+        let sample: DVector<f64> = Gaussian::default().sample_into(n, &mut rng);
+
+        assert_eq!(sample.len(), n);
+        assert!(sample.mean().abs() < 3.0 / (n as f64).sqrt());
+        assert!((sample.variance() - 1.0).abs() < 0.15);
+    }
+
+    #[test]
+    fn test_uniform_into_matrix() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let uniform = Uniform::new(2.0, 5.0, DistributionClass::Continuous);
+        let sample = uniform.sample_into_matrix(20, 30, &mut rng);
+
+        assert_eq!(sample.shape(), (20, 30));
+        assert!(sample.iter().all(|x| (2.0..5.0).contains(x)));
+    }
+
+    #[test]
+    fn test_seeded_samples_are_reproducible() {
+        let bernoulli = Bernoulli::new(0.3);
+        let first: DVector<f64> = bernoulli.sample_into(100, &mut StdRng::seed_from_u64(3));
+        let second: DVector<f64> = bernoulli.sample_into(100, &mut StdRng::seed_from_u64(3));
+
+        assert_eq!(first, second);
+        assert!(first.iter().all(|x| *x == 0.0 || *x == 1.0));
+    }
+}
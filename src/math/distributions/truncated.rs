@@ -0,0 +1,295 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::Distribution;
+use crate::error::RustQuantError;
+use crate::math::integrate;
+use num::Complex;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// A distribution truncated to the interval `[lower, upper]`, for quantities
+/// with natural bounds (volatilities, recovery rates, etc).
+///
+/// The density is renormalised by $Z = F(b) - F(a)$, where $F$ is the
+/// distribution function of the inner distribution. Expectations (moments,
+/// entropy, the characteristic and moment generating functions) are
+/// computed numerically, as $\mathbb{E}[g(X)] = \int_0^1 g(F^{-1}(F(a) + u Z)) \, du$,
+/// which also handles infinite bounds.
+pub struct TruncatedDistribution<D: Distribution> {
+    /// Distribution being truncated.
+    inner: D,
+    /// Lower bound (may be `f64::NEG_INFINITY`).
+    lower: f64,
+    /// Upper bound (may be `f64::INFINITY`).
+    upper: f64,
+    /// Normalisation constant, `cdf(upper) - cdf(lower)`.
+    norm_const: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl<D: Distribution> TruncatedDistribution<D> {
+    /// New instance of `inner` truncated to `[lower, upper]`.
+    /// # Examples
+    /// ```
+    /// # use RustQuant::assert_approx_equal;
+    /// # use RustQuant::math::distributions::*;
+    ///
+    /// let half_normal = TruncatedDistribution::new(Gaussian::default(), 0.0, f64::INFINITY);
+    ///
+    /// assert_approx_equal!(half_normal.pdf(0.0), 2.0 * Gaussian::default().pdf(0.0), 1e-12);
+    /// assert_approx_equal!(half_normal.median(), 0.6744897501960817, 1e-9);
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lower` is not below `upper`, or the interval has zero
+    /// probability under `inner`.
+    #[must_use]
+    pub fn new(inner: D, lower: f64, upper: f64) -> Self {
+        assert!(lower < upper, "Lower bound must be below the upper bound.");
+
+        let norm_const = inner.cdf(upper) - inner.cdf(lower);
+        assert!(
+            norm_const > 0.0,
+            "Truncation interval has zero probability."
+        );
+
+        Self {
+            inner,
+            lower,
+            upper,
+            norm_const,
+        }
+    }
+
+    /// Distribution being truncated.
+    pub fn inner(&self) -> &D {
+        &self.inner
+    }
+
+    /// Bounds of the support, `(lower, upper)`.
+    pub fn bounds(&self) -> (f64, f64) {
+        (self.lower, self.upper)
+    }
+
+    /// Normalisation constant, `cdf(upper) - cdf(lower)`.
+    pub fn norm_const(&self) -> f64 {
+        self.norm_const
+    }
+
+    fn contains(&self, x: f64) -> bool {
+        (self.lower..=self.upper).contains(&x)
+    }
+
+    /// $\mathbb{E}[g(X)]$, by integrating over the quantiles.
+    fn expectation<G>(&self, g: G) -> f64
+    where
+        G: Fn(f64) -> f64,
+    {
+        integrate(|u| g(self.inv_cdf(u)), 0.0, 1.0)
+    }
+
+    /// Central moment $\mathbb{E}[(X - \mu)^k]$.
+    fn central_moment(&self, k: i32) -> f64 {
+        let mean = self.mean();
+
+        self.expectation(|x| (x - mean).powi(k))
+    }
+}
+
+impl<D: Distribution> Distribution for TruncatedDistribution<D> {
+    fn cf(&self, t: f64) -> Complex<f64> {
+        Complex::new(
+            self.expectation(|x| (t * x).cos()),
+            self.expectation(|x| (t * x).sin()),
+        )
+    }
+
+    fn pdf(&self, x: f64) -> f64 {
+        if self.contains(x) {
+            self.inner.pdf(x) / self.norm_const
+        } else {
+            0.0
+        }
+    }
+
+    fn pmf(&self, x: f64) -> f64 {
+        if self.contains(x) {
+            self.inner.pmf(x) / self.norm_const
+        } else {
+            0.0
+        }
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        if x < self.lower {
+            0.0
+        } else if x >= self.upper {
+            1.0
+        } else {
+            (self.inner.cdf(x) - self.inner.cdf(self.lower)) / self.norm_const
+        }
+    }
+
+    fn inv_cdf(&self, p: f64) -> f64 {
+        let p = self.inner.cdf(self.lower) + p * self.norm_const;
+
+        self.inner.inv_cdf(p).clamp(self.lower, self.upper)
+    }
+
+    fn mean(&self) -> f64 {
+        self.expectation(|x| x)
+    }
+
+    fn median(&self) -> f64 {
+        self.inv_cdf(0.5)
+    }
+
+    /// Mode of the inner distribution, clamped to the bounds (exact for
+    /// unimodal inner distributions).
+    fn mode(&self) -> f64 {
+        self.inner.mode().clamp(self.lower, self.upper)
+    }
+
+    fn variance(&self) -> f64 {
+        self.central_moment(2)
+    }
+
+    fn skewness(&self) -> f64 {
+        self.central_moment(3) / self.variance().powf(1.5)
+    }
+
+    /// Excess kurtosis.
+    fn kurtosis(&self) -> f64 {
+        self.central_moment(4) / self.variance().powi(2) - 3.0
+    }
+
+    fn entropy(&self) -> f64 {
+        self.norm_const.ln() - self.expectation(|x| self.inner.pdf(x).ln())
+    }
+
+    fn mgf(&self, t: f64) -> f64 {
+        self.expectation(|x| (t * x).exp())
+    }
+
+    /// Samples by inversion: $F^{-1}(F(a) + U Z)$ for uniform $U$.
+    fn sample(&self, n: usize) -> Result<Vec<f64>, RustQuantError> {
+        use rand::{thread_rng, Rng};
+
+        assert!(n > 0);
+
+        let mut rng = thread_rng();
+
+        Ok((0..n).map(|_| self.inv_cdf(rng.gen::<f64>())).collect())
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_truncated {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::math::distributions::{DistributionClass, Exponential, Gaussian, Uniform};
+    use std::f64::consts::{E, PI};
+
+    #[test]
+    fn test_half_normal_moments() {
+        // Half-normal distribution with scale sigma = 2.
+        let sigma = 2.0;
+        let half_normal =
+            TruncatedDistribution::new(Gaussian::new(0.0, sigma * sigma), 0.0, f64::INFINITY);
+
+        assert_approx_equal!(half_normal.norm_const(), 0.5, 1e-12);
+        assert_approx_equal!(half_normal.mean(), sigma * (2.0 / PI).sqrt(), 1e-8);
+        assert_approx_equal!(
+            half_normal.variance(),
+            sigma * sigma * (1.0 - 2.0 / PI),
+            1e-8
+        );
+        assert_approx_equal!(
+            half_normal.skewness(),
+            2.0_f64.sqrt() * (4.0 - PI) / (PI - 2.0).powf(1.5),
+            1e-6
+        );
+        assert_approx_equal!(
+            half_normal.kurtosis(),
+            8.0 * (PI - 3.0) / (PI - 2.0).powi(2),
+            1e-6
+        );
+        assert_approx_equal!(
+            half_normal.entropy(),
+            0.5 * (PI * E * sigma * sigma / 2.0).ln(),
+            1e-8
+        );
+        assert_approx_equal!(half_normal.mode(), 0.0, 1e-12);
+    }
+
+    #[test]
+    fn test_distribution_functions() {
+        let truncated = TruncatedDistribution::new(Gaussian::default(), -1.0, 2.0);
+        let gaussian = Gaussian::default();
+        let z = gaussian.cdf(2.0) - gaussian.cdf(-1.0);
+
+        assert_approx_equal!(truncated.pdf(0.5), gaussian.pdf(0.5) / z, 1e-12);
+        assert_eq!(truncated.pdf(2.5), 0.0);
+        assert_eq!(truncated.cdf(-1.5), 0.0);
+        assert_eq!(truncated.cdf(2.0), 1.0);
+
+        for p in [0.01, 0.3, 0.5, 0.9] {
+            assert_approx_equal!(truncated.cdf(truncated.inv_cdf(p)), p, 1e-9);
+        }
+
+        // Densities integrate to one, and the mgf at zero is one.
+        assert_approx_equal!(integrate(|x| truncated.pdf(x), -1.0, 2.0), 1.0, 1e-9);
+        assert_approx_equal!(truncated.mgf(0.0), 1.0, 1e-12);
+        assert_approx_equal!(truncated.cf(0.0).re, 1.0, 1e-12);
+    }
+
+    #[test]
+    fn test_truncated_exponential_memoryless() {
+        // Truncating an exponential below at a shifts it by a.
+        let exponential = TruncatedDistribution::new(Exponential::new(2.0), 1.0, f64::INFINITY);
+
+        assert_approx_equal!(exponential.mean(), 1.5, 1e-8);
+        assert_approx_equal!(exponential.variance(), 0.25, 1e-8);
+    }
+
+    #[test]
+    fn test_truncated_uniform() {
+        let uniform = TruncatedDistribution::new(
+            Uniform::new(0.0, 10.0, DistributionClass::Continuous),
+            2.0,
+            4.0,
+        );
+
+        assert_approx_equal!(uniform.mean(), 3.0, 1e-9);
+        assert_approx_equal!(uniform.variance(), 4.0 / 12.0, 1e-9);
+    }
+
+    #[test]
+    fn test_inversion_sampling() {
+        let truncated = TruncatedDistribution::new(Gaussian::new(0.2, 0.09), 0.0, 1.0);
+        let sample = truncated.sample(10_000).unwrap();
+
+        assert!(sample.iter().all(|x| (0.0..=1.0).contains(x)));
+
+        let mean = sample.iter().sum::<f64>() / sample.len() as f64;
+        let error = (truncated.variance() / sample.len() as f64).sqrt();
+        assert!((mean - truncated.mean()).abs() < 5.0 * error);
+    }
+}
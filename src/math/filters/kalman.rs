@@ -0,0 +1,459 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Linear Gaussian state-space model:
+//!
+//! $$
+//! \begin{aligned}
+//! x_t &= F_t x_{t-1} + w_t, & w_t &\sim N(0, Q_t), \\\\
+//! y_t &= H_t x_t + v_t, & v_t &\sim N(0, R_t),
+//! \end{aligned}
+//! $$
+//!
+//! with prior $x_0 \sim N(m_0, P_0)$ for the first state. The system
+//! matrices are functions of the time index $t$, so time-varying models
+//! (e.g. regressions with $H_t = [1, x_t]$) fit in the same framework.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use nalgebra::{DMatrix, DVector};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// System matrix of a state-space model, as a function of the time index.
+pub type SystemMatrix<'a> = Box<dyn Fn(usize) -> DMatrix<f64> + 'a>;
+
+/// Linear Kalman filter.
+pub struct KalmanFilter<'a> {
+    /// State transition matrix $F_t$ (the value at $t = 0$ is not used).
+    transition: SystemMatrix<'a>,
+    /// Observation matrix $H_t$.
+    observation: SystemMatrix<'a>,
+    /// Process noise covariance $Q_t$ (the value at $t = 0$ is not used).
+    process_noise: SystemMatrix<'a>,
+    /// Observation noise covariance $R_t$.
+    observation_noise: SystemMatrix<'a>,
+    /// Prior mean $m_0$ of the first state.
+    initial_state: DVector<f64>,
+    /// Prior covariance $P_0$ of the first state.
+    initial_covariance: DMatrix<f64>,
+}
+
+/// Output of [`KalmanFilter::filter`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct KalmanFilterOutput {
+    /// Predicted states $x_{t|t-1}$.
+    pub predicted_states: Vec<DVector<f64>>,
+    /// Predicted state covariances $P_{t|t-1}$.
+    pub predicted_covariances: Vec<DMatrix<f64>>,
+    /// Filtered states $x_{t|t}$.
+    pub filtered_states: Vec<DVector<f64>>,
+    /// Filtered state covariances $P_{t|t}$.
+    pub filtered_covariances: Vec<DMatrix<f64>>,
+    /// One-step-ahead forecast errors $v_t = y_t - H_t x_{t|t-1}$
+    /// (`None` for missing observations).
+    pub innovations: Vec<Option<DVector<f64>>>,
+    /// Forecast error covariances $S_t = H_t P_{t|t-1} H_t^\top + R_t$
+    /// (`None` for missing observations).
+    pub innovation_covariances: Vec<Option<DMatrix<f64>>>,
+    /// Log-likelihood of the (non-missing) observations.
+    pub log_likelihood: f64,
+}
+
+/// Output of [`KalmanFilter::smooth`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct KalmanSmootherOutput {
+    /// Smoothed states $x_{t|n}$.
+    pub smoothed_states: Vec<DVector<f64>>,
+    /// Smoothed state covariances $P_{t|n}$.
+    pub smoothed_covariances: Vec<DMatrix<f64>>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl<'a> KalmanFilter<'a> {
+    /// New Kalman filter with time-varying system matrices.
+    ///
+    /// # Errors
+    /// Returns an error if the initial covariance is not square with the
+    /// dimension of the initial state.
+    pub fn new(
+        transition: impl Fn(usize) -> DMatrix<f64> + 'a,
+        observation: impl Fn(usize) -> DMatrix<f64> + 'a,
+        process_noise: impl Fn(usize) -> DMatrix<f64> + 'a,
+        observation_noise: impl Fn(usize) -> DMatrix<f64> + 'a,
+        initial_state: DVector<f64>,
+        initial_covariance: DMatrix<f64>,
+    ) -> Result<Self, RustQuantError> {
+        let n = initial_state.len();
+
+        if initial_covariance.shape() != (n, n) {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "Initial covariance must be {n}x{n}."
+            )));
+        }
+
+        Ok(Self {
+            transition: Box::new(transition),
+            observation: Box::new(observation),
+            process_noise: Box::new(process_noise),
+            observation_noise: Box::new(observation_noise),
+            initial_state,
+            initial_covariance,
+        })
+    }
+
+    /// New Kalman filter with constant system matrices.
+    ///
+    /// # Errors
+    /// Returns an error if the initial covariance is not square with the
+    /// dimension of the initial state.
+    pub fn time_invariant(
+        transition: DMatrix<f64>,
+        observation: DMatrix<f64>,
+        process_noise: DMatrix<f64>,
+        observation_noise: DMatrix<f64>,
+        initial_state: DVector<f64>,
+        initial_covariance: DMatrix<f64>,
+    ) -> Result<Self, RustQuantError> {
+        Self::new(
+            move |_| transition.clone(),
+            move |_| observation.clone(),
+            move |_| process_noise.clone(),
+            move |_| observation_noise.clone(),
+            initial_state,
+            initial_covariance,
+        )
+    }
+
+    /// Run the filter over the observations $y_0, \ldots, y_{n-1}$
+    /// (`None` marks a missing observation, for which the update step is
+    /// skipped).
+    ///
+    /// The covariance update uses the Joseph form, which keeps the filtered
+    /// covariances symmetric positive semi-definite.
+    ///
+    /// # Errors
+    /// Returns an error if a forecast error covariance $S_t$ is not
+    /// positive definite.
+    pub fn filter(
+        &self,
+        observations: &[Option<DVector<f64>>],
+    ) -> Result<KalmanFilterOutput, RustQuantError> {
+        let n = observations.len();
+        let dimension = self.initial_state.len();
+        let identity = DMatrix::<f64>::identity(dimension, dimension);
+
+        let mut output = KalmanFilterOutput {
+            predicted_states: Vec::with_capacity(n),
+            predicted_covariances: Vec::with_capacity(n),
+            filtered_states: Vec::with_capacity(n),
+            filtered_covariances: Vec::with_capacity(n),
+            innovations: Vec::with_capacity(n),
+            innovation_covariances: Vec::with_capacity(n),
+            log_likelihood: 0.0,
+        };
+
+        let mut state = self.initial_state.clone();
+        let mut covariance = self.initial_covariance.clone();
+
+        for (t, y) in observations.iter().enumerate() {
+            // Predict.
+            if t > 0 {
+                let f = (self.transition)(t);
+                state = &f * state;
+                covariance = &f * covariance * f.transpose() + (self.process_noise)(t);
+            }
+
+            output.predicted_states.push(state.clone());
+            output.predicted_covariances.push(covariance.clone());
+
+            // Update.
+            match y {
+                Some(y) => {
+                    let h = (self.observation)(t);
+                    let r = (self.observation_noise)(t);
+
+                    let innovation = y - &h * &state;
+                    let s = &h * &covariance * h.transpose() + &r;
+
+                    let cholesky = s
+                        .clone()
+                        .cholesky()
+                        .ok_or(RustQuantError::MatrixInversionFailed)?;
+
+                    // K = P H' S^{-1}, from S K' = H P.
+                    let gain = cholesky.solve(&(&h * &covariance)).transpose();
+
+                    let log_determinant = 2.0 * cholesky.l().diagonal().map(f64::ln).sum();
+                    let mahalanobis = innovation.dot(&cholesky.solve(&innovation));
+
+                    output.log_likelihood -= 0.5
+                        * (y.len() as f64 * (2.0 * std::f64::consts::PI).ln()
+                            + log_determinant
+                            + mahalanobis);
+
+                    state += &gain * &innovation;
+
+                    let a = &identity - &gain * &h;
+                    covariance = &a * covariance * a.transpose() + &gain * r * gain.transpose();
+
+                    output.innovations.push(Some(innovation));
+                    output.innovation_covariances.push(Some(s));
+                }
+                None => {
+                    output.innovations.push(None);
+                    output.innovation_covariances.push(None);
+                }
+            }
+
+            output.filtered_states.push(state.clone());
+            output.filtered_covariances.push(covariance.clone());
+        }
+
+        Ok(output)
+    }
+
+    /// Rauch-Tung-Striebel smoother: the states given all the observations,
+    /// from the output of [`KalmanFilter::filter`] on the same model.
+    ///
+    /// # Errors
+    /// Returns an error if a predicted covariance $P_{t+1|t}$ is singular.
+    pub fn smooth(
+        &self,
+        filtered: &KalmanFilterOutput,
+    ) -> Result<KalmanSmootherOutput, RustQuantError> {
+        let mut smoothed_states = filtered.filtered_states.clone();
+        let mut smoothed_covariances = filtered.filtered_covariances.clone();
+
+        for t in (0..smoothed_states.len().saturating_sub(1)).rev() {
+            let f = (self.transition)(t + 1);
+
+            let predicted_inverse = filtered.predicted_covariances[t + 1]
+                .clone()
+                .try_inverse()
+                .ok_or(RustQuantError::MatrixInversionFailed)?;
+
+            // Smoother gain J = P_{t|t} F' P_{t+1|t}^{-1}.
+            let gain = &filtered.filtered_covariances[t] * f.transpose() * predicted_inverse;
+
+            let state_correction =
+                &gain * (&smoothed_states[t + 1] - &filtered.predicted_states[t + 1]);
+            let covariance_correction = &gain
+                * (&smoothed_covariances[t + 1] - &filtered.predicted_covariances[t + 1])
+                * gain.transpose();
+
+            smoothed_states[t] += state_correction;
+            smoothed_covariances[t] += covariance_correction;
+        }
+
+        Ok(KalmanSmootherOutput {
+            smoothed_states,
+            smoothed_covariances,
+        })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_kalman {
+    use super::*;
+    use crate::assert_approx_equal;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::StandardNormal;
+
+    // Local level model: x_t = x_{t-1} + w_t, y_t = x_t + v_t.
+    fn local_level(q: f64, r: f64) -> KalmanFilter<'static> {
+        KalmanFilter::time_invariant(
+            DMatrix::identity(1, 1),
+            DMatrix::identity(1, 1),
+            DMatrix::from_element(1, 1, q),
+            DMatrix::from_element(1, 1, r),
+            DVector::from_element(1, 0.0),
+            DMatrix::from_element(1, 1, 10.0),
+        )
+        .unwrap()
+    }
+
+    // Simulated states and observations of the local level model.
+    fn simulate_local_level(q: f64, r: f64, n: usize, seed: u64) -> (Vec<f64>, Vec<f64>) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut x = 0.0;
+
+        (0..n)
+            .map(|_| {
+                x += q.sqrt() * rng.sample::<f64, _>(StandardNormal);
+                (x, x + r.sqrt() * rng.sample::<f64, _>(StandardNormal))
+            })
+            .unzip()
+    }
+
+    fn observations(y: &[f64]) -> Vec<Option<DVector<f64>>> {
+        y.iter()
+            .map(|&y| Some(DVector::from_element(1, y)))
+            .collect()
+    }
+
+    #[test]
+    fn test_local_level_credible_intervals() {
+        let (states, y) = simulate_local_level(0.5, 2.0, 2_000, 170);
+        let output = local_level(0.5, 2.0).filter(&observations(&y)).unwrap();
+
+        // The true state is inside the 95% interval ~95% of the time.
+        let covered = states
+            .iter()
+            .zip(
+                output
+                    .filtered_states
+                    .iter()
+                    .zip(&output.filtered_covariances),
+            )
+            .filter(|(x, (m, p))| (*x - m[0]).abs() < 1.96 * p[(0, 0)].sqrt())
+            .count() as f64
+            / states.len() as f64;
+
+        assert!(covered > 0.93 && covered < 0.97, "coverage: {covered}");
+
+        // Steady-state variance: P = (q + sqrt(q^2 + 4 q r)) / 2 for the
+        // prediction, and P r / (P + r) after the update.
+        let predicted = 0.5 * (0.5 + (0.25_f64 + 4.0 * 0.5 * 2.0).sqrt());
+        let filtered = predicted * 2.0 / (predicted + 2.0);
+        assert_approx_equal!(output.filtered_covariances[1_999][(0, 0)], filtered, 1e-10);
+    }
+
+    #[test]
+    fn test_smoother_beats_filter() {
+        let (states, y) = simulate_local_level(0.5, 2.0, 2_000, 7);
+        let kalman = local_level(0.5, 2.0);
+        let filtered = kalman.filter(&observations(&y)).unwrap();
+        let smoothed = kalman.smooth(&filtered).unwrap();
+
+        let mse = |estimates: &[DVector<f64>]| {
+            states
+                .iter()
+                .zip(estimates)
+                .map(|(x, m)| (x - m[0]).powi(2))
+                .sum::<f64>()
+                / states.len() as f64
+        };
+
+        let (filter_mse, smoother_mse) = (
+            mse(&filtered.filtered_states),
+            mse(&smoothed.smoothed_states),
+        );
+
+        assert!(smoother_mse < 0.8 * filter_mse);
+
+        // The smoothed and filtered estimates agree at the last observation.
+        assert_eq!(
+            smoothed.smoothed_states[1_999],
+            filtered.filtered_states[1_999]
+        );
+        assert!(
+            smoothed.smoothed_covariances[1_000][(0, 0)]
+                < filtered.filtered_covariances[1_000][(0, 0)]
+        );
+    }
+
+    // The local level observations are jointly Gaussian, with mean 0 and
+    // covariance P_0 + q min(i, j) + r 1{i = j}, so the log-likelihood
+    // can be computed directly.
+    fn direct_log_likelihood(y: &[f64], times: &[usize], q: f64, r: f64, p0: f64) -> f64 {
+        let n = y.len();
+        let covariance = DMatrix::from_fn(n, n, |i, j| {
+            p0 + q * times[i].min(times[j]) as f64 + if i == j { r } else { 0.0 }
+        });
+
+        let cholesky = covariance.cholesky().unwrap();
+        let y = DVector::from_column_slice(y);
+        let log_determinant = 2.0 * cholesky.l().diagonal().map(f64::ln).sum();
+
+        -0.5 * (n as f64 * (2.0 * std::f64::consts::PI).ln()
+            + log_determinant
+            + y.dot(&cholesky.solve(&y)))
+    }
+
+    #[test]
+    fn test_log_likelihood() {
+        let y = [0.3, -0.4, 1.2, 2.1, 1.7, 0.9, 2.8, 3.5];
+        let times: Vec<usize> = (0..y.len()).collect();
+
+        let output = local_level(0.5, 2.0).filter(&observations(&y)).unwrap();
+
+        assert_approx_equal!(
+            output.log_likelihood,
+            direct_log_likelihood(&y, &times, 0.5, 2.0, 10.0),
+            1e-10
+        );
+
+        // Missing observations drop out of the likelihood.
+        let mut missing = observations(&y);
+        missing[2] = None;
+        missing[5] = None;
+
+        let output = local_level(0.5, 2.0).filter(&missing).unwrap();
+        let observed = [0, 1, 3, 4, 6, 7];
+
+        assert_approx_equal!(
+            output.log_likelihood,
+            direct_log_likelihood(&observed.map(|t| y[t]), &observed, 0.5, 2.0, 10.0),
+            1e-10
+        );
+        assert!(output.innovations[2].is_none());
+        assert_eq!(output.filtered_states[2], output.predicted_states[2]);
+    }
+
+    #[test]
+    fn test_time_varying_regression() {
+        // y_t = 2 x_t + v_t, with the coefficient as a constant state.
+        let mut rng = StdRng::seed_from_u64(1);
+        let x: Vec<f64> = (0..500).map(|t| (t as f64 / 10.0).sin() + 1.5).collect();
+        let y: Vec<f64> = x
+            .iter()
+            .map(|x| 2.0 * x + 0.1 * rng.sample::<f64, _>(StandardNormal))
+            .collect();
+
+        let kalman = KalmanFilter::new(
+            |_| DMatrix::identity(1, 1),
+            |t| DMatrix::from_element(1, 1, x[t]),
+            |_| DMatrix::zeros(1, 1),
+            |_| DMatrix::from_element(1, 1, 0.01),
+            DVector::zeros(1),
+            DMatrix::from_element(1, 1, 100.0),
+        )
+        .unwrap();
+
+        let output = kalman.filter(&observations(&y)).unwrap();
+
+        assert_approx_equal!(output.filtered_states[499][0], 2.0, 0.01);
+    }
+
+    #[test]
+    fn test_invalid_initial_covariance() {
+        assert!(KalmanFilter::time_invariant(
+            DMatrix::identity(2, 2),
+            DMatrix::identity(1, 2),
+            DMatrix::identity(2, 2),
+            DMatrix::identity(1, 1),
+            DVector::zeros(2),
+            DMatrix::identity(1, 1),
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,21 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! State-space filtering and smoothing.
+//!
+//! - Linear Kalman filter and Rauch-Tung-Striebel smoother, with time-varying
+//!   system matrices, missing observations, and the log-likelihood of the
+//!   observations (for maximum likelihood or EM parameter fitting).
+//!
+//! See [`crate::trading::pairs::kalman_hedge_ratio`] for an application to
+//! time-varying hedge ratios.
+
+/// Linear Kalman filter and smoother.
+pub mod kalman;
+pub use kalman::*;
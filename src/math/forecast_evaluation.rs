@@ -0,0 +1,225 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Forecast evaluation: comparing the accuracy of two forecasts, and
+//! testing the efficiency of a single forecast.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::math::distributions::{Distribution, Gaussian};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Diebold-Mariano test of equal predictive accuracy of two forecasts,
+/// from their errors `e1` and `e2` at forecast horizon `h`, under squared
+/// error loss.
+///
+/// With the loss differential $d_t = e_{1,t}^2 - e_{2,t}^2$, the statistic is
+///
+/// $$
+/// DM = \frac{\bar{d}}{\sqrt{\hat{\sigma}^2_d / n}}, \quad
+/// \hat{\sigma}^2_d = \hat{\gamma}_0 + 2 \sum_{j=1}^{h-1} \left(1 - \frac{j}{h}\right) \hat{\gamma}_j,
+/// $$
+///
+/// the Newey-West (Bartlett kernel) long-run variance with bandwidth
+/// $h - 1$, since $h$-step forecast errors are at most MA($h - 1$).
+/// Under the null $DM \sim N(0, 1)$ asymptotically; a negative statistic
+/// means the first forecast is more accurate.
+///
+/// Returns the statistic and its two-sided p-value.
+///
+/// # Panics
+/// Panics if the error series have different lengths, fewer than two
+/// observations, or `h` is zero.
+#[must_use]
+pub fn diebold_mariano_test(e1: &[f64], e2: &[f64], h: usize) -> (f64, f64) {
+    assert_eq!(
+        e1.len(),
+        e2.len(),
+        "Error series must have the same length."
+    );
+    assert!(e1.len() >= 2, "At least two forecast errors are required.");
+    assert!(h > 0, "Forecast horizon must be positive.");
+
+    let d: Vec<f64> = e1.iter().zip(e2).map(|(a, b)| a * a - b * b).collect();
+    let n = d.len();
+    let mean = d.iter().sum::<f64>() / n as f64;
+
+    let autocovariance = |lag: usize| {
+        d.iter()
+            .zip(&d[lag..])
+            .map(|(a, b)| (a - mean) * (b - mean))
+            .sum::<f64>()
+            / n as f64
+    };
+
+    let long_run_variance = autocovariance(0)
+        + 2.0
+            * (1..h.min(n))
+                .map(|j| (1.0 - j as f64 / h as f64) * autocovariance(j))
+                .sum::<f64>();
+
+    let statistic = mean / (long_run_variance / n as f64).sqrt();
+    let p_value = 2.0 * Gaussian::default().cdf(-statistic.abs());
+
+    (statistic, p_value)
+}
+
+/// Mincer-Zarnowitz regression of the realized values on the forecasts,
+///
+/// $$
+/// y_t = \alpha + \beta \hat{y}_t + \varepsilon_t,
+/// $$
+///
+/// by ordinary least squares. An efficient (unbiased) forecast has
+/// $\alpha = 0$ and $\beta = 1$, and $R^2$ measures its explanatory power.
+///
+/// Returns $(\alpha, \beta, R^2)$.
+///
+/// # Panics
+/// Panics if the series have different lengths, fewer than two
+/// observations, or the forecasts are constant.
+#[must_use]
+pub fn mincer_zarnowitz_regression(actuals: &[f64], forecasts: &[f64]) -> (f64, f64, f64) {
+    assert_eq!(
+        actuals.len(),
+        forecasts.len(),
+        "Actuals and forecasts must have the same length."
+    );
+    assert!(
+        actuals.len() >= 2,
+        "At least two observations are required."
+    );
+
+    let n = actuals.len() as f64;
+    let y_mean = actuals.iter().sum::<f64>() / n;
+    let f_mean = forecasts.iter().sum::<f64>() / n;
+
+    let (mut sff, mut sfy, mut syy) = (0.0, 0.0, 0.0);
+    for (y, f) in actuals.iter().zip(forecasts) {
+        sff += (f - f_mean).powi(2);
+        sfy += (f - f_mean) * (y - y_mean);
+        syy += (y - y_mean).powi(2);
+    }
+
+    assert!(sff > 0.0, "Forecasts must not be constant.");
+
+    let beta = sfy / sff;
+    let alpha = y_mean - beta * f_mean;
+    let r_squared = if syy > 0.0 {
+        sfy * sfy / (sff * syy)
+    } else {
+        1.0
+    };
+
+    (alpha, beta, r_squared)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_forecast_evaluation {
+    use super::*;
+    use crate::assert_approx_equal;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::StandardNormal;
+
+    // MA(h - 1) forecast errors, as for h-step-ahead forecasts.
+    fn ma_errors(n: usize, h: usize, scale: f64, rng: &mut StdRng) -> Vec<f64> {
+        let shocks: Vec<f64> = (0..n + h)
+            .map(|_| scale * rng.sample::<f64, _>(StandardNormal))
+            .collect();
+
+        shocks.windows(h).map(|w| w.iter().sum()).take(n).collect()
+    }
+
+    #[test]
+    fn test_diebold_mariano_equal_accuracy() {
+        let mut rng = StdRng::seed_from_u64(172);
+
+        for h in [1, 3] {
+            let mut rejections = 0;
+
+            for _ in 0..200 {
+                let e1 = ma_errors(500, h, 1.0, &mut rng);
+                let e2 = ma_errors(500, h, 1.0, &mut rng);
+                let (statistic, p_value) = diebold_mariano_test(&e1, &e2, h);
+
+                assert!(statistic.abs() < 5.0);
+                if p_value < 0.05 {
+                    rejections += 1;
+                }
+            }
+
+            // Nominal size 5%: about 10 rejections out of 200.
+            assert!(rejections <= 20, "h = {h}: {rejections} rejections");
+        }
+    }
+
+    #[test]
+    fn test_diebold_mariano_detects_better_forecast() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let e1 = ma_errors(500, 2, 1.0, &mut rng);
+        let e2 = ma_errors(500, 2, 1.3, &mut rng);
+
+        let (statistic, p_value) = diebold_mariano_test(&e1, &e2, 2);
+        assert!(statistic < -3.0);
+        assert!(p_value < 0.01);
+
+        // Swapping the forecasts flips the sign.
+        let (swapped, _) = diebold_mariano_test(&e2, &e1, 2);
+        assert_approx_equal!(swapped, -statistic, 1e-12);
+    }
+
+    #[test]
+    fn test_diebold_mariano_statistic() {
+        // d = [3, -3, 5, -1]: mean 1, gamma_0 = 10, gamma_1 = -8.
+        let e1 = [2.0, 1.0, 3.0, 0.0];
+        let e2 = [1.0, 2.0, 2.0, 1.0];
+
+        let (h1, _) = diebold_mariano_test(&e1, &e2, 1);
+        assert_approx_equal!(h1, 1.0 / (10.0_f64 / 4.0).sqrt(), 1e-12);
+
+        // h = 2: 10 + 2 * 0.5 * (-8) = 2.
+        let (h2, _) = diebold_mariano_test(&e1, &e2, 2);
+        assert_approx_equal!(h2, 1.0 / (2.0_f64 / 4.0).sqrt(), 1e-12);
+    }
+
+    #[test]
+    fn test_mincer_zarnowitz_regression() {
+        let forecasts = [1.0, 2.0, 3.0, 4.0, 5.0];
+        let actuals: Vec<f64> = forecasts.iter().map(|f| 1.0 + 2.0 * f).collect();
+
+        let (alpha, beta, r_squared) = mincer_zarnowitz_regression(&actuals, &forecasts);
+        assert_approx_equal!(alpha, 1.0, 1e-12);
+        assert_approx_equal!(beta, 2.0, 1e-12);
+        assert_approx_equal!(r_squared, 1.0, 1e-12);
+
+        // Conditional expectation forecasts are efficient.
+        let mut rng = StdRng::seed_from_u64(1);
+        let forecasts: Vec<f64> = (0..10_000)
+            .map(|_| rng.sample::<f64, _>(StandardNormal))
+            .collect();
+        let actuals: Vec<f64> = forecasts
+            .iter()
+            .map(|f| f + rng.sample::<f64, _>(StandardNormal))
+            .collect();
+
+        let (alpha, beta, r_squared) = mincer_zarnowitz_regression(&actuals, &forecasts);
+        assert_approx_equal!(alpha, 0.0, 0.05);
+        assert_approx_equal!(beta, 1.0, 0.05);
+        assert_approx_equal!(r_squared, 0.5, 0.02);
+    }
+}
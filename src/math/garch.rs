@@ -0,0 +1,230 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! GARCH(1,1) conditional variance filter.
+//!
+//! For zero-mean returns $r_t = \sigma_t z_t$ with i.i.d. standardized
+//! innovations $z_t$,
+//!
+//! $$
+//! \sigma_t^2 = \omega + \alpha r_{t-1}^2 + \beta \sigma_{t-1}^2,
+//! $$
+//!
+//! which is covariance stationary when $\alpha + \beta < 1$, with
+//! unconditional variance $\omega / (1 - \alpha - \beta)$. The recursion is
+//! started at the unconditional variance.
+//!
+//! Models are compared by the information criteria of
+//! [`crate::ml::model_selection`], from the Gaussian (quasi-)log-likelihood
+//! $-\frac{1}{2} \sum_t [\ln(2 \pi \sigma_t^2) + r_t^2 / \sigma_t^2]$.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use crate::ml::model_selection::{aic, bic};
+use std::f64::consts::PI;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Number of parameters of the model: $\omega$, $\alpha$, and $\beta$.
+const N_PARAMS: usize = 3;
+
+/// GARCH(1,1) model of the conditional variance of returns.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Garch11 {
+    /// Constant $\omega$.
+    pub omega: f64,
+    /// Weight $\alpha$ of the last squared return.
+    pub alpha: f64,
+    /// Weight $\beta$ of the last conditional variance.
+    pub beta: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Garch11 {
+    /// New GARCH(1,1) model.
+    ///
+    /// # Errors
+    /// `InvalidArgument` unless $\omega > 0$, $\alpha, \beta \geq 0$, and
+    /// $\alpha + \beta < 1$.
+    pub fn new(omega: f64, alpha: f64, beta: f64) -> Result<Self, RustQuantError> {
+        if !(omega > 0.0 && alpha >= 0.0 && beta >= 0.0 && alpha + beta < 1.0) {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "GARCH(1,1) needs omega > 0, alpha, beta >= 0 and alpha + beta < 1, \
+                 got omega = {omega}, alpha = {alpha}, beta = {beta}."
+            )));
+        }
+
+        Ok(Self { omega, alpha, beta })
+    }
+
+    /// Unconditional variance $\omega / (1 - \alpha - \beta)$.
+    #[must_use]
+    pub fn unconditional_variance(&self) -> f64 {
+        self.omega / (1.0 - self.alpha - self.beta)
+    }
+
+    /// Conditional variances $\sigma_1^2, \dots, \sigma_n^2$ of the
+    /// returns, each using the returns before it.
+    #[must_use]
+    pub fn conditional_variances(&self, returns: &[f64]) -> Vec<f64> {
+        let mut variance = self.unconditional_variance();
+
+        returns
+            .iter()
+            .map(|r| {
+                let current = variance;
+                variance = self.next_variance(*r, current);
+                current
+            })
+            .collect()
+    }
+
+    /// One-step-ahead variance forecast $\sigma_{n+1}^2$ after the returns.
+    #[must_use]
+    pub fn forecast_variance(&self, returns: &[f64]) -> f64 {
+        returns
+            .iter()
+            .fold(self.unconditional_variance(), |variance, r| {
+                self.next_variance(*r, variance)
+            })
+    }
+
+    /// Standardized residuals $r_t / \sigma_t$.
+    #[must_use]
+    pub fn standardized_residuals(&self, returns: &[f64]) -> Vec<f64> {
+        returns
+            .iter()
+            .zip(self.conditional_variances(returns))
+            .map(|(r, variance)| r / variance.sqrt())
+            .collect()
+    }
+
+    /// Gaussian log-likelihood of the returns.
+    #[must_use]
+    pub fn log_likelihood(&self, returns: &[f64]) -> f64 {
+        -0.5 * returns
+            .iter()
+            .zip(self.conditional_variances(returns))
+            .map(|(r, variance)| (2.0 * PI * variance).ln() + r * r / variance)
+            .sum::<f64>()
+    }
+
+    /// Akaike information criterion of the model on the returns.
+    #[must_use]
+    pub fn aic(&self, returns: &[f64]) -> f64 {
+        aic(self.log_likelihood(returns), N_PARAMS)
+    }
+
+    /// Bayesian information criterion of the model on the returns.
+    #[must_use]
+    pub fn bic(&self, returns: &[f64]) -> f64 {
+        bic(self.log_likelihood(returns), N_PARAMS, returns.len())
+    }
+
+    fn next_variance(&self, r: f64, variance: f64) -> f64 {
+        self.omega + self.alpha * r * r + self.beta * variance
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_garch {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    #[test]
+    fn test_recursion() {
+        let garch = Garch11::new(1e-5, 0.1, 0.85).unwrap();
+        let returns = [0.01, -0.02, 0.005];
+
+        assert_approx_equal!(garch.unconditional_variance(), 2e-4, 1e-15);
+
+        let v0 = 2e-4;
+        let v1 = 1e-5 + 0.1 * 1e-4 + 0.85 * v0;
+        let v2 = 1e-5 + 0.1 * 4e-4 + 0.85 * v1;
+        let v3 = 1e-5 + 0.1 * 2.5e-5 + 0.85 * v2;
+
+        let variances = garch.conditional_variances(&returns);
+        assert_approx_equal!(variances[0], v0, 1e-15);
+        assert_approx_equal!(variances[1], v1, 1e-15);
+        assert_approx_equal!(variances[2], v2, 1e-15);
+        assert_approx_equal!(garch.forecast_variance(&returns), v3, 1e-15);
+
+        let residuals = garch.standardized_residuals(&returns);
+        assert_approx_equal!(residuals[1], -0.02 / v1.sqrt(), 1e-12);
+    }
+
+    #[test]
+    fn test_log_likelihood_and_information_criteria() {
+        let garch = Garch11::new(1e-5, 0.1, 0.85).unwrap();
+        let returns = [0.01, -0.02, 0.005];
+
+        let v0 = 2e-4;
+        let v1 = 1e-5 + 0.1 * 1e-4 + 0.85 * v0;
+        let v2 = 1e-5 + 0.1 * 4e-4 + 0.85 * v1;
+
+        let log_likelihood = -0.5
+            * ((2.0 * PI * v0).ln()
+                + 1e-4 / v0
+                + (2.0 * PI * v1).ln()
+                + 4e-4 / v1
+                + (2.0 * PI * v2).ln()
+                + 2.5e-5 / v2);
+
+        assert_approx_equal!(garch.log_likelihood(&returns), log_likelihood, 1e-12);
+        assert_approx_equal!(garch.aic(&returns), 6.0 - 2.0 * log_likelihood, 1e-12);
+        assert_approx_equal!(
+            garch.bic(&returns),
+            3.0 * 3_f64.ln() - 2.0 * log_likelihood,
+            1e-12
+        );
+    }
+
+    #[test]
+    fn test_information_criteria_prefer_the_true_model() {
+        use rand::{rngs::StdRng, Rng, SeedableRng};
+        use rand_distr::StandardNormal;
+
+        let garch = Garch11::new(2e-6, 0.1, 0.88).unwrap();
+        let mut rng = StdRng::seed_from_u64(192);
+        let mut variance = garch.unconditional_variance();
+
+        let returns: Vec<f64> = (0..2_000)
+            .map(|_| {
+                let r = variance.sqrt() * rng.sample::<f64, _>(StandardNormal);
+                variance = garch.next_variance(r, variance);
+                r
+            })
+            .collect();
+
+        // Constant variance at the same unconditional level.
+        let constant = Garch11::new(garch.unconditional_variance(), 0.0, 0.0).unwrap();
+
+        assert!(garch.aic(&returns) < constant.aic(&returns));
+        assert!(garch.bic(&returns) < constant.bic(&returns));
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        assert!(Garch11::new(0.0, 0.1, 0.8).is_err());
+        assert!(Garch11::new(1e-5, -0.1, 0.8).is_err());
+        assert!(Garch11::new(1e-5, 0.3, 0.7).is_err());
+    }
+}
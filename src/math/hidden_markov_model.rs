@@ -0,0 +1,733 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Hidden Markov models with Gaussian emissions, e.g. for volatility
+//! regime detection on a return series.
+//!
+//! The hidden state $s_t \in \{0, \ldots, K - 1\}$ follows a Markov chain
+//! with transition matrix $A_{ij} = P(s_t = j \mid s_{t-1} = i)$, and the
+//! observation is $x_t \mid s_t = k \sim N(\mu_k, \sigma_k^2)$.
+//!
+//! The forward-backward recursions are computed in log space, so long
+//! series do not underflow. `NaN` observations (nulls, for `Series`) are
+//! treated as missing: they carry no information about the state, and the
+//! outputs stay aligned with the input.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use nalgebra::DMatrix;
+use polars::prelude::*;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Hidden Markov model with Gaussian emissions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GaussianHmm {
+    /// Probabilities of the initial state.
+    pub initial_probabilities: Vec<f64>,
+    /// Transition matrix: row $i$ holds the probabilities of moving from state $i$.
+    pub transition_matrix: DMatrix<f64>,
+    /// Emission means, per state.
+    pub means: Vec<f64>,
+    /// Emission variances, per state.
+    pub variances: Vec<f64>,
+}
+
+/// A model fitted by [`GaussianHmm::fit`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct HmmFit {
+    /// Fitted model. States are ordered by increasing variance.
+    pub model: GaussianHmm,
+    /// Log-likelihood of the data under the fitted model.
+    pub log_likelihood: f64,
+    /// Number of (non-missing) observations.
+    pub n_observations: usize,
+    /// Number of EM iterations.
+    pub iterations: usize,
+    /// Whether the log-likelihood converged within the maximum number of iterations.
+    pub converged: bool,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl GaussianHmm {
+    /// New Gaussian HMM.
+    ///
+    /// # Errors
+    /// Returns an error if the dimensions are inconsistent, the
+    /// probabilities are not valid distributions, or a variance is not
+    /// positive.
+    pub fn new(
+        initial_probabilities: Vec<f64>,
+        transition_matrix: DMatrix<f64>,
+        means: Vec<f64>,
+        variances: Vec<f64>,
+    ) -> Result<Self, RustQuantError> {
+        let k = means.len();
+
+        if k == 0
+            || initial_probabilities.len() != k
+            || variances.len() != k
+            || transition_matrix.shape() != (k, k)
+        {
+            return Err(RustQuantError::InvalidArgument(
+                "Model dimensions must match the number of states.".to_string(),
+            ));
+        }
+
+        let is_distribution =
+            |p: &[f64]| p.iter().all(|p| *p >= 0.0) && (p.iter().sum::<f64>() - 1.0).abs() < 1e-8;
+
+        if !is_distribution(&initial_probabilities)
+            || !transition_matrix
+                .row_iter()
+                .all(|row| is_distribution(&row.iter().copied().collect::<Vec<f64>>()))
+        {
+            return Err(RustQuantError::InvalidArgument(
+                "Initial and transition probabilities must be non-negative and sum to 1."
+                    .to_string(),
+            ));
+        }
+
+        if variances.iter().any(|v| *v <= 0.0) {
+            return Err(RustQuantError::InvalidArgument(
+                "Variances must be positive.".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            initial_probabilities,
+            transition_matrix,
+            means,
+            variances,
+        })
+    }
+
+    /// Fit a model with `n_states` states by Baum-Welch (EM), stopping when
+    /// the log-likelihood improves by less than `tolerance`, or after
+    /// `max_iterations` iterations.
+    ///
+    /// The initial guess splits the observations into `n_states` groups by
+    /// their squared deviation from the mean, and the fitted states are
+    /// ordered by increasing variance (so state 0 is the calmest regime).
+    /// Variances are floored at $10^{-6}$ times the sample variance.
+    ///
+    /// # Errors
+    /// Returns an error if `n_states` is zero, or there are fewer than two
+    /// observations per state.
+    pub fn fit(
+        returns: &[f64],
+        n_states: usize,
+        max_iterations: usize,
+        tolerance: f64,
+    ) -> Result<HmmFit, RustQuantError> {
+        let observed: Vec<f64> = returns.iter().copied().filter(|x| !x.is_nan()).collect();
+
+        if n_states == 0 {
+            return Err(RustQuantError::InvalidArgument(
+                "Number of states must be positive.".to_string(),
+            ));
+        }
+        if observed.len() < 2 * n_states {
+            return Err(RustQuantError::InvalidArgument(format!(
+                "At least {} observations are required.",
+                2 * n_states
+            )));
+        }
+
+        let n = observed.len() as f64;
+        let mean = observed.iter().sum::<f64>() / n;
+        let variance = observed.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+        let variance_floor = 1e-6 * variance;
+
+        let mut model = Self::initial_guess(&observed, n_states, mean, variance_floor);
+
+        let mut previous = f64::NEG_INFINITY;
+        let mut iterations = 0;
+        let mut converged = false;
+
+        loop {
+            let log_emissions = model.log_emissions(returns);
+            let (log_alpha, log_likelihood) = model.forward(&log_emissions);
+
+            if log_likelihood - previous < tolerance {
+                converged = true;
+            }
+            if converged || iterations == max_iterations {
+                model.sort_by_variance();
+
+                return Ok(HmmFit {
+                    model,
+                    log_likelihood,
+                    n_observations: observed.len(),
+                    iterations,
+                    converged,
+                });
+            }
+
+            let log_beta = model.backward(&log_emissions);
+            model.update(
+                returns,
+                &log_emissions,
+                &log_alpha,
+                &log_beta,
+                log_likelihood,
+                variance_floor,
+            );
+
+            previous = log_likelihood;
+            iterations += 1;
+        }
+    }
+
+    /// Number of hidden states.
+    #[must_use]
+    pub fn n_states(&self) -> usize {
+        self.means.len()
+    }
+
+    /// Log-likelihood of the observations.
+    #[must_use]
+    pub fn log_likelihood(&self, returns: &[f64]) -> f64 {
+        self.forward(&self.log_emissions(returns)).1
+    }
+
+    /// Filtered state probabilities $P(s_t = k \mid x_0, \ldots, x_t)$,
+    /// one row per observation.
+    #[must_use]
+    pub fn filtered_probabilities(&self, returns: &[f64]) -> DMatrix<f64> {
+        let (mut probabilities, _) = self.forward(&self.log_emissions(returns));
+
+        for mut row in probabilities.row_iter_mut() {
+            let normalization = log_sum_exp(&row.iter().copied().collect::<Vec<f64>>());
+            row.apply(|p| *p = (*p - normalization).exp());
+        }
+
+        probabilities
+    }
+
+    /// Smoothed state probabilities $P(s_t = k \mid x_0, \ldots, x_{n-1})$,
+    /// one row per observation.
+    #[must_use]
+    pub fn smoothed_probabilities(&self, returns: &[f64]) -> DMatrix<f64> {
+        let log_emissions = self.log_emissions(returns);
+        let (log_alpha, log_likelihood) = self.forward(&log_emissions);
+        let log_beta = self.backward(&log_emissions);
+
+        (log_alpha + log_beta).map(|p| (p - log_likelihood).exp())
+    }
+
+    /// Most likely state path (Viterbi decoding).
+    #[must_use]
+    pub fn viterbi(&self, returns: &[f64]) -> Vec<usize> {
+        let log_emissions = self.log_emissions(returns);
+        let log_transition = self.transition_matrix.map(f64::ln);
+        let (n, k) = log_emissions.shape();
+
+        if n == 0 {
+            return Vec::new();
+        }
+
+        let mut delta: Vec<f64> = (0..k)
+            .map(|j| self.initial_probabilities[j].ln() + log_emissions[(0, j)])
+            .collect();
+        let mut backpointers = vec![vec![0; k]; n];
+
+        for t in 1..n {
+            delta = (0..k)
+                .map(|j| {
+                    let (best, score) = (0..k)
+                        .map(|i| (i, delta[i] + log_transition[(i, j)]))
+                        .max_by(|a, b| a.1.total_cmp(&b.1))
+                        .unwrap_or((0, f64::NEG_INFINITY));
+
+                    backpointers[t][j] = best;
+                    score + log_emissions[(t, j)]
+                })
+                .collect();
+        }
+
+        let mut state = argmax(&delta);
+        let mut path = vec![state; n];
+
+        for t in (1..n).rev() {
+            state = backpointers[t][state];
+            path[t - 1] = state;
+        }
+
+        path
+    }
+
+    /// Filtered (or, if `smoothed`, smoothed) state probabilities of a
+    /// return series, as a `DataFrame` with one column `regime_k` per state
+    /// and one row per element of the series (nulls are missing observations).
+    ///
+    /// # Errors
+    /// Returns an error if the series cannot be cast to `f64`.
+    pub fn regime_probabilities(
+        &self,
+        returns: &Series,
+        smoothed: bool,
+    ) -> Result<DataFrame, RustQuantError> {
+        let values = series_values(returns)?;
+
+        let probabilities = if smoothed {
+            self.smoothed_probabilities(&values)
+        } else {
+            self.filtered_probabilities(&values)
+        };
+
+        let columns = probabilities
+            .column_iter()
+            .enumerate()
+            .map(|(k, column)| Series::new(&format!("regime_{k}"), column.as_slice()))
+            .collect();
+
+        Ok(DataFrame::new(columns)?)
+    }
+
+    /// Most likely state path of a return series, as a `UInt32` series
+    /// named `regime`, aligned with the input (nulls are missing observations).
+    ///
+    /// # Errors
+    /// Returns an error if the series cannot be cast to `f64`.
+    pub fn viterbi_series(&self, returns: &Series) -> Result<Series, RustQuantError> {
+        let path: Vec<u32> = self
+            .viterbi(&series_values(returns)?)
+            .into_iter()
+            .map(|state| state as u32)
+            .collect();
+
+        Ok(Series::new("regime", path))
+    }
+
+    // Groups of observations by squared deviation from the mean, with a
+    // persistent transition matrix.
+    fn initial_guess(observed: &[f64], k: usize, mean: f64, variance_floor: f64) -> Self {
+        let mut sorted = observed.to_vec();
+        sorted.sort_by(|a, b| (a - mean).abs().total_cmp(&(b - mean).abs()));
+
+        let size = sorted.len() / k;
+        let (means, variances) = (0..k)
+            .map(|j| {
+                let group = if j == k - 1 {
+                    &sorted[j * size..]
+                } else {
+                    &sorted[j * size..(j + 1) * size]
+                };
+                let m = group.len() as f64;
+
+                (
+                    group.iter().sum::<f64>() / m,
+                    (group.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / m).max(variance_floor),
+                )
+            })
+            .unzip();
+
+        let transition_matrix = if k == 1 {
+            DMatrix::from_element(1, 1, 1.0)
+        } else {
+            DMatrix::from_fn(k, k, |i, j| if i == j { 0.9 } else { 0.1 / (k - 1) as f64 })
+        };
+
+        Self {
+            initial_probabilities: vec![1.0 / k as f64; k],
+            transition_matrix,
+            means,
+            variances,
+        }
+    }
+
+    // Log emission densities, one row per observation (zero if missing).
+    fn log_emissions(&self, returns: &[f64]) -> DMatrix<f64> {
+        DMatrix::from_fn(returns.len(), self.n_states(), |t, k| {
+            let x = returns[t];
+
+            if x.is_nan() {
+                0.0
+            } else {
+                let variance = self.variances[k];
+                -0.5 * ((2.0 * std::f64::consts::PI * variance).ln()
+                    + (x - self.means[k]).powi(2) / variance)
+            }
+        })
+    }
+
+    // Forward pass: ln P(x_0, ..., x_t, s_t = k), and the log-likelihood.
+    fn forward(&self, log_emissions: &DMatrix<f64>) -> (DMatrix<f64>, f64) {
+        let (n, k) = log_emissions.shape();
+        let log_transition = self.transition_matrix.map(f64::ln);
+        let mut log_alpha = DMatrix::zeros(n, k);
+
+        if n == 0 {
+            return (log_alpha, 0.0);
+        }
+
+        for j in 0..k {
+            log_alpha[(0, j)] = self.initial_probabilities[j].ln() + log_emissions[(0, j)];
+        }
+
+        for t in 1..n {
+            for j in 0..k {
+                let terms: Vec<f64> = (0..k)
+                    .map(|i| log_alpha[(t - 1, i)] + log_transition[(i, j)])
+                    .collect();
+                log_alpha[(t, j)] = log_sum_exp(&terms) + log_emissions[(t, j)];
+            }
+        }
+
+        let log_likelihood =
+            log_sum_exp(&log_alpha.row(n - 1).iter().copied().collect::<Vec<f64>>());
+
+        (log_alpha, log_likelihood)
+    }
+
+    // Backward pass: ln P(x_{t+1}, ..., x_{n-1} | s_t = k).
+    fn backward(&self, log_emissions: &DMatrix<f64>) -> DMatrix<f64> {
+        let (n, k) = log_emissions.shape();
+        let log_transition = self.transition_matrix.map(f64::ln);
+        let mut log_beta = DMatrix::zeros(n, k);
+
+        for t in (0..n.saturating_sub(1)).rev() {
+            for i in 0..k {
+                let terms: Vec<f64> = (0..k)
+                    .map(|j| {
+                        log_transition[(i, j)] + log_emissions[(t + 1, j)] + log_beta[(t + 1, j)]
+                    })
+                    .collect();
+                log_beta[(t, i)] = log_sum_exp(&terms);
+            }
+        }
+
+        log_beta
+    }
+
+    // Baum-Welch (M-step) update from the forward-backward quantities.
+    fn update(
+        &mut self,
+        returns: &[f64],
+        log_emissions: &DMatrix<f64>,
+        log_alpha: &DMatrix<f64>,
+        log_beta: &DMatrix<f64>,
+        log_likelihood: f64,
+        variance_floor: f64,
+    ) {
+        let (n, k) = log_emissions.shape();
+        let log_transition = self.transition_matrix.map(f64::ln);
+
+        let gamma = (log_alpha + log_beta).map(|p| (p - log_likelihood).exp());
+
+        let mut transitions = DMatrix::<f64>::zeros(k, k);
+        for t in 0..n - 1 {
+            for i in 0..k {
+                for j in 0..k {
+                    transitions[(i, j)] += (log_alpha[(t, i)]
+                        + log_transition[(i, j)]
+                        + log_emissions[(t + 1, j)]
+                        + log_beta[(t + 1, j)]
+                        - log_likelihood)
+                        .exp();
+                }
+            }
+        }
+
+        self.initial_probabilities = gamma.row(0).iter().copied().collect();
+
+        for i in 0..k {
+            let total = transitions.row(i).sum();
+            if total > 0.0 {
+                self.transition_matrix
+                    .set_row(i, &(transitions.row(i) / total));
+            }
+
+            let (mut weight, mut sum, mut sum_squares) = (0.0, 0.0, 0.0);
+            for (t, &x) in returns.iter().enumerate().filter(|(_, x)| !x.is_nan()) {
+                weight += gamma[(t, i)];
+                sum += gamma[(t, i)] * x;
+                sum_squares += gamma[(t, i)] * x * x;
+            }
+
+            if weight > 0.0 {
+                let mean = sum / weight;
+                self.means[i] = mean;
+                self.variances[i] = (sum_squares / weight - mean * mean).max(variance_floor);
+            }
+        }
+    }
+
+    // Relabel the states by increasing variance.
+    fn sort_by_variance(&mut self) {
+        let mut order: Vec<usize> = (0..self.n_states()).collect();
+        order.sort_by(|&a, &b| self.variances[a].total_cmp(&self.variances[b]));
+
+        self.initial_probabilities = order
+            .iter()
+            .map(|&i| self.initial_probabilities[i])
+            .collect();
+        self.means = order.iter().map(|&i| self.means[i]).collect();
+        self.variances = order.iter().map(|&i| self.variances[i]).collect();
+        self.transition_matrix = DMatrix::from_fn(order.len(), order.len(), |i, j| {
+            self.transition_matrix[(order[i], order[j])]
+        });
+    }
+}
+
+impl HmmFit {
+    /// Number of free parameters: $(K - 1) + K (K - 1) + 2 K$.
+    #[must_use]
+    pub fn n_parameters(&self) -> usize {
+        let k = self.model.n_states();
+
+        (k - 1) + k * (k - 1) + 2 * k
+    }
+
+    /// Bayesian information criterion: $-2 \ln L + p \ln n$ (lower is better).
+    #[must_use]
+    pub fn bic(&self) -> f64 {
+        crate::ml::bic(
+            self.log_likelihood,
+            self.n_parameters(),
+            self.n_observations,
+        )
+    }
+}
+
+/// Fit models with $1, \ldots,$ `max_states` states (see [`GaussianHmm::fit`]),
+/// and return the one with the lowest BIC.
+///
+/// # Errors
+/// Returns an error if `max_states` is zero, or a fit fails.
+pub fn select_hmm_by_bic(
+    returns: &[f64],
+    max_states: usize,
+    max_iterations: usize,
+    tolerance: f64,
+) -> Result<HmmFit, RustQuantError> {
+    let mut best: Option<HmmFit> = None;
+
+    for n_states in 1..=max_states {
+        let fit = GaussianHmm::fit(returns, n_states, max_iterations, tolerance)?;
+
+        if best.as_ref().is_none_or(|best| fit.bic() < best.bic()) {
+            best = Some(fit);
+        }
+    }
+
+    best.ok_or_else(|| {
+        RustQuantError::InvalidArgument("Maximum number of states must be positive.".to_string())
+    })
+}
+
+/// $\ln \sum_i e^{x_i}$, computed without overflow.
+fn log_sum_exp(values: &[f64]) -> f64 {
+    let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+
+    if max == f64::NEG_INFINITY {
+        return max;
+    }
+
+    max + values.iter().map(|x| (x - max).exp()).sum::<f64>().ln()
+}
+
+fn argmax(values: &[f64]) -> usize {
+    values
+        .iter()
+        .enumerate()
+        .max_by(|a, b| a.1.total_cmp(b.1))
+        .map_or(0, |(i, _)| i)
+}
+
+/// Values of a numeric `Series` as `f64`, with nulls as `NaN`.
+fn series_values(series: &Series) -> Result<Vec<f64>, RustQuantError> {
+    Ok(series
+        .cast(&DataType::Float64)?
+        .f64()?
+        .into_iter()
+        .map(|value| value.unwrap_or(f64::NAN))
+        .collect())
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_hidden_markov_model {
+    use super::*;
+    use crate::assert_approx_equal;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::StandardNormal;
+
+    fn two_regimes() -> GaussianHmm {
+        GaussianHmm::new(
+            vec![0.5, 0.5],
+            DMatrix::from_row_slice(2, 2, &[0.98, 0.02, 0.05, 0.95]),
+            vec![0.0005, -0.001],
+            vec![0.01_f64.powi(2), 0.03_f64.powi(2)],
+        )
+        .unwrap()
+    }
+
+    // Simulated returns and states.
+    fn simulate(model: &GaussianHmm, n: usize, seed: u64) -> (Vec<f64>, Vec<usize>) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut state = 0;
+
+        (0..n)
+            .map(|_| {
+                let z: f64 = rng.sample(StandardNormal);
+                let x = model.means[state] + model.variances[state].sqrt() * z;
+                let current = state;
+
+                let u: f64 = rng.gen();
+                let mut cumulative = 0.0;
+                for j in 0..model.n_states() {
+                    cumulative += model.transition_matrix[(state, j)];
+                    if u < cumulative {
+                        state = j;
+                        break;
+                    }
+                }
+
+                (x, current)
+            })
+            .unzip()
+    }
+
+    #[test]
+    fn test_fit_recovers_two_regimes() {
+        let truth = two_regimes();
+        let (returns, states) = simulate(&truth, 5_000, 171);
+
+        let fit = GaussianHmm::fit(&returns, 2, 500, 1e-8).unwrap();
+        let model = &fit.model;
+
+        assert!(fit.converged);
+        for k in 0..2 {
+            assert_approx_equal!(model.variances[k] / truth.variances[k], 1.0, 0.1);
+            for j in 0..2 {
+                assert_approx_equal!(
+                    model.transition_matrix[(k, j)],
+                    truth.transition_matrix[(k, j)],
+                    0.02
+                );
+            }
+        }
+
+        let decoded = model.viterbi(&returns);
+        let accuracy = decoded.iter().zip(&states).filter(|(a, b)| a == b).count() as f64
+            / states.len() as f64;
+        assert!(accuracy > 0.9, "accuracy: {accuracy}");
+
+        // The maximum likelihood fit is at least as likely as the true model.
+        assert!(fit.log_likelihood >= truth.log_likelihood(&returns));
+    }
+
+    #[test]
+    fn test_log_likelihood_matches_enumeration() {
+        let model = two_regimes();
+        let returns = [0.01, -0.02, 0.005, 0.04, -0.035];
+        let n = returns.len();
+
+        let density = |x: f64, k: usize| {
+            (-(x - model.means[k]).powi(2) / (2.0 * model.variances[k])).exp()
+                / (2.0 * std::f64::consts::PI * model.variances[k]).sqrt()
+        };
+
+        // Sum over all 2^n state paths.
+        let likelihood: f64 = (0..1 << n)
+            .map(|bits: usize| {
+                let path: Vec<usize> = (0..n).map(|t| (bits >> t) & 1).collect();
+
+                (0..n).fold(model.initial_probabilities[path[0]], |p, t| {
+                    let transition = if t == 0 {
+                        1.0
+                    } else {
+                        model.transition_matrix[(path[t - 1], path[t])]
+                    };
+                    p * transition * density(returns[t], path[t])
+                })
+            })
+            .sum();
+
+        assert_approx_equal!(model.log_likelihood(&returns), likelihood.ln(), 1e-10);
+
+        // Smoothed probabilities are distributions.
+        let smoothed = model.smoothed_probabilities(&returns);
+        for row in smoothed.row_iter() {
+            assert_approx_equal!(row.sum(), 1.0, 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_bic_selects_two_states() {
+        let (returns, _) = simulate(&two_regimes(), 3_000, 3);
+        let best = select_hmm_by_bic(&returns, 3, 500, 1e-8).unwrap();
+
+        assert_eq!(best.model.n_states(), 2);
+        assert_eq!(best.n_parameters(), 7);
+    }
+
+    #[test]
+    fn test_series_outputs_are_aligned() {
+        let model = two_regimes();
+        let (returns, _) = simulate(&model, 200, 11);
+
+        let mut values: Vec<Option<f64>> = returns.into_iter().map(Some).collect();
+        values[50] = None;
+        let series = Series::new("returns", values);
+
+        let probabilities = model.regime_probabilities(&series, false).unwrap();
+        assert_eq!(probabilities.shape(), (200, 2));
+        assert_eq!(probabilities.get_column_names(), ["regime_0", "regime_1"]);
+
+        let regime_0 = probabilities.column("regime_0").unwrap().f64().unwrap();
+        let regime_1 = probabilities.column("regime_1").unwrap().f64().unwrap();
+        for (p, q) in regime_0.into_iter().zip(regime_1) {
+            assert_approx_equal!(p.unwrap() + q.unwrap(), 1.0, 1e-12);
+        }
+
+        let regimes = model.viterbi_series(&series).unwrap();
+        assert_eq!(regimes.len(), 200);
+        assert_eq!(regimes.name(), "regime");
+    }
+
+    #[test]
+    fn test_invalid_models() {
+        let transition = DMatrix::from_row_slice(2, 2, &[0.9, 0.1, 0.2, 0.8]);
+
+        assert!(
+            GaussianHmm::new(vec![1.0], transition.clone(), vec![0.0; 2], vec![1.0; 2]).is_err()
+        );
+        assert!(GaussianHmm::new(
+            vec![0.5; 2],
+            transition.clone(),
+            vec![0.0; 2],
+            vec![1.0, 0.0]
+        )
+        .is_err());
+        assert!(GaussianHmm::new(
+            vec![0.5; 2],
+            DMatrix::from_row_slice(2, 2, &[0.9, 0.2, 0.2, 0.8]),
+            vec![0.0; 2],
+            vec![1.0; 2]
+        )
+        .is_err());
+
+        assert!(GaussianHmm::fit(&[0.1, 0.2, 0.3], 2, 100, 1e-8).is_err());
+        assert!(GaussianHmm::fit(&[0.1, 0.2, 0.3], 0, 100, 1e-8).is_err());
+    }
+}
@@ -0,0 +1,389 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Tests for mean reversion and persistence of a return series.
+//!
+//! - Hurst exponent $H$: $H = 0.5$ for uncorrelated returns,
+//!   $H < 0.5$ for anti-persistent (mean-reverting) returns,
+//!   and $H > 0.5$ for persistent (trending) returns. Estimated by the
+//!   rescaled range and the aggregated variance methods.
+//! - Lo-MacKinlay variance ratio test: the variance of $q$-period returns
+//!   is $q$ times that of 1-period returns under the random walk hypothesis.
+//!   A variance ratio below 1 indicates mean reversion.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use crate::math::distributions::{Distribution, Gaussian};
+use statrs::function::gamma::ln_gamma;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Estimate of the Hurst exponent, from a log-log regression.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HurstEstimate {
+    /// Point estimate of the Hurst exponent.
+    pub hurst: f64,
+    /// Standard error of the estimate (from the regression).
+    pub standard_error: f64,
+}
+
+/// Result of a Lo-MacKinlay variance ratio test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VarianceRatioTest {
+    /// Aggregation lag $q$.
+    pub lag: usize,
+    /// Variance ratio $VR(q)$.
+    pub variance_ratio: f64,
+    /// Test statistic under homoskedasticity, $z(q)$.
+    pub z_statistic: f64,
+    /// Heteroskedasticity-robust test statistic, $z^*(q)$.
+    pub robust_z_statistic: f64,
+    /// Two-sided p-value of the robust test statistic.
+    pub p_value: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl HurstEstimate {
+    /// Two-sided confidence interval at the given level (e.g. 0.95).
+    ///
+    /// The regression points are not independent, so the interval is only
+    /// indicative, and tends to be too narrow.
+    ///
+    /// # Panics
+    /// Panics if `level` is not in $(0, 1)$.
+    #[must_use]
+    pub fn confidence_interval(&self, level: f64) -> (f64, f64) {
+        assert!(level > 0.0 && level < 1.0, "Level must be in (0, 1).");
+
+        let z = Gaussian::default().inv_cdf(0.5 + 0.5 * level);
+
+        (
+            self.hurst - z * self.standard_error,
+            self.hurst + z * self.standard_error,
+        )
+    }
+}
+
+/// Hurst exponent by the rescaled range (R/S) method, with the
+/// Anis-Lloyd-Peters small-sample correction.
+///
+/// For window sizes $n = 8, 16, 32, \ldots$ (at least 2 windows each),
+/// the returns are split into non-overlapping windows, and the range of
+/// the cumulative demeaned returns, divided by their standard deviation,
+/// is averaged over the windows. $H$ is $0.5$ plus the slope of
+/// $\ln(R/S)_n - \ln E[(R/S)_n]$ against $\ln n$, where $E[(R/S)_n]$ is the
+/// expected rescaled range of i.i.d. returns (Anis and Lloyd, 1976), so the
+/// estimate is unbiased for uncorrelated returns.
+///
+/// # Errors
+/// `RustQuantError::InvalidArgument` if there are fewer than 32 returns.
+pub fn hurst_rescaled_range(returns: &[f64]) -> Result<HurstEstimate, RustQuantError> {
+    let points: Vec<(f64, f64)> = window_sizes(returns.len(), 8)?
+        .into_iter()
+        .map(|n| {
+            let mean_rs = returns.chunks_exact(n).map(rescaled_range).sum::<f64>()
+                / (returns.len() / n) as f64;
+
+            let n = n as f64;
+            (n.ln(), mean_rs.ln() - expected_rescaled_range(n).ln())
+        })
+        .collect();
+
+    let (slope, standard_error) = regression_slope(&points);
+
+    Ok(HurstEstimate {
+        hurst: 0.5 + slope,
+        standard_error,
+    })
+}
+
+/// Hurst exponent by the aggregated variance method.
+///
+/// For block sizes $m = 2, 4, 8, \ldots$ (at least 8 blocks each), the
+/// variance of the means of non-overlapping blocks of $m$ returns
+/// scales as $m^{2H - 2}$, so $H$ is $1$ plus half the slope of the log
+/// variance against $\ln m$.
+///
+/// # Errors
+/// `RustQuantError::InvalidArgument` if there are fewer than 32 returns.
+pub fn hurst_aggregated_variance(returns: &[f64]) -> Result<HurstEstimate, RustQuantError> {
+    let points: Vec<(f64, f64)> = window_sizes(returns.len() / 4, 2)?
+        .into_iter()
+        .map(|m| {
+            let means: Vec<f64> = returns
+                .chunks_exact(m)
+                .map(|block| block.iter().sum::<f64>() / m as f64)
+                .collect();
+
+            ((m as f64).ln(), sample_variance(&means).ln())
+        })
+        .collect();
+
+    let (slope, standard_error) = regression_slope(&points);
+
+    Ok(HurstEstimate {
+        hurst: 1.0 + 0.5 * slope,
+        standard_error: 0.5 * standard_error,
+    })
+}
+
+/// Lo-MacKinlay (1988) variance ratio test, with overlapping $q$-period
+/// returns and bias-corrected variances.
+///
+/// $VR(q) = \sigma_c^2(q) / \sigma_a^2$, the ratio of the variance of
+/// $q$-period returns (divided by $q$) to that of 1-period returns.
+/// Under the random walk hypothesis, $VR(q) = 1$, and both test statistics
+/// are asymptotically standard normal. The robust statistic $z^*(q)$ allows
+/// for conditional heteroskedasticity (e.g. volatility clustering).
+///
+/// # Errors
+/// `RustQuantError::InvalidArgument` if `lag` is less than 2, or there are
+/// fewer than `2 * lag` returns.
+pub fn variance_ratio_test(
+    returns: &[f64],
+    lag: usize,
+) -> Result<VarianceRatioTest, RustQuantError> {
+    let q = lag;
+    let T = returns.len();
+
+    if q < 2 || T < 2 * q {
+        return Err(RustQuantError::InvalidArgument(format!(
+            "Variance ratio test needs a lag of at least 2, and at least twice as many returns ({T}) as the lag ({q})."
+        )));
+    }
+
+    let (q_f, T_f) = (q as f64, T as f64);
+    let mu = returns.iter().sum::<f64>() / T_f;
+    let deviations: Vec<f64> = returns.iter().map(|x| x - mu).collect();
+
+    let sum_squares: f64 = deviations.iter().map(|d| d * d).sum();
+    let sigma_a = sum_squares / (T_f - 1.0);
+
+    let m = q_f * (T_f - q_f + 1.0) * (1.0 - q_f / T_f);
+    let sigma_c = deviations
+        .windows(q)
+        .map(|window| window.iter().sum::<f64>().powi(2))
+        .sum::<f64>()
+        / m;
+
+    let variance_ratio = sigma_c / sigma_a;
+
+    // Asymptotic variance of VR(q) under homoskedasticity.
+    let phi = 2.0 * (2.0 * q_f - 1.0) * (q_f - 1.0) / (3.0 * q_f * T_f);
+
+    // Heteroskedasticity-consistent asymptotic variance.
+    let theta: f64 = (1..q)
+        .map(|j| {
+            let delta = deviations
+                .iter()
+                .zip(&deviations[j..])
+                .map(|(a, b)| a * a * b * b)
+                .sum::<f64>()
+                / sum_squares.powi(2);
+
+            (2.0 * (q - j) as f64 / q_f).powi(2) * delta
+        })
+        .sum();
+
+    let z_statistic = (variance_ratio - 1.0) / phi.sqrt();
+    let robust_z_statistic = (variance_ratio - 1.0) / theta.sqrt();
+    let p_value = 2.0 * Gaussian::default().cdf(-robust_z_statistic.abs());
+
+    Ok(VarianceRatioTest {
+        lag,
+        variance_ratio,
+        z_statistic,
+        robust_z_statistic,
+        p_value,
+    })
+}
+
+/// Powers of two from `smallest` up to `n / 2`.
+fn window_sizes(n: usize, smallest: usize) -> Result<Vec<usize>, RustQuantError> {
+    let sizes: Vec<usize> = std::iter::successors(Some(smallest), |size| Some(size * 2))
+        .take_while(|size| 2 * size <= n)
+        .collect();
+
+    if sizes.len() < 2 {
+        return Err(RustQuantError::InvalidArgument(
+            "At least 32 returns are needed to estimate the Hurst exponent.".to_string(),
+        ));
+    }
+
+    Ok(sizes)
+}
+
+/// Range of the cumulative demeaned values, divided by their standard deviation.
+fn rescaled_range(x: &[f64]) -> f64 {
+    let n = x.len() as f64;
+    let mean = x.iter().sum::<f64>() / n;
+    let std_dev = (x.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n).sqrt();
+
+    let (mut cumulative, mut min, mut max) = (0.0, 0.0_f64, 0.0_f64);
+    for x in x {
+        cumulative += x - mean;
+        min = min.min(cumulative);
+        max = max.max(cumulative);
+    }
+
+    (max - min) / std_dev
+}
+
+/// Anis-Lloyd-Peters expected rescaled range of `n` i.i.d. observations.
+fn expected_rescaled_range(n: f64) -> f64 {
+    let sum: f64 = (1..n as usize)
+        .map(|i| ((n - i as f64) / i as f64).sqrt())
+        .sum();
+
+    // Gamma((n - 1) / 2) / (sqrt(pi) Gamma(n / 2)), which tends to sqrt(2 / (pi n)).
+    let ratio = if n <= 340.0 {
+        (ln_gamma(0.5 * (n - 1.0)) - ln_gamma(0.5 * n)).exp() / std::f64::consts::PI.sqrt()
+    } else {
+        (2.0 / (std::f64::consts::PI * n)).sqrt()
+    };
+
+    (n - 0.5) / n * ratio * sum
+}
+
+/// Ordinary least squares slope of `(x, y)` points, and its standard error.
+fn regression_slope(points: &[(f64, f64)]) -> (f64, f64) {
+    let n = points.len() as f64;
+    let x_mean = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let y_mean = points.iter().map(|p| p.1).sum::<f64>() / n;
+
+    let sxx: f64 = points.iter().map(|p| (p.0 - x_mean).powi(2)).sum();
+    let sxy: f64 = points.iter().map(|p| (p.0 - x_mean) * (p.1 - y_mean)).sum();
+    let slope = sxy / sxx;
+
+    let residuals: f64 = points
+        .iter()
+        .map(|p| (p.1 - y_mean - slope * (p.0 - x_mean)).powi(2))
+        .sum();
+
+    let standard_error = if points.len() > 2 {
+        (residuals / (n - 2.0) / sxx).sqrt()
+    } else {
+        f64::NAN
+    };
+
+    (slope, standard_error)
+}
+
+fn sample_variance(x: &[f64]) -> f64 {
+    let n = x.len() as f64;
+    let mean = x.iter().sum::<f64>() / n;
+
+    x.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_mean_reversion {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::models::FractionalBrownianMotion;
+    use crate::stochastics::fractional_brownian_motion::FractionalProcessGeneratorMethod;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::StandardNormal;
+
+    fn white_noise(n: usize, seed: u64) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        (0..n).map(|_| rng.sample(StandardNormal)).collect()
+    }
+
+    fn ar1(phi: f64, n: usize, seed: u64) -> Vec<f64> {
+        let mut x = 0.0;
+
+        white_noise(n, seed)
+            .into_iter()
+            .map(|e| {
+                x = phi * x + e;
+                x
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_hurst_of_white_noise() {
+        let returns = white_noise(16_384, 1);
+
+        let rs = hurst_rescaled_range(&returns).unwrap();
+        let av = hurst_aggregated_variance(&returns).unwrap();
+
+        assert_approx_equal!(rs.hurst, 0.5, 0.05);
+        assert_approx_equal!(av.hurst, 0.5, 0.05);
+
+        let (lower, upper) = rs.confidence_interval(0.95);
+        assert!(lower < rs.hurst && rs.hurst < upper);
+    }
+
+    #[test]
+    fn test_hurst_of_persistent_process() {
+        // Fractional Gaussian noise with H = 0.8.
+        let fbm = FractionalBrownianMotion::new(0.8, FractionalProcessGeneratorMethod::CHOLESKY);
+        let returns = fbm.seedable_fgn_cholesky(1_024, 1.0, 2);
+
+        assert!(hurst_rescaled_range(&returns).unwrap().hurst > 0.6);
+        assert!(hurst_aggregated_variance(&returns).unwrap().hurst > 0.6);
+    }
+
+    #[test]
+    fn test_hurst_of_mean_reverting_process() {
+        // Differences of white noise are strongly anti-persistent.
+        let noise = white_noise(8_193, 3);
+        let returns: Vec<f64> = noise.windows(2).map(|w| w[1] - w[0]).collect();
+
+        assert!(hurst_rescaled_range(&returns).unwrap().hurst < 0.4);
+        assert!(hurst_aggregated_variance(&returns).unwrap().hurst < 0.4);
+    }
+
+    #[test]
+    fn test_variance_ratio_of_white_noise() {
+        let returns = white_noise(5_000, 4);
+
+        for lag in [2, 5, 10] {
+            let test = variance_ratio_test(&returns, lag).unwrap();
+
+            assert_eq!(test.lag, lag);
+            assert_approx_equal!(test.variance_ratio, 1.0, 0.1);
+            assert!(test.p_value > 0.01);
+        }
+    }
+
+    #[test]
+    fn test_variance_ratio_of_negative_ar1() {
+        // VR(2) = 1 + phi for an AR(1).
+        let returns = ar1(-0.3, 5_000, 5);
+        let test = variance_ratio_test(&returns, 2).unwrap();
+
+        assert_approx_equal!(test.variance_ratio, 0.7, 0.05);
+        assert!(test.robust_z_statistic < -10.0);
+        assert!(test.z_statistic < -10.0);
+        assert!(test.p_value < 1e-6);
+    }
+
+    #[test]
+    fn test_invalid_inputs() {
+        assert!(hurst_rescaled_range(&[0.1; 16]).is_err());
+        assert!(variance_ratio_test(&[0.1, -0.1, 0.2], 2).is_err());
+        assert!(variance_ratio_test(&white_noise(100, 6), 1).is_err());
+    }
+}
@@ -62,6 +62,18 @@
 //!
 //! - [x] Risk-Reward Measures (Sharpe, Treynor, Sortino, etc)
 
+/// ARIMA(p, d, q) time series models.
+pub mod arima;
+pub use arima::*;
+
+/// Unit root and cointegration tests (ADF, Engle-Granger, Johansen).
+pub mod cointegration;
+pub use cointegration::*;
+
+/// Incremental covariance estimators (EWMA and rolling window).
+pub mod covariance;
+pub use covariance::*;
+
 /// Statistical distributions.
 pub mod distributions;
 pub use distributions::*;
@@ -79,10 +91,40 @@ pub use optimization::*;
 pub mod fft;
 pub use fft::*;
 
+/// State-space filtering: Kalman filter and smoother.
+pub mod filters;
+pub use filters::*;
+
+/// Forecast evaluation: Diebold-Mariano and Mincer-Zarnowitz tests.
+pub mod forecast_evaluation;
+pub use forecast_evaluation::*;
+
+pub mod garch;
+pub use garch::*;
+
+/// Gaussian hidden Markov models for regime detection.
+pub mod hidden_markov_model;
+pub use hidden_markov_model::*;
+
 /// Interpolation routines.
 pub mod interpolation;
 pub use interpolation::*;
 
+/// Hurst exponent and variance ratio tests for mean reversion.
+pub mod mean_reversion;
+pub use mean_reversion::*;
+
+pub mod monte_carlo;
+pub use monte_carlo::*;
+
+/// Newey-West HAC standard errors for regressions.
+pub mod newey_west;
+pub use newey_west::*;
+
+/// Realized and range-based volatility estimators from intraday bars.
+pub mod realized_volatility;
+pub use realized_volatility::*;
+
 /// Simple risk/reward measures.
 pub mod risk_reward;
 pub use risk_reward::*;
@@ -95,6 +137,26 @@ pub use rootfinding::*;
 pub mod sequences;
 pub use sequences::*;
 
+/// Signal processing: periodograms and wavelet denoising.
+pub mod signal;
+pub use signal::*;
+
+/// Shared simulation settings and deterministic seeding.
+pub mod simulation_config;
+pub use simulation_config::*;
+
 /// Statistic trait.
 pub mod statistic;
 pub use statistic::*;
+
+/// Streaming descriptive statistics with parallel merging.
+pub mod streaming_statistics;
+pub use streaming_statistics::*;
+
+/// Survival analysis: Kaplan-Meier, log-rank test, and Weibull AFT regression.
+pub mod survival;
+pub use survival::*;
+
+/// t-digest approximate quantiles.
+pub mod t_digest;
+pub use t_digest::*;
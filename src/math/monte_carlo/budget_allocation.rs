@@ -0,0 +1,146 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Allocation of a Monte Carlo path budget across several estimates.
+//!
+//! With $N_i$ paths, an estimate whose samples have standard deviation
+//! $\sigma_i$ has standard error $\sigma_i / \sqrt{N_i}$. Minimising the sum
+//! of the squared errors relative to their targets $\epsilon_i$,
+//! $\sum_i \sigma_i^2 / (N_i \epsilon_i^2)$, subject to a total budget
+//! $\sum_i N_i = N$ gives
+//!
+//! $$
+//! N_i = N \frac{\sigma_i / \epsilon_i}{\sum_j \sigma_j / \epsilon_j}.
+//! $$
+//!
+//! The variances are usually estimated from a small pilot run.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Number of paths for each estimate, proportional to $\sigma_i / \epsilon_i$
+/// for the target standard errors $\epsilon_i$ and the sample variances
+/// $\sigma_i^2$.
+///
+/// The allocation is rounded by largest remainder, so it always sums to
+/// `total_budget`. If every variance is zero the budget is split evenly.
+///
+/// # Panics
+/// Panics if the slices have different lengths or are empty, a target
+/// error is not positive, or a variance is negative.
+#[must_use]
+pub fn allocate_budget(
+    target_errors: &[f64],
+    variance_estimates: &[f64],
+    total_budget: usize,
+) -> Vec<usize> {
+    assert_eq!(
+        target_errors.len(),
+        variance_estimates.len(),
+        "Need one target error per variance estimate."
+    );
+    assert!(!target_errors.is_empty(), "Need at least one estimate.");
+    assert!(
+        target_errors.iter().all(|&e| e > 0.0),
+        "Target errors must be positive."
+    );
+    assert!(
+        variance_estimates.iter().all(|&v| v >= 0.0),
+        "Variances must be non-negative."
+    );
+
+    let mut weights: Vec<f64> = target_errors
+        .iter()
+        .zip(variance_estimates)
+        .map(|(e, v)| v.sqrt() / e)
+        .collect();
+
+    if weights.iter().all(|&w| w == 0.0) {
+        weights.fill(1.0);
+    }
+
+    let total_weight: f64 = weights.iter().sum();
+    let shares: Vec<f64> = weights
+        .iter()
+        .map(|w| total_budget as f64 * w / total_weight)
+        .collect();
+
+    let mut allocation: Vec<usize> = shares.iter().map(|s| s.floor() as usize).collect();
+    let allocated: usize = allocation.iter().sum();
+
+    // Hand out the remaining paths to the largest fractional parts.
+    let mut order: Vec<usize> = (0..shares.len()).collect();
+    order.sort_by(|&i, &j| {
+        (shares[j] - shares[j].floor()).total_cmp(&(shares[i] - shares[i].floor()))
+    });
+
+    for &i in order
+        .iter()
+        .cycle()
+        .take(total_budget.saturating_sub(allocated))
+    {
+        allocation[i] += 1;
+    }
+
+    allocation
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_budget_allocation {
+    use super::*;
+
+    #[test]
+    fn test_allocation_sums_to_budget() {
+        let targets = [0.01, 0.02, 0.05, 0.01];
+        let variances = [1.3, 0.7, 4.1, 0.2];
+
+        for budget in [0, 1, 7, 999, 100_003] {
+            let allocation = allocate_budget(&targets, &variances, budget);
+
+            assert_eq!(allocation.iter().sum::<usize>(), budget);
+        }
+    }
+
+    #[test]
+    fn test_allocation_is_proportional() {
+        // sigma / epsilon = 1 / 0.1, 2 / 0.1, 2 / 0.2 = 10, 20, 10.
+        let allocation = allocate_budget(&[0.1, 0.1, 0.2], &[1.0, 4.0, 4.0], 40_000);
+
+        assert_eq!(allocation, vec![10_000, 20_000, 10_000]);
+    }
+
+    #[test]
+    fn test_higher_variance_gets_more_paths() {
+        let allocation = allocate_budget(&[0.01; 3], &[0.5, 2.0, 8.0], 10_000);
+
+        assert!(allocation[0] < allocation[1] && allocation[1] < allocation[2]);
+
+        // Tighter targets also need more paths.
+        let allocation = allocate_budget(&[0.01, 0.001], &[1.0, 1.0], 10_000);
+
+        assert!(allocation[1] > allocation[0]);
+    }
+
+    #[test]
+    fn test_zero_variance() {
+        // Deterministic estimates need no paths, unless all are.
+        assert_eq!(allocate_budget(&[0.1, 0.1], &[0.0, 1.0], 100), vec![0, 100]);
+        assert_eq!(
+            allocate_budget(&[0.1, 0.1], &[0.0, 0.0], 101)
+                .iter()
+                .sum::<usize>(),
+            101
+        );
+    }
+}
@@ -0,0 +1,260 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Convergence diagnostics for Monte Carlo estimates.
+//!
+//! - [`running_mean`] traces the estimate as the sample grows.
+//! - [`confidence_interval`] is the central limit theorem interval
+//!   $\bar{x} \pm z_{1 - \alpha / 2} \, s / \sqrt{n}$, valid for
+//!   independent payoffs with finite variance.
+//! - [`effective_sample_size`] corrects the sample size for
+//!   autocorrelation (e.g. of Markov chain samples), as
+//!   $n / (1 + 2 \sum_{k \geq 1} \rho_k)$.
+//! - [`gelman_rubin_statistic`] compares the variance between and within
+//!   several chains (Gelman and Rubin, 1992): values close to 1 indicate
+//!   that the chains have forgotten their starting points.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::math::distributions::{Distribution, Gaussian};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Mean of the first $i + 1$ payoffs, for each $i$.
+#[must_use]
+pub fn running_mean(payoffs: &[f64]) -> Vec<f64> {
+    let mut sum = 0.0;
+
+    payoffs
+        .iter()
+        .enumerate()
+        .map(|(i, x)| {
+            sum += x;
+            sum / (i + 1) as f64
+        })
+        .collect()
+}
+
+/// Two-sided confidence interval for the mean of `payoffs`, at confidence
+/// level $1 - \alpha$ (so `alpha = 0.05` gives a 95% interval), from the
+/// central limit theorem.
+///
+/// # Panics
+/// Panics if there are fewer than two payoffs, or `alpha` is not in
+/// $(0, 1)$.
+#[must_use]
+pub fn confidence_interval(payoffs: &[f64], alpha: f64) -> (f64, f64) {
+    assert!(payoffs.len() >= 2, "Need at least two payoffs.");
+    assert!(0.0 < alpha && alpha < 1.0, "Alpha must be in (0, 1).");
+
+    let (mean, variance) = mean_and_variance(payoffs);
+    let z = Gaussian::default().inv_cdf(1.0 - 0.5 * alpha);
+    let half_width = z * (variance / payoffs.len() as f64).sqrt();
+
+    (mean - half_width, mean + half_width)
+}
+
+/// Effective sample size of `payoffs`, $n / \tau$, with integrated
+/// autocorrelation time $\tau = 1 + 2 \sum_{k \geq 1} \rho_k$.
+///
+/// The sum of the sample autocorrelations is truncated by Geyer's (1992)
+/// initial positive sequence: it stops at the first pair
+/// $\rho_{2m} + \rho_{2m+1}$ that is not positive, where the estimates
+/// are dominated by noise. The result is at most $n$; constant payoffs
+/// give $n$.
+///
+/// # Panics
+/// Panics if `payoffs` is empty.
+#[must_use]
+pub fn effective_sample_size(payoffs: &[f64]) -> f64 {
+    assert!(!payoffs.is_empty(), "Need at least one payoff.");
+
+    let n = payoffs.len();
+    let mean = payoffs.iter().sum::<f64>() / n as f64;
+    let autocovariance = |lag: usize| {
+        payoffs
+            .iter()
+            .zip(&payoffs[lag..])
+            .map(|(a, b)| (a - mean) * (b - mean))
+            .sum::<f64>()
+            / n as f64
+    };
+
+    let variance = autocovariance(0);
+    if variance == 0.0 {
+        return n as f64;
+    }
+
+    // Sum of the pairs (rho_0 + rho_1), (rho_2 + rho_3), ...
+    let mut sum = 0.0;
+    let mut m = 0;
+    while 2 * m + 1 < n {
+        let pair = (autocovariance(2 * m) + autocovariance(2 * m + 1)) / variance;
+        if pair <= 0.0 {
+            break;
+        }
+        sum += pair;
+        m += 1;
+    }
+
+    // tau = 2 * sum - rho_0, which is at least 1 / n.
+    let tau = (2.0 * sum - 1.0).max(1.0 / n as f64);
+
+    (n as f64 / tau).min(n as f64)
+}
+
+/// Gelman-Rubin potential scale reduction factor $\hat{R}$ of `chains`.
+///
+/// With $m$ chains of length $n$, mean within-chain variance $W$, and
+/// between-chain variance $B$ ($n$ times the variance of the chain means),
+/// $$
+/// \hat{R} = \sqrt{\frac{\frac{n - 1}{n} W + \frac{1}{n} B}{W}}.
+/// $$
+/// It approaches 1 from above as the chains converge; values above about
+/// 1.1 suggest running the chains for longer.
+///
+/// # Panics
+/// Panics if there are fewer than two chains, or the chains have different
+/// lengths or fewer than two samples each.
+#[must_use]
+pub fn gelman_rubin_statistic(chains: &[Vec<f64>]) -> f64 {
+    assert!(chains.len() >= 2, "Need at least two chains.");
+    let n = chains[0].len();
+    assert!(n >= 2, "Need at least two samples per chain.");
+    assert!(
+        chains.iter().all(|chain| chain.len() == n),
+        "Chains must have the same length."
+    );
+
+    let m = chains.len() as f64;
+    let n = n as f64;
+
+    let moments: Vec<(f64, f64)> = chains
+        .iter()
+        .map(|chain| mean_and_variance(chain))
+        .collect();
+    let grand_mean = moments.iter().map(|(mean, _)| mean).sum::<f64>() / m;
+
+    let within = moments.iter().map(|(_, variance)| variance).sum::<f64>() / m;
+    let between = n * moments
+        .iter()
+        .map(|(mean, _)| (mean - grand_mean).powi(2))
+        .sum::<f64>()
+        / (m - 1.0);
+
+    let pooled = (n - 1.0) / n * within + between / n;
+
+    (pooled / within).sqrt()
+}
+
+/// Sample mean and (unbiased) variance.
+fn mean_and_variance(x: &[f64]) -> (f64, f64) {
+    let n = x.len() as f64;
+    let mean = x.iter().sum::<f64>() / n;
+    let variance = x.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+
+    (mean, variance)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_convergence {
+    use super::*;
+    use crate::assert_approx_equal;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::{Exp1, StandardNormal};
+
+    /// AR(1) chain $x_{t+1} = \phi x_t + \varepsilon_{t+1}$ started at `x0`.
+    fn ar1(phi: f64, x0: f64, n: usize, rng: &mut StdRng) -> Vec<f64> {
+        let mut x = x0;
+
+        (0..n)
+            .map(|_| {
+                x = phi * x + rng.sample::<f64, _>(StandardNormal);
+                x
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_running_mean() {
+        assert_eq!(
+            running_mean(&[2.0, 4.0, 0.0, 6.0]),
+            vec![2.0, 3.0, 2.0, 3.0]
+        );
+        assert!(running_mean(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_confidence_interval_coverage() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let (mut gaussian_hits, mut exponential_hits) = (0, 0);
+        let trials = 2000;
+
+        for _ in 0..trials {
+            let gaussian: Vec<f64> = (0..200).map(|_| rng.sample(StandardNormal)).collect();
+            let (lower, upper) = confidence_interval(&gaussian, 0.05);
+            gaussian_hits += usize::from(lower <= 0.0 && 0.0 <= upper);
+
+            let exponential: Vec<f64> = (0..200).map(|_| rng.sample(Exp1)).collect();
+            let (lower, upper) = confidence_interval(&exponential, 0.05);
+            exponential_hits += usize::from(lower <= 1.0 && 1.0 <= upper);
+        }
+
+        assert_approx_equal!(gaussian_hits as f64 / trials as f64, 0.95, 0.015);
+        assert_approx_equal!(exponential_hits as f64 / trials as f64, 0.95, 0.015);
+    }
+
+    #[test]
+    fn test_effective_sample_size() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let n = 10_000;
+
+        let iid: Vec<f64> = (0..n).map(|_| rng.sample(StandardNormal)).collect();
+        assert!(effective_sample_size(&iid) > 0.9 * n as f64);
+
+        // For an AR(1) chain, tau = (1 + phi) / (1 - phi) = 3.
+        let chain = ar1(0.5, 0.0, n, &mut rng);
+        assert_approx_equal!(
+            effective_sample_size(&chain),
+            n as f64 / 3.0,
+            0.1 * n as f64 / 3.0
+        );
+
+        assert_eq!(effective_sample_size(&[1.0; 10]), 10.0);
+    }
+
+    #[test]
+    fn test_gelman_rubin_approaches_one() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let chains: Vec<Vec<f64>> = [-20.0, -10.0, 10.0, 20.0]
+            .iter()
+            .map(|x0| ar1(0.95, *x0, 20_000, &mut rng))
+            .collect();
+
+        let statistics: Vec<f64> = [50, 200, 1000, 5000, 20_000]
+            .iter()
+            .map(|n| {
+                let prefixes: Vec<Vec<f64>> = chains.iter().map(|c| c[..*n].to_vec()).collect();
+                gelman_rubin_statistic(&prefixes)
+            })
+            .collect();
+
+        assert!(statistics[0] > 1.1);
+        assert!(statistics.windows(2).all(|w| w[1] < w[0]));
+        assert!(statistics[4] < 1.01);
+    }
+}
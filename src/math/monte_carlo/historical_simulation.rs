@@ -0,0 +1,305 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Scenario generation by historical simulation.
+//!
+//! - [`bootstrap_scenarios`] resamples historical return vectors with the
+//!   stationary block bootstrap of Politis and Romano (1994): blocks start
+//!   at uniformly random dates and have geometrically distributed lengths
+//!   with mean `block_size`, wrapping around the end of the sample. The
+//!   scenarios form a sequence that keeps the serial dependence of the
+//!   data within blocks, while each scenario is marginally a uniform draw
+//!   from the history.
+//! - [`filtered_historical_simulation`] (Barone-Adesi, Giannopoulos and
+//!   Vosper, 1999) divides the returns by their GARCH(1,1) volatilities,
+//!   resamples the standardized residuals, and rescales them by the
+//!   forecast volatility, so the scenarios reflect current rather than
+//!   average market conditions.
+//!
+//! [`bootstrap_scenarios_with_progress`] reports progress and can be
+//! cancelled through a [`ProgressHandle`].
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::math::Garch11;
+use crate::progress::{Outcome, ProgressHandle};
+use nalgebra::DVector;
+use rand::Rng;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Sequence of `n_scenarios` return vectors drawn from `historical_returns`
+/// (one vector of asset returns per date) with the stationary block
+/// bootstrap, with mean block length `block_size`.
+///
+/// A `block_size` of one resamples dates independently.
+///
+/// # Panics
+/// Panics if `historical_returns` is empty or `block_size` is zero.
+pub fn bootstrap_scenarios(
+    historical_returns: &[DVector<f64>],
+    n_scenarios: usize,
+    block_size: usize,
+    rng: &mut impl Rng,
+) -> Vec<DVector<f64>> {
+    bootstrap_scenarios_with_progress(
+        historical_returns,
+        n_scenarios,
+        block_size,
+        rng,
+        &mut ProgressHandle::new(),
+    )
+    .into_inner()
+}
+
+/// [`bootstrap_scenarios`], reporting the number of scenarios drawn to
+/// `progress` (without an estimate).
+///
+/// If the cancellation token is set, returns the scenarios drawn so far as
+/// [`Outcome::Cancelled`].
+///
+/// # Panics
+/// Panics if `historical_returns` is empty or `block_size` is zero.
+pub fn bootstrap_scenarios_with_progress(
+    historical_returns: &[DVector<f64>],
+    n_scenarios: usize,
+    block_size: usize,
+    rng: &mut impl Rng,
+    progress: &mut ProgressHandle,
+) -> Outcome<Vec<DVector<f64>>> {
+    let mut scenarios = Vec::with_capacity(n_scenarios);
+
+    for i in stationary_bootstrap_indices(historical_returns.len(), n_scenarios, block_size, rng) {
+        if progress.is_cancelled() {
+            return Outcome::Cancelled(scenarios);
+        }
+
+        scenarios.push(historical_returns[i].clone());
+        progress.report(scenarios.len(), n_scenarios, None);
+    }
+
+    Outcome::Completed(scenarios)
+}
+
+/// `n_scenarios` one-period-ahead returns by filtered historical
+/// simulation: standardized residuals $r_t / \sigma_t$ of the `returns`
+/// under `garch_model`, resampled independently and multiplied by the
+/// forecast volatility $\sigma_{n+1}$.
+///
+/// # Panics
+/// Panics if `returns` is empty.
+pub fn filtered_historical_simulation(
+    returns: &[f64],
+    garch_model: &Garch11,
+    n_scenarios: usize,
+    rng: &mut impl Rng,
+) -> Vec<f64> {
+    assert!(!returns.is_empty(), "At least one return is required.");
+
+    let residuals = garch_model.standardized_residuals(returns);
+    let volatility = garch_model.forecast_variance(returns).sqrt();
+
+    (0..n_scenarios)
+        .map(|_| volatility * residuals[rng.gen_range(0..residuals.len())])
+        .collect()
+}
+
+/// Indices into a sample of size `n` from the stationary block bootstrap,
+/// drawn lazily.
+fn stationary_bootstrap_indices<'a, R: Rng>(
+    n: usize,
+    n_scenarios: usize,
+    block_size: usize,
+    rng: &'a mut R,
+) -> impl Iterator<Item = usize> + 'a {
+    assert!(n > 0, "At least one historical observation is required.");
+    assert!(block_size > 0, "The block size must be positive.");
+
+    let restart_probability = 1.0 / block_size as f64;
+    let mut index = rng.gen_range(0..n);
+
+    (0..n_scenarios).map(move |k| {
+        if k > 0 {
+            index = if rng.gen::<f64>() < restart_probability {
+                rng.gen_range(0..n)
+            } else {
+                (index + 1) % n
+            };
+        }
+        index
+    })
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_historical_simulation {
+    use super::*;
+    use crate::assert_approx_equal;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rand_distr::StandardNormal;
+
+    fn mean_and_variance(x: &[f64]) -> (f64, f64) {
+        let n = x.len() as f64;
+        let mean = x.iter().sum::<f64>() / n;
+        let variance = x.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / n;
+
+        (mean, variance)
+    }
+
+    /// Two correlated AR(1) return series.
+    fn history(rng: &mut StdRng) -> Vec<DVector<f64>> {
+        let mut previous = [0.0, 0.0];
+
+        (0..500)
+            .map(|_| {
+                let z: f64 = rng.sample(StandardNormal);
+                let w: f64 = rng.sample(StandardNormal);
+                previous = [
+                    0.001 + 0.5 * previous[0] + 0.01 * z,
+                    -0.002 + 0.5 * previous[1] + 0.02 * (0.6 * z + 0.8 * w),
+                ];
+                DVector::from_column_slice(&previous)
+            })
+            .collect()
+    }
+
+    fn lag_one_autocorrelation(x: &[f64]) -> f64 {
+        let (mean, variance) = mean_and_variance(x);
+        let covariance = x
+            .windows(2)
+            .map(|w| (w[0] - mean) * (w[1] - mean))
+            .sum::<f64>()
+            / x.len() as f64;
+
+        covariance / variance
+    }
+
+    #[test]
+    fn test_bootstrap_matches_moments() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let history = history(&mut rng);
+        let scenarios = bootstrap_scenarios(&history, 200_000, 10, &mut rng);
+
+        assert_eq!(scenarios.len(), 200_000);
+
+        for asset in 0..2 {
+            let data: Vec<f64> = history.iter().map(|r| r[asset]).collect();
+            let sample: Vec<f64> = scenarios.iter().map(|r| r[asset]).collect();
+
+            let (mean, variance) = mean_and_variance(&data);
+            let (sample_mean, sample_variance) = mean_and_variance(&sample);
+
+            assert_approx_equal!(sample_mean, mean, 0.05 * variance.sqrt());
+            assert_approx_equal!(sample_variance, variance, 0.03 * variance);
+        }
+
+        // Blocks keep the autocorrelation that independent resampling loses.
+        let data: Vec<f64> = history.iter().map(|r| r[0]).collect();
+        let blocks: Vec<f64> = scenarios.iter().map(|r| r[0]).collect();
+        let iid: Vec<f64> = bootstrap_scenarios(&history, 200_000, 1, &mut rng)
+            .iter()
+            .map(|r| r[0])
+            .collect();
+
+        let rho = lag_one_autocorrelation(&data);
+        assert!(rho > 0.3);
+        assert_approx_equal!(lag_one_autocorrelation(&blocks), 0.9 * rho, 0.05);
+        assert_approx_equal!(lag_one_autocorrelation(&iid), 0.0, 0.01);
+    }
+
+    #[test]
+    fn test_bootstrap_progress_and_cancellation() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        let mut rng = StdRng::seed_from_u64(3);
+        let history = history(&mut rng);
+
+        // Reporting does not change the scenarios.
+        let mut calls = 0;
+        let mut progress = ProgressHandle::new().with_callback(100, |_, total, estimate| {
+            assert_eq!((total, estimate), (950, None));
+            calls += 1;
+        });
+        let outcome = bootstrap_scenarios_with_progress(
+            &history,
+            950,
+            10,
+            &mut StdRng::seed_from_u64(5),
+            &mut progress,
+        );
+        drop(progress);
+
+        assert_eq!(calls, 10);
+        assert_eq!(
+            outcome,
+            Outcome::Completed(bootstrap_scenarios(
+                &history,
+                950,
+                10,
+                &mut StdRng::seed_from_u64(5)
+            ))
+        );
+
+        // Cancelling keeps the scenarios drawn so far.
+        let cancel = Arc::new(AtomicBool::new(false));
+        let token = Arc::clone(&cancel);
+        let mut progress = ProgressHandle::new()
+            .with_callback(100, move |done, _, _| {
+                if done == 300 {
+                    token.store(true, Ordering::Relaxed);
+                }
+            })
+            .with_cancellation(cancel);
+        let outcome = bootstrap_scenarios_with_progress(&history, 950, 10, &mut rng, &mut progress);
+
+        assert!(outcome.is_cancelled());
+        assert_eq!(outcome.value().len(), 300);
+    }
+
+    #[test]
+    fn test_filtered_historical_simulation() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let garch = Garch11::new(2e-6, 0.08, 0.9).unwrap();
+
+        // Simulate from the model, ending in a volatile spell.
+        let mut variance = garch.unconditional_variance();
+        let mut returns = Vec::new();
+        for t in 0..2000 {
+            let z: f64 = rng.sample(StandardNormal);
+            let r = if t >= 1990 { 4.0 * z.signum() } else { z } * variance.sqrt();
+            returns.push(r);
+            variance = 2e-6 + 0.08 * r * r + 0.9 * variance;
+        }
+
+        let scenarios = filtered_historical_simulation(&returns, &garch, 200_000, &mut rng);
+
+        let residuals = garch.standardized_residuals(&returns);
+        let volatility = garch.forecast_variance(&returns).sqrt();
+        let (residual_mean, residual_variance) = mean_and_variance(&residuals);
+        let (mean, variance) = mean_and_variance(&scenarios);
+
+        assert_approx_equal!(mean, volatility * residual_mean, 0.01 * volatility);
+        assert_approx_equal!(
+            variance,
+            volatility * volatility * residual_variance,
+            0.02 * volatility * volatility
+        );
+
+        // The forecast volatility reflects the recent turbulence.
+        assert!(volatility > 1.5 * garch.unconditional_variance().sqrt());
+    }
+}
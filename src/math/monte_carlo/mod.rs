@@ -0,0 +1,32 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Monte Carlo scenario generation and convergence diagnostics.
+//!
+//! - Historical simulation: stationary block bootstrap of multivariate
+//!   returns, and filtered historical simulation with GARCH(1,1) volatility.
+//! - Convergence: running means, CLT confidence intervals, effective sample
+//!   size, and the Gelman-Rubin statistic.
+//! - Budget allocation: splitting a path budget across several estimates
+//!   by their variances and target errors.
+//!
+//! Parametric paths are generated by the stochastic processes in
+//! [`crate::stochastics`].
+
+/// Allocation of a path budget across several estimates.
+pub mod budget_allocation;
+pub use budget_allocation::*;
+
+/// Convergence diagnostics for Monte Carlo estimates.
+pub mod convergence;
+pub use convergence::*;
+
+/// Historical simulation (bootstrapped) scenarios.
+pub mod historical_simulation;
+pub use historical_simulation::*;
@@ -0,0 +1,247 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Newey-West heteroskedasticity and autocorrelation consistent (HAC)
+//! standard errors for least squares regressions.
+//!
+//! For the regression $y = X \beta + u$, the HAC variance-covariance
+//! matrix of the estimates is the sandwich
+//!
+//! $$
+//! \hat{V} = (X^\top X)^{-1} \hat{S} (X^\top X)^{-1}, \quad
+//! \hat{S} = \hat{\Gamma}_0 + \sum_{l=1}^L \left(1 - \frac{l}{L + 1}\right)
+//!     \left(\hat{\Gamma}_l + \hat{\Gamma}_l^\top\right), \quad
+//! \hat{\Gamma}_l = \sum_{t=l+1}^n u_t u_{t-l} x_t x_{t-l}^\top,
+//! $$
+//!
+//! with Bartlett kernel weights, which keep $\hat{S}$ positive
+//! semi-definite. With bandwidth $L = 0$ it is White's (HC0) estimator.
+//! No small-sample adjustment is applied, which matches R's
+//! `sandwich::NeweyWest(model, lag = L, prewhite = FALSE, adjust = FALSE)`.
+//!
+//! [`newey_west_long_run_covariance`] applies the same kernel to a
+//! multivariate time series, for the standard errors of its sample mean.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use nalgebra::{DMatrix, DVector};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Newey-West HAC variance-covariance matrix of least squares estimates,
+/// from the design matrix `x` (including any intercept column), the
+/// regression residuals, and the Bartlett kernel bandwidth (number of
+/// lags).
+///
+/// # Panics
+/// Panics if there is not one residual per row of `x`, or $X^\top X$ is
+/// singular.
+#[must_use]
+#[allow(non_snake_case)]
+pub fn newey_west_vcov(X: &DMatrix<f64>, residuals: &[f64], bandwidth: usize) -> DMatrix<f64> {
+    assert_eq!(
+        X.nrows(),
+        residuals.len(),
+        "There must be one residual per observation."
+    );
+
+    // Scores x_t u_t, one per row.
+    let scores = DMatrix::from_fn(X.nrows(), X.ncols(), |t, i| X[(t, i)] * residuals[t]);
+    let meat = bartlett_sum(&scores, bandwidth);
+
+    let bread = (X.transpose() * X)
+        .try_inverse()
+        .expect("The design matrix must have full column rank.");
+
+    &bread * meat * &bread
+}
+
+/// Newey-West HAC estimate of the long-run covariance matrix
+/// $\lim_{n \to \infty} n \operatorname{Var}(\bar{x})$ of a multivariate
+/// time series, with one observation per row of `observations`, and the
+/// given Bartlett kernel bandwidth.
+///
+/// Dividing it by the number of observations gives the HAC covariance
+/// matrix of the sample mean, e.g. of the Fama-MacBeth risk premia.
+///
+/// # Panics
+/// Panics if there are no observations.
+#[must_use]
+pub fn newey_west_long_run_covariance(
+    observations: &DMatrix<f64>,
+    bandwidth: usize,
+) -> DMatrix<f64> {
+    let n = observations.nrows();
+    assert!(n > 0, "There must be at least one observation.");
+
+    let mean = observations.row_mean();
+    let deviations = DMatrix::from_fn(n, observations.ncols(), |t, i| {
+        observations[(t, i)] - mean[i]
+    });
+
+    bartlett_sum(&deviations, bandwidth) / n as f64
+}
+
+/// Sum of the lagged outer products of the rows of `scores`, with Bartlett
+/// kernel weights: $\hat{\Gamma}_0 + \sum_l w_l (\hat{\Gamma}_l + \hat{\Gamma}_l^\top)$.
+fn bartlett_sum(scores: &DMatrix<f64>, bandwidth: usize) -> DMatrix<f64> {
+    let n = scores.nrows();
+
+    let mut sum = scores.transpose() * scores;
+    for lag in 1..=bandwidth.min(n.saturating_sub(1)) {
+        let weight = 1.0 - lag as f64 / (bandwidth as f64 + 1.0);
+        let gamma = scores.rows(lag, n - lag).transpose() * scores.rows(0, n - lag);
+
+        sum += weight * (&gamma + gamma.transpose());
+    }
+
+    sum
+}
+
+/// t-statistics of the estimates `coefs` given their variance-covariance
+/// matrix, e.g. from [`newey_west_vcov`].
+///
+/// # Panics
+/// Panics if `vcov` is not square with one row per coefficient.
+#[must_use]
+pub fn newey_west_t_stats(coefs: &DVector<f64>, vcov: &DMatrix<f64>) -> DVector<f64> {
+    assert!(
+        vcov.is_square() && vcov.nrows() == coefs.len(),
+        "The variance-covariance matrix must have one row and column per coefficient."
+    );
+
+    DVector::from_fn(coefs.len(), |i, _| coefs[i] / vcov[(i, i)].sqrt())
+}
+
+/// Newey and West's (1994) rule of thumb for the Bartlett kernel
+/// bandwidth with `n` observations, $\lfloor 4 (n / 100)^{2/9} \rfloor$.
+#[must_use]
+pub fn newey_west_bandwidth(n: usize) -> usize {
+    (4.0 * (n as f64 / 100.0).powf(2.0 / 9.0)).floor() as usize
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_newey_west {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    /// y = 1 + 0.5 t + u_t, t = 1, ..., 10, with autocorrelated errors.
+    fn regression() -> (DMatrix<f64>, DVector<f64>, Vec<f64>) {
+        let errors = [0.3, 0.5, 0.2, -0.1, -0.4, -0.2, 0.1, 0.4, 0.3, -0.2];
+        let x = DMatrix::from_fn(10, 2, |t, i| if i == 0 { 1.0 } else { (t + 1) as f64 });
+        let y = DVector::from_fn(10, |t, _| 1.0 + 0.5 * (t + 1) as f64 + errors[t]);
+
+        let coefs = (x.transpose() * &x).try_inverse().unwrap() * x.transpose() * &y;
+        let residuals = (&y - &x * &coefs).iter().copied().collect();
+
+        (x, coefs, residuals)
+    }
+
+    #[test]
+    fn test_newey_west_vcov() {
+        let (x, coefs, residuals) = regression();
+        assert_approx_equal!(coefs[0], 1.226_666_666_666_666, 1e-12);
+        assert_approx_equal!(coefs[1], 0.475_151_515_151_515, 1e-12);
+
+        // Reference values from the definition used by R's
+        // sandwich::NeweyWest(lm(y ~ t), lag = L, prewhite = FALSE, adjust = FALSE).
+        let expected = [
+            (
+                0,
+                [
+                    0.023_649_077_441_077_7,
+                    -0.003_190_116_926_844_25,
+                    0.000_628_853_382_307_937,
+                ],
+            ),
+            (
+                1,
+                [
+                    0.032_528_161_616_162_1,
+                    -0.004_014_464_646_464_74,
+                    0.000_751_460_389_014_127,
+                ],
+            ),
+            (
+                3,
+                [
+                    0.028_715_549_841_853_4,
+                    -0.003_532_366_604_521_00,
+                    0.000_610_876_238_805_141,
+                ],
+            ),
+        ];
+
+        for (bandwidth, [v00, v01, v11]) in expected {
+            let vcov = newey_west_vcov(&x, &residuals, bandwidth);
+
+            assert_approx_equal!(vcov[(0, 0)], v00, 1e-12);
+            assert_approx_equal!(vcov[(0, 1)], v01, 1e-12);
+            assert_approx_equal!(vcov[(1, 0)], v01, 1e-12);
+            assert_approx_equal!(vcov[(1, 1)], v11, 1e-12);
+        }
+
+        let t_stats = newey_west_t_stats(&coefs, &newey_west_vcov(&x, &residuals, 1));
+        assert_approx_equal!(t_stats[0], 6.801_376_310_497_26, 1e-9);
+        assert_approx_equal!(t_stats[1], 17.333_212_923_199_2, 1e-9);
+    }
+
+    #[test]
+    fn test_zero_bandwidth_is_white() {
+        let (x, _, residuals) = regression();
+        let bread = (x.transpose() * &x).try_inverse().unwrap();
+
+        let mut meat = DMatrix::zeros(2, 2);
+        for (t, u) in residuals.iter().enumerate() {
+            let row = x.row(t);
+            meat += u * u * row.transpose() * row;
+        }
+
+        let white = &bread * meat * &bread;
+        let vcov = newey_west_vcov(&x, &residuals, 0);
+
+        for (a, b) in vcov.iter().zip(white.iter()) {
+            assert_approx_equal!(*a, *b, 1e-15);
+        }
+    }
+
+    #[test]
+    fn test_long_run_covariance_matches_regression_on_constant() {
+        // The variance of a sample mean is the HAC variance of the
+        // intercept in a regression on a constant.
+        let (_, _, series) = regression();
+        let n = series.len();
+        let ones = DMatrix::from_element(n, 1, 1.0);
+        let mean = series.iter().sum::<f64>() / n as f64;
+        let deviations: Vec<f64> = series.iter().map(|x| x - mean).collect();
+        let observations = DMatrix::from_column_slice(n, 1, &series);
+
+        for bandwidth in [0, 1, 3] {
+            let long_run = newey_west_long_run_covariance(&observations, bandwidth);
+            let vcov = newey_west_vcov(&ones, &deviations, bandwidth);
+
+            assert_approx_equal!(long_run[(0, 0)] / n as f64, vcov[(0, 0)], 1e-15);
+        }
+    }
+
+    #[test]
+    fn test_bandwidth_rule() {
+        assert_eq!(newey_west_bandwidth(100), 4);
+        assert_eq!(newey_west_bandwidth(1000), 6);
+        assert_eq!(newey_west_bandwidth(10), 2);
+    }
+}
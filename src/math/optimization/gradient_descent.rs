@@ -73,6 +73,7 @@
 //! $$
 
 use crate::autodiff::{variables::variable::Variable, Accumulate, Gradient, Graph};
+use crate::progress::{Outcome, ProgressHandle};
 use std::time::{Duration, Instant};
 
 // use ::log::{info, max_level, warn, Level};
@@ -150,12 +151,44 @@ impl GradientDescent {
     // }
 
     /// Performs gradient descent optimization.
-    #[allow(clippy::assign_op_pattern)]
     pub fn optimize<F>(&self, f: F, x0: &[f64], verbose: bool) -> GradientDescentResult
+    where
+        F: for<'v> Fn(&[Variable<'v>]) -> Variable<'v>,
+    {
+        self.descend(f, x0, verbose, &mut ProgressHandle::new())
+            .into_inner()
+    }
+
+    /// Performs gradient descent optimization, reporting the iteration and
+    /// the function value to `progress` after each step.
+    ///
+    /// If the cancellation token is set, returns the iterate reached so far
+    /// as [`Outcome::Cancelled`].
+    pub fn optimize_with_progress<F>(
+        &self,
+        f: F,
+        x0: &[f64],
+        progress: &mut ProgressHandle,
+    ) -> Outcome<GradientDescentResult>
+    where
+        F: for<'v> Fn(&[Variable<'v>]) -> Variable<'v>,
+    {
+        self.descend(f, x0, false, progress)
+    }
+
+    #[allow(clippy::assign_op_pattern)]
+    fn descend<F>(
+        &self,
+        f: F,
+        x0: &[f64],
+        verbose: bool,
+        progress: &mut ProgressHandle,
+    ) -> Outcome<GradientDescentResult>
     where
         F: for<'v> Fn(&[Variable<'v>]) -> Variable<'v>,
     {
         let start = Instant::now();
+        let mut cancelled = false;
 
         let tolerance = self.tolerance.unwrap_or(f64::EPSILON.sqrt());
 
@@ -167,6 +200,11 @@ impl GradientDescent {
         };
 
         for k in 0..self.max_iterations {
+            if progress.is_cancelled() {
+                cancelled = true;
+                break;
+            }
+
             let graph = Graph::new();
 
             result.iterations = k + 1;
@@ -191,6 +229,7 @@ impl GradientDescent {
                 .for_each(|(xi, gi)| *xi = *xi - self.learning_rate * gi);
 
             result.minimum = f(&location).value;
+            progress.report(k + 1, self.max_iterations, Some(function.value));
 
             if verbose {
                 println!(
@@ -214,7 +253,7 @@ impl GradientDescent {
         }
 
         result.elapsed = start.elapsed();
-        result
+        Outcome::new(result, cancelled)
     }
 }
 
@@ -314,6 +353,45 @@ mod test_gradient_descent {
         println!("Iterations: {:?}", result.iterations);
     }
 
+    // Test cancelling the optimization after a fixed number of iterations.
+    #[test]
+    fn test_optimize_with_progress_cancellation() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        fn f<'v>(x: &[Variable<'v>]) -> Variable<'v> {
+            x[0] * x[0]
+        }
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        let token = Arc::clone(&cancel);
+        let values = std::cell::RefCell::new(Vec::new());
+
+        let mut progress = ProgressHandle::new()
+            .with_callback(1, |k, total, value| {
+                assert_eq!(total, 1000);
+                values.borrow_mut().push(value.unwrap());
+                if k == 5 {
+                    token.store(true, Ordering::Relaxed);
+                }
+            })
+            .with_cancellation(cancel);
+
+        let gd = GradientDescent::new(0.1, 1000, Some(0.000_001));
+        let outcome = gd.optimize_with_progress(f, &[10.0], &mut progress);
+        drop(progress);
+
+        assert!(outcome.is_cancelled());
+        let result = outcome.into_inner();
+        assert_eq!(result.iterations, 5);
+        // Each step multiplies x by 1 - 2 * 0.1.
+        assert!((result.minimizer[0] - 10.0 * 0.8_f64.powi(5)).abs() < 1e-12);
+
+        let values = values.into_inner();
+        assert_eq!(values.len(), 5);
+        assert!(values.windows(2).all(|w| w[1] < w[0]));
+    }
+
     // Test the optimize function on Himmelblau function.
 
     // Test the optimize function on Beale function.
@@ -0,0 +1,167 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::series_values;
+use crate::error::RustQuantError;
+use crate::math::fft_complex;
+use num::Complex;
+use polars::prelude::*;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Periodogram of a series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Periodogram {
+    /// Frequencies, in cycles per observation, from 0 to 0.5 (Nyquist).
+    pub frequencies: Vec<f64>,
+    /// Power $|X_k|^2 / n$ at each frequency.
+    pub power: Vec<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Periodogram {
+    /// Frequency with the highest power (excluding zero frequency).
+    #[must_use]
+    pub fn peak_frequency(&self) -> f64 {
+        let peak = self
+            .power
+            .iter()
+            .enumerate()
+            .skip(1)
+            .max_by(|a, b| a.1.total_cmp(b.1))
+            .map_or(0, |(k, _)| k);
+
+        self.frequencies[peak]
+    }
+}
+
+/// FFT of a real series, returning the $n / 2 + 1$ non-negative frequency
+/// coefficients (the others are their complex conjugates).
+///
+/// # Panics
+/// Panics if the length of `x` is not a power of 2.
+#[must_use]
+pub fn real_fft(x: &[f64]) -> Vec<Complex<f64>> {
+    let complex: Vec<Complex<f64>> = x.iter().map(|&x| Complex::new(x, 0.0)).collect();
+
+    let mut coefficients = fft_complex(&complex);
+    coefficients.truncate(x.len() / 2 + 1);
+
+    coefficients
+}
+
+/// Periodogram of a series: $I(f_k) = |X_k|^2 / n$ at the Fourier frequencies
+/// $f_k = k / n$, $k = 0, \ldots, n / 2$.
+///
+/// The series is demeaned, and zero-padded to the next power of 2
+/// (so $n$ is the padded length).
+///
+/// # Panics
+/// Panics if `x` is empty.
+#[must_use]
+pub fn periodogram(x: &[f64]) -> Periodogram {
+    assert!(!x.is_empty(), "Series must not be empty.");
+
+    let mean = x.iter().sum::<f64>() / x.len() as f64;
+    let n = x.len().next_power_of_two();
+
+    let mut padded: Vec<f64> = x.iter().map(|x| x - mean).collect();
+    padded.resize(n, 0.0);
+
+    let coefficients = real_fft(&padded);
+
+    Periodogram {
+        frequencies: (0..coefficients.len())
+            .map(|k| k as f64 / n as f64)
+            .collect(),
+        power: coefficients
+            .iter()
+            .map(|c| c.norm_sqr() / n as f64)
+            .collect(),
+    }
+}
+
+/// Periodogram of a polars `Series`.
+///
+/// # Errors
+/// - `RustQuantError::PolarsError` if the series is not numeric.
+/// - `RustQuantError::MissingInput` if the series contains nulls.
+///
+/// # Panics
+/// Panics if the series is empty.
+pub fn periodogram_series(series: &Series) -> Result<Periodogram, RustQuantError> {
+    Ok(periodogram(&series_values(series)?))
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_fourier {
+    use super::*;
+    use crate::assert_approx_equal;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_periodogram_peak_of_sinusoid() {
+        // 12 cycles over 256 observations: frequency 12/256 = bin 12.
+        let x: Vec<f64> = (0..256)
+            .map(|t| 3.0 + (2.0 * PI * 12.0 * t as f64 / 256.0).sin())
+            .collect();
+
+        let periodogram = periodogram(&x);
+
+        assert_eq!(periodogram.frequencies.len(), 129);
+        assert_approx_equal!(periodogram.peak_frequency(), 12.0 / 256.0, 1e-15);
+
+        // All the power of the (demeaned) sinusoid is in that bin: n / 4.
+        assert_approx_equal!(periodogram.power[12], 64.0, 1e-9);
+        assert_approx_equal!(periodogram.power[0], 0.0, 1e-9);
+        assert!(periodogram.power[13] < 1e-9);
+    }
+
+    #[test]
+    fn test_periodogram_series() -> Result<(), RustQuantError> {
+        // Alternating series: all power at the Nyquist frequency.
+        let series = Series::new("returns", [1.0, -1.0, 1.0, -1.0, 1.0, -1.0, 1.0, -1.0]);
+        let periodogram = periodogram_series(&series)?;
+
+        assert_approx_equal!(periodogram.peak_frequency(), 0.5, 1e-15);
+        assert_approx_equal!(periodogram.power[4], 8.0, 1e-12);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_real_fft_matches_definition() {
+        let x = [1.0, 2.0, 0.5, -1.0, 3.0, 0.0, -2.0, 1.5];
+        let coefficients = real_fft(&x);
+
+        assert_eq!(coefficients.len(), 5);
+        for (k, c) in coefficients.iter().enumerate() {
+            let exact: Complex<f64> = x
+                .iter()
+                .enumerate()
+                .map(|(t, &x)| x * Complex::new(0.0, -2.0 * PI * (k * t) as f64 / 8.0).exp())
+                .sum();
+
+            assert!((c - exact).norm() < 1e-12);
+        }
+    }
+}
@@ -0,0 +1,41 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Signal processing utilities for price and return series.
+//!
+//! - Periodogram (spectral density) estimation, using the in-crate radix-2 FFT.
+//! - Discrete wavelet transforms (Haar and Daubechies-4), with soft-threshold
+//!   denoising, e.g. to remove microstructure noise before estimating volatility.
+//!
+//! Functions work on `&[f64]`, and have variants for polars `Series`.
+
+use crate::error::RustQuantError;
+use polars::prelude::*;
+
+/// Periodogram estimation.
+pub mod fourier;
+pub use fourier::*;
+
+/// Discrete wavelet transforms and denoising.
+pub mod wavelet;
+pub use wavelet::*;
+
+/// Values of a numeric `Series` as `f64`.
+fn series_values(series: &Series) -> Result<Vec<f64>, RustQuantError> {
+    series
+        .cast(&DataType::Float64)?
+        .f64()?
+        .into_iter()
+        .map(|value| {
+            value.ok_or_else(|| {
+                RustQuantError::MissingInput(format!("Null value in series '{}'.", series.name()))
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,306 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::series_values;
+use crate::error::RustQuantError;
+use polars::prelude::*;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Orthogonal wavelet families.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wavelet {
+    /// Haar wavelet (2 coefficients).
+    Haar,
+    /// Daubechies wavelet with 2 vanishing moments (4 coefficients).
+    Daubechies4,
+}
+
+/// Multi-level discrete wavelet decomposition.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaveletDecomposition {
+    /// Approximation (scaling) coefficients at the coarsest level.
+    pub approximation: Vec<f64>,
+    /// Detail (wavelet) coefficients, from the finest level to the coarsest.
+    pub details: Vec<Vec<f64>>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Wavelet {
+    /// Low-pass (scaling) filter coefficients.
+    #[must_use]
+    pub fn low_pass(&self) -> Vec<f64> {
+        match self {
+            Self::Haar => vec![std::f64::consts::FRAC_1_SQRT_2; 2],
+            Self::Daubechies4 => {
+                let sqrt_3 = 3_f64.sqrt();
+                let scale = 4.0 * std::f64::consts::SQRT_2;
+
+                vec![
+                    (1.0 + sqrt_3) / scale,
+                    (3.0 + sqrt_3) / scale,
+                    (3.0 - sqrt_3) / scale,
+                    (1.0 - sqrt_3) / scale,
+                ]
+            }
+        }
+    }
+
+    /// High-pass (wavelet) filter coefficients: $g_k = (-1)^k h_{L - 1 - k}$.
+    #[must_use]
+    pub fn high_pass(&self) -> Vec<f64> {
+        let h = self.low_pass();
+
+        h.iter()
+            .rev()
+            .enumerate()
+            .map(|(k, &h)| if k % 2 == 0 { h } else { -h })
+            .collect()
+    }
+}
+
+/// Multi-level discrete wavelet transform, with periodic boundary conditions.
+///
+/// # Panics
+/// Panics if `levels` is zero or the length of `x` is not a (non-zero)
+/// multiple of $2^{\text{levels}}$.
+#[must_use]
+pub fn dwt(x: &[f64], wavelet: Wavelet, levels: usize) -> WaveletDecomposition {
+    assert!(levels > 0, "Number of levels must be positive.");
+    assert!(
+        !x.is_empty() && x.len().is_multiple_of(1 << levels),
+        "Length must be a multiple of 2^levels."
+    );
+
+    let h = wavelet.low_pass();
+    let g = wavelet.high_pass();
+
+    let mut approximation = x.to_vec();
+    let mut details = Vec::with_capacity(levels);
+
+    for _ in 0..levels {
+        let n = approximation.len();
+        let (mut a, mut d) = (vec![0.0; n / 2], vec![0.0; n / 2]);
+
+        for i in 0..n / 2 {
+            for k in 0..h.len() {
+                let x = approximation[(2 * i + k) % n];
+                a[i] += h[k] * x;
+                d[i] += g[k] * x;
+            }
+        }
+
+        approximation = a;
+        details.push(d);
+    }
+
+    WaveletDecomposition {
+        approximation,
+        details,
+    }
+}
+
+/// Inverse of [`dwt`].
+#[must_use]
+pub fn idwt(decomposition: &WaveletDecomposition, wavelet: Wavelet) -> Vec<f64> {
+    let h = wavelet.low_pass();
+    let g = wavelet.high_pass();
+
+    let mut approximation = decomposition.approximation.clone();
+
+    for d in decomposition.details.iter().rev() {
+        let n = 2 * approximation.len();
+        let mut x = vec![0.0; n];
+
+        for (i, (a, d)) in approximation.iter().zip(d).enumerate() {
+            for k in 0..h.len() {
+                x[(2 * i + k) % n] += h[k] * a + g[k] * d;
+            }
+        }
+
+        approximation = x;
+    }
+
+    approximation
+}
+
+/// Soft thresholding: shrink `x` towards zero by `threshold`.
+#[must_use]
+pub fn soft_threshold(x: f64, threshold: f64) -> f64 {
+    x.signum() * (x.abs() - threshold).max(0.0)
+}
+
+/// Wavelet denoising (Donoho and Johnstone, 1994).
+///
+/// The detail coefficients are soft-thresholded, and the series is
+/// reconstructed. If `threshold` is `None`, the "universal" threshold
+/// $\hat{\sigma} \sqrt{2 \ln n}$ is used, where the noise level $\hat{\sigma}$
+/// is estimated by the median absolute finest-level detail coefficient / 0.6745.
+///
+/// # Panics
+/// Panics if `levels` is zero or the length of `x` is not a (non-zero)
+/// multiple of $2^{\text{levels}}$.
+#[must_use]
+pub fn denoise(x: &[f64], wavelet: Wavelet, levels: usize, threshold: Option<f64>) -> Vec<f64> {
+    let mut decomposition = dwt(x, wavelet, levels);
+
+    let threshold = threshold.unwrap_or_else(|| {
+        let mut finest: Vec<f64> = decomposition.details[0].iter().map(|d| d.abs()).collect();
+        finest.sort_by(f64::total_cmp);
+
+        let m = finest.len();
+        let median = if m.is_multiple_of(2) {
+            0.5 * (finest[m / 2 - 1] + finest[m / 2])
+        } else {
+            finest[m / 2]
+        };
+
+        median / 0.6745 * (2.0 * (x.len() as f64).ln()).sqrt()
+    });
+
+    for d in decomposition.details.iter_mut().flatten() {
+        *d = soft_threshold(*d, threshold);
+    }
+
+    idwt(&decomposition, wavelet)
+}
+
+/// Wavelet denoising of a polars `Series` (see [`denoise`]).
+///
+/// # Errors
+/// - `RustQuantError::PolarsError` if the series is not numeric.
+/// - `RustQuantError::MissingInput` if the series contains nulls.
+///
+/// # Panics
+/// Panics if `levels` is zero or the length of the series is not a (non-zero)
+/// multiple of $2^{\text{levels}}$.
+pub fn denoise_series(
+    series: &Series,
+    wavelet: Wavelet,
+    levels: usize,
+    threshold: Option<f64>,
+) -> Result<Series, RustQuantError> {
+    let denoised = denoise(&series_values(series)?, wavelet, levels, threshold);
+
+    Ok(Series::new(series.name(), denoised))
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_wavelet {
+    use super::*;
+    use crate::assert_approx_equal;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rand_distr::{Distribution, Normal};
+    use std::f64::consts::PI;
+
+    fn clean_signal(n: usize) -> Vec<f64> {
+        (0..n)
+            .map(|t| 100.0 + 2.0 * (2.0 * PI * 3.0 * t as f64 / n as f64).sin())
+            .collect()
+    }
+
+    fn noisy_signal(n: usize) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(11);
+        let noise = Normal::new(0.0, 0.5).unwrap();
+
+        clean_signal(n)
+            .into_iter()
+            .map(|x| x + noise.sample(&mut rng))
+            .collect()
+    }
+
+    fn mse(x: &[f64], y: &[f64]) -> f64 {
+        x.iter().zip(y).map(|(x, y)| (x - y).powi(2)).sum::<f64>() / x.len() as f64
+    }
+
+    #[test]
+    fn test_dwt_round_trip() {
+        let x = noisy_signal(96);
+
+        for wavelet in [Wavelet::Haar, Wavelet::Daubechies4] {
+            for levels in [1, 3, 5] {
+                let decomposition = dwt(&x, wavelet, levels);
+                assert_eq!(decomposition.details.len(), levels);
+                assert_eq!(decomposition.approximation.len(), 96 >> levels);
+
+                let reconstructed = idwt(&decomposition, wavelet);
+                for (x, y) in x.iter().zip(&reconstructed) {
+                    assert_approx_equal!(x, y, 1e-10);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_filters_are_orthonormal() {
+        for wavelet in [Wavelet::Haar, Wavelet::Daubechies4] {
+            let h = wavelet.low_pass();
+            let g = wavelet.high_pass();
+
+            assert_approx_equal!(h.iter().sum::<f64>(), std::f64::consts::SQRT_2, 1e-12);
+            assert_approx_equal!(h.iter().map(|h| h * h).sum::<f64>(), 1.0, 1e-12);
+            assert_approx_equal!(g.iter().sum::<f64>(), 0.0, 1e-12);
+            assert_approx_equal!(
+                h.iter().zip(&g).map(|(h, g)| h * g).sum::<f64>(),
+                0.0,
+                1e-12
+            );
+        }
+    }
+
+    #[test]
+    fn test_denoising_reduces_error() {
+        let clean = clean_signal(1024);
+        let noisy = noisy_signal(1024);
+
+        for wavelet in [Wavelet::Haar, Wavelet::Daubechies4] {
+            let denoised = denoise(&noisy, wavelet, 6, None);
+
+            assert!(mse(&denoised, &clean) < 0.5 * mse(&noisy, &clean));
+        }
+
+        // A zero threshold leaves the series unchanged.
+        let unchanged = denoise(&noisy, Wavelet::Daubechies4, 5, Some(0.0));
+        assert!(mse(&unchanged, &noisy) < 1e-20);
+    }
+
+    #[test]
+    fn test_denoise_series() -> Result<(), RustQuantError> {
+        let clean = clean_signal(256);
+        let series = Series::new("price", noisy_signal(256));
+
+        let denoised = denoise_series(&series, Wavelet::Daubechies4, 4, None)?;
+        let values: Vec<f64> = denoised.f64()?.into_no_null_iter().collect();
+
+        assert_eq!(denoised.name(), "price");
+        assert!(mse(&values, &clean) < mse(&noisy_signal(256), &clean));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_soft_threshold() {
+        assert_eq!(soft_threshold(3.0, 1.0), 2.0);
+        assert_eq!(soft_threshold(-3.0, 1.0), -2.0);
+        assert_eq!(soft_threshold(0.5, 1.0), 0.0);
+    }
+}
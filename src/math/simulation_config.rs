@@ -0,0 +1,353 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Shared simulation settings and deterministic seeding.
+//!
+//! A [`SimulationConfig`] carries a single master seed. Each consumer asks
+//! for a random number generator by a fixed label (e.g.
+//! `"stochastics.euler_maruyama"`), and gets a child seed derived from the
+//! master seed and a hash of the label only:
+//!
+//! ```text
+//! master seed ──┬── child_seed("pricer.monte_carlo_engine")
+//!               ├── child_seed("stochastics.euler_maruyama") ──┬── path 0
+//!               │                                               ├── path 1
+//!               │                                               └── ...
+//!               └── ...
+//! ```
+//!
+//! Child seeds are mixed with [`SplitMix64`], so streams are decorrelated
+//! even for adjacent master seeds, and adding a new consumer (a new label)
+//! does not perturb the streams of the existing ones. Paths are seeded by
+//! their index, so results do not depend on the number of threads.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::math::distributions::{Distribution, Gaussian};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use rand_distr::StandardNormal;
+use rayon::prelude::*;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// How the standard normal draws are generated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SamplingMethod {
+    /// Independent pseudo-random draws.
+    #[default]
+    PseudoRandom,
+    /// Latin hypercube sampling: each dimension is stratified into one
+    /// equiprobable stratum per draw.
+    LatinHypercube,
+}
+
+/// Settings shared by the simulation routines of the crate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimulationConfig {
+    /// Master seed, from which every consumer's seed is derived.
+    pub seed: u64,
+    /// Number of paths.
+    pub n_paths: usize,
+    /// Number of time steps per path.
+    pub n_steps: usize,
+    /// How the normal draws are generated.
+    pub sampling: SamplingMethod,
+    /// Whether paths come in antithetic pairs $(Z, -Z)$.
+    pub antithetic: bool,
+    /// Number of worker threads (0 for the rayon default).
+    pub n_threads: usize,
+}
+
+/// SplitMix64 generator (Steele, Lea and Flood, 2014), used to derive
+/// child seeds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl SplitMix64 {
+    /// New generator with the given `seed`.
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    /// Next output of the generator.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// First output of a generator seeded with `seed ^ key`.
+    #[must_use]
+    pub fn mix(seed: u64, key: u64) -> u64 {
+        Self::new(seed ^ key).next_u64()
+    }
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            seed: 42,
+            n_paths: 10_000,
+            n_steps: 252,
+            sampling: SamplingMethod::PseudoRandom,
+            antithetic: false,
+            n_threads: 0,
+        }
+    }
+}
+
+impl SimulationConfig {
+    /// Default settings with master seed `seed`: 10,000 pseudo-random paths
+    /// of 252 steps, no antithetic pairing, and the default thread pool.
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            ..Self::default()
+        }
+    }
+
+    /// Set the number of paths.
+    #[must_use]
+    pub fn with_paths(mut self, n_paths: usize) -> Self {
+        self.n_paths = n_paths;
+        self
+    }
+
+    /// Set the number of time steps per path.
+    #[must_use]
+    pub fn with_steps(mut self, n_steps: usize) -> Self {
+        self.n_steps = n_steps;
+        self
+    }
+
+    /// Set the sampling method.
+    #[must_use]
+    pub fn with_sampling(mut self, sampling: SamplingMethod) -> Self {
+        self.sampling = sampling;
+        self
+    }
+
+    /// Use antithetic pairs.
+    #[must_use]
+    pub fn with_antithetic(mut self, antithetic: bool) -> Self {
+        self.antithetic = antithetic;
+        self
+    }
+
+    /// Set the number of worker threads (0 for the rayon default).
+    #[must_use]
+    pub fn with_threads(mut self, n_threads: usize) -> Self {
+        self.n_threads = n_threads;
+        self
+    }
+
+    /// Seed of the consumer labelled `component`.
+    ///
+    /// Depends only on the master seed and the label, so consumers never
+    /// shift each other's streams.
+    #[must_use]
+    pub fn child_seed(&self, component: &str) -> u64 {
+        SplitMix64::mix(self.seed, fnv1a(component))
+    }
+
+    /// Random number generator of the consumer labelled `component`.
+    #[must_use]
+    pub fn rng(&self, component: &str) -> StdRng {
+        StdRng::seed_from_u64(self.child_seed(component))
+    }
+
+    /// Random number generator of path `path` of the consumer labelled
+    /// `component`.
+    #[must_use]
+    pub fn path_rng(&self, component: &str, path: usize) -> StdRng {
+        StdRng::seed_from_u64(SplitMix64::mix(self.child_seed(component), path as u64))
+    }
+
+    /// Standard normal draws for the consumer labelled `component`: one row
+    /// of `dimension` draws per path, following the sampling method and
+    /// antithetic pairing (rows `2i` and `2i + 1` are a pair).
+    ///
+    /// The draws are identical for any number of threads.
+    #[must_use]
+    pub fn standard_normals(&self, component: &str, dimension: usize) -> Vec<Vec<f64>> {
+        let n_draws = if self.antithetic {
+            self.n_paths.div_ceil(2)
+        } else {
+            self.n_paths
+        };
+
+        let draws = match self.sampling {
+            SamplingMethod::PseudoRandom => self.install(|| {
+                (0..n_draws)
+                    .into_par_iter()
+                    .map(|i| {
+                        let mut rng = self.path_rng(component, i);
+                        (0..dimension).map(|_| rng.sample(StandardNormal)).collect()
+                    })
+                    .collect()
+            }),
+            SamplingMethod::LatinHypercube => {
+                latin_hypercube_normals(&mut self.rng(component), n_draws, dimension)
+            }
+        };
+
+        if !self.antithetic {
+            return draws;
+        }
+
+        draws
+            .into_iter()
+            .flat_map(|z| {
+                let reflected = z.iter().map(|z| -z).collect();
+                [z, reflected]
+            })
+            .take(self.n_paths)
+            .collect()
+    }
+
+    /// Run `op` on a thread pool with `n_threads` workers (on the global
+    /// pool if `n_threads` is 0).
+    ///
+    /// # Panics
+    /// Panics if the thread pool cannot be built.
+    pub fn install<T, OP>(&self, op: OP) -> T
+    where
+        OP: FnOnce() -> T + Send,
+        T: Send,
+    {
+        if self.n_threads == 0 {
+            return op();
+        }
+
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(self.n_threads)
+            .build()
+            .expect("Failed to build the thread pool.")
+            .install(op)
+    }
+}
+
+/// 64-bit FNV-1a hash of a label.
+fn fnv1a(label: &str) -> u64 {
+    label.bytes().fold(0xCBF2_9CE4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01B3)
+    })
+}
+
+/// `n` Latin hypercube samples of `dimension` standard normals: in each
+/// dimension, every one of the `n` equiprobable strata holds one draw.
+pub(crate) fn latin_hypercube_normals<R: Rng + ?Sized>(
+    rng: &mut R,
+    n: usize,
+    dimension: usize,
+) -> Vec<Vec<f64>> {
+    let gaussian = Gaussian::default();
+    let mut draws = vec![vec![0.0; dimension]; n];
+    let mut strata: Vec<usize> = (0..n).collect();
+
+    for j in 0..dimension {
+        strata.shuffle(rng);
+
+        for (row, &stratum) in draws.iter_mut().zip(&strata) {
+            // Open interval, so the inverse CDF stays finite.
+            let u: f64 = rng.gen_range(f64::EPSILON..1.0 - f64::EPSILON);
+            row[j] = gaussian.inv_cdf((stratum as f64 + u) / n as f64);
+        }
+    }
+
+    draws
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_simulation_config {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    #[test]
+    fn test_splitmix64_reference_values() {
+        // Reference outputs for seed 0.
+        let mut generator = SplitMix64::new(0);
+
+        assert_eq!(generator.next_u64(), 0xE220_A839_7B1D_CDAF);
+        assert_eq!(generator.next_u64(), 0x6E78_9E6A_A1B9_65F4);
+        assert_eq!(generator.next_u64(), 0x06C4_5D18_8009_454F);
+    }
+
+    #[test]
+    fn test_child_seeds_are_independent_of_other_consumers() {
+        let config = SimulationConfig::new(7);
+        let seed = config.child_seed("pricer.monte_carlo_engine");
+
+        // Asking for other streams first does not shift this one.
+        let _ = config.child_seed("stochastics.euler_maruyama");
+        assert_eq!(config.child_seed("pricer.monte_carlo_engine"), seed);
+
+        assert_ne!(config.child_seed("stochastics.euler_maruyama"), seed);
+        assert_ne!(
+            SimulationConfig::new(8).child_seed("pricer.monte_carlo_engine"),
+            seed
+        );
+    }
+
+    #[test]
+    fn test_draws_do_not_depend_on_threads() {
+        let config = SimulationConfig::new(1)
+            .with_paths(501)
+            .with_antithetic(true);
+
+        let serial = config.with_threads(1).standard_normals("test", 3);
+        let parallel = config.with_threads(4).standard_normals("test", 3);
+
+        assert_eq!(serial, parallel);
+        assert_eq!(serial.len(), 501);
+        assert_eq!(serial[1], serial[0].iter().map(|z| -z).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_latin_hypercube_stratification() {
+        let n = 1000;
+        let config = SimulationConfig::new(3)
+            .with_paths(n)
+            .with_sampling(SamplingMethod::LatinHypercube);
+        let draws = config.standard_normals("test", 2);
+        let gaussian = Gaussian::default();
+
+        for j in 0..2 {
+            let mut strata: Vec<usize> = draws
+                .iter()
+                .map(|row| (gaussian.cdf(row[j]) * n as f64) as usize)
+                .collect();
+            strata.sort_unstable();
+
+            assert_eq!(strata, (0..n).collect::<Vec<_>>());
+
+            let mean = draws.iter().map(|row| row[j]).sum::<f64>() / n as f64;
+            assert_approx_equal!(mean, 0.0, 1e-3);
+        }
+    }
+}
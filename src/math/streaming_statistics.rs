@@ -0,0 +1,313 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Streaming (single-pass) descriptive statistics.
+//!
+//! The first four central moments are updated one observation at a time
+//! with the numerically stable formulas of Welford (1962) and Terriberry (2007),
+//! and two sets of moments are combined with the pairwise formulas of
+//! Chan, Golub and LeVeque (1979) and Pébay (2008), so a stream can be
+//! split into chunks, summarised in parallel, and merged.
+//!
+//! The estimators are the same as those of the [`Statistic`](super::Statistic)
+//! trait: sample variance, adjusted Fisher-Pearson skewness, and sample excess kurtosis.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use serde::{Deserialize, Serialize};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Running count, mean, variance, skewness, kurtosis, minimum and maximum of a stream.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct StreamingStats {
+    count: u64,
+    mean: f64,
+    /// Sums of the 2nd, 3rd, and 4th powers of the deviations from the mean.
+    m2: f64,
+    m3: f64,
+    m4: f64,
+    min: f64,
+    max: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl StreamingStats {
+    /// Create a new, empty, `StreamingStats`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an observation.
+    pub fn push(&mut self, x: f64) {
+        if self.count == 0 {
+            self.min = x;
+            self.max = x;
+        } else {
+            self.min = self.min.min(x);
+            self.max = self.max.max(x);
+        }
+
+        let n1 = self.count as f64;
+        self.count += 1;
+        let n = self.count as f64;
+
+        let delta = x - self.mean;
+        let delta_n = delta / n;
+        let delta_n2 = delta_n * delta_n;
+        let term = delta * delta_n * n1;
+
+        self.mean += delta_n;
+        self.m4 += term * delta_n2 * (n * n - 3.0 * n + 3.0) + 6.0 * delta_n2 * self.m2
+            - 4.0 * delta_n * self.m3;
+        self.m3 += term * delta_n * (n - 2.0) - 3.0 * delta_n * self.m2;
+        self.m2 += term;
+    }
+
+    /// Combine with the statistics of another (disjoint) set of observations,
+    /// as if all observations had been pushed into `self`.
+    pub fn merge(&mut self, other: &Self) {
+        if other.count == 0 {
+            return;
+        }
+        if self.count == 0 {
+            *self = *other;
+            return;
+        }
+
+        let (na, nb) = (self.count as f64, other.count as f64);
+        let n = na + nb;
+
+        let delta = other.mean - self.mean;
+        let delta2 = delta * delta;
+        let delta3 = delta2 * delta;
+        let delta4 = delta2 * delta2;
+
+        let m2 = self.m2 + other.m2 + delta2 * na * nb / n;
+        let m3 = self.m3
+            + other.m3
+            + delta3 * na * nb * (na - nb) / (n * n)
+            + 3.0 * delta * (na * other.m2 - nb * self.m2) / n;
+        let m4 = self.m4
+            + other.m4
+            + delta4 * na * nb * (na * na - na * nb + nb * nb) / (n * n * n)
+            + 6.0 * delta2 * (na * na * other.m2 + nb * nb * self.m2) / (n * n)
+            + 4.0 * delta * (na * other.m3 - nb * self.m3) / n;
+
+        self.count += other.count;
+        self.mean += delta * nb / n;
+        self.m2 = m2;
+        self.m3 = m3;
+        self.m4 = m4;
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    /// Number of observations.
+    #[must_use]
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Mean (`NaN` if there are no observations).
+    #[must_use]
+    pub fn mean(&self) -> f64 {
+        if self.count == 0 {
+            f64::NAN
+        } else {
+            self.mean
+        }
+    }
+
+    /// Sample variance (`NaN` if there are fewer than two observations).
+    #[must_use]
+    pub fn variance(&self) -> f64 {
+        if self.count < 2 {
+            f64::NAN
+        } else {
+            self.m2 / (self.count as f64 - 1.0)
+        }
+    }
+
+    /// Population variance (`NaN` if there are no observations).
+    #[must_use]
+    pub fn population_variance(&self) -> f64 {
+        if self.count == 0 {
+            f64::NAN
+        } else {
+            self.m2 / self.count as f64
+        }
+    }
+
+    /// Sample standard deviation (`NaN` if there are fewer than two observations).
+    #[must_use]
+    pub fn standard_deviation(&self) -> f64 {
+        self.variance().sqrt()
+    }
+
+    /// Adjusted Fisher-Pearson skewness (`NaN` if there are fewer than three observations).
+    #[must_use]
+    pub fn skewness(&self) -> f64 {
+        if self.count < 3 {
+            return f64::NAN;
+        }
+
+        let n = self.count as f64;
+
+        self.m3 / self.variance().powf(1.5) * n / ((n - 1.0) * (n - 2.0))
+    }
+
+    /// Sample excess kurtosis (`NaN` if there are fewer than four observations).
+    #[must_use]
+    pub fn kurtosis(&self) -> f64 {
+        if self.count < 4 {
+            return f64::NAN;
+        }
+
+        let n = self.count as f64;
+        let variance = self.variance();
+
+        self.m4 / (variance * variance) * n * (n + 1.0) / ((n - 1.0) * (n - 2.0) * (n - 3.0))
+            - 3.0 * (n - 1.0).powi(2) / ((n - 2.0) * (n - 3.0))
+    }
+
+    /// Smallest observation (`NaN` if there are no observations).
+    #[must_use]
+    pub fn min(&self) -> f64 {
+        if self.count == 0 {
+            f64::NAN
+        } else {
+            self.min
+        }
+    }
+
+    /// Largest observation (`NaN` if there are no observations).
+    #[must_use]
+    pub fn max(&self) -> f64 {
+        if self.count == 0 {
+            f64::NAN
+        } else {
+            self.max
+        }
+    }
+}
+
+impl Extend<f64> for StreamingStats {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|x| self.push(x));
+    }
+}
+
+impl FromIterator<f64> for StreamingStats {
+    fn from_iter<I: IntoIterator<Item = f64>>(iter: I) -> Self {
+        let mut stats = Self::new();
+        stats.extend(iter);
+        stats
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_streaming_statistics {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::math::Statistic;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rand_distr::{Distribution, LogNormal};
+
+    // Skewed, fat-tailed data with a non-zero mean.
+    fn data(n: usize) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(2024);
+        let lognormal = LogNormal::new(0.0, 0.5).unwrap();
+
+        (0..n).map(|_| 10.0 + lognormal.sample(&mut rng)).collect()
+    }
+
+    #[test]
+    fn test_streaming_stats_match_batch_statistics() {
+        let x = data(10_000);
+        let stats: StreamingStats = x.iter().copied().collect();
+
+        assert_eq!(stats.count(), 10_000);
+        assert_approx_equal!(stats.mean(), x.mean(), 1e-10);
+        assert_approx_equal!(stats.variance(), x.variance(), 1e-10);
+        assert_approx_equal!(stats.population_variance(), x.population_variance(), 1e-10);
+        assert_approx_equal!(stats.skewness(), x.skewness(), 1e-8);
+        assert_approx_equal!(stats.kurtosis(), x.kurtosis(), 1e-8);
+        assert_eq!(stats.min(), Statistic::min(&x));
+        assert_eq!(stats.max(), Statistic::max(&x));
+    }
+
+    #[test]
+    fn test_streaming_stats_merge_equals_single_pass() {
+        let x = data(10_000);
+        let single_pass: StreamingStats = x.iter().copied().collect();
+
+        // Uneven chunks, summarised separately (e.g. on different threads).
+        let mut merged = StreamingStats::new();
+        for chunk in x.chunks(1_237) {
+            let chunk_stats: StreamingStats = chunk.iter().copied().collect();
+            merged.merge(&chunk_stats);
+        }
+
+        assert_eq!(merged.count(), single_pass.count());
+        assert_approx_equal!(merged.mean(), single_pass.mean(), 1e-10);
+        assert_approx_equal!(merged.variance(), single_pass.variance(), 1e-10);
+        assert_approx_equal!(merged.skewness(), single_pass.skewness(), 1e-10);
+        assert_approx_equal!(merged.kurtosis(), single_pass.kurtosis(), 1e-10);
+        assert_eq!(merged.min(), single_pass.min());
+        assert_eq!(merged.max(), single_pass.max());
+
+        // Merging with an empty set changes nothing.
+        let mut with_empty = single_pass;
+        with_empty.merge(&StreamingStats::new());
+        assert_eq!(with_empty, single_pass);
+    }
+
+    #[test]
+    fn test_streaming_stats_small_samples() {
+        let mut stats = StreamingStats::new();
+        assert!(stats.mean().is_nan());
+        assert!(stats.min().is_nan());
+
+        stats.push(2.0);
+        assert_eq!(stats.mean(), 2.0);
+        assert!(stats.variance().is_nan());
+
+        stats.extend([4.0, 9.0]);
+        assert_approx_equal!(stats.mean(), 5.0, 1e-12);
+        assert_approx_equal!(stats.variance(), 13.0, 1e-12);
+        assert!(stats.kurtosis().is_nan());
+    }
+
+    #[test]
+    fn test_streaming_stats_serde_round_trip() {
+        let mut stats: StreamingStats = data(100).into_iter().collect();
+        let json = serde_json::to_string(&stats).unwrap();
+        let mut restored: StreamingStats = serde_json::from_str(&json).unwrap();
+
+        stats.push(12.0);
+        restored.push(12.0);
+
+        assert_eq!(restored.count(), stats.count());
+        assert_approx_equal!(restored.mean(), stats.mean(), 1e-12);
+        assert_approx_equal!(restored.kurtosis(), stats.kurtosis(), 1e-12);
+    }
+}
@@ -0,0 +1,210 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Kaplan-Meier estimator of the survival function, and the log-rank test.
+//!
+//! Each subject has a duration and an event flag: `true` if the event (e.g.
+//! default) was observed at that time, `false` if the subject was censored
+//! (still alive when last observed). With $d_i$ events among the $n_i$
+//! subjects at risk (durations of at least $t_i$) at each event time $t_i$,
+//! $$
+//! \hat{S}(t) = \prod_{t_i \leq t} \left(1 - \frac{d_i}{n_i}\right).
+//! $$
+//! Subjects censored at an event time count as at risk at that time.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::math::distributions::{ChiSquared, Distribution};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Kaplan-Meier estimate: the time zero and the distinct event times, in
+/// increasing order, with the estimated survival probability just after
+/// each (so the first is 1).
+///
+/// # Panics
+/// Panics if `times` and `events` have different lengths, or a time is
+/// negative or NaN.
+#[must_use]
+pub fn kaplan_meier(times: &[f64], events: &[bool]) -> (Vec<f64>, Vec<f64>) {
+    let mut event_times = vec![0.0];
+    let mut survival = vec![1.0];
+
+    for (time, at_risk, deaths) in risk_table(times, events) {
+        let last = survival[survival.len() - 1];
+        event_times.push(time);
+        survival.push(last * (1.0 - deaths as f64 / at_risk as f64));
+    }
+
+    (event_times, survival)
+}
+
+/// Log-rank test of equal survival functions in two groups: the statistic,
+/// asymptotically $\chi^2_1$ under the null hypothesis, and its p-value.
+///
+/// The statistic is $(O_1 - E_1)^2 / V$, where $O_1$ is the number of
+/// events in the first group, $E_1$ its expectation given the numbers at
+/// risk in both groups at each event time, and $V$ its (hypergeometric)
+/// variance. With no events, the statistic is 0 and the p-value 1.
+///
+/// # Panics
+/// Panics if the times and events of a group have different lengths, or a
+/// time is negative or NaN.
+#[must_use]
+pub fn log_rank_test(
+    times1: &[f64],
+    events1: &[bool],
+    times2: &[f64],
+    events2: &[bool],
+) -> (f64, f64) {
+    validate(times1, events1);
+    validate(times2, events2);
+
+    let pooled_times: Vec<f64> = times1.iter().chain(times2).copied().collect();
+    let pooled_events: Vec<bool> = events1.iter().chain(events2).copied().collect();
+
+    let (mut observed_minus_expected, mut variance) = (0.0, 0.0);
+
+    for (time, at_risk, deaths) in risk_table(&pooled_times, &pooled_events) {
+        let at_risk_1 = times1.iter().filter(|t| **t >= time).count() as f64;
+        let deaths_1 = times1
+            .iter()
+            .zip(events1)
+            .filter(|(t, event)| **t == time && **event)
+            .count() as f64;
+
+        let (n, d) = (at_risk as f64, deaths as f64);
+        observed_minus_expected += deaths_1 - d * at_risk_1 / n;
+        if n > 1.0 {
+            variance += d * (at_risk_1 / n) * (1.0 - at_risk_1 / n) * (n - d) / (n - 1.0);
+        }
+    }
+
+    if variance <= 0.0 {
+        return (0.0, 1.0);
+    }
+
+    let statistic = observed_minus_expected.powi(2) / variance;
+
+    (statistic, 1.0 - ChiSquared::new(1).cdf(statistic))
+}
+
+/// Distinct event times in increasing order, with the number at risk and
+/// the number of events at each.
+fn risk_table(times: &[f64], events: &[bool]) -> Vec<(f64, usize, usize)> {
+    validate(times, events);
+
+    let mut subjects: Vec<(f64, bool)> =
+        times.iter().copied().zip(events.iter().copied()).collect();
+    subjects.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut table = Vec::new();
+    let mut i = 0;
+    while i < subjects.len() {
+        let time = subjects[i].0;
+        let at_risk = subjects.len() - i;
+
+        let mut deaths = 0;
+        while i < subjects.len() && subjects[i].0 == time {
+            deaths += usize::from(subjects[i].1);
+            i += 1;
+        }
+
+        if deaths > 0 {
+            table.push((time, at_risk, deaths));
+        }
+    }
+
+    table
+}
+
+/// Checks that `times` and `events` match and the times are valid.
+fn validate(times: &[f64], events: &[bool]) {
+    assert_eq!(times.len(), events.len(), "Need one event flag per time.");
+    assert!(
+        times.iter().all(|t| *t >= 0.0),
+        "Times must be non-negative."
+    );
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_kaplan_meier {
+    use super::*;
+    use crate::assert_approx_equal;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::Exp1;
+
+    /// Exponential durations with rate `rate`, censored at time 2.
+    fn exponential_sample(rate: f64, n: usize, rng: &mut StdRng) -> (Vec<f64>, Vec<bool>) {
+        (0..n)
+            .map(|_| {
+                let time = rng.sample::<f64, _>(Exp1) / rate;
+                (time.min(2.0), time < 2.0)
+            })
+            .unzip()
+    }
+
+    #[test]
+    fn test_kaplan_meier_small_sample() {
+        let times = [3.0, 1.0, 2.0, 5.0, 2.0, 4.0];
+        let events = [true, true, true, true, false, false];
+
+        let (event_times, survival) = kaplan_meier(&times, &events);
+
+        assert_eq!(event_times, vec![0.0, 1.0, 2.0, 3.0, 5.0]);
+        let expected = [1.0, 5.0 / 6.0, 2.0 / 3.0, 4.0 / 9.0, 0.0];
+        for (s, e) in survival.iter().zip(expected) {
+            assert_approx_equal!(*s, e, 1e-12);
+        }
+    }
+
+    #[test]
+    fn test_kaplan_meier_is_a_survival_function() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let (times, events) = exponential_sample(0.5, 5_000, &mut rng);
+
+        let (event_times, survival) = kaplan_meier(&times, &events);
+
+        assert_eq!((event_times[0], survival[0]), (0.0, 1.0));
+        assert!(event_times.windows(2).all(|w| w[0] < w[1]));
+        assert!(survival.windows(2).all(|w| w[1] <= w[0]));
+
+        // Close to exp(-0.5 t) before the censoring time.
+        for (t, s) in event_times.iter().zip(&survival) {
+            assert_approx_equal!(*s, (-0.5 * t).exp(), 0.03);
+        }
+    }
+
+    #[test]
+    fn test_log_rank_test() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let (times1, events1) = exponential_sample(1.0, 300, &mut rng);
+        let (times2, events2) = exponential_sample(1.0, 300, &mut rng);
+        let (times3, events3) = exponential_sample(1.5, 300, &mut rng);
+
+        let (statistic, p_value) = log_rank_test(&times1, &events1, &times1, &events1);
+        assert_approx_equal!(statistic, 0.0, 1e-12);
+        assert_approx_equal!(p_value, 1.0, 1e-12);
+
+        let (_, p_value) = log_rank_test(&times1, &events1, &times2, &events2);
+        assert!(p_value > 0.05);
+
+        let (statistic, p_value) = log_rank_test(&times1, &events1, &times3, &events3);
+        assert!(statistic > 10.0);
+        assert!(p_value < 0.01);
+    }
+}
@@ -0,0 +1,24 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Survival analysis of right-censored durations (e.g. times to default or
+//! to prepayment).
+//!
+//! - Kaplan-Meier estimate of the survival function, and the log-rank test
+//!   comparing two groups.
+//! - Weibull accelerated failure time regression, fitted by maximum
+//!   likelihood (the exponential model is the case of shape one).
+
+/// Kaplan-Meier estimator and log-rank test.
+pub mod kaplan_meier;
+pub use kaplan_meier::*;
+
+/// Weibull accelerated failure time model.
+pub mod weibull_aft;
+pub use weibull_aft::*;
@@ -0,0 +1,328 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! t-digest: approximate quantiles of a stream in bounded memory.
+//!
+//! The distribution is summarised by weighted centroids, which are kept
+//! small in the tails and large in the middle, so extreme quantiles
+//! (such as those needed for VaR) are estimated most accurately.
+//! The number of centroids is of the order of the compression parameter.
+//!
+//! Reference: Dunning and Ertl (2019), *Computing Extremely Accurate
+//! Quantiles Using t-Digests*. This is the merging variant with the
+//! $k_1$ scale function $k(q) = \frac{\delta}{2\pi} \arcsin(2q - 1)$.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// A cluster of observations.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Centroid {
+    /// Mean of the observations.
+    pub mean: f64,
+    /// Number of observations.
+    pub weight: f64,
+}
+
+/// t-digest quantile sketch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TDigest {
+    /// Compression parameter $\delta$: higher is more accurate, but uses more memory.
+    compression: f64,
+    /// Compressed centroids, sorted by mean.
+    centroids: Vec<Centroid>,
+    /// Observations not yet merged into the centroids.
+    buffer: Vec<Centroid>,
+    /// Total weight, including the buffer.
+    count: f64,
+    min: f64,
+    max: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Default for TDigest {
+    fn default() -> Self {
+        Self::new(100.0)
+    }
+}
+
+impl TDigest {
+    /// Create a new, empty, `TDigest` with the given compression.
+    /// A compression of 100 to 500 is typical.
+    ///
+    /// # Panics
+    /// Panics if `compression` is less than 10.
+    #[must_use]
+    pub fn new(compression: f64) -> Self {
+        assert!(compression >= 10.0, "Compression must be at least 10.");
+
+        Self {
+            compression,
+            centroids: Vec::new(),
+            buffer: Vec::new(),
+            count: 0.0,
+            min: 0.0,
+            max: 0.0,
+        }
+    }
+
+    /// Compression parameter.
+    #[must_use]
+    pub fn compression(&self) -> f64 {
+        self.compression
+    }
+
+    /// Number of observations.
+    #[must_use]
+    pub fn count(&self) -> f64 {
+        self.count
+    }
+
+    /// Add an observation.
+    pub fn push(&mut self, x: f64) {
+        self.update_min_max(x, x);
+        self.count += 1.0;
+        self.buffer.push(Centroid {
+            mean: x,
+            weight: 1.0,
+        });
+
+        if self.buffer.len() >= self.buffer_capacity() {
+            self.compress();
+        }
+    }
+
+    /// Combine with the digest of another set of observations.
+    pub fn merge(&mut self, other: &Self) {
+        if other.count == 0.0 {
+            return;
+        }
+
+        self.update_min_max(other.min, other.max);
+        self.count += other.count;
+        self.buffer.extend_from_slice(&other.centroids);
+        self.buffer.extend_from_slice(&other.buffer);
+
+        self.compress();
+    }
+
+    /// Merge the buffered observations into the centroids.
+    pub fn compress(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+
+        let mut all = std::mem::take(&mut self.centroids);
+        all.append(&mut self.buffer);
+        all.sort_by(|a, b| a.mean.total_cmp(&b.mean));
+
+        let total: f64 = all.iter().map(|c| c.weight).sum();
+        let mut merged = Vec::with_capacity(self.compression.ceil() as usize);
+
+        let mut current = all[0];
+        let mut weight_before = 0.0;
+        let mut weight_limit = total * self.k_inverse(self.k(0.0) + 1.0);
+
+        for c in all.into_iter().skip(1) {
+            if weight_before + current.weight + c.weight <= weight_limit {
+                current.weight += c.weight;
+                current.mean += (c.mean - current.mean) * c.weight / current.weight;
+            } else {
+                weight_before += current.weight;
+                weight_limit = total * self.k_inverse(self.k(weight_before / total) + 1.0);
+                merged.push(current);
+                current = c;
+            }
+        }
+        merged.push(current);
+
+        self.centroids = merged;
+    }
+
+    /// Approximate `q`-quantile (`NaN` if there are no observations).
+    ///
+    /// # Panics
+    /// Panics if `q` is not in $[0, 1]$.
+    pub fn quantile(&mut self, q: f64) -> f64 {
+        assert!((0.0..=1.0).contains(&q), "Quantile must be in [0, 1].");
+
+        self.compress();
+
+        let centroids = &self.centroids;
+        let n = centroids.len();
+
+        if n == 0 {
+            return f64::NAN;
+        }
+        if n == 1 {
+            return centroids[0].mean;
+        }
+
+        let index = q * self.count;
+
+        // Between the minimum and the centre of the first centroid.
+        let first = centroids[0];
+        if index < first.weight / 2.0 {
+            return self.min + (first.mean - self.min) * index / (first.weight / 2.0);
+        }
+
+        // Between the centre of the last centroid and the maximum.
+        let last = centroids[n - 1];
+        if index > self.count - last.weight / 2.0 {
+            let remaining = self.count - index;
+            return self.max - (self.max - last.mean) * remaining / (last.weight / 2.0);
+        }
+
+        // Linear interpolation between the centres of adjacent centroids.
+        let mut centre = first.weight / 2.0;
+        for pair in centroids.windows(2) {
+            let gap = (pair[0].weight + pair[1].weight) / 2.0;
+
+            if index <= centre + gap {
+                return pair[0].mean + (pair[1].mean - pair[0].mean) * (index - centre) / gap;
+            }
+            centre += gap;
+        }
+
+        last.mean
+    }
+
+    /// Number of centroids (after compressing the buffer).
+    pub fn centroid_count(&mut self) -> usize {
+        self.compress();
+        self.centroids.len()
+    }
+
+    fn buffer_capacity(&self) -> usize {
+        5 * self.compression.ceil() as usize
+    }
+
+    fn update_min_max(&mut self, min: f64, max: f64) {
+        if self.count == 0.0 {
+            self.min = min;
+            self.max = max;
+        } else {
+            self.min = self.min.min(min);
+            self.max = self.max.max(max);
+        }
+    }
+
+    /// Scale function $k_1$.
+    fn k(&self, q: f64) -> f64 {
+        self.compression / (2.0 * PI) * (2.0 * q - 1.0).asin()
+    }
+
+    /// Inverse of the scale function.
+    fn k_inverse(&self, k: f64) -> f64 {
+        let k = k.min(self.compression / 4.0);
+
+        ((2.0 * PI * k / self.compression).sin() + 1.0) / 2.0
+    }
+}
+
+impl Extend<f64> for TDigest {
+    fn extend<I: IntoIterator<Item = f64>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|x| self.push(x));
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_t_digest {
+    use super::*;
+    use crate::math::{Distribution, Gaussian};
+    use rand::Rng;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rand_distr::StandardNormal;
+
+    fn relative_error(estimate: f64, exact: f64) -> f64 {
+        ((estimate - exact) / exact).abs()
+    }
+
+    #[test]
+    fn test_t_digest_normal_quantiles() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut digest = TDigest::new(100.0);
+
+        digest.extend((0..1_000_000).map(|_| rng.sample::<f64, _>(StandardNormal)));
+
+        assert_eq!(digest.count(), 1_000_000.0);
+        assert!(digest.centroid_count() <= 100);
+
+        let normal = Gaussian::default();
+        for q in [0.99, 0.975, 0.01, 0.25] {
+            let exact = normal.inv_cdf(q);
+            assert!(relative_error(digest.quantile(q), exact) < 0.005);
+        }
+    }
+
+    #[test]
+    fn test_t_digest_merge() {
+        let mut rng = StdRng::seed_from_u64(8);
+        let x: Vec<f64> = (0..200_000)
+            .map(|_| rng.sample::<f64, _>(StandardNormal))
+            .collect();
+
+        let mut merged = TDigest::new(200.0);
+        for chunk in x.chunks(30_000) {
+            let mut digest = TDigest::new(200.0);
+            digest.extend(chunk.iter().copied());
+            merged.merge(&digest);
+        }
+
+        let mut sorted = x.clone();
+        sorted.sort_by(f64::total_cmp);
+
+        assert_eq!(merged.count(), 200_000.0);
+        assert_eq!(merged.quantile(0.0), sorted[0]);
+        assert_eq!(merged.quantile(1.0), sorted[199_999]);
+        assert!(relative_error(merged.quantile(0.99), sorted[197_999]) < 0.005);
+        assert!(relative_error(merged.quantile(0.05), sorted[9_999]) < 0.005);
+    }
+
+    #[test]
+    fn test_t_digest_small_and_empty() {
+        let mut digest = TDigest::default();
+        assert!(digest.quantile(0.5).is_nan());
+
+        digest.push(3.0);
+        assert_eq!(digest.quantile(0.9), 3.0);
+
+        digest.extend([1.0, 2.0, 4.0, 5.0]);
+        assert_eq!(digest.quantile(0.0), 1.0);
+        assert_eq!(digest.quantile(0.5), 3.0);
+        assert_eq!(digest.quantile(1.0), 5.0);
+    }
+
+    #[test]
+    fn test_t_digest_serde_round_trip() {
+        let mut digest = TDigest::new(50.0);
+        digest.extend((0..10_000).map(|i| f64::from(i % 977)));
+
+        let json = serde_json::to_string(&digest).unwrap();
+        let mut restored: TDigest = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(restored.count(), digest.count());
+        assert!((restored.quantile(0.95) - digest.quantile(0.95)).abs() < 1e-9);
+    }
+}
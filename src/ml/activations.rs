@@ -172,3 +172,50 @@ impl ActivationFunction for DVector<f64> {
         self.map(|x| x.gaussian())
     }
 }
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_activations {
+    use super::*;
+    use crate::autodiff::check_gradient;
+    use num::Complex;
+
+    #[test]
+    fn test_gradients_complex_step() {
+        type Case = (
+            &'static str,
+            for<'v> fn(&Variable<'v>) -> Variable<'v>,
+            fn(Complex<f64>) -> Complex<f64>,
+        );
+
+        // GELU is left out: `num` has no complex error function.
+        let cases: [Case; 7] = [
+            ("sigmoid", |x| x.sigmoid(), |z| (1.0 + (-z).exp()).inv()),
+            ("identity", |x| x.identity(), |z| z),
+            ("logistic", |x| x.logistic(), |z| (1.0 + (-z).exp()).inv()),
+            (
+                "relu",
+                |x| x.relu(),
+                |z| if z.re > 0.0 { z } else { 0.0 * z },
+            ),
+            ("tanh", |x| ActivationFunction::tanh(x), |z| z.tanh()),
+            ("softplus", |x| x.softplus(), |z| (1.0 + z.exp()).ln()),
+            ("gaussian", |x| x.gaussian(), |z| (-z * z).exp()),
+        ];
+
+        // Away from the kink of the ReLU.
+        let points: Vec<Vec<f64>> = [-3.2, -0.9, -0.2, 0.4, 1.1, 4.5]
+            .iter()
+            .map(|x| vec![*x])
+            .collect();
+
+        for (name, f_real, f_complex) in cases {
+            let report = check_gradient(|x| f_real(&x[0]), |z| f_complex(z[0]), &points);
+
+            assert!(report.passed(1e-14), "{name}: {report:?}");
+        }
+    }
+}
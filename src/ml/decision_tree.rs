@@ -0,0 +1,472 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Regression trees with histogram-based split finding.
+//!
+//! The features are binned once into at most 256 quantile bins
+//! ([`FeatureBins`]). Finding the best split of a node then takes one pass
+//! over its rows per feature to build a histogram of the targets, and one
+//! pass over the bins, whatever the number of distinct feature values.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use nalgebra::{DMatrix, DVector};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Quantile bins of each feature.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeatureBins {
+    /// Upper edges of the bins of each feature, in increasing order. A value
+    /// is in the first bin whose edge it does not exceed, or in the last
+    /// (unbounded) bin.
+    edges: Vec<Vec<f64>>,
+}
+
+/// Features binned by [`FeatureBins`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BinnedFeatures {
+    /// Bin of each row, one column per feature.
+    columns: Vec<Vec<u8>>,
+}
+
+/// Settings for growing a regression tree.
+#[derive(Debug, Clone, Copy)]
+pub struct DecisionTreeConfig {
+    /// Maximum depth of the tree (a single leaf has depth zero).
+    pub max_depth: usize,
+    /// Minimum number of rows in each leaf.
+    pub min_samples_leaf: usize,
+    /// Maximum number of bins of each feature, at most 256.
+    pub max_bins: usize,
+}
+
+/// Regression tree, minimizing the squared error of its splits.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecisionTreeRegressor {
+    /// Nodes of the tree; the root is the first.
+    nodes: Vec<Node>,
+    /// Total reduction in squared error of the splits on each feature.
+    feature_gains: Vec<f64>,
+}
+
+/// Node of a regression tree.
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    /// Terminal node, predicting `value`.
+    Leaf { value: f64 },
+    /// Rows with `feature` at most `threshold` (in bin at most `bin`) go
+    /// to the `left` node, the others to the `right` node.
+    Split {
+        feature: usize,
+        bin: u8,
+        threshold: f64,
+        left: usize,
+        right: usize,
+    },
+}
+
+/// Data shared while growing a tree.
+struct Grower<'a, L> {
+    binned: &'a BinnedFeatures,
+    bins: &'a FeatureBins,
+    targets: &'a [f64],
+    features: &'a [usize],
+    config: &'a DecisionTreeConfig,
+    leaf_value: L,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Default for DecisionTreeConfig {
+    fn default() -> Self {
+        Self {
+            max_depth: 6,
+            min_samples_leaf: 5,
+            max_bins: 256,
+        }
+    }
+}
+
+impl FeatureBins {
+    /// Bins of each column of `x`, with edges at its quantiles. A feature
+    /// with at most `max_bins` distinct values has a bin for each.
+    ///
+    /// # Panics
+    /// Panics if `max_bins` is not between 2 and 256.
+    #[must_use]
+    pub fn new(x: &DMatrix<f64>, max_bins: usize) -> Self {
+        assert!(
+            (2..=256).contains(&max_bins),
+            "The number of bins must be between 2 and 256."
+        );
+
+        let edges = x
+            .column_iter()
+            .map(|column| {
+                let mut values: Vec<f64> = column.iter().copied().collect();
+                values.sort_by(f64::total_cmp);
+
+                let mut distinct = values.clone();
+                distinct.dedup();
+
+                let mut edges = if distinct.len() <= max_bins {
+                    distinct
+                } else {
+                    (1..max_bins)
+                        .map(|i| values[i * values.len() / max_bins - 1])
+                        .collect()
+                };
+                edges.dedup();
+                // No value exceeds the largest, so it closes the last bin.
+                if edges.last() == values.last() {
+                    edges.pop();
+                }
+
+                edges
+            })
+            .collect();
+
+        Self { edges }
+    }
+
+    /// Number of features.
+    #[must_use]
+    pub fn n_features(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Bins the rows of `x`.
+    ///
+    /// # Panics
+    /// Panics if `x` has a different number of features.
+    #[must_use]
+    pub fn bin(&self, x: &DMatrix<f64>) -> BinnedFeatures {
+        assert_eq!(x.ncols(), self.n_features());
+
+        let columns = self
+            .edges
+            .iter()
+            .zip(x.column_iter())
+            .map(|(edges, column)| {
+                column
+                    .iter()
+                    .map(|value| edges.partition_point(|edge| edge < value) as u8)
+                    .collect()
+            })
+            .collect();
+
+        BinnedFeatures { columns }
+    }
+
+    /// Number of bins of `feature`.
+    fn n_bins(&self, feature: usize) -> usize {
+        self.edges[feature].len() + 1
+    }
+}
+
+impl BinnedFeatures {
+    /// Number of rows.
+    #[must_use]
+    pub fn n_rows(&self) -> usize {
+        self.columns.first().map_or(0, Vec::len)
+    }
+}
+
+impl DecisionTreeRegressor {
+    /// Fits a regression tree to `y`.
+    ///
+    /// # Panics
+    /// Panics if `x` and `y` have different numbers of rows, or there are
+    /// no rows.
+    #[must_use]
+    pub fn fit(x: &DMatrix<f64>, y: &DVector<f64>, config: &DecisionTreeConfig) -> Self {
+        assert!(
+            x.nrows() == y.len() && !y.is_empty(),
+            "Expected one response per row."
+        );
+
+        let bins = FeatureBins::new(x, config.max_bins);
+        let binned = bins.bin(x);
+        let features: Vec<usize> = (0..x.ncols()).collect();
+        let mut rows: Vec<usize> = (0..y.len()).collect();
+
+        Self::grow(
+            &binned,
+            &bins,
+            y.as_slice(),
+            &mut rows,
+            &features,
+            config,
+            |rows: &[usize]| rows.iter().map(|&row| y[row]).sum::<f64>() / rows.len() as f64,
+        )
+    }
+
+    /// Grows a tree on the `rows` of `binned`, splitting on the `features`
+    /// to minimize the squared error of the `targets`. The value of each
+    /// leaf is `leaf_value` of its rows. The `rows` are reordered.
+    pub(crate) fn grow<L>(
+        binned: &BinnedFeatures,
+        bins: &FeatureBins,
+        targets: &[f64],
+        rows: &mut [usize],
+        features: &[usize],
+        config: &DecisionTreeConfig,
+        leaf_value: L,
+    ) -> Self
+    where
+        L: Fn(&[usize]) -> f64,
+    {
+        let grower = Grower {
+            binned,
+            bins,
+            targets,
+            features,
+            config,
+            leaf_value,
+        };
+        let mut tree = Self {
+            nodes: Vec::new(),
+            feature_gains: vec![0.0; bins.n_features()],
+        };
+        tree.grow_node(&grower, rows, 0);
+
+        tree
+    }
+
+    /// Grows the subtree on `rows`, and returns the index of its root.
+    fn grow_node<L>(&mut self, grower: &Grower<L>, rows: &mut [usize], depth: usize) -> usize
+    where
+        L: Fn(&[usize]) -> f64,
+    {
+        let index = self.nodes.len();
+
+        let split = if depth < grower.config.max_depth {
+            grower.best_split(rows)
+        } else {
+            None
+        };
+
+        match split {
+            Some((feature, bin, gain)) => {
+                let column = &grower.binned.columns[feature];
+                let n_left = partition(rows, |row| column[row] <= bin);
+
+                // Reserve the node, and fill it once the children are known.
+                self.nodes.push(Node::Leaf { value: 0.0 });
+                self.feature_gains[feature] += gain;
+
+                let (left_rows, right_rows) = rows.split_at_mut(n_left);
+                let left = self.grow_node(grower, left_rows, depth + 1);
+                let right = self.grow_node(grower, right_rows, depth + 1);
+
+                self.nodes[index] = Node::Split {
+                    feature,
+                    bin,
+                    threshold: grower.bins.edges[feature][bin as usize],
+                    left,
+                    right,
+                };
+            }
+            None => self.nodes.push(Node::Leaf {
+                value: (grower.leaf_value)(rows),
+            }),
+        }
+
+        index
+    }
+
+    /// Predicts the response for each row of `x`.
+    #[must_use]
+    pub fn predict(&self, x: &DMatrix<f64>) -> DVector<f64> {
+        DVector::from_fn(x.nrows(), |row, _| {
+            self.leaf(|feature, _, threshold| x[(row, feature)] <= threshold)
+        })
+    }
+
+    /// Prediction for `row` of the `binned` features.
+    pub(crate) fn predict_binned(&self, binned: &BinnedFeatures, row: usize) -> f64 {
+        self.leaf(|feature, bin, _| binned.columns[feature][row] <= bin)
+    }
+
+    /// Total reduction in squared error of the splits on each feature.
+    #[must_use]
+    pub fn feature_gains(&self) -> &[f64] {
+        &self.feature_gains
+    }
+
+    /// Number of leaves.
+    #[must_use]
+    pub fn n_leaves(&self) -> usize {
+        self.nodes
+            .iter()
+            .filter(|node| matches!(node, Node::Leaf { .. }))
+            .count()
+    }
+
+    /// Value of the leaf reached by going left at each split where
+    /// `goes_left(feature, bin, threshold)`.
+    fn leaf<F>(&self, goes_left: F) -> f64
+    where
+        F: Fn(usize, u8, f64) -> bool,
+    {
+        let mut index = 0;
+
+        loop {
+            match self.nodes[index] {
+                Node::Leaf { value } => return value,
+                Node::Split {
+                    feature,
+                    bin,
+                    threshold,
+                    left,
+                    right,
+                } => {
+                    index = if goes_left(feature, bin, threshold) {
+                        left
+                    } else {
+                        right
+                    };
+                }
+            }
+        }
+    }
+}
+
+impl<L> Grower<'_, L> {
+    /// Feature and bin of the split of `rows` with the largest reduction in
+    /// squared error, and the reduction, if any split reduces it.
+    fn best_split(&self, rows: &[usize]) -> Option<(usize, u8, f64)> {
+        let n = rows.len();
+        let min_leaf = self.config.min_samples_leaf.max(1);
+        if n < 2 * min_leaf {
+            return None;
+        }
+
+        let total: f64 = rows.iter().map(|&row| self.targets[row]).sum();
+        let parent_score = total * total / n as f64;
+        let mut best: Option<(usize, u8, f64)> = None;
+
+        for &feature in self.features {
+            let column = &self.binned.columns[feature];
+            let n_bins = self.bins.n_bins(feature);
+
+            let mut sums = vec![0.0; n_bins];
+            let mut counts = vec![0_usize; n_bins];
+            for &row in rows {
+                let bin = column[row] as usize;
+                sums[bin] += self.targets[row];
+                counts[bin] += 1;
+            }
+
+            let (mut left_sum, mut left_count) = (0.0, 0);
+
+            for bin in 0..n_bins - 1 {
+                left_sum += sums[bin];
+                left_count += counts[bin];
+
+                if left_count < min_leaf {
+                    continue;
+                }
+                let right_count = n - left_count;
+                if right_count < min_leaf {
+                    break;
+                }
+
+                let right_sum = total - left_sum;
+                let gain = left_sum * left_sum / left_count as f64
+                    + right_sum * right_sum / right_count as f64
+                    - parent_score;
+
+                if gain > best.map_or(0.0, |(_, _, best_gain)| best_gain) {
+                    best = Some((feature, bin as u8, gain));
+                }
+            }
+        }
+
+        best
+    }
+}
+
+/// Reorders `rows` so those satisfying `predicate` come first, and returns
+/// their number.
+fn partition<P>(rows: &mut [usize], predicate: P) -> usize
+where
+    P: Fn(usize) -> bool,
+{
+    let mut n_true = 0;
+
+    for i in 0..rows.len() {
+        if predicate(rows[i]) {
+            rows.swap(i, n_true);
+            n_true += 1;
+        }
+    }
+
+    n_true
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_decision_tree {
+    use super::*;
+    use crate::assert_approx_equal;
+
+    #[test]
+    fn test_step_function_is_fitted_exactly() {
+        let x = DMatrix::from_fn(40, 2, |i, j| if j == 0 { i as f64 } else { (i % 3) as f64 });
+        let y = DVector::from_fn(40, |i, _| if i < 15 { 1.0 } else { 3.0 });
+        let tree = DecisionTreeRegressor::fit(&x, &y, &DecisionTreeConfig::default());
+
+        assert_eq!(tree.n_leaves(), 2);
+        assert_eq!(tree.predict(&x), y);
+        assert_approx_equal!(tree.feature_gains()[0], 15.0 * 25.0 / 40.0 * 4.0, 1e-10);
+        assert_approx_equal!(tree.feature_gains()[1], 0.0, 1e-15);
+    }
+
+    #[test]
+    fn test_binned_and_raw_predictions_agree() {
+        let x = DMatrix::from_fn(500, 3, |i, j| ((i * (j + 7) * 31) % 97) as f64 / 9.7 - 5.0);
+        let y = DVector::from_fn(500, |i, _| (x[(i, 0)] * x[(i, 1)]).sin() + x[(i, 2)]);
+        let config = DecisionTreeConfig {
+            max_bins: 16,
+            ..DecisionTreeConfig::default()
+        };
+        let tree = DecisionTreeRegressor::fit(&x, &y, &config);
+        let bins = FeatureBins::new(&x, config.max_bins);
+        let binned = bins.bin(&x);
+
+        let raw = tree.predict(&x);
+        for row in 0..x.nrows() {
+            assert_eq!(tree.predict_binned(&binned, row), raw[row]);
+        }
+    }
+
+    #[test]
+    fn test_quantile_bins() {
+        let x = DMatrix::from_fn(1_000, 1, |i, _| i as f64);
+        let bins = FeatureBins::new(&x, 10);
+        let binned = bins.bin(&x);
+
+        assert_eq!(bins.n_bins(0), 10);
+        for bin in 0..10_u8 {
+            let count = binned.columns[0].iter().filter(|b| **b == bin).count();
+            assert_eq!(count, 100, "{bin}");
+        }
+    }
+}
@@ -0,0 +1,476 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Fama-MacBeth estimation of the risk premia of asset characteristics.
+//!
+//! Each period, the asset returns are regressed cross-sectionally on the
+//! characteristics (with an intercept):
+//!
+//! $$
+//! r_{i,t} = \lambda_{0,t} + \sum_k \lambda_{k,t} x_{k,i,t} + \varepsilon_{i,t},
+//! $$
+//!
+//! and the risk premia are the time series means of the period estimates,
+//! $\hat{\lambda} = \frac{1}{T} \sum_t \hat{\lambda}_t$. Their standard
+//! errors are Newey-West HAC standard errors of the means (see
+//! [`crate::math::newey_west_long_run_covariance`]), which with bandwidth
+//! zero are the classical Fama-MacBeth standard errors (without the
+//! degrees of freedom correction).
+//!
+//! Each cross-section only uses the assets with a return and all the
+//! characteristics in that period, so assets entering or leaving the panel
+//! are not dropped from the other periods.
+//!
+//! References:
+//!     - Fama, E. F. and MacBeth, J. D. (1973). Risk, Return, and
+//!       Equilibrium: Empirical Tests. Journal of Political Economy, 81(3).
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use crate::math::{newey_west_bandwidth, newey_west_long_run_covariance, newey_west_t_stats};
+use nalgebra::{DMatrix, DVector};
+use polars::prelude::*;
+use std::collections::{BTreeMap, HashMap};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Panel of asset returns and characteristics.
+///
+/// Both `DataFrame`s are in long format, keyed by a `"date"` column (any
+/// integer or temporal type) and an `"asset"` column (any type that casts
+/// to a string). `returns` has the returns in a `"return"` column, and
+/// `characteristics` one column per characteristic.
+#[derive(Debug, Clone)]
+pub struct FamaMacBethInput<'a> {
+    /// Asset returns: `"date"`, `"asset"`, and `"return"` columns.
+    pub returns: &'a DataFrame,
+    /// Asset characteristics: `"date"`, `"asset"`, and the `factors` columns.
+    pub characteristics: &'a DataFrame,
+    /// Names of the characteristic columns to estimate premia for.
+    pub factors: Vec<String>,
+}
+
+/// Fama-MacBeth risk premia estimates.
+#[derive(Debug, Clone)]
+pub struct FamaMacBethOutput {
+    /// Names of the estimates: `"intercept"`, then the factors.
+    pub names: Vec<String>,
+    /// Risk premia: the means of the period estimates.
+    pub premia: DVector<f64>,
+    /// Newey-West standard errors of the premia.
+    pub standard_errors: DVector<f64>,
+    /// t-statistics of the premia.
+    pub t_stats: DVector<f64>,
+    /// Bartlett kernel bandwidth of the standard errors.
+    pub bandwidth: usize,
+    /// Date of each cross-sectional regression, in the type of the input
+    /// `"date"` column.
+    pub dates: Series,
+    /// Period estimates, one row per date and one column per estimate.
+    pub period_premia: DMatrix<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl<'a> FamaMacBethInput<'a> {
+    /// Creates a new `FamaMacBethInput`.
+    #[must_use]
+    pub fn new(returns: &'a DataFrame, characteristics: &'a DataFrame, factors: &[&str]) -> Self {
+        Self {
+            returns,
+            characteristics,
+            factors: factors.iter().map(|factor| (*factor).to_string()).collect(),
+        }
+    }
+
+    /// Runs the cross-sectional regressions and aggregates the period
+    /// estimates, with standard errors using the given Bartlett kernel
+    /// `bandwidth`, or Newey and West's rule of thumb if `None`.
+    ///
+    /// Periods with no more assets than estimates, or with collinear
+    /// characteristics, are skipped.
+    ///
+    /// # Errors
+    /// - `PolarsError` if a column is missing or has an unsupported type.
+    /// - `InvalidArgument` if there are no factors, or fewer than two
+    ///   periods can be estimated.
+    pub fn fit(&self, bandwidth: Option<usize>) -> Result<FamaMacBethOutput, RustQuantError> {
+        if self.factors.is_empty() {
+            return Err(RustQuantError::InvalidArgument(
+                "Expected at least one factor.".to_string(),
+            ));
+        }
+        let n_estimates = self.factors.len() + 1;
+
+        // Characteristics of each (date, asset), when none are missing.
+        let characteristic_dates = dates(self.characteristics)?;
+        let characteristic_assets = assets(self.characteristics)?;
+        let characteristic_columns = self
+            .factors
+            .iter()
+            .map(|factor| {
+                Ok(self
+                    .characteristics
+                    .column(factor)?
+                    .cast(&DataType::Float64)?
+                    .f64()?
+                    .into_iter()
+                    .collect::<Vec<_>>())
+            })
+            .collect::<Result<Vec<_>, RustQuantError>>()?;
+
+        let mut characteristics: HashMap<(i64, String), Vec<f64>> = HashMap::new();
+        for (row, (date, asset)) in characteristic_dates
+            .into_iter()
+            .zip(characteristic_assets)
+            .enumerate()
+        {
+            let values: Option<Vec<f64>> = characteristic_columns
+                .iter()
+                .map(|column| column[row])
+                .collect();
+
+            if let (Some(date), Some(asset), Some(values)) = (date, asset, values) {
+                characteristics.insert((date, asset), values);
+            }
+        }
+
+        // Cross-section of (return, characteristics) for each date.
+        let return_values: Vec<Option<f64>> = self
+            .returns
+            .column("return")?
+            .cast(&DataType::Float64)?
+            .f64()?
+            .into_iter()
+            .collect();
+
+        let mut cross_sections: BTreeMap<i64, Vec<(f64, &[f64])>> = BTreeMap::new();
+        for ((date, asset), value) in dates(self.returns)?
+            .into_iter()
+            .zip(assets(self.returns)?)
+            .zip(return_values)
+        {
+            if let (Some(date), Some(asset), Some(value)) = (date, asset, value) {
+                if let Some(x) = characteristics.get(&(date, asset)) {
+                    cross_sections
+                        .entry(date)
+                        .or_default()
+                        .push((value, x.as_slice()));
+                }
+            }
+        }
+
+        let mut used_dates = Vec::new();
+        let mut estimates = Vec::new();
+        for (date, cross_section) in &cross_sections {
+            if let Some(lambda) = cross_sectional_regression(cross_section, n_estimates) {
+                used_dates.push(*date);
+                estimates.extend(lambda.iter());
+            }
+        }
+
+        let n_periods = used_dates.len();
+        if n_periods < 2 {
+            return Err(RustQuantError::InvalidArgument(
+                "Expected at least two periods with enough assets to regress.".to_string(),
+            ));
+        }
+
+        let period_premia = DMatrix::from_row_slice(n_periods, n_estimates, &estimates);
+        let premia = period_premia.row_mean().transpose();
+
+        let bandwidth = bandwidth.unwrap_or_else(|| newey_west_bandwidth(n_periods));
+        let vcov = newey_west_long_run_covariance(&period_premia, bandwidth) / n_periods as f64;
+        let standard_errors = vcov.diagonal().map(f64::sqrt);
+        let t_stats = newey_west_t_stats(&premia, &vcov);
+
+        let dates = Series::new("date", used_dates).cast(self.returns.column("date")?.dtype())?;
+        let names = std::iter::once("intercept".to_string())
+            .chain(self.factors.iter().cloned())
+            .collect();
+
+        Ok(FamaMacBethOutput {
+            names,
+            premia,
+            standard_errors,
+            t_stats,
+            bandwidth,
+            dates,
+            period_premia,
+        })
+    }
+}
+
+impl FamaMacBethOutput {
+    /// Summary table with one row per estimate: `"name"`, `"premium"`,
+    /// `"std_error"`, and `"t_stat"` columns.
+    ///
+    /// # Errors
+    /// `PolarsError` if the `DataFrame` cannot be built.
+    pub fn summary(&self) -> Result<DataFrame, RustQuantError> {
+        Ok(df!(
+            "name" => self.names.clone(),
+            "premium" => self.premia.as_slice(),
+            "std_error" => self.standard_errors.as_slice(),
+            "t_stat" => self.t_stats.as_slice(),
+        )?)
+    }
+
+    /// Period estimates as a `DataFrame`, with a `"date"` column and one
+    /// column per estimate.
+    ///
+    /// # Errors
+    /// `PolarsError` if the `DataFrame` cannot be built.
+    pub fn period_premia_frame(&self) -> Result<DataFrame, RustQuantError> {
+        let columns = std::iter::once(self.dates.clone())
+            .chain(
+                self.period_premia
+                    .column_iter()
+                    .zip(&self.names)
+                    .map(|(column, name)| {
+                        Series::new(name, column.iter().copied().collect::<Vec<_>>())
+                    }),
+            )
+            .collect();
+
+        Ok(DataFrame::new(columns)?)
+    }
+}
+
+/// Dates of the rows of `df`, as integers.
+fn dates(df: &DataFrame) -> Result<Vec<Option<i64>>, RustQuantError> {
+    Ok(df
+        .column("date")?
+        .cast(&DataType::Int64)?
+        .i64()?
+        .into_iter()
+        .collect())
+}
+
+/// Assets of the rows of `df`, as strings.
+fn assets(df: &DataFrame) -> Result<Vec<Option<String>>, RustQuantError> {
+    Ok(df
+        .column("asset")?
+        .cast(&DataType::String)?
+        .str()?
+        .into_iter()
+        .map(|asset| asset.map(str::to_string))
+        .collect())
+}
+
+/// Least squares estimates of the intercept and slopes of the returns on
+/// the characteristics, or `None` if they are not identified.
+fn cross_sectional_regression(
+    cross_section: &[(f64, &[f64])],
+    n_estimates: usize,
+) -> Option<DVector<f64>> {
+    let n_assets = cross_section.len();
+    if n_assets <= n_estimates {
+        return None;
+    }
+
+    let x = DMatrix::from_fn(n_assets, n_estimates, |i, j| {
+        if j == 0 {
+            1.0
+        } else {
+            cross_section[i].1[j - 1]
+        }
+    });
+    let y = DVector::from_iterator(n_assets, cross_section.iter().map(|(r, _)| *r));
+
+    let xtx = x.transpose() * &x;
+    let cholesky = xtx.cholesky()?;
+
+    Some(cholesky.solve(&(x.transpose() * y)))
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_fama_macbeth {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::StandardNormal;
+
+    const PREMIA: [f64; 3] = [0.002, 0.005, -0.003];
+
+    /// Panel of `n_periods` dates and `n_assets` assets with two
+    /// characteristics, whose period premia are `PREMIA` plus AR(1) shocks
+    /// with autocorrelation `phi` and unit-free standard deviation 0.01.
+    /// Each return and each characteristic row is missing with probability
+    /// `missing`.
+    fn panel(
+        n_periods: usize,
+        n_assets: usize,
+        phi: f64,
+        missing: f64,
+        seed: u64,
+    ) -> (DataFrame, DataFrame) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let innovation_scale = 0.01 * (1.0 - phi * phi).sqrt();
+        let mut shocks = [0.0; 3];
+
+        let (mut return_dates, mut return_assets, mut returns) = (vec![], vec![], vec![]);
+        let (mut dates, mut assets, mut size, mut value) = (vec![], vec![], vec![], vec![]);
+
+        for t in 0..n_periods {
+            for shock in &mut shocks {
+                *shock = phi * *shock + innovation_scale * rng.sample::<f64, _>(StandardNormal);
+            }
+
+            for i in 0..n_assets {
+                let x: [f64; 2] = [rng.sample(StandardNormal), rng.sample(StandardNormal)];
+                let r = PREMIA[0]
+                    + shocks[0]
+                    + (PREMIA[1] + shocks[1]) * x[0]
+                    + (PREMIA[2] + shocks[2]) * x[1]
+                    + 0.02 * rng.sample::<f64, _>(StandardNormal);
+
+                if rng.gen::<f64>() >= missing {
+                    return_dates.push(t as i32);
+                    return_assets.push(format!("asset_{i}"));
+                    returns.push(r);
+                }
+                if rng.gen::<f64>() >= missing {
+                    dates.push(t as i32);
+                    assets.push(format!("asset_{i}"));
+                    size.push(x[0]);
+                    value.push(x[1]);
+                }
+            }
+        }
+
+        let returns = df!(
+            "date" => Series::new("date", return_dates).cast(&DataType::Date).unwrap(),
+            "asset" => return_assets,
+            "return" => returns,
+        )
+        .unwrap();
+        let characteristics = df!(
+            "date" => Series::new("date", dates).cast(&DataType::Date).unwrap(),
+            "asset" => assets,
+            "size" => size,
+            "value" => value,
+        )
+        .unwrap();
+
+        (returns, characteristics)
+    }
+
+    #[test]
+    fn test_premia_are_unbiased() {
+        // Average the estimates over independent panels, each with 10% of
+        // the returns and characteristics missing.
+        let n_panels = 20;
+        let mut mean = DVector::zeros(3);
+        let mut standard_error = DVector::zeros(3);
+
+        for seed in 0..n_panels {
+            let (returns, characteristics) = panel(120, 50, 0.0, 0.1, seed);
+            let output = FamaMacBethInput::new(&returns, &characteristics, &["size", "value"])
+                .fit(Some(0))
+                .unwrap();
+
+            assert_eq!(output.names, ["intercept", "size", "value"]);
+            assert_eq!(output.dates.len(), 120);
+            mean += &output.premia / n_panels as f64;
+            standard_error += &output.standard_errors / n_panels as f64;
+        }
+
+        // The mean of the estimates has standard error of about
+        // `standard_error / sqrt(n_panels)`.
+        for k in 0..3 {
+            let z = (mean[k] - PREMIA[k]) / (standard_error[k] / (n_panels as f64).sqrt());
+            assert!(z.abs() < 3.5, "estimate {k}: {} (z = {z})", mean[k]);
+        }
+    }
+
+    #[test]
+    fn test_missing_assets_are_aligned_per_period() {
+        // The first asset has no characteristics in period 0, and the second
+        // no return in period 1: each is only left out of that period.
+        let returns = df!(
+            "date" => [0i64, 0, 0, 0, 1, 1, 1],
+            "asset" => ["a", "b", "c", "d", "a", "c", "d"],
+            "return" => [0.1, 0.2, 0.3, 0.5, 0.2, 0.6, 1.0],
+        )
+        .unwrap();
+        let characteristics = df!(
+            "date" => [0i64, 0, 0, 1, 1, 1, 1],
+            "asset" => ["b", "c", "d", "a", "b", "c", "d"],
+            "beta" => [1.0, 2.0, 4.0, 1.0, 2.0, 3.0, 5.0],
+        )
+        .unwrap();
+
+        let output = FamaMacBethInput::new(&returns, &characteristics, &["beta"])
+            .fit(Some(0))
+            .unwrap();
+
+        // Period 0 regresses b, c, d: r = 0.1 beta + 0.1.
+        // Period 1 regresses a, c, d: r = 0.2 beta.
+        let expected = [[0.1, 0.1], [0.0, 0.2]];
+        for (t, row) in expected.iter().enumerate() {
+            for (k, value) in row.iter().enumerate() {
+                assert!((output.period_premia[(t, k)] - value).abs() < 1e-12);
+            }
+        }
+        assert!((output.premia[1] - 0.15).abs() < 1e-12);
+
+        let frame = output.period_premia_frame().unwrap();
+        assert_eq!(frame.get_column_names(), ["date", "intercept", "beta"]);
+        assert_eq!(output.summary().unwrap().height(), 2);
+    }
+
+    #[test]
+    fn test_newey_west_errors_widen_with_autocorrelation() {
+        let fit = |phi: f64, bandwidth: usize| {
+            let (returns, characteristics) = panel(400, 30, phi, 0.0, 7);
+            FamaMacBethInput::new(&returns, &characteristics, &["size", "value"])
+                .fit(Some(bandwidth))
+                .unwrap()
+                .standard_errors
+        };
+
+        // Without autocorrelation the correction hardly matters, with
+        // autocorrelation 0.7 the long-run variance is (1 + 0.7) / (1 - 0.7)
+        // times larger, and the Newey-West errors pick up most of it.
+        let bandwidth = 12;
+        let (white, newey_west) = (fit(0.0, 0), fit(0.0, bandwidth));
+        let (white_ar, newey_west_ar) = (fit(0.7, 0), fit(0.7, bandwidth));
+
+        for k in 0..3 {
+            let ratio = newey_west[k] / white[k];
+            let ratio_ar = newey_west_ar[k] / white_ar[k];
+
+            assert!((0.75..1.25).contains(&ratio), "factor {k}: {ratio}");
+            assert!(ratio_ar > 1.6, "factor {k}: {ratio_ar}");
+        }
+    }
+
+    #[test]
+    fn test_too_few_periods() {
+        let (returns, characteristics) = panel(1, 20, 0.0, 0.0, 1);
+
+        assert!(FamaMacBethInput::new(&returns, &characteristics, &["size"])
+            .fit(None)
+            .is_err());
+        assert!(FamaMacBethInput::new(&returns, &characteristics, &[])
+            .fit(None)
+            .is_err());
+    }
+}
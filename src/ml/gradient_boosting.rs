@@ -0,0 +1,594 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Gradient boosting of regression trees (Friedman's gradient boosting
+//! machine).
+//!
+//! Each round fits a [`DecisionTreeRegressor`] to the negative gradient of
+//! the loss (the pseudo-residuals) on a subsample of the rows and features,
+//! then sets the value of each leaf to the optimal step for the loss of the
+//! residuals in it, and adds the tree, scaled by the learning rate, to the
+//! model. The features are binned once, so each split is found from
+//! histograms.
+//!
+//! References:
+//!     - Friedman, J. H. (2001). Greedy Function Approximation: A Gradient
+//!       Boosting Machine. Annals of Statistics, 29(5).
+//!     - Friedman, J. H. (2002). Stochastic Gradient Boosting. Computational
+//!       Statistics & Data Analysis, 38(4).
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{pinball_loss, BinnedFeatures, DecisionTreeConfig, DecisionTreeRegressor, FeatureBins};
+use crate::error::RustQuantError;
+use nalgebra::{DMatrix, DVector};
+use rand::{rngs::StdRng, seq::index, SeedableRng};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Loss minimized by gradient boosting.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BoostingObjective {
+    /// Squared error, $r^2$.
+    SquaredError,
+    /// Huber loss, quadratic for residuals up to `delta` in absolute value
+    /// and linear beyond, so outliers have bounded influence.
+    Huber {
+        /// Residual at which the loss becomes linear.
+        delta: f64,
+    },
+    /// Pinball loss at quantile `tau`, to predict the `tau` quantile of
+    /// the response.
+    Quantile {
+        /// Quantile to predict, in (0, 1).
+        tau: f64,
+    },
+}
+
+/// Settings for gradient boosting.
+#[derive(Debug, Clone, Copy)]
+pub struct GradientBoostingConfig {
+    /// Maximum number of boosting rounds (trees).
+    pub n_rounds: usize,
+    /// Scale of each tree added to the model.
+    pub learning_rate: f64,
+    /// Maximum depth of each tree.
+    pub max_depth: usize,
+    /// Minimum number of rows in each leaf.
+    pub min_samples_leaf: usize,
+    /// Fraction of the rows each tree is fitted to, in (0, 1].
+    pub row_subsample: f64,
+    /// Fraction of the features each tree may split on, in (0, 1].
+    pub column_subsample: f64,
+    /// Maximum number of bins of each feature, at most 256.
+    pub max_bins: usize,
+    /// Loss to minimize.
+    pub objective: BoostingObjective,
+    /// With a validation set, training stops once the validation loss has
+    /// not improved for this many rounds, and the model keeps the trees up
+    /// to the round with the lowest validation loss (possibly none).
+    pub early_stopping_rounds: Option<usize>,
+    /// Seed of the random number generator used for subsampling.
+    pub seed: u64,
+}
+
+/// Gradient boosted regression trees.
+#[derive(Debug, Clone)]
+pub struct GradientBoostingRegressor {
+    /// Loss the model minimizes.
+    objective: BoostingObjective,
+    /// Scale of each tree.
+    learning_rate: f64,
+    /// Prediction before the first tree.
+    initial: f64,
+    /// Trees of the model.
+    trees: Vec<DecisionTreeRegressor>,
+    /// Mean validation loss after each round, starting with no trees.
+    validation_losses: Vec<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Default for GradientBoostingConfig {
+    fn default() -> Self {
+        Self {
+            n_rounds: 100,
+            learning_rate: 0.1,
+            max_depth: 3,
+            min_samples_leaf: 5,
+            row_subsample: 1.0,
+            column_subsample: 1.0,
+            max_bins: 256,
+            objective: BoostingObjective::SquaredError,
+            early_stopping_rounds: None,
+            seed: 42,
+        }
+    }
+}
+
+impl BoostingObjective {
+    /// Loss of a `residual` $r = y - \hat{y}$.
+    #[must_use]
+    pub fn loss(&self, residual: f64) -> f64 {
+        match *self {
+            Self::SquaredError => residual * residual,
+            Self::Huber { delta } => {
+                if residual.abs() <= delta {
+                    0.5 * residual * residual
+                } else {
+                    delta * (residual.abs() - 0.5 * delta)
+                }
+            }
+            Self::Quantile { tau } => pinball_loss(residual, tau),
+        }
+    }
+
+    /// Negative gradient of the loss with respect to the prediction, up to
+    /// a constant factor.
+    fn pseudo_residual(&self, residual: f64) -> f64 {
+        match *self {
+            Self::SquaredError => residual,
+            Self::Huber { delta } => residual.clamp(-delta, delta),
+            Self::Quantile { tau } => {
+                if residual < 0.0 {
+                    tau - 1.0
+                } else {
+                    tau
+                }
+            }
+        }
+    }
+
+    /// Constant minimizing (for the Huber loss, approximately) the loss of
+    /// the `residuals`.
+    fn optimal_constant(&self, residuals: &mut [f64]) -> f64 {
+        match *self {
+            Self::SquaredError => residuals.iter().sum::<f64>() / residuals.len() as f64,
+            // One step from the median (Friedman, 2001).
+            Self::Huber { delta } => {
+                let median = quantile(residuals, 0.5);
+
+                median
+                    + residuals
+                        .iter()
+                        .map(|r| (r - median).clamp(-delta, delta))
+                        .sum::<f64>()
+                        / residuals.len() as f64
+            }
+            Self::Quantile { tau } => quantile(residuals, tau),
+        }
+    }
+
+    /// Checks the parameters of the objective.
+    fn validate(&self) -> Result<(), RustQuantError> {
+        let valid = match *self {
+            Self::SquaredError => true,
+            Self::Huber { delta } => delta > 0.0,
+            Self::Quantile { tau } => tau > 0.0 && tau < 1.0,
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            Err(RustQuantError::InvalidArgument(
+                "The Huber delta must be positive, and the quantile in (0, 1).".to_string(),
+            ))
+        }
+    }
+}
+
+impl GradientBoostingRegressor {
+    /// Fits gradient boosted trees to `y`, for `config.n_rounds` rounds.
+    ///
+    /// # Errors
+    /// `InvalidArgument` if the inputs have inconsistent shapes, or the
+    /// settings are invalid.
+    pub fn fit(
+        x: &DMatrix<f64>,
+        y: &DVector<f64>,
+        config: &GradientBoostingConfig,
+    ) -> Result<Self, RustQuantError> {
+        Self::fit_impl(x, y, None, config)
+    }
+
+    /// Fits gradient boosted trees to `y`, recording the loss on the
+    /// validation set (`x_validation`, `y_validation`) after each round,
+    /// and stopping early if `config.early_stopping_rounds` is set.
+    ///
+    /// # Errors
+    /// `InvalidArgument` if the inputs have inconsistent shapes, or the
+    /// settings are invalid.
+    pub fn fit_with_validation(
+        x: &DMatrix<f64>,
+        y: &DVector<f64>,
+        x_validation: &DMatrix<f64>,
+        y_validation: &DVector<f64>,
+        config: &GradientBoostingConfig,
+    ) -> Result<Self, RustQuantError> {
+        if x_validation.nrows() != y_validation.len() || x_validation.ncols() != x.ncols() {
+            return Err(RustQuantError::InvalidArgument(
+                "Expected one validation response per row, and the same features.".to_string(),
+            ));
+        }
+
+        Self::fit_impl(x, y, Some((x_validation, y_validation)), config)
+    }
+
+    fn fit_impl(
+        x: &DMatrix<f64>,
+        y: &DVector<f64>,
+        validation: Option<(&DMatrix<f64>, &DVector<f64>)>,
+        config: &GradientBoostingConfig,
+    ) -> Result<Self, RustQuantError> {
+        let (n_rows, n_features) = x.shape();
+
+        if n_rows != y.len() || n_rows == 0 || n_features == 0 {
+            return Err(RustQuantError::InvalidArgument(
+                "Expected one response per row, and at least one row and feature.".to_string(),
+            ));
+        }
+        let fraction = 0.0..=1.0;
+        if !(config.learning_rate > 0.0
+            && config.row_subsample > 0.0
+            && fraction.contains(&config.row_subsample)
+            && config.column_subsample > 0.0
+            && fraction.contains(&config.column_subsample)
+            && (2..=256).contains(&config.max_bins))
+        {
+            return Err(RustQuantError::InvalidArgument(
+                "Invalid learning rate, subsampling fraction, or number of bins.".to_string(),
+            ));
+        }
+        config.objective.validate()?;
+
+        let objective = config.objective;
+        let tree_config = DecisionTreeConfig {
+            max_depth: config.max_depth,
+            min_samples_leaf: config.min_samples_leaf,
+            max_bins: config.max_bins,
+        };
+
+        let bins = FeatureBins::new(x, config.max_bins);
+        let binned = bins.bin(x);
+        let initial = objective.optimal_constant(&mut y.as_slice().to_vec());
+        let mut predictions = vec![initial; n_rows];
+
+        let mut validation = validation.map(|(x, y)| Validation::new(bins.bin(x), y, initial));
+        let mut validation_losses = Vec::new();
+        let mut best_round = 0;
+        if let Some(validation) = &validation {
+            validation_losses.push(validation.loss(&objective));
+        }
+
+        let n_sampled_rows = ((n_rows as f64 * config.row_subsample).ceil() as usize).max(1);
+        let n_sampled_features =
+            ((n_features as f64 * config.column_subsample).ceil() as usize).max(1);
+        let mut rng = StdRng::seed_from_u64(config.seed);
+        let mut trees = Vec::with_capacity(config.n_rounds);
+
+        for _ in 0..config.n_rounds {
+            let residuals: Vec<f64> = y
+                .iter()
+                .zip(&predictions)
+                .map(|(y, prediction)| y - prediction)
+                .collect();
+            let pseudo_residuals: Vec<f64> = residuals
+                .iter()
+                .map(|&r| objective.pseudo_residual(r))
+                .collect();
+
+            let mut rows = sample(&mut rng, n_rows, n_sampled_rows);
+            let features = sample(&mut rng, n_features, n_sampled_features);
+
+            let tree = DecisionTreeRegressor::grow(
+                &binned,
+                &bins,
+                &pseudo_residuals,
+                &mut rows,
+                &features,
+                &tree_config,
+                |leaf_rows: &[usize]| {
+                    let mut leaf_residuals: Vec<f64> =
+                        leaf_rows.iter().map(|&row| residuals[row]).collect();
+                    objective.optimal_constant(&mut leaf_residuals)
+                },
+            );
+
+            for (row, prediction) in predictions.iter_mut().enumerate() {
+                *prediction += config.learning_rate * tree.predict_binned(&binned, row);
+            }
+
+            trees.push(tree);
+
+            if let Some(validation) = &mut validation {
+                validation.add_tree(&trees[trees.len() - 1], config.learning_rate);
+                let loss = validation.loss(&objective);
+                validation_losses.push(loss);
+
+                if loss < validation_losses[best_round] {
+                    best_round = trees.len();
+                } else if config
+                    .early_stopping_rounds
+                    .is_some_and(|patience| trees.len() - best_round >= patience)
+                {
+                    break;
+                }
+            }
+        }
+
+        if validation.is_some() && config.early_stopping_rounds.is_some() {
+            trees.truncate(best_round);
+        }
+
+        Ok(Self {
+            objective,
+            learning_rate: config.learning_rate,
+            initial,
+            trees,
+            validation_losses,
+        })
+    }
+
+    /// Predicts the response for each row of `x` (for the quantile
+    /// objective, its `tau` quantile).
+    #[must_use]
+    pub fn predict(&self, x: &DMatrix<f64>) -> DVector<f64> {
+        self.trees.iter().fold(
+            DVector::from_element(x.nrows(), self.initial),
+            |sum, tree| sum + tree.predict(x) * self.learning_rate,
+        )
+    }
+
+    /// Mean loss of the predictions for `x`, given responses `y`.
+    #[must_use]
+    pub fn score(&self, x: &DMatrix<f64>, y: &DVector<f64>) -> f64 {
+        (y - self.predict(x))
+            .iter()
+            .map(|&residual| self.objective.loss(residual))
+            .sum::<f64>()
+            / y.len() as f64
+    }
+
+    /// Number of trees in the model.
+    #[must_use]
+    pub fn n_trees(&self) -> usize {
+        self.trees.len()
+    }
+
+    /// Mean validation loss before the first round and after each round
+    /// trained (including those discarded by early stopping).
+    #[must_use]
+    pub fn validation_losses(&self) -> &[f64] {
+        &self.validation_losses
+    }
+
+    /// Importance of each feature: the total reduction in squared error of
+    /// the pseudo-residuals by the splits on it, as a fraction of the total
+    /// over all features.
+    #[must_use]
+    pub fn feature_importances(&self) -> Vec<f64> {
+        let n_features = self
+            .trees
+            .first()
+            .map_or(0, |tree| tree.feature_gains().len());
+        let mut gains = vec![0.0; n_features];
+
+        for tree in &self.trees {
+            for (gain, tree_gain) in gains.iter_mut().zip(tree.feature_gains()) {
+                *gain += tree_gain;
+            }
+        }
+
+        let total: f64 = gains.iter().sum();
+        if total > 0.0 {
+            gains.iter_mut().for_each(|gain| *gain /= total);
+        }
+
+        gains
+    }
+}
+
+/// Binned validation set, with its current predictions.
+struct Validation<'a> {
+    binned: BinnedFeatures,
+    y: &'a DVector<f64>,
+    predictions: Vec<f64>,
+}
+
+impl<'a> Validation<'a> {
+    fn new(binned: BinnedFeatures, y: &'a DVector<f64>, initial: f64) -> Self {
+        Self {
+            binned,
+            y,
+            predictions: vec![initial; y.len()],
+        }
+    }
+
+    fn add_tree(&mut self, tree: &DecisionTreeRegressor, learning_rate: f64) {
+        for (row, prediction) in self.predictions.iter_mut().enumerate() {
+            *prediction += learning_rate * tree.predict_binned(&self.binned, row);
+        }
+    }
+
+    fn loss(&self, objective: &BoostingObjective) -> f64 {
+        self.y
+            .iter()
+            .zip(&self.predictions)
+            .map(|(y, prediction)| objective.loss(y - prediction))
+            .sum::<f64>()
+            / self.y.len() as f64
+    }
+}
+
+/// Empirical `tau` quantile of `values` (the order statistic at rank
+/// $\lfloor \tau n \rfloor$), reordering them.
+fn quantile(values: &mut [f64], tau: f64) -> f64 {
+    let rank = ((tau * values.len() as f64) as usize).min(values.len() - 1);
+    *values.select_nth_unstable_by(rank, f64::total_cmp).1
+}
+
+/// `amount` distinct indices below `length`, sorted, or all of them.
+fn sample(rng: &mut StdRng, length: usize, amount: usize) -> Vec<usize> {
+    if amount >= length {
+        return (0..length).collect();
+    }
+
+    let mut indices = index::sample(rng, length, amount).into_vec();
+    indices.sort_unstable();
+    indices
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_gradient_boosting {
+    use super::*;
+    use crate::ml::LinearRegressionInput;
+    use rand::Rng;
+    use rand_distr::StandardNormal;
+
+    /// `n` rows of three uniform features on [-1, 1], with
+    /// $y = \sin(3 x_0) + x_1^2 + 0.1 \varepsilon$ (the last feature is noise).
+    fn data(n: usize, seed: u64) -> (DMatrix<f64>, DVector<f64>) {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let x = DMatrix::from_fn(n, 3, |_, _| rng.gen_range(-1.0_f64..1.0));
+        let y = DVector::from_fn(n, |i, _| {
+            (3.0 * x[(i, 0)]).sin() + x[(i, 1)].powi(2) + 0.1 * rng.sample::<f64, _>(StandardNormal)
+        });
+
+        (x, y)
+    }
+
+    fn mse(prediction: &DVector<f64>, y: &DVector<f64>) -> f64 {
+        (prediction - y).norm_squared() / y.len() as f64
+    }
+
+    #[test]
+    fn test_boosting_beats_tree_and_linear_model() {
+        let (x, y) = data(1_000, 1);
+        let (x_test, y_test) = data(1_000, 2);
+        let config = GradientBoostingConfig {
+            n_rounds: 200,
+            row_subsample: 0.8,
+            column_subsample: 0.67,
+            ..Default::default()
+        };
+
+        let gbm = GradientBoostingRegressor::fit(&x, &y, &config).unwrap();
+        let tree = DecisionTreeRegressor::fit(&x, &y, &DecisionTreeConfig::default());
+        let linear = LinearRegressionInput::new(x.clone(), y.clone())
+            .fit(crate::ml::Decomposition::QR)
+            .unwrap();
+
+        let gbm_mse = mse(&gbm.predict(&x_test), &y_test);
+        let tree_mse = mse(&tree.predict(&x_test), &y_test);
+        let linear_mse = mse(&linear.predict(x_test.clone()).unwrap(), &y_test);
+
+        assert!(gbm_mse < tree_mse, "{gbm_mse} vs {tree_mse}");
+        assert!(tree_mse < linear_mse, "{tree_mse} vs {linear_mse}");
+        assert!(gbm_mse < 0.03, "{gbm_mse}");
+
+        let importances = gbm.feature_importances();
+        assert!((importances.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(importances[2] < importances[0] && importances[2] < importances[1]);
+    }
+
+    #[test]
+    fn test_early_stopping() {
+        // Training and validation responses move in opposite directions, so
+        // every tree makes the validation loss worse.
+        let x = DMatrix::from_fn(8, 1, |i, _| i as f64);
+        let y = DVector::from_fn(8, |i, _| i as f64);
+        let y_validation = DVector::from_fn(8, |i, _| 7.0 - i as f64);
+        let config = GradientBoostingConfig {
+            n_rounds: 50,
+            learning_rate: 0.5,
+            min_samples_leaf: 1,
+            early_stopping_rounds: Some(3),
+            ..Default::default()
+        };
+
+        let gbm =
+            GradientBoostingRegressor::fit_with_validation(&x, &y, &x, &y_validation, &config)
+                .unwrap();
+
+        assert_eq!(gbm.n_trees(), 0);
+        assert_eq!(gbm.validation_losses().len(), 4);
+        assert!(gbm
+            .predict(&x)
+            .iter()
+            .all(|prediction| (prediction - 3.5).abs() < 1e-12));
+    }
+
+    #[test]
+    fn test_quantile_objective_coverage() {
+        // The coverage is exact in sample. Out of sample, the estimation
+        // error of the quantile lowers it (the normal cdf is concave at the
+        // 90% quantile), so the model is fitted on a sample large enough
+        // for that error to be small.
+        let (x, y) = data(10_000, 3);
+        let (x_test, y_test) = data(2_000, 4);
+        let config = GradientBoostingConfig {
+            objective: BoostingObjective::Quantile { tau: 0.9 },
+            ..Default::default()
+        };
+
+        let gbm = GradientBoostingRegressor::fit(&x, &y, &config).unwrap();
+        let prediction = gbm.predict(&x_test);
+        let coverage = y_test
+            .iter()
+            .zip(prediction.iter())
+            .filter(|(y, q)| y <= q)
+            .count() as f64
+            / y_test.len() as f64;
+
+        assert!((coverage - 0.9).abs() < 0.03, "{coverage}");
+    }
+
+    #[test]
+    fn test_huber_objective_resists_outliers() {
+        let (x, mut y) = data(1_000, 5);
+        let (x_test, y_test) = data(1_000, 6);
+        for i in (0..y.len()).step_by(20) {
+            y[i] += 50.0;
+        }
+
+        let fit = |objective| {
+            let config = GradientBoostingConfig {
+                objective,
+                ..Default::default()
+            };
+            let gbm = GradientBoostingRegressor::fit(&x, &y, &config).unwrap();
+            mse(&gbm.predict(&x_test), &y_test)
+        };
+
+        assert!(
+            fit(BoostingObjective::Huber { delta: 0.5 }) < fit(BoostingObjective::SquaredError)
+        );
+    }
+
+    #[test]
+    fn test_invalid_config() {
+        let (x, y) = data(10, 7);
+        let config = GradientBoostingConfig {
+            objective: BoostingObjective::Quantile { tau: 1.5 },
+            ..Default::default()
+        };
+
+        assert!(GradientBoostingRegressor::fit(&x, &y, &config).is_err());
+    }
+}
@@ -14,7 +14,8 @@ See:
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // IMPORTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
-use nalgebra::{DMatrix, DVector};
+use nalgebra::{DMatrix, DVector, Scalar};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -22,7 +23,11 @@ use std::collections::HashMap;
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 /// KNN Classifier struct
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "T: Scalar + Serialize",
+    deserialize = "T: Scalar + Deserialize<'de>"
+))]
 pub struct KNearestClassifier<T> {
     /// Input data matrix.
     /// Rows correspond to data points, and each column is a different
@@ -39,7 +44,7 @@ pub struct KNearestClassifier<T> {
 }
 
 /// Metric for computing distances
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub enum Metric {
     /// Euclidean distance (default).
     /// Equivalent to Minkowski at $p=2$.
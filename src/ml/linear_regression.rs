@@ -13,9 +13,11 @@
 // IMPORTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use nalgebra::{DMatrix, DVector};
+use nalgebra::{DMatrix, DVector, Scalar};
+use serde::{Deserialize, Serialize};
 
 use crate::error::RustQuantError;
+use crate::math::newey_west_vcov;
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // STRUCTS, ENUMS, AND TRAITS
@@ -35,7 +37,11 @@ pub struct LinearRegressionInput<T> {
 
 /// Struct to hold the output data for a linear regression.
 #[allow(clippy::module_name_repetitions)]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "T: Scalar + Serialize",
+    deserialize = "T: Scalar + Deserialize<'de>"
+))]
 pub struct LinearRegressionOutput<T> {
     /// The intercept of the linear regression,
     /// often denoted as b0 or alpha.
@@ -165,6 +171,27 @@ impl LinearRegressionOutput<f64> {
 
         Ok(predictions)
     }
+
+    /// Newey-West HAC variance-covariance matrix of the intercept and
+    /// coefficients (in the order of `coefficients`), fitted to `input`,
+    /// with the given Bartlett kernel bandwidth.
+    ///
+    /// See [`newey_west_vcov`].
+    ///
+    /// # Panics
+    /// Panics if the design matrix (with the intercept column) is rank
+    /// deficient.
+    #[must_use]
+    pub fn newey_west_vcov(
+        &self,
+        input: &LinearRegressionInput<f64>,
+        bandwidth: usize,
+    ) -> DMatrix<f64> {
+        let x = input.x.clone().insert_column(0, 1.);
+        let residuals = &input.y - &x * &self.coefficients;
+
+        newey_west_vcov(&x, residuals.as_slice(), bandwidth)
+    }
 }
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -283,4 +310,21 @@ mod tests_linear_regression {
         }
         Ok(())
     }
+
+    #[test]
+    fn test_newey_west_standard_errors() -> Result<(), RustQuantError> {
+        let errors = [0.3, 0.5, 0.2, -0.1, -0.4, -0.2, 0.1, 0.4, 0.3, -0.2];
+        let input = LinearRegressionInput::new(
+            DMatrix::from_fn(10, 1, |t, _| (t + 1) as f64),
+            DVector::from_fn(10, |t, _| 1.0 + 0.5 * (t + 1) as f64 + errors[t]),
+        );
+
+        let output = input.fit(Decomposition::QR)?;
+        let vcov = output.newey_west_vcov(&input, 1);
+
+        assert_approx_equal!(vcov[(0, 0)], 0.032_528_161_616_162_1, 1e-10);
+        assert_approx_equal!(vcov[(1, 1)], 0.000_751_460_389_014_127, 1e-10);
+
+        Ok(())
+    }
 }
@@ -14,7 +14,8 @@
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 use crate::ml::ActivationFunction;
-use nalgebra::{DMatrix, DVector};
+use nalgebra::{DMatrix, DVector, Scalar};
+use serde::{Deserialize, Serialize};
 
 // use std::f64::EPSILON as EPS;
 
@@ -37,7 +38,11 @@ pub struct LogisticRegressionInput<T> {
 
 /// Struct to hold the output data for a logistic regression.
 #[allow(clippy::module_name_repetitions)]
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "T: Scalar + Serialize",
+    deserialize = "T: Scalar + Deserialize<'de>"
+))]
 pub struct LogisticRegressionOutput<T> {
     /// The coefficients of the logistic regression,
     /// often denoted as b0, b1, b2, ..., bn.
@@ -0,0 +1,53 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Loss functions on `autodiff` variables, and the pinball loss of quantile
+//! regression.
+//!
+//! The classification losses are fused operations of the `Graph` tape (see
+//! [`crate::autodiff::fused`]): the loss of a batch over $n$ classes is one
+//! vertex with an analytic gradient, instead of several vertices per logit.
+//!
+//! ```
+//! use RustQuant::autodiff::*;
+//! use RustQuant::ml::softmax_cross_entropy_batch;
+//!
+//! let g = Graph::new();
+//! let logits = vec![g.vars(&[2.0, 0.5, -1.0]), g.vars(&[0.1, 0.2, 0.3])];
+//!
+//! let loss = softmax_cross_entropy_batch(&logits, &[0, 2]);
+//! let grad = loss.accumulate();
+//!
+//! // The gradient of each row sums to zero.
+//! let total: f64 = logits[0].iter().map(|x| grad.wrt(x)).sum();
+//! assert!(total.abs() < 1e-15);
+//! ```
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+pub use crate::autodiff::fused::{logsumexp, softmax_cross_entropy, softmax_cross_entropy_batch};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Pinball (check) loss of a `residual` $r = y - \hat{y}$ at quantile
+/// `tau`: $\rho_\tau(r) = r (\tau - 1_{r < 0})$.
+///
+/// Its expectation is minimized by the `tau` quantile of $y$.
+#[must_use]
+pub fn pinball_loss(residual: f64, tau: f64) -> f64 {
+    if residual < 0.0 {
+        residual * (tau - 1.0)
+    } else {
+        residual * tau
+    }
+}
@@ -13,15 +13,61 @@
 //!
 //! - [x] Linear (using QR or SVD decomposition)
 //! - [x] Logistic (via IRLS, adding MLE in the future).
+//! - [x] Quantile (Frisch-Newton interior point, or subgradient descent)
+//! - [x] Decision trees (histogram splits)
+//! - [x] Gradient boosting (squared error, Huber, and quantile objectives)
+//! - [x] Fama-MacBeth cross-sectional regressions (Newey-West standard errors)
+//!
+//! ### Losses
+//!
+//! - [x] Softmax cross-entropy and log-sum-exp (fused autodiff operations)
+//! - [x] Pinball loss
 //!
 //! ### Classification
 //!
 //! - [x] K-Nearest Neighbours
+//!
+//! ### Dimensionality Reduction
+//!
+//! - [x] Principal component analysis
+//!
+//! ### Validation
+//!
+//! - [x] Time series cross-validation (walk-forward and purged k-fold)
+//!
+//! ### Model Selection
+//!
+//! - [x] Information criteria (AIC, BIC, HQIC) and lag order selection
+//!
+//! ### Training
+//!
+//! - [x] Optimizers (SGD, Adam, AdamW, RMSProp)
+//! - [x] Learning-rate schedules (step decay, cosine, warmup) and gradient clipping
+//!
+//! ### Persistence
+//!
+//! - [x] Saving and loading fitted models (JSON, with a schema version)
+//!
+//! ### Uncertainty Quantification
+//!
+//! - [x] Split conformal prediction intervals
 
 /// Submodule of `ml`: activation functions.
 pub mod activations;
 pub use activations::*;
 
+/// Regression trees with histogram splits.
+pub mod decision_tree;
+pub use decision_tree::*;
+
+/// Fama-MacBeth estimation of characteristic risk premia.
+pub mod fama_macbeth;
+pub use fama_macbeth::*;
+
+/// Gradient boosted regression trees.
+pub mod gradient_boosting;
+pub use gradient_boosting::*;
+
 /// K Nearest Neighbor classifier
 pub mod k_nearest_neighbors;
 pub use k_nearest_neighbors::*;
@@ -33,3 +79,39 @@ pub use linear_regression::*;
 /// Logistic regression.
 pub mod logistic_regression;
 pub use logistic_regression::*;
+
+/// Loss functions (fused softmax cross-entropy, pinball loss).
+pub mod losses;
+pub use losses::*;
+
+/// Model selection by information criteria.
+pub mod model_selection;
+pub use model_selection::*;
+
+/// Optimizers (SGD, Adam, AdamW, RMSProp) and learning-rate schedules.
+pub mod optimizers;
+pub use optimizers::*;
+
+/// Saving and loading fitted models.
+pub mod persistence;
+pub use persistence::*;
+
+/// Principal component analysis.
+pub mod principal_component_analysis;
+pub use principal_component_analysis::*;
+
+/// Quantile regression.
+pub mod quantile_regression;
+pub use quantile_regression::*;
+
+/// Training loop for models built from autodiff variables.
+pub mod training;
+pub use training::*;
+
+/// Uncertainty quantification for model predictions.
+pub mod uncertainty;
+pub use uncertainty::*;
+
+/// Model validation (e.g. cross-validation schemes).
+pub mod validation;
+pub use validation::*;
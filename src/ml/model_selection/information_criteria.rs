@@ -0,0 +1,211 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Information criteria for selecting between models fitted by maximum
+//! likelihood.
+//!
+//! Each criterion penalises the maximised log-likelihood $\ln L$ by the
+//! number of parameters $k$ (and observations $n$); lower is better:
+//!
+//! - Akaike: $\text{AIC} = -2 \ln L + 2k$.
+//! - Bayesian (Schwarz): $\text{BIC} = -2 \ln L + k \ln n$.
+//! - Hannan-Quinn: $\text{HQIC} = -2 \ln L + 2k \ln \ln n$.
+//!
+//! BIC and HQIC are consistent (they select the true order with probability
+//! tending to one), while AIC tends to over-fit but has better predictive
+//! properties in small samples.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Information criterion used for model selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InformationCriterion {
+    /// Akaike information criterion.
+    Aic,
+    /// Bayesian (Schwarz) information criterion.
+    Bic,
+    /// Hannan-Quinn information criterion.
+    Hqic,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl InformationCriterion {
+    /// Value of the criterion for a model with maximised log-likelihood
+    /// `log_likelihood`, `n_params` parameters, and `n_obs` observations.
+    #[must_use]
+    pub fn value(&self, log_likelihood: f64, n_params: usize, n_obs: usize) -> f64 {
+        match self {
+            Self::Aic => aic(log_likelihood, n_params),
+            Self::Bic => bic(log_likelihood, n_params, n_obs),
+            Self::Hqic => hqic(log_likelihood, n_params, n_obs),
+        }
+    }
+}
+
+/// Akaike information criterion: $-2 \ln L + 2k$.
+#[must_use]
+pub fn aic(log_likelihood: f64, n_params: usize) -> f64 {
+    -2.0 * log_likelihood + 2.0 * n_params as f64
+}
+
+/// Bayesian information criterion: $-2 \ln L + k \ln n$.
+#[must_use]
+pub fn bic(log_likelihood: f64, n_params: usize, n_obs: usize) -> f64 {
+    -2.0 * log_likelihood + n_params as f64 * (n_obs as f64).ln()
+}
+
+/// Hannan-Quinn information criterion: $-2 \ln L + 2k \ln \ln n$.
+#[must_use]
+pub fn hqic(log_likelihood: f64, n_params: usize, n_obs: usize) -> f64 {
+    -2.0 * log_likelihood + 2.0 * n_params as f64 * (n_obs as f64).ln().ln()
+}
+
+/// Lag order $p \in \{0, \ldots,$ `max_lag`$\}$ minimising `criterion`.
+///
+/// `log_likelihood_fn(sample, p)` returns the maximised log-likelihood of
+/// an order-$p$ model of `sample`, conditional on its first `p`
+/// observations. It is passed the last `data.len() - max_lag + p`
+/// observations, so every order is evaluated on the same
+/// `data.len() - max_lag` observations and the criteria are comparable.
+/// The order-$p$ model is counted as having $p$ parameters: parameters
+/// common to all orders (intercept, variance) do not change the selection.
+///
+/// # Panics
+/// Panics if `data` has no more than `max_lag` observations.
+pub fn select_lag_order<F>(
+    data: &[f64],
+    max_lag: usize,
+    log_likelihood_fn: F,
+    criterion: InformationCriterion,
+) -> usize
+where
+    F: Fn(&[f64], usize) -> f64,
+{
+    assert!(
+        data.len() > max_lag,
+        "More observations than the maximum lag are required."
+    );
+
+    let n_obs = data.len() - max_lag;
+
+    (0..=max_lag)
+        .map(|p| {
+            let log_likelihood = log_likelihood_fn(&data[max_lag - p..], p);
+            (p, criterion.value(log_likelihood, p, n_obs))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+        .map_or(0, |(p, _)| p)
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_information_criteria {
+    use super::*;
+    use crate::assert_approx_equal;
+    use nalgebra::{DMatrix, DVector};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::StandardNormal;
+    use std::f64::consts::PI;
+
+    // Conditional Gaussian log-likelihood of an AR(p) with intercept,
+    // fitted by least squares.
+    fn ar_log_likelihood(sample: &[f64], p: usize) -> f64 {
+        let n = sample.len() - p;
+        let x = DMatrix::from_fn(
+            n,
+            p + 1,
+            |t, j| if j == 0 { 1.0 } else { sample[t + p - j] },
+        );
+        let y = DVector::from_fn(n, |t, _| sample[t + p]);
+
+        let beta = (x.transpose() * &x)
+            .cholesky()
+            .unwrap()
+            .solve(&(x.transpose() * &y));
+        let variance = (y - x * beta).norm_squared() / n as f64;
+
+        -0.5 * n as f64 * ((2.0 * PI * variance).ln() + 1.0)
+    }
+
+    fn simulate_ar1(phi: f64, n: usize, rng: &mut StdRng) -> Vec<f64> {
+        let mut x = 0.0;
+        (0..n + 100)
+            .map(|_| {
+                x = phi * x + rng.sample::<f64, _>(StandardNormal);
+                x
+            })
+            .skip(100)
+            .collect()
+    }
+
+    #[test]
+    fn test_criteria_values() {
+        assert_approx_equal!(aic(-100.0, 3), 206.0, 1e-12);
+        assert_approx_equal!(bic(-100.0, 3, 100), 200.0 + 3.0 * 100_f64.ln(), 1e-12);
+        assert_approx_equal!(hqic(-100.0, 3, 100), 200.0 + 6.0 * 100_f64.ln().ln(), 1e-12);
+
+        assert_approx_equal!(
+            InformationCriterion::Bic.value(-100.0, 3, 100),
+            bic(-100.0, 3, 100),
+            1e-12
+        );
+
+        // BIC penalises more than AIC once ln n > 2.
+        assert!(bic(-100.0, 3, 8) > aic(-100.0, 3));
+    }
+
+    #[test]
+    fn test_bic_selects_ar1() {
+        let mut rng = StdRng::seed_from_u64(173);
+        let trials = 200;
+        let mut correct = 0;
+
+        for _ in 0..trials {
+            let data = simulate_ar1(0.5, 500, &mut rng);
+            if select_lag_order(&data, 5, ar_log_likelihood, InformationCriterion::Bic) == 1 {
+                correct += 1;
+            }
+        }
+
+        assert!(correct * 10 > trials * 9, "{correct} of {trials}");
+    }
+
+    #[test]
+    fn test_aic_overfits_more_than_bic() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let (mut aic_order, mut bic_order) = (0, 0);
+
+        for _ in 0..100 {
+            let data = simulate_ar1(0.5, 500, &mut rng);
+            aic_order += select_lag_order(&data, 5, ar_log_likelihood, InformationCriterion::Aic);
+            bic_order += select_lag_order(&data, 5, ar_log_likelihood, InformationCriterion::Bic);
+        }
+
+        assert!(aic_order > bic_order);
+    }
+
+    #[test]
+    fn test_white_noise_selects_order_zero() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let data = simulate_ar1(0.0, 1_000, &mut rng);
+
+        assert_eq!(
+            select_lag_order(&data, 3, ar_log_likelihood, InformationCriterion::Hqic),
+            0
+        );
+    }
+}
@@ -0,0 +1,379 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! First-order optimizers and learning-rate schedules for training models
+//! by gradient descent (see [`Trainer`](super::Trainer)).
+//!
+//! With gradient $g_t$ at step $t$ and learning rate $\alpha_t$:
+//!
+//! - [`Sgd`]: $\theta_t = \theta_{t-1} - \alpha_t g_t$.
+//! - [`Adam`] (Kingma and Ba, 2015): exponential moving averages of $g_t$
+//!   and $g_t^2$, bias-corrected for their zero initialisation,
+//!   $\theta_t = \theta_{t-1} - \alpha_t \hat{m}_t / (\sqrt{\hat{v}_t} + \epsilon)$.
+//! - [`AdamW`] (Loshchilov and Hutter, 2019): Adam with the weight decay
+//!   $\alpha_t \lambda \theta_{t-1}$ applied to the parameters directly,
+//!   rather than added to the gradient (where Adam would rescale it).
+//! - [`RmsProp`]: $\theta_t = \theta_{t-1} - \alpha_t g_t / (\sqrt{s_t} + \epsilon)$,
+//!   with $s_t$ the moving average of $g_t^2$.
+//!
+//! The optimizers and schedules are serializable, so training can be
+//! saved and resumed.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Optimizer updating the parameters of a model from their gradient.
+pub trait Optimizer {
+    /// Updates the `parameters` in place, from the `gradient` of the loss,
+    /// with step size `learning_rate`.
+    fn step(&mut self, parameters: &mut [f64], gradient: &[f64], learning_rate: f64);
+}
+
+/// Plain (stochastic) gradient descent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Sgd;
+
+/// Adam, with bias-corrected moment estimates.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Adam {
+    /// Decay rate of the first moment estimate.
+    pub beta_1: f64,
+    /// Decay rate of the second moment estimate.
+    pub beta_2: f64,
+    /// Added to the denominator for numerical stability.
+    pub epsilon: f64,
+    /// Number of steps taken.
+    steps: i32,
+    /// First moment estimate of each parameter.
+    m: Vec<f64>,
+    /// Second moment estimate of each parameter.
+    v: Vec<f64>,
+}
+
+/// Adam with decoupled weight decay.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AdamW {
+    /// The underlying Adam optimizer.
+    pub adam: Adam,
+    /// Weight decay rate $\lambda$.
+    pub weight_decay: f64,
+}
+
+/// RMSProp.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RmsProp {
+    /// Decay rate of the mean square of the gradient.
+    pub decay: f64,
+    /// Added to the denominator for numerical stability.
+    pub epsilon: f64,
+    /// Mean square of the gradient of each parameter.
+    mean_square: Vec<f64>,
+}
+
+/// Learning rate as a function of the epoch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum LearningRateSchedule {
+    /// The same learning rate every epoch.
+    Constant(f64),
+    /// `initial * factor^(epoch / step)`, decaying every `step` epochs.
+    StepDecay {
+        /// Learning rate of the first epochs.
+        initial: f64,
+        /// Multiplier applied every `step` epochs.
+        factor: f64,
+        /// Number of epochs between decays.
+        step: usize,
+    },
+    /// Cosine annealing from `initial` to `minimum` over `epochs` epochs,
+    /// then `minimum`.
+    Cosine {
+        /// Learning rate of the first epoch.
+        initial: f64,
+        /// Learning rate at the end of the annealing.
+        minimum: f64,
+        /// Length of the annealing.
+        epochs: usize,
+    },
+    /// Linear warmup over `epochs` epochs to the first learning rate of
+    /// `then`, which starts after the warmup.
+    Warmup {
+        /// Length of the warmup.
+        epochs: usize,
+        /// Schedule after the warmup.
+        then: Box<LearningRateSchedule>,
+    },
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Optimizer for Sgd {
+    fn step(&mut self, parameters: &mut [f64], gradient: &[f64], learning_rate: f64) {
+        for (theta, g) in parameters.iter_mut().zip(gradient) {
+            *theta -= learning_rate * g;
+        }
+    }
+}
+
+impl Default for Adam {
+    fn default() -> Self {
+        Self {
+            beta_1: 0.9,
+            beta_2: 0.999,
+            epsilon: 1e-8,
+            steps: 0,
+            m: Vec::new(),
+            v: Vec::new(),
+        }
+    }
+}
+
+impl Adam {
+    /// Adam with the usual decay rates (0.9 and 0.999) and epsilon (1e-8).
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the decay rates of the moment estimates.
+    #[must_use]
+    pub fn with_betas(mut self, beta_1: f64, beta_2: f64) -> Self {
+        self.beta_1 = beta_1;
+        self.beta_2 = beta_2;
+        self
+    }
+
+    /// Sets epsilon.
+    #[must_use]
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon;
+        self
+    }
+}
+
+impl Optimizer for Adam {
+    fn step(&mut self, parameters: &mut [f64], gradient: &[f64], learning_rate: f64) {
+        self.m.resize(parameters.len(), 0.0);
+        self.v.resize(parameters.len(), 0.0);
+        self.steps += 1;
+
+        let correction_1 = 1.0 - self.beta_1.powi(self.steps);
+        let correction_2 = 1.0 - self.beta_2.powi(self.steps);
+
+        for (i, (theta, g)) in parameters.iter_mut().zip(gradient).enumerate() {
+            self.m[i] = self.beta_1 * self.m[i] + (1.0 - self.beta_1) * g;
+            self.v[i] = self.beta_2 * self.v[i] + (1.0 - self.beta_2) * g * g;
+
+            let m_hat = self.m[i] / correction_1;
+            let v_hat = self.v[i] / correction_2;
+
+            *theta -= learning_rate * m_hat / (v_hat.sqrt() + self.epsilon);
+        }
+    }
+}
+
+impl AdamW {
+    /// AdamW with weight decay rate `weight_decay` and the default Adam
+    /// parameters.
+    #[must_use]
+    pub fn new(weight_decay: f64) -> Self {
+        Self {
+            adam: Adam::new(),
+            weight_decay,
+        }
+    }
+}
+
+impl Optimizer for AdamW {
+    fn step(&mut self, parameters: &mut [f64], gradient: &[f64], learning_rate: f64) {
+        for theta in parameters.iter_mut() {
+            *theta -= learning_rate * self.weight_decay * *theta;
+        }
+
+        self.adam.step(parameters, gradient, learning_rate);
+    }
+}
+
+impl Default for RmsProp {
+    fn default() -> Self {
+        Self {
+            decay: 0.9,
+            epsilon: 1e-8,
+            mean_square: Vec::new(),
+        }
+    }
+}
+
+impl RmsProp {
+    /// RMSProp with decay rate 0.9 and epsilon 1e-8.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the decay rate.
+    #[must_use]
+    pub fn with_decay(mut self, decay: f64) -> Self {
+        self.decay = decay;
+        self
+    }
+}
+
+impl Optimizer for RmsProp {
+    fn step(&mut self, parameters: &mut [f64], gradient: &[f64], learning_rate: f64) {
+        self.mean_square.resize(parameters.len(), 0.0);
+
+        for ((theta, g), s) in parameters
+            .iter_mut()
+            .zip(gradient)
+            .zip(self.mean_square.iter_mut())
+        {
+            *s = self.decay * *s + (1.0 - self.decay) * g * g;
+            *theta -= learning_rate * g / (s.sqrt() + self.epsilon);
+        }
+    }
+}
+
+impl LearningRateSchedule {
+    /// Learning rate of the `epoch` (counted from zero).
+    #[must_use]
+    pub fn learning_rate(&self, epoch: usize) -> f64 {
+        match self {
+            Self::Constant(rate) => *rate,
+            Self::StepDecay {
+                initial,
+                factor,
+                step,
+            } => {
+                let decays = i32::try_from(epoch / (*step).max(1)).unwrap_or(i32::MAX);
+                initial * factor.powi(decays)
+            }
+            Self::Cosine {
+                initial,
+                minimum,
+                epochs,
+            } => {
+                let progress = epoch.min(*epochs) as f64 / (*epochs).max(1) as f64;
+                minimum + 0.5 * (initial - minimum) * (1.0 + (PI * progress).cos())
+            }
+            Self::Warmup { epochs, then } => {
+                if epoch < *epochs {
+                    then.learning_rate(0) * (epoch + 1) as f64 / *epochs as f64
+                } else {
+                    then.learning_rate(epoch - epochs)
+                }
+            }
+        }
+    }
+}
+
+/// Scales `gradient` so its Euclidean norm is at most `max_norm`, and
+/// returns its norm before clipping.
+pub fn clip_by_global_norm(gradient: &mut [f64], max_norm: f64) -> f64 {
+    let norm = gradient.iter().map(|g| g * g).sum::<f64>().sqrt();
+
+    if norm > max_norm {
+        let scale = max_norm / norm;
+        gradient.iter_mut().for_each(|g| *g *= scale);
+    }
+
+    norm
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_optimizers {
+    use super::*;
+
+    #[test]
+    fn test_adam_first_step_is_bias_corrected() {
+        // After bias correction, the first step is the learning rate in the
+        // direction of the gradient, whatever its scale.
+        let mut adam = Adam::new();
+        let mut parameters = [1.0, 1.0, 1.0];
+        adam.step(&mut parameters, &[1e-3, -5.0, 200.0], 0.1);
+
+        for (theta, expected) in parameters.iter().zip([0.9, 1.1, 0.9]) {
+            assert!((theta - expected).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_adamw_decays_without_gradient() {
+        let mut adamw = AdamW::new(0.1);
+        let mut parameters = [2.0, -4.0];
+        adamw.step(&mut parameters, &[0.0, 0.0], 0.5);
+
+        assert_eq!(parameters, [1.9, -3.8]);
+    }
+
+    #[test]
+    fn test_rmsprop_step() {
+        let mut rmsprop = RmsProp::new();
+        let mut parameters = [1.0];
+        rmsprop.step(&mut parameters, &[2.0], 0.01);
+
+        // s = 0.1 * 4, so the step is 0.01 * 2 / sqrt(0.4).
+        assert!((parameters[0] - (1.0 - 0.02 / 0.4_f64.sqrt())).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_schedules() {
+        let step = LearningRateSchedule::StepDecay {
+            initial: 0.1,
+            factor: 0.5,
+            step: 10,
+        };
+        assert_eq!(step.learning_rate(0), 0.1);
+        assert_eq!(step.learning_rate(9), 0.1);
+        assert_eq!(step.learning_rate(10), 0.05);
+        assert_eq!(step.learning_rate(25), 0.025);
+
+        let cosine = LearningRateSchedule::Cosine {
+            initial: 1.0,
+            minimum: 0.1,
+            epochs: 100,
+        };
+        assert_eq!(cosine.learning_rate(0), 1.0);
+        assert!((cosine.learning_rate(50) - 0.55).abs() < 1e-15);
+        assert!((cosine.learning_rate(100) - 0.1).abs() < 1e-15);
+        assert!((cosine.learning_rate(500) - 0.1).abs() < 1e-15);
+
+        let warmup = LearningRateSchedule::Warmup {
+            epochs: 4,
+            then: Box::new(step),
+        };
+        for (epoch, rate) in [0.025, 0.05, 0.075, 0.1, 0.1, 0.1].iter().enumerate() {
+            assert!((warmup.learning_rate(epoch) - rate).abs() < 1e-15);
+        }
+        assert_eq!(warmup.learning_rate(14), 0.05);
+    }
+
+    #[test]
+    fn test_clip_by_global_norm() {
+        let mut gradient = [3.0, 4.0];
+        assert_eq!(clip_by_global_norm(&mut gradient, 10.0), 5.0);
+        assert_eq!(gradient, [3.0, 4.0]);
+
+        assert_eq!(clip_by_global_norm(&mut gradient, 1.0), 5.0);
+        assert!((gradient[0] - 0.6).abs() < 1e-15 && (gradient[1] - 0.8).abs() < 1e-15);
+    }
+}
@@ -0,0 +1,293 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Saving and loading fitted models.
+//!
+//! A model is saved as a JSON document of the form
+//!
+//! ```text
+//! { "schema_version": 1, "kind": "LinearRegression", "model": { ... } }
+//! ```
+//!
+//! where `model` holds the fitted parameters and the training metadata of
+//! the model (e.g. the number of iterations). The floats are written in
+//! their shortest round-trip form, so a loaded model predicts exactly the
+//! same values as the model that was saved.
+//!
+//! Models saved by another schema version, or of another kind, are
+//! rejected with a [`ModelError`].
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{
+    KNearestClassifier, LinearRegressionOutput, LogisticRegressionOutput, Optimizer,
+    PrincipalComponents, Trainer,
+};
+use crate::error::{ModelError, RustQuantError};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::path::Path;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Version of the saved model format written by this build.
+pub const MODEL_SCHEMA_VERSION: u32 = 1;
+
+/// A fitted model that can be saved to, and loaded from, a file.
+pub trait SavedModel: Serialize + DeserializeOwned {
+    /// Name of the model, recorded in the saved document.
+    const KIND: &'static str;
+
+    /// Saves the model to `path`.
+    ///
+    /// # Errors
+    /// - `IoError` if the file cannot be written.
+    /// - `ModelError` if the model cannot be encoded (e.g. a non-finite
+    ///   parameter).
+    fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), RustQuantError> {
+        std::fs::write(path, self.to_json()?)?;
+
+        Ok(())
+    }
+
+    /// Loads a model saved by [`SavedModel::save`].
+    ///
+    /// # Errors
+    /// - `IoError` if the file cannot be read.
+    /// - `ModelError` if it is not a model of this kind and schema version.
+    fn load<P: AsRef<Path>>(path: P) -> Result<Self, RustQuantError> {
+        Ok(Self::from_json(&std::fs::read_to_string(path)?)?)
+    }
+
+    /// The model, encoded as by [`SavedModel::save`].
+    ///
+    /// # Errors
+    /// `ModelError::Malformed` if the model cannot be encoded.
+    fn to_json(&self) -> Result<String, ModelError> {
+        let document = Document {
+            schema_version: MODEL_SCHEMA_VERSION,
+            kind: Self::KIND.to_string(),
+            model: self,
+        };
+
+        serde_json::to_string(&document).map_err(|error| ModelError::Malformed(error.to_string()))
+    }
+
+    /// Decodes a model encoded by [`SavedModel::to_json`].
+    ///
+    /// # Errors
+    /// - `ModelError::VersionMismatch` if it was saved by another schema
+    ///   version.
+    /// - `ModelError::KindMismatch` if it is another kind of model.
+    /// - `ModelError::Malformed` if it is not a valid document.
+    fn from_json(json: &str) -> Result<Self, ModelError> {
+        let malformed = |error: serde_json::Error| ModelError::Malformed(error.to_string());
+
+        let header: Header = serde_json::from_str(json).map_err(malformed)?;
+        if header.schema_version != MODEL_SCHEMA_VERSION {
+            return Err(ModelError::VersionMismatch {
+                found: header.schema_version,
+                expected: MODEL_SCHEMA_VERSION,
+            });
+        }
+        if header.kind != Self::KIND {
+            return Err(ModelError::KindMismatch {
+                found: header.kind,
+                expected: Self::KIND,
+            });
+        }
+
+        let document: Document<Self> = serde_json::from_str(json).map_err(malformed)?;
+
+        Ok(document.model)
+    }
+}
+
+/// Saved model document.
+#[derive(Serialize, Deserialize)]
+struct Document<M> {
+    schema_version: u32,
+    kind: String,
+    model: M,
+}
+
+/// Fields of a saved model document read before the model itself.
+#[derive(Deserialize)]
+struct Header {
+    schema_version: u32,
+    kind: String,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl SavedModel for LinearRegressionOutput<f64> {
+    const KIND: &'static str = "LinearRegression";
+}
+
+impl SavedModel for LogisticRegressionOutput<f64> {
+    const KIND: &'static str = "LogisticRegression";
+}
+
+impl SavedModel for KNearestClassifier<f64> {
+    const KIND: &'static str = "KNearestClassifier";
+}
+
+impl SavedModel for PrincipalComponents {
+    const KIND: &'static str = "PrincipalComponents";
+}
+
+impl<O> SavedModel for Trainer<O>
+where
+    O: Optimizer + Serialize + DeserializeOwned,
+{
+    const KIND: &'static str = "Trainer";
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_persistence {
+    use super::*;
+    use crate::autodiff::variables::variable::Variable;
+    use crate::ml::{
+        Adam, Decomposition, LearningRateSchedule, LinearRegressionInput,
+        LogisticRegressionAlgorithm, LogisticRegressionInput, Metric,
+    };
+    use nalgebra::{DMatrix, DVector};
+
+    fn features() -> DMatrix<f64> {
+        DMatrix::from_fn(12, 2, |i, j| ((3 * i + 7 * j) % 11) as f64 / 7.0 - 0.6)
+    }
+
+    fn round_trip<M: SavedModel>(model: &M) -> M {
+        let path =
+            std::env::temp_dir().join(format!("rustquant_{}_{}.json", M::KIND, std::process::id()));
+        model.save(&path).unwrap();
+        let loaded = M::load(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        loaded
+    }
+
+    fn bits(values: &[f64]) -> Vec<u64> {
+        values.iter().map(|value| value.to_bits()).collect()
+    }
+
+    #[test]
+    fn test_linear_regression_round_trip() {
+        let x = features();
+        let y = DVector::from_fn(12, |i, _| {
+            0.3 + 1.7 * x[(i, 0)] - 0.2 * x[(i, 1)] + 0.01 * i as f64
+        });
+        let model = LinearRegressionInput::new(x.clone(), y)
+            .fit(Decomposition::QR)
+            .unwrap();
+        let loaded = round_trip(&model);
+
+        assert_eq!(
+            bits(model.predict(x.clone()).unwrap().as_slice()),
+            bits(loaded.predict(x).unwrap().as_slice())
+        );
+    }
+
+    #[test]
+    fn test_logistic_regression_round_trip() {
+        let x = features();
+        let y = DVector::from_fn(12, |i, _| f64::from(u8::from(i % 3 != 0)));
+        let model = LogisticRegressionInput::new(x.clone(), y)
+            .fit(LogisticRegressionAlgorithm::IRLS, 1e-8)
+            .unwrap();
+        let loaded = round_trip(&model);
+
+        assert_eq!(model.iterations, loaded.iterations);
+        assert_eq!(
+            bits(model.predict_proba(&x).as_slice()),
+            bits(loaded.predict_proba(&x).as_slice())
+        );
+    }
+
+    #[test]
+    fn test_k_nearest_classifier_round_trip() {
+        let x = features();
+        let y = DVector::from_fn(12, |i, _| (i % 3) as f64);
+        let model = KNearestClassifier::new(x.clone(), y, Metric::Minkowski(3));
+        let loaded = round_trip(&model);
+
+        assert_eq!(bits(&model.predict(&x, &3)), bits(&loaded.predict(&x, &3)));
+    }
+
+    #[test]
+    fn test_principal_components_round_trip() {
+        let x = features();
+        let model = PrincipalComponents::fit(&x).unwrap();
+        let loaded = round_trip(&model);
+
+        assert_eq!(model, loaded);
+        assert_eq!(
+            bits(model.transform(&x, 2).as_slice()),
+            bits(loaded.transform(&x, 2).as_slice())
+        );
+    }
+
+    /// Squared distance of the parameters from `(1, 0)`.
+    fn quadratic_loss<'v>(p: &[Variable<'v>]) -> Variable<'v> {
+        (p[0] - 1.0) * (p[0] - 1.0) + p[1] * p[1]
+    }
+
+    #[test]
+    fn test_trainer_round_trip() {
+        let mut model = Trainer::new(
+            vec![0.5, -1.5],
+            Adam::new(),
+            LearningRateSchedule::Constant(0.1),
+        );
+        model.train(quadratic_loss, 5);
+        let loaded = round_trip(&model);
+
+        assert_eq!(model, loaded);
+    }
+
+    #[test]
+    fn test_newer_schema_version_is_rejected() {
+        let model = PrincipalComponents::fit(&features()).unwrap();
+        let json =
+            model
+                .to_json()
+                .unwrap()
+                .replacen("\"schema_version\":1", "\"schema_version\":2", 1);
+
+        assert_eq!(
+            PrincipalComponents::from_json(&json),
+            Err(ModelError::VersionMismatch {
+                found: 2,
+                expected: MODEL_SCHEMA_VERSION,
+            })
+        );
+    }
+
+    #[test]
+    fn test_other_kind_is_rejected() {
+        let json = PrincipalComponents::fit(&features())
+            .unwrap()
+            .to_json()
+            .unwrap();
+
+        assert!(matches!(
+            LinearRegressionOutput::<f64>::from_json(&json),
+            Err(ModelError::KindMismatch { .. })
+        ));
+    }
+}
@@ -0,0 +1,162 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Principal component analysis.
+//!
+//! The principal components of a data set are the eigenvectors of its
+//! sample covariance matrix, ordered by decreasing eigenvalue (explained
+//! variance). For daily yield curve changes, the first three are the
+//! familiar level, slope, and curvature factors.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use nalgebra::{DMatrix, DVector};
+use serde::{Deserialize, Serialize};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Principal components of a data set.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PrincipalComponents {
+    /// Mean of each variable.
+    pub mean: DVector<f64>,
+    /// Variance explained by each component, in decreasing order.
+    pub explained_variance: DVector<f64>,
+    /// Unit-norm loadings, one component per column. Each is signed so its
+    /// largest entry (in absolute value) is positive.
+    pub components: DMatrix<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl PrincipalComponents {
+    /// Principal components of `data`, with one observation per row.
+    ///
+    /// # Errors
+    /// `InvalidArgument` if there are fewer than two observations.
+    pub fn fit(data: &DMatrix<f64>) -> Result<Self, RustQuantError> {
+        let n = data.nrows();
+        if n < 2 {
+            return Err(RustQuantError::InvalidArgument(
+                "At least two observations are required.".to_string(),
+            ));
+        }
+
+        let mean = data.row_mean().transpose();
+        let mut centered = data.clone();
+        for mut row in centered.row_iter_mut() {
+            row -= mean.transpose();
+        }
+        let covariance = centered.transpose() * &centered / (n - 1) as f64;
+
+        let eigen = covariance.symmetric_eigen();
+        let mut order: Vec<usize> = (0..eigen.eigenvalues.len()).collect();
+        order.sort_by(|&a, &b| eigen.eigenvalues[b].total_cmp(&eigen.eigenvalues[a]));
+
+        let explained_variance = DVector::from_iterator(
+            order.len(),
+            order.iter().map(|&i| eigen.eigenvalues[i].max(0.0)),
+        );
+        let mut components = eigen.eigenvectors.select_columns(&order);
+        for mut column in components.column_iter_mut() {
+            let largest = column.iamax();
+            if column[largest] < 0.0 {
+                column.neg_mut();
+            }
+        }
+
+        Ok(Self {
+            mean,
+            explained_variance,
+            components,
+        })
+    }
+
+    /// Fraction of the total variance explained by each component.
+    #[must_use]
+    pub fn explained_variance_ratio(&self) -> DVector<f64> {
+        &self.explained_variance / self.explained_variance.sum()
+    }
+
+    /// Scores of `data` (one observation per row) on the first
+    /// `n_components` components.
+    ///
+    /// # Panics
+    /// Panics if `data` has a different number of variables, or
+    /// `n_components` exceeds it.
+    #[must_use]
+    pub fn transform(&self, data: &DMatrix<f64>, n_components: usize) -> DMatrix<f64> {
+        let mut centered = data.clone();
+        for mut row in centered.row_iter_mut() {
+            row -= self.mean.transpose();
+        }
+
+        centered * self.components.columns(0, n_components)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_principal_component_analysis {
+    use super::*;
+    use crate::assert_approx_equal;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::StandardNormal;
+
+    #[test]
+    fn test_two_dimensional_components() {
+        // Points along the diagonal, with a smaller spread across it.
+        let data = DMatrix::from_row_slice(4, 2, &[1.0, 1.0, -1.0, -1.0, 0.5, -0.5, -0.5, 0.5]);
+        let pca = PrincipalComponents::fit(&data).unwrap();
+
+        let diagonal = 1.0 / 2.0_f64.sqrt();
+        assert_approx_equal!(pca.components[(0, 0)], diagonal, 1e-12);
+        assert_approx_equal!(pca.components[(1, 0)], diagonal, 1e-12);
+        assert_approx_equal!(pca.explained_variance[0], 4.0 / 3.0, 1e-12);
+        assert_approx_equal!(pca.explained_variance[1], 1.0 / 3.0, 1e-12);
+        assert_approx_equal!(pca.explained_variance_ratio()[0], 0.8, 1e-12);
+
+        let scores = pca.transform(&data, 1);
+        assert_approx_equal!(scores[(0, 0)], 2.0_f64.sqrt(), 1e-12);
+        assert_approx_equal!(scores[(2, 0)], 0.0, 1e-12);
+    }
+
+    #[test]
+    fn test_recovers_level_factor() {
+        let mut rng = StdRng::seed_from_u64(174);
+
+        // Five variables driven by a common level factor, plus noise.
+        let data = DMatrix::from_row_iterator(
+            2_000,
+            5,
+            (0..2_000).flat_map(|_| {
+                let level: f64 = 10.0 * rng.sample::<f64, _>(StandardNormal);
+                let noise: Vec<f64> = (0..5).map(|_| rng.sample(StandardNormal)).collect();
+                noise.into_iter().map(move |e: f64| level + e)
+            }),
+        );
+
+        let pca = PrincipalComponents::fit(&data).unwrap();
+
+        for loading in pca.components.column(0).iter() {
+            assert_approx_equal!(*loading, 1.0 / 5.0_f64.sqrt(), 0.01);
+        }
+        assert!(pca.explained_variance_ratio()[0] > 0.95);
+    }
+}
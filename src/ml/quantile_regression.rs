@@ -0,0 +1,538 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Linear quantile regression.
+//!
+//! The $\tau$ quantile of the response is modelled as
+//! $\beta_0 + x^T \beta$, with the coefficients minimizing the
+//! [`pinball_loss`] of the residuals. This is a linear program, which is
+//! solved either exactly by the Frisch-Newton interior point method, or
+//! approximately by subgradient descent.
+//!
+//! Quantiles fitted separately may cross. When several quantiles are fitted
+//! with `non_crossing`, the predictions at each point are sorted
+//! (rearranged), which gives monotone quantiles at least as close to the
+//! true ones.
+//!
+//! References:
+//!     - Koenker, R. (2005). Quantile Regression. Cambridge University Press.
+//!     - Portnoy, S. and Koenker, R. (1997). The Gaussian Hare and the
+//!       Laplacian Tortoise. Statistical Science, 12(4).
+//!     - Chernozhukov, V., Fernández-Val, I. and Galichon, A. (2010).
+//!       Quantile and Probability Curves Without Crossing. Econometrica,
+//!       78(3).
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::pinball_loss;
+use crate::error::RustQuantError;
+use nalgebra::{DMatrix, DVector};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Struct to hold the input data for a quantile regression.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Clone, Debug)]
+pub struct QuantileRegressionInput<T> {
+    /// The input data matrix, also known as the design matrix.
+    /// You do not need to add a column of ones to the design matrix,
+    /// as this is done automatically.
+    pub x: DMatrix<T>,
+    /// The output data vector, also known as the response vector.
+    pub y: DVector<T>,
+}
+
+/// Struct to hold the output data for a quantile regression.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Clone, Debug)]
+pub struct QuantileRegressionOutput<T> {
+    /// The quantile that was fitted, in (0, 1).
+    pub tau: f64,
+    /// The intercept of the quantile regression.
+    pub intercept: T,
+    /// The coefficients of the quantile regression.
+    /// The first coefficient is the intercept.
+    pub coefficients: DVector<T>,
+    /// Number of iterations of the solver.
+    pub iterations: usize,
+}
+
+/// Quantile regressions of the same data at several quantiles.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Clone, Debug)]
+pub struct QuantileRegressionSet {
+    /// The fitted quantiles, in increasing order of `tau`.
+    pub quantiles: Vec<QuantileRegressionOutput<f64>>,
+    /// Whether predictions are rearranged so they do not cross.
+    pub non_crossing: bool,
+}
+
+/// Algorithm to use for quantile regression.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Copy, Clone, Debug)]
+pub enum QuantileRegressionMethod {
+    /// Frisch-Newton (primal-dual log-barrier) interior point method,
+    /// with Mehrotra's predictor-corrector steps. Exact, and fast for
+    /// large samples.
+    FrischNewton,
+    /// Subgradient descent on the mean pinball loss, with step
+    /// `learning_rate / sqrt(k + 1)` at iteration `k`. The best iterate is
+    /// returned.
+    SubgradientDescent {
+        /// Initial step size.
+        learning_rate: f64,
+        /// Number of iterations.
+        iterations: usize,
+    },
+}
+
+/// Fraction of the distance to the boundary taken by an interior point step.
+const STEP_FRACTION: f64 = 0.999_95;
+
+/// Duality gap at which the interior point method stops.
+const DUALITY_GAP_TOLERANCE: f64 = 1e-6;
+
+/// Maximum number of interior point iterations.
+const MAX_INTERIOR_POINT_ITERATIONS: usize = 100;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl QuantileRegressionInput<f64> {
+    /// Create a new `QuantileRegressionInput` struct.
+    #[must_use]
+    pub fn new(x: DMatrix<f64>, y: DVector<f64>) -> Self {
+        Self { x, y }
+    }
+
+    /// Fits the `tau` quantile of the response.
+    ///
+    /// # Errors
+    /// - `InvalidArgument` if `tau` is not in (0, 1), or `x` and `y` have
+    ///   different numbers of rows, or fewer rows than coefficients.
+    /// - `MatrixInversionFailed` if the design matrix is rank deficient.
+    /// - `ComputationError` if the interior point method does not converge.
+    pub fn fit(
+        &self,
+        tau: f64,
+        method: QuantileRegressionMethod,
+    ) -> Result<QuantileRegressionOutput<f64>, RustQuantError> {
+        if !(tau > 0.0 && tau < 1.0) {
+            return Err(RustQuantError::InvalidArgument(
+                "The quantile must be in (0, 1).".to_string(),
+            ));
+        }
+        if self.x.nrows() != self.y.len() || self.x.nrows() <= self.x.ncols() + 1 {
+            return Err(RustQuantError::InvalidArgument(
+                "Expected one response per row, and more rows than coefficients.".to_string(),
+            ));
+        }
+
+        // Insert a column of 1s to the input data matrix,
+        // to account for the intercept.
+        let x = self.x.clone().insert_column(0, 1.);
+
+        let (coefficients, iterations) = match method {
+            QuantileRegressionMethod::FrischNewton => frisch_newton(&x, &self.y, tau)?,
+            QuantileRegressionMethod::SubgradientDescent {
+                learning_rate,
+                iterations,
+            } => (
+                subgradient_descent(&x, &self.y, tau, learning_rate, iterations),
+                iterations,
+            ),
+        };
+
+        Ok(QuantileRegressionOutput {
+            tau,
+            intercept: coefficients[0],
+            coefficients,
+            iterations,
+        })
+    }
+
+    /// Fits each quantile in `taus`. With `non_crossing`, the predictions
+    /// of the set are rearranged so they increase with the quantile.
+    ///
+    /// # Errors
+    /// As for [`QuantileRegressionInput::fit`], for any of the quantiles.
+    pub fn fit_quantiles(
+        &self,
+        taus: &[f64],
+        method: QuantileRegressionMethod,
+        non_crossing: bool,
+    ) -> Result<QuantileRegressionSet, RustQuantError> {
+        let mut quantiles = taus
+            .iter()
+            .map(|&tau| self.fit(tau, method))
+            .collect::<Result<Vec<_>, _>>()?;
+        quantiles.sort_by(|a, b| a.tau.total_cmp(&b.tau));
+
+        Ok(QuantileRegressionSet {
+            quantiles,
+            non_crossing,
+        })
+    }
+}
+
+impl QuantileRegressionOutput<f64> {
+    /// Predicts the `tau` quantile of the response for the given input data.
+    #[must_use]
+    pub fn predict(&self, input: &DMatrix<f64>) -> DVector<f64> {
+        let slopes = self.coefficients.rows(1, self.coefficients.len() - 1);
+
+        (input * slopes).add_scalar(self.intercept)
+    }
+
+    /// Mean pinball loss of the predictions for `x`, given responses `y`.
+    #[must_use]
+    pub fn score_pinball(&self, x: &DMatrix<f64>, y: &DVector<f64>) -> f64 {
+        (y - self.predict(x))
+            .iter()
+            .map(|&residual| pinball_loss(residual, self.tau))
+            .sum::<f64>()
+            / y.len() as f64
+    }
+}
+
+impl QuantileRegressionSet {
+    /// Quantiles that were fitted, in increasing order.
+    #[must_use]
+    pub fn taus(&self) -> Vec<f64> {
+        self.quantiles.iter().map(|quantile| quantile.tau).collect()
+    }
+
+    /// Predicted quantiles for the given input data, with one row per
+    /// observation and one column per quantile.
+    #[must_use]
+    pub fn predict(&self, input: &DMatrix<f64>) -> DMatrix<f64> {
+        let columns: Vec<DVector<f64>> = self
+            .quantiles
+            .iter()
+            .map(|quantile| quantile.predict(input))
+            .collect();
+        let mut predictions = DMatrix::from_columns(&columns);
+
+        if self.non_crossing {
+            for mut row in predictions.row_iter_mut() {
+                let mut sorted: Vec<f64> = row.iter().copied().collect();
+                sorted.sort_by(f64::total_cmp);
+                row.copy_from_slice(&sorted);
+            }
+        }
+
+        predictions
+    }
+}
+
+/// Coefficients minimizing the pinball loss of `y - x b`, and the number of
+/// iterations, by the Frisch-Newton interior point method.
+///
+/// This solves the dual of the linear program, $\max y^T a$ subject to
+/// $X^T a = (1 - \tau) X^T 1$ and $0 \le a \le 1$; the coefficients are
+/// minus its Lagrange multipliers. This follows Koenker's `rq.fit.fnb`.
+fn frisch_newton(
+    x: &DMatrix<f64>,
+    y: &DVector<f64>,
+    tau: f64,
+) -> Result<(DVector<f64>, usize), RustQuantError> {
+    let n = x.nrows();
+    let x_t = x.transpose();
+
+    // Linear program: min c^T a, s.t. X^T a = b and 0 <= a <= 1.
+    let c = -y;
+    let mut a = DVector::from_element(n, 1.0 - tau);
+    let mut s = a.map(|a_i| 1.0 - a_i);
+    let b = &x_t * &a;
+
+    // Start from the least squares multipliers.
+    let mut dual = (&x_t * x)
+        .cholesky()
+        .ok_or(RustQuantError::MatrixInversionFailed)?
+        .solve(&(&x_t * &c));
+    let r = (&c - x * &dual).map(|r_i| if r_i == 0.0 { 0.001 } else { r_i });
+    let mut z = r.map(|r_i| r_i.max(0.0));
+    let mut w = &z - &r;
+
+    let duality_gap =
+        |a: &DVector<f64>, dual: &DVector<f64>, w: &DVector<f64>| c.dot(a) - dual.dot(&b) + w.sum();
+
+    let mut iterations = 0;
+
+    while duality_gap(&a, &dual, &w) > DUALITY_GAP_TOLERANCE {
+        if iterations == MAX_INTERIOR_POINT_ITERATIONS {
+            return Err(RustQuantError::ComputationError(
+                "Quantile regression: the interior point method did not converge.".to_string(),
+            ));
+        }
+        iterations += 1;
+
+        // Affine (predictor) step.
+        let q = DVector::from_fn(n, |i, _| 1.0 / (z[i] / a[i] + w[i] / s[i]));
+        let r = &z - &w;
+        let x_q = DMatrix::from_fn(n, x.ncols(), |i, j| q[i] * x[(i, j)]);
+        let normal = NormalEquations::new(&x_t * &x_q)?;
+
+        let mut d_dual = normal.solve(&(&x_t * q.component_mul(&r)))?;
+        let mut d_a = q.component_mul(&(x * &d_dual - &r));
+        let mut d_s = -&d_a;
+        let mut d_z = -z.component_mul(&d_a.component_div(&a).add_scalar(1.0));
+        let mut d_w = -w.component_mul(&d_s.component_div(&s).add_scalar(1.0));
+
+        let (mut primal_step, mut dual_step) =
+            step_lengths([(&a, &d_a), (&s, &d_s)], [(&z, &d_z), (&w, &d_w)]);
+
+        // Centering (corrector) step, if the affine step is blocked.
+        if primal_step.min(dual_step) < 1.0 {
+            let mu = z.dot(&a) + w.dot(&s);
+            let g = (&z + dual_step * &d_z).dot(&(&a + primal_step * &d_a))
+                + (&w + dual_step * &d_w).dot(&(&s + primal_step * &d_s));
+            let mu = mu * (g / mu).powi(3) / (2.0 * n as f64);
+
+            let d_a_d_z = d_a.component_mul(&d_z);
+            let d_s_d_w = d_s.component_mul(&d_w);
+            let a_inv = a.map(f64::recip);
+            let s_inv = s.map(f64::recip);
+            let xi = (&a_inv - &s_inv) * mu;
+
+            let rhs = &r + &d_a_d_z - &d_s_d_w - xi;
+            d_dual = normal.solve(&(&x_t * q.component_mul(&rhs)))?;
+            d_a = q.component_mul(&(x * &d_dual - &rhs));
+            d_s = -&d_a;
+            d_z = &a_inv * mu - &z - a_inv.component_mul(&z).component_mul(&d_a) - d_a_d_z;
+            d_w = &s_inv * mu - &w - s_inv.component_mul(&w).component_mul(&d_s) - d_s_d_w;
+
+            (primal_step, dual_step) =
+                step_lengths([(&a, &d_a), (&s, &d_s)], [(&z, &d_z), (&w, &d_w)]);
+        }
+
+        a += primal_step * d_a;
+        s += primal_step * d_s;
+        dual += dual_step * d_dual;
+        z += dual_step * d_z;
+        w += dual_step * d_w;
+    }
+
+    Ok((-dual, iterations))
+}
+
+/// Factorization of the normal equations $X^T Q X$ of an interior point
+/// step. Near the solution the weights $Q$ span many orders of magnitude,
+/// and when the Cholesky factorization fails the LU factorization is used.
+enum NormalEquations {
+    Cholesky(nalgebra::Cholesky<f64, nalgebra::Dyn>),
+    Lu(nalgebra::LU<f64, nalgebra::Dyn, nalgebra::Dyn>),
+}
+
+impl NormalEquations {
+    fn new(matrix: DMatrix<f64>) -> Result<Self, RustQuantError> {
+        match matrix.clone().cholesky() {
+            Some(cholesky) => Ok(Self::Cholesky(cholesky)),
+            None => {
+                let lu = matrix.lu();
+                if lu.is_invertible() {
+                    Ok(Self::Lu(lu))
+                } else {
+                    Err(RustQuantError::MatrixInversionFailed)
+                }
+            }
+        }
+    }
+
+    fn solve(&self, rhs: &DVector<f64>) -> Result<DVector<f64>, RustQuantError> {
+        match self {
+            Self::Cholesky(cholesky) => Ok(cholesky.solve(rhs)),
+            Self::Lu(lu) => lu.solve(rhs).ok_or(RustQuantError::MatrixInversionFailed),
+        }
+    }
+}
+
+/// Primal and dual step lengths: the largest steps, up to one, that keep
+/// the primal and dual variables a fraction [`STEP_FRACTION`] of the way to
+/// zero.
+fn step_lengths(
+    primal: [(&DVector<f64>, &DVector<f64>); 2],
+    dual: [(&DVector<f64>, &DVector<f64>); 2],
+) -> (f64, f64) {
+    let bound = |pairs: [(&DVector<f64>, &DVector<f64>); 2]| {
+        let largest = pairs
+            .iter()
+            .flat_map(|(v, dv)| v.iter().zip(dv.iter()))
+            .filter(|(_, dv_i)| **dv_i < 0.0)
+            .map(|(v_i, dv_i)| -v_i / dv_i)
+            .fold(f64::INFINITY, f64::min);
+
+        (STEP_FRACTION * largest).min(1.0)
+    };
+
+    (bound(primal), bound(dual))
+}
+
+/// Coefficients minimizing the mean pinball loss of `y - x b`, by
+/// subgradient descent from zero.
+fn subgradient_descent(
+    x: &DMatrix<f64>,
+    y: &DVector<f64>,
+    tau: f64,
+    learning_rate: f64,
+    iterations: usize,
+) -> DVector<f64> {
+    let n = y.len() as f64;
+    let loss = |b: &DVector<f64>| {
+        (y - x * b)
+            .iter()
+            .map(|&residual| pinball_loss(residual, tau))
+            .sum::<f64>()
+            / n
+    };
+
+    let mut b = DVector::zeros(x.ncols());
+    let mut best = (loss(&b), b.clone());
+
+    for k in 0..iterations {
+        // Subgradient of the pinball loss with respect to the residuals.
+        let residuals = y - x * &b;
+        let weights = residuals.map(|r_i| if r_i < 0.0 { tau - 1.0 } else { tau });
+
+        b += x.transpose() * weights * (learning_rate / ((k + 1) as f64).sqrt() / n);
+
+        let current = loss(&b);
+        if current < best.0 {
+            best = (current, b.clone());
+        }
+    }
+
+    best.1
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_quantile_regression {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::StandardNormal;
+
+    /// 5% quantile of the standard normal distribution.
+    const Z_05: f64 = -1.644_853_626_951_472_2;
+
+    /// `y = 1 + 2 x + (0.5 + 0.5 x) e`, with `x` uniform on [0, 2] and `e`
+    /// standard normal, so the `tau` quantile of `y` is
+    /// `(1 + 0.5 z) + (2 + 0.5 z) x`, with `z` the standard normal quantile.
+    fn heteroskedastic_data(n: usize, seed: u64) -> QuantileRegressionInput<f64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let x = DMatrix::from_fn(n, 1, |_, _| rng.gen_range(0.0..2.0));
+        let y = DVector::from_fn(n, |i, _| {
+            let e: f64 = rng.sample(StandardNormal);
+            1.0 + 2.0 * x[(i, 0)] + (0.5 + 0.5 * x[(i, 0)]) * e
+        });
+
+        QuantileRegressionInput::new(x, y)
+    }
+
+    #[test]
+    fn test_tail_quantiles_match_theory() -> Result<(), RustQuantError> {
+        let input = heteroskedastic_data(5_000, 1);
+        let set =
+            input.fit_quantiles(&[0.95, 0.05], QuantileRegressionMethod::FrischNewton, true)?;
+
+        assert_eq!(set.taus(), vec![0.05, 0.95]);
+        for (quantile, z) in set.quantiles.iter().zip([Z_05, -Z_05]) {
+            assert!((quantile.intercept - (1.0 + 0.5 * z)).abs() < 0.15);
+            assert!((quantile.coefficients[1] - (2.0 + 0.5 * z)).abs() < 0.15);
+        }
+
+        let predictions = set.predict(&input.x);
+        let inside = input
+            .y
+            .iter()
+            .zip(predictions.row_iter())
+            .filter(|(y, bounds)| bounds[0] <= **y && **y <= bounds[1])
+            .count() as f64
+            / input.y.len() as f64;
+
+        assert!((inside - 0.9).abs() < 0.02, "{inside}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_median_of_symmetric_data() -> Result<(), RustQuantError> {
+        let input = heteroskedastic_data(2_000, 2);
+        let median = input.fit(0.5, QuantileRegressionMethod::FrischNewton)?;
+
+        assert!((median.intercept - 1.0).abs() < 0.1);
+        assert!((median.coefficients[1] - 2.0).abs() < 0.1);
+
+        let below = (&input.y - median.predict(&input.x))
+            .iter()
+            .filter(|residual| **residual < 0.0)
+            .count();
+        assert!(below.abs_diff(1_000) <= 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_subgradient_descent_approaches_optimum() -> Result<(), RustQuantError> {
+        let input = heteroskedastic_data(1_000, 3);
+        let exact = input.fit(0.05, QuantileRegressionMethod::FrischNewton)?;
+        let approximate = input.fit(
+            0.05,
+            QuantileRegressionMethod::SubgradientDescent {
+                learning_rate: 1.0,
+                iterations: 20_000,
+            },
+        )?;
+
+        let optimum = exact.score_pinball(&input.x, &input.y);
+        let loss = approximate.score_pinball(&input.x, &input.y);
+
+        assert!(optimum <= loss + 1e-12);
+        assert!(loss < 1.02 * optimum, "{loss} {optimum}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_rearranged_quantiles_do_not_cross() -> Result<(), RustQuantError> {
+        let input = heteroskedastic_data(50, 4);
+        let set = input.fit_quantiles(
+            &[0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9],
+            QuantileRegressionMethod::FrischNewton,
+            true,
+        )?;
+
+        // Far outside the data, where separately fitted lines may cross.
+        let x = DMatrix::from_column_slice(3, 1, &[-20.0, 1.0, 20.0]);
+        for row in set.predict(&x).row_iter() {
+            assert!(row.iter().zip(row.iter().skip(1)).all(|(a, b)| a <= b));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_quantile() {
+        let input = heteroskedastic_data(10, 5);
+
+        for tau in [0.0, 1.0, f64::NAN] {
+            assert!(input
+                .fit(tau, QuantileRegressionMethod::FrischNewton)
+                .is_err());
+        }
+    }
+}
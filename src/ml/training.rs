@@ -0,0 +1,250 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Training loop for models whose loss is built from `autodiff` variables.
+//!
+//! Each epoch records the loss of the model on a fresh graph, accumulates
+//! its gradient with respect to the parameters, optionally clips it by
+//! its global norm, and passes it to the [`Optimizer`] with the learning
+//! rate of the epoch from the [`LearningRateSchedule`].
+//!
+//! A [`Trainer`] holds the parameters, the optimizer state, and the epoch
+//! count, and is serializable: a trainer saved after some epochs and
+//! loaded again continues exactly as if it had not been interrupted.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{clip_by_global_norm, LearningRateSchedule, Optimizer};
+use crate::autodiff::{variables::variable::Variable, Accumulate, Gradient, Graph};
+use serde::{Deserialize, Serialize};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Gradient-based training of a model's parameters.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trainer<O: Optimizer> {
+    /// Current parameters of the model.
+    pub parameters: Vec<f64>,
+    /// Optimizer, with its state.
+    pub optimizer: O,
+    /// Learning rate of each epoch.
+    pub schedule: LearningRateSchedule,
+    /// Maximum global norm of the gradient, if it is clipped.
+    pub max_gradient_norm: Option<f64>,
+    /// Number of epochs trained.
+    pub epoch: usize,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl<O: Optimizer> Trainer<O> {
+    /// New trainer, starting from the `parameters`.
+    #[must_use]
+    pub fn new(parameters: Vec<f64>, optimizer: O, schedule: LearningRateSchedule) -> Self {
+        Self {
+            parameters,
+            optimizer,
+            schedule,
+            max_gradient_norm: None,
+            epoch: 0,
+        }
+    }
+
+    /// Clips the gradient of each epoch to a global norm of `max_norm`.
+    #[must_use]
+    pub fn with_gradient_clipping(mut self, max_norm: f64) -> Self {
+        self.max_gradient_norm = Some(max_norm);
+        self
+    }
+
+    /// Trains one epoch on the `loss` of the parameters, and returns the
+    /// loss before the update.
+    pub fn train_epoch<F>(&mut self, loss: F) -> f64
+    where
+        F: for<'v> Fn(&[Variable<'v>]) -> Variable<'v>,
+    {
+        let graph = Graph::new();
+        let parameters = graph.vars(&self.parameters);
+        let value = loss(&parameters);
+        let mut gradient = value.accumulate().wrt(&parameters);
+
+        if let Some(max_norm) = self.max_gradient_norm {
+            clip_by_global_norm(&mut gradient, max_norm);
+        }
+
+        let learning_rate = self.schedule.learning_rate(self.epoch);
+        self.optimizer
+            .step(&mut self.parameters, &gradient, learning_rate);
+        self.epoch += 1;
+
+        value.value
+    }
+
+    /// Trains `epochs` epochs, and returns the loss of each.
+    pub fn train<F>(&mut self, loss: F, epochs: usize) -> Vec<f64>
+    where
+        F: for<'v> Fn(&[Variable<'v>]) -> Variable<'v>,
+    {
+        (0..epochs).map(|_| self.train_epoch(&loss)).collect()
+    }
+
+    /// Trains until the loss is at most `target`, for at most `max_epochs`
+    /// epochs, and returns the number of epochs trained to reach it (`None`
+    /// if it was not reached).
+    pub fn train_until<F>(&mut self, loss: F, target: f64, max_epochs: usize) -> Option<usize>
+    where
+        F: for<'v> Fn(&[Variable<'v>]) -> Variable<'v>,
+    {
+        (0..max_epochs).find(|_| self.train_epoch(&loss) <= target)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_training {
+    use super::*;
+    use crate::ml::{Adam, AdamW, RmsProp, Sgd};
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    /// Initial parameters, uniform on [-1, 1] with a fixed seed.
+    fn initial_parameters(n: usize, seed: u64) -> Vec<f64> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        (0..n).map(|_| rng.gen_range(-1.0..1.0)).collect()
+    }
+
+    /// Mean squared error of a 2-2-1 network (tanh hidden layer, sigmoid
+    /// output) on XOR. The parameters are the hidden weights and biases,
+    /// then the output weights and bias.
+    fn xor_loss<'v>(p: &[Variable<'v>]) -> Variable<'v> {
+        let data = [
+            ([0.0, 0.0], 0.0),
+            ([0.0, 1.0], 1.0),
+            ([1.0, 0.0], 1.0),
+            ([1.0, 1.0], 0.0),
+        ];
+
+        let errors: Variable<'v> = data
+            .iter()
+            .map(|([x_1, x_2], y)| {
+                let h_1 = (p[0] * *x_1 + p[1] * *x_2 + p[2]).tanh();
+                let h_2 = (p[3] * *x_1 + p[4] * *x_2 + p[5]).tanh();
+                let output = 1.0 / (1.0 + (-(p[6] * h_1 + p[7] * h_2 + p[8])).exp());
+                let error = output - *y;
+                error * error
+            })
+            .sum();
+
+        errors / data.len() as f64
+    }
+
+    /// Mean squared error of a linear regression on badly scaled features,
+    /// `y = 3 x_1 - 2 x_2 + 1` with `x_2` a hundred times larger.
+    fn regression_loss<'v>(p: &[Variable<'v>]) -> Variable<'v> {
+        let errors: Variable<'v> = (0..20)
+            .map(|i| {
+                let x_1 = f64::from(i) / 20.0;
+                let x_2 = 100.0 * ((f64::from(i) * 0.37).sin());
+                let y = 3.0 * x_1 - 2.0 * x_2 + 1.0;
+                let error = p[0] * x_1 + p[1] * x_2 + p[2] - y;
+                error * error
+            })
+            .sum();
+
+        errors / 20.0
+    }
+
+    #[test]
+    fn test_adam_beats_sgd_on_xor() {
+        let start = initial_parameters(9, 1);
+        let max_epochs = 20_000;
+
+        let mut sgd = Trainer::new(start.clone(), Sgd, LearningRateSchedule::Constant(0.5));
+        let mut adam = Trainer::new(start, Adam::new(), LearningRateSchedule::Constant(0.05));
+
+        let sgd_epochs = sgd.train_until(xor_loss, 0.01, max_epochs);
+        let adam_epochs = adam.train_until(xor_loss, 0.01, max_epochs);
+
+        let adam_epochs = adam_epochs.expect("Adam fits XOR.");
+        assert!(adam_epochs < 100, "{adam_epochs}");
+        assert!(sgd_epochs.is_none_or(|sgd_epochs| sgd_epochs > 4 * adam_epochs));
+    }
+
+    #[test]
+    fn test_adam_beats_sgd_on_regression() {
+        let start = initial_parameters(3, 7);
+        let max_epochs = 20_000;
+
+        // Larger SGD learning rates diverge, since the x_2 direction is so
+        // much steeper.
+        let mut sgd = Trainer::new(start.clone(), Sgd, LearningRateSchedule::Constant(1e-4));
+        let mut adam = Trainer::new(start, Adam::new(), LearningRateSchedule::Constant(0.1));
+
+        let sgd_epochs = sgd.train_until(regression_loss, 1e-4, max_epochs);
+        let adam_epochs = adam.train_until(regression_loss, 1e-4, max_epochs);
+
+        let adam_epochs = adam_epochs.expect("Adam fits the regression.");
+        assert!(adam_epochs < 2_000, "{adam_epochs}");
+        assert!(sgd_epochs.is_none_or(|sgd_epochs| sgd_epochs > 4 * adam_epochs));
+    }
+
+    #[test]
+    fn test_other_optimizers_fit_xor() {
+        let start = initial_parameters(9, 1);
+
+        let mut rmsprop = Trainer::new(
+            start.clone(),
+            RmsProp::new(),
+            LearningRateSchedule::Constant(0.01),
+        );
+        assert!(rmsprop.train_until(xor_loss, 0.01, 5_000).is_some());
+
+        let schedule = LearningRateSchedule::Warmup {
+            epochs: 20,
+            then: Box::new(LearningRateSchedule::Cosine {
+                initial: 0.05,
+                minimum: 0.005,
+                epochs: 2_000,
+            }),
+        };
+        let mut adamw = Trainer::new(start, AdamW::new(1e-4), schedule).with_gradient_clipping(1.0);
+        assert!(adamw.train_until(xor_loss, 0.01, 5_000).is_some());
+    }
+
+    #[test]
+    fn test_resume_matches_uninterrupted_training() {
+        let schedule = LearningRateSchedule::StepDecay {
+            initial: 0.05,
+            factor: 0.5,
+            step: 30,
+        };
+        let start = Trainer::new(initial_parameters(9, 1), AdamW::new(1e-3), schedule)
+            .with_gradient_clipping(0.5);
+
+        let mut uninterrupted = start.clone();
+        let losses = uninterrupted.train(xor_loss, 100);
+
+        let mut first = start;
+        let mut resumed_losses = first.train(xor_loss, 40);
+        let saved = serde_json::to_string(&first).unwrap();
+        let mut resumed: Trainer<AdamW> = serde_json::from_str(&saved).unwrap();
+        resumed_losses.extend(resumed.train(xor_loss, 60));
+
+        assert_eq!(resumed, uninterrupted);
+        assert_eq!(resumed_losses, losses);
+    }
+}
@@ -0,0 +1,284 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Cross-validation schemes for time series.
+//!
+//! Standard k-fold cross-validation shuffles observations into folds, which
+//! leaks future information into the training set when the data is a time
+//! series. The schemes in this module respect the ordering of the data:
+//!
+//! - [`TimeSeriesCV`]: walk-forward splits, where each training window ends
+//!   (at least `gap` observations) before its test window begins.
+//! - [`PurgedKFold`]: k-fold splits from Lopez de Prado (2018), *Advances in
+//!   Financial Machine Learning*, ch. 7, where training observations whose
+//!   labels overlap the test period are purged, and an embargo is applied
+//!   after each test fold.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use std::ops::Range;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Walk-forward time series cross-validation.
+///
+/// The data is divided into `n_splits + 1` blocks of equal size.
+/// The `k`-th split tests on block `k + 1` and trains on the observations
+/// before it, leaving `gap` observations between the end of the training
+/// window and the start of the test window.
+#[derive(Clone, Copy, Debug)]
+pub struct TimeSeriesCV {
+    /// Number of train/test splits.
+    pub n_splits: usize,
+
+    /// Number of observations dropped between each training and test window.
+    pub gap: usize,
+
+    /// Maximum size of the training window.
+    /// - `None`: expanding window (train on all past observations).
+    /// - `Some(size)`: rolling window of at most `size` observations.
+    pub max_train_size: Option<usize>,
+}
+
+/// Purged k-fold cross-validation (Lopez de Prado, 2018).
+///
+/// The data is divided into `n_splits` contiguous test folds. For each fold,
+/// the training set is every other observation, except:
+/// - observations whose label overlaps the test fold are *purged*, and
+/// - the `embargo` observations following the test fold are dropped.
+#[derive(Clone, Copy, Debug)]
+pub struct PurgedKFold {
+    /// Number of folds.
+    pub n_splits: usize,
+
+    /// Number of observations embargoed after each test fold.
+    pub embargo: usize,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl TimeSeriesCV {
+    /// Create a new expanding-window `TimeSeriesCV`.
+    #[must_use]
+    pub fn new(n_splits: usize, gap: usize) -> Self {
+        Self {
+            n_splits,
+            gap,
+            max_train_size: None,
+        }
+    }
+
+    /// Create a new rolling-window `TimeSeriesCV`, whose training windows
+    /// contain at most `window` observations.
+    #[must_use]
+    pub fn rolling(n_splits: usize, gap: usize, window: usize) -> Self {
+        Self {
+            n_splits,
+            gap,
+            max_train_size: Some(window),
+        }
+    }
+
+    /// Generate the `(train, test)` index ranges for `n` observations.
+    ///
+    /// # Panics
+    /// Panics if `n_splits` is zero, or if `n` is too small to leave a
+    /// non-empty training window before the first test window.
+    #[must_use]
+    pub fn split(&self, n: usize) -> Vec<(Range<usize>, Range<usize>)> {
+        assert!(self.n_splits > 0, "Number of splits must be positive.");
+
+        let test_size = n / (self.n_splits + 1);
+        let first_test_start = n - self.n_splits * test_size;
+
+        assert!(
+            test_size > 0,
+            "Too few observations for the number of splits."
+        );
+        assert!(
+            first_test_start > self.gap,
+            "Too few observations for the number of splits and the gap."
+        );
+
+        (0..self.n_splits)
+            .map(|k| {
+                let test_start = first_test_start + k * test_size;
+                let train_end = test_start - self.gap;
+                let train_start = self
+                    .max_train_size
+                    .map_or(0, |size| train_end.saturating_sub(size));
+
+                (train_start..train_end, test_start..test_start + test_size)
+            })
+            .collect()
+    }
+}
+
+impl PurgedKFold {
+    /// Create a new `PurgedKFold`.
+    #[must_use]
+    pub fn new(n_splits: usize, embargo: usize) -> Self {
+        Self { n_splits, embargo }
+    }
+
+    /// Generate the `(train, test)` indices for `n` observations,
+    /// assuming each label only depends on its own observation.
+    ///
+    /// # Panics
+    /// Panics if `n_splits < 2` or `n < n_splits`.
+    #[must_use]
+    pub fn split(&self, n: usize) -> Vec<(Vec<usize>, Range<usize>)> {
+        let label_ends: Vec<usize> = (0..n).collect();
+
+        self.split_with_label_ends(&label_ends)
+    }
+
+    /// Generate the `(train, test)` indices when labels span several observations.
+    ///
+    /// `label_ends[i]` is the index of the last observation used to compute the
+    /// label of observation `i` (e.g. the exit of a triple-barrier trade),
+    /// so `label_ends[i] >= i`. Training observation `i` before a test fold is
+    /// purged if `label_ends[i]` reaches into the fold, and training
+    /// observations after the fold start only once the labels of the test
+    /// observations have ended, plus the embargo.
+    ///
+    /// # Panics
+    /// Panics if `n_splits < 2`, if there are fewer observations than folds,
+    /// or if `label_ends[i] < i` for some `i`.
+    #[must_use]
+    pub fn split_with_label_ends(&self, label_ends: &[usize]) -> Vec<(Vec<usize>, Range<usize>)> {
+        let n = label_ends.len();
+
+        assert!(self.n_splits >= 2, "Number of splits must be at least 2.");
+        assert!(
+            n >= self.n_splits,
+            "Too few observations for the number of splits."
+        );
+        assert!(
+            label_ends.iter().enumerate().all(|(i, &end)| end >= i),
+            "Labels cannot end before their observation."
+        );
+
+        // Distribute the remainder over the first folds, as in standard k-fold.
+        let fold_size = n / self.n_splits;
+        let remainder = n % self.n_splits;
+
+        let mut splits = Vec::with_capacity(self.n_splits);
+        let mut test_start = 0;
+
+        for k in 0..self.n_splits {
+            let test_end = test_start + fold_size + usize::from(k < remainder);
+
+            let test_label_end = label_ends[test_start..test_end]
+                .iter()
+                .copied()
+                .max()
+                .unwrap_or(test_end - 1);
+            let resume = test_label_end.max(test_end - 1) + 1 + self.embargo;
+
+            let train = (0..test_start)
+                .filter(|&i| label_ends[i] < test_start)
+                .chain(resume.min(n)..n)
+                .collect();
+
+            splits.push((train, test_start..test_end));
+            test_start = test_end;
+        }
+
+        splits
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_time_series_cv {
+    use super::*;
+
+    #[test]
+    fn test_expanding_window_splits() {
+        let cv = TimeSeriesCV::new(4, 0);
+        let splits = cv.split(100);
+
+        assert_eq!(splits.len(), 4);
+        assert_eq!(splits[0], (0..20, 20..40));
+        assert_eq!(splits[3], (0..80, 80..100));
+    }
+
+    #[test]
+    fn test_rolling_window_with_gap() {
+        let cv = TimeSeriesCV::rolling(3, 5, 30);
+
+        for (train, test) in cv.split(103) {
+            assert!(train.len() <= 30);
+            assert_eq!(test.start - train.end, 5);
+        }
+    }
+
+    #[test]
+    fn test_time_series_cv_has_no_lookahead() {
+        for gap in [0, 1, 7] {
+            for (train, test) in TimeSeriesCV::new(5, gap).split(250) {
+                assert!(!train.is_empty() && !test.is_empty());
+                assert!(train.clone().all(|i| !test.contains(&i)));
+                assert!(train.end + gap <= test.start);
+            }
+        }
+    }
+
+    #[test]
+    fn test_purged_k_fold_covers_all_observations() {
+        let splits = PurgedKFold::new(4, 0).split(103);
+        let tested: Vec<usize> = splits.iter().flat_map(|(_, test)| test.clone()).collect();
+
+        assert_eq!(tested, (0..103).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn test_purged_k_fold_no_test_index_in_train() {
+        for (train, test) in PurgedKFold::new(5, 3).split(100) {
+            assert!(train.iter().all(|i| !test.contains(i)));
+
+            // Embargo: nothing in the 3 observations after the test fold.
+            assert!(train.iter().all(|&i| i < test.start || i >= test.end + 3));
+        }
+    }
+
+    #[test]
+    fn test_purged_k_fold_purges_overlapping_labels() {
+        // Each label looks 4 observations ahead.
+        let n = 60;
+        let label_ends: Vec<usize> = (0..n).map(|i| (i + 4).min(n - 1)).collect();
+
+        for (train, test) in PurgedKFold::new(3, 2).split_with_label_ends(&label_ends) {
+            let test_label_end = label_ends[test.clone()].iter().max().copied().unwrap();
+
+            for &i in &train {
+                assert!(!test.contains(&i));
+
+                // No training label overlaps the test period...
+                let overlaps = i <= test_label_end && label_ends[i] >= test.start;
+                assert!(!overlaps);
+
+                // ...and the embargo follows the end of the test labels.
+                if i > test.start {
+                    assert!(i > test_label_end + 2);
+                }
+            }
+        }
+    }
+}
@@ -36,4 +36,58 @@ impl CoxIngersollRoss {
             theta: theta.into(),
         }
     }
+
+    /// Whether the Feller condition $2 \theta \mu \ge \sigma^2$ holds for
+    /// the parameters at $t = 0$, so the process never reaches zero.
+    pub fn feller_satisfied(&self) -> bool {
+        self.feller_violation().is_none()
+    }
+
+    /// The Feller condition violation at $t = 0$, if any.
+    pub fn feller_violation(&self) -> Option<FellerViolationWarning> {
+        FellerViolationWarning::check(self.theta.0(0.0), self.mu.0(0.0), self.sigma.0(0.0))
+    }
+
+    /// Lower the volatility to the Feller boundary $\sqrt{2 \theta \mu}$
+    /// if it is above it. The volatility becomes constant.
+    pub fn enforce_feller(&mut self) {
+        if let Some(violation) = self.feller_violation() {
+            self.sigma = violation.two_kappa_theta.max(0.0).sqrt().into();
+        }
+    }
+}
+
+/// Warning that a square-root (CIR) process violates the Feller condition
+/// $2 \kappa \theta \ge \sigma^2$, so it can reach zero.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FellerViolationWarning {
+    /// $2 \kappa \theta$, for the mean reversion rate $\kappa$ and the
+    /// long-run level $\theta$.
+    pub two_kappa_theta: f64,
+    /// $\sigma^2$.
+    pub sigma_squared: f64,
+}
+
+impl FellerViolationWarning {
+    /// The violation for mean reversion rate `kappa`, long-run level
+    /// `theta`, and volatility `sigma`, if any.
+    pub fn check(kappa: f64, theta: f64, sigma: f64) -> Option<Self> {
+        let two_kappa_theta = 2.0 * kappa * theta;
+        let sigma_squared = sigma * sigma;
+
+        (two_kappa_theta < sigma_squared).then_some(Self {
+            two_kappa_theta,
+            sigma_squared,
+        })
+    }
+}
+
+impl std::fmt::Display for FellerViolationWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Feller condition violated: 2 kappa theta = {} < sigma^2 = {}.",
+            self.two_kappa_theta, self.sigma_squared
+        )
+    }
 }
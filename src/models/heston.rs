@@ -7,6 +7,7 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+use crate::models::cox_ingersoll_ross::FellerViolationWarning;
 use crate::models::model_parameter::ModelParameter;
 
 /// Struct containing the Heston model parameters.
@@ -44,4 +45,28 @@ impl Heston {
             volatility_of_volatility: volatility_of_volatility.into(),
         }
     }
+
+    /// Whether the variance process satisfies the Feller condition
+    /// $2 \kappa \theta \ge \sigma^2$ at $t = 0$, so it never reaches zero.
+    pub fn feller_satisfied(&self) -> bool {
+        self.feller_violation().is_none()
+    }
+
+    /// The Feller condition violation at $t = 0$, if any.
+    pub fn feller_violation(&self) -> Option<FellerViolationWarning> {
+        FellerViolationWarning::check(
+            self.mean_reversion_rate.0(0.0),
+            self.long_run_variance.0(0.0),
+            self.volatility_of_volatility.0(0.0),
+        )
+    }
+
+    /// Lower the volatility of volatility to the Feller boundary
+    /// $\sqrt{2 \kappa \theta}$ if it is above it. The volatility of
+    /// volatility becomes constant.
+    pub fn enforce_feller(&mut self) {
+        if let Some(violation) = self.feller_violation() {
+            self.volatility_of_volatility = violation.two_kappa_theta.max(0.0).sqrt().into();
+        }
+    }
 }
@@ -7,8 +7,37 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+//! Nelson-Siegel-Svensson (1994) yield curve model.
+//!
+//! The spot rate for maturity $t$ (in years) is
+//!
+//! $$
+//! y(t) = \beta_0 + \beta_1 \frac{1 - e^{-t/\tau_1}}{t/\tau_1}
+//!     + \beta_2 \left[\frac{1 - e^{-t/\tau_1}}{t/\tau_1} - e^{-t/\tau_1}\right]
+//!     + \beta_3 \left[\frac{1 - e^{-t/\tau_2}}{t/\tau_2} - e^{-t/\tau_2}\right]
+//! $$
+//!
+//! where the $\beta_3$ term adds a second hump to the Nelson-Siegel curve.
+//! Rates are in percent.
+//!
+//! [`NelsonSiegelSvensson::fit`] fits the model to observed yields by least
+//! squares. Given the time scales, the spot rate is linear in the betas,
+//! so they are solved for by linear least squares, and only the time
+//! scales are optimized (variable projection): a grid search over
+//! $(\tau_1, \tau_2)$ gives the starting point of L-BFGS on their
+//! logarithms, which keeps them positive, with gradients from `autodiff`.
+//! Optimizing all six parameters at once is badly conditioned: the betas
+//! drift to large values that nearly cancel.
+
+use crate::autodiff::{variables::variable::Variable, Accumulate, Gradient as _, Graph};
 use crate::data::CurveModel;
+use crate::error::CalibrationError;
 use crate::time::{today, DayCountConvention};
+use argmin::{
+    core::{CostFunction, Executor, Gradient, State},
+    solver::{linesearch::MoreThuenteLineSearch, quasinewton::LBFGS},
+};
+use nalgebra::{DMatrix, DVector};
 use time::Date;
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -30,36 +59,127 @@ pub struct NelsonSiegelSvensson {
     /// $\beta_3$
     pub beta3: f64,
 
-    /// $\lambda_1$
-    pub lambda1: f64,
+    /// $\tau_1$
+    pub tau1: f64,
+
+    /// $\tau_2$
+    pub tau2: f64,
+}
 
-    /// $\lambda_2$
-    pub lambda2: f64,
+/// Least squares fit of the spot rate to yields, as a function of
+/// $(\ln \tau_1, \ln \tau_2)$, with the betas solved for each.
+struct FitProblem<'a> {
+    maturities: &'a [f64],
+    yields: &'a [f64],
 }
 
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
+/// Time scales (in years) of the grid search in [`NelsonSiegelSvensson::fit`].
+const TAU_GRID: [f64; 16] = [
+    0.1, 0.2, 0.3, 0.5, 0.75, 1.0, 1.5, 2.0, 3.0, 4.0, 5.0, 7.0, 10.0, 15.0, 20.0, 30.0,
+];
+
+/// Maximum number of L-BFGS iterations in [`NelsonSiegelSvensson::fit`].
+const FIT_MAX_ITERATIONS: u64 = 1_000;
+
 impl NelsonSiegelSvensson {
-    /// Create a new Nelson-Siegel model.
+    /// Create a new Nelson-Siegel-Svensson model.
     #[must_use]
-    pub const fn new(
-        beta0: f64,
-        beta1: f64,
-        beta2: f64,
-        beta3: f64,
-        lambda1: f64,
-        lambda2: f64,
-    ) -> Self {
+    pub const fn new(beta0: f64, beta1: f64, beta2: f64, beta3: f64, tau1: f64, tau2: f64) -> Self {
         Self {
             beta0,
             beta1,
             beta2,
             beta3,
-            lambda1,
-            lambda2,
+            tau1,
+            tau2,
+        }
+    }
+
+    /// Spot rate for maturity `t`, in years.
+    #[must_use]
+    pub fn spot_rate(&self, t: f64) -> f64 {
+        let [l1, l2, l3] = loadings(t, self.tau1, self.tau2);
+
+        self.beta0 + self.beta1 * l1 + self.beta2 * l2 + self.beta3 * l3
+    }
+
+    /// Fits the model to the `yields` observed for the `maturities` (in
+    /// years), by least squares, with $\tau_1, \tau_2 > 0$ and
+    /// $\tau_1 \neq \tau_2$.
+    ///
+    /// # Errors
+    /// - `CalibrationError::InvalidData` if there are fewer than six
+    ///   points, the slices have different lengths, or a maturity is not
+    ///   positive or a yield not finite.
+    /// - `CalibrationError::NotConverged` if the optimizer fails.
+    /// - `CalibrationError::DegenerateParameters` if the fitted time
+    ///   scales coincide, which leaves the $\beta_2$ and $\beta_3$ terms
+    ///   indistinguishable.
+    pub fn fit(maturities: &[f64], yields: &[f64]) -> Result<Self, CalibrationError> {
+        if maturities.len() != yields.len() {
+            return Err(CalibrationError::InvalidData(
+                "There must be one yield per maturity.",
+            ));
+        }
+        if maturities.len() < 6 {
+            return Err(CalibrationError::InvalidData(
+                "At least six points are needed to fit six parameters.",
+            ));
+        }
+        if maturities.iter().any(|t| !t.is_finite() || *t <= 0.0)
+            || yields.iter().any(|y| !y.is_finite())
+        {
+            return Err(CalibrationError::InvalidData(
+                "Maturities must be positive and yields finite.",
+            ));
+        }
+
+        let problem = FitProblem { maturities, yields };
+
+        // The loss has a local minimum for each ordering of the time
+        // scales, so both are refined.
+        let mut best: Option<(f64, Vec<f64>)> = None;
+        for start in problem.grid_search() {
+            let solver = LBFGS::new(MoreThuenteLineSearch::new(), 7)
+                .with_tolerance_grad(1e-12)
+                .map_err(|_| CalibrationError::NotConverged)?;
+            let Ok(result) = Executor::new(FitProblem { maturities, yields }, solver)
+                .configure(|state| state.param(start).max_iters(FIT_MAX_ITERATIONS))
+                .run()
+            else {
+                continue;
+            };
+
+            let state = result.state();
+            if let Some(u) = state.get_best_param() {
+                let cost = state.get_best_cost();
+                if cost.is_finite() && best.as_ref().is_none_or(|(best_cost, _)| cost < *best_cost)
+                {
+                    best = Some((cost, u.clone()));
+                }
+            }
         }
+
+        let (tau1, tau2) = match best {
+            Some((_, u)) if u.iter().all(|u| u.is_finite()) => (u[0].exp(), u[1].exp()),
+            _ => return Err(CalibrationError::NotConverged),
+        };
+        let betas = FitProblem { maturities, yields }
+            .betas(tau1, tau2)
+            .ok_or(CalibrationError::NotConverged)?;
+
+        let nss = Self::new(betas[0], betas[1], betas[2], betas[3], tau1, tau2);
+        if (nss.tau1 - nss.tau2).abs() <= 1e-8 * nss.tau1.max(nss.tau2) {
+            return Err(CalibrationError::DegenerateParameters(
+                "The time scales coincide.",
+            ));
+        }
+
+        Ok(nss)
     }
 }
 
@@ -68,11 +188,11 @@ impl CurveModel for NelsonSiegelSvensson {
     fn forward_rate(&self, date: Date) -> f64 {
         assert!(date > today(), "Date must be in the future.");
 
-        let tau = DayCountConvention::default().day_count_factor(today(), date);
+        let t = DayCountConvention::default().day_count_factor(today(), date);
 
-        let term1 = f64::exp(-tau / self.lambda1);
-        let term2 = (tau / self.lambda1) * term1;
-        let term3 = (tau / self.lambda2) * f64::exp(-tau / self.lambda2);
+        let term1 = f64::exp(-t / self.tau1);
+        let term2 = (t / self.tau1) * term1;
+        let term3 = (t / self.tau2) * f64::exp(-t / self.tau2);
 
         self.beta0 + self.beta1 * term1 + self.beta2 * term2 + self.beta3 * term3
     }
@@ -81,20 +201,123 @@ impl CurveModel for NelsonSiegelSvensson {
     fn spot_rate(&self, date: Date) -> f64 {
         assert!(date > today(), "Date must be in the future.");
 
-        let tau = DayCountConvention::default().day_count_factor(today(), date);
+        let t = DayCountConvention::default().day_count_factor(today(), date);
 
-        let term1 = self.lambda1 * (1. - f64::exp(-tau / self.lambda1)) / tau;
-        let term2 = term1 - f64::exp(-tau / self.lambda1);
-        let term3 = self.lambda2 * (1. - f64::exp(-tau / self.lambda2)) / tau
-            - f64::exp(-tau / self.lambda2);
-
-        self.beta0 + self.beta1 * term1 + self.beta2 * term2 + self.beta3 * term3
+        NelsonSiegelSvensson::spot_rate(self, t)
     }
 
     fn discount_factor(&self, date: Date) -> f64 {
-        let tau = DayCountConvention::default().day_count_factor(today(), date);
+        let t = DayCountConvention::default().day_count_factor(today(), date);
+
+        f64::exp(-CurveModel::spot_rate(self, date) * t / 100.)
+    }
+}
+
+/// Factor loadings of $\beta_1$, $\beta_2$, and $\beta_3$ at maturity `t`.
+fn loadings(t: f64, tau1: f64, tau2: f64) -> [f64; 3] {
+    let (x1, x2) = (t / tau1, t / tau2);
+    let slope = (1.0 - (-x1).exp()) / x1;
+
+    [
+        slope,
+        slope - (-x1).exp(),
+        (1.0 - (-x2).exp()) / x2 - (-x2).exp(),
+    ]
+}
+
+impl FitProblem<'_> {
+    /// Betas minimizing the squared residuals for the time scales.
+    fn betas(&self, tau1: f64, tau2: f64) -> Option<Vec<f64>> {
+        let x = DMatrix::from_fn(self.maturities.len(), 4, |i, j| match j {
+            0 => 1.0,
+            _ => loadings(self.maturities[i], tau1, tau2)[j - 1],
+        });
+        let y = DVector::from_column_slice(self.yields);
+        let betas = x.svd(true, true).solve(&y, 1e-12).ok()?;
+
+        betas
+            .iter()
+            .all(|beta| beta.is_finite())
+            .then(|| betas.as_slice().to_vec())
+    }
+
+    /// Best logarithms of the time scales over the grid, with
+    /// $\tau_1 < \tau_2$ and with $\tau_1 > \tau_2$.
+    fn grid_search(&self) -> Vec<Vec<f64>> {
+        let mut best: [Option<(f64, Vec<f64>)>; 2] = [None, None];
 
-        f64::exp(-self.spot_rate(date) * tau / 100.)
+        for &tau1 in &TAU_GRID {
+            for &tau2 in TAU_GRID.iter().filter(|tau2| **tau2 != tau1) {
+                let u = vec![tau1.ln(), tau2.ln()];
+                let Ok(cost) = self.cost(&u) else {
+                    continue;
+                };
+
+                let best = &mut best[usize::from(tau1 > tau2)];
+                if best.as_ref().is_none_or(|(best_cost, _)| cost < *best_cost) {
+                    *best = Some((cost, u));
+                }
+            }
+        }
+
+        best.into_iter().flatten().map(|(_, u)| u).collect()
+    }
+
+    /// Mean squared residual for the `betas`, as a function of the
+    /// logarithms `u` of the time scales.
+    fn loss<'v>(&self, betas: &[f64], u: &[Variable<'v>]) -> Variable<'v> {
+        let (tau1, tau2) = (u[0].exp(), u[1].exp());
+
+        let squares: Variable<'v> = self
+            .maturities
+            .iter()
+            .zip(self.yields)
+            .map(|(t, y)| {
+                let (x1, x2) = (*t / tau1, *t / tau2);
+                let slope = (1.0 - (-x1).exp()) / x1;
+                let hump_1 = slope - (-x1).exp();
+                let hump_2 = (1.0 - (-x2).exp()) / x2 - (-x2).exp();
+                let residual =
+                    slope * betas[1] + hump_1 * betas[2] + hump_2 * betas[3] + (betas[0] - *y);
+
+                residual * residual
+            })
+            .sum();
+
+        squares / self.maturities.len() as f64
+    }
+
+    /// The betas for the time scales `exp(u)`.
+    fn projected_betas(&self, u: &[f64]) -> Result<Vec<f64>, argmin::core::Error> {
+        self.betas(u[0].exp(), u[1].exp())
+            .ok_or_else(|| argmin::core::Error::msg("Singular least squares problem."))
+    }
+}
+
+impl CostFunction for FitProblem<'_> {
+    type Param = Vec<f64>;
+    type Output = f64;
+
+    fn cost(&self, u: &Self::Param) -> Result<Self::Output, argmin::core::Error> {
+        let betas = self.projected_betas(u)?;
+        let graph = Graph::new();
+
+        Ok(self.loss(&betas, &graph.vars(u)).value)
+    }
+}
+
+impl Gradient for FitProblem<'_> {
+    type Param = Vec<f64>;
+    type Gradient = Vec<f64>;
+
+    /// Since the betas minimize the loss, its gradient is the partial
+    /// derivative with the betas held fixed.
+    fn gradient(&self, u: &Self::Param) -> Result<Self::Gradient, argmin::core::Error> {
+        let betas = self.projected_betas(u)?;
+        let graph = Graph::new();
+        let variables = graph.vars(u);
+
+        Ok(self.loss(&betas, &variables).accumulate().wrt(&variables))
     }
 }
 
@@ -114,8 +337,8 @@ mod tests_nelson_siegel_svensson {
             beta1: -0.0031,
             beta2: -0.0625,
             beta3: -0.0198,
-            lambda1: 1.58,
-            lambda2: 0.15,
+            tau1: 1.58,
+            tau2: 0.15,
         };
 
         let dates = (2..365 * 30)
@@ -138,4 +361,60 @@ mod tests_nelson_siegel_svensson {
         //     "./images/nelson_siegel_svensson_discount.png"
         // );
     }
+
+    // US Treasury par yield curve on 2019-12-31, in percent.
+    const MATURITIES: [f64; 12] = [
+        1.0 / 12.0,
+        2.0 / 12.0,
+        0.25,
+        0.5,
+        1.0,
+        2.0,
+        3.0,
+        5.0,
+        7.0,
+        10.0,
+        20.0,
+        30.0,
+    ];
+    const YIELDS: [f64; 12] = [
+        1.48, 1.51, 1.55, 1.60, 1.59, 1.58, 1.62, 1.69, 1.83, 1.92, 2.25, 2.39,
+    ];
+
+    #[test]
+    fn test_fit_treasury_curve() {
+        let nss = NelsonSiegelSvensson::fit(&MATURITIES, &YIELDS).unwrap();
+
+        assert!(nss.tau1 > 0.0 && nss.tau2 > 0.0 && nss.tau1 != nss.tau2);
+        for (t, y) in MATURITIES.iter().zip(YIELDS) {
+            let residual = nss.spot_rate(*t) - y;
+            assert!(residual.abs() < 0.05, "{t}: {residual}");
+        }
+    }
+
+    #[test]
+    fn test_fit_recovers_parameters() {
+        let true_nss = NelsonSiegelSvensson::new(4.0, 1.5, -2.0, 3.0, 1.2, 8.0);
+        let yields: Vec<f64> = MATURITIES.iter().map(|t| true_nss.spot_rate(*t)).collect();
+
+        let nss = NelsonSiegelSvensson::fit(&MATURITIES, &yields).unwrap();
+        for t in [0.1, 1.5, 4.0, 12.0, 25.0] {
+            assert!((nss.spot_rate(t) - true_nss.spot_rate(t)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_fit_invalid_data() {
+        assert_eq!(
+            NelsonSiegelSvensson::fit(&MATURITIES[..5], &YIELDS[..5]),
+            Err(CalibrationError::InvalidData(
+                "At least six points are needed to fit six parameters."
+            ))
+        );
+        assert!(NelsonSiegelSvensson::fit(&MATURITIES, &YIELDS[1..]).is_err());
+
+        let mut maturities = MATURITIES;
+        maturities[0] = 0.0;
+        assert!(NelsonSiegelSvensson::fit(&maturities, &YIELDS).is_err());
+    }
 }
@@ -25,11 +25,11 @@
 //! // Create a position of 100 call options.
 //! let position_1 = Position {
 //!    instrument: BlackScholesMerton::new(
-//!         0.08,
-//!         60.0,
-//!         65.0,
-//!         0.3,
-//!         0.08,
+//!         0.08.into(),
+//!         60.0.into(),
+//!         65.0.into(),
+//!         0.3.into(),
+//!         0.08.into(),
 //!         None,
 //!         today() + Duration::days(91),
 //!         TypeFlag::Call,
@@ -43,11 +43,11 @@
 //! // Create a position of 100 put options.
 //! let position_2 = Position {
 //!    instrument: BlackScholesMerton::new(
-//!         0.1 - 0.05,
-//!         100.0,
-//!         95.0,
-//!         0.2,
-//!         0.1,
+//!         (0.1 - 0.05).into(),
+//!         100.0.into(),
+//!         95.0.into(),
+//!         0.2.into(),
+//!         0.1.into(),
 //!         None,
 //!         today() + Duration::days(182),
 //!         TypeFlag::Put,
@@ -80,6 +80,14 @@
 use crate::{instruments::fx::currency::Currency, instruments::Instrument};
 use std::collections::HashMap;
 
+/// Black-Litterman expected return model.
+pub mod black_litterman;
+pub use black_litterman::*;
+
+/// Bond portfolio immunization.
+pub mod immunization;
+pub use immunization::*;
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // STRUCTS, ENUMS, AND TRAITS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -243,11 +251,11 @@ mod tests_portfolio {
         // Create a position of 100 call options.
         let position_1 = Position {
             instrument: BlackScholesMerton::new(
-                0.08,
-                60.0,
-                65.0,
-                0.3,
-                0.08,
+                0.08.into(),
+                60.0.into(),
+                65.0.into(),
+                0.3.into(),
+                0.08.into(),
                 None,
                 today() + Duration::days(91),
                 TypeFlag::Call,
@@ -261,11 +269,11 @@ mod tests_portfolio {
         // Create a position of 100 put options.
         let position_2 = Position {
             instrument: BlackScholesMerton::new(
-                0.1 - 0.05,
-                100.0,
-                95.0,
-                0.2,
-                0.1,
+                (0.1 - 0.05).into(),
+                100.0.into(),
+                95.0.into(),
+                0.2.into(),
+                0.1.into(),
                 None,
                 today() + Duration::days(182),
                 TypeFlag::Put,
@@ -0,0 +1,404 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Black-Litterman expected return model.
+//!
+//! The prior is the market equilibrium: the expected returns $\Pi$ implied by
+//! reverse-optimizing the market-capitalization weights,
+//!
+//! $$
+//! \Pi = \delta \Sigma w_{mkt}
+//! $$
+//!
+//! The investor's views are expressed as $P \mu = Q + \varepsilon$ with
+//! $\varepsilon \sim N(0, \Omega)$, and are blended with the prior to give the
+//! posterior expected returns and covariance:
+//!
+//! $$
+//! \bar{\mu} = \Pi + \tau \Sigma P^T (P \tau \Sigma P^T + \Omega)^{-1} (Q - P \Pi)
+//! $$
+//!
+//! $$
+//! \bar{\Sigma} = \Sigma + \tau \Sigma - \tau \Sigma P^T (P \tau \Sigma P^T + \Omega)^{-1} P \tau \Sigma
+//! $$
+//!
+//! References:
+//! - He, G. and Litterman, R. (1999), *The Intuition Behind Black-Litterman Model Portfolios*.
+//! - Idzorek, T. (2005), *A Step-by-Step Guide to the Black-Litterman Model*.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use nalgebra::{DMatrix, DVector};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Black-Litterman model inputs describing the market prior.
+#[derive(Clone, Debug)]
+pub struct BlackLitterman {
+    /// Covariance matrix of asset returns, $\Sigma$.
+    pub covariance: DMatrix<f64>,
+
+    /// Market-capitalization weights, $w_{mkt}$.
+    pub market_weights: DVector<f64>,
+
+    /// Risk-aversion coefficient, $\delta$.
+    pub risk_aversion: f64,
+
+    /// Scaling of the uncertainty of the prior, $\tau$.
+    pub tau: f64,
+}
+
+/// Uncertainty of the views, $\Omega$.
+#[derive(Clone, Debug)]
+pub enum ViewUncertainty {
+    /// He and Litterman (1999): $\Omega = \text{diag}(P \tau \Sigma P^T)$.
+    Proportional,
+
+    /// Idzorek (2005): a confidence level in $(0, 1]$ for each view.
+    ///
+    /// Uses the closed form $\omega_k = \frac{1 - c_k}{c_k} p_k \tau \Sigma p_k^T$,
+    /// so a confidence of 100% gives $\omega_k = 0$ (the view holds exactly),
+    /// and a confidence of 50% gives the same $\omega_k$ as
+    /// [`ViewUncertainty::Proportional`].
+    Idzorek(Vec<f64>),
+
+    /// A user-supplied $\Omega$ matrix.
+    Custom(DMatrix<f64>),
+}
+
+/// Investor views on the expected returns.
+#[derive(Clone, Debug)]
+pub struct BlackLittermanViews {
+    /// Pick matrix, $P$ (one row per view, one column per asset).
+    pub pick: DMatrix<f64>,
+
+    /// Expected returns of the views, $Q$.
+    pub returns: DVector<f64>,
+
+    /// Uncertainty of the views, $\Omega$.
+    pub uncertainty: ViewUncertainty,
+}
+
+/// Black-Litterman posterior distribution of returns.
+#[derive(Clone, Debug)]
+pub struct BlackLittermanOutput {
+    /// Posterior expected returns, $\bar{\mu}$.
+    pub expected_returns: DVector<f64>,
+
+    /// Posterior covariance of returns, $\bar{\Sigma}$.
+    pub covariance: DMatrix<f64>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl BlackLittermanViews {
+    /// Create a new set of views.
+    #[must_use]
+    pub fn new(pick: DMatrix<f64>, returns: DVector<f64>, uncertainty: ViewUncertainty) -> Self {
+        Self {
+            pick,
+            returns,
+            uncertainty,
+        }
+    }
+}
+
+impl BlackLitterman {
+    /// Create a new Black-Litterman model.
+    #[must_use]
+    pub fn new(
+        covariance: DMatrix<f64>,
+        market_weights: DVector<f64>,
+        risk_aversion: f64,
+        tau: f64,
+    ) -> Self {
+        Self {
+            covariance,
+            market_weights,
+            risk_aversion,
+            tau,
+        }
+    }
+
+    /// Implied equilibrium excess returns, $\Pi = \delta \Sigma w_{mkt}$.
+    #[must_use]
+    pub fn equilibrium_returns(&self) -> DVector<f64> {
+        self.risk_aversion * &self.covariance * &self.market_weights
+    }
+
+    /// The prior distribution of returns (i.e. the posterior with no views):
+    /// expected returns $\Pi$ and covariance $(1 + \tau) \Sigma$.
+    #[must_use]
+    pub fn prior(&self) -> BlackLittermanOutput {
+        BlackLittermanOutput {
+            expected_returns: self.equilibrium_returns(),
+            covariance: (1.0 + self.tau) * &self.covariance,
+        }
+    }
+
+    /// View uncertainty matrix $\Omega$ for the given views.
+    ///
+    /// # Errors
+    /// - `RustQuantError::InvalidArgument` if the dimensions are inconsistent
+    ///   or an Idzorek confidence is outside $(0, 1]$.
+    pub fn omega(&self, views: &BlackLittermanViews) -> Result<DMatrix<f64>, RustQuantError> {
+        let k = views.pick.nrows();
+        let view_variances = || {
+            let tau_sigma = self.tau * &self.covariance;
+            (&views.pick * tau_sigma * views.pick.transpose()).diagonal()
+        };
+
+        match &views.uncertainty {
+            ViewUncertainty::Proportional => Ok(DMatrix::from_diagonal(&view_variances())),
+            ViewUncertainty::Idzorek(confidences) => {
+                if confidences.len() != k {
+                    return Err(RustQuantError::InvalidArgument(
+                        "Black-Litterman: one confidence level is required per view.".to_string(),
+                    ));
+                }
+                if confidences.iter().any(|&c| c <= 0.0 || c > 1.0) {
+                    return Err(RustQuantError::InvalidArgument(
+                        "Black-Litterman: confidence levels must be in (0, 1].".to_string(),
+                    ));
+                }
+
+                let variances = view_variances();
+                let diagonal = DVector::from_fn(k, |i, _| {
+                    variances[i] * (1.0 - confidences[i]) / confidences[i]
+                });
+
+                Ok(DMatrix::from_diagonal(&diagonal))
+            }
+            ViewUncertainty::Custom(omega) => {
+                if omega.shape() != (k, k) {
+                    return Err(RustQuantError::InvalidArgument(
+                        "Black-Litterman: Omega must be a K x K matrix.".to_string(),
+                    ));
+                }
+
+                Ok(omega.clone())
+            }
+        }
+    }
+
+    /// Posterior expected returns and covariance, combining the prior with `views`.
+    ///
+    /// With no views (`None`, or a pick matrix with zero rows) the prior is
+    /// returned exactly.
+    ///
+    /// # Errors
+    /// - `RustQuantError::InvalidArgument` if the dimensions are inconsistent.
+    /// - `RustQuantError::MatrixInversionFailed` if $P \tau \Sigma P^T + \Omega$ is singular.
+    pub fn posterior(
+        &self,
+        views: Option<&BlackLittermanViews>,
+    ) -> Result<BlackLittermanOutput, RustQuantError> {
+        let n = self.market_weights.len();
+
+        if self.covariance.shape() != (n, n) {
+            return Err(RustQuantError::InvalidArgument(
+                "Black-Litterman: covariance must be an N x N matrix.".to_string(),
+            ));
+        }
+
+        let views = match views {
+            Some(views) if views.pick.nrows() > 0 => views,
+            _ => return Ok(self.prior()),
+        };
+
+        if views.pick.ncols() != n || views.returns.len() != views.pick.nrows() {
+            return Err(RustQuantError::InvalidArgument(
+                "Black-Litterman: P must be K x N and Q must have length K.".to_string(),
+            ));
+        }
+
+        let pi = self.equilibrium_returns();
+        let tau_sigma = self.tau * &self.covariance;
+        let omega = self.omega(views)?;
+
+        let p = &views.pick;
+        let tau_sigma_pt = &tau_sigma * p.transpose();
+        let a_inv = (p * &tau_sigma_pt + omega)
+            .try_inverse()
+            .ok_or(RustQuantError::MatrixInversionFailed)?;
+
+        let gain = &tau_sigma_pt * a_inv;
+        let expected_returns = &pi + &gain * (&views.returns - p * &pi);
+        let covariance = &self.covariance + &tau_sigma - gain * tau_sigma_pt.transpose();
+
+        Ok(BlackLittermanOutput {
+            expected_returns,
+            covariance,
+        })
+    }
+
+    /// Unconstrained mean-variance optimal weights for a posterior,
+    /// $w^* = (\delta \bar{\Sigma})^{-1} \bar{\mu}$.
+    ///
+    /// # Errors
+    /// - `RustQuantError::MatrixInversionFailed` if $\bar{\Sigma}$ is singular.
+    pub fn optimal_weights(
+        &self,
+        posterior: &BlackLittermanOutput,
+    ) -> Result<DVector<f64>, RustQuantError> {
+        (self.risk_aversion * &posterior.covariance)
+            .cholesky()
+            .map(|cholesky| cholesky.solve(&posterior.expected_returns))
+            .ok_or(RustQuantError::MatrixInversionFailed)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_black_litterman {
+    use super::*;
+
+    // He and Litterman (1999): Australia, Canada, France, Germany, Japan, UK, USA.
+    fn he_litterman_model() -> BlackLitterman {
+        let volatilities = [0.160, 0.203, 0.248, 0.271, 0.210, 0.200, 0.187];
+
+        #[rustfmt::skip]
+        let correlation = DMatrix::from_row_slice(7, 7, &[
+            1.000, 0.488, 0.478, 0.515, 0.439, 0.512, 0.491,
+            0.488, 1.000, 0.664, 0.655, 0.310, 0.608, 0.779,
+            0.478, 0.664, 1.000, 0.861, 0.355, 0.783, 0.668,
+            0.515, 0.655, 0.861, 1.000, 0.354, 0.777, 0.653,
+            0.439, 0.310, 0.355, 0.354, 1.000, 0.405, 0.306,
+            0.512, 0.608, 0.783, 0.777, 0.405, 1.000, 0.652,
+            0.491, 0.779, 0.668, 0.653, 0.306, 0.652, 1.000,
+        ]);
+
+        let covariance = DMatrix::from_fn(7, 7, |i, j| {
+            volatilities[i] * volatilities[j] * correlation[(i, j)]
+        });
+        let market_weights =
+            DVector::from_row_slice(&[0.016, 0.022, 0.052, 0.055, 0.116, 0.124, 0.615]);
+
+        BlackLitterman::new(covariance, market_weights, 2.5, 0.05)
+    }
+
+    fn he_litterman_views(uncertainty: ViewUncertainty) -> BlackLittermanViews {
+        // View 1: Germany outperforms the rest of Europe by 5%.
+        // View 2: Canada outperforms the USA by 3%.
+        #[rustfmt::skip]
+        let pick = DMatrix::from_row_slice(2, 7, &[
+            0.0, 0.0, -0.295, 1.0, 0.0, -0.705,  0.0,
+            0.0, 1.0,  0.0,   0.0, 0.0,  0.0,   -1.0,
+        ]);
+
+        BlackLittermanViews::new(pick, DVector::from_row_slice(&[0.05, 0.03]), uncertainty)
+    }
+
+    #[test]
+    fn test_equilibrium_returns() {
+        let model = he_litterman_model();
+        let pi = model.equilibrium_returns();
+
+        // Table 2 of He and Litterman (1999), in percent.
+        let expected = [3.9, 6.9, 8.4, 9.0, 4.3, 6.8, 7.6];
+
+        for (i, &e) in expected.iter().enumerate() {
+            assert_approx_equal!(100.0 * pi[i], e, 0.05);
+        }
+    }
+
+    #[test]
+    fn test_he_litterman_posterior() -> Result<(), RustQuantError> {
+        let model = he_litterman_model();
+        let views = he_litterman_views(ViewUncertainty::Proportional);
+        let posterior = model.posterior(Some(&views))?;
+        let weights = model.optimal_weights(&posterior)?;
+
+        // Table 4 of He and Litterman (1999), in percent.
+        let expected_returns = [4.4, 8.7, 9.5, 11.2, 4.6, 7.0, 7.5];
+        let expected_weights = [1.5, 41.9, -3.4, 33.6, 11.0, -8.2, 18.8];
+
+        for i in 0..7 {
+            assert_approx_equal!(
+                100.0 * posterior.expected_returns[i],
+                expected_returns[i],
+                0.05
+            );
+            assert_approx_equal!(100.0 * weights[i], expected_weights[i], 0.05);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_no_views_returns_prior() -> Result<(), RustQuantError> {
+        let model = he_litterman_model();
+        let prior = model.prior();
+
+        let empty = BlackLittermanViews::new(
+            DMatrix::zeros(0, 7),
+            DVector::zeros(0),
+            ViewUncertainty::Proportional,
+        );
+
+        for views in [None, Some(&empty)] {
+            let posterior = model.posterior(views)?;
+
+            assert_eq!(posterior.expected_returns, prior.expected_returns);
+            assert_eq!(posterior.covariance, prior.covariance);
+        }
+
+        // With no views, the optimal portfolio is the market scaled by 1 / (1 + tau).
+        let weights = model.optimal_weights(&prior)?;
+        for i in 0..7 {
+            assert_approx_equal!(weights[i], model.market_weights[i] / 1.05, 1e-12);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_idzorek_confidence() -> Result<(), RustQuantError> {
+        let model = he_litterman_model();
+
+        // 50% confidence is equivalent to the He-Litterman choice of Omega.
+        let proportional =
+            model.posterior(Some(&he_litterman_views(ViewUncertainty::Proportional)))?;
+        let half = model.posterior(Some(&he_litterman_views(ViewUncertainty::Idzorek(vec![
+            0.5, 0.5,
+        ]))))?;
+
+        for i in 0..7 {
+            assert_approx_equal!(
+                proportional.expected_returns[i],
+                half.expected_returns[i],
+                1e-14
+            );
+        }
+
+        // 100% confidence means the views hold exactly.
+        let views = he_litterman_views(ViewUncertainty::Idzorek(vec![1.0, 1.0]));
+        let full = model.posterior(Some(&views))?;
+        let implied = &views.pick * &full.expected_returns;
+
+        assert_approx_equal!(implied[0], 0.05, 1e-12);
+        assert_approx_equal!(implied[1], 0.03, 1e-12);
+
+        // Confidence levels must lie in (0, 1].
+        let invalid = he_litterman_views(ViewUncertainty::Idzorek(vec![0.0, 0.5]));
+        assert!(model.posterior(Some(&invalid)).is_err());
+
+        Ok(())
+    }
+}
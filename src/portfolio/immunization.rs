@@ -0,0 +1,492 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Immunization of a stream of liabilities with a portfolio of bonds.
+//!
+//! The portfolio has the present value of the liabilities, and value
+//! weights $w_i \geq 0$ (summing to one) in the bonds of the universe. With
+//! bond durations $D_i$ and convexities $C_i$ at their yields, it is chosen
+//! to solve the quadratic program
+//! $$
+//! \min_w \| E w - \ell \|^2 + \epsilon \| w \|^2
+//! \quad \text{s.t.} \quad
+//! \sum_i w_i = 1, \quad \sum_i w_i D_i = D^*, \quad
+//! \sum_i w_i C_i \geq C_L, \quad w \geq 0,
+//! $$
+//! where $E_{ki}$ is the fraction of the present value of bond $i$ paid in
+//! year $k$ and $\ell_k$ that of the liabilities: the tracking error is the
+//! mismatch of the timing of the cash flows. $D^*$ is the target duration
+//! and $C_L$ the convexity of the liabilities, so a parallel shift of the
+//! yields does not (to second order) leave the portfolio short of the
+//! liabilities when $D^*$ is their duration. The small ridge $\epsilon$
+//! makes the solution unique.
+//!
+//! The convexity constraint becomes an equality with a non-negative slack,
+//! and the equalities are enforced by heavily weighted rows, so the
+//! program is a non-negative least squares problem, solved exactly with
+//! the active set method of Lawson and Hanson (1974).
+//!
+//! Durations and convexities are as in [`StepUpBond`] (continuously
+//! compounded), at each bond's yield to maturity from its price. The
+//! liabilities are discounted at the mean yield of the universe, from the
+//! evaluation date of the first bond.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::cashflows::Cashflow;
+use crate::error::RustQuantError;
+use crate::instruments::bonds::StepUpBond;
+use crate::time::DayCountConvention;
+use nalgebra::{DMatrix, DVector};
+use time::Date;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Immunizing portfolio of a stream of liabilities.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImmunizationResult {
+    /// Fraction of the portfolio value in each bond.
+    pub weights: Vec<f64>,
+    /// Number of units of each bond.
+    pub quantities: Vec<f64>,
+    /// Duration of the portfolio.
+    pub duration: f64,
+    /// Convexity of the portfolio.
+    pub convexity: f64,
+    /// Present value of the liabilities (and of the portfolio).
+    pub liability_value: f64,
+    /// Duration of the liabilities.
+    pub liability_duration: f64,
+    /// Convexity of the liabilities.
+    pub liability_convexity: f64,
+    /// Root sum of squares of the differences between the fractions of the
+    /// present values of the portfolio and the liabilities paid each year.
+    pub tracking_error: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Weight of the equality constraints in the least squares problem.
+const CONSTRAINT_WEIGHT: f64 = 1e4;
+
+/// Ridge penalty $\epsilon$ on the weights.
+const RIDGE: f64 = 1e-8;
+
+/// Relative tolerance on the constraints of the solution.
+const TOLERANCE: f64 = 1e-6;
+
+/// Portfolio of `bond_universe`, bought at `prices`, immunizing
+/// `liabilities` with duration `target_duration` (see the module
+/// documentation). Liabilities due on or before the evaluation date are
+/// ignored.
+///
+/// # Errors
+/// - `UnequalLength` if there is not one price per bond.
+/// - `InvalidArgument` if the universe is empty, a price or the target
+///   duration is not positive, or no liability is due after the
+///   evaluation date.
+/// - `ComputationError` if no long-only portfolio has the target duration
+///   and at least the convexity of the liabilities.
+pub fn immunize(
+    liabilities: &[Cashflow],
+    bond_universe: &[StepUpBond],
+    prices: &[f64],
+    target_duration: f64,
+) -> Result<ImmunizationResult, RustQuantError> {
+    if bond_universe.len() != prices.len() {
+        return Err(RustQuantError::UnequalLength);
+    }
+    if bond_universe.is_empty() {
+        return Err(RustQuantError::InvalidArgument(
+            "The bond universe is empty.".to_string(),
+        ));
+    }
+    if prices.iter().any(|price| price.is_nan() || *price <= 0.0) {
+        return Err(RustQuantError::InvalidArgument(
+            "Bond prices must be positive.".to_string(),
+        ));
+    }
+    if target_duration.is_nan() || target_duration <= 0.0 {
+        return Err(RustQuantError::InvalidArgument(
+            "The target duration must be positive.".to_string(),
+        ));
+    }
+
+    let n = bond_universe.len();
+    let evaluation_date = bond_universe[0].evaluation_date().date();
+
+    let yields: Vec<f64> = bond_universe
+        .iter()
+        .zip(prices)
+        .map(|(bond, price)| bond.yield_to_maturity(*price))
+        .collect();
+    let durations: Vec<f64> = bond_universe
+        .iter()
+        .zip(&yields)
+        .map(|(bond, y)| bond.duration(*y))
+        .collect();
+    let convexities: Vec<f64> = bond_universe
+        .iter()
+        .zip(&yields)
+        .map(|(bond, y)| bond.convexity(*y))
+        .collect();
+
+    let liability_flows: Vec<(f64, f64)> = liabilities
+        .iter()
+        .map(|cashflow| {
+            (
+                year_fraction(evaluation_date, cashflow.date),
+                cashflow.amount,
+            )
+        })
+        .filter(|(t, _)| *t > 0.0)
+        .collect();
+    if liability_flows.is_empty() {
+        return Err(RustQuantError::InvalidArgument(
+            "No liability is due after the evaluation date.".to_string(),
+        ));
+    }
+
+    let liability_yield = yields.iter().sum::<f64>() / n as f64;
+    let (liability_value, liability_duration, liability_convexity) =
+        value_duration_convexity(&liability_flows, liability_yield);
+
+    // Fractions of present value paid in each year.
+    let bond_flows: Vec<Vec<(f64, f64)>> = bond_universe
+        .iter()
+        .map(|bond| {
+            let evaluation_date = bond.evaluation_date().date();
+            bond.cash_flows()
+                .iter()
+                .map(|(date, amount)| (year_fraction(evaluation_date, date.date()), *amount))
+                .collect()
+        })
+        .collect();
+
+    let n_buckets = bond_flows
+        .iter()
+        .flatten()
+        .chain(&liability_flows)
+        .map(|(t, _)| t.ceil() as usize)
+        .max()
+        .unwrap_or(1)
+        .max(1);
+
+    let liability_profile = present_value_profile(&liability_flows, liability_yield, n_buckets);
+    let bond_profiles: Vec<DVector<f64>> = bond_flows
+        .iter()
+        .zip(&yields)
+        .map(|(flows, y)| present_value_profile(flows, *y, n_buckets))
+        .collect();
+
+    // Variables: the weights and the convexity slack. Rows: the budget,
+    // duration, and convexity constraints (scaled to order one), the
+    // tracking error, and the ridge.
+    let m = 3 + n_buckets + n;
+    let mut a = DMatrix::<f64>::zeros(m, n + 1);
+    let mut b = DVector::<f64>::zeros(m);
+
+    let convexity_scale = liability_convexity.max(1.0);
+    for i in 0..n {
+        a[(0, i)] = CONSTRAINT_WEIGHT;
+        a[(1, i)] = CONSTRAINT_WEIGHT * durations[i] / target_duration.max(1.0);
+        a[(2, i)] = CONSTRAINT_WEIGHT * convexities[i] / convexity_scale;
+        a.view_mut((3, i), (n_buckets, 1))
+            .copy_from(&bond_profiles[i]);
+        a[(3 + n_buckets + i, i)] = RIDGE.sqrt();
+    }
+    a[(2, n)] = -CONSTRAINT_WEIGHT;
+
+    b[0] = CONSTRAINT_WEIGHT;
+    b[1] = CONSTRAINT_WEIGHT * target_duration / target_duration.max(1.0);
+    b[2] = CONSTRAINT_WEIGHT * liability_convexity / convexity_scale;
+    b.rows_mut(3, n_buckets).copy_from(&liability_profile);
+
+    let solution = nnls(&a, &b);
+    let weights: Vec<f64> = solution.iter().take(n).copied().collect();
+
+    let dot = |x: &[f64]| weights.iter().zip(x).map(|(w, x)| w * x).sum::<f64>();
+    let duration = dot(&durations);
+    let convexity = dot(&convexities);
+    let total_weight = weights.iter().sum::<f64>();
+
+    if (total_weight - 1.0).abs() > TOLERANCE
+        || (duration - target_duration).abs() > TOLERANCE * target_duration.max(1.0)
+        || convexity < liability_convexity - TOLERANCE * convexity_scale
+    {
+        return Err(RustQuantError::ComputationError(format!(
+            "No long-only portfolio has duration {target_duration} and convexity of at least {liability_convexity}."
+        )));
+    }
+
+    let portfolio_profile = bond_profiles
+        .iter()
+        .zip(&weights)
+        .fold(DVector::zeros(n_buckets), |profile, (bond, w)| {
+            profile + bond * *w
+        });
+
+    Ok(ImmunizationResult {
+        quantities: weights
+            .iter()
+            .zip(prices)
+            .map(|(w, price)| w * liability_value / price)
+            .collect(),
+        weights,
+        duration,
+        convexity,
+        liability_value,
+        liability_duration,
+        liability_convexity,
+        tracking_error: (portfolio_profile - liability_profile).norm(),
+    })
+}
+
+/// Year fraction from `start` to `end` with the default day count.
+fn year_fraction(start: Date, end: Date) -> f64 {
+    DayCountConvention::default().day_count_factor(start, end)
+}
+
+/// Present value, duration, and convexity of cash flows `(t, amount)` at
+/// the continuously compounded yield `y`.
+fn value_duration_convexity(flows: &[(f64, f64)], y: f64) -> (f64, f64, f64) {
+    let (value, first, second) = flows
+        .iter()
+        .fold((0.0, 0.0, 0.0), |(v, d, c), (t, amount)| {
+            let present_value = amount * (-y * t).exp();
+            (
+                v + present_value,
+                d + t * present_value,
+                c + t * t * present_value,
+            )
+        });
+
+    (value, first / value, second / value)
+}
+
+/// Fractions of the present value at yield `y` of cash flows `(t, amount)`
+/// paid in each of the years $(k, k + 1]$.
+fn present_value_profile(flows: &[(f64, f64)], y: f64, n_buckets: usize) -> DVector<f64> {
+    let mut profile = DVector::<f64>::zeros(n_buckets);
+    for (t, amount) in flows {
+        let bucket = (t.ceil() as usize).clamp(1, n_buckets) - 1;
+        profile[bucket] += amount * (-y * t).exp();
+    }
+
+    let total = profile.sum();
+    profile / total
+}
+
+/// Non-negative least squares, $\min_x \| A x - b \|$ subject to
+/// $x \geq 0$, by the active set method of Lawson and Hanson.
+fn nnls(a: &DMatrix<f64>, b: &DVector<f64>) -> DVector<f64> {
+    let n = a.ncols();
+    let tolerance = 1e-12 * a.norm() * b.norm().max(1.0);
+
+    let mut x = DVector::<f64>::zeros(n);
+    let mut passive = vec![false; n];
+
+    // Least squares on the passive columns, zero elsewhere.
+    let solve_passive = |passive: &[bool]| {
+        let columns: Vec<usize> = (0..n).filter(|j| passive[*j]).collect();
+        let sub = DMatrix::from_fn(a.nrows(), columns.len(), |i, k| a[(i, columns[k])]);
+        let solution = sub
+            .svd(true, true)
+            .solve(b, 1e-14)
+            .unwrap_or_else(|_| DVector::zeros(columns.len()));
+
+        let mut z = DVector::zeros(n);
+        for (k, j) in columns.iter().enumerate() {
+            z[*j] = solution[k];
+        }
+        z
+    };
+
+    for _ in 0..3 * n + 10 {
+        let gradient = a.transpose() * (b - a * &x);
+        let entering = (0..n)
+            .filter(|j| !passive[*j] && gradient[*j] > tolerance)
+            .max_by(|i, j| gradient[*i].total_cmp(&gradient[*j]));
+
+        let Some(entering) = entering else {
+            break;
+        };
+        passive[entering] = true;
+
+        loop {
+            let z = solve_passive(&passive);
+            if (0..n).filter(|j| passive[*j]).all(|j| z[j] > 0.0) {
+                x = z;
+                break;
+            }
+
+            // Step towards z until a passive variable hits zero.
+            let alpha = (0..n)
+                .filter(|j| passive[*j] && z[*j] <= 0.0)
+                .map(|j| x[j] / (x[j] - z[j]))
+                .fold(f64::INFINITY, f64::min);
+            x += (z - &x) * alpha;
+
+            for j in 0..n {
+                if passive[j] && x[j] <= tolerance {
+                    passive[j] = false;
+                    x[j] = 0.0;
+                }
+            }
+        }
+    }
+
+    x
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_immunization {
+    use super::*;
+    use crate::assert_approx_equal;
+    use time::{Month, OffsetDateTime};
+
+    const YIELD: f64 = 0.04;
+
+    fn january_first(year: i32) -> Date {
+        Date::from_calendar_date(year, Month::January, 1).unwrap()
+    }
+
+    /// Annual coupon bond maturing in `years`, evaluated on 2024-01-01.
+    fn coupon_bond(coupon_rate: f64, years: i32) -> StepUpBond {
+        let date = |k: i32| january_first(2024 + k).midnight().assume_utc();
+        let schedule: Vec<(OffsetDateTime, f64)> =
+            (1..=years).map(|k| (date(k), coupon_rate)).collect();
+
+        StepUpBond {
+            evaluation_date: Some(date(0)),
+            ..StepUpBond::new(100.0.into(), schedule, date(years))
+        }
+    }
+
+    fn universe() -> (Vec<StepUpBond>, Vec<f64>) {
+        let bonds: Vec<StepUpBond> = [(0.02, 1), (0.03, 3), (0.035, 5), (0.04, 10), (0.045, 20)]
+            .iter()
+            .map(|(coupon, years)| coupon_bond(*coupon, *years))
+            .collect();
+        let prices = bonds
+            .iter()
+            .map(|bond| bond.price_from_yield(YIELD))
+            .collect();
+
+        (bonds, prices)
+    }
+
+    /// 1 million a year for 15 years, from 2027.
+    fn pension_liabilities() -> Vec<Cashflow> {
+        (3..18)
+            .map(|k| Cashflow::new(1_000_000.0, january_first(2024 + k)))
+            .collect()
+    }
+
+    #[test]
+    fn test_matches_liability_duration() -> Result<(), RustQuantError> {
+        let (bonds, prices) = universe();
+        let liabilities = pension_liabilities();
+
+        let flows: Vec<(f64, f64)> = (3..18).map(|k| (k as f64, 1_000_000.0)).collect();
+        let (_, target, _) = value_duration_convexity(&flows, YIELD);
+        let result = immunize(&liabilities, &bonds, &prices, target)?;
+
+        assert_approx_equal!(result.liability_duration, target, 1e-6);
+        assert!((result.duration - target).abs() < 0.01);
+        assert!(result.convexity >= result.liability_convexity - 1e-6);
+        assert!(result.weights.iter().all(|w| *w >= 0.0));
+        assert_approx_equal!(result.weights.iter().sum::<f64>(), 1.0, 1e-6);
+        assert!(result.tracking_error < 0.5);
+
+        // The portfolio is worth the liabilities, and covers them after
+        // parallel shifts of the yield curve.
+        let portfolio_value = |y: f64| {
+            bonds
+                .iter()
+                .zip(&result.quantities)
+                .map(|(bond, q)| q * bond.price_from_yield(y))
+                .sum::<f64>()
+        };
+        let liability_value = |y: f64| {
+            liabilities
+                .iter()
+                .map(|cf| cf.amount * (-y * year_fraction(january_first(2024), cf.date)).exp())
+                .sum::<f64>()
+        };
+
+        assert_approx_equal!(
+            portfolio_value(YIELD),
+            result.liability_value,
+            1e-9 * result.liability_value
+        );
+        assert_approx_equal!(liability_value(YIELD), result.liability_value, 1e-3);
+        for shift in [-0.02, -0.01, 0.01, 0.02] {
+            let surplus = portfolio_value(YIELD + shift) - liability_value(YIELD + shift);
+            assert!(
+                surplus > -1e-6 * result.liability_value,
+                "{shift}: {surplus}"
+            );
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_other_targets() -> Result<(), RustQuantError> {
+        let (bonds, prices) = universe();
+        let liabilities = pension_liabilities();
+
+        for target in [8.5, 10.0] {
+            let result = immunize(&liabilities, &bonds, &prices, target)?;
+            assert!((result.duration - target).abs() < 0.01);
+            assert!(result.convexity >= result.liability_convexity - 1e-6);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_infeasible_and_invalid_inputs() {
+        let (bonds, prices) = universe();
+        let liabilities = pension_liabilities();
+
+        // Longer than the longest bond.
+        assert!(immunize(&liabilities, &bonds, &prices, 25.0).is_err());
+        // Short duration cannot reach the convexity of the liabilities.
+        assert!(immunize(&liabilities, &bonds, &prices, 1.5).is_err());
+
+        assert!(immunize(&liabilities, &bonds, &prices[1..], 8.0).is_err());
+        assert!(immunize(&liabilities, &[], &[], 8.0).is_err());
+        assert!(immunize(&[], &bonds, &prices, 8.0).is_err());
+        assert!(immunize(&liabilities, &bonds, &prices, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_nnls() {
+        // The unconstrained solution (1, -1) is infeasible; the best with
+        // x >= 0 has x2 = 0 and x1 = 1/2.
+        let a = DMatrix::from_row_slice(3, 2, &[1.0, 0.0, 1.0, 1.0, 0.0, 1.0]);
+        let b = DVector::from_column_slice(&[1.0, 0.0, -1.0]);
+
+        let x = nnls(&a, &b);
+        assert_approx_equal!(x[0], 0.5, 1e-12);
+        assert_eq!(x[1], 0.0);
+    }
+}
@@ -0,0 +1,366 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Pricing audit trail.
+//!
+//! An [`AuditRecord`] holds everything that produced a price: the model,
+//! the instrument parameters, identifiers and content hashes of the market
+//! data, intermediate quantities (e.g. $d_1$, $d_2$, the forward, and the
+//! discount factor), the result, and any further outputs such as Greeks.
+//! Records serialize to JSON with floats that round-trip exactly, so an
+//! instrument rebuilt from a stored record reprices bit-for-bit (see
+//! [`BlackScholesMerton::from_audit_record`](crate::instruments::options::BlackScholesMerton::from_audit_record)).
+//!
+//! Audited entry points push their records to an [`AuditCollector`]. With
+//! the `tracing` feature, each record is also emitted as a structured
+//! `tracing` event with target `rustquant::audit`.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use crate::trading::risk::AutodiffGreeks;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use time::{Date, Month};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Identifier of a piece of market data (a quote, curve, or surface), with
+/// a hash of its content.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MarketDataId {
+    /// Name of the market data, e.g. `"USD-SOFR"` or `"SPX-vol"`.
+    pub id: String,
+    /// FNV-1a hash of the bits of the values, as 16 hex digits.
+    pub content_hash: String,
+}
+
+/// Inputs, intermediate quantities, and result of one pricing.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct AuditRecord {
+    /// Pricing model.
+    pub model: String,
+    /// Numeric inputs by name.
+    pub parameters: BTreeMap<String, f64>,
+    /// Non-numeric inputs by name (flags, dates, symbols).
+    pub labels: BTreeMap<String, String>,
+    /// Market data used.
+    pub market_data: Vec<MarketDataId>,
+    /// Intermediate quantities by name.
+    pub intermediates: BTreeMap<String, f64>,
+    /// Price.
+    pub result: f64,
+    /// Further outputs (e.g. Greeks) by name.
+    pub outputs: BTreeMap<String, f64>,
+}
+
+/// Collects [`AuditRecord`]s in place of (or, with the `tracing` feature,
+/// as well as) logging them.
+#[derive(Debug, Clone, Default)]
+pub struct AuditCollector {
+    records: Vec<AuditRecord>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl MarketDataId {
+    /// Identifier `id` of market data with the given `values`.
+    #[must_use]
+    pub fn new(id: &str, values: &[f64]) -> Self {
+        Self {
+            id: id.to_string(),
+            content_hash: format!("{:016x}", content_hash(values)),
+        }
+    }
+}
+
+impl AuditRecord {
+    /// Empty record of a pricing with `model`.
+    #[must_use]
+    pub fn new(model: &str) -> Self {
+        Self {
+            model: model.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Add a numeric input.
+    #[must_use]
+    pub fn with_parameter(mut self, name: &str, value: f64) -> Self {
+        self.parameters.insert(name.to_string(), value);
+        self
+    }
+
+    /// Add a non-numeric input.
+    #[must_use]
+    pub fn with_label(mut self, name: &str, value: impl ToString) -> Self {
+        self.labels.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Add a date input, as `YYYY-MM-DD`.
+    #[must_use]
+    pub fn with_date(self, name: &str, date: Date) -> Self {
+        let label = format!(
+            "{:04}-{:02}-{:02}",
+            date.year(),
+            u8::from(date.month()),
+            date.day()
+        );
+        self.with_label(name, label)
+    }
+
+    /// Add the identifier of market data used.
+    #[must_use]
+    pub fn with_market_data(mut self, market_data: MarketDataId) -> Self {
+        self.market_data.push(market_data);
+        self
+    }
+
+    /// Add an intermediate quantity.
+    #[must_use]
+    pub fn with_intermediate(mut self, name: &str, value: f64) -> Self {
+        self.intermediates.insert(name.to_string(), value);
+        self
+    }
+
+    /// Set the price.
+    #[must_use]
+    pub fn with_result(mut self, result: f64) -> Self {
+        self.result = result;
+        self
+    }
+
+    /// Add a further output.
+    #[must_use]
+    pub fn with_output(mut self, name: &str, value: f64) -> Self {
+        self.outputs.insert(name.to_string(), value);
+        self
+    }
+
+    /// Add the price, delta, gamma, vega, and vanna of `greeks` as outputs.
+    #[must_use]
+    pub fn with_greeks(self, greeks: &AutodiffGreeks) -> Self {
+        self.with_output("greeks_price", greeks.price)
+            .with_output("delta", greeks.delta)
+            .with_output("gamma", greeks.gamma)
+            .with_output("vega", greeks.vega)
+            .with_output("vanna", greeks.vanna)
+    }
+
+    /// Numeric input `name`.
+    ///
+    /// # Errors
+    /// - `MissingInput` if the record has no such input.
+    pub fn parameter(&self, name: &str) -> Result<f64, RustQuantError> {
+        self.parameters.get(name).copied().ok_or_else(|| {
+            RustQuantError::MissingInput(format!("No parameter '{name}' in the audit record."))
+        })
+    }
+
+    /// Non-numeric input `name`.
+    ///
+    /// # Errors
+    /// - `MissingInput` if the record has no such input.
+    pub fn label(&self, name: &str) -> Result<&str, RustQuantError> {
+        self.labels.get(name).map(String::as_str).ok_or_else(|| {
+            RustQuantError::MissingInput(format!("No label '{name}' in the audit record."))
+        })
+    }
+
+    /// Date input `name`.
+    ///
+    /// # Errors
+    /// - `MissingInput` if the record has no such input.
+    /// - `InvalidArgument` if it is not a `YYYY-MM-DD` date.
+    pub fn date(&self, name: &str) -> Result<Date, RustQuantError> {
+        let label = self.label(name)?;
+        let invalid = || RustQuantError::InvalidArgument(format!("Invalid date '{label}'."));
+
+        let mut parts = label.splitn(3, '-');
+        let mut next = || parts.next().ok_or_else(invalid);
+        let (year, month, day) = (next()?, next()?, next()?);
+
+        let year = year.parse::<i32>().map_err(|_| invalid())?;
+        let month = month
+            .parse::<u8>()
+            .ok()
+            .and_then(|m| Month::try_from(m).ok())
+            .ok_or_else(invalid)?;
+        let day = day.parse::<u8>().map_err(|_| invalid())?;
+
+        Date::from_calendar_date(year, month, day).map_err(|_| invalid())
+    }
+
+    /// Serialize to JSON.
+    ///
+    /// # Errors
+    /// - `ComputationError` if serialization fails.
+    pub fn to_json(&self) -> Result<String, RustQuantError> {
+        serde_json::to_string(self).map_err(|e| RustQuantError::ComputationError(e.to_string()))
+    }
+
+    /// Deserialize from JSON.
+    ///
+    /// # Errors
+    /// - `InvalidArgument` if `json` is not a valid record.
+    pub fn from_json(json: &str) -> Result<Self, RustQuantError> {
+        serde_json::from_str(json).map_err(|e| RustQuantError::InvalidArgument(e.to_string()))
+    }
+}
+
+impl AuditCollector {
+    /// Empty collector.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `record`, emitting it as a `tracing` event if the feature is on.
+    pub fn record(&mut self, record: AuditRecord) {
+        emit(&record);
+        self.records.push(record);
+    }
+
+    /// Records collected so far, in order.
+    #[must_use]
+    pub fn records(&self) -> &[AuditRecord] {
+        &self.records
+    }
+
+    /// The collected records.
+    #[must_use]
+    pub fn into_records(self) -> Vec<AuditRecord> {
+        self.records
+    }
+
+    /// Serialize the collected records to a JSON array.
+    ///
+    /// # Errors
+    /// - `ComputationError` if serialization fails.
+    pub fn to_json(&self) -> Result<String, RustQuantError> {
+        serde_json::to_string(&self.records)
+            .map_err(|e| RustQuantError::ComputationError(e.to_string()))
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Emits `record` as a `tracing` event with target `rustquant::audit`.
+#[cfg(feature = "tracing")]
+pub fn emit(record: &AuditRecord) {
+    tracing::info!(
+        target: "rustquant::audit",
+        model = %record.model,
+        parameters = ?record.parameters,
+        labels = ?record.labels,
+        market_data = ?record.market_data,
+        intermediates = ?record.intermediates,
+        outputs = ?record.outputs,
+        result = record.result,
+        "priced"
+    );
+}
+
+/// Emits `record` as a `tracing` event; does nothing without the `tracing`
+/// feature.
+#[cfg(not(feature = "tracing"))]
+pub fn emit(_record: &AuditRecord) {}
+
+/// 64-bit FNV-1a hash of the bits of `values`, stable across platforms and
+/// releases.
+fn content_hash(values: &[f64]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    values
+        .iter()
+        .flat_map(|value| value.to_bits().to_le_bytes())
+        .fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+        })
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_audit {
+    use super::*;
+
+    #[test]
+    fn test_market_data_id() {
+        let curve = [0.01, 0.015, 0.02];
+        let id = MarketDataId::new("USD", &curve);
+
+        assert_eq!(id.content_hash.len(), 16);
+        assert_eq!(id, MarketDataId::new("USD", &curve));
+        assert_ne!(
+            id.content_hash,
+            MarketDataId::new("USD", &[0.01, 0.015, 0.020_000_000_000_001]).content_hash
+        );
+        // FNV-1a of no bytes is the offset basis.
+        assert_eq!(MarketDataId::new("", &[]).content_hash, "cbf29ce484222325");
+    }
+
+    #[test]
+    fn test_json_round_trip_is_exact() {
+        let record = AuditRecord::new("test")
+            .with_parameter("x", 0.1 + 0.2)
+            .with_parameter("tiny", f64::MIN_POSITIVE)
+            .with_label("flag", "Call")
+            .with_date("expiry", time::macros::date!(2025 - 03 - 07))
+            .with_market_data(MarketDataId::new("curve", &[1.0 / 3.0]))
+            .with_intermediate("d1", std::f64::consts::PI)
+            .with_result(1.0 / 7.0)
+            .with_output("delta", -2.0 / 3.0);
+
+        let restored = AuditRecord::from_json(&record.to_json().unwrap()).unwrap();
+
+        assert_eq!(restored, record);
+        assert_eq!(
+            restored.parameter("x").unwrap().to_bits(),
+            (0.1_f64 + 0.2).to_bits()
+        );
+        assert_eq!(restored.label("flag").unwrap(), "Call");
+        assert_eq!(
+            restored.date("expiry").unwrap(),
+            time::macros::date!(2025 - 03 - 07)
+        );
+        assert!(restored.parameter("y").is_err());
+        assert!(AuditRecord::from_json("{").is_err());
+        assert!(AuditRecord::new("x")
+            .with_label("d", "2025-13-01")
+            .date("d")
+            .is_err());
+    }
+
+    #[test]
+    fn test_collector() {
+        let mut collector = AuditCollector::new();
+        collector.record(AuditRecord::new("a").with_result(1.0));
+        collector.record(AuditRecord::new("b").with_result(2.0));
+
+        assert_eq!(collector.records().len(), 2);
+        assert_eq!(collector.records()[1].model, "b");
+
+        let json = collector.to_json().unwrap();
+        let restored: Vec<AuditRecord> = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, collector.into_records());
+    }
+}
@@ -58,11 +58,11 @@ impl Merton1976 {
     #[must_use]
     pub fn price(&self) -> f64 {
         let mut bsm = BlackScholesMerton::new(
-            self.risk_free_rate,
-            self.underlying_price,
-            self.strike_price,
-            self.volatility,
-            self.risk_free_rate,
+            self.risk_free_rate.into(),
+            self.underlying_price.into(),
+            self.strike_price.into(),
+            self.volatility.into(),
+            self.risk_free_rate.into(),
             self.evaluation_date,
             self.expiration_date,
             self.type_flag,
@@ -0,0 +1,176 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023-2024 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Monte Carlo pricing of a book of instruments under a shared path budget.
+//!
+//! Each instrument is first priced with a pilot run to estimate the
+//! variance of its estimator. The rest of the budget is then split with
+//! [`allocate_budget`], so instruments with noisier payoffs or tighter
+//! target errors get more paths.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::math::allocate_budget;
+use crate::pricer::{MonteCarloEngine, MonteCarloResult};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Monte Carlo engine with a boxed payoff, so a book can hold different
+/// payoffs.
+pub type BookEngine = MonteCarloEngine<Box<dyn Fn(&[f64]) -> f64>>;
+
+/// Prices a book of instruments, allocating a total path budget by the
+/// pilot variance of each instrument and its target standard error.
+pub struct BookPricer {
+    instruments: Vec<(BookEngine, f64)>,
+    pilot_paths: usize,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Default for BookPricer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BookPricer {
+    /// New, empty book, with pilot runs of 1,000 paths.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            instruments: Vec::new(),
+            pilot_paths: 1_000,
+        }
+    }
+
+    /// Number of pilot paths per instrument.
+    ///
+    /// # Panics
+    /// Panics if `pilot_paths` is less than two (the variance needs two).
+    #[must_use]
+    pub fn with_pilot_paths(mut self, pilot_paths: usize) -> Self {
+        assert!(pilot_paths >= 2, "Need at least two pilot paths.");
+        self.pilot_paths = pilot_paths;
+        self
+    }
+
+    /// Add an instrument, priced by `engine`, with the standard error
+    /// `target_error` it should be priced to.
+    ///
+    /// # Panics
+    /// Panics if `target_error` is not positive.
+    #[must_use]
+    pub fn with_instrument(mut self, engine: BookEngine, target_error: f64) -> Self {
+        assert!(target_error > 0.0, "Target error must be positive.");
+        self.instruments.push((engine, target_error));
+        self
+    }
+
+    /// Price the book with `total_budget` paths in all, pilots included.
+    ///
+    /// Each instrument is priced again with its pilot and allocated paths.
+    /// With pseudo-random sampling the pilot paths are the first paths of
+    /// that run, since both start from the engine's seed.
+    ///
+    /// # Panics
+    /// Panics if the book is empty, or the budget does not cover the pilot
+    /// runs.
+    #[must_use]
+    pub fn price(&self, total_budget: usize) -> Vec<MonteCarloResult> {
+        assert!(!self.instruments.is_empty(), "The book is empty.");
+
+        let pilot_budget = self.pilot_paths * self.instruments.len();
+        assert!(
+            total_budget >= pilot_budget,
+            "The budget must cover the pilot runs."
+        );
+
+        let (target_errors, variances): (Vec<f64>, Vec<f64>) = self
+            .instruments
+            .iter()
+            .map(|(engine, target_error)| {
+                let pilot = engine.run(self.pilot_paths);
+                let variance = pilot.standard_error.powi(2) * pilot.n_paths as f64;
+
+                (*target_error, variance)
+            })
+            .unzip();
+
+        let allocation = allocate_budget(&target_errors, &variances, total_budget - pilot_budget);
+
+        self.instruments
+            .iter()
+            .zip(allocation)
+            .map(|((engine, _), n_paths)| engine.run(self.pilot_paths + n_paths))
+            .collect()
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_book_pricer {
+    use super::*;
+    use crate::instruments::options::black_scholes;
+
+    const S: f64 = 100.0;
+    const R: f64 = 0.05;
+    const T: f64 = 1.0;
+
+    // Discounted call payoff under Black-Scholes.
+    fn call(strike: f64, vol: f64) -> BookEngine {
+        let payoff: Box<dyn Fn(&[f64]) -> f64> = Box::new(move |z: &[f64]| {
+            let terminal = S * ((R - 0.5 * vol * vol) * T + vol * T.sqrt() * z[0]).exp();
+
+            (-R * T).exp() * (terminal - strike).max(0.0)
+        });
+
+        MonteCarloEngine::new(1, payoff)
+    }
+
+    #[test]
+    fn test_book_uses_the_whole_budget() {
+        let book = BookPricer::new()
+            .with_instrument(call(100.0, 0.1), 0.05)
+            .with_instrument(call(100.0, 0.4), 0.05)
+            .with_instrument(call(130.0, 0.2), 0.05);
+
+        let results = book.price(60_000);
+
+        assert_eq!(results.iter().map(|r| r.n_paths).sum::<usize>(), 60_000);
+        assert!(results.iter().all(|r| r.n_paths >= 1_000));
+    }
+
+    #[test]
+    fn test_higher_variance_gets_more_paths() {
+        let book = BookPricer::new()
+            .with_instrument(call(100.0, 0.1), 0.05)
+            .with_instrument(call(100.0, 0.4), 0.05);
+
+        let results = book.price(50_000);
+
+        assert!(results[1].n_paths > 2 * results[0].n_paths);
+
+        // Both prices are within four standard errors of Black-Scholes.
+        for (result, vol) in results.iter().zip([0.1, 0.4]) {
+            let exact = black_scholes::call_price(S, 100.0, R, 0.0, vol, T);
+
+            assert!((result.price - exact).abs() < 4.0 * result.standard_error);
+        }
+    }
+}
@@ -9,7 +9,7 @@
 
 //! Market data container.
 
-use crate::data::{DiscountCurve, FlatCurve, ForwardCurve, SpotCurve};
+use crate::data::{CurveSet, DiscountCurve, FlatCurve, ForwardCurve, SpotCurve};
 use crate::instruments::ExchangeRate;
 use crate::time::Calendar;
 use derive_builder::Builder;
@@ -52,4 +52,8 @@ where
     /// Flat curve.
     #[builder(default)]
     pub flat_curve: Option<FlatCurve<C>>,
+
+    /// Discount (OIS) and forecast curves, keyed by index.
+    #[builder(default)]
+    pub curve_set: Option<CurveSet>,
 }
@@ -25,9 +25,18 @@ pub use priceable::*;
 pub mod monte_carlo_pricer;
 pub use monte_carlo_pricer::*;
 
+pub mod monte_carlo_engine;
+pub use monte_carlo_engine::*;
+
+pub mod book_pricer;
+pub use book_pricer::*;
+
 pub mod analytic_pricer;
 pub use analytic_pricer::*;
 
+pub mod audit;
+pub use audit::*;
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // PRICER STRUCT
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
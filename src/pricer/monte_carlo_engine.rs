@@ -0,0 +1,598 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Monte Carlo engine with convergence diagnostics.
+//!
+//! The engine averages a discounted payoff of a vector of independent
+//! standard normals, optionally with antithetic pairs $(Z, -Z)$ and a
+//! control variate $C$ with known mean $\mu_C$, in which case the estimator
+//! is $\bar{Y} - \hat{\beta} (\bar{C} - \mu_C)$ with the regression
+//! coefficient $\hat{\beta} = \widehat{\mathrm{Cov}}(Y, C) /
+//! \widehat{\mathrm{Var}}(C)$.
+//!
+//! All statistics are accumulated in a single pass, so the engine can keep
+//! adding batches of paths until a target precision is reached.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::math::{latin_hypercube_normals, SamplingMethod, SimulationConfig, StreamingStats};
+use crate::progress::{Outcome, ProgressHandle};
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rand_distr::StandardNormal;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// 97.5% quantile of the standard normal, for 95% confidence intervals.
+const Z_975: f64 = 1.959_963_984_540_054;
+
+/// Result of a Monte Carlo run.
+///
+/// A path is one draw of the normals; with antithetics on, a path is the
+/// average over the pair, so it costs two payoff evaluations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonteCarloResult {
+    /// Price estimate.
+    pub price: f64,
+    /// Standard error of the estimate.
+    pub standard_error: f64,
+    /// 95% confidence interval for the price.
+    pub confidence_interval: (f64, f64),
+    /// Number of paths.
+    pub n_paths: usize,
+    /// Running price estimate `(paths, estimate)` every `trace_interval` paths.
+    pub convergence_trace: Vec<(usize, f64)>,
+    /// Skewness of the discounted payoffs.
+    pub skewness: f64,
+    /// Excess kurtosis of the discounted payoffs.
+    pub kurtosis: f64,
+    /// Variance of a plain estimator over that of this one, for the same
+    /// number of payoff evaluations; `None` without antithetics or controls.
+    pub variance_reduction_factor: Option<f64>,
+    /// Whether the target tolerance was met (always `true` for a fixed
+    /// number of paths).
+    pub converged: bool,
+}
+
+/// Control variate function, with its known mean.
+type ControlVariate = (Box<dyn Fn(&[f64]) -> f64>, f64);
+
+/// Monte Carlo engine for a discounted payoff of `dimension` independent
+/// standard normals.
+pub struct MonteCarloEngine<F>
+where
+    F: Fn(&[f64]) -> f64,
+{
+    payoff: F,
+    dimension: usize,
+    antithetic: bool,
+    sampling: SamplingMethod,
+    control: Option<ControlVariate>,
+    batch_size: usize,
+    trace_interval: usize,
+    seed: u64,
+}
+
+/// Single-pass accumulator for the estimator samples.
+#[derive(Debug, Clone, Default)]
+struct Accumulator {
+    /// Payoff samples (averaged over antithetic pairs).
+    samples: StreamingStats,
+    /// Control samples (averaged over antithetic pairs).
+    controls: StreamingStats,
+    /// Sum of the products of the deviations of samples and controls.
+    co_moment: f64,
+    /// Individual discounted payoff evaluations.
+    evaluations: StreamingStats,
+    trace: Vec<(usize, f64)>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl MonteCarloResult {
+    /// Half-width of the 95% confidence interval.
+    #[must_use]
+    pub fn half_width(&self) -> f64 {
+        0.5 * (self.confidence_interval.1 - self.confidence_interval.0)
+    }
+}
+
+impl Accumulator {
+    fn push(&mut self, sample: f64, control: f64) {
+        // Co-moment update with the pre-update control mean and the
+        // post-update sample mean (Welford).
+        let control_delta = if self.controls.count() == 0 {
+            0.0
+        } else {
+            control - self.controls.mean()
+        };
+
+        self.samples.push(sample);
+        self.controls.push(control);
+        self.co_moment += control_delta * (sample - self.samples.mean());
+    }
+
+    /// Regression coefficient of the samples on the controls.
+    fn beta(&self) -> f64 {
+        let n = self.samples.count();
+        let control_variance = self.controls.variance();
+
+        if n < 2 || control_variance.is_nan() || control_variance <= 0.0 {
+            return 0.0;
+        }
+
+        self.co_moment / (n as f64 - 1.0) / control_variance
+    }
+
+    fn estimate(&self, control_mean: Option<f64>) -> f64 {
+        match control_mean {
+            Some(mu) => self.samples.mean() - self.beta() * (self.controls.mean() - mu),
+            None => self.samples.mean(),
+        }
+    }
+
+    /// Variance of a single estimator sample, net of the control.
+    fn variance(&self, control: bool) -> f64 {
+        let variance = self.samples.variance();
+
+        if control {
+            let n = self.samples.count() as f64;
+            (variance - self.beta() * self.co_moment / (n - 1.0)).max(0.0)
+        } else {
+            variance
+        }
+    }
+}
+
+impl<F> MonteCarloEngine<F>
+where
+    F: Fn(&[f64]) -> f64,
+{
+    /// New engine for `payoff`, a discounted payoff of `dimension` standard
+    /// normals, with batches of 10,000 paths, a trace point every 1,000
+    /// paths, and seed 42.
+    ///
+    /// # Panics
+    /// Panics if `dimension` is zero.
+    #[must_use]
+    pub fn new(dimension: usize, payoff: F) -> Self {
+        assert!(dimension > 0, "Dimension must be positive.");
+
+        Self {
+            payoff,
+            dimension,
+            antithetic: false,
+            sampling: SamplingMethod::PseudoRandom,
+            control: None,
+            batch_size: 10_000,
+            trace_interval: 1_000,
+            seed: 42,
+        }
+    }
+
+    /// Use antithetic pairs $(Z, -Z)$.
+    #[must_use]
+    pub fn with_antithetic(mut self, antithetic: bool) -> Self {
+        self.antithetic = antithetic;
+        self
+    }
+
+    /// Use `control`, a function of the same normals with known `mean`, as a
+    /// control variate.
+    #[must_use]
+    pub fn with_control_variate(
+        mut self,
+        control: impl Fn(&[f64]) -> f64 + 'static,
+        mean: f64,
+    ) -> Self {
+        self.control = Some((Box::new(control), mean));
+        self
+    }
+
+    /// Number of paths added at a time by [`MonteCarloEngine::run_until`].
+    ///
+    /// # Panics
+    /// Panics if `batch_size` is zero.
+    #[must_use]
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "Batch size must be positive.");
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Number of paths between points of the convergence trace.
+    ///
+    /// # Panics
+    /// Panics if `trace_interval` is zero.
+    #[must_use]
+    pub fn with_trace_interval(mut self, trace_interval: usize) -> Self {
+        assert!(trace_interval > 0, "Trace interval must be positive.");
+        self.trace_interval = trace_interval;
+        self
+    }
+
+    /// Seed of the random number generator.
+    #[must_use]
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Take the seed, sampling method, and antithetic pairing from
+    /// `config`. The seed is the config's child seed for
+    /// `"pricer.monte_carlo_engine"`; pass `config.n_paths` to
+    /// [`MonteCarloEngine::run`].
+    ///
+    /// With Latin hypercube sampling, each batch of paths is stratified on
+    /// its own.
+    #[must_use]
+    pub fn with_config(mut self, config: &SimulationConfig) -> Self {
+        self.seed = config.child_seed("pricer.monte_carlo_engine");
+        self.sampling = config.sampling;
+        self.antithetic = config.antithetic;
+        self
+    }
+
+    /// Price with a fixed number of paths.
+    #[must_use]
+    pub fn run(&self, n_paths: usize) -> MonteCarloResult {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut accumulator = Accumulator::default();
+
+        self.simulate(&mut rng, &mut accumulator, n_paths);
+
+        self.result(&accumulator, true)
+    }
+
+    /// Price with a fixed number of paths, in batches, reporting the number
+    /// of paths done and the price so far to `progress` after each batch.
+    ///
+    /// If the cancellation token is set, returns the result of the batches
+    /// done so far as [`Outcome::Cancelled`] (flagged as not converged).
+    /// With pseudo-random sampling, a completed run gives the same result
+    /// as [`MonteCarloEngine::run`]; with Latin hypercube sampling, each
+    /// batch is stratified on its own.
+    pub fn run_with_progress(
+        &self,
+        n_paths: usize,
+        progress: &mut ProgressHandle,
+    ) -> Outcome<MonteCarloResult> {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut accumulator = Accumulator::default();
+        let mut done = 0;
+
+        while done < n_paths {
+            if progress.is_cancelled() {
+                return Outcome::Cancelled(self.result(&accumulator, false));
+            }
+
+            let batch = self.batch_size.min(n_paths - done);
+            self.simulate(&mut rng, &mut accumulator, batch);
+            done += batch;
+
+            let control_mean = self.control.as_ref().map(|(_, mean)| *mean);
+            progress.report(done, n_paths, Some(accumulator.estimate(control_mean)));
+        }
+
+        Outcome::Completed(self.result(&accumulator, true))
+    }
+
+    /// Price adaptively: add batches of paths until the half-width of the
+    /// 95% confidence interval is below `tolerance`, or `max_paths` paths
+    /// have been used, in which case the result is flagged as not
+    /// converged.
+    #[must_use]
+    pub fn run_until(&self, tolerance: f64, max_paths: usize) -> MonteCarloResult {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let mut accumulator = Accumulator::default();
+
+        loop {
+            let n = accumulator.samples.count() as usize;
+            self.simulate(
+                &mut rng,
+                &mut accumulator,
+                self.batch_size.min(max_paths - n),
+            );
+
+            let result = self.result(&accumulator, false);
+            if result.half_width() < tolerance {
+                return MonteCarloResult {
+                    converged: true,
+                    ..result
+                };
+            }
+            if result.n_paths >= max_paths {
+                return result;
+            }
+        }
+    }
+
+    fn simulate(&self, rng: &mut StdRng, accumulator: &mut Accumulator, n_paths: usize) {
+        let mut z = vec![0.0; self.dimension];
+        let control_mean = self.control.as_ref().map(|(_, mean)| *mean);
+
+        let stratified = match self.sampling {
+            SamplingMethod::PseudoRandom => Vec::new(),
+            SamplingMethod::LatinHypercube => latin_hypercube_normals(rng, n_paths, self.dimension),
+        };
+
+        for i in 0..n_paths {
+            match stratified.get(i) {
+                Some(draw) => z.copy_from_slice(draw),
+                None => z.iter_mut().for_each(|z| *z = rng.sample(StandardNormal)),
+            }
+
+            let mut payoff = (self.payoff)(&z);
+            let mut control = self.control.as_ref().map_or(0.0, |(c, _)| c(&z));
+            accumulator.evaluations.push(payoff);
+
+            if self.antithetic {
+                z.iter_mut().for_each(|z| *z = -*z);
+
+                let reflected = (self.payoff)(&z);
+                accumulator.evaluations.push(reflected);
+
+                payoff = 0.5 * (payoff + reflected);
+                control = 0.5 * (control + self.control.as_ref().map_or(0.0, |(c, _)| c(&z)));
+            }
+
+            accumulator.push(payoff, control);
+
+            let n = accumulator.samples.count() as usize;
+            if n.is_multiple_of(self.trace_interval) {
+                accumulator
+                    .trace
+                    .push((n, accumulator.estimate(control_mean)));
+            }
+        }
+    }
+
+    fn result(&self, accumulator: &Accumulator, converged: bool) -> MonteCarloResult {
+        let control_mean = self.control.as_ref().map(|(_, mean)| *mean);
+        let n = accumulator.samples.count() as usize;
+
+        let price = accumulator.estimate(control_mean);
+        let variance = accumulator.variance(control_mean.is_some());
+        let standard_error = (variance / n as f64).sqrt();
+        let half_width = Z_975 * standard_error;
+
+        let variance_reduction_factor = (self.antithetic || self.control.is_some()).then(|| {
+            let evaluations_per_path = if self.antithetic { 2.0 } else { 1.0 };
+            accumulator.evaluations.variance() / (evaluations_per_path * variance)
+        });
+
+        MonteCarloResult {
+            price,
+            standard_error,
+            confidence_interval: (price - half_width, price + half_width),
+            n_paths: n,
+            convergence_trace: accumulator.trace.clone(),
+            skewness: accumulator.evaluations.skewness(),
+            kurtosis: accumulator.evaluations.kurtosis(),
+            variance_reduction_factor,
+            converged,
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_monte_carlo_engine {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::instruments::options::black_scholes;
+
+    const S: f64 = 100.0;
+    const K: f64 = 100.0;
+    const R: f64 = 0.05;
+    const V: f64 = 0.2;
+    const T: f64 = 1.0;
+
+    fn terminal_price(z: &[f64]) -> f64 {
+        S * ((R - 0.5 * V * V) * T + V * T.sqrt() * z[0]).exp()
+    }
+
+    fn call(z: &[f64]) -> f64 {
+        (-R * T).exp() * (terminal_price(z) - K).max(0.0)
+    }
+
+    fn black_scholes_call() -> f64 {
+        black_scholes::call_price(S, K, R, 0.0, V, T)
+    }
+
+    #[test]
+    fn test_progress_callback_fires_per_batch() {
+        let calls = std::cell::RefCell::new(Vec::new());
+        let engine = MonteCarloEngine::new(1, call).with_batch_size(10_000);
+
+        let mut progress = ProgressHandle::new().with_callback(10_000, |done, total, price| {
+            calls.borrow_mut().push((done, total, price.unwrap()));
+        });
+        let outcome = engine.run_with_progress(95_000, &mut progress);
+        drop(progress);
+
+        let calls = calls.into_inner();
+        assert_eq!(calls.len(), 10);
+        assert_eq!(calls[0].0, 10_000);
+        assert_eq!(calls[9], (95_000, 95_000, outcome.value().price));
+        assert!(!outcome.is_cancelled());
+        assert_eq!(outcome.into_inner(), engine.run(95_000));
+    }
+
+    #[test]
+    fn test_cancellation_returns_partial_result() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        let token = Arc::clone(&cancel);
+        let engine = MonteCarloEngine::new(1, call).with_batch_size(10_000);
+
+        // Cancel after 3 batches.
+        let mut progress = ProgressHandle::new()
+            .with_callback(10_000, move |done, _, _| {
+                if done >= 30_000 {
+                    token.store(true, Ordering::Relaxed);
+                }
+            })
+            .with_cancellation(cancel);
+
+        let outcome = engine.run_with_progress(1_000_000, &mut progress);
+        assert!(outcome.is_cancelled());
+
+        let partial = outcome.into_inner();
+        let full = engine.run(1_000_000);
+        assert_eq!(partial.n_paths, 30_000);
+        assert!(!partial.converged);
+        assert_eq!(
+            partial,
+            MonteCarloResult {
+                converged: false,
+                ..engine.run(30_000)
+            }
+        );
+        assert_approx_equal!(
+            partial.standard_error / full.standard_error,
+            (1_000_000.0_f64 / 30_000.0).sqrt(),
+            0.3
+        );
+    }
+
+    #[test]
+    fn test_plain_estimator() {
+        let result = MonteCarloEngine::new(1, call).run(100_000);
+
+        assert_eq!(result.n_paths, 100_000);
+        assert!((result.price - black_scholes_call()).abs() < 4.0 * result.standard_error);
+        assert!(result.confidence_interval.0 < result.price);
+        assert_approx_equal!(result.half_width(), Z_975 * result.standard_error, 1e-12);
+        assert!(result.variance_reduction_factor.is_none());
+        assert!(result.converged);
+
+        // Call payoffs are right-skewed and fat-tailed.
+        assert!(result.skewness > 1.0);
+        assert!(result.kurtosis > 1.0);
+
+        // Normal payoffs have no skew or excess kurtosis.
+        let normal = MonteCarloEngine::new(1, |z: &[f64]| z[0]).run(100_000);
+        assert!(normal.skewness.abs() < 0.05);
+        assert!(normal.kurtosis.abs() < 0.1);
+        assert_approx_equal!(normal.standard_error, 1.0 / 100_000_f64.sqrt(), 1e-4);
+    }
+
+    #[test]
+    fn test_variance_reduction() {
+        let exact = black_scholes_call();
+
+        let antithetic = MonteCarloEngine::new(1, call)
+            .with_antithetic(true)
+            .run(50_000);
+        assert!((antithetic.price - exact).abs() < 4.0 * antithetic.standard_error);
+        assert!(antithetic.variance_reduction_factor.unwrap() > 1.0);
+
+        // The discounted terminal price is a martingale, with mean S.
+        let control = MonteCarloEngine::new(1, call)
+            .with_control_variate(|z| (-R * T).exp() * terminal_price(z), S)
+            .run(50_000);
+        assert!((control.price - exact).abs() < 4.0 * control.standard_error);
+        assert!(control.variance_reduction_factor.unwrap() > 2.0);
+
+        let plain = MonteCarloEngine::new(1, call).run(50_000);
+        assert!(control.standard_error < plain.standard_error);
+
+        // A linear payoff is its own perfect control, and is exactly
+        // cancelled by antithetics.
+        let linear = MonteCarloEngine::new(1, |z: &[f64]| 2.0 * z[0])
+            .with_control_variate(|z| z[0], 0.0)
+            .run(1000);
+        assert_approx_equal!(linear.price, 0.0, 1e-12);
+        assert_approx_equal!(linear.standard_error, 0.0, 1e-6);
+    }
+
+    #[test]
+    fn test_run_until() {
+        let engine = MonteCarloEngine::new(1, call).with_batch_size(5_000);
+
+        let easy = engine.run_until(0.2, 200_000);
+        assert!(easy.converged);
+        assert!(easy.half_width() < 0.2);
+        assert!(easy.n_paths <= 200_000);
+        assert_eq!(easy.n_paths % 5_000, 0);
+
+        let impossible = engine.run_until(1e-6, 20_000);
+        assert!(!impossible.converged);
+        assert_eq!(impossible.n_paths, 20_000);
+
+        // The adaptive run reproduces a fixed run with the same paths.
+        let fixed = engine.run(easy.n_paths);
+        assert_approx_equal!(fixed.price, easy.price, 1e-12);
+    }
+
+    #[test]
+    fn test_convergence_trace() {
+        let engine = MonteCarloEngine::new(1, call).with_trace_interval(500);
+        let result = engine.run(64_000);
+
+        assert_eq!(result.convergence_trace.len(), 128);
+        assert_eq!(result.convergence_trace[0].0, 500);
+        let (n, last) = result.convergence_trace[127];
+        assert_eq!(n, 64_000);
+        assert_approx_equal!(last, result.price, 1e-12);
+
+        // Confidence intervals shrink like one over the square root of the
+        // number of paths.
+        let widths: Vec<f64> = [1_000, 4_000, 16_000, 64_000]
+            .iter()
+            .map(|n| engine.run(*n).half_width())
+            .collect();
+        for w in widths.windows(2) {
+            assert!(w[1] < w[0]);
+            assert!((w[0] / w[1] - 2.0).abs() < 0.3);
+        }
+    }
+
+    #[test]
+    fn test_reproducible_with_simulation_config() {
+        let price = |config: &SimulationConfig| {
+            MonteCarloEngine::new(1, call)
+                .with_config(config)
+                .run(config.n_paths)
+        };
+
+        for sampling in [SamplingMethod::PseudoRandom, SamplingMethod::LatinHypercube] {
+            let config = SimulationConfig::new(2024)
+                .with_paths(20_000)
+                .with_sampling(sampling)
+                .with_antithetic(true);
+
+            let first = price(&config);
+            let second = price(&config);
+            assert_eq!(first.price.to_bits(), second.price.to_bits());
+            assert_eq!(
+                first.standard_error.to_bits(),
+                second.standard_error.to_bits()
+            );
+
+            let reseeded = price(&SimulationConfig {
+                seed: 2025,
+                ..config
+            });
+            assert_ne!(first.price.to_bits(), reseeded.price.to_bits());
+            assert!((first.price - black_scholes_call()).abs() < 4.0 * first.standard_error);
+        }
+    }
+}
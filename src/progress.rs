@@ -0,0 +1,225 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Progress reporting and cancellation for long-running computations.
+//!
+//! Entry points such as
+//! [`MonteCarloEngine::run_with_progress`](crate::pricer::MonteCarloEngine::run_with_progress),
+//! [`GradientDescent::optimize_with_progress`](crate::math::optimization::gradient_descent::GradientDescent::optimize_with_progress),
+//! [`bootstrap_scenarios_with_progress`](crate::math::bootstrap_scenarios_with_progress), and
+//! [`Backtest::run_with_progress`](crate::trading::backtest::Backtest::run_with_progress)
+//! take a [`ProgressHandle`]. It calls back with
+//! `(completed, total, current_estimate)` every `every` units of work
+//! (paths, iterations, scenarios, or bars), and holds an optional
+//! cancellation token. Once the token is set, the computation stops at the
+//! next check and returns [`Outcome::Cancelled`] with its partial result.
+//!
+//! ```
+//! use std::sync::atomic::{AtomicBool, Ordering};
+//! use std::sync::Arc;
+//! use RustQuant::pricer::MonteCarloEngine;
+//! use RustQuant::progress::ProgressHandle;
+//!
+//! let cancel = Arc::new(AtomicBool::new(false));
+//! let token = Arc::clone(&cancel);
+//!
+//! // Stop once 20,000 paths have been simulated.
+//! let mut progress = ProgressHandle::new()
+//!     .with_callback(10_000, move |completed, _total, _price| {
+//!         if completed >= 20_000 {
+//!             token.store(true, Ordering::Relaxed);
+//!         }
+//!     })
+//!     .with_cancellation(cancel);
+//!
+//! let outcome = MonteCarloEngine::new(1, |z| z[0].max(0.0))
+//!     .run_with_progress(100_000, &mut progress);
+//!
+//! assert!(outcome.is_cancelled());
+//! assert_eq!(outcome.value().n_paths, 20_000);
+//! ```
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Callback receiving `(completed, total, current_estimate)`.
+pub type ProgressCallback<'a> = Box<dyn FnMut(usize, usize, Option<f64>) + 'a>;
+
+/// Progress callback and cancellation token of a computation.
+///
+/// The default handle reports nothing and is never cancelled.
+#[derive(Default)]
+pub struct ProgressHandle<'a> {
+    /// Callback, with the number of units of work between calls.
+    callback: Option<(ProgressCallback<'a>, usize)>,
+    /// Cancellation token.
+    cancel: Option<Arc<AtomicBool>>,
+    /// Units of work completed at the last call of the callback.
+    last_reported: usize,
+}
+
+/// Result of a computation that can be cancelled.
+#[derive(Debug, Clone, PartialEq)]
+#[must_use]
+pub enum Outcome<T> {
+    /// The computation ran to the end.
+    Completed(T),
+    /// The computation was cancelled; the result covers the work done.
+    Cancelled(T),
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl<'a> ProgressHandle<'a> {
+    /// Handle without a callback or cancellation token.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Call `callback` with `(completed, total, current_estimate)` each time
+    /// at least `every` more units of work are done, and at the end.
+    ///
+    /// # Panics
+    /// Panics if `every` is zero.
+    #[must_use]
+    pub fn with_callback(
+        mut self,
+        every: usize,
+        callback: impl FnMut(usize, usize, Option<f64>) + 'a,
+    ) -> Self {
+        assert!(every > 0, "Must report at least every unit of work.");
+        self.callback = Some((Box::new(callback), every));
+        self
+    }
+
+    /// Stop the computation once `token` is set.
+    #[must_use]
+    pub fn with_cancellation(mut self, token: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(token);
+        self
+    }
+
+    /// Reports that `completed` of `total` units of work are done, calling
+    /// the callback if it is due.
+    pub fn report(&mut self, completed: usize, total: usize, estimate: Option<f64>) {
+        if let Some((callback, every)) = self.callback.as_mut() {
+            if completed >= self.last_reported + *every
+                || (completed == total && completed > self.last_reported)
+            {
+                callback(completed, total, estimate);
+                self.last_reported = completed;
+            }
+        }
+    }
+
+    /// Whether the cancellation token is set.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .is_some_and(|token| token.load(Ordering::Relaxed))
+    }
+}
+
+impl<T> Outcome<T> {
+    /// Wraps `value` as cancelled if `cancelled`, else as completed.
+    pub fn new(value: T, cancelled: bool) -> Self {
+        if cancelled {
+            Self::Cancelled(value)
+        } else {
+            Self::Completed(value)
+        }
+    }
+
+    /// Whether the computation was cancelled.
+    #[must_use]
+    pub fn is_cancelled(&self) -> bool {
+        matches!(self, Self::Cancelled(_))
+    }
+
+    /// The (possibly partial) result.
+    #[must_use]
+    pub fn value(&self) -> &T {
+        match self {
+            Self::Completed(value) | Self::Cancelled(value) => value,
+        }
+    }
+
+    /// The (possibly partial) result, by value.
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Completed(value) | Self::Cancelled(value) => value,
+        }
+    }
+
+    /// Applies `f` to the result, keeping the status.
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> Outcome<U> {
+        match self {
+            Self::Completed(value) => Outcome::Completed(f(value)),
+            Self::Cancelled(value) => Outcome::Cancelled(f(value)),
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_progress {
+    use super::*;
+    use std::cell::RefCell;
+
+    #[test]
+    fn test_report_granularity() {
+        let calls = RefCell::new(Vec::new());
+        let mut progress = ProgressHandle::new()
+            .with_callback(10, |done, total, _| calls.borrow_mut().push((done, total)));
+
+        for completed in 1..=25 {
+            progress.report(completed, 25, None);
+        }
+        drop(progress);
+
+        assert_eq!(calls.into_inner(), vec![(10, 25), (20, 25), (25, 25)]);
+    }
+
+    #[test]
+    fn test_cancellation_token() {
+        let token = Arc::new(AtomicBool::new(false));
+        let progress = ProgressHandle::new().with_cancellation(Arc::clone(&token));
+
+        assert!(!progress.is_cancelled());
+        token.store(true, Ordering::Relaxed);
+        assert!(progress.is_cancelled());
+        assert!(!ProgressHandle::new().is_cancelled());
+    }
+
+    #[test]
+    fn test_outcome() {
+        let outcome = Outcome::new(3, true);
+
+        assert!(outcome.is_cancelled());
+        assert_eq!(*outcome.value(), 3);
+        assert_eq!(outcome.map(|x| x * 2), Outcome::Cancelled(6));
+        assert_eq!(Outcome::new("done", false).into_inner(), "done");
+    }
+}
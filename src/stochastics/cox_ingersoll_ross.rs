@@ -7,8 +7,11 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::models::cox_ingersoll_ross::CoxIngersollRoss;
-use crate::stochastics::process::StochasticProcess;
+use crate::math::SimulationConfig;
+use crate::models::cox_ingersoll_ross::{CoxIngersollRoss, FellerViolationWarning};
+use crate::models::model_parameter::ModelParameter;
+use crate::stochastics::process::{StochasticProcess, Trajectories};
+use rayon::prelude::*;
 
 impl StochasticProcess for CoxIngersollRoss {
     fn drift(&self, x: f64, t: f64) -> f64 {
@@ -29,6 +32,85 @@ impl StochasticProcess for CoxIngersollRoss {
     }
 }
 
+impl CoxIngersollRoss {
+    /// Non-negative Euler-Maruyama simulation, driven by a
+    /// [`SimulationConfig`].
+    ///
+    /// Steps that would take the process below zero are truncated at zero.
+    /// If the Feller condition is violated (see
+    /// [`CoxIngersollRoss::feller_violation`]), zero is made absorbing and
+    /// the violation is returned alongside the paths.
+    pub fn simulate_non_negative(
+        &self,
+        x_0: f64,
+        t_0: f64,
+        t_n: f64,
+        config: &SimulationConfig,
+    ) -> (Trajectories, Option<FellerViolationWarning>) {
+        let violation = self.feller_violation();
+        let paths = simulate_square_root(
+            [&self.theta, &self.mu, &self.sigma],
+            x_0,
+            t_0,
+            t_n,
+            config,
+            violation.is_some(),
+        );
+
+        (paths, violation)
+    }
+}
+
+/// Euler-Maruyama simulation of the square-root process
+/// $dX_t = \kappa (\theta - X_t) dt + \sigma \sqrt{X_t} dW_t$, with the
+/// parameters given as `[kappa, theta, sigma]`.
+///
+/// Steps below zero are truncated at zero, and if `absorbing` a path that
+/// reaches zero stays there.
+pub(crate) fn simulate_square_root(
+    [kappa, theta, sigma]: [&ModelParameter; 3],
+    x_0: f64,
+    t_0: f64,
+    t_n: f64,
+    config: &SimulationConfig,
+    absorbing: bool,
+) -> Trajectories {
+    assert!(t_0 < t_n);
+
+    let n_steps = config.n_steps;
+    let dt: f64 = (t_n - t_0) / (n_steps as f64);
+    let scale = dt.sqrt();
+    let times: Vec<f64> = (0..=n_steps).map(|t| t_0 + dt * (t as f64)).collect();
+
+    let normals = config.standard_normals("stochastics.square_root", n_steps);
+
+    let paths = config.install(|| {
+        normals
+            .par_iter()
+            .map(|z| {
+                let mut path = vec![x_0.max(0.0); n_steps + 1];
+
+                for t in 0..n_steps {
+                    let (x, s) = (path[t], times[t]);
+
+                    path[t + 1] = if absorbing && x == 0.0 {
+                        0.0
+                    } else {
+                        let step = kappa.0(s) * (theta.0(s) - x) * dt
+                            + sigma.0(s) * x.sqrt() * z[t] * scale;
+
+                        (x + step).max(0.0)
+                    };
+                }
+
+                path
+            })
+            .collect()
+    });
+
+    Trajectories { times, paths }
+}
+
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 // TESTS
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
@@ -75,4 +157,65 @@ mod tests_cir {
         // let file2 = "./images/CIR2.png";
         // plot_vector((&output.trajectories[1]).clone(), file2)
     }
+
+    #[test]
+    fn test_feller_condition() {
+        let mut cir = CoxIngersollRoss::new(0.04, 0.5, 1.0);
+
+        // 2 * 1.0 * 0.04 = 0.08 < 0.25.
+        assert!(!cir.feller_satisfied());
+        assert_eq!(
+            cir.feller_violation(),
+            Some(FellerViolationWarning {
+                two_kappa_theta: 0.08,
+                sigma_squared: 0.25
+            })
+        );
+
+        cir.enforce_feller();
+        assert!(cir.feller_satisfied());
+        assert_approx_equal!(cir.sigma.0(0.0), 0.08_f64.sqrt(), 1e-15);
+
+        // Already satisfied: unchanged.
+        let mut cir = CoxIngersollRoss::new(0.04, 0.1, 1.0);
+        cir.enforce_feller();
+        assert_eq!(cir.sigma.0(0.0), 0.1);
+    }
+
+    #[test]
+    fn test_simulation_stays_non_negative() {
+        let config = SimulationConfig::new(42).with_paths(500).with_steps(250);
+
+        // Plain Euler-Maruyama goes below zero (or NaN) when the Feller
+        // condition is violated.
+        let violating = CoxIngersollRoss::new(0.04, 0.5, 1.0);
+        let euler = violating.simulate(0.04, 0.0, 1.0, &config);
+        assert!(euler.paths.iter().flatten().any(|x| x.is_nan() || *x < 0.0));
+
+        // The non-negative scheme warns and absorbs at zero.
+        let (paths, warning) = violating.simulate_non_negative(0.04, 0.0, 1.0, &config);
+        assert!(warning.is_some());
+        assert!(paths.paths.iter().flatten().all(|x| *x >= 0.0));
+        let absorbed = paths
+            .paths
+            .iter()
+            .filter(|path| path.contains(&0.0))
+            .collect::<Vec<_>>();
+        assert!(!absorbed.is_empty());
+        for path in absorbed {
+            let first_zero = path.iter().position(|x| *x == 0.0).unwrap();
+            assert!(path[first_zero..].iter().all(|x| *x == 0.0));
+        }
+
+        // After enforcement there is no warning, and no negative variance.
+        let mut enforced = violating;
+        enforced.enforce_feller();
+        let (paths, warning) = enforced.simulate_non_negative(0.04, 0.0, 1.0, &config);
+        assert!(warning.is_none());
+        assert!(paths
+            .paths
+            .iter()
+            .flatten()
+            .all(|x| x.is_finite() && *x >= 0.0));
+    }
 }
@@ -7,7 +7,11 @@
 //      - LICENSE-MIT.md
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
-use crate::{models::Heston, stochastics::StochasticProcess};
+use crate::math::SimulationConfig;
+use crate::models::{FellerViolationWarning, Heston};
+use crate::stochastics::{
+    cox_ingersoll_ross::simulate_square_root, StochasticProcess, Trajectories,
+};
 
 impl StochasticProcess for Heston {
     fn drift(&self, _x: f64, _t: f64) -> f64 {
@@ -32,3 +36,63 @@ impl StochasticProcess for Heston {
         ]
     }
 }
+
+impl Heston {
+    /// Non-negative Euler-Maruyama simulation of the variance process,
+    /// starting from the initial variance at `t_0`, driven by a
+    /// [`SimulationConfig`].
+    ///
+    /// Steps that would take the variance below zero are truncated at zero.
+    /// If the Feller condition is violated (see
+    /// [`Heston::feller_violation`]), zero variance is made absorbing and the
+    /// violation is returned alongside the paths.
+    pub fn simulate_variance(
+        &self,
+        t_0: f64,
+        t_n: f64,
+        config: &SimulationConfig,
+    ) -> (Trajectories, Option<FellerViolationWarning>) {
+        let violation = self.feller_violation();
+        let paths = simulate_square_root(
+            [
+                &self.mean_reversion_rate,
+                &self.long_run_variance,
+                &self.volatility_of_volatility,
+            ],
+            self.initial_variance.0(t_0),
+            t_0,
+            t_n,
+            config,
+            violation.is_some(),
+        );
+
+        (paths, violation)
+    }
+}
+
+#[cfg(test)]
+mod tests_heston {
+    use super::*;
+
+    #[test]
+    fn test_feller_enforcement_keeps_variance_non_negative() {
+        let config = SimulationConfig::new(7).with_paths(500).with_steps(250);
+        let mut heston = Heston::new(0.04, 0.04, 1.5, -0.7, 0.6);
+
+        assert!(!heston.feller_satisfied());
+        let (paths, warning) = heston.simulate_variance(0.0, 1.0, &config);
+        assert!(warning.is_some());
+        assert!(paths.paths.iter().flatten().all(|v| *v >= 0.0));
+
+        heston.enforce_feller();
+        assert!(heston.feller_satisfied());
+
+        let (paths, warning) = heston.simulate_variance(0.0, 1.0, &config);
+        assert!(warning.is_none());
+        assert!(paths
+            .paths
+            .iter()
+            .flatten()
+            .all(|v| v.is_finite() && *v >= 0.0));
+    }
+}
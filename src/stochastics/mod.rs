@@ -14,6 +14,8 @@
 //!   - Geometric Brownian Motion
 //!     - $dX(t) = \mu X(t) dt + \sigma X(t) dW(t)$
 //!   - Fractional Brownian Motion
+//! - Random walk with drift
+//!   - $X_{t+1} = X_t + \mu + \sigma \varepsilon_{t+1}$
 //! - Cox-Ingersoll-Ross (1985)
 //!   - $dX(t) = \left[ \theta - \alpha X(t) \right] dt + \sigma \sqrt{r_t} dW(t)$
 //! - Ornstein-Uhlenbeck process
@@ -89,6 +91,10 @@ pub mod merton_jump_diffusion;
 /// Ornstein-Uhlenbeck process.
 pub mod ornstein_uhlenbeck;
 
+/// Gaussian random walk with drift.
+pub mod random_walk;
+pub use random_walk::*;
+
 /// SABR model process.
 pub mod sabr;
 
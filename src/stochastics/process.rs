@@ -14,6 +14,7 @@
 //! Autonomous refers to processes where the drift and diffusion
 //! do not explicitly depend on the time `t`.
 
+use crate::math::SimulationConfig;
 use rand::prelude::Distribution;
 use rand::{rngs::StdRng, SeedableRng};
 use rayon::prelude::*;
@@ -277,10 +278,53 @@ pub trait StochasticProcess: Sync {
 
         Trajectories { times, paths }
     }
+
+    /// Euler-Maruyama discretisation scheme driven by a [`SimulationConfig`].
+    ///
+    /// Simulates `config.n_paths` paths of `config.n_steps` steps, with the
+    /// normals of the config's child stream `"stochastics.euler_maruyama"`,
+    /// so the paths are reproducible and do not depend on the number of
+    /// threads.
+    ///
+    /// # Arguments:
+    /// * `x_0` - The process' initial value at `t_0`.
+    /// * `t_0` - The initial time point.
+    /// * `t_n` - The terminal time point.
+    /// * `config` - The simulation settings.
+    fn simulate(&self, x_0: f64, t_0: f64, t_n: f64, config: &SimulationConfig) -> Trajectories {
+        assert!(t_0 < t_n);
+
+        let n_steps = config.n_steps;
+        let dt: f64 = (t_n - t_0) / (n_steps as f64);
+        let scale = dt.sqrt();
+        let times: Vec<f64> = (0..=n_steps).map(|t| t_0 + dt * (t as f64)).collect();
+
+        let normals = config.standard_normals("stochastics.euler_maruyama", n_steps);
+
+        let paths = config.install(|| {
+            normals
+                .par_iter()
+                .map(|z| {
+                    let mut path = vec![x_0; n_steps + 1];
+
+                    for t in 0..n_steps {
+                        path[t + 1] = path[t]
+                            + self.drift(path[t], times[t]) * dt
+                            + self.diffusion(path[t], times[t]) * z[t] * scale;
+                    }
+
+                    path
+                })
+                .collect()
+        });
+
+        Trajectories { times, paths }
+    }
 }
 
 #[cfg(test)]
 mod test_process {
+    use crate::math::SimulationConfig;
     use crate::models::geometric_brownian_motion::GeometricBrownianMotion;
     use crate::stochastics::process::StochasticProcess;
     use crate::stochastics::StochasticProcessConfig;
@@ -333,4 +377,24 @@ mod test_process {
         // To see the output of this "test", run:
         // cargo test test_process -- --nocapture
     }
+
+    #[test]
+    fn test_simulate_with_config() {
+        let gbm = GeometricBrownianMotion::new(0.05, 0.9);
+        let config = SimulationConfig::new(123456789)
+            .with_paths(1000)
+            .with_steps(125);
+
+        let serial = gbm.simulate(10.0, 0.0, 1.0, &config.with_threads(1));
+        let parallel = gbm.simulate(10.0, 0.0, 1.0, &config.with_threads(4));
+
+        // Bit-identical for any number of threads.
+        assert_eq!(serial.paths, parallel.paths);
+        assert_eq!(serial.paths.len(), 1000);
+        assert_eq!(serial.paths[0].len(), 126);
+        assert_ne!(serial.paths[0], serial.paths[1]);
+
+        let reseeded = gbm.simulate(10.0, 0.0, 1.0, &SimulationConfig { seed: 1, ..config });
+        assert_ne!(serial.paths, reseeded.paths);
+    }
 }
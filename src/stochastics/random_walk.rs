@@ -0,0 +1,126 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Gaussian random walk with drift, $X_{t+1} = X_t + \mu + \sigma \varepsilon_{t+1}$,
+//! with i.i.d. standard normal $\varepsilon_t$.
+//!
+//! It is the null hypothesis of the tests in [`crate::math::mean_reversion`]:
+//! its increments have Hurst exponent $H = 0.5$, and variance ratio 1 at
+//! every horizon. The log of a geometric Brownian motion sampled at a
+//! fixed interval $\Delta t$ is a random walk with drift
+//! $(\mu - \sigma^2 / 2) \Delta t$ and volatility $\sigma \sqrt{\Delta t}$.
+
+use rand::Rng;
+use rand_distr::StandardNormal;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Gaussian random walk with drift.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RandomWalk {
+    /// Drift per step, $\mu$.
+    pub mu: f64,
+    /// Standard deviation of each step, $\sigma$.
+    pub sigma: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl RandomWalk {
+    /// New random walk with drift `mu` and step standard deviation `sigma`.
+    ///
+    /// # Panics
+    /// Panics if `sigma` is negative.
+    #[must_use]
+    pub fn new(mu: f64, sigma: f64) -> Self {
+        assert!(sigma >= 0.0, "Sigma must be non-negative.");
+
+        Self { mu, sigma }
+    }
+
+    /// Path of `n` steps starting at `x0`, so `n + 1` values including `x0`.
+    pub fn simulate(&self, x0: f64, n: usize, rng: &mut impl Rng) -> Vec<f64> {
+        let mut path = Vec::with_capacity(n + 1);
+        path.push(x0);
+
+        let mut x = x0;
+        for _ in 0..n {
+            x += self.mu + self.sigma * rng.sample::<f64, _>(StandardNormal);
+            path.push(x);
+        }
+
+        path
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_random_walk {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::math::{hurst_aggregated_variance, hurst_rescaled_range, variance_ratio_test};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn increments(path: &[f64]) -> Vec<f64> {
+        path.windows(2).map(|w| w[1] - w[0]).collect()
+    }
+
+    #[test]
+    fn test_moments_of_increments() {
+        let walk = RandomWalk::new(0.1, 2.0);
+        let path = walk.simulate(5.0, 100_000, &mut StdRng::seed_from_u64(1));
+        assert_eq!(path.len(), 100_001);
+        assert_eq!(path[0], 5.0);
+
+        let steps = increments(&path);
+        let n = steps.len() as f64;
+        let mean = steps.iter().sum::<f64>() / n;
+        let variance = steps.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+
+        assert_approx_equal!(mean, 0.1, 0.02);
+        assert_approx_equal!(variance, 4.0, 0.05);
+    }
+
+    #[test]
+    fn test_hurst_exponent_of_random_walk() {
+        let walk = RandomWalk::new(0.01, 1.0);
+        let steps = increments(&walk.simulate(0.0, 16_384, &mut StdRng::seed_from_u64(2)));
+
+        assert_approx_equal!(hurst_rescaled_range(&steps).unwrap().hurst, 0.5, 0.05);
+        assert_approx_equal!(hurst_aggregated_variance(&steps).unwrap().hurst, 0.5, 0.05);
+    }
+
+    #[test]
+    fn test_variance_ratio_does_not_reject_gbm() {
+        // Daily GBM prices with 5% drift and 20% volatility: the log prices
+        // are a random walk with drift.
+        let (mu, sigma, dt) = (0.05, 0.2, 1.0 / 252.0);
+        let walk = RandomWalk::new((mu - 0.5 * sigma * sigma) * dt, sigma * dt.sqrt());
+        let prices: Vec<f64> = walk
+            .simulate(100_f64.ln(), 10_000, &mut StdRng::seed_from_u64(3))
+            .into_iter()
+            .map(f64::exp)
+            .collect();
+
+        let returns: Vec<f64> = prices.windows(2).map(|w| (w[1] / w[0]).ln()).collect();
+
+        for lag in [2, 4, 8, 16] {
+            let test = variance_ratio_test(&returns, lag).unwrap();
+
+            assert!(test.p_value > 0.05, "lag {lag}: p-value {}", test.p_value);
+        }
+    }
+}
@@ -0,0 +1,114 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Approximate comparison of floats, slices, and matrices.
+//!
+//! Two numbers are close if $|a - b| \le \max(\epsilon_{rel} \max(|a|, |b|),
+//! \epsilon_{abs})$: the relative tolerance governs large values and the
+//! absolute tolerance values near zero.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use nalgebra::DMatrix;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Whether `a` and `b` are within `rel_tol` of each other relative to the
+/// larger magnitude, or within `abs_tol` absolutely.
+///
+/// `NaN` is never close to anything; equal infinities are close.
+#[must_use]
+pub fn approx_eq(a: f64, b: f64, rel_tol: f64, abs_tol: f64) -> bool {
+    if a == b {
+        return true;
+    }
+
+    let difference = (a - b).abs();
+
+    difference <= abs_tol || difference <= rel_tol * a.abs().max(b.abs())
+}
+
+/// Assert that `actual` and `expected` have the same length and are
+/// element-wise [`approx_eq`].
+///
+/// # Panics
+/// Panics with the first offending index otherwise.
+#[track_caller]
+pub fn assert_slices_approx_eq(actual: &[f64], expected: &[f64], rel_tol: f64, abs_tol: f64) {
+    assert_eq!(actual.len(), expected.len(), "Lengths differ.");
+
+    for (i, (a, e)) in actual.iter().zip(expected).enumerate() {
+        assert!(
+            approx_eq(*a, *e, rel_tol, abs_tol),
+            "Element {i} differs: {a} vs {e} (relative tolerance {rel_tol}, absolute tolerance {abs_tol})."
+        );
+    }
+}
+
+/// Assert that `actual` and `expected` have the same shape and are
+/// element-wise [`approx_eq`].
+///
+/// # Panics
+/// Panics with the first offending entry otherwise.
+#[track_caller]
+pub fn assert_matrices_approx_eq(
+    actual: &DMatrix<f64>,
+    expected: &DMatrix<f64>,
+    rel_tol: f64,
+    abs_tol: f64,
+) {
+    assert_eq!(actual.shape(), expected.shape(), "Shapes differ.");
+
+    for i in 0..actual.nrows() {
+        for j in 0..actual.ncols() {
+            let (a, e) = (actual[(i, j)], expected[(i, j)]);
+
+            assert!(
+                approx_eq(a, e, rel_tol, abs_tol),
+                "Entry ({i}, {j}) differs: {a} vs {e} (relative tolerance {rel_tol}, absolute tolerance {abs_tol})."
+            );
+        }
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_approx {
+    use super::*;
+
+    #[test]
+    fn test_approx_eq() {
+        assert!(approx_eq(1e6, 1e6 + 1.0, 1e-5, 0.0));
+        assert!(!approx_eq(1.0, 2.0, 1e-5, 1e-8));
+        assert!(approx_eq(0.0, 1e-12, 1e-5, 1e-10));
+        assert!(approx_eq(f64::INFINITY, f64::INFINITY, 0.0, 0.0));
+        assert!(!approx_eq(f64::NAN, f64::NAN, 1.0, 1.0));
+    }
+
+    #[test]
+    fn test_slices_and_matrices() {
+        assert_slices_approx_eq(&[1.0, 100.0], &[1.0 + 1e-10, 100.0 + 1e-8], 1e-9, 0.0);
+
+        let a = DMatrix::from_row_slice(2, 2, &[1.0, 2.0, 3.0, 4.0]);
+        assert_matrices_approx_eq(&a, &(&a * (1.0 + 1e-12)), 1e-10, 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Element 1 differs")]
+    fn test_slices_mismatch() {
+        assert_slices_approx_eq(&[1.0, 2.0], &[1.0, 2.1], 1e-6, 1e-6);
+    }
+}
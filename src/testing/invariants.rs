@@ -0,0 +1,241 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Pricing invariants every model should satisfy.
+//!
+//! Each check returns `Ok(())` or an `Err` describing the first violation,
+//! so it can be used with `prop_assert!` or unwrapped in a plain test.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::OptionParameters;
+use crate::math::distributions::Distribution;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Put-call parity of European options:
+/// $C - P = S e^{-qT} - K e^{-rT}$, to within `tolerance` relative to the
+/// spot.
+///
+/// # Errors
+/// - If either price is not finite or the parity gap exceeds the
+///   tolerance.
+pub fn check_put_call_parity(
+    call: f64,
+    put: f64,
+    p: &OptionParameters,
+    tolerance: f64,
+) -> Result<(), String> {
+    if !(call.is_finite() && put.is_finite()) {
+        return Err(format!("Prices must be finite: call {call}, put {put}."));
+    }
+
+    let parity = p.s * (-p.q * p.t).exp() - p.k * (-p.r * p.t).exp();
+    let gap = call - put - parity;
+
+    if gap.abs() > tolerance * p.s {
+        return Err(format!(
+            "Put-call parity violated by {gap}: call {call}, put {put}, {p:?}."
+        ));
+    }
+
+    Ok(())
+}
+
+/// `price` is non-decreasing over the increasing `points`, to within
+/// `tolerance` (e.g. a call price in the spot, or a put price in the
+/// strike).
+///
+/// # Errors
+/// - If a price is not finite or decreases by more than the tolerance.
+pub fn check_non_decreasing<F>(price: F, points: &[f64], tolerance: f64) -> Result<(), String>
+where
+    F: Fn(f64) -> f64,
+{
+    let values = finite_values(&price, points)?;
+
+    for (i, pair) in values.windows(2).enumerate() {
+        if pair[1] < pair[0] - tolerance {
+            return Err(format!(
+                "Decreasing from {} at {} to {} at {}.",
+                pair[0],
+                points[i],
+                pair[1],
+                points[i + 1]
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// `price` is non-increasing over the increasing `points`, to within
+/// `tolerance` (e.g. a call price in the strike).
+///
+/// # Errors
+/// - If a price is not finite or increases by more than the tolerance.
+pub fn check_non_increasing<F>(price: F, points: &[f64], tolerance: f64) -> Result<(), String>
+where
+    F: Fn(f64) -> f64,
+{
+    check_non_decreasing(|x| -price(x), points, tolerance)
+}
+
+/// `price` is convex over the increasing `points` (e.g. an option price in
+/// the strike, which rules out butterfly arbitrage): every point lies on or
+/// below the chord of its neighbours, to within `tolerance`.
+///
+/// # Errors
+/// - If a price is not finite or lies above a chord by more than the
+///   tolerance.
+pub fn check_convex<F>(price: F, points: &[f64], tolerance: f64) -> Result<(), String>
+where
+    F: Fn(f64) -> f64,
+{
+    let values = finite_values(&price, points)?;
+
+    for i in 1..points.len().saturating_sub(1) {
+        let (x0, x1, x2) = (points[i - 1], points[i], points[i + 1]);
+        let weight = (x2 - x1) / (x2 - x0);
+        let chord = weight * values[i - 1] + (1.0 - weight) * values[i + 1];
+
+        if values[i] > chord + tolerance {
+            return Err(format!(
+                "Not convex at {x1}: {} above the chord {chord}.",
+                values[i]
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// The density of `distribution` is non-negative and finite, and its CDF is
+/// within $[0, 1]$ and non-decreasing, at the increasing `points`.
+///
+/// # Errors
+/// - On the first point where any of these fails.
+pub fn check_density<D>(distribution: &D, points: &[f64]) -> Result<(), String>
+where
+    D: Distribution + ?Sized,
+{
+    let mut previous_cdf = 0.0;
+
+    for &x in points {
+        let density = distribution.pdf(x);
+        if !(density.is_finite() && density >= 0.0) {
+            return Err(format!("Invalid density {density} at {x}."));
+        }
+
+        let cdf = distribution.cdf(x);
+        if !(0.0..=1.0).contains(&cdf) || cdf < previous_cdf - 1e-12 {
+            return Err(format!(
+                "Invalid CDF {cdf} at {x} (previous {previous_cdf})."
+            ));
+        }
+        previous_cdf = cdf;
+    }
+
+    Ok(())
+}
+
+/// `price` at each of the `points`, all finite.
+fn finite_values<F>(price: &F, points: &[f64]) -> Result<Vec<f64>, String>
+where
+    F: Fn(f64) -> f64,
+{
+    points
+        .iter()
+        .map(|&x| {
+            let value = price(x);
+
+            if value.is_finite() {
+                Ok(value)
+            } else {
+                Err(format!("Price {value} at {x} is not finite."))
+            }
+        })
+        .collect()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_invariants {
+    use super::*;
+    use crate::math::distributions::{Binomial, ChiSquared, Exponential, Gamma, Gaussian, Poisson};
+    use crate::testing::option_parameters;
+    use proptest::prelude::*;
+
+    #[test]
+    fn test_checks_catch_violations() {
+        let points = [1.0, 2.0, 3.0, 4.0];
+
+        assert!(check_non_decreasing(|x| x * x, &points, 0.0).is_ok());
+        assert!(check_non_decreasing(|x| -x, &points, 0.0).is_err());
+        assert!(check_non_increasing(|x| -x, &points, 0.0).is_ok());
+        assert!(check_convex(|x| x * x, &points, 0.0).is_ok());
+        assert!(check_convex(|x: f64| x.sqrt(), &points, 0.0).is_err());
+        assert!(check_convex(|_| f64::NAN, &points, 0.0).is_err());
+
+        let p = OptionParameters {
+            s: 100.0,
+            k: 100.0,
+            r: 0.0,
+            q: 0.0,
+            v: 0.2,
+            t: 1.0,
+        };
+        assert!(check_put_call_parity(8.0, 8.0, &p, 1e-12).is_ok());
+        assert!(check_put_call_parity(8.0, 7.0, &p, 1e-12).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn test_densities_are_valid(mean in -10.0..10.0_f64, scale in 0.01..10.0_f64) {
+            let points: Vec<f64> = (-100..=100).map(|i| f64::from(i) * 0.2).collect();
+
+            prop_assert!(check_density(&Gaussian::new(mean, scale * scale), &points).is_ok());
+            prop_assert!(check_density(&Exponential::new(scale), &points).is_ok());
+            prop_assert!(check_density(&Gamma::new(scale, 1.0 / scale), &points).is_ok());
+
+            let counts: Vec<f64> = (0..50).map(f64::from).collect();
+            prop_assert!(check_density(&Poisson::new(scale), &counts).is_ok());
+        }
+
+        #[test]
+        fn test_integer_parameter_densities_are_valid(n in 1..60_usize, probability in 0.0..1.0_f64) {
+            let binomial = Binomial::new(n, probability);
+            let counts: Vec<f64> = (0..=n).map(|k| k as f64).collect();
+
+            prop_assert!(check_density(&binomial, &counts).is_ok());
+            let total: f64 = counts.iter().map(|&k| binomial.pmf(k)).sum();
+            prop_assert!((total - 1.0).abs() < 1e-9);
+
+            let points: Vec<f64> = (-10..=200).map(|i| f64::from(i) * 0.25).collect();
+            prop_assert!(check_density(&ChiSquared::new(n), &points).is_ok());
+        }
+
+        #[test]
+        fn test_intrinsic_value_satisfies_parity_at_zero_rates(mut p in option_parameters()) {
+            p.r = 0.0;
+            p.q = 0.0;
+
+            let call = (p.s - p.k).max(0.0);
+            let put = (p.k - p.s).max(0.0);
+            prop_assert!(check_put_call_parity(call, put, &p, 1e-12).is_ok());
+        }
+    }
+}
@@ -0,0 +1,42 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Test-support utilities (requires the `testing` feature).
+//!
+//! - [`approx`]: element-wise comparison of slices and matrices with a
+//!   relative tolerance.
+//! - [`strategies`]: [`proptest`] strategies for dates, discount curves, and
+//!   option parameters within no-arbitrage bounds.
+//! - [`invariants`]: reusable checks of pricing invariants (put-call
+//!   parity, monotonicity, convexity, valid densities).
+//!
+//! ```ignore
+//! use proptest::prelude::*;
+//! use RustQuant::testing::*;
+//!
+//! proptest! {
+//!     #[test]
+//!     fn parity(p in option_parameters()) {
+//!         let (call, put) = my_pricer(&p);
+//!         prop_assert!(check_put_call_parity(call, put, &p, 1e-10).is_ok());
+//!     }
+//! }
+//! ```
+
+/// Approximate comparison of slices and matrices.
+pub mod approx;
+pub use approx::*;
+
+/// Reusable pricing invariant checks.
+pub mod invariants;
+pub use invariants::*;
+
+/// Proptest strategies for financial inputs.
+pub mod strategies;
+pub use strategies::*;
@@ -0,0 +1,156 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! [`proptest`] strategies for dates, discount curves, and option
+//! parameters.
+//!
+//! The ranges are wide enough to reach extreme but meaningful inputs (deep
+//! in or out of the money, very short or long expiries, low and high
+//! volatilities), and narrow enough that every sample is arbitrage-free.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::data::{CurveInterpolation, PiecewiseCurve};
+use proptest::prelude::*;
+use time::{Date, Duration};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Inputs of a European option on a dividend-paying underlying.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OptionParameters {
+    /// Spot price.
+    pub s: f64,
+    /// Strike price.
+    pub k: f64,
+    /// Continuously compounded risk-free rate.
+    pub r: f64,
+    /// Continuous dividend yield.
+    pub q: f64,
+    /// Volatility.
+    pub v: f64,
+    /// Time to expiry, in years.
+    pub t: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl OptionParameters {
+    /// Forward price of the underlying at expiry.
+    #[must_use]
+    pub fn forward(&self) -> f64 {
+        self.s * ((self.r - self.q) * self.t).exp()
+    }
+}
+
+/// Dates from 2000-01-01 to 2099-12-31.
+pub fn dates() -> impl Strategy<Value = Date> {
+    // Julian days of 2000-01-01 and 2099-12-31.
+    (2_451_545..=2_488_069_i32).prop_map(|day| Date::from_julian_day(day).expect("Valid day."))
+}
+
+/// Ordered pairs of dates, between 1 and `max_days` days apart.
+///
+/// # Panics
+/// Panics if `max_days` is less than 1.
+pub fn date_pairs(max_days: i64) -> impl Strategy<Value = (Date, Date)> {
+    assert!(max_days >= 1, "Maximum gap must be at least a day.");
+
+    (dates(), 1..=max_days).prop_map(|(start, days)| (start, start + Duration::days(days)))
+}
+
+/// Log-linear discount curves with 1 to 8 knots out to at most 24 years,
+/// and non-negative forward rates of up to 10%, so discount factors are
+/// positive and non-increasing.
+pub fn discount_curves() -> impl Strategy<Value = PiecewiseCurve> {
+    prop::collection::vec((0.1..3.0_f64, 0.0..0.1_f64), 1..=8).prop_map(|segments| {
+        let mut knots = Vec::with_capacity(segments.len());
+        let mut log_discounts = Vec::with_capacity(segments.len());
+        let (mut t, mut log_discount) = (0.0, 0.0);
+
+        for (dt, forward) in segments {
+            t += dt;
+            log_discount -= forward * dt;
+            knots.push(t);
+            log_discounts.push(log_discount);
+        }
+
+        PiecewiseCurve::new(
+            &knots,
+            &log_discounts,
+            CurveInterpolation::LogLinear,
+            vec![],
+        )
+        .expect("Knots are increasing.")
+    })
+}
+
+/// Option parameters: spot from 1 to 1,000, log-moneyness within $\pm 1$,
+/// rates from -2% to 10%, dividend yields up to 8%, volatilities from 1%
+/// to 150%, and expiries from a day to 5 years.
+pub fn option_parameters() -> impl Strategy<Value = OptionParameters> {
+    (
+        1.0..1000.0_f64,
+        -1.0..1.0_f64,
+        -0.02..0.1_f64,
+        0.0..0.08_f64,
+        0.01..1.5_f64,
+        1.0 / 365.0..5.0_f64,
+    )
+        .prop_map(|(s, moneyness, r, q, v, t)| OptionParameters {
+            s,
+            k: s * moneyness.exp(),
+            r,
+            q,
+            v,
+            t,
+        })
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_strategies {
+    use super::*;
+    use time::macros::date;
+
+    proptest! {
+        #[test]
+        fn test_dates_in_range((start, end) in date_pairs(30)) {
+            prop_assert!(start >= date!(2000 - 01 - 01));
+            prop_assert!(start <= date!(2099 - 12 - 31));
+            prop_assert!(end > start && end - start <= Duration::days(30));
+        }
+
+        #[test]
+        fn test_discount_curves_are_arbitrage_free(curve in discount_curves()) {
+            let mut previous = 1.0;
+
+            for i in 1..=100 {
+                let df = curve.discount_factor(0.25 * f64::from(i));
+                prop_assert!(df > 0.0 && df <= previous + 1e-15);
+                previous = df;
+            }
+        }
+
+        #[test]
+        fn test_option_parameters_in_bounds(p in option_parameters()) {
+            prop_assert!(p.s > 0.0 && p.k > 0.0 && p.v > 0.0 && p.t > 0.0);
+            prop_assert!(p.forward() > 0.0);
+        }
+    }
+}
@@ -12,6 +12,7 @@
 // ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
 
 use super::{contains_leap_year, days_between, is_last_day_of_february, leap_year_count};
+use crate::error::PricingError;
 use crate::time::Calendar;
 use std::fmt;
 use time::{util::is_leap_year, Date, Duration, Month};
@@ -352,6 +353,11 @@ impl Default for DayCountConvention {
 
 impl DayCountConvention {
     /// Entry point for day count factor calculation.
+    ///
+    /// The factor is signed: it is zero when both dates are the same day and
+    /// negative when `end_date` is before `start_date`. Only dates are
+    /// counted, so two instants on the same day are zero years apart. Use
+    /// [`Self::time_to_expiry`] to reject expired instruments.
     #[rustfmt::skip]
     pub fn day_count_factor(&self, start_date: Date, end_date: Date) -> f64 {
         match self {
@@ -376,6 +382,23 @@ impl DayCountConvention {
             Self::Thirty_U_360          => Self::day_count_factor_thirty_u_360(start_date, end_date),
         }
     }
+
+    /// Time from `valuation_date` to `expiry`, in years.
+    ///
+    /// Zero when the instrument expires on the valuation date, in which case
+    /// pricers return the intrinsic value.
+    ///
+    /// # Errors
+    /// [`PricingError::Expired`] if `expiry` is before `valuation_date`.
+    pub fn time_to_expiry(&self, valuation_date: Date, expiry: Date) -> Result<f64, PricingError> {
+        let T = self.day_count_factor(valuation_date, expiry);
+
+        if T < 0.0 {
+            Err(PricingError::Expired(-T))
+        } else {
+            Ok(T)
+        }
+    }
 }
 
 impl DayCountConvention {
@@ -775,3 +798,35 @@ mod TESTS_actual_actual {
         );
     }
 }
+
+#[cfg(test)]
+mod TESTS_signed_factors {
+    use crate::error::PricingError;
+    use crate::time::DayCountConvention;
+    use time::macros::date;
+
+    const DATE_1: time::Date = date!(2023 - 11 - 1);
+    const DATE_2: time::Date = date!(2024 - 5 - 1);
+
+    #[test]
+    fn same_day_is_zero() {
+        let convention = DayCountConvention::default();
+
+        assert_eq!(convention.day_count_factor(DATE_1, DATE_1), 0.0);
+        assert_eq!(convention.time_to_expiry(DATE_1, DATE_1), Ok(0.0));
+    }
+
+    #[test]
+    fn reversed_dates_are_negative() {
+        let convention = DayCountConvention::default();
+        let forward = convention.day_count_factor(DATE_1, DATE_2);
+
+        assert!(forward > 0.0);
+        assert_eq!(convention.day_count_factor(DATE_2, DATE_1), -forward);
+        assert_eq!(convention.time_to_expiry(DATE_1, DATE_2), Ok(forward));
+        assert_eq!(
+            convention.time_to_expiry(DATE_2, DATE_1),
+            Err(PricingError::Expired(forward))
+        );
+    }
+}
@@ -0,0 +1,217 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Intraday expiry conventions.
+//!
+//! Options expire at a specific local time on their expiry date (the NYSE
+//! close, the CME settlement, the FX New York cut), not at midnight. Near
+//! expiry the hours matter, so an [`ExpiryConvention`] locates the expiry
+//! instant and measures the time to it to the second, as Actual/365 Fixed
+//! with fractional days.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use time::macros::{offset, time};
+use time::{Date, Month, OffsetDateTime, Time, UtcOffset, Weekday};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Seconds in an Actual/365 Fixed year.
+const SECONDS_PER_YEAR: f64 = 365.0 * 86_400.0;
+
+/// Time zone of a market's expiry cut.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarketTimeZone {
+    /// A fixed offset from UTC.
+    Fixed(UtcOffset),
+    /// US Eastern time (New York), with US daylight saving time.
+    NewYork,
+    /// US Central time (Chicago), with US daylight saving time.
+    Chicago,
+}
+
+/// Local time of day at which options expire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExpiryConvention {
+    /// Time zone of the cut.
+    pub time_zone: MarketTimeZone,
+    /// Local time of the cut.
+    pub cut_time: Time,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl MarketTimeZone {
+    /// UTC offset in effect on `date`.
+    ///
+    /// US daylight saving time runs from the second Sunday of March to the
+    /// first Sunday of November (the rules since 2007). The switch happens
+    /// at 02:00 local time, so the offset is exact for cut times after
+    /// 03:00.
+    #[must_use]
+    pub fn utc_offset(&self, date: Date) -> UtcOffset {
+        let (standard, daylight) = match self {
+            Self::Fixed(offset) => return *offset,
+            Self::NewYork => (offset!(-5), offset!(-4)),
+            Self::Chicago => (offset!(-6), offset!(-5)),
+        };
+
+        if is_us_daylight_saving(date) {
+            daylight
+        } else {
+            standard
+        }
+    }
+}
+
+impl ExpiryConvention {
+    /// New expiry convention.
+    #[must_use]
+    pub const fn new(time_zone: MarketTimeZone, cut_time: Time) -> Self {
+        Self {
+            time_zone,
+            cut_time,
+        }
+    }
+
+    /// NYSE close: 16:00 New York time.
+    #[must_use]
+    pub const fn nyse_close() -> Self {
+        Self::new(MarketTimeZone::NewYork, time!(16:00))
+    }
+
+    /// CME settlement: 15:00 Chicago time.
+    #[must_use]
+    pub const fn cme_settlement() -> Self {
+        Self::new(MarketTimeZone::Chicago, time!(15:00))
+    }
+
+    /// FX New York cut: 10:00 New York time.
+    #[must_use]
+    pub const fn fx_new_york_cut() -> Self {
+        Self::new(MarketTimeZone::NewYork, time!(10:00))
+    }
+
+    /// The instant at which an option expiring on `expiry_date` expires.
+    #[must_use]
+    pub fn expiry_instant(&self, expiry_date: Date) -> OffsetDateTime {
+        expiry_date
+            .with_time(self.cut_time)
+            .assume_offset(self.time_zone.utc_offset(expiry_date))
+    }
+
+    /// Time from `evaluation` to the expiry instant on `expiry_date`, in
+    /// Actual/365 Fixed years with fractional days.
+    ///
+    /// Negative after expiry, and independent of the offset `evaluation`
+    /// is expressed in.
+    #[must_use]
+    pub fn year_fraction(&self, evaluation: OffsetDateTime, expiry_date: Date) -> f64 {
+        (self.expiry_instant(expiry_date) - evaluation).as_seconds_f64() / SECONDS_PER_YEAR
+    }
+
+    /// Time from `evaluation` to expiry as whole days and the remaining
+    /// (fractional) hours, for display. Both are negative after expiry.
+    #[must_use]
+    pub fn time_to_expiry_days_hours(
+        &self,
+        evaluation: OffsetDateTime,
+        expiry_date: Date,
+    ) -> (i64, f64) {
+        let remaining = self.expiry_instant(expiry_date) - evaluation;
+        let days = remaining.whole_days();
+        let hours = (remaining - time::Duration::days(days)).as_seconds_f64() / 3600.0;
+
+        (days, hours)
+    }
+}
+
+/// Whether US daylight saving time is in effect on `date`.
+fn is_us_daylight_saving(date: Date) -> bool {
+    let first_sunday_from = |month: Month, day: u8| {
+        let date = Date::from_calendar_date(date.year(), month, day).expect("Valid date.");
+
+        if date.weekday() == Weekday::Sunday {
+            date
+        } else {
+            date.next_occurrence(Weekday::Sunday)
+        }
+    };
+
+    let start = first_sunday_from(Month::March, 8);
+    let end = first_sunday_from(Month::November, 1);
+
+    start <= date && date < end
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_expiry_convention {
+    use super::*;
+    use crate::assert_approx_equal;
+    use time::macros::{date, datetime};
+
+    #[test]
+    fn test_daylight_saving() {
+        let new_york = MarketTimeZone::NewYork;
+
+        // 2024: DST from 10 March to 3 November.
+        assert_eq!(new_york.utc_offset(date!(2024 - 03 - 09)), offset!(-5));
+        assert_eq!(new_york.utc_offset(date!(2024 - 03 - 10)), offset!(-4));
+        assert_eq!(new_york.utc_offset(date!(2024 - 11 - 02)), offset!(-4));
+        assert_eq!(new_york.utc_offset(date!(2024 - 11 - 03)), offset!(-5));
+        assert_eq!(
+            MarketTimeZone::Chicago.utc_offset(date!(2024 - 07 - 01)),
+            offset!(-5)
+        );
+
+        assert_eq!(
+            ExpiryConvention::nyse_close().expiry_instant(date!(2024 - 07 - 19)),
+            datetime!(2024-07-19 20:00 UTC)
+        );
+        assert_eq!(
+            ExpiryConvention::nyse_close().expiry_instant(date!(2024 - 01 - 19)),
+            datetime!(2024-01-19 21:00 UTC)
+        );
+    }
+
+    #[test]
+    fn test_expiry_today_at_the_close() {
+        let convention = ExpiryConvention::nyse_close();
+        let expiry = date!(2024 - 07 - 19);
+
+        let noon_new_york = datetime!(2024-07-19 12:00 -4);
+        let noon_new_york_in_utc = datetime!(2024-07-19 16:00 UTC);
+
+        let T = convention.year_fraction(noon_new_york, expiry);
+        assert_approx_equal!(T, 4.0 / (365.0 * 24.0), 1e-15);
+        assert_eq!(convention.year_fraction(noon_new_york_in_utc, expiry), T);
+
+        assert_eq!(
+            convention.time_to_expiry_days_hours(noon_new_york, expiry),
+            (0, 4.0)
+        );
+        assert_eq!(
+            convention.time_to_expiry_days_hours(datetime!(2024-07-17 09:30 -4), expiry),
+            (2, 6.5)
+        );
+
+        // After the close the option has expired.
+        assert!(convention.year_fraction(datetime!(2024-07-19 16:00:01 -4), expiry) < 0.0);
+    }
+}
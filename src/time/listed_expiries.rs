@@ -0,0 +1,433 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Exchange-listed expiry conventions.
+//!
+//! - Equity options expire on the third Friday of the month, or the
+//!   business day before if that is a holiday.
+//! - IMM dates are the third Wednesday of March, June, September, and
+//!   December.
+//! - Futures are identified by a root, a month code (`F` for January to
+//!   `Z` for December), and the last digit of the year, e.g. `ESZ4` for
+//!   the December 2024 E-mini S&P 500.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use crate::time::{previous_business_day, Calendar};
+use std::fmt;
+use time::{Date, Duration, Month, Weekday};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Futures month codes, January to December.
+const MONTH_CODES: [char; 12] = ['F', 'G', 'H', 'J', 'K', 'M', 'N', 'Q', 'U', 'V', 'X', 'Z'];
+
+/// Kind of a listed option expiry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ExpiryKind {
+    /// A Friday that is not a third Friday.
+    Weekly,
+    /// The third Friday of a month outside the quarterly cycle.
+    Monthly,
+    /// The third Friday of March, June, September, or December.
+    Quarterly,
+}
+
+/// A listed option expiry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ListedExpiry {
+    /// Expiry date, after any holiday adjustment.
+    pub date: Date,
+    /// Weekly, monthly, or quarterly.
+    pub kind: ExpiryKind,
+}
+
+/// A futures contract ticker, such as `ESZ4`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FuturesTicker {
+    /// Contract root, e.g. `ES`.
+    pub root: String,
+    /// Delivery month.
+    pub month: Month,
+    /// Delivery year.
+    pub year: i32,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// The `n`-th (1-based) `weekday` of a month.
+///
+/// # Panics
+/// Panics if `n` is zero or the month has fewer than `n` such weekdays.
+#[must_use]
+pub fn nth_weekday_of_month(year: i32, month: Month, weekday: Weekday, n: u8) -> Date {
+    assert!(n > 0, "n must be positive.");
+
+    let first = Date::from_calendar_date(year, month, 1).expect("Valid date.");
+    let offset =
+        (7 + weekday.number_days_from_monday() - first.weekday().number_days_from_monday()) % 7;
+    let date = first + Duration::days(i64::from(offset + 7 * (n - 1)));
+
+    assert_eq!(
+        date.month(),
+        month,
+        "The month has fewer than {n} {weekday}s."
+    );
+
+    date
+}
+
+/// Third Friday of the month: the standard monthly option expiry, before
+/// holiday adjustment.
+#[must_use]
+pub fn third_friday(year: i32, month: Month) -> Date {
+    nth_weekday_of_month(year, month, Weekday::Friday, 3)
+}
+
+/// Monthly equity option expiry: the third Friday of the month, or the
+/// previous business day of `calendar` if it is a holiday.
+#[must_use]
+pub fn equity_option_expiry<C: Calendar>(year: i32, month: Month, calendar: &C) -> Date {
+    previous_business_day(third_friday(year, month), calendar)
+}
+
+/// IMM date of the month: its third Wednesday. The IMM months are March,
+/// June, September, and December.
+#[must_use]
+pub fn imm_date(year: i32, month: Month) -> Date {
+    nth_weekday_of_month(year, month, Weekday::Wednesday, 3)
+}
+
+/// Whether `date` is an IMM date (the third Wednesday of March, June,
+/// September, or December).
+#[must_use]
+pub fn is_imm_date(date: Date) -> bool {
+    is_quarterly_month(date.month()) && date == imm_date(date.year(), date.month())
+}
+
+/// The first IMM date strictly after `date`.
+#[must_use]
+pub fn next_imm_date(date: Date) -> Date {
+    let (mut year, mut month) = (date.year(), date.month());
+
+    loop {
+        if is_quarterly_month(month) {
+            let imm = imm_date(year, month);
+            if imm > date {
+                return imm;
+            }
+        }
+
+        if month == Month::December {
+            year += 1;
+        }
+        month = month.next();
+    }
+}
+
+/// Futures month code of `month` (`F` for January to `Z` for December).
+#[must_use]
+pub fn futures_month_code(month: Month) -> char {
+    MONTH_CODES[usize::from(u8::from(month)) - 1]
+}
+
+/// Month of a futures month code, if valid.
+#[must_use]
+pub fn month_from_futures_code(code: char) -> Option<Month> {
+    let index = MONTH_CODES.iter().position(|&c| c == code)?;
+
+    Month::try_from(index as u8 + 1).ok()
+}
+
+/// Listed option expiries on or after `valuation_date`: the next
+/// `n_weeklies` weeklies, `n_monthlies` monthlies, and `n_quarterlies`
+/// quarterlies, in date order.
+///
+/// Weeklies expire on Fridays, and monthlies and quarterlies on third
+/// Fridays; each moves to the previous business day of `calendar` on a
+/// holiday.
+#[must_use]
+pub fn listed_expiries<C: Calendar>(
+    valuation_date: Date,
+    n_weeklies: usize,
+    n_monthlies: usize,
+    n_quarterlies: usize,
+    calendar: &C,
+) -> Vec<ListedExpiry> {
+    let mut counts = [
+        (ExpiryKind::Weekly, n_weeklies),
+        (ExpiryKind::Monthly, n_monthlies),
+        (ExpiryKind::Quarterly, n_quarterlies),
+    ];
+    let mut expiries = Vec::with_capacity(n_weeklies + n_monthlies + n_quarterlies);
+
+    // Holidays only move expiries earlier, so start from the first Friday
+    // on or after the valuation date.
+    let mut friday = if valuation_date.weekday() == Weekday::Friday {
+        valuation_date
+    } else {
+        valuation_date.next_occurrence(Weekday::Friday)
+    };
+
+    while counts.iter().any(|(_, remaining)| *remaining > 0) {
+        let kind = if friday != third_friday(friday.year(), friday.month()) {
+            ExpiryKind::Weekly
+        } else if is_quarterly_month(friday.month()) {
+            ExpiryKind::Quarterly
+        } else {
+            ExpiryKind::Monthly
+        };
+
+        let date = previous_business_day(friday, calendar);
+        let remaining = &mut counts[kind as usize].1;
+
+        if date >= valuation_date && *remaining > 0 {
+            expiries.push(ListedExpiry { date, kind });
+            *remaining -= 1;
+        }
+
+        friday += Duration::weeks(1);
+    }
+
+    expiries
+}
+
+fn is_quarterly_month(month: Month) -> bool {
+    matches!(
+        month,
+        Month::March | Month::June | Month::September | Month::December
+    )
+}
+
+impl FuturesTicker {
+    /// New futures ticker.
+    #[must_use]
+    pub fn new(root: &str, month: Month, year: i32) -> Self {
+        Self {
+            root: root.to_string(),
+            month,
+            year,
+        }
+    }
+
+    /// Parse a ticker such as `ESZ4` or `ESZ24`.
+    ///
+    /// A one-digit year is the first year ending in that digit on or after
+    /// `reference_year` (so `ESZ4` is December 2024 from 2020 to 2024, and
+    /// December 2034 from 2025); a two-digit year is in the 2000s.
+    ///
+    /// # Errors
+    /// `InvalidArgument` if the ticker has no root, an invalid month code,
+    /// or a year that is not one or two digits.
+    pub fn parse(ticker: &str, reference_year: i32) -> Result<Self, RustQuantError> {
+        let invalid =
+            || RustQuantError::InvalidArgument(format!("Invalid futures ticker: {ticker}."));
+
+        let digits = ticker
+            .chars()
+            .rev()
+            .take_while(char::is_ascii_digit)
+            .count();
+        let (rest, year) = ticker.split_at(ticker.len() - digits);
+
+        let mut chars = rest.chars();
+        let code = chars.next_back().ok_or_else(invalid)?;
+        let root = chars.as_str();
+        let month = month_from_futures_code(code).ok_or_else(invalid)?;
+
+        if root.is_empty() {
+            return Err(invalid());
+        }
+
+        let year: i32 = match digits {
+            1 => {
+                let digit: i32 = year.parse().map_err(|_| invalid())?;
+                reference_year + (digit - reference_year).rem_euclid(10)
+            }
+            2 => 2000 + year.parse::<i32>().map_err(|_| invalid())?,
+            _ => return Err(invalid()),
+        };
+
+        Ok(Self::new(root, month, year))
+    }
+}
+
+impl fmt::Display for FuturesTicker {
+    /// Format as root, month code, and the last digit of the year.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}{}{}",
+            self.root,
+            futures_month_code(self.month),
+            self.year.rem_euclid(10)
+        )
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_listed_expiries {
+    use super::*;
+    use crate::time::north_america::united_states::UnitedStatesCalendar;
+    use time::macros::date;
+
+    const MONTHS: [Month; 12] = [
+        Month::January,
+        Month::February,
+        Month::March,
+        Month::April,
+        Month::May,
+        Month::June,
+        Month::July,
+        Month::August,
+        Month::September,
+        Month::October,
+        Month::November,
+        Month::December,
+    ];
+
+    // The US calendar with Good Friday, on which US exchanges close.
+    struct ExchangeCalendar;
+
+    impl Calendar for ExchangeCalendar {
+        fn name(&self) -> &'static str {
+            "Test exchange"
+        }
+
+        fn country_code(&self) -> crate::iso::ISO_3166 {
+            crate::iso::UNITED_STATES_OF_AMERICA
+        }
+
+        fn market_identifier_code(&self) -> crate::iso::ISO_10383 {
+            crate::iso::XNYS
+        }
+
+        fn is_holiday(&self, date: Date) -> bool {
+            UnitedStatesCalendar.is_holiday(date) || date == date!(2025 - 04 - 18)
+        }
+    }
+
+    #[test]
+    fn test_equity_option_expiries() {
+        let expected = [
+            (2024, [19, 16, 15, 19, 17, 21, 19, 16, 20, 18, 15, 20]),
+            // 18 April is Good Friday.
+            (2025, [17, 21, 21, 17, 16, 20, 18, 15, 19, 17, 21, 19]),
+            // 19 June is Juneteenth.
+            (2026, [16, 20, 20, 17, 15, 18, 17, 21, 18, 16, 20, 18]),
+        ];
+
+        for (year, days) in expected {
+            for (month, day) in MONTHS.into_iter().zip(days) {
+                let expiry = Date::from_calendar_date(year, month, day).unwrap();
+
+                assert_eq!(equity_option_expiry(year, month, &ExchangeCalendar), expiry);
+            }
+        }
+
+        assert_eq!(third_friday(2026, Month::June), date!(2026 - 06 - 19));
+        assert_eq!(
+            equity_option_expiry(2026, Month::June, &UnitedStatesCalendar),
+            date!(2026 - 06 - 18)
+        );
+    }
+
+    #[test]
+    fn test_imm_dates() {
+        let expected = [
+            (2024, [20, 19, 18, 18]),
+            (2025, [19, 18, 17, 17]),
+            (2026, [18, 17, 16, 16]),
+        ];
+        let quarterly = [Month::March, Month::June, Month::September, Month::December];
+
+        for (year, days) in expected {
+            for (month, day) in quarterly.into_iter().zip(days) {
+                let imm = Date::from_calendar_date(year, month, day).unwrap();
+
+                assert_eq!(imm_date(year, month), imm);
+                assert!(is_imm_date(imm));
+                assert!(!is_imm_date(imm.next_day().unwrap()));
+            }
+        }
+
+        assert!(!is_imm_date(date!(2024 - 01 - 17)));
+        assert_eq!(next_imm_date(date!(2024 - 01 - 02)), date!(2024 - 03 - 20));
+        assert_eq!(next_imm_date(date!(2024 - 03 - 20)), date!(2024 - 06 - 19));
+        assert_eq!(next_imm_date(date!(2024 - 12 - 18)), date!(2025 - 03 - 19));
+    }
+
+    #[test]
+    fn test_futures_tickers() {
+        for (month, code) in MONTHS.into_iter().zip(MONTH_CODES) {
+            assert_eq!(futures_month_code(month), code);
+            assert_eq!(month_from_futures_code(code), Some(month));
+        }
+        assert_eq!(month_from_futures_code('A'), None);
+
+        let es = FuturesTicker::parse("ESZ4", 2024).unwrap();
+        assert_eq!(es, FuturesTicker::new("ES", Month::December, 2024));
+        assert_eq!(es.to_string(), "ESZ4");
+
+        assert_eq!(FuturesTicker::parse("ESZ4", 2021).unwrap().year, 2024);
+        assert_eq!(FuturesTicker::parse("ESZ4", 2025).unwrap().year, 2034);
+        assert_eq!(
+            FuturesTicker::parse("CLH26", 2024).unwrap(),
+            FuturesTicker::new("CL", Month::March, 2026)
+        );
+        assert_eq!(
+            FuturesTicker::parse("ZNU5", 2024).unwrap(),
+            FuturesTicker::new("ZN", Month::September, 2025)
+        );
+
+        for invalid in ["", "Z4", "ESA4", "ESZ", "ESZ123"] {
+            assert!(FuturesTicker::parse(invalid, 2024).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn test_listed_expiry_ladder() {
+        // Wednesday 10 June 2026: the June quarterly moves to Thursday 18
+        // June (Juneteenth).
+        let ladder = listed_expiries(date!(2026 - 06 - 10), 4, 2, 2, &UnitedStatesCalendar);
+
+        let expected = [
+            (date!(2026 - 06 - 12), ExpiryKind::Weekly),
+            (date!(2026 - 06 - 18), ExpiryKind::Quarterly),
+            (date!(2026 - 06 - 26), ExpiryKind::Weekly),
+            (date!(2026 - 07 - 02), ExpiryKind::Weekly),
+            (date!(2026 - 07 - 10), ExpiryKind::Weekly),
+            (date!(2026 - 07 - 17), ExpiryKind::Monthly),
+            (date!(2026 - 08 - 21), ExpiryKind::Monthly),
+            (date!(2026 - 09 - 18), ExpiryKind::Quarterly),
+        ];
+
+        assert_eq!(
+            ladder,
+            expected
+                .map(|(date, kind)| ListedExpiry { date, kind })
+                .to_vec()
+        );
+
+        // An expiry on the valuation date is included.
+        let ladder = listed_expiries(date!(2026 - 06 - 12), 1, 0, 0, &UnitedStatesCalendar);
+        assert_eq!(ladder[0].date, date!(2026 - 06 - 12));
+    }
+}
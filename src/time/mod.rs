@@ -31,6 +31,10 @@ pub use date_rolling::*;
 pub mod day_counting;
 pub use day_counting::*;
 
+/// Intraday expiry conventions (time zone and cut time).
+pub mod expiry_convention;
+pub use expiry_convention::*;
+
 /// Frequency of payments.
 pub mod frequency;
 pub use frequency::*;
@@ -39,6 +43,10 @@ pub use frequency::*;
 pub mod holiday;
 pub use holiday::*;
 
+/// Exchange-listed expiries, IMM dates, and futures tickers.
+pub mod listed_expiries;
+pub use listed_expiries::*;
+
 /// Utility functions for working with dates and times.
 pub mod utilities;
 pub use utilities::*;
@@ -0,0 +1,243 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Order book imbalance signals for short-term price prediction.
+//!
+//! The imbalance $I = (Q_b - Q_a) / (Q_b + Q_a)$ of the resting quantities
+//! at the best bid and ask lies in $[-1, 1]$: a heavy bid ($I \to 1$)
+//! predicts an up-tick, a heavy ask ($I \to -1$) a down-tick. The
+//! microprice $P_b \frac{Q_a}{Q_b + Q_a} + P_a \frac{Q_b}{Q_b + Q_a}$ moves
+//! the mid towards the side that is more likely to trade through.
+//!
+//! Book levels are `(price, quantity)` pairs, best first: bids in
+//! decreasing and asks in increasing price order.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// The visible levels of an order book at a point in time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderBookSnapshot {
+    /// Snapshot time.
+    pub timestamp: OffsetDateTime,
+    /// Bid levels `(price, quantity)`, best (highest) first.
+    pub bids: Vec<(f64, f64)>,
+    /// Ask levels `(price, quantity)`, best (lowest) first.
+    pub asks: Vec<(f64, f64)>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, TRAITS, AND FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl OrderBookSnapshot {
+    /// Create a new snapshot.
+    #[must_use]
+    pub fn new(timestamp: OffsetDateTime, bids: Vec<(f64, f64)>, asks: Vec<(f64, f64)>) -> Self {
+        Self {
+            timestamp,
+            bids,
+            asks,
+        }
+    }
+
+    /// Best bid `(price, quantity)`, if any.
+    #[must_use]
+    pub fn best_bid(&self) -> Option<(f64, f64)> {
+        self.bids.first().copied()
+    }
+
+    /// Best ask `(price, quantity)`, if any.
+    #[must_use]
+    pub fn best_ask(&self) -> Option<(f64, f64)> {
+        self.asks.first().copied()
+    }
+
+    /// Imbalance of the quantities at the best bid and ask (see
+    /// [`book_imbalance`]), with an empty side counting as zero quantity.
+    #[must_use]
+    pub fn imbalance(&self) -> f64 {
+        let quantity = |level: Option<(f64, f64)>| level.map_or(0.0, |(_, q)| q);
+
+        book_imbalance(quantity(self.best_bid()), quantity(self.best_ask()))
+    }
+
+    /// Microprice of the best bid and ask (see [`microprice`]), or `None`
+    /// if either side is empty.
+    #[must_use]
+    pub fn microprice(&self) -> Option<f64> {
+        let ((bid, bid_qty), (ask, ask_qty)) = (self.best_bid()?, self.best_ask()?);
+
+        Some(microprice(bid, ask, bid_qty, ask_qty))
+    }
+}
+
+/// Order book imbalance $(Q_b - Q_a) / (Q_b + Q_a)$, in $[-1, 1]$.
+///
+/// Zero when both quantities are zero.
+#[must_use]
+pub fn book_imbalance(best_bid_qty: f64, best_ask_qty: f64) -> f64 {
+    let total = best_bid_qty + best_ask_qty;
+
+    if total > 0.0 {
+        (best_bid_qty - best_ask_qty) / total
+    } else {
+        0.0
+    }
+}
+
+/// Microprice: the mid price weighted by the opposite side's quantity,
+/// $P_b \frac{Q_a}{Q_b + Q_a} + P_a \frac{Q_b}{Q_b + Q_a}$.
+///
+/// Equals the mid price when the quantities are equal (or both zero), and
+/// tends to the ask as the bid quantity dominates.
+#[must_use]
+pub fn microprice(best_bid: f64, best_ask: f64, bid_qty: f64, ask_qty: f64) -> f64 {
+    let mid = 0.5 * (best_bid + best_ask);
+    let half_spread = 0.5 * (best_ask - best_bid);
+
+    mid + half_spread * book_imbalance(bid_qty, ask_qty)
+}
+
+/// Depth-weighted mid price over several book levels: the microprice of
+/// the volume-weighted average bid and ask prices, weighted by the total
+/// quantities on each side.
+///
+/// With a single level per side this is the [`microprice`].
+///
+/// # Panics
+/// Panics if either side has no quantity.
+#[must_use]
+pub fn weighted_mid_price(bids: &[(f64, f64)], asks: &[(f64, f64)]) -> f64 {
+    let vwap = |levels: &[(f64, f64)]| {
+        let quantity: f64 = levels.iter().map(|(_, q)| q).sum();
+        let notional: f64 = levels.iter().map(|(p, q)| p * q).sum();
+
+        assert!(quantity > 0.0, "Both sides of the book must have quantity.");
+
+        (notional / quantity, quantity)
+    };
+
+    let ((bid, bid_qty), (ask, ask_qty)) = (vwap(bids), vwap(asks));
+
+    microprice(bid, ask, bid_qty, ask_qty)
+}
+
+/// Top-of-book imbalance of each snapshot, averaged over the last `window`
+/// snapshots (fewer for the first `window - 1`).
+///
+/// # Panics
+/// Panics if `window` is zero.
+#[must_use]
+pub fn rolling_imbalance(book_snapshots: &[OrderBookSnapshot], window: usize) -> Vec<f64> {
+    assert!(window > 0, "Window must be positive.");
+
+    let imbalances: Vec<f64> = book_snapshots
+        .iter()
+        .map(OrderBookSnapshot::imbalance)
+        .collect();
+
+    let mut sum = 0.0;
+
+    imbalances
+        .iter()
+        .enumerate()
+        .map(|(i, imbalance)| {
+            sum += imbalance;
+            if i >= window {
+                sum -= imbalances[i - window];
+            }
+
+            sum / (i + 1).min(window) as f64
+        })
+        .collect()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_order_book_signals {
+    use super::*;
+    use crate::assert_approx_equal;
+    use time::{macros::datetime, Duration};
+
+    #[test]
+    fn test_book_imbalance() {
+        assert_eq!(book_imbalance(5.0, 5.0), 0.0);
+        assert_eq!(book_imbalance(0.0, 0.0), 0.0);
+        assert_eq!(book_imbalance(5.0, 0.0), 1.0);
+        assert_eq!(book_imbalance(0.0, 5.0), -1.0);
+        assert_approx_equal!(book_imbalance(3.0, 1.0), 0.5, 1e-12);
+    }
+
+    #[test]
+    fn test_microprice() {
+        // Symmetric book: the microprice is the mid.
+        assert_approx_equal!(microprice(99.0, 101.0, 7.0, 7.0), 100.0, 1e-12);
+
+        // Heavy bid: pulled towards the ask, but within the spread.
+        let price = microprice(99.0, 101.0, 30.0, 10.0);
+        assert_approx_equal!(price, 100.5, 1e-12);
+        assert!(microprice(99.0, 101.0, 1e6, 1.0) < 101.0);
+    }
+
+    #[test]
+    fn test_weighted_mid_price() {
+        let bids = [(99.0, 10.0), (98.0, 30.0)];
+        let asks = [(101.0, 20.0), (103.0, 20.0)];
+
+        // Bid VWAP 98.25, ask VWAP 102, equal depth.
+        assert_approx_equal!(weighted_mid_price(&bids, &asks), 100.125, 1e-12);
+        assert_approx_equal!(
+            weighted_mid_price(&bids[..1], &asks[..1]),
+            microprice(99.0, 101.0, 10.0, 20.0),
+            1e-12
+        );
+    }
+
+    #[test]
+    fn test_rolling_imbalance() {
+        let start = datetime!(2024-01-02 14:30:00 UTC);
+        let snapshots: Vec<OrderBookSnapshot> = [(3.0, 1.0), (1.0, 1.0), (1.0, 3.0), (1.0, 3.0)]
+            .iter()
+            .enumerate()
+            .map(|(i, &(bid_qty, ask_qty))| {
+                OrderBookSnapshot::new(
+                    start + Duration::milliseconds(100 * i as i64),
+                    vec![(99.0, bid_qty), (98.0, 50.0)],
+                    vec![(101.0, ask_qty)],
+                )
+            })
+            .collect();
+
+        // Top-of-book imbalances 0.5, 0, -0.5, -0.5.
+        let rolling = rolling_imbalance(&snapshots, 2);
+        let expected = [0.5, 0.25, -0.25, -0.5];
+
+        assert_eq!(rolling.len(), expected.len());
+        for (r, e) in rolling.iter().zip(expected) {
+            assert_approx_equal!(*r, e, 1e-12);
+        }
+
+        assert_eq!(snapshots[1].microprice(), Some(100.0));
+        assert_eq!(
+            OrderBookSnapshot::new(start, vec![], vec![(101.0, 1.0)]).imbalance(),
+            -1.0
+        );
+    }
+}
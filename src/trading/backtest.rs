@@ -0,0 +1,421 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Bar-by-bar backtesting of a single-asset strategy.
+//!
+//! On each bar the [`Strategy`] sees the bar and returns market orders,
+//! which are filled at the close of that bar, less a proportional
+//! commission. The equity (cash plus the position marked at the close) is
+//! recorded after every bar.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{fill::Fill, order_side::OrderSide};
+use crate::data::Bar;
+use crate::error::RustQuantError;
+use crate::progress::{Outcome, ProgressHandle};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Market order placed by a strategy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BacktestOrder {
+    /// Buy (bid) or sell (ask).
+    pub side: OrderSide,
+    /// Number of units.
+    pub quantity: u64,
+}
+
+/// A trading strategy, called once per bar.
+pub trait Strategy {
+    /// Orders to fill at the close of `bar`.
+    fn on_bar(&mut self, bar: &Bar) -> Vec<BacktestOrder>;
+}
+
+/// Backtest settings.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Backtest {
+    /// Starting cash.
+    pub initial_cash: f64,
+    /// Commission, as a fraction of the traded value.
+    pub commission: f64,
+    /// Number of bars per year, to annualise the Sharpe ratio.
+    pub periods_per_year: f64,
+}
+
+/// Result of a backtest.
+#[derive(Debug, Clone)]
+pub struct BacktestResult {
+    /// Equity after each bar.
+    pub equity_curve: Vec<f64>,
+    /// Executions, in order.
+    pub fills: Vec<Fill>,
+    /// Position after the last bar.
+    pub final_position: i64,
+    /// Number of bars per year, to annualise the Sharpe ratio.
+    pub periods_per_year: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl BacktestOrder {
+    /// Buy `quantity` units.
+    #[must_use]
+    pub fn buy(quantity: u64) -> Self {
+        Self {
+            side: OrderSide::BID,
+            quantity,
+        }
+    }
+
+    /// Sell `quantity` units.
+    #[must_use]
+    pub fn sell(quantity: u64) -> Self {
+        Self {
+            side: OrderSide::ASK,
+            quantity,
+        }
+    }
+}
+
+impl<F> Strategy for F
+where
+    F: FnMut(&Bar) -> Vec<BacktestOrder>,
+{
+    fn on_bar(&mut self, bar: &Bar) -> Vec<BacktestOrder> {
+        self(bar)
+    }
+}
+
+impl Backtest {
+    /// New backtest with `initial_cash`, no commission, and daily bars
+    /// (252 per year).
+    #[must_use]
+    pub fn new(initial_cash: f64) -> Self {
+        Self {
+            initial_cash,
+            commission: 0.0,
+            periods_per_year: 252.0,
+        }
+    }
+
+    /// Set the commission, as a fraction of the traded value.
+    #[must_use]
+    pub fn with_commission(mut self, commission: f64) -> Self {
+        self.commission = commission;
+        self
+    }
+
+    /// Set the number of bars per year.
+    #[must_use]
+    pub fn with_periods_per_year(mut self, periods_per_year: f64) -> Self {
+        self.periods_per_year = periods_per_year;
+        self
+    }
+
+    /// Run `strategy` over `bars`.
+    ///
+    /// # Errors
+    /// - `InvalidArgument` if a bar does not have a positive, finite close.
+    pub fn run<S>(&self, strategy: &mut S, bars: &[Bar]) -> Result<BacktestResult, RustQuantError>
+    where
+        S: Strategy + ?Sized,
+    {
+        self.run_with_progress(strategy, bars, &mut ProgressHandle::new())
+            .map(Outcome::into_inner)
+    }
+
+    /// Run `strategy` over `bars`, reporting the number of bars processed
+    /// and the equity to `progress`.
+    ///
+    /// If the cancellation token is set, returns the result over the bars
+    /// processed so far as [`Outcome::Cancelled`].
+    ///
+    /// # Errors
+    /// - `InvalidArgument` if a bar does not have a positive, finite close.
+    pub fn run_with_progress<S>(
+        &self,
+        strategy: &mut S,
+        bars: &[Bar],
+        progress: &mut ProgressHandle,
+    ) -> Result<Outcome<BacktestResult>, RustQuantError>
+    where
+        S: Strategy + ?Sized,
+    {
+        let mut cancelled = false;
+        let mut cash = self.initial_cash;
+        let mut position: i64 = 0;
+        let mut fills = Vec::new();
+        let mut equity_curve = Vec::with_capacity(bars.len());
+
+        for bar in bars {
+            if progress.is_cancelled() {
+                cancelled = true;
+                break;
+            }
+
+            if !(bar.close.is_finite() && bar.close > 0.0) {
+                return Err(RustQuantError::InvalidArgument(format!(
+                    "Bar close must be positive and finite, got {}.",
+                    bar.close
+                )));
+            }
+
+            for order in strategy.on_bar(bar) {
+                if order.quantity == 0 {
+                    continue;
+                }
+
+                let fill = Fill::new(
+                    fills.len() as u64 + 1,
+                    0,
+                    order.side,
+                    bar.close,
+                    order.quantity,
+                    bar.end,
+                );
+                let value = order.quantity as f64 * bar.close;
+
+                cash -= fill.signed_quantity() as f64 * bar.close + self.commission * value;
+                position += fill.signed_quantity();
+                fills.push(fill);
+            }
+
+            let equity = cash + position as f64 * bar.close;
+            equity_curve.push(equity);
+            progress.report(equity_curve.len(), bars.len(), Some(equity));
+        }
+
+        let result = BacktestResult {
+            equity_curve,
+            fills,
+            final_position: position,
+            periods_per_year: self.periods_per_year,
+        };
+
+        Ok(Outcome::new(result, cancelled))
+    }
+}
+
+impl BacktestResult {
+    /// Number of executed orders.
+    #[must_use]
+    pub fn total_trades(&self) -> usize {
+        self.fills.len()
+    }
+
+    /// Simple returns of the equity curve, bar to bar.
+    #[must_use]
+    pub fn returns(&self) -> Vec<f64> {
+        self.equity_curve
+            .windows(2)
+            .map(|w| w[1] / w[0] - 1.0)
+            .collect()
+    }
+
+    /// Total return over the backtest, from the equity after the first bar.
+    #[must_use]
+    pub fn total_return(&self) -> f64 {
+        match (self.equity_curve.first(), self.equity_curve.last()) {
+            (Some(first), Some(last)) => last / first - 1.0,
+            _ => 0.0,
+        }
+    }
+
+    /// Annualised Sharpe ratio of the bar returns, with a zero risk-free
+    /// rate (0 if the returns have no variance).
+    #[must_use]
+    pub fn sharpe_ratio(&self) -> f64 {
+        let returns = self.returns();
+        let n = returns.len() as f64;
+
+        if returns.len() < 2 {
+            return 0.0;
+        }
+
+        let mean = returns.iter().sum::<f64>() / n;
+        let variance = returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+
+        if variance > 0.0 {
+            mean / variance.sqrt() * self.periods_per_year.sqrt()
+        } else {
+            0.0
+        }
+    }
+
+    /// Maximum drawdown of the equity curve, as a positive fraction of the
+    /// running peak.
+    #[must_use]
+    pub fn max_drawdown(&self) -> f64 {
+        let mut peak = f64::NEG_INFINITY;
+
+        self.equity_curve
+            .iter()
+            .fold(0.0, |drawdown: f64, &equity| {
+                peak = peak.max(equity);
+                drawdown.max(1.0 - equity / peak)
+            })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_backtest {
+    use super::*;
+    use crate::assert_approx_equal;
+    use time::{Duration, OffsetDateTime};
+
+    fn bars(closes: &[f64]) -> Vec<Bar> {
+        let start = OffsetDateTime::UNIX_EPOCH;
+
+        closes
+            .iter()
+            .enumerate()
+            .map(|(i, &close)| Bar {
+                start: start + Duration::days(i as i64),
+                end: start + Duration::days(i as i64 + 1),
+                open: close,
+                high: close,
+                low: close,
+                close,
+                volume: 0.0,
+                dollar_volume: 0.0,
+                trade_count: 0,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_buy_and_hold() {
+        let closes = [100.0, 110.0, 99.0, 120.0];
+        let mut bought = false;
+        let mut strategy = |_: &Bar| {
+            if bought {
+                vec![]
+            } else {
+                bought = true;
+                vec![BacktestOrder::buy(10)]
+            }
+        };
+
+        let result = Backtest::new(1000.0)
+            .run(&mut strategy, &bars(&closes))
+            .unwrap();
+
+        assert_eq!(result.equity_curve, vec![1000.0, 1100.0, 990.0, 1200.0]);
+        assert_eq!(result.total_trades(), 1);
+        assert_eq!(result.final_position, 10);
+        assert_approx_equal!(result.total_return(), 0.2, 1e-12);
+        assert_approx_equal!(result.max_drawdown(), 0.1, 1e-12);
+        assert!(result.sharpe_ratio() > 0.0);
+    }
+
+    #[test]
+    fn test_round_trip_with_commission() {
+        let mut bar_index = 0;
+        let mut strategy = |_: &Bar| {
+            bar_index += 1;
+            match bar_index {
+                1 => vec![BacktestOrder::buy(10)],
+                3 => vec![BacktestOrder::sell(10)],
+                _ => vec![],
+            }
+        };
+
+        let result = Backtest::new(1000.0)
+            .with_commission(0.001)
+            .run(&mut strategy, &bars(&[100.0, 105.0, 110.0, 90.0]))
+            .unwrap();
+
+        // Commissions of 1 and 1.1; flat after the sale.
+        assert_approx_equal!(result.equity_curve[0], 999.0, 1e-12);
+        assert_approx_equal!(result.equity_curve[2], 1000.0 + 100.0 - 2.1, 1e-12);
+        assert_approx_equal!(result.equity_curve[3], result.equity_curve[2], 1e-12);
+        assert_eq!(result.total_trades(), 2);
+        assert_eq!(result.final_position, 0);
+    }
+
+    #[test]
+    fn test_progress_and_cancellation() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::sync::Arc;
+
+        let closes: Vec<f64> = (1..=95).map(f64::from).collect();
+        let mut buy_once = {
+            let mut bought = false;
+            move |_: &Bar| {
+                let orders = if bought {
+                    vec![]
+                } else {
+                    vec![BacktestOrder::buy(1)]
+                };
+                bought = true;
+                orders
+            }
+        };
+
+        let mut reported = Vec::new();
+        let mut progress = ProgressHandle::new().with_callback(10, |done, total, equity| {
+            assert_eq!(total, 95);
+            reported.push((done, equity.unwrap()));
+        });
+        let outcome = Backtest::new(100.0)
+            .run_with_progress(&mut buy_once, &bars(&closes), &mut progress)
+            .unwrap();
+        drop(progress);
+
+        assert!(!outcome.is_cancelled());
+        assert_eq!(reported.len(), 10);
+        assert_eq!(reported[0], (10, 109.0));
+        assert_eq!(reported[9], (95, 194.0));
+
+        // Stop after 42 bars.
+        let cancel = Arc::new(AtomicBool::new(false));
+        let token = Arc::clone(&cancel);
+        let mut progress = ProgressHandle::new()
+            .with_callback(1, move |done, _, _| {
+                if done == 42 {
+                    token.store(true, Ordering::Relaxed);
+                }
+            })
+            .with_cancellation(cancel);
+        let mut idle = |_: &Bar| vec![];
+        let outcome = Backtest::new(100.0)
+            .run_with_progress(&mut idle, &bars(&closes), &mut progress)
+            .unwrap();
+
+        assert!(outcome.is_cancelled());
+        assert_eq!(outcome.value().equity_curve, vec![100.0; 42]);
+    }
+
+    #[test]
+    fn test_no_trades_and_bad_bars() {
+        let mut idle = |_: &Bar| vec![];
+        let result = Backtest::new(500.0)
+            .run(&mut idle, &bars(&[1.0, 2.0, 3.0]))
+            .unwrap();
+
+        assert_eq!(result.equity_curve, vec![500.0; 3]);
+        assert_approx_equal!(result.sharpe_ratio(), 0.0, 1e-15);
+        assert_approx_equal!(result.max_drawdown(), 0.0, 1e-15);
+
+        assert!(Backtest::new(500.0)
+            .run(&mut idle, &bars(&[1.0, f64::NAN]))
+            .is_err());
+    }
+}
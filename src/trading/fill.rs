@@ -0,0 +1,71 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use std::fmt;
+
+use super::{order::OrderID, order_side::OrderSide};
+use time::OffsetDateTime;
+
+/// A (partial) execution of an order.
+#[derive(Debug, Clone, Copy)]
+pub struct Fill {
+    /// Identifier of the order that was executed.
+    pub order_id: OrderID,
+    /// Symbol Identifier
+    pub symbol_id: u32,
+    /// Side of the execution (bid = buy, ask = sell).
+    pub side: OrderSide,
+    /// Execution price.
+    pub price: f64,
+    /// Executed quantity.
+    pub quantity: u64,
+    /// Execution timestamp.
+    pub timestamp: OffsetDateTime,
+}
+
+impl Fill {
+    /// Create a new `Fill`.
+    #[must_use]
+    pub fn new(
+        order_id: OrderID,
+        symbol_id: u32,
+        side: OrderSide,
+        price: f64,
+        quantity: u64,
+        timestamp: OffsetDateTime,
+    ) -> Self {
+        Self {
+            order_id,
+            symbol_id,
+            side,
+            price,
+            quantity,
+            timestamp,
+        }
+    }
+
+    /// Signed quantity of the fill: positive for buys, negative for sells.
+    #[must_use]
+    pub fn signed_quantity(&self) -> i64 {
+        match self.side {
+            OrderSide::BID => self.quantity as i64,
+            OrderSide::ASK => -(self.quantity as i64),
+        }
+    }
+}
+
+impl fmt::Display for Fill {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Fill(order_id={}, symbol_id={}, side={}, price={}, quantity={}, timestamp={})",
+            self.order_id, self.symbol_id, self.side, self.price, self.quantity, self.timestamp
+        )
+    }
+}
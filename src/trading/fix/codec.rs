@@ -0,0 +1,327 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Low-level `tag=value` encoding and decoding.
+
+use super::FixError;
+use std::fmt::{Display, Write};
+use std::str::FromStr;
+
+/// Field delimiter (start of header, ASCII 0x01).
+pub const SOH: u8 = 0x01;
+
+/// BeginString(8) of FIX 4.4 messages.
+pub const BEGIN_STRING: &str = "FIX.4.4";
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// A validated FIX message, as a list of `(tag, value)` pairs.
+///
+/// Values borrow from the input buffer, nothing is copied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawMessage<'a> {
+    fields: Vec<(u32, &'a str)>,
+}
+
+/// Builder for the body of an outgoing message.
+///
+/// Fields are written in the order they are added,
+/// and [`FieldWriter::finish`] adds the BeginString(8), BodyLength(9),
+/// and CheckSum(10) fields around them.
+#[derive(Debug, Clone, Default)]
+pub struct FieldWriter {
+    body: String,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Checksum of a byte buffer: the sum of its bytes modulo 256.
+#[must_use]
+pub fn checksum(bytes: &[u8]) -> u8 {
+    bytes.iter().fold(0_u8, |sum, &b| sum.wrapping_add(b))
+}
+
+impl<'a> RawMessage<'a> {
+    /// Split a message into its fields and validate its framing:
+    /// - the first three fields are BeginString(8), BodyLength(9), and MsgType(35),
+    /// - the last field is CheckSum(10),
+    /// - BodyLength(9) is the number of bytes between the end of the
+    ///   BodyLength(9) field and the start of the CheckSum(10) field,
+    /// - CheckSum(10) is the checksum of every byte before it.
+    ///
+    /// # Errors
+    /// - `FixError::Malformed` if the message is not a sequence of `tag=value<SOH>` fields,
+    ///   or the framing fields are missing or out of place.
+    /// - `FixError::BodyLength` or `FixError::Checksum` if validation fails.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, FixError> {
+        let mut fields = Vec::new();
+        let mut starts = Vec::new();
+        let mut pos = 0;
+
+        while pos < bytes.len() {
+            let end = bytes[pos..]
+                .iter()
+                .position(|&b| b == SOH)
+                .map(|offset| pos + offset)
+                .ok_or_else(|| FixError::Malformed("field not terminated by SOH".to_string()))?;
+
+            fields.push(Self::parse_field(&bytes[pos..end])?);
+            starts.push(pos);
+            pos = end + 1;
+        }
+
+        let n = fields.len();
+
+        if n < 4 || fields[0].0 != 8 || fields[1].0 != 9 || fields[2].0 != 35 {
+            return Err(FixError::Malformed(
+                "message must start with the 8, 9, and 35 fields".to_string(),
+            ));
+        }
+        if fields[n - 1].0 != 10 {
+            return Err(FixError::Malformed(
+                "message must end with the 10 field".to_string(),
+            ));
+        }
+
+        let trailer_start = starts[n - 1];
+
+        let declared = parse_value::<usize>(9, fields[1].1)?;
+        let actual = trailer_start - starts[2];
+        if declared != actual {
+            return Err(FixError::BodyLength { declared, actual });
+        }
+
+        let checksum_value = fields[n - 1].1;
+        if checksum_value.len() != 3 {
+            return Err(FixError::InvalidValue {
+                tag: 10,
+                value: checksum_value.to_string(),
+            });
+        }
+        let declared = parse_value::<u8>(10, checksum_value)?;
+        let computed = checksum(&bytes[..trailer_start]);
+        if declared != computed {
+            return Err(FixError::Checksum { declared, computed });
+        }
+
+        Ok(Self { fields })
+    }
+
+    fn parse_field(field: &'a [u8]) -> Result<(u32, &'a str), FixError> {
+        let text = std::str::from_utf8(field)
+            .map_err(|_| FixError::Malformed("field is not valid UTF-8".to_string()))?;
+
+        let (tag, value) = text
+            .split_once('=')
+            .ok_or_else(|| FixError::Malformed(format!("field '{text}' has no '='")))?;
+
+        if tag.is_empty() || !tag.bytes().all(|b| b.is_ascii_digit()) || value.is_empty() {
+            return Err(FixError::Malformed(format!("invalid field '{text}'")));
+        }
+
+        let tag = tag
+            .parse()
+            .map_err(|_| FixError::Malformed(format!("invalid tag in '{text}'")))?;
+
+        Ok((tag, value))
+    }
+
+    /// All fields, in message order (including the 8, 9, and 10 fields).
+    #[must_use]
+    pub fn fields(&self) -> &[(u32, &'a str)] {
+        &self.fields
+    }
+
+    /// MsgType(35) of the message.
+    #[must_use]
+    pub fn msg_type(&self) -> &'a str {
+        self.fields[2].1
+    }
+
+    /// Value of the first occurrence of a field.
+    #[must_use]
+    pub fn get(&self, tag: u32) -> Option<&'a str> {
+        self.fields.iter().find(|(t, _)| *t == tag).map(|(_, v)| *v)
+    }
+
+    /// Value of a required field.
+    ///
+    /// # Errors
+    /// `FixError::MissingField` if the field is absent.
+    pub fn required(&self, tag: u32) -> Result<&'a str, FixError> {
+        self.get(tag).ok_or(FixError::MissingField(tag))
+    }
+
+    /// Parsed value of a required field.
+    ///
+    /// # Errors
+    /// `FixError::MissingField` or `FixError::InvalidValue`.
+    pub fn parse_required<T: FromStr>(&self, tag: u32) -> Result<T, FixError> {
+        parse_value(tag, self.required(tag)?)
+    }
+
+    /// Parsed value of an optional field.
+    ///
+    /// # Errors
+    /// `FixError::InvalidValue` if the field is present but cannot be parsed.
+    pub fn parse_optional<T: FromStr>(&self, tag: u32) -> Result<Option<T>, FixError> {
+        self.get(tag)
+            .map(|value| parse_value(tag, value))
+            .transpose()
+    }
+
+    /// Value of an optional boolean (`Y`/`N`) field.
+    ///
+    /// # Errors
+    /// `FixError::InvalidValue` if the field is neither `Y` nor `N`.
+    pub fn optional_bool(&self, tag: u32) -> Result<Option<bool>, FixError> {
+        self.get(tag)
+            .map(|value| match value {
+                "Y" => Ok(true),
+                "N" => Ok(false),
+                _ => Err(FixError::InvalidValue {
+                    tag,
+                    value: value.to_string(),
+                }),
+            })
+            .transpose()
+    }
+}
+
+fn parse_value<T: FromStr>(tag: u32, value: &str) -> Result<T, FixError> {
+    value.parse().map_err(|_| FixError::InvalidValue {
+        tag,
+        value: value.to_string(),
+    })
+}
+
+impl FieldWriter {
+    /// Create a new, empty, `FieldWriter`.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a field.
+    pub fn field<T: Display>(&mut self, tag: u32, value: T) -> &mut Self {
+        // Writing to a `String` cannot fail.
+        let _ = write!(self.body, "{tag}={value}\u{1}");
+        self
+    }
+
+    /// Append a field if it is present.
+    pub fn optional<T: Display>(&mut self, tag: u32, value: Option<T>) -> &mut Self {
+        if let Some(value) = value {
+            self.field(tag, value);
+        }
+        self
+    }
+
+    /// Append a boolean (`Y`/`N`) field if it is present.
+    pub fn optional_bool(&mut self, tag: u32, value: Option<bool>) -> &mut Self {
+        self.optional(tag, value.map(|v| if v { 'Y' } else { 'N' }))
+    }
+
+    /// Frame the body into a complete message.
+    #[must_use]
+    pub fn finish(&self, begin_string: &str) -> Vec<u8> {
+        let mut message = format!("8={begin_string}\u{1}9={}\u{1}", self.body.len()).into_bytes();
+        message.extend_from_slice(self.body.as_bytes());
+
+        let trailer = format!("10={:03}\u{1}", checksum(&message));
+        message.extend_from_slice(trailer.as_bytes());
+
+        message
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_codec {
+    use super::*;
+
+    // FIX messages are usually written with '|' in place of SOH.
+    fn fix(message: &str) -> Vec<u8> {
+        message.replace('|', "\u{1}").into_bytes()
+    }
+
+    const HEARTBEAT: &str =
+        "8=FIX.4.4|9=55|35=0|49=BROKER|56=CLIENT|34=2|52=20240102-14:30:30.000|10=065|";
+
+    #[test]
+    fn test_parse_fields() -> Result<(), FixError> {
+        let bytes = fix(HEARTBEAT);
+        let raw = RawMessage::parse(&bytes)?;
+
+        assert_eq!(raw.msg_type(), "0");
+        assert_eq!(raw.get(49), Some("BROKER"));
+        assert_eq!(raw.parse_required::<u64>(34)?, 2);
+        assert_eq!(raw.get(112), None);
+        assert_eq!(raw.fields().len(), 8);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_writer_framing() {
+        let mut writer = FieldWriter::new();
+        writer
+            .field(35, 0)
+            .field(49, "BROKER")
+            .field(56, "CLIENT")
+            .field(34, 2)
+            .field(52, "20240102-14:30:30.000");
+
+        assert_eq!(writer.finish(BEGIN_STRING), fix(HEARTBEAT));
+    }
+
+    #[test]
+    fn test_checksum_rejection() {
+        // Corrupt one byte of the body: BROKER -> BROKEX.
+        let bytes = fix(&HEARTBEAT.replace("BROKER", "BROKEX"));
+
+        assert_eq!(
+            RawMessage::parse(&bytes),
+            Err(FixError::Checksum {
+                declared: 65,
+                computed: 71
+            })
+        );
+    }
+
+    #[test]
+    fn test_body_length_rejection() {
+        let bytes = fix(&HEARTBEAT
+            .replace("BROKER", "BROKERS")
+            .replace("10=065", "10=148"));
+
+        assert!(matches!(
+            RawMessage::parse(&bytes),
+            Err(FixError::BodyLength {
+                declared: 55,
+                actual: 56
+            })
+        ));
+    }
+
+    #[test]
+    fn test_malformed_messages() {
+        assert!(RawMessage::parse(b"").is_err());
+        assert!(RawMessage::parse(&fix("8=FIX.4.4|9=5|35=0")).is_err());
+        assert!(RawMessage::parse(&fix("8=FIX.4.4|9=5|35=0|garbage|10=000|")).is_err());
+        assert!(RawMessage::parse(&fix("9=5|8=FIX.4.4|35=0|10=000|")).is_err());
+    }
+}
@@ -0,0 +1,70 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use thiserror::Error;
+
+/// Error type for FIX parsing, generation, and session handling.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum FixError {
+    /// The message is not a sequence of `tag=value<SOH>` fields.
+    #[error("Malformed FIX message: {0}")]
+    Malformed(String),
+
+    /// A required field is absent.
+    #[error("Missing required field {0}")]
+    MissingField(u32),
+
+    /// A field has a value that cannot be parsed.
+    #[error("Invalid value '{value}' for field {tag}")]
+    InvalidValue {
+        /// Field tag.
+        tag: u32,
+        /// Raw field value.
+        value: String,
+    },
+
+    /// The BodyLength(9) field does not match the length of the body.
+    #[error("Body length mismatch: declared {declared}, actual {actual}")]
+    BodyLength {
+        /// Declared body length.
+        declared: usize,
+        /// Actual body length.
+        actual: usize,
+    },
+
+    /// The CheckSum(10) field does not match the checksum of the message.
+    #[error("Checksum mismatch: declared {declared:03}, computed {computed:03}")]
+    Checksum {
+        /// Declared checksum.
+        declared: u8,
+        /// Computed checksum.
+        computed: u8,
+    },
+
+    /// The MsgType(35) is not one of the supported messages.
+    #[error("Unsupported message type '{0}'")]
+    UnsupportedMsgType(String),
+
+    /// A value of the crate's order types has no FIX equivalent.
+    #[error("Unsupported value: {0}")]
+    Unsupported(String),
+
+    /// The counterparty's MsgSeqNum(34) is lower than expected.
+    #[error("MsgSeqNum too low: expected {expected}, received {received}")]
+    SequenceTooLow {
+        /// Expected sequence number.
+        expected: u64,
+        /// Received sequence number.
+        received: u64,
+    },
+
+    /// The message is not allowed in the current session state.
+    #[error("Invalid session state: {0}")]
+    InvalidState(String),
+}
@@ -0,0 +1,272 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Enumerated FIX 4.4 field values, and conversions from the crate's order types.
+
+use super::FixError;
+use crate::trading::{
+    order_lifespan::OrderTimeInForce, order_side::OrderSide, order_type::OrderType,
+};
+use std::fmt;
+use time::{OffsetDateTime, UtcOffset};
+
+/// Defines an enumerated FIX field: the enum, its tag,
+/// and the conversions from and to the FIX value.
+macro_rules! fix_enum {
+    (
+        $(#[$meta:meta])*
+        $name:ident = $tag:literal {
+            $($(#[$variant_meta:meta])* $variant:ident = $value:literal,)+
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)+
+        }
+
+        impl $name {
+            /// Tag of the field.
+            pub const TAG: u32 = $tag;
+
+            /// Parse the FIX value of the field.
+            ///
+            /// # Errors
+            /// `FixError::InvalidValue` if the value is not valid for the field.
+            pub fn from_fix(value: &str) -> Result<Self, FixError> {
+                match value {
+                    $($value => Ok(Self::$variant),)+
+                    _ => Err(FixError::InvalidValue {
+                        tag: $tag,
+                        value: value.to_string(),
+                    }),
+                }
+            }
+
+            /// FIX value of the field.
+            #[must_use]
+            pub fn as_fix(&self) -> &'static str {
+                match self {
+                    $(Self::$variant => $value,)+
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_fix())
+            }
+        }
+    };
+}
+
+fix_enum! {
+    /// Side(54).
+    Side = 54 {
+        /// Buy.
+        Buy = "1",
+        /// Sell.
+        Sell = "2",
+        /// Buy minus.
+        BuyMinus = "3",
+        /// Sell plus.
+        SellPlus = "4",
+        /// Sell short.
+        SellShort = "5",
+        /// Sell short exempt.
+        SellShortExempt = "6",
+    }
+}
+
+fix_enum! {
+    /// OrdType(40).
+    OrdType = 40 {
+        /// Market order.
+        Market = "1",
+        /// Limit order.
+        Limit = "2",
+        /// Stop order.
+        Stop = "3",
+        /// Stop-limit order.
+        StopLimit = "4",
+    }
+}
+
+fix_enum! {
+    /// TimeInForce(59).
+    TimeInForce = 59 {
+        /// Day (the default when the field is absent).
+        Day = "0",
+        /// Good till cancel.
+        GoodTillCancel = "1",
+        /// At the opening.
+        AtTheOpening = "2",
+        /// Immediate or cancel.
+        ImmediateOrCancel = "3",
+        /// Fill or kill.
+        FillOrKill = "4",
+        /// Good till crossing.
+        GoodTillCrossing = "5",
+        /// Good till date.
+        GoodTillDate = "6",
+        /// At the close.
+        AtTheClose = "7",
+    }
+}
+
+fix_enum! {
+    /// ExecType(150).
+    ExecType = 150 {
+        /// New.
+        New = "0",
+        /// Done for day.
+        DoneForDay = "3",
+        /// Canceled.
+        Canceled = "4",
+        /// Replaced.
+        Replaced = "5",
+        /// Pending cancel.
+        PendingCancel = "6",
+        /// Stopped.
+        Stopped = "7",
+        /// Rejected.
+        Rejected = "8",
+        /// Suspended.
+        Suspended = "9",
+        /// Pending new.
+        PendingNew = "A",
+        /// Calculated.
+        Calculated = "B",
+        /// Expired.
+        Expired = "C",
+        /// Restated.
+        Restated = "D",
+        /// Pending replace.
+        PendingReplace = "E",
+        /// Trade (partial fill or fill).
+        Trade = "F",
+        /// Trade correct.
+        TradeCorrect = "G",
+        /// Trade cancel.
+        TradeCancel = "H",
+        /// Order status.
+        OrderStatus = "I",
+    }
+}
+
+fix_enum! {
+    /// OrdStatus(39).
+    OrdStatus = 39 {
+        /// New.
+        New = "0",
+        /// Partially filled.
+        PartiallyFilled = "1",
+        /// Filled.
+        Filled = "2",
+        /// Done for day.
+        DoneForDay = "3",
+        /// Canceled.
+        Canceled = "4",
+        /// Pending cancel.
+        PendingCancel = "6",
+        /// Stopped.
+        Stopped = "7",
+        /// Rejected.
+        Rejected = "8",
+        /// Suspended.
+        Suspended = "9",
+        /// Pending new.
+        PendingNew = "A",
+        /// Calculated.
+        Calculated = "B",
+        /// Expired.
+        Expired = "C",
+        /// Accepted for bidding.
+        AcceptedForBidding = "D",
+        /// Pending replace.
+        PendingReplace = "E",
+    }
+}
+
+impl From<OrderSide> for Side {
+    fn from(side: OrderSide) -> Self {
+        match side {
+            OrderSide::BID => Side::Buy,
+            OrderSide::ASK => Side::Sell,
+        }
+    }
+}
+
+impl From<Side> for OrderSide {
+    fn from(side: Side) -> Self {
+        match side {
+            Side::Buy | Side::BuyMinus => OrderSide::BID,
+            Side::Sell | Side::SellPlus | Side::SellShort | Side::SellShortExempt => OrderSide::ASK,
+        }
+    }
+}
+
+impl TryFrom<OrderType> for OrdType {
+    type Error = FixError;
+
+    fn try_from(order_type: OrderType) -> Result<Self, Self::Error> {
+        match order_type {
+            OrderType::Market => Ok(OrdType::Market),
+            OrderType::Limit => Ok(OrdType::Limit),
+            OrderType::Stop => Ok(OrdType::Stop),
+            OrderType::StopLimit => Ok(OrdType::StopLimit),
+            OrderType::TrailingStop | OrderType::TrailingStopLimit => Err(FixError::Unsupported(
+                format!("order type {order_type} (use peg instructions)"),
+            )),
+        }
+    }
+}
+
+impl From<OrdType> for OrderType {
+    fn from(ord_type: OrdType) -> Self {
+        match ord_type {
+            OrdType::Market => OrderType::Market,
+            OrdType::Limit => OrderType::Limit,
+            OrdType::Stop => OrderType::Stop,
+            OrdType::StopLimit => OrderType::StopLimit,
+        }
+    }
+}
+
+impl TryFrom<OrderTimeInForce> for TimeInForce {
+    type Error = FixError;
+
+    fn try_from(time_in_force: OrderTimeInForce) -> Result<Self, Self::Error> {
+        match time_in_force {
+            OrderTimeInForce::GoodTillCancelled => Ok(TimeInForce::GoodTillCancel),
+            OrderTimeInForce::ImmediateOrCancel => Ok(TimeInForce::ImmediateOrCancel),
+            OrderTimeInForce::FillOrKill => Ok(TimeInForce::FillOrKill),
+            OrderTimeInForce::AllOrNone => Err(FixError::Unsupported(
+                "time in force AON (use ExecInst(18) = G)".to_string(),
+            )),
+        }
+    }
+}
+
+/// Format a timestamp as a FIX UTCTimestamp (`YYYYMMDD-HH:MM:SS.sss`).
+#[must_use]
+pub fn utc_timestamp(timestamp: OffsetDateTime) -> String {
+    let t = timestamp.to_offset(UtcOffset::UTC);
+
+    format!(
+        "{:04}{:02}{:02}-{:02}:{:02}:{:02}.{:03}",
+        t.year(),
+        u8::from(t.month()),
+        t.day(),
+        t.hour(),
+        t.minute(),
+        t.second(),
+        t.millisecond()
+    )
+}
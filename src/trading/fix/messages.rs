@@ -0,0 +1,868 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Typed FIX 4.4 messages.
+//!
+//! String fields are `Cow`s: parsed messages borrow from the input buffer,
+//! while messages generated from the crate's [`Order`] own their strings.
+//!
+//! Fields are serialized in a fixed order: the standard header
+//! (8, 9, 35, 49, 56, 34, 43, 52), then the body fields in the order
+//! of the struct definitions, then the CheckSum(10).
+
+use super::{
+    utc_timestamp, ExecType, FieldWriter, FixError, OrdStatus, OrdType, RawMessage, Side,
+    TimeInForce,
+};
+use crate::trading::{order::Order, order::OrderID, order_type::OrderType};
+use std::borrow::Cow;
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Body of a FIX message of a given MsgType(35).
+pub trait FixBody<'a>: Sized {
+    /// MsgType(35) of the message.
+    const MSG_TYPE: &'static str;
+
+    /// Decode the body from a validated message.
+    ///
+    /// # Errors
+    /// `FixError::MissingField` or `FixError::InvalidValue`.
+    fn decode(raw: &RawMessage<'a>) -> Result<Self, FixError>;
+
+    /// Write the body fields (after the standard header).
+    fn encode_body(&self, writer: &mut FieldWriter);
+}
+
+/// Standard message header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header<'a> {
+    /// BeginString(8).
+    pub begin_string: Cow<'a, str>,
+    /// SenderCompID(49).
+    pub sender_comp_id: Cow<'a, str>,
+    /// TargetCompID(56).
+    pub target_comp_id: Cow<'a, str>,
+    /// MsgSeqNum(34).
+    pub msg_seq_num: u64,
+    /// PossDupFlag(43).
+    pub poss_dup_flag: Option<bool>,
+    /// SendingTime(52), as a UTCTimestamp.
+    pub sending_time: Cow<'a, str>,
+}
+
+/// A complete FIX message: header and typed body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixMessage<'a> {
+    /// Standard header.
+    pub header: Header<'a>,
+    /// Message body.
+    pub body: Message<'a>,
+}
+
+/// Supported FIX messages.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message<'a> {
+    /// Heartbeat (0).
+    Heartbeat(Heartbeat<'a>),
+    /// TestRequest (1).
+    TestRequest(TestRequest<'a>),
+    /// ResendRequest (2).
+    ResendRequest(ResendRequest),
+    /// Reject (3).
+    Reject(Reject<'a>),
+    /// SequenceReset (4).
+    SequenceReset(SequenceReset),
+    /// Logout (5).
+    Logout(Logout<'a>),
+    /// Logon (A).
+    Logon(Logon),
+    /// NewOrderSingle (D).
+    NewOrderSingle(NewOrderSingle<'a>),
+    /// OrderCancelRequest (F).
+    OrderCancelRequest(OrderCancelRequest<'a>),
+    /// OrderCancelReplaceRequest (G).
+    OrderCancelReplaceRequest(OrderCancelReplaceRequest<'a>),
+    /// ExecutionReport (8).
+    ExecutionReport(ExecutionReport<'a>),
+}
+
+/// Heartbeat (0).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heartbeat<'a> {
+    /// TestReqID(112), when answering a TestRequest.
+    pub test_req_id: Option<Cow<'a, str>>,
+}
+
+/// TestRequest (1).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TestRequest<'a> {
+    /// TestReqID(112).
+    pub test_req_id: Cow<'a, str>,
+}
+
+/// ResendRequest (2).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResendRequest {
+    /// BeginSeqNo(7).
+    pub begin_seq_no: u64,
+    /// EndSeqNo(16), zero for "all messages after `begin_seq_no`".
+    pub end_seq_no: u64,
+}
+
+/// Session-level Reject (3).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reject<'a> {
+    /// RefSeqNum(45): sequence number of the rejected message.
+    pub ref_seq_num: u64,
+    /// RefTagID(371): tag of the offending field.
+    pub ref_tag_id: Option<u32>,
+    /// RefMsgType(372): MsgType of the rejected message.
+    pub ref_msg_type: Option<Cow<'a, str>>,
+    /// SessionRejectReason(373).
+    pub session_reject_reason: Option<u32>,
+    /// Text(58).
+    pub text: Option<Cow<'a, str>>,
+}
+
+/// SequenceReset (4).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SequenceReset {
+    /// GapFillFlag(123).
+    pub gap_fill_flag: Option<bool>,
+    /// NewSeqNo(36).
+    pub new_seq_no: u64,
+}
+
+/// Logout (5).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Logout<'a> {
+    /// Text(58).
+    pub text: Option<Cow<'a, str>>,
+}
+
+/// Logon (A).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Logon {
+    /// EncryptMethod(98), zero for none.
+    pub encrypt_method: u32,
+    /// HeartBtInt(108), in seconds.
+    pub heart_bt_int: u64,
+    /// ResetSeqNumFlag(141).
+    pub reset_seq_num_flag: Option<bool>,
+}
+
+/// NewOrderSingle (D).
+#[derive(Debug, Clone, PartialEq)]
+pub struct NewOrderSingle<'a> {
+    /// ClOrdID(11).
+    pub cl_ord_id: Cow<'a, str>,
+    /// Symbol(55).
+    pub symbol: Cow<'a, str>,
+    /// Side(54).
+    pub side: Side,
+    /// TransactTime(60).
+    pub transact_time: Cow<'a, str>,
+    /// OrderQty(38).
+    pub order_qty: u64,
+    /// OrdType(40).
+    pub ord_type: OrdType,
+    /// Price(44), for limit and stop-limit orders.
+    pub price: Option<f64>,
+    /// StopPx(99), for stop and stop-limit orders.
+    pub stop_px: Option<f64>,
+    /// TimeInForce(59).
+    pub time_in_force: Option<TimeInForce>,
+}
+
+/// OrderCancelRequest (F).
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderCancelRequest<'a> {
+    /// OrigClOrdID(41): ClOrdID of the order to cancel.
+    pub orig_cl_ord_id: Cow<'a, str>,
+    /// ClOrdID(11) of the cancel request.
+    pub cl_ord_id: Cow<'a, str>,
+    /// Symbol(55).
+    pub symbol: Cow<'a, str>,
+    /// Side(54).
+    pub side: Side,
+    /// TransactTime(60).
+    pub transact_time: Cow<'a, str>,
+    /// OrderQty(38).
+    pub order_qty: Option<u64>,
+}
+
+/// OrderCancelReplaceRequest (G).
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderCancelReplaceRequest<'a> {
+    /// OrigClOrdID(41): ClOrdID of the order to replace.
+    pub orig_cl_ord_id: Cow<'a, str>,
+    /// ClOrdID(11) of the replacement order.
+    pub cl_ord_id: Cow<'a, str>,
+    /// Symbol(55).
+    pub symbol: Cow<'a, str>,
+    /// Side(54).
+    pub side: Side,
+    /// TransactTime(60).
+    pub transact_time: Cow<'a, str>,
+    /// OrderQty(38).
+    pub order_qty: u64,
+    /// OrdType(40).
+    pub ord_type: OrdType,
+    /// Price(44).
+    pub price: Option<f64>,
+    /// StopPx(99).
+    pub stop_px: Option<f64>,
+    /// TimeInForce(59).
+    pub time_in_force: Option<TimeInForce>,
+}
+
+/// ExecutionReport (8).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutionReport<'a> {
+    /// OrderID(37), assigned by the venue.
+    pub order_id: Cow<'a, str>,
+    /// ClOrdID(11).
+    pub cl_ord_id: Option<Cow<'a, str>>,
+    /// OrigClOrdID(41).
+    pub orig_cl_ord_id: Option<Cow<'a, str>>,
+    /// ExecID(17).
+    pub exec_id: Cow<'a, str>,
+    /// ExecType(150).
+    pub exec_type: ExecType,
+    /// OrdStatus(39).
+    pub ord_status: OrdStatus,
+    /// Symbol(55).
+    pub symbol: Cow<'a, str>,
+    /// Side(54).
+    pub side: Side,
+    /// OrderQty(38).
+    pub order_qty: Option<u64>,
+    /// Price(44).
+    pub price: Option<f64>,
+    /// LastQty(32).
+    pub last_qty: Option<u64>,
+    /// LastPx(31).
+    pub last_px: Option<f64>,
+    /// LeavesQty(151).
+    pub leaves_qty: u64,
+    /// CumQty(14).
+    pub cum_qty: u64,
+    /// AvgPx(6).
+    pub avg_px: f64,
+    /// TransactTime(60).
+    pub transact_time: Option<Cow<'a, str>>,
+    /// Text(58).
+    pub text: Option<Cow<'a, str>>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+fn text<'a>(raw: &RawMessage<'a>, tag: u32) -> Result<Cow<'a, str>, FixError> {
+    raw.required(tag).map(Cow::Borrowed)
+}
+
+fn optional_text<'a>(raw: &RawMessage<'a>, tag: u32) -> Option<Cow<'a, str>> {
+    raw.get(tag).map(Cow::Borrowed)
+}
+
+fn side(raw: &RawMessage<'_>) -> Result<Side, FixError> {
+    Side::from_fix(raw.required(Side::TAG)?)
+}
+
+fn ord_type(raw: &RawMessage<'_>) -> Result<OrdType, FixError> {
+    OrdType::from_fix(raw.required(OrdType::TAG)?)
+}
+
+fn time_in_force(raw: &RawMessage<'_>) -> Result<Option<TimeInForce>, FixError> {
+    raw.get(TimeInForce::TAG)
+        .map(TimeInForce::from_fix)
+        .transpose()
+}
+
+/// Price(44) and StopPx(99) of an order, according to its type.
+fn order_prices(order: &Order) -> (Option<f64>, Option<f64>) {
+    match order.order_type {
+        OrderType::Limit => (Some(order.price), None),
+        OrderType::Stop => (None, Some(order.stop_price)),
+        OrderType::StopLimit => (Some(order.price), Some(order.stop_price)),
+        _ => (None, None),
+    }
+}
+
+impl<'a> Header<'a> {
+    fn decode(raw: &RawMessage<'a>) -> Result<Self, FixError> {
+        Ok(Self {
+            begin_string: text(raw, 8)?,
+            sender_comp_id: text(raw, 49)?,
+            target_comp_id: text(raw, 56)?,
+            msg_seq_num: raw.parse_required(34)?,
+            poss_dup_flag: raw.optional_bool(43)?,
+            sending_time: text(raw, 52)?,
+        })
+    }
+}
+
+impl<'a> FixMessage<'a> {
+    /// Parse and validate a message.
+    ///
+    /// # Errors
+    /// - Any framing error of [`RawMessage::parse`], including checksum
+    ///   and body length mismatches.
+    /// - `FixError::UnsupportedMsgType` for other message types.
+    /// - `FixError::MissingField` or `FixError::InvalidValue` for invalid fields.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, FixError> {
+        let raw = RawMessage::parse(bytes)?;
+
+        Ok(Self {
+            header: Header::decode(&raw)?,
+            body: Message::decode(&raw)?,
+        })
+    }
+
+    /// Serialize the message, computing its BodyLength(9) and CheckSum(10).
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut writer = FieldWriter::new();
+
+        writer
+            .field(35, self.body.msg_type())
+            .field(49, &self.header.sender_comp_id)
+            .field(56, &self.header.target_comp_id)
+            .field(34, self.header.msg_seq_num)
+            .optional_bool(43, self.header.poss_dup_flag)
+            .field(52, &self.header.sending_time);
+
+        self.body.encode_body(&mut writer);
+
+        writer.finish(&self.header.begin_string)
+    }
+}
+
+impl<'a> Message<'a> {
+    /// MsgType(35) of the message.
+    #[must_use]
+    pub fn msg_type(&self) -> &'static str {
+        match self {
+            Message::Heartbeat(_) => Heartbeat::MSG_TYPE,
+            Message::TestRequest(_) => TestRequest::MSG_TYPE,
+            Message::ResendRequest(_) => ResendRequest::MSG_TYPE,
+            Message::Reject(_) => Reject::MSG_TYPE,
+            Message::SequenceReset(_) => SequenceReset::MSG_TYPE,
+            Message::Logout(_) => Logout::MSG_TYPE,
+            Message::Logon(_) => Logon::MSG_TYPE,
+            Message::NewOrderSingle(_) => NewOrderSingle::MSG_TYPE,
+            Message::OrderCancelRequest(_) => OrderCancelRequest::MSG_TYPE,
+            Message::OrderCancelReplaceRequest(_) => OrderCancelReplaceRequest::MSG_TYPE,
+            Message::ExecutionReport(_) => ExecutionReport::MSG_TYPE,
+        }
+    }
+
+    /// Whether the message is a session-level (administrative) message.
+    #[must_use]
+    pub fn is_admin(&self) -> bool {
+        matches!(
+            self,
+            Message::Heartbeat(_)
+                | Message::TestRequest(_)
+                | Message::ResendRequest(_)
+                | Message::Reject(_)
+                | Message::SequenceReset(_)
+                | Message::Logout(_)
+                | Message::Logon(_)
+        )
+    }
+
+    fn decode(raw: &RawMessage<'a>) -> Result<Self, FixError> {
+        Ok(match raw.msg_type() {
+            Heartbeat::MSG_TYPE => Message::Heartbeat(Heartbeat::decode(raw)?),
+            TestRequest::MSG_TYPE => Message::TestRequest(TestRequest::decode(raw)?),
+            ResendRequest::MSG_TYPE => Message::ResendRequest(ResendRequest::decode(raw)?),
+            Reject::MSG_TYPE => Message::Reject(Reject::decode(raw)?),
+            SequenceReset::MSG_TYPE => Message::SequenceReset(SequenceReset::decode(raw)?),
+            Logout::MSG_TYPE => Message::Logout(Logout::decode(raw)?),
+            Logon::MSG_TYPE => Message::Logon(Logon::decode(raw)?),
+            NewOrderSingle::MSG_TYPE => Message::NewOrderSingle(NewOrderSingle::decode(raw)?),
+            OrderCancelRequest::MSG_TYPE => {
+                Message::OrderCancelRequest(OrderCancelRequest::decode(raw)?)
+            }
+            OrderCancelReplaceRequest::MSG_TYPE => {
+                Message::OrderCancelReplaceRequest(OrderCancelReplaceRequest::decode(raw)?)
+            }
+            ExecutionReport::MSG_TYPE => Message::ExecutionReport(ExecutionReport::decode(raw)?),
+            other => return Err(FixError::UnsupportedMsgType(other.to_string())),
+        })
+    }
+
+    fn encode_body(&self, writer: &mut FieldWriter) {
+        match self {
+            Message::Heartbeat(body) => body.encode_body(writer),
+            Message::TestRequest(body) => body.encode_body(writer),
+            Message::ResendRequest(body) => body.encode_body(writer),
+            Message::Reject(body) => body.encode_body(writer),
+            Message::SequenceReset(body) => body.encode_body(writer),
+            Message::Logout(body) => body.encode_body(writer),
+            Message::Logon(body) => body.encode_body(writer),
+            Message::NewOrderSingle(body) => body.encode_body(writer),
+            Message::OrderCancelRequest(body) => body.encode_body(writer),
+            Message::OrderCancelReplaceRequest(body) => body.encode_body(writer),
+            Message::ExecutionReport(body) => body.encode_body(writer),
+        }
+    }
+}
+
+impl<'a> FixBody<'a> for Heartbeat<'a> {
+    const MSG_TYPE: &'static str = "0";
+
+    fn decode(raw: &RawMessage<'a>) -> Result<Self, FixError> {
+        Ok(Self {
+            test_req_id: optional_text(raw, 112),
+        })
+    }
+
+    fn encode_body(&self, writer: &mut FieldWriter) {
+        writer.optional(112, self.test_req_id.as_ref());
+    }
+}
+
+impl<'a> FixBody<'a> for TestRequest<'a> {
+    const MSG_TYPE: &'static str = "1";
+
+    fn decode(raw: &RawMessage<'a>) -> Result<Self, FixError> {
+        Ok(Self {
+            test_req_id: text(raw, 112)?,
+        })
+    }
+
+    fn encode_body(&self, writer: &mut FieldWriter) {
+        writer.field(112, &self.test_req_id);
+    }
+}
+
+impl<'a> FixBody<'a> for ResendRequest {
+    const MSG_TYPE: &'static str = "2";
+
+    fn decode(raw: &RawMessage<'a>) -> Result<Self, FixError> {
+        Ok(Self {
+            begin_seq_no: raw.parse_required(7)?,
+            end_seq_no: raw.parse_required(16)?,
+        })
+    }
+
+    fn encode_body(&self, writer: &mut FieldWriter) {
+        writer
+            .field(7, self.begin_seq_no)
+            .field(16, self.end_seq_no);
+    }
+}
+
+impl<'a> FixBody<'a> for Reject<'a> {
+    const MSG_TYPE: &'static str = "3";
+
+    fn decode(raw: &RawMessage<'a>) -> Result<Self, FixError> {
+        Ok(Self {
+            ref_seq_num: raw.parse_required(45)?,
+            ref_tag_id: raw.parse_optional(371)?,
+            ref_msg_type: optional_text(raw, 372),
+            session_reject_reason: raw.parse_optional(373)?,
+            text: optional_text(raw, 58),
+        })
+    }
+
+    fn encode_body(&self, writer: &mut FieldWriter) {
+        writer
+            .field(45, self.ref_seq_num)
+            .optional(371, self.ref_tag_id)
+            .optional(372, self.ref_msg_type.as_ref())
+            .optional(373, self.session_reject_reason)
+            .optional(58, self.text.as_ref());
+    }
+}
+
+impl<'a> FixBody<'a> for SequenceReset {
+    const MSG_TYPE: &'static str = "4";
+
+    fn decode(raw: &RawMessage<'a>) -> Result<Self, FixError> {
+        Ok(Self {
+            gap_fill_flag: raw.optional_bool(123)?,
+            new_seq_no: raw.parse_required(36)?,
+        })
+    }
+
+    fn encode_body(&self, writer: &mut FieldWriter) {
+        writer
+            .optional_bool(123, self.gap_fill_flag)
+            .field(36, self.new_seq_no);
+    }
+}
+
+impl<'a> FixBody<'a> for Logout<'a> {
+    const MSG_TYPE: &'static str = "5";
+
+    fn decode(raw: &RawMessage<'a>) -> Result<Self, FixError> {
+        Ok(Self {
+            text: optional_text(raw, 58),
+        })
+    }
+
+    fn encode_body(&self, writer: &mut FieldWriter) {
+        writer.optional(58, self.text.as_ref());
+    }
+}
+
+impl<'a> FixBody<'a> for Logon {
+    const MSG_TYPE: &'static str = "A";
+
+    fn decode(raw: &RawMessage<'a>) -> Result<Self, FixError> {
+        Ok(Self {
+            encrypt_method: raw.parse_required(98)?,
+            heart_bt_int: raw.parse_required(108)?,
+            reset_seq_num_flag: raw.optional_bool(141)?,
+        })
+    }
+
+    fn encode_body(&self, writer: &mut FieldWriter) {
+        writer
+            .field(98, self.encrypt_method)
+            .field(108, self.heart_bt_int)
+            .optional_bool(141, self.reset_seq_num_flag);
+    }
+}
+
+impl<'a> NewOrderSingle<'a> {
+    /// Create a `NewOrderSingle` for an order of the crate.
+    ///
+    /// The ClOrdID(11) is the order ID, and the TransactTime(60) the order timestamp.
+    ///
+    /// # Errors
+    /// `FixError::Unsupported` for trailing orders and all-or-none orders.
+    pub fn from_order(order: &Order, symbol: &'a str) -> Result<Self, FixError> {
+        let (price, stop_px) = order_prices(order);
+
+        Ok(Self {
+            cl_ord_id: Cow::Owned(order.id.to_string()),
+            symbol: Cow::Borrowed(symbol),
+            side: order.order_side.into(),
+            transact_time: Cow::Owned(utc_timestamp(order.timestamp)),
+            order_qty: order.quantity,
+            ord_type: order.order_type.try_into()?,
+            price,
+            stop_px,
+            time_in_force: Some(order.time_in_force.try_into()?),
+        })
+    }
+}
+
+impl<'a> FixBody<'a> for NewOrderSingle<'a> {
+    const MSG_TYPE: &'static str = "D";
+
+    fn decode(raw: &RawMessage<'a>) -> Result<Self, FixError> {
+        Ok(Self {
+            cl_ord_id: text(raw, 11)?,
+            symbol: text(raw, 55)?,
+            side: side(raw)?,
+            transact_time: text(raw, 60)?,
+            order_qty: raw.parse_required(38)?,
+            ord_type: ord_type(raw)?,
+            price: raw.parse_optional(44)?,
+            stop_px: raw.parse_optional(99)?,
+            time_in_force: time_in_force(raw)?,
+        })
+    }
+
+    fn encode_body(&self, writer: &mut FieldWriter) {
+        writer
+            .field(11, &self.cl_ord_id)
+            .field(55, &self.symbol)
+            .field(54, self.side)
+            .field(60, &self.transact_time)
+            .field(38, self.order_qty)
+            .field(40, self.ord_type)
+            .optional(44, self.price)
+            .optional(99, self.stop_px)
+            .optional(59, self.time_in_force);
+    }
+}
+
+impl<'a> OrderCancelRequest<'a> {
+    /// Create an `OrderCancelRequest` for an order of the crate.
+    #[must_use]
+    pub fn from_order(
+        order: &Order,
+        symbol: &'a str,
+        cl_ord_id: OrderID,
+        transact_time: OffsetDateTime,
+    ) -> Self {
+        Self {
+            orig_cl_ord_id: Cow::Owned(order.id.to_string()),
+            cl_ord_id: Cow::Owned(cl_ord_id.to_string()),
+            symbol: Cow::Borrowed(symbol),
+            side: order.order_side.into(),
+            transact_time: Cow::Owned(utc_timestamp(transact_time)),
+            order_qty: Some(order.quantity),
+        }
+    }
+}
+
+impl<'a> FixBody<'a> for OrderCancelRequest<'a> {
+    const MSG_TYPE: &'static str = "F";
+
+    fn decode(raw: &RawMessage<'a>) -> Result<Self, FixError> {
+        Ok(Self {
+            orig_cl_ord_id: text(raw, 41)?,
+            cl_ord_id: text(raw, 11)?,
+            symbol: text(raw, 55)?,
+            side: side(raw)?,
+            transact_time: text(raw, 60)?,
+            order_qty: raw.parse_optional(38)?,
+        })
+    }
+
+    fn encode_body(&self, writer: &mut FieldWriter) {
+        writer
+            .field(41, &self.orig_cl_ord_id)
+            .field(11, &self.cl_ord_id)
+            .field(55, &self.symbol)
+            .field(54, self.side)
+            .field(60, &self.transact_time)
+            .optional(38, self.order_qty);
+    }
+}
+
+impl<'a> OrderCancelReplaceRequest<'a> {
+    /// Create an `OrderCancelReplaceRequest` replacing `original` by `replacement`.
+    ///
+    /// # Errors
+    /// `FixError::Unsupported` for trailing orders and all-or-none orders.
+    pub fn from_orders(
+        original: &Order,
+        replacement: &Order,
+        symbol: &'a str,
+    ) -> Result<Self, FixError> {
+        let (price, stop_px) = order_prices(replacement);
+
+        Ok(Self {
+            orig_cl_ord_id: Cow::Owned(original.id.to_string()),
+            cl_ord_id: Cow::Owned(replacement.id.to_string()),
+            symbol: Cow::Borrowed(symbol),
+            side: replacement.order_side.into(),
+            transact_time: Cow::Owned(utc_timestamp(replacement.timestamp)),
+            order_qty: replacement.quantity,
+            ord_type: replacement.order_type.try_into()?,
+            price,
+            stop_px,
+            time_in_force: Some(replacement.time_in_force.try_into()?),
+        })
+    }
+}
+
+impl<'a> FixBody<'a> for OrderCancelReplaceRequest<'a> {
+    const MSG_TYPE: &'static str = "G";
+
+    fn decode(raw: &RawMessage<'a>) -> Result<Self, FixError> {
+        Ok(Self {
+            orig_cl_ord_id: text(raw, 41)?,
+            cl_ord_id: text(raw, 11)?,
+            symbol: text(raw, 55)?,
+            side: side(raw)?,
+            transact_time: text(raw, 60)?,
+            order_qty: raw.parse_required(38)?,
+            ord_type: ord_type(raw)?,
+            price: raw.parse_optional(44)?,
+            stop_px: raw.parse_optional(99)?,
+            time_in_force: time_in_force(raw)?,
+        })
+    }
+
+    fn encode_body(&self, writer: &mut FieldWriter) {
+        writer
+            .field(41, &self.orig_cl_ord_id)
+            .field(11, &self.cl_ord_id)
+            .field(55, &self.symbol)
+            .field(54, self.side)
+            .field(60, &self.transact_time)
+            .field(38, self.order_qty)
+            .field(40, self.ord_type)
+            .optional(44, self.price)
+            .optional(99, self.stop_px)
+            .optional(59, self.time_in_force);
+    }
+}
+
+impl<'a> FixBody<'a> for ExecutionReport<'a> {
+    const MSG_TYPE: &'static str = "8";
+
+    fn decode(raw: &RawMessage<'a>) -> Result<Self, FixError> {
+        Ok(Self {
+            order_id: text(raw, 37)?,
+            cl_ord_id: optional_text(raw, 11),
+            orig_cl_ord_id: optional_text(raw, 41),
+            exec_id: text(raw, 17)?,
+            exec_type: ExecType::from_fix(raw.required(ExecType::TAG)?)?,
+            ord_status: OrdStatus::from_fix(raw.required(OrdStatus::TAG)?)?,
+            symbol: text(raw, 55)?,
+            side: side(raw)?,
+            order_qty: raw.parse_optional(38)?,
+            price: raw.parse_optional(44)?,
+            last_qty: raw.parse_optional(32)?,
+            last_px: raw.parse_optional(31)?,
+            leaves_qty: raw.parse_required(151)?,
+            cum_qty: raw.parse_required(14)?,
+            avg_px: raw.parse_required(6)?,
+            transact_time: optional_text(raw, 60),
+            text: optional_text(raw, 58),
+        })
+    }
+
+    fn encode_body(&self, writer: &mut FieldWriter) {
+        writer
+            .field(37, &self.order_id)
+            .optional(11, self.cl_ord_id.as_ref())
+            .optional(41, self.orig_cl_ord_id.as_ref())
+            .field(17, &self.exec_id)
+            .field(150, self.exec_type)
+            .field(39, self.ord_status)
+            .field(55, &self.symbol)
+            .field(54, self.side)
+            .optional(38, self.order_qty)
+            .optional(44, self.price)
+            .optional(32, self.last_qty)
+            .optional(31, self.last_px)
+            .field(151, self.leaves_qty)
+            .field(14, self.cum_qty)
+            .field(6, self.avg_px)
+            .optional(60, self.transact_time.as_ref())
+            .optional(58, self.text.as_ref());
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_messages {
+    use super::*;
+    use crate::trading::{order_lifespan::OrderTimeInForce, order_side::OrderSide};
+    use time::macros::datetime;
+
+    fn fix(message: &str) -> Vec<u8> {
+        message.replace('|', "\u{1}").into_bytes()
+    }
+
+    const NEW_ORDER_SINGLE: &str = "8=FIX.4.4|9=131|35=D|49=CLIENT|56=BROKER|34=2|52=20240102-14:30:00.000|11=ORD1001|55=AAPL|54=1|60=20240102-14:30:00.000|38=100|40=2|44=185.25|59=0|10=219|";
+    const ORDER_CANCEL_REQUEST: &str = "8=FIX.4.4|9=122|35=F|49=CLIENT|56=BROKER|34=3|52=20240102-14:31:00.000|41=ORD1001|11=ORD1002|55=AAPL|54=1|60=20240102-14:31:00.000|38=100|10=162|";
+    const ORDER_CANCEL_REPLACE_REQUEST: &str = "8=FIX.4.4|9=141|35=G|49=CLIENT|56=BROKER|34=4|52=20240102-14:32:00.000|41=ORD1001|11=ORD1003|55=AAPL|54=1|60=20240102-14:32:00.000|38=200|40=2|44=185.5|59=0|10=000|";
+    const EXECUTION_REPORT: &str = "8=FIX.4.4|9=186|35=8|49=BROKER|56=CLIENT|34=5|52=20240102-14:30:01.250|37=B-778|11=ORD1001|17=E-1|150=F|39=1|55=AAPL|54=1|38=100|44=185.25|32=40|31=185.25|151=60|14=40|6=185.25|60=20240102-14:30:01.250|10=252|";
+    const REJECT: &str = "8=FIX.4.4|9=129|35=3|49=BROKER|56=CLIENT|34=6|52=20240102-14:30:02.000|45=7|371=44|372=D|373=5|58=Value is incorrect (out of range) for this tag|10=217|";
+
+    #[test]
+    fn test_round_trip_samples() -> Result<(), FixError> {
+        for sample in [
+            NEW_ORDER_SINGLE,
+            ORDER_CANCEL_REQUEST,
+            ORDER_CANCEL_REPLACE_REQUEST,
+            EXECUTION_REPORT,
+            REJECT,
+        ] {
+            let bytes = fix(sample);
+            let message = FixMessage::parse(&bytes)?;
+
+            assert_eq!(message.encode(), bytes, "{sample}");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_execution_report() -> Result<(), FixError> {
+        let bytes = fix(EXECUTION_REPORT);
+        let message = FixMessage::parse(&bytes)?;
+
+        assert_eq!(message.header.sender_comp_id, "BROKER");
+        assert_eq!(message.header.msg_seq_num, 5);
+
+        let Message::ExecutionReport(report) = message.body else {
+            panic!("Expected an ExecutionReport.");
+        };
+
+        // Zero-copy: the strings borrow from the input buffer.
+        assert!(matches!(report.order_id, Cow::Borrowed("B-778")));
+        assert_eq!(report.exec_type, ExecType::Trade);
+        assert_eq!(report.ord_status, OrdStatus::PartiallyFilled);
+        assert_eq!(report.last_qty, Some(40));
+        assert_eq!(report.last_px, Some(185.25));
+        assert_eq!(report.leaves_qty, 60);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_order_single_from_order() -> Result<(), FixError> {
+        let order = Order {
+            id: 1001,
+            symbol_id: 1,
+            order_type: OrderType::Limit,
+            order_side: OrderSide::BID,
+            price: 185.25,
+            stop_price: 0.0,
+            quantity: 100,
+            executed_quantity: 0,
+            leaves_quantity: 100,
+            time_in_force: OrderTimeInForce::GoodTillCancelled,
+            timestamp: datetime!(2024-01-02 14:30:00 UTC),
+        };
+
+        let message = FixMessage {
+            header: Header {
+                begin_string: Cow::Borrowed("FIX.4.4"),
+                sender_comp_id: Cow::Borrowed("CLIENT"),
+                target_comp_id: Cow::Borrowed("BROKER"),
+                msg_seq_num: 2,
+                poss_dup_flag: None,
+                sending_time: Cow::Borrowed("20240102-14:30:00.000"),
+            },
+            body: Message::NewOrderSingle(NewOrderSingle::from_order(&order, "AAPL")?),
+        };
+
+        assert_eq!(
+            message.encode(),
+            fix("8=FIX.4.4|9=128|35=D|49=CLIENT|56=BROKER|34=2|52=20240102-14:30:00.000|11=1001|55=AAPL|54=1|60=20240102-14:30:00.000|38=100|40=2|44=185.25|59=1|10=253|")
+        );
+
+        let trailing = Order {
+            order_type: OrderType::TrailingStop,
+            ..order
+        };
+        assert!(NewOrderSingle::from_order(&trailing, "AAPL").is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_unsupported_msg_type() {
+        let mut writer = FieldWriter::new();
+        writer
+            .field(35, "V")
+            .field(49, "CLIENT")
+            .field(56, "BROKER")
+            .field(34, 3)
+            .field(52, "20240102-14:30:00.000");
+
+        assert_eq!(
+            FixMessage::parse(&writer.finish("FIX.4.4")),
+            Err(FixError::UnsupportedMsgType("V".to_string()))
+        );
+    }
+}
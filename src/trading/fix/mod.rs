@@ -0,0 +1,42 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! FIX 4.4 messages for order entry.
+//!
+//! Only the subset needed to trade through a venue is supported:
+//! NewOrderSingle, OrderCancelRequest, OrderCancelReplaceRequest,
+//! ExecutionReport, Reject, and the session-level messages.
+//!
+//! ```ignore
+//! let mut session = FixSession::new("CLIENT", "BROKER", 30);
+//! let logon = session.logon(now)?;
+//! // ... write `logon`, read the counterparty's logon ...
+//! let order = NewOrderSingle::from_order(&order, "AAPL")?;
+//! let bytes = session.send(Message::NewOrderSingle(order), now)?;
+//! ```
+
+/// Low-level `tag=value` parsing and framing.
+pub mod codec;
+pub use codec::*;
+
+/// FIX error type.
+pub mod error;
+pub use error::*;
+
+/// Enumerated field values.
+pub mod fields;
+pub use fields::*;
+
+/// Typed messages.
+pub mod messages;
+pub use messages::*;
+
+/// Session state machine.
+pub mod session;
+pub use session::*;
@@ -0,0 +1,457 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Minimal FIX session state machine.
+//!
+//! The session assigns outgoing sequence numbers, checks incoming ones,
+//! and answers the session-level messages (logon, heartbeat, test request,
+//! resend request, logout). It does not do any I/O, and does not store sent
+//! messages: resend requests are answered with a SequenceReset (reset mode).
+
+use super::{
+    utc_timestamp, FixError, FixMessage, Header, Heartbeat, Logon, Logout, Message, ResendRequest,
+    SequenceReset, BEGIN_STRING,
+};
+use std::borrow::Cow;
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// State of a FIX session.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionState {
+    /// No logon sent or received.
+    Disconnected,
+    /// Logon sent, waiting for the counterparty's logon.
+    LogonSent,
+    /// Logged on: application messages may be exchanged.
+    Active,
+    /// Logout sent, waiting for the counterparty's logout.
+    LogoutSent,
+    /// Logged out.
+    Closed,
+}
+
+/// Result of processing an incoming message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionEvent {
+    /// Whether the message should be passed on to the application.
+    /// Session-level messages and out-of-sequence messages are not.
+    pub deliver: bool,
+    /// Messages to send back to the counterparty (see [`FixSession::send`]).
+    pub replies: Vec<Message<'static>>,
+}
+
+/// A FIX session between two parties.
+#[derive(Debug, Clone)]
+pub struct FixSession {
+    /// Our SenderCompID(49).
+    pub sender_comp_id: String,
+    /// The counterparty's CompID, our TargetCompID(56).
+    pub target_comp_id: String,
+    /// Heartbeat interval, in seconds.
+    pub heartbeat_interval: u64,
+
+    state: SessionState,
+    next_outgoing_seq_num: u64,
+    next_incoming_seq_num: u64,
+    resend_requested: bool,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl SessionEvent {
+    fn deliver() -> Self {
+        Self {
+            deliver: true,
+            replies: Vec::new(),
+        }
+    }
+
+    fn reply(replies: Vec<Message<'static>>) -> Self {
+        Self {
+            deliver: false,
+            replies,
+        }
+    }
+}
+
+impl FixSession {
+    /// Create a new, disconnected, `FixSession`.
+    #[must_use]
+    pub fn new(sender_comp_id: &str, target_comp_id: &str, heartbeat_interval: u64) -> Self {
+        Self {
+            sender_comp_id: sender_comp_id.to_string(),
+            target_comp_id: target_comp_id.to_string(),
+            heartbeat_interval,
+            state: SessionState::Disconnected,
+            next_outgoing_seq_num: 1,
+            next_incoming_seq_num: 1,
+            resend_requested: false,
+        }
+    }
+
+    /// Current state of the session.
+    #[must_use]
+    pub fn state(&self) -> SessionState {
+        self.state
+    }
+
+    /// MsgSeqNum(34) of the next outgoing message.
+    #[must_use]
+    pub fn next_outgoing_seq_num(&self) -> u64 {
+        self.next_outgoing_seq_num
+    }
+
+    /// Expected MsgSeqNum(34) of the next incoming message.
+    #[must_use]
+    pub fn next_incoming_seq_num(&self) -> u64 {
+        self.next_incoming_seq_num
+    }
+
+    /// Whether a heartbeat should be sent, given the number of seconds
+    /// since the last outgoing message.
+    #[must_use]
+    pub fn heartbeat_due(&self, seconds_since_last_sent: u64) -> bool {
+        self.state == SessionState::Active && seconds_since_last_sent >= self.heartbeat_interval
+    }
+
+    /// Serialize an outgoing message, with the next sequence number.
+    ///
+    /// # Errors
+    /// `FixError::InvalidState` if an application message is sent
+    /// before logon completes or after logout.
+    pub fn send(
+        &mut self,
+        body: Message<'_>,
+        sending_time: OffsetDateTime,
+    ) -> Result<Vec<u8>, FixError> {
+        let allowed = match self.state {
+            SessionState::Active => true,
+            SessionState::Disconnected => matches!(body, Message::Logon(_)),
+            SessionState::LogonSent | SessionState::LogoutSent => body.is_admin(),
+            // Answering the counterparty's logout.
+            SessionState::Closed => matches!(body, Message::Logout(_)),
+        };
+
+        if !allowed {
+            return Err(FixError::InvalidState(format!(
+                "cannot send MsgType {} in state {:?}",
+                body.msg_type(),
+                self.state
+            )));
+        }
+
+        let message = FixMessage {
+            header: Header {
+                begin_string: Cow::Borrowed(BEGIN_STRING),
+                sender_comp_id: Cow::Borrowed(&self.sender_comp_id),
+                target_comp_id: Cow::Borrowed(&self.target_comp_id),
+                msg_seq_num: self.next_outgoing_seq_num,
+                poss_dup_flag: None,
+                sending_time: Cow::Owned(utc_timestamp(sending_time)),
+            },
+            body,
+        };
+
+        let bytes = message.encode();
+        self.next_outgoing_seq_num += 1;
+
+        match message.body {
+            Message::Logon(_) if self.state == SessionState::Disconnected => {
+                self.state = SessionState::LogonSent;
+            }
+            Message::Logout(_) if self.state == SessionState::Active => {
+                self.state = SessionState::LogoutSent;
+            }
+            Message::Logout(_) => self.state = SessionState::Closed,
+            _ => {}
+        }
+
+        Ok(bytes)
+    }
+
+    /// Serialize a Logon, initiating the session.
+    ///
+    /// # Errors
+    /// `FixError::InvalidState` if the session is not disconnected.
+    pub fn logon(&mut self, sending_time: OffsetDateTime) -> Result<Vec<u8>, FixError> {
+        let logon = self.logon_message();
+        self.send(logon, sending_time)
+    }
+
+    /// Serialize a Logout, terminating the session.
+    ///
+    /// # Errors
+    /// `FixError::InvalidState` if the session is closed.
+    pub fn logout(&mut self, sending_time: OffsetDateTime) -> Result<Vec<u8>, FixError> {
+        self.send(Message::Logout(Logout { text: None }), sending_time)
+    }
+
+    fn logon_message(&self) -> Message<'static> {
+        Message::Logon(Logon {
+            encrypt_method: 0,
+            heart_bt_int: self.heartbeat_interval,
+            reset_seq_num_flag: None,
+        })
+    }
+
+    /// Process an incoming message.
+    ///
+    /// If the sequence number is higher than expected, a ResendRequest is
+    /// returned and the message is not delivered: the counterparty will
+    /// resend it along with the missing messages.
+    ///
+    /// # Errors
+    /// - `FixError::InvalidValue` if the CompIDs do not match the session.
+    /// - `FixError::SequenceTooLow` if the sequence number is lower than
+    ///   expected and the message is not a possible duplicate.
+    ///   This is fatal: the session should be logged out.
+    /// - `FixError::InvalidState` if the message is not allowed in the current state.
+    pub fn on_message(&mut self, message: &FixMessage<'_>) -> Result<SessionEvent, FixError> {
+        let header = &message.header;
+
+        if header.sender_comp_id != self.target_comp_id.as_str() {
+            return Err(FixError::InvalidValue {
+                tag: 49,
+                value: header.sender_comp_id.to_string(),
+            });
+        }
+        if header.target_comp_id != self.sender_comp_id.as_str() {
+            return Err(FixError::InvalidValue {
+                tag: 56,
+                value: header.target_comp_id.to_string(),
+            });
+        }
+
+        // A SequenceReset in reset mode ignores the sequence number.
+        if let Message::SequenceReset(reset) = &message.body {
+            if reset.gap_fill_flag != Some(true) || header.msg_seq_num >= self.next_incoming_seq_num
+            {
+                self.next_incoming_seq_num = reset.new_seq_no;
+                self.resend_requested = false;
+                return Ok(SessionEvent::reply(Vec::new()));
+            }
+        }
+
+        let seq_num = header.msg_seq_num;
+
+        if seq_num < self.next_incoming_seq_num {
+            if header.poss_dup_flag == Some(true) {
+                return Ok(SessionEvent::reply(Vec::new()));
+            }
+            return Err(FixError::SequenceTooLow {
+                expected: self.next_incoming_seq_num,
+                received: seq_num,
+            });
+        }
+
+        let mut replies = Vec::new();
+
+        if seq_num > self.next_incoming_seq_num {
+            if !self.resend_requested {
+                self.resend_requested = true;
+                replies.push(Message::ResendRequest(ResendRequest {
+                    begin_seq_no: self.next_incoming_seq_num,
+                    end_seq_no: 0,
+                }));
+            }
+
+            // The logon must still be processed for the session to start.
+            if !matches!(message.body, Message::Logon(_)) {
+                return Ok(SessionEvent::reply(replies));
+            }
+        } else {
+            self.next_incoming_seq_num += 1;
+            self.resend_requested = false;
+        }
+
+        match &message.body {
+            Message::Logon(_) => match self.state {
+                SessionState::LogonSent => self.state = SessionState::Active,
+                SessionState::Disconnected => {
+                    self.state = SessionState::Active;
+                    replies.insert(0, self.logon_message());
+                }
+                state => {
+                    return Err(FixError::InvalidState(format!(
+                        "unexpected Logon in state {state:?}"
+                    )))
+                }
+            },
+            Message::TestRequest(request) => replies.push(Message::Heartbeat(Heartbeat {
+                test_req_id: Some(Cow::Owned(request.test_req_id.to_string())),
+            })),
+            Message::ResendRequest(_) => replies.push(Message::SequenceReset(SequenceReset {
+                gap_fill_flag: None,
+                new_seq_no: self.next_outgoing_seq_num,
+            })),
+            Message::Logout(_) => {
+                if self.state != SessionState::LogoutSent {
+                    replies.push(Message::Logout(Logout { text: None }));
+                }
+                self.state = SessionState::Closed;
+            }
+            Message::Heartbeat(_) | Message::SequenceReset(_) => {}
+            Message::Reject(_) => return Ok(SessionEvent::deliver()),
+            _ => {
+                if self.state != SessionState::Active {
+                    return Err(FixError::InvalidState(format!(
+                        "application message before logon, in state {:?}",
+                        self.state
+                    )));
+                }
+                return Ok(SessionEvent::deliver());
+            }
+        }
+
+        Ok(SessionEvent::reply(replies))
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_session {
+    use super::*;
+    use crate::trading::fix::TestRequest;
+    use time::macros::datetime;
+
+    const NOW: OffsetDateTime = datetime!(2024-01-02 14:30:00 UTC);
+
+    // Deliver every message sent by `from` to `to`.
+    fn exchange(from: &mut FixSession, to: &mut FixSession, body: Message<'_>) -> SessionEvent {
+        let bytes = from.send(body, NOW).unwrap();
+        let message = FixMessage::parse(&bytes).unwrap();
+
+        to.on_message(&message).unwrap()
+    }
+
+    #[test]
+    fn test_logon_and_logout() {
+        let mut client = FixSession::new("CLIENT", "BROKER", 30);
+        let mut broker = FixSession::new("BROKER", "CLIENT", 30);
+
+        let logon = client.logon(NOW).unwrap();
+        assert_eq!(client.state(), SessionState::LogonSent);
+
+        // The acceptor answers the logon.
+        let event = broker
+            .on_message(&FixMessage::parse(&logon).unwrap())
+            .unwrap();
+        assert_eq!(broker.state(), SessionState::Active);
+        assert!(matches!(event.replies[..], [Message::Logon(_)]));
+
+        let event = exchange(&mut broker, &mut client, event.replies[0].clone());
+        assert!(event.replies.is_empty());
+        assert_eq!(client.state(), SessionState::Active);
+
+        // Logout handshake.
+        let logout = client.logout(NOW).unwrap();
+        assert_eq!(client.state(), SessionState::LogoutSent);
+        let event = broker
+            .on_message(&FixMessage::parse(&logout).unwrap())
+            .unwrap();
+        assert_eq!(broker.state(), SessionState::Closed);
+
+        exchange(&mut broker, &mut client, event.replies[0].clone());
+        assert_eq!(client.state(), SessionState::Closed);
+    }
+
+    fn active_pair() -> (FixSession, FixSession) {
+        let mut client = FixSession::new("CLIENT", "BROKER", 30);
+        let mut broker = FixSession::new("BROKER", "CLIENT", 30);
+
+        let logon = client.logon_message();
+        let event = exchange(&mut client, &mut broker, logon);
+        exchange(&mut broker, &mut client, event.replies[0].clone());
+
+        (client, broker)
+    }
+
+    #[test]
+    fn test_sequence_numbers_and_test_request() {
+        let (mut client, mut broker) = active_pair();
+
+        assert_eq!(client.next_outgoing_seq_num(), 2);
+        assert_eq!(broker.next_incoming_seq_num(), 2);
+
+        let event = exchange(
+            &mut client,
+            &mut broker,
+            Message::TestRequest(TestRequest {
+                test_req_id: Cow::Borrowed("PING"),
+            }),
+        );
+
+        assert!(!event.deliver);
+        assert_eq!(
+            event.replies,
+            vec![Message::Heartbeat(Heartbeat {
+                test_req_id: Some(Cow::Borrowed("PING"))
+            })]
+        );
+        assert!(client.heartbeat_due(30));
+        assert!(!client.heartbeat_due(29));
+    }
+
+    #[test]
+    fn test_sequence_gap() {
+        let (mut client, mut broker) = active_pair();
+
+        // A message from the client is lost.
+        client
+            .send(Message::Heartbeat(Heartbeat { test_req_id: None }), NOW)
+            .unwrap();
+
+        let event = exchange(
+            &mut client,
+            &mut broker,
+            Message::Heartbeat(Heartbeat { test_req_id: None }),
+        );
+        assert_eq!(
+            event.replies,
+            vec![Message::ResendRequest(ResendRequest {
+                begin_seq_no: 2,
+                end_seq_no: 0
+            })]
+        );
+        assert_eq!(broker.next_incoming_seq_num(), 2);
+
+        // The client does not store messages: it resets the sequence.
+        let request = event.replies[0].clone();
+        let event = exchange(&mut broker, &mut client, request);
+        exchange(&mut client, &mut broker, event.replies[0].clone());
+        assert_eq!(broker.next_incoming_seq_num(), 4);
+
+        // Lower sequence numbers are fatal.
+        let bytes = client
+            .send(Message::Logout(Logout { text: None }), NOW)
+            .unwrap();
+        broker.next_incoming_seq_num = 10;
+        assert!(matches!(
+            broker.on_message(&FixMessage::parse(&bytes).unwrap()),
+            Err(FixError::SequenceTooLow { .. })
+        ));
+    }
+
+    #[test]
+    fn test_send_requires_logon() {
+        let mut client = FixSession::new("CLIENT", "BROKER", 30);
+
+        let logout = Message::Logout(Logout { text: None });
+        assert!(client.send(logout, NOW).is_err());
+    }
+}
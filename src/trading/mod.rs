@@ -9,6 +9,18 @@
 
 //! Trading related items.
 
+/// Market microstructure analytics: order book signals.
+pub mod analytics;
+
+/// Bar-by-bar strategy backtesting.
+pub mod backtest;
+
+/// Fill (execution) definition.
+pub mod fill;
+
+/// FIX 4.4 message parsing, generation, and sessions.
+pub mod fix;
+
 /// Contains limit order book implementation
 pub mod limit_order_book;
 
@@ -26,3 +38,15 @@ pub mod order_side;
 
 /// Order types definitions.
 pub mod order_type;
+
+/// Pairs trading: hedge ratio, spread half-life, and z-score signals.
+pub mod pairs;
+
+/// Position, average price, and P&L tracking from fills.
+pub mod position_tracker;
+
+/// Position risk: Greeks, Value-at-Risk, and risk reports.
+pub mod risk;
+
+/// Rolling-window model refitting for backtests.
+pub mod rolling_model;
@@ -0,0 +1,421 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Pairs trading on the spread of two cointegrated price series.
+//!
+//! A [`Pair`] is fitted to the prices $y_t$ and $x_t$ of two assets:
+//! the hedge ratio $\beta$ and the mean $\alpha$ of the spread
+//! $s_t = y_t - \beta x_t$ come from the Engle-Granger regression, and the
+//! speed of mean reversion of the spread from an AR(1) (discretely observed
+//! Ornstein-Uhlenbeck) fit. Trading signals are target positions in the
+//! spread (long one unit of $y$ and short $\beta$ units of $x$), one per
+//! observation, generated from the z-score of the spread.
+//!
+//! When the relationship drifts, [`kalman_hedge_ratio`] estimates a
+//! time-varying intercept and hedge ratio with a Kalman filter instead.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use crate::math::{engle_granger, EngleGranger, KalmanFilter};
+use nalgebra::{DMatrix, DVector};
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// A fitted pair of assets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pair {
+    /// Hedge ratio $\beta$: units of `x` per unit of `y`.
+    pub hedge_ratio: f64,
+    /// Mean $\alpha$ of the spread $y_t - \beta x_t$.
+    pub spread_mean: f64,
+    /// Standard deviation of the spread.
+    pub spread_std: f64,
+    /// Half-life of the spread's mean reversion, in observations
+    /// (infinite if the spread does not mean-revert).
+    pub half_life: f64,
+    /// Engle-Granger cointegration test of the pair.
+    pub cointegration: EngleGranger,
+}
+
+/// Time-varying intercept and hedge ratio, from [`kalman_hedge_ratio`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct KalmanHedgeRatio {
+    /// Filtered intercepts $\alpha_t$ (given observations up to $t$).
+    pub intercepts: Vec<f64>,
+    /// Filtered hedge ratios $\beta_t$ (given observations up to $t$).
+    pub hedge_ratios: Vec<f64>,
+    /// One-step-ahead forecast errors $y_t - \alpha_{t|t-1} - \beta_{t|t-1} x_t$
+    /// (`NaN` for missing observations).
+    pub forecast_errors: Vec<f64>,
+    /// Standard deviations of the forecast errors (`NaN` for missing observations).
+    pub forecast_standard_deviations: Vec<f64>,
+    /// Log-likelihood of the observations, e.g. to choose the variances.
+    pub log_likelihood: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl Pair {
+    /// Fit a pair to the prices `y` and `x`, with `lags` lagged differences
+    /// in the Engle-Granger ADF regression.
+    ///
+    /// The half-life is $-\ln 2 / \ln \phi$, where $\phi$ is the AR(1)
+    /// coefficient of the spread, i.e. $\ln 2 / \theta$ for an
+    /// Ornstein-Uhlenbeck spread with mean reversion speed $\theta$
+    /// (per observation).
+    ///
+    /// # Errors
+    /// Returns an error if the Engle-Granger test fails (e.g. the series
+    /// have different lengths, or are too short).
+    pub fn fit(y: &[f64], x: &[f64], lags: usize) -> Result<Self, RustQuantError> {
+        let cointegration = engle_granger(y, x, lags)?;
+        let residuals = &cointegration.residuals;
+
+        let n = residuals.len() as f64;
+        let spread_std = (residuals.iter().map(|e| e * e).sum::<f64>() / (n - 1.0)).sqrt();
+
+        // AR(1) regression of the spread: s_t = c + phi s_{t-1}.
+        let (previous, current) = (&residuals[..residuals.len() - 1], &residuals[1..]);
+        let m = previous.len() as f64;
+        let (p_mean, c_mean) = (
+            previous.iter().sum::<f64>() / m,
+            current.iter().sum::<f64>() / m,
+        );
+        let spp: f64 = previous.iter().map(|p| (p - p_mean).powi(2)).sum();
+        let spc: f64 = previous
+            .iter()
+            .zip(current)
+            .map(|(p, c)| (p - p_mean) * (c - c_mean))
+            .sum();
+        let phi = spc / spp;
+
+        let half_life = if phi > 0.0 && phi < 1.0 {
+            -std::f64::consts::LN_2 / phi.ln()
+        } else {
+            f64::INFINITY
+        };
+
+        Ok(Self {
+            hedge_ratio: cointegration.hedge_ratio,
+            spread_mean: cointegration.intercept,
+            spread_std,
+            half_life,
+            cointegration,
+        })
+    }
+
+    /// Spread $y_t - \beta x_t$.
+    #[must_use]
+    pub fn spread(&self, y: &[f64], x: &[f64]) -> Vec<f64> {
+        y.iter()
+            .zip(x)
+            .map(|(y, x)| y - self.hedge_ratio * x)
+            .collect()
+    }
+
+    /// z-score of the spread: its distance from the mean in standard deviations.
+    #[must_use]
+    pub fn z_scores(&self, y: &[f64], x: &[f64]) -> Vec<f64> {
+        self.spread(y, x)
+            .iter()
+            .map(|s| (s - self.spread_mean) / self.spread_std)
+            .collect()
+    }
+
+    /// Target positions in the spread (see [`z_score_signals`]).
+    ///
+    /// # Panics
+    /// Panics if the thresholds do not satisfy `0 <= exit < entry`.
+    #[must_use]
+    pub fn signals(&self, y: &[f64], x: &[f64], entry: f64, exit: f64) -> Vec<f64> {
+        z_score_signals(&self.z_scores(y, x), entry, exit)
+    }
+}
+
+impl KalmanHedgeRatio {
+    /// Forecast errors in units of their standard deviation, which can be
+    /// used as z-scores in [`z_score_signals`].
+    #[must_use]
+    pub fn z_scores(&self) -> Vec<f64> {
+        self.forecast_errors
+            .iter()
+            .zip(&self.forecast_standard_deviations)
+            .map(|(e, s)| e / s)
+            .collect()
+    }
+}
+
+/// Dynamic hedge ratio from a Kalman filter regression of `y` on `x`:
+///
+/// $$
+/// y_t = \alpha_t + \beta_t x_t + \varepsilon_t, \quad
+/// \varepsilon_t \sim N(0, \sigma_\varepsilon^2),
+/// $$
+///
+/// where the state $(\alpha_t, \beta_t)$ follows a random walk with
+/// independent increments of variance `state_variance`, and
+/// $\sigma_\varepsilon^2$ is `observation_variance`. The prior of the
+/// first state is diffuse. Observations where either price is `NaN` are
+/// treated as missing.
+///
+/// # Errors
+/// Returns an error if the series have different lengths or the variances
+/// are not positive.
+pub fn kalman_hedge_ratio(
+    y: &[f64],
+    x: &[f64],
+    state_variance: f64,
+    observation_variance: f64,
+) -> Result<KalmanHedgeRatio, RustQuantError> {
+    if y.len() != x.len() {
+        return Err(RustQuantError::InvalidArgument(
+            "Price series must have the same length.".to_string(),
+        ));
+    }
+    if state_variance <= 0.0 || observation_variance <= 0.0 {
+        return Err(RustQuantError::InvalidArgument(
+            "Variances must be positive.".to_string(),
+        ));
+    }
+
+    let kalman = KalmanFilter::new(
+        |_| DMatrix::identity(2, 2),
+        |t| DMatrix::from_row_slice(1, 2, &[1.0, x[t]]),
+        |_| DMatrix::from_diagonal_element(2, 2, state_variance),
+        |_| DMatrix::from_element(1, 1, observation_variance),
+        DVector::zeros(2),
+        DMatrix::from_diagonal_element(2, 2, 1e6),
+    )?;
+
+    let observations: Vec<Option<DVector<f64>>> = y
+        .iter()
+        .zip(x)
+        .map(|(y, x)| (!y.is_nan() && !x.is_nan()).then(|| DVector::from_element(1, *y)))
+        .collect();
+
+    let output = kalman.filter(&observations)?;
+
+    Ok(KalmanHedgeRatio {
+        intercepts: output.filtered_states.iter().map(|s| s[0]).collect(),
+        hedge_ratios: output.filtered_states.iter().map(|s| s[1]).collect(),
+        forecast_errors: output
+            .innovations
+            .iter()
+            .map(|v| v.as_ref().map_or(f64::NAN, |v| v[0]))
+            .collect(),
+        forecast_standard_deviations: output
+            .innovation_covariances
+            .iter()
+            .map(|s| s.as_ref().map_or(f64::NAN, |s| s[(0, 0)].sqrt()))
+            .collect(),
+        log_likelihood: output.log_likelihood,
+    })
+}
+
+/// Mean-reversion signals from z-scores: target positions of
+/// `1.0` (long the spread), `-1.0` (short) or `0.0` (flat).
+///
+/// A short position is entered when the z-score rises above `entry`, and
+/// closed when it falls back to `exit`; symmetrically, a long position is
+/// entered below `-entry` and closed at `-exit`.
+///
+/// # Panics
+/// Panics if the thresholds do not satisfy `0 <= exit < entry`.
+#[must_use]
+pub fn z_score_signals(z_scores: &[f64], entry: f64, exit: f64) -> Vec<f64> {
+    assert!(
+        0.0 <= exit && exit < entry,
+        "Thresholds must satisfy 0 <= exit < entry."
+    );
+
+    let mut position = 0.0;
+
+    z_scores
+        .iter()
+        .map(|&z| {
+            position = if z > entry {
+                -1.0
+            } else if z < -entry {
+                1.0
+            } else if (position > 0.0 && z >= -exit) || (position < 0.0 && z <= exit) {
+                0.0
+            } else {
+                position
+            };
+
+            position
+        })
+        .collect()
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_pairs {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::math::SignificanceLevel;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::StandardNormal;
+
+    // y = 1 + 0.8 x + s, where x is a random walk and s an OU process
+    // with a half-life of 10 observations.
+    fn simulated_pair(n: usize) -> (Vec<f64>, Vec<f64>) {
+        let mut rng = StdRng::seed_from_u64(169);
+        let phi = (-std::f64::consts::LN_2 / 10.0).exp();
+
+        let (mut x, mut s) = (100.0, 0.0);
+        let mut xs = Vec::with_capacity(n);
+        let mut ys = Vec::with_capacity(n);
+
+        for _ in 0..n {
+            x += rng.sample::<f64, _>(StandardNormal);
+            s = phi * s + 0.5 * rng.sample::<f64, _>(StandardNormal);
+
+            xs.push(x);
+            ys.push(1.0 + 0.8 * x + s);
+        }
+
+        (ys, xs)
+    }
+
+    #[test]
+    fn test_pair_fit() {
+        let (y, x) = simulated_pair(20_000);
+        let pair = Pair::fit(&y, &x, 1).unwrap();
+
+        assert!(pair
+            .cointegration
+            .is_cointegrated(SignificanceLevel::OnePercent));
+        assert_approx_equal!(pair.hedge_ratio, 0.8, 0.01);
+
+        // Standard error of the half-life: ~0.4.
+        assert_approx_equal!(pair.half_life, 10.0, 1.5);
+
+        // Stationary variance of the spread: 0.25 / (1 - phi^2).
+        let phi = (-std::f64::consts::LN_2 / 10.0).exp();
+        assert_approx_equal!(pair.spread_std, (0.25 / (1.0 - phi * phi)).sqrt(), 0.1);
+
+        let z = pair.z_scores(&y, &x);
+        let mean = z.iter().sum::<f64>() / z.len() as f64;
+        assert_approx_equal!(mean, 0.0, 1e-6);
+    }
+
+    #[test]
+    fn test_z_score_signals() {
+        let z = [0.0, 2.5, 1.0, 0.2, -0.1, -2.5, -1.0, 0.3, -2.1, 2.2];
+
+        assert_eq!(
+            z_score_signals(&z, 2.0, 0.5),
+            vec![0.0, -1.0, -1.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0, -1.0]
+        );
+    }
+
+    #[test]
+    fn test_pair_signals() {
+        let (y, x) = simulated_pair(2_000);
+        let pair = Pair::fit(&y, &x, 1).unwrap();
+
+        let z = pair.z_scores(&y, &x);
+        let signals = pair.signals(&y, &x, 2.0, 0.0);
+
+        assert_eq!(signals.len(), y.len());
+        for t in 1..signals.len() {
+            // Positions are only opened beyond the entry thresholds.
+            if signals[t] != signals[t - 1] && signals[t] == -1.0 {
+                assert!(z[t] > 2.0);
+            }
+            if signals[t] != signals[t - 1] && signals[t] == 1.0 {
+                assert!(z[t] < -2.0);
+            }
+        }
+
+        // The spread reverts, so trades are opened and closed.
+        let trades = signals
+            .windows(2)
+            .filter(|w| w[0] == 0.0 && w[1] != 0.0)
+            .count();
+        assert!(trades > 5);
+    }
+
+    #[test]
+    fn test_kalman_hedge_ratio_tracks_drift() {
+        let mut rng = StdRng::seed_from_u64(170);
+        let n = 2_000;
+
+        // The hedge ratio drifts from 0.5 to 1.5.
+        let beta = |t: usize| 0.5 + t as f64 / n as f64;
+
+        let mut price = 50.0;
+        let (y, x): (Vec<f64>, Vec<f64>) = (0..n)
+            .map(|t| {
+                price += rng.sample::<f64, _>(StandardNormal);
+                let y = 2.0 + beta(t) * price + 0.5 * rng.sample::<f64, _>(StandardNormal);
+                (y, price)
+            })
+            .unzip();
+
+        let kalman = kalman_hedge_ratio(&y, &x, 1e-5, 0.25).unwrap();
+
+        let tracking_error = (n / 2..n)
+            .map(|t| (kalman.hedge_ratios[t] - beta(t)).abs())
+            .sum::<f64>()
+            / (n / 2) as f64;
+        assert!(tracking_error < 0.05, "tracking error: {tracking_error}");
+
+        // Forecast errors are approximately standard normal in z-score units.
+        let z = kalman.z_scores();
+        let variance = z[100..].iter().map(|z| z * z).sum::<f64>() / (n - 100) as f64;
+        assert_approx_equal!(variance, 1.0, 0.2);
+    }
+
+    #[test]
+    fn test_kalman_hedge_ratio_missing_prices() {
+        let (mut y, x) = simulated_pair(500);
+        y[100] = f64::NAN;
+
+        let kalman = kalman_hedge_ratio(&y, &x, 1e-6, 0.25).unwrap();
+
+        assert!(kalman.forecast_errors[100].is_nan());
+        assert_eq!(kalman.hedge_ratios[100], kalman.hedge_ratios[99]);
+        assert_approx_equal!(kalman.hedge_ratios[499], 0.8, 0.05);
+
+        assert!(kalman_hedge_ratio(&y, &x[1..], 1e-6, 0.25).is_err());
+        assert!(kalman_hedge_ratio(&y, &x, 0.0, 0.25).is_err());
+    }
+
+    #[test]
+    fn test_random_walk_spread_does_not_revert() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let (mut a, mut b) = (0.0, 0.0);
+        let (y, x): (Vec<f64>, Vec<f64>) = (0..2_000)
+            .map(|_| {
+                a += rng.sample::<f64, _>(StandardNormal);
+                b += rng.sample::<f64, _>(StandardNormal);
+                (a, b)
+            })
+            .unzip();
+
+        let pair = Pair::fit(&y, &x, 1).unwrap();
+
+        assert!(!pair
+            .cointegration
+            .is_cointegrated(SignificanceLevel::FivePercent));
+        assert!(pair.half_life > 50.0);
+    }
+}
@@ -0,0 +1,414 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Position and P&L tracking from a stream of [`Fill`]s.
+//!
+//! The [`PositionTracker`] keeps, for each symbol, the signed position,
+//! the average entry price of the open position, and the realized P&L,
+//! using either FIFO or average-cost accounting. Every time (part of) a
+//! position is closed, a [`RoundTrip`] is recorded, from which
+//! [`TradeStatistics`] such as the win rate and profit factor are computed.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{fill::Fill, order_side::OrderSide};
+use std::collections::{HashMap, VecDeque};
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Method used to match closing fills against the open position.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccountingMethod {
+    /// First-in, first-out: closing fills are matched against the oldest lots.
+    Fifo,
+    /// Average cost: closing fills are matched against the average entry price.
+    AverageCost,
+}
+
+/// An open lot: a quantity entered at a given price and time.
+#[derive(Debug, Clone, Copy)]
+struct Lot {
+    /// Unsigned quantity remaining in the lot.
+    quantity: u64,
+    /// Entry price of the lot.
+    price: f64,
+    /// Entry time of the lot.
+    timestamp: OffsetDateTime,
+}
+
+/// Position in a single symbol.
+#[derive(Debug, Clone, Default)]
+pub struct SymbolPosition {
+    /// Signed position: positive when long, negative when short.
+    pub quantity: i64,
+    /// Realized P&L of the symbol.
+    pub realized_pnl: f64,
+    /// Open lots, oldest first (a single lot under average-cost accounting).
+    lots: VecDeque<Lot>,
+}
+
+/// A closed trade: an entry matched with (part of) an exit.
+#[derive(Debug, Clone, Copy)]
+pub struct RoundTrip {
+    /// Symbol Identifier
+    pub symbol_id: u32,
+    /// Side of the entry (bid = long trade, ask = short trade).
+    pub side: OrderSide,
+    /// Quantity closed.
+    pub quantity: u64,
+    /// Entry price.
+    pub entry_price: f64,
+    /// Exit price.
+    pub exit_price: f64,
+    /// Entry time.
+    pub entry_time: OffsetDateTime,
+    /// Exit time.
+    pub exit_time: OffsetDateTime,
+    /// Realized P&L of the trade.
+    pub pnl: f64,
+}
+
+/// Summary statistics of a set of round-trip trades.
+#[derive(Debug, Clone, Copy)]
+pub struct TradeStatistics {
+    /// Number of round trips.
+    pub trades: usize,
+    /// Fraction of trades with a positive P&L.
+    pub win_rate: f64,
+    /// Average P&L of the winning trades.
+    pub average_win: f64,
+    /// Average P&L of the losing trades (a negative number).
+    pub average_loss: f64,
+    /// Gross profit divided by gross loss.
+    pub profit_factor: f64,
+}
+
+/// Tracks positions, average prices, and P&L from a stream of fills.
+#[derive(Debug, Clone)]
+pub struct PositionTracker {
+    /// Accounting method used to compute realized P&L.
+    pub method: AccountingMethod,
+    /// Positions by symbol.
+    positions: HashMap<u32, SymbolPosition>,
+    /// Closed trades, in the order they were closed.
+    round_trips: Vec<RoundTrip>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl SymbolPosition {
+    /// Volume-weighted average entry price of the open position.
+    /// Returns `None` if the position is flat.
+    #[must_use]
+    pub fn average_price(&self) -> Option<f64> {
+        let quantity: u64 = self.lots.iter().map(|lot| lot.quantity).sum();
+
+        (quantity > 0).then(|| {
+            self.lots
+                .iter()
+                .map(|lot| lot.quantity as f64 * lot.price)
+                .sum::<f64>()
+                / quantity as f64
+        })
+    }
+
+    /// Unrealized P&L of the open position at the given mark price.
+    #[must_use]
+    pub fn unrealized_pnl(&self, mark_price: f64) -> f64 {
+        self.average_price()
+            .map_or(0.0, |price| self.quantity as f64 * (mark_price - price))
+    }
+}
+
+impl PositionTracker {
+    /// Create a new, empty, `PositionTracker`.
+    #[must_use]
+    pub fn new(method: AccountingMethod) -> Self {
+        Self {
+            method,
+            positions: HashMap::new(),
+            round_trips: Vec::new(),
+        }
+    }
+
+    /// Position in a symbol, if any fills have been seen for it.
+    #[must_use]
+    pub fn position(&self, symbol_id: u32) -> Option<&SymbolPosition> {
+        self.positions.get(&symbol_id)
+    }
+
+    /// Positions by symbol.
+    #[must_use]
+    pub fn positions(&self) -> &HashMap<u32, SymbolPosition> {
+        &self.positions
+    }
+
+    /// Closed trades, in the order they were closed.
+    #[must_use]
+    pub fn round_trips(&self) -> &[RoundTrip] {
+        &self.round_trips
+    }
+
+    /// Realized P&L across all symbols.
+    #[must_use]
+    pub fn realized_pnl(&self) -> f64 {
+        self.positions.values().map(|p| p.realized_pnl).sum()
+    }
+
+    /// Unrealized P&L of a symbol at the given mark price.
+    #[must_use]
+    pub fn unrealized_pnl(&self, symbol_id: u32, mark_price: f64) -> f64 {
+        self.position(symbol_id)
+            .map_or(0.0, |position| position.unrealized_pnl(mark_price))
+    }
+
+    /// Process a fill.
+    ///
+    /// A fill that reduces the position is matched against the open lots
+    /// and realizes P&L. A fill larger than the open position (a flip from
+    /// long to short or vice versa) is split into a close of the whole
+    /// position and an open of the remainder at the fill price.
+    pub fn on_fill(&mut self, fill: &Fill) {
+        let method = self.method;
+        let position = self.positions.entry(fill.symbol_id).or_default();
+
+        let is_closing = (position.quantity > 0 && fill.side == OrderSide::ASK)
+            || (position.quantity < 0 && fill.side == OrderSide::BID);
+
+        let mut remaining = fill.quantity;
+        position.quantity += fill.signed_quantity();
+
+        if is_closing {
+            let entry_side = !fill.side;
+            let direction = match entry_side {
+                OrderSide::BID => 1.0,
+                OrderSide::ASK => -1.0,
+            };
+
+            while remaining > 0 {
+                let Some(lot) = position.lots.front_mut() else {
+                    break;
+                };
+
+                let quantity = remaining.min(lot.quantity);
+                let pnl = direction * quantity as f64 * (fill.price - lot.price);
+
+                self.round_trips.push(RoundTrip {
+                    symbol_id: fill.symbol_id,
+                    side: entry_side,
+                    quantity,
+                    entry_price: lot.price,
+                    exit_price: fill.price,
+                    entry_time: lot.timestamp,
+                    exit_time: fill.timestamp,
+                    pnl,
+                });
+
+                position.realized_pnl += pnl;
+                lot.quantity -= quantity;
+                remaining -= quantity;
+
+                if lot.quantity == 0 {
+                    position.lots.pop_front();
+                }
+            }
+        }
+
+        // Whatever is left opens (or adds to) a position on the fill's side.
+        if remaining > 0 {
+            let lot = Lot {
+                quantity: remaining,
+                price: fill.price,
+                timestamp: fill.timestamp,
+            };
+
+            match (method, position.lots.back_mut()) {
+                (AccountingMethod::AverageCost, Some(open)) => {
+                    let total = open.quantity + lot.quantity;
+                    open.price = (open.quantity as f64 * open.price
+                        + lot.quantity as f64 * lot.price)
+                        / total as f64;
+                    open.quantity = total;
+                }
+                _ => position.lots.push_back(lot),
+            }
+        }
+    }
+
+    /// Summary statistics of the round trips closed so far.
+    #[must_use]
+    pub fn statistics(&self) -> TradeStatistics {
+        trade_statistics(&self.round_trips)
+    }
+}
+
+/// Summary statistics of a set of round-trip trades.
+///
+/// Break-even trades count towards the number of trades,
+/// but neither as wins nor losses.
+#[must_use]
+pub fn trade_statistics(round_trips: &[RoundTrip]) -> TradeStatistics {
+    let (wins, losses): (Vec<f64>, Vec<f64>) = round_trips
+        .iter()
+        .map(|trade| trade.pnl)
+        .filter(|&pnl| pnl != 0.0)
+        .partition(|&pnl| pnl > 0.0);
+
+    let gross_profit: f64 = wins.iter().sum();
+    let gross_loss: f64 = losses.iter().sum();
+
+    let mean = |sum: f64, n: usize| if n == 0 { 0.0 } else { sum / n as f64 };
+
+    TradeStatistics {
+        trades: round_trips.len(),
+        win_rate: mean(wins.len() as f64, round_trips.len()),
+        average_win: mean(gross_profit, wins.len()),
+        average_loss: mean(gross_loss, losses.len()),
+        profit_factor: if gross_loss == 0.0 {
+            f64::INFINITY
+        } else {
+            gross_profit / -gross_loss
+        },
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_position_tracker {
+    use super::*;
+    use time::{macros::datetime, Duration};
+
+    const EPS: f64 = 1e-12;
+
+    // Buy 100 @ 10, buy 100 @ 12, sell 150 @ 13 (partial close),
+    // sell 100 @ 11 (flip to short 50), buy 50 @ 9 (close).
+    fn fills() -> Vec<Fill> {
+        let t0 = datetime!(2024-01-02 14:30 UTC);
+
+        [
+            (OrderSide::BID, 10.0, 100),
+            (OrderSide::BID, 12.0, 100),
+            (OrderSide::ASK, 13.0, 150),
+            (OrderSide::ASK, 11.0, 100),
+            (OrderSide::BID, 9.0, 50),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(i, &(side, price, quantity))| {
+            Fill::new(
+                i as u64,
+                1,
+                side,
+                price,
+                quantity,
+                t0 + Duration::minutes(i as i64),
+            )
+        })
+        .collect()
+    }
+
+    #[test]
+    fn test_fifo_accounting() {
+        let fills = fills();
+        let mut tracker = PositionTracker::new(AccountingMethod::Fifo);
+
+        fills[..3].iter().for_each(|fill| tracker.on_fill(fill));
+
+        // Partial close: 100 @ 10 and 50 @ 12 are closed, 50 @ 12 remains.
+        let position = tracker.position(1).unwrap();
+        assert_eq!(position.quantity, 50);
+        assert_approx_equal!(position.average_price().unwrap(), 12.0, EPS);
+        assert_approx_equal!(position.realized_pnl, 350.0, EPS);
+        assert_approx_equal!(tracker.unrealized_pnl(1, 14.0), 100.0, EPS);
+
+        // Flip: close 50 @ 12 at 11, open short 50 @ 11.
+        tracker.on_fill(&fills[3]);
+        let position = tracker.position(1).unwrap();
+        assert_eq!(position.quantity, -50);
+        assert_approx_equal!(position.average_price().unwrap(), 11.0, EPS);
+        assert_approx_equal!(position.realized_pnl, 300.0, EPS);
+        assert_approx_equal!(tracker.unrealized_pnl(1, 10.0), 50.0, EPS);
+
+        tracker.on_fill(&fills[4]);
+        let position = tracker.position(1).unwrap();
+        assert_eq!(position.quantity, 0);
+        assert!(position.average_price().is_none());
+        assert_approx_equal!(tracker.realized_pnl(), 400.0, EPS);
+
+        let pnls: Vec<f64> = tracker.round_trips().iter().map(|t| t.pnl).collect();
+        assert_eq!(pnls, vec![300.0, 50.0, -50.0, 100.0]);
+        assert_eq!(tracker.round_trips()[3].side, OrderSide::ASK);
+
+        let stats = tracker.statistics();
+        assert_eq!(stats.trades, 4);
+        assert_approx_equal!(stats.win_rate, 0.75, EPS);
+        assert_approx_equal!(stats.average_win, 150.0, EPS);
+        assert_approx_equal!(stats.average_loss, -50.0, EPS);
+        assert_approx_equal!(stats.profit_factor, 9.0, EPS);
+    }
+
+    #[test]
+    fn test_average_cost_accounting() {
+        let fills = fills();
+        let mut tracker = PositionTracker::new(AccountingMethod::AverageCost);
+
+        fills[..3].iter().for_each(|fill| tracker.on_fill(fill));
+
+        // Partial close at the average cost of 11.
+        let position = tracker.position(1).unwrap();
+        assert_eq!(position.quantity, 50);
+        assert_approx_equal!(position.average_price().unwrap(), 11.0, EPS);
+        assert_approx_equal!(position.realized_pnl, 300.0, EPS);
+        assert_approx_equal!(tracker.unrealized_pnl(1, 14.0), 150.0, EPS);
+
+        // Flip: close 50 @ 11 at 11 (break-even), open short 50 @ 11.
+        tracker.on_fill(&fills[3]);
+        let position = tracker.position(1).unwrap();
+        assert_eq!(position.quantity, -50);
+        assert_approx_equal!(position.average_price().unwrap(), 11.0, EPS);
+        assert_approx_equal!(position.realized_pnl, 300.0, EPS);
+
+        tracker.on_fill(&fills[4]);
+        assert_eq!(tracker.position(1).unwrap().quantity, 0);
+        assert_approx_equal!(tracker.realized_pnl(), 400.0, EPS);
+
+        let pnls: Vec<f64> = tracker.round_trips().iter().map(|t| t.pnl).collect();
+        assert_eq!(pnls, vec![300.0, 0.0, 100.0]);
+
+        let stats = tracker.statistics();
+        assert_eq!(stats.trades, 3);
+        assert_approx_equal!(stats.win_rate, 2.0 / 3.0, EPS);
+        assert_approx_equal!(stats.average_win, 200.0, EPS);
+        assert!(stats.profit_factor.is_infinite());
+    }
+
+    #[test]
+    fn test_symbols_are_tracked_separately() {
+        let t = datetime!(2024-01-02 14:30 UTC);
+        let mut tracker = PositionTracker::new(AccountingMethod::Fifo);
+
+        tracker.on_fill(&Fill::new(1, 1, OrderSide::BID, 100.0, 10, t));
+        tracker.on_fill(&Fill::new(2, 2, OrderSide::ASK, 50.0, 20, t));
+
+        assert_eq!(tracker.position(1).unwrap().quantity, 10);
+        assert_eq!(tracker.position(2).unwrap().quantity, -20);
+        assert_approx_equal!(tracker.unrealized_pnl(2, 45.0), 100.0, EPS);
+        assert!(tracker.round_trips().is_empty());
+    }
+}
@@ -0,0 +1,277 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Yield curve risk: key-rate DV01s.
+//!
+//! The key-rate DV01 of pillar $k$ is the loss in value when the zero rate
+//! at that knot of the curve alone rises by one basis point, computed by
+//! bumping and revaluing (with a central difference). Bumping the knot's
+//! zero rate under the curve's own interpolation gives "tent" shaped
+//! bumps, which add up to a parallel shift: the key-rate DV01s sum to the
+//! parallel DV01.
+//!
+//! Key-rate DV01s can be compressed onto a handful of principal component
+//! factors of historical curve moves (level, slope, curvature).
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::data::PiecewiseCurve;
+use crate::error::RustQuantError;
+use crate::ml::PrincipalComponents;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// One basis point, in rate units.
+pub const BASIS_POINT: f64 = 1e-4;
+
+/// An instrument valued off a yield curve.
+pub trait CurvePriced {
+    /// Value of the instrument on `curve`.
+    fn price_on_curve(&self, curve: &PiecewiseCurve) -> f64;
+}
+
+/// Key-rate and parallel DV01s of an instrument or portfolio.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurveRiskReport {
+    /// Pillars (knot times) of the curve.
+    pub pillars: Vec<f64>,
+    /// Loss for a one basis point rise in each pillar's zero rate.
+    pub key_rate_dv01s: Vec<f64>,
+    /// Loss for a one basis point parallel rise in the zero rates.
+    pub parallel_dv01: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl<F> CurvePriced for F
+where
+    F: Fn(&PiecewiseCurve) -> f64,
+{
+    fn price_on_curve(&self, curve: &PiecewiseCurve) -> f64 {
+        self(curve)
+    }
+}
+
+impl CurveRiskReport {
+    /// Sum of the key-rate DV01s.
+    #[must_use]
+    pub fn total_key_rate_dv01(&self) -> f64 {
+        self.key_rate_dv01s.iter().sum()
+    }
+
+    /// DV01s with respect to the first `n_factors` principal components of
+    /// curve moves: the loss for a unit move along each component, with
+    /// `pca` fitted on zero rate changes at the pillars in basis points.
+    ///
+    /// # Errors
+    /// `InvalidArgument` if `pca` has a different number of variables than
+    /// there are pillars, or fewer than `n_factors` components.
+    pub fn factor_dv01s(
+        &self,
+        pca: &PrincipalComponents,
+        n_factors: usize,
+    ) -> Result<Vec<f64>, RustQuantError> {
+        let components = &pca.components;
+        if components.nrows() != self.pillars.len() || components.ncols() < n_factors {
+            return Err(RustQuantError::InvalidArgument(
+                "Principal components do not match the curve pillars.".to_string(),
+            ));
+        }
+
+        Ok((0..n_factors)
+            .map(|j| {
+                components
+                    .column(j)
+                    .iter()
+                    .zip(&self.key_rate_dv01s)
+                    .map(|(loading, dv01)| loading * dv01)
+                    .sum()
+            })
+            .collect())
+    }
+}
+
+/// Key-rate and parallel DV01s of `instrument` on `curve`.
+#[must_use]
+pub fn curve_risk<I: CurvePriced + ?Sized>(
+    instrument: &I,
+    curve: &PiecewiseCurve,
+) -> CurveRiskReport {
+    let n = curve.knots().len();
+
+    // Central difference of the value for the zero rate shifts `bump` (in
+    // basis points), as a loss per basis point.
+    let dv01 = |bump: &dyn Fn(usize) -> f64| {
+        let shifts =
+            |sign: f64| -> Vec<f64> { (0..n).map(|k| sign * BASIS_POINT * bump(k)).collect() };
+        let up = instrument.price_on_curve(&curve.with_zero_rate_shifts(&shifts(1.0)));
+        let down = instrument.price_on_curve(&curve.with_zero_rate_shifts(&shifts(-1.0)));
+
+        0.5 * (down - up)
+    };
+
+    let key_rate_dv01s = (0..n)
+        .map(|pillar| dv01(&|k| if k == pillar { 1.0 } else { 0.0 }))
+        .collect();
+
+    CurveRiskReport {
+        pillars: curve.knots().to_vec(),
+        key_rate_dv01s,
+        parallel_dv01: dv01(&|_| 1.0),
+    }
+}
+
+/// Curve risk of a portfolio of `(quantity, instrument)` positions: the
+/// quantity-weighted sum of the positions' reports.
+#[must_use]
+pub fn portfolio_curve_risk(
+    positions: &[(f64, &dyn CurvePriced)],
+    curve: &PiecewiseCurve,
+) -> CurveRiskReport {
+    let mut total = CurveRiskReport {
+        pillars: curve.knots().to_vec(),
+        key_rate_dv01s: vec![0.0; curve.knots().len()],
+        parallel_dv01: 0.0,
+    };
+
+    for (quantity, instrument) in positions {
+        let report = curve_risk(*instrument, curve);
+
+        for (total, dv01) in total.key_rate_dv01s.iter_mut().zip(&report.key_rate_dv01s) {
+            *total += quantity * dv01;
+        }
+        total.parallel_dv01 += quantity * report.parallel_dv01;
+    }
+
+    total
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_curve_risk {
+    use super::*;
+    use crate::assert_approx_equal;
+    use crate::data::CurveInterpolation;
+    use nalgebra::DMatrix;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+    use rand_distr::StandardNormal;
+
+    const PILLARS: [f64; 10] = [0.25, 0.5, 1.0, 2.0, 3.0, 5.0, 7.0, 10.0, 20.0, 30.0];
+
+    fn curve(interpolation: CurveInterpolation) -> PiecewiseCurve {
+        // Upward sloping zero curve from 3% to 4.5%.
+        let log_discounts: Vec<f64> = PILLARS
+            .iter()
+            .map(|t| -(0.03 + 0.015 * (1.0 - (-t / 5.0).exp())) * t)
+            .collect();
+
+        PiecewiseCurve::new(&PILLARS, &log_discounts, interpolation, Vec::new()).unwrap()
+    }
+
+    // Bullet bond with annual coupons, per 100 face value.
+    fn bond(maturity: usize, coupon: f64) -> impl Fn(&PiecewiseCurve) -> f64 {
+        move |curve: &PiecewiseCurve| {
+            (1..=maturity)
+                .map(|t| 100.0 * coupon * curve.discount_factor(t as f64))
+                .sum::<f64>()
+                + 100.0 * curve.discount_factor(maturity as f64)
+        }
+    }
+
+    #[test]
+    fn test_key_rates_sum_to_parallel() {
+        for interpolation in [
+            CurveInterpolation::LogLinear,
+            CurveInterpolation::MonotoneConvex,
+        ] {
+            let report = curve_risk(&bond(10, 0.04), &curve(interpolation));
+
+            assert!(report.parallel_dv01 > 0.0);
+            assert_approx_equal!(
+                report.total_key_rate_dv01() / report.parallel_dv01,
+                1.0,
+                0.01
+            );
+
+            // The 10y pillar carries the principal.
+            let largest = report
+                .key_rate_dv01s
+                .iter()
+                .copied()
+                .fold(f64::NEG_INFINITY, f64::max);
+            assert_approx_equal!(largest, report.key_rate_dv01s[7], 1e-15);
+        }
+    }
+
+    #[test]
+    fn test_short_bond_ignores_long_pillars() {
+        let report = curve_risk(&bond(2, 0.03), &curve(CurveInterpolation::LogLinear));
+
+        assert_approx_equal!(report.key_rate_dv01s[9], 0.0, 1e-12);
+        assert_approx_equal!(report.key_rate_dv01s[8], 0.0, 1e-12);
+        assert!(report.key_rate_dv01s[3] > 0.9 * report.parallel_dv01);
+    }
+
+    #[test]
+    fn test_portfolio_aggregation() {
+        let curve = curve(CurveInterpolation::LogLinear);
+        let (long, short) = (bond(10, 0.04), bond(2, 0.03));
+
+        let portfolio = portfolio_curve_risk(&[(2.0, &long), (-3.0, &short)], &curve);
+        let (long, short) = (curve_risk(&long, &curve), curve_risk(&short, &curve));
+
+        for k in 0..PILLARS.len() {
+            assert_approx_equal!(
+                portfolio.key_rate_dv01s[k],
+                2.0 * long.key_rate_dv01s[k] - 3.0 * short.key_rate_dv01s[k],
+                1e-10
+            );
+        }
+        assert_approx_equal!(
+            portfolio.parallel_dv01,
+            2.0 * long.parallel_dv01 - 3.0 * short.parallel_dv01,
+            1e-10
+        );
+    }
+
+    #[test]
+    fn test_principal_component_compression() {
+        // Daily zero rate changes (in bp) dominated by a level factor.
+        let mut rng = StdRng::seed_from_u64(174);
+        let changes = DMatrix::from_row_iterator(
+            5_000,
+            PILLARS.len(),
+            (0..5_000).flat_map(|_| {
+                let level: f64 = 10.0 * rng.sample::<f64, _>(StandardNormal);
+                let noise: Vec<f64> = PILLARS.iter().map(|_| rng.sample(StandardNormal)).collect();
+                noise.into_iter().map(move |e: f64| level + e)
+            }),
+        );
+        let pca = PrincipalComponents::fit(&changes).unwrap();
+
+        let report = curve_risk(&bond(10, 0.04), &curve(CurveInterpolation::LogLinear));
+        let factors = report.factor_dv01s(&pca, 3).unwrap();
+
+        // The level factor moves every pillar by about 1 / sqrt(10) bp.
+        let level = report.parallel_dv01 / (PILLARS.len() as f64).sqrt();
+        assert_approx_equal!(factors[0] / level, 1.0, 0.02);
+        assert!(factors[1].abs() < factors[0].abs());
+
+        assert!(report.factor_dv01s(&pca, 11).is_err());
+    }
+}
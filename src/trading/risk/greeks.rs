@@ -0,0 +1,192 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Greeks of an instrument via automatic differentiation, either
+//! reverse-mode over the tape or forward-mode with hyper-dual numbers.
+
+use crate::autodiff::{Accumulate, Gradient, Graph, HyperDual, Variable};
+use crate::pricer::AuditRecord;
+
+/// A pricing function of the spot and volatility of the underlying,
+/// written in terms of [`Variable`]s so that it can be differentiated.
+pub trait Pricer {
+    /// Price of one unit of the instrument.
+    fn price<'v>(&self, spot: Variable<'v>, volatility: Variable<'v>) -> Variable<'v>;
+
+    /// Price of one unit of the instrument in [`HyperDual`] arithmetic, for
+    /// exact second-order Greeks. `None` (the default) if the pricer has no
+    /// hyper-dual implementation.
+    fn price_hyper_dual(&self, _spot: HyperDual, _volatility: HyperDual) -> Option<HyperDual> {
+        None
+    }
+
+    /// [`AuditRecord`] of the price at the given spot and volatility, for
+    /// audited risk reports. `None` (the default) if the pricer does not
+    /// record its inputs.
+    fn audit_record(&self, _spot: f64, _volatility: f64) -> Option<AuditRecord> {
+        None
+    }
+}
+
+/// How [`AutodiffGreeks`] are computed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GreeksBackend {
+    /// Reverse-mode over the tape: exact delta and vega, with gamma and
+    /// vanna from central differences of them.
+    #[default]
+    Tape,
+    /// Forward-mode with hyper-dual numbers: every Greek exact, from two
+    /// evaluations of the price. Falls back to [`GreeksBackend::Tape`] for
+    /// pricers without [`Pricer::price_hyper_dual`].
+    HyperDual,
+}
+
+/// Price and Greeks of one unit of an instrument.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AutodiffGreeks {
+    /// Price.
+    pub price: f64,
+    /// First derivative of the price with respect to the spot.
+    pub delta: f64,
+    /// Second derivative of the price with respect to the spot.
+    pub gamma: f64,
+    /// First derivative of the price with respect to the volatility.
+    pub vega: f64,
+    /// Cross derivative of the price with respect to the spot and the
+    /// volatility.
+    pub vanna: f64,
+}
+
+impl AutodiffGreeks {
+    /// Relative spot bump used for gamma.
+    const GAMMA_BUMP: f64 = 1e-4;
+
+    /// Compute the price and Greeks of a [`Pricer`] at the given spot and volatility.
+    ///
+    /// Delta and vega are exact, from a single reverse pass over the tape.
+    /// The tape only supports first-order derivatives, so gamma and vanna are
+    /// the central differences of the (exact) autodiff deltas and vegas at
+    /// bumped spots.
+    #[must_use]
+    pub fn compute(pricer: &dyn Pricer, spot: f64, volatility: f64) -> Self {
+        let (price, delta, vega) = Self::first_order(pricer, spot, volatility);
+
+        let h = Self::GAMMA_BUMP * spot.abs().max(1.0);
+        let (_, delta_up, vega_up) = Self::first_order(pricer, spot + h, volatility);
+        let (_, delta_down, vega_down) = Self::first_order(pricer, spot - h, volatility);
+
+        Self {
+            price,
+            delta,
+            gamma: (delta_up - delta_down) / (2.0 * h),
+            vega,
+            vanna: (vega_up - vega_down) / (2.0 * h),
+        }
+    }
+
+    /// Compute the price and Greeks of a [`Pricer`] with the given backend.
+    #[must_use]
+    pub fn compute_with_backend(
+        pricer: &dyn Pricer,
+        spot: f64,
+        volatility: f64,
+        backend: GreeksBackend,
+    ) -> Self {
+        match backend {
+            GreeksBackend::Tape => Self::compute(pricer, spot, volatility),
+            GreeksBackend::HyperDual => Self::hyper_dual(pricer, spot, volatility)
+                .unwrap_or_else(|| Self::compute(pricer, spot, volatility)),
+        }
+    }
+
+    /// All Greeks exact from two hyper-dual evaluations: $S + \epsilon_1 +
+    /// \epsilon_2$ gives the price, delta, and gamma, and $(S + \epsilon_1,
+    /// \sigma + \epsilon_2)$ gives the vega and vanna.
+    fn hyper_dual(pricer: &dyn Pricer, spot: f64, volatility: f64) -> Option<Self> {
+        let v = HyperDual::constant(volatility);
+        let spot_only = pricer.price_hyper_dual(HyperDual::new(spot, 1.0, 1.0, 0.0), v)?;
+
+        let s = HyperDual::new(spot, 1.0, 0.0, 0.0);
+        let v = HyperDual::new(volatility, 0.0, 1.0, 0.0);
+        let cross = pricer.price_hyper_dual(s, v)?;
+
+        Some(Self {
+            price: spot_only.value,
+            delta: spot_only.e1,
+            gamma: spot_only.e1e2,
+            vega: cross.e2,
+            vanna: cross.e1e2,
+        })
+    }
+
+    /// Price, delta, and vega from one forward and one reverse pass.
+    fn first_order(pricer: &dyn Pricer, spot: f64, volatility: f64) -> (f64, f64, f64) {
+        let graph = Graph::new();
+
+        let s = graph.var(spot);
+        let v = graph.var(volatility);
+
+        let price = pricer.price(s, v);
+        let gradient = price.accumulate();
+
+        (price.value(), gradient.wrt(&s), gradient.wrt(&v))
+    }
+}
+
+#[cfg(test)]
+mod tests_greeks {
+    use super::*;
+    use crate::instruments::options::black_scholes::{self, autodiff_price};
+    use crate::instruments::TypeFlag;
+
+    struct BlackScholesCall {
+        strike: f64,
+        rate: f64,
+        time: f64,
+    }
+
+    impl Pricer for BlackScholesCall {
+        fn price<'v>(&self, spot: Variable<'v>, volatility: Variable<'v>) -> Variable<'v> {
+            let (k, r, t) = (self.strike, self.rate, self.time);
+
+            autodiff_price(spot, k, r, r, volatility, t, TypeFlag::Call)
+        }
+    }
+
+    #[test]
+    fn test_black_scholes_greeks() {
+        let (s, k, r, t, v) = (100.0, 95.0, 0.05, 0.5, 0.25);
+        let pricer = BlackScholesCall {
+            strike: k,
+            rate: r,
+            time: t,
+        };
+
+        let greeks = AutodiffGreeks::compute(&pricer, s, v);
+
+        let delta = black_scholes::delta(s, k, r, 0.0, v, t, TypeFlag::Call);
+        assert_approx_equal!(greeks.delta, delta, 1e-10);
+        assert_approx_equal!(greeks.gamma, black_scholes::gamma(s, k, r, 0.0, v, t), 1e-6);
+        assert_approx_equal!(greeks.vega, black_scholes::vega(s, k, r, 0.0, v, t), 1e-9);
+    }
+
+    #[test]
+    fn test_hyper_dual_backend_falls_back_to_tape() {
+        let pricer = BlackScholesCall {
+            strike: 95.0,
+            rate: 0.05,
+            time: 0.5,
+        };
+
+        assert_eq!(
+            AutodiffGreeks::compute_with_backend(&pricer, 100.0, 0.25, GreeksBackend::HyperDual),
+            AutodiffGreeks::compute(&pricer, 100.0, 0.25)
+        );
+    }
+}
@@ -0,0 +1,34 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Position risk: Greeks, curve risk, Value-at-Risk, and risk reports.
+
+/// Cornish-Fisher (modified) Value-at-Risk.
+pub mod cornish_fisher;
+pub use cornish_fisher::*;
+
+/// Copula-based portfolio Conditional Value-at-Risk.
+pub mod copula_cvar;
+pub use copula_cvar::*;
+
+/// Key-rate DV01s and principal component curve risk.
+pub mod curve_risk;
+pub use curve_risk::*;
+
+/// Greeks of a pricing function via automatic differentiation.
+pub mod greeks;
+pub use greeks::*;
+
+/// Position risk report generation.
+pub mod position_report;
+pub use position_report::*;
+
+/// Parametric and historical Value-at-Risk.
+pub mod var;
+pub use var::*;
@@ -0,0 +1,442 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Position risk report.
+//!
+//! For each position, the report contains the market value, the position
+//! Greeks (computed with [`AutodiffGreeks`]), and the 1-day 95% parametric
+//! VaR from a delta-vega approximation of the P&L.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::{delta_vega_pnl_volatility, parametric_var, AutodiffGreeks, Pricer};
+use crate::error::RustQuantError;
+use crate::pricer::{AuditCollector, AuditRecord, MarketDataId};
+use std::collections::HashMap;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// A position in an instrument on some underlying.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Position {
+    /// Symbol of the instrument.
+    pub symbol: String,
+    /// Symbol of the underlying, used to look up market data.
+    pub underlying: String,
+    /// Signed quantity (negative when short).
+    pub quantity: f64,
+}
+
+/// Market data for one underlying.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarketQuote {
+    /// Spot price.
+    pub spot: f64,
+    /// Implied volatility (annualized).
+    pub implied_volatility: f64,
+    /// Realized volatility of the spot (annualized).
+    pub realized_volatility: f64,
+    /// Volatility of the implied volatility (annualized, lognormal).
+    pub volatility_of_volatility: f64,
+}
+
+/// Snapshot of market data, by underlying symbol.
+#[derive(Debug, Clone, Default)]
+pub struct MarketDataSnapshot {
+    /// Quotes by underlying symbol.
+    pub quotes: HashMap<String, MarketQuote>,
+}
+
+/// Risk of a single position (or the total of a report).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionRisk {
+    /// Symbol of the instrument.
+    pub symbol: String,
+    /// Signed quantity.
+    pub quantity: f64,
+    /// Market value: quantity times unit price.
+    pub market_value: f64,
+    /// Position delta.
+    pub delta: f64,
+    /// Position gamma.
+    pub gamma: f64,
+    /// Position vega.
+    pub vega: f64,
+    /// 1-day 95% parametric Value-at-Risk (a positive number).
+    pub var_1day_95: f64,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS, FUNCTIONS, AND MACROS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl MarketDataSnapshot {
+    /// Create a new, empty, snapshot.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add (or replace) the quote of an underlying.
+    pub fn insert(&mut self, underlying: &str, quote: MarketQuote) {
+        self.quotes.insert(underlying.to_string(), quote);
+    }
+
+    /// Quote of an underlying.
+    #[must_use]
+    pub fn get(&self, underlying: &str) -> Option<&MarketQuote> {
+        self.quotes.get(underlying)
+    }
+}
+
+/// Generate the risk report of a set of positions.
+///
+/// `pricers[i]` prices one unit of the instrument in `positions[i]`.
+///
+/// # Errors
+/// - `RustQuantError::UnequalLength` if there is not one pricer per position.
+/// - `RustQuantError::MissingInput` if a position's underlying has no market data.
+pub fn generate_report(
+    positions: &[Position],
+    pricers: &[Box<dyn Pricer>],
+    market_data: &MarketDataSnapshot,
+) -> Result<Vec<PositionRisk>, RustQuantError> {
+    report(positions, pricers, market_data, None)
+}
+
+/// Generate the risk report of a set of positions, as in
+/// [`generate_report`], recording one [`AuditRecord`] per position in
+/// `collector`.
+///
+/// Each record is the pricer's own record at the quoted spot and implied
+/// volatility ([`Pricer::audit_record`]), or the spot, volatility, and
+/// price if it has none. The symbol, underlying, quantity, the quote's
+/// [`MarketDataId`], the Greeks, the market value, and the VaR are added.
+///
+/// # Errors
+/// - `RustQuantError::UnequalLength` if there is not one pricer per position.
+/// - `RustQuantError::MissingInput` if a position's underlying has no market data.
+pub fn generate_report_audited(
+    positions: &[Position],
+    pricers: &[Box<dyn Pricer>],
+    market_data: &MarketDataSnapshot,
+    collector: &mut AuditCollector,
+) -> Result<Vec<PositionRisk>, RustQuantError> {
+    report(positions, pricers, market_data, Some(collector))
+}
+
+fn report(
+    positions: &[Position],
+    pricers: &[Box<dyn Pricer>],
+    market_data: &MarketDataSnapshot,
+    mut collector: Option<&mut AuditCollector>,
+) -> Result<Vec<PositionRisk>, RustQuantError> {
+    if positions.len() != pricers.len() {
+        return Err(RustQuantError::UnequalLength);
+    }
+
+    positions
+        .iter()
+        .zip(pricers)
+        .map(|(position, pricer)| {
+            let quote = market_data.get(&position.underlying).ok_or_else(|| {
+                RustQuantError::MissingInput(format!(
+                    "No market data for underlying '{}'.",
+                    position.underlying
+                ))
+            })?;
+
+            let greeks =
+                AutodiffGreeks::compute(pricer.as_ref(), quote.spot, quote.implied_volatility);
+
+            let quantity = position.quantity;
+            let delta = quantity * greeks.delta;
+            let vega = quantity * greeks.vega;
+
+            let pnl_volatility = delta_vega_pnl_volatility(
+                delta,
+                vega,
+                quote.spot,
+                quote.realized_volatility,
+                quote.implied_volatility,
+                quote.volatility_of_volatility,
+                1.0,
+            );
+
+            let risk = PositionRisk {
+                symbol: position.symbol.clone(),
+                quantity,
+                market_value: quantity * greeks.price,
+                delta,
+                gamma: quantity * greeks.gamma,
+                vega,
+                var_1day_95: parametric_var(pnl_volatility, 0.95),
+            };
+
+            if let Some(collector) = collector.as_deref_mut() {
+                let record = pricer
+                    .audit_record(quote.spot, quote.implied_volatility)
+                    .unwrap_or_else(|| {
+                        AuditRecord::new("Pricer")
+                            .with_parameter("spot", quote.spot)
+                            .with_parameter("volatility", quote.implied_volatility)
+                            .with_result(greeks.price)
+                    });
+
+                collector.record(
+                    record
+                        .with_label("symbol", &position.symbol)
+                        .with_label("underlying", &position.underlying)
+                        .with_parameter("quantity", quantity)
+                        .with_market_data(MarketDataId::new(
+                            &position.underlying,
+                            &[
+                                quote.spot,
+                                quote.implied_volatility,
+                                quote.realized_volatility,
+                                quote.volatility_of_volatility,
+                            ],
+                        ))
+                        .with_greeks(&greeks)
+                        .with_output("market_value", risk.market_value)
+                        .with_output("var_1day_95", risk.var_1day_95),
+                );
+            }
+
+            Ok(risk)
+        })
+        .collect()
+}
+
+/// Sum of a risk report.
+///
+/// The VaR of the total is the sum of the position VaRs,
+/// i.e. it ignores any diversification between positions.
+#[must_use]
+pub fn report_totals(report: &[PositionRisk]) -> PositionRisk {
+    report.iter().fold(
+        PositionRisk {
+            symbol: "TOTAL".to_string(),
+            quantity: 0.0,
+            market_value: 0.0,
+            delta: 0.0,
+            gamma: 0.0,
+            vega: 0.0,
+            var_1day_95: 0.0,
+        },
+        |total, risk| PositionRisk {
+            quantity: total.quantity + risk.quantity,
+            market_value: total.market_value + risk.market_value,
+            delta: total.delta + risk.delta,
+            gamma: total.gamma + risk.gamma,
+            vega: total.vega + risk.vega,
+            var_1day_95: total.var_1day_95 + risk.var_1day_95,
+            ..total
+        },
+    )
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_position_report {
+    use super::*;
+    use crate::autodiff::Variable;
+    use crate::trading::risk::TRADING_DAYS_PER_YEAR;
+
+    // Mock pricer with known Greeks around any point:
+    // price = a * S + b * S^2 / 2 + c * sigma
+    // delta = a + b * S, gamma = b, vega = c.
+    struct MockPricer {
+        a: f64,
+        b: f64,
+        c: f64,
+    }
+
+    impl Pricer for MockPricer {
+        fn price<'v>(&self, spot: Variable<'v>, volatility: Variable<'v>) -> Variable<'v> {
+            self.a * spot + 0.5 * self.b * spot * spot + self.c * volatility
+        }
+    }
+
+    fn snapshot() -> MarketDataSnapshot {
+        let mut snapshot = MarketDataSnapshot::new();
+
+        snapshot.insert(
+            "AAA",
+            MarketQuote {
+                spot: 100.0,
+                implied_volatility: 0.2,
+                realized_volatility: 0.25,
+                volatility_of_volatility: 0.0,
+            },
+        );
+        snapshot.insert(
+            "BBB",
+            MarketQuote {
+                spot: 50.0,
+                implied_volatility: 0.3,
+                realized_volatility: 0.3,
+                volatility_of_volatility: 0.8,
+            },
+        );
+
+        snapshot
+    }
+
+    #[test]
+    fn test_generate_report() -> Result<(), RustQuantError> {
+        let positions = vec![
+            Position {
+                symbol: "AAA".to_string(),
+                underlying: "AAA".to_string(),
+                quantity: 100.0,
+            },
+            Position {
+                symbol: "BBB_OPTION".to_string(),
+                underlying: "BBB".to_string(),
+                quantity: -10.0,
+            },
+        ];
+        let pricers: Vec<Box<dyn Pricer>> = vec![
+            // A share: price = S.
+            Box::new(MockPricer {
+                a: 1.0,
+                b: 0.0,
+                c: 0.0,
+            }),
+            // An option-like payoff with delta 0.5 + 0.01 * 50 = 1.0, gamma 0.01, vega 20.
+            Box::new(MockPricer {
+                a: 0.5,
+                b: 0.01,
+                c: 20.0,
+            }),
+        ];
+
+        let report = generate_report(&positions, &pricers, &snapshot())?;
+        let z = 1.644_853_626_951_472_2;
+        let sqrt_dt = (1.0 / TRADING_DAYS_PER_YEAR).sqrt();
+
+        // Shares: delta 100, VaR = z * 100 * 100 * 25% * sqrt(1/252).
+        assert_approx_equal!(report[0].market_value, 10_000.0, 1e-9);
+        assert_approx_equal!(report[0].delta, 100.0, 1e-9);
+        assert_approx_equal!(report[0].gamma, 0.0, 1e-6);
+        assert_approx_equal!(report[0].vega, 0.0, 1e-9);
+        assert_approx_equal!(report[0].var_1day_95, z * 2_500.0 * sqrt_dt, 1e-8);
+
+        // Short 10 options.
+        let unit_price = 0.5 * 50.0 + 0.5 * 0.01 * 2_500.0 + 20.0 * 0.3;
+        let spot_term: f64 = -10.0 * 1.0 * 50.0 * 0.3;
+        let vol_term = -10.0 * 20.0 * 0.3 * 0.8;
+
+        assert_approx_equal!(report[1].market_value, -10.0 * unit_price, 1e-9);
+        assert_approx_equal!(report[1].delta, -10.0, 1e-9);
+        assert_approx_equal!(report[1].gamma, -0.1, 1e-6);
+        assert_approx_equal!(report[1].vega, -200.0, 1e-9);
+        assert_approx_equal!(
+            report[1].var_1day_95,
+            z * spot_term.hypot(vol_term) * sqrt_dt,
+            1e-8
+        );
+
+        // Totals.
+        let totals = report_totals(&report);
+        assert_eq!(totals.symbol, "TOTAL");
+        assert_approx_equal!(totals.market_value, 10_000.0 - 10.0 * unit_price, 1e-9);
+        assert_approx_equal!(totals.delta, 90.0, 1e-9);
+        assert_approx_equal!(totals.gamma, -0.1, 1e-6);
+        assert_approx_equal!(totals.vega, -200.0, 1e-9);
+        assert_approx_equal!(
+            totals.var_1day_95,
+            report[0].var_1day_95 + report[1].var_1day_95,
+            1e-9
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_report_audited() -> Result<(), RustQuantError> {
+        use crate::instruments::options::{BlackScholesMerton, TypeFlag};
+
+        let evaluation_date = time::macros::date!(2024 - 06 - 03);
+        let positions = vec![
+            Position {
+                symbol: "AAA".to_string(),
+                underlying: "AAA".to_string(),
+                quantity: 100.0,
+            },
+            Position {
+                symbol: "BBB_CALL".to_string(),
+                underlying: "BBB".to_string(),
+                quantity: -10.0,
+            },
+        ];
+        let pricers: Vec<Box<dyn Pricer>> = vec![
+            Box::new(MockPricer {
+                a: 1.0,
+                b: 0.0,
+                c: 0.0,
+            }),
+            // Spot and volatility come from the snapshot.
+            Box::new(BlackScholesMerton::new(
+                0.01.into(),
+                0.0.into(),
+                55.0.into(),
+                0.0.into(),
+                0.01.into(),
+                Some(evaluation_date),
+                evaluation_date + time::Duration::days(91),
+                TypeFlag::Call,
+            )),
+        ];
+
+        let mut collector = AuditCollector::new();
+        let report = generate_report_audited(&positions, &pricers, &snapshot(), &mut collector)?;
+        let records = collector.into_records();
+
+        assert_eq!(report, generate_report(&positions, &pricers, &snapshot())?);
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].model, "Pricer");
+        assert_eq!(records[1].label("symbol")?, "BBB_CALL");
+        assert_eq!(records[1].market_data[0].id, "BBB");
+        assert_eq!(records[1].outputs["market_value"], report[1].market_value);
+
+        // The option record replays at the quoted spot and volatility.
+        let option = BlackScholesMerton::from_audit_record(&records[1])?;
+        assert_eq!(option.underlying_price, 50.0);
+        assert_eq!(option.volatility, 0.3);
+        assert_eq!(option.price().to_bits(), records[1].result.to_bits());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_market_data() {
+        let positions = vec![Position {
+            symbol: "CCC".to_string(),
+            underlying: "CCC".to_string(),
+            quantity: 1.0,
+        }];
+        let pricers: Vec<Box<dyn Pricer>> = vec![Box::new(MockPricer {
+            a: 1.0,
+            b: 0.0,
+            c: 0.0,
+        })];
+
+        assert!(generate_report(&positions, &pricers, &snapshot()).is_err());
+        assert!(generate_report(&positions, &[], &snapshot()).is_err());
+    }
+}
@@ -0,0 +1,119 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Parametric (variance-covariance) Value-at-Risk.
+
+use crate::math::{Distribution, Gaussian};
+
+/// Number of trading days per year, used to scale annualized volatilities.
+pub const TRADING_DAYS_PER_YEAR: f64 = 252.0;
+
+/// Parametric (Gaussian, zero-mean) Value-at-Risk of a P&L with the given
+/// standard deviation, at the given confidence level (e.g. `0.95`).
+///
+/// The VaR is reported as a positive number (a loss).
+///
+/// # Panics
+/// Panics if `confidence` is not in $(0, 1)$.
+#[must_use]
+pub fn parametric_var(pnl_volatility: f64, confidence: f64) -> f64 {
+    assert!(
+        confidence > 0.0 && confidence < 1.0,
+        "Confidence must be in (0, 1)."
+    );
+
+    Gaussian::default().inv_cdf(confidence) * pnl_volatility.abs()
+}
+
+/// Historical (non-parametric) Value-at-Risk of a sample of P&L, at the
+/// given confidence level (e.g. `0.95`): minus the empirical
+/// $(1 - c)$-quantile, linearly interpolated between order statistics.
+///
+/// The VaR is reported as a positive number (a loss).
+///
+/// # Panics
+/// Panics if `pnl` is empty or `confidence` is not in $(0, 1)$.
+#[must_use]
+pub fn historical_var(pnl: &[f64], confidence: f64) -> f64 {
+    assert!(!pnl.is_empty(), "P&L sample must not be empty.");
+    assert!(
+        confidence > 0.0 && confidence < 1.0,
+        "Confidence must be in (0, 1)."
+    );
+
+    let mut sorted = pnl.to_vec();
+    sorted.sort_by(f64::total_cmp);
+
+    let position = (1.0 - confidence) * (sorted.len() - 1) as f64;
+    let (lower, weight) = (position.floor() as usize, position.fract());
+    let upper = (lower + 1).min(sorted.len() - 1);
+
+    -(sorted[lower] + weight * (sorted[upper] - sorted[lower]))
+}
+
+/// Standard deviation of the P&L of a position over `horizon_days`,
+/// from a first-order (delta-vega) approximation:
+///
+/// $$
+/// \sigma_{P\&L} = \sqrt{(\Delta S \sigma_S)^2 + (\nu \sigma_{IV} \xi)^2} \sqrt{\frac{h}{252}}
+/// $$
+///
+/// where:
+/// - $\Delta$ and $\nu$ are the position's delta and vega,
+/// - $S$ and $\sigma_S$ are the spot and the realized (annualized) volatility of the underlying,
+/// - $\sigma_{IV}$ and $\xi$ are the implied volatility and its (annualized, lognormal) volatility.
+///
+/// Spot and volatility moves are assumed to be uncorrelated.
+#[must_use]
+pub fn delta_vega_pnl_volatility(
+    delta: f64,
+    vega: f64,
+    spot: f64,
+    realized_volatility: f64,
+    implied_volatility: f64,
+    volatility_of_volatility: f64,
+    horizon_days: f64,
+) -> f64 {
+    let spot_term = delta * spot * realized_volatility;
+    let vol_term = vega * implied_volatility * volatility_of_volatility;
+
+    spot_term.hypot(vol_term) * (horizon_days / TRADING_DAYS_PER_YEAR).sqrt()
+}
+
+#[cfg(test)]
+mod tests_var {
+    use super::*;
+
+    #[test]
+    fn test_parametric_var() {
+        assert_approx_equal!(parametric_var(1.0, 0.95), 1.644_853_626_951_472_2, 1e-12);
+        assert_approx_equal!(
+            parametric_var(-2.0, 0.99),
+            2.0 * 2.326_347_874_040_841,
+            1e-12
+        );
+    }
+
+    #[test]
+    fn test_historical_var() {
+        let pnl: Vec<f64> = (0..=100).map(|i| f64::from(i) - 50.0).collect();
+
+        // 5% quantile of -50, ..., 50 is -45.
+        assert_approx_equal!(historical_var(&pnl, 0.95), 45.0, 1e-12);
+        assert_approx_equal!(historical_var(&pnl, 0.995), 49.5, 1e-12);
+    }
+
+    #[test]
+    fn test_delta_only_pnl_volatility() {
+        // 100 shares of a 50 stock with 20% vol: 1000 * 0.2 / sqrt(252).
+        let sigma = delta_vega_pnl_volatility(100.0, 0.0, 50.0, 0.2, 0.2, 0.5, 1.0);
+
+        assert_approx_equal!(sigma, 1_000.0 / TRADING_DAYS_PER_YEAR.sqrt(), 1e-12);
+    }
+}
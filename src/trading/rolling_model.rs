@@ -0,0 +1,446 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Periodic refitting of a model during a backtest, without lookahead.
+//!
+//! A [`RollingModel`] sees the bars one at a time. Every `refit_every`
+//! bars (once enough bars have been seen) it refits its
+//! [`RollingEstimator`] on a trailing or expanding window of the bars up to
+//! and including the current one, and it predicts with the most recently
+//! fitted model only. There is no prediction before the first fit.
+//!
+//! Each fitted model is scored out of sample, on the bars it predicted
+//! until it was replaced. [`Backtest::run_rolling`] drives a
+//! [`RollingStrategy`], which turns the predictions into orders, and
+//! reports the refits with the backtest result.
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use super::backtest::{Backtest, BacktestOrder, BacktestResult, Strategy};
+use crate::data::Bar;
+use crate::error::RustQuantError;
+use rand::{rngs::StdRng, SeedableRng};
+use time::OffsetDateTime;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// A model fitted to a window of bars, which predicts from a bar.
+pub trait RollingEstimator {
+    /// Fits the model to `window` (oldest bar first). Randomized models
+    /// should draw from `rng`, so results are reproducible.
+    ///
+    /// # Errors
+    /// Any error from fitting, which stops the backtest.
+    fn fit(&mut self, window: &[Bar], rng: &mut StdRng) -> Result<(), RustQuantError>;
+
+    /// Prediction from `bar` (e.g. of the next bar's return).
+    fn predict(&self, bar: &Bar) -> f64;
+
+    /// Out-of-sample score of the fitted model on `bars` (e.g. the mean
+    /// squared error of its predictions).
+    fn score(&self, bars: &[Bar]) -> f64;
+}
+
+/// Training window of a [`RollingModel`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrainingWindow {
+    /// The last `n` bars.
+    Trailing(usize),
+    /// All bars so far, once there are at least `n`.
+    Expanding(usize),
+}
+
+/// A refit of a [`RollingModel`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RefitRecord {
+    /// Index of the bar at whose close the model was refitted.
+    pub bar: usize,
+    /// End of that bar: the model saw no data after it.
+    pub timestamp: OffsetDateTime,
+    /// Index of the first bar of the training window.
+    pub window_start: usize,
+    /// Score of the model on the bars it predicted, after this one until
+    /// the next refit (`None` until it is replaced or the run ends, or if
+    /// it predicted no later bars).
+    pub validation_score: Option<f64>,
+}
+
+/// An estimator refitted periodically on a rolling window of bars.
+#[derive(Debug, Clone)]
+pub struct RollingModel<E: RollingEstimator> {
+    /// The estimator.
+    estimator: E,
+    /// Training window.
+    window: TrainingWindow,
+    /// Number of bars between refits.
+    refit_every: usize,
+    /// Random number generator passed to the estimator.
+    rng: StdRng,
+    /// Bars seen so far.
+    history: Vec<Bar>,
+    /// Refits so far.
+    refits: Vec<RefitRecord>,
+}
+
+/// A strategy trading on the predictions of a [`RollingModel`].
+///
+/// `rule` turns the prediction for each bar (`None` before the first fit)
+/// into orders.
+pub struct RollingStrategy<E: RollingEstimator, F> {
+    /// The model.
+    pub model: RollingModel<E>,
+    /// Trading rule.
+    rule: F,
+    /// Prediction for each bar so far.
+    predictions: Vec<Option<f64>>,
+    /// First error from the model, which stops the backtest.
+    error: Option<RustQuantError>,
+}
+
+/// Result of [`Backtest::run_rolling`].
+#[derive(Debug, Clone)]
+pub struct RollingBacktestResult {
+    /// Backtest result.
+    pub backtest: BacktestResult,
+    /// Prediction for each bar, `None` before the first refit.
+    pub predictions: Vec<Option<f64>>,
+    /// Refits, with their out-of-sample validation scores.
+    pub refits: Vec<RefitRecord>,
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+impl TrainingWindow {
+    /// Number of bars needed before the first fit.
+    #[must_use]
+    pub fn min_bars(&self) -> usize {
+        match *self {
+            Self::Trailing(n) | Self::Expanding(n) => n.max(1),
+        }
+    }
+}
+
+impl<E: RollingEstimator> RollingModel<E> {
+    /// New rolling model, refitting `estimator` every `refit_every` bars on
+    /// `window`, with the random number generator seeded by `seed`.
+    ///
+    /// # Panics
+    /// Panics if `refit_every` is zero.
+    #[must_use]
+    pub fn new(estimator: E, window: TrainingWindow, refit_every: usize, seed: u64) -> Self {
+        assert!(refit_every > 0, "Must refit at least every bar.");
+
+        Self {
+            estimator,
+            window,
+            refit_every,
+            rng: StdRng::seed_from_u64(seed),
+            history: Vec::new(),
+            refits: Vec::new(),
+        }
+    }
+
+    /// Feeds the next bar, refitting if due, and returns the prediction of
+    /// the most recently fitted model for it (`None` before the first fit).
+    ///
+    /// # Errors
+    /// Any error from fitting the estimator.
+    pub fn update(&mut self, bar: &Bar) -> Result<Option<f64>, RustQuantError> {
+        self.history.push(*bar);
+        let index = self.history.len() - 1;
+
+        let due = match self.refits.last() {
+            None => self.history.len() >= self.window.min_bars(),
+            Some(last) => index - last.bar >= self.refit_every,
+        };
+
+        if due {
+            self.close_validation(index);
+
+            let window_start = match self.window {
+                TrainingWindow::Trailing(n) => self.history.len().saturating_sub(n),
+                TrainingWindow::Expanding(_) => 0,
+            };
+            self.estimator
+                .fit(&self.history[window_start..], &mut self.rng)?;
+
+            self.refits.push(RefitRecord {
+                bar: index,
+                timestamp: bar.end,
+                window_start,
+                validation_score: None,
+            });
+        }
+
+        Ok((!self.refits.is_empty()).then(|| self.estimator.predict(bar)))
+    }
+
+    /// Scores the current model on the bars it has predicted since it was
+    /// fitted. Called at the end of a run.
+    pub fn finish(&mut self) {
+        self.close_validation(self.history.len());
+    }
+
+    /// Refits so far.
+    #[must_use]
+    pub fn refits(&self) -> &[RefitRecord] {
+        &self.refits
+    }
+
+    /// The estimator, as last fitted.
+    #[must_use]
+    pub fn estimator(&self) -> &E {
+        &self.estimator
+    }
+
+    /// Scores the current model on the bars after its fit and before `end`.
+    fn close_validation(&mut self, end: usize) {
+        if let Some(last) = self.refits.last_mut() {
+            if last.validation_score.is_none() && last.bar + 1 < end {
+                last.validation_score =
+                    Some(self.estimator.score(&self.history[last.bar + 1..end]));
+            }
+        }
+    }
+}
+
+impl<E, F> RollingStrategy<E, F>
+where
+    E: RollingEstimator,
+    F: FnMut(Option<f64>, &Bar) -> Vec<BacktestOrder>,
+{
+    /// New strategy trading on `model` with `rule`.
+    #[must_use]
+    pub fn new(model: RollingModel<E>, rule: F) -> Self {
+        Self {
+            model,
+            rule,
+            predictions: Vec::new(),
+            error: None,
+        }
+    }
+}
+
+impl<E, F> Strategy for RollingStrategy<E, F>
+where
+    E: RollingEstimator,
+    F: FnMut(Option<f64>, &Bar) -> Vec<BacktestOrder>,
+{
+    fn on_bar(&mut self, bar: &Bar) -> Vec<BacktestOrder> {
+        if self.error.is_some() {
+            self.predictions.push(None);
+            return vec![];
+        }
+
+        match self.model.update(bar) {
+            Ok(prediction) => {
+                self.predictions.push(prediction);
+                (self.rule)(prediction, bar)
+            }
+            Err(error) => {
+                self.error = Some(error);
+                self.predictions.push(None);
+                vec![]
+            }
+        }
+    }
+}
+
+impl Backtest {
+    /// Run a [`RollingStrategy`] over `bars`, reporting its predictions and
+    /// refits with the result.
+    ///
+    /// # Errors
+    /// - `InvalidArgument` if a bar does not have a positive, finite close.
+    /// - Any error from fitting the model.
+    pub fn run_rolling<E, F>(
+        &self,
+        strategy: &mut RollingStrategy<E, F>,
+        bars: &[Bar],
+    ) -> Result<RollingBacktestResult, RustQuantError>
+    where
+        E: RollingEstimator,
+        F: FnMut(Option<f64>, &Bar) -> Vec<BacktestOrder>,
+    {
+        let backtest = self.run(strategy, bars)?;
+
+        if let Some(error) = strategy.error.take() {
+            return Err(error);
+        }
+        strategy.model.finish();
+
+        Ok(RollingBacktestResult {
+            backtest,
+            predictions: std::mem::take(&mut strategy.predictions),
+            refits: strategy.model.refits().to_vec(),
+        })
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_rolling_model {
+    use super::*;
+    use rand::Rng;
+    use time::Duration;
+
+    /// Predicts the next return as a bootstrapped mean of the window's
+    /// returns, and records the last bar it was fitted on.
+    #[derive(Default)]
+    struct InstrumentedMomentum {
+        mean: f64,
+        max_seen: Vec<OffsetDateTime>,
+    }
+
+    impl RollingEstimator for InstrumentedMomentum {
+        fn fit(&mut self, window: &[Bar], rng: &mut StdRng) -> Result<(), RustQuantError> {
+            let returns: Vec<f64> = window
+                .windows(2)
+                .map(|w| w[1].close / w[0].close - 1.0)
+                .collect();
+            let draws = 20;
+
+            self.mean = (0..draws)
+                .map(|_| returns[rng.gen_range(0..returns.len())])
+                .sum::<f64>()
+                / draws as f64;
+            self.max_seen
+                .push(window.iter().map(|bar| bar.end).max().unwrap());
+
+            Ok(())
+        }
+
+        fn predict(&self, _: &Bar) -> f64 {
+            self.mean
+        }
+
+        fn score(&self, bars: &[Bar]) -> f64 {
+            let errors: Vec<f64> = bars
+                .windows(2)
+                .map(|w| (w[1].close / w[0].close - 1.0 - self.mean).powi(2))
+                .collect();
+
+            errors.iter().sum::<f64>() / errors.len().max(1) as f64
+        }
+    }
+
+    fn bars(n: usize) -> Vec<Bar> {
+        let start = OffsetDateTime::UNIX_EPOCH;
+
+        (0..n)
+            .map(|i| {
+                let close = 100.0 + 10.0 * (i as f64 / 5.0).sin() + 0.1 * i as f64;
+                Bar {
+                    start: start + Duration::days(i as i64),
+                    end: start + Duration::days(i as i64 + 1),
+                    open: close,
+                    high: close,
+                    low: close,
+                    close,
+                    volume: 0.0,
+                    dollar_volume: 0.0,
+                    trade_count: 0,
+                }
+            })
+            .collect()
+    }
+
+    fn strategy(
+        window: TrainingWindow,
+        seed: u64,
+    ) -> RollingStrategy<InstrumentedMomentum, impl FnMut(Option<f64>, &Bar) -> Vec<BacktestOrder>>
+    {
+        let model = RollingModel::new(InstrumentedMomentum::default(), window, 5, seed);
+        let mut long = false;
+
+        RollingStrategy::new(model, move |prediction: Option<f64>, _: &Bar| {
+            match (prediction, long) {
+                (Some(p), false) if p > 0.0 => {
+                    long = true;
+                    vec![BacktestOrder::buy(1)]
+                }
+                (Some(p), true) if p <= 0.0 => {
+                    long = false;
+                    vec![BacktestOrder::sell(1)]
+                }
+                _ => vec![],
+            }
+        })
+    }
+
+    #[test]
+    fn test_no_predictions_before_first_refit() {
+        let result = Backtest::new(1_000.0)
+            .run_rolling(&mut strategy(TrainingWindow::Trailing(10), 1), &bars(60))
+            .unwrap();
+
+        assert_eq!(result.predictions.len(), 60);
+        assert!(result.predictions[..9].iter().all(Option::is_none));
+        assert!(result.predictions[9..].iter().all(Option::is_some));
+
+        let refit_bars: Vec<usize> = result.refits.iter().map(|refit| refit.bar).collect();
+        assert_eq!(refit_bars, [9, 14, 19, 24, 29, 34, 39, 44, 49, 54, 59]);
+        assert!(result
+            .refits
+            .iter()
+            .all(|refit| refit.window_start + 10 == refit.bar + 1));
+
+        // Every model but the last predicted later bars, and was scored.
+        let scored = result
+            .refits
+            .iter()
+            .filter(|refit| refit.validation_score.is_some());
+        assert_eq!(scored.count(), result.refits.len() - 1);
+    }
+
+    #[test]
+    fn test_refits_never_see_future_bars() {
+        let bars = bars(80);
+        for window in [TrainingWindow::Trailing(15), TrainingWindow::Expanding(15)] {
+            let mut strategy = strategy(window, 2);
+            let result = Backtest::new(1_000.0)
+                .run_rolling(&mut strategy, &bars)
+                .unwrap();
+
+            let max_seen = &strategy.model.estimator().max_seen;
+            assert_eq!(max_seen.len(), result.refits.len());
+
+            for (seen, refit) in max_seen.iter().zip(&result.refits) {
+                assert_eq!(*seen, refit.timestamp);
+                assert_eq!(refit.timestamp, bars[refit.bar].end);
+            }
+        }
+    }
+
+    #[test]
+    fn test_reproducible_with_seed() {
+        let run = |seed| {
+            Backtest::new(1_000.0)
+                .run_rolling(
+                    &mut strategy(TrainingWindow::Expanding(10), seed),
+                    &bars(100),
+                )
+                .unwrap()
+        };
+        let (first, second) = (run(7), run(7));
+
+        assert_eq!(first.predictions, second.predictions);
+        assert_eq!(first.refits, second.refits);
+        assert_eq!(first.backtest.equity_curve, second.backtest.equity_curve);
+    }
+}
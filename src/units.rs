@@ -0,0 +1,450 @@
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// RustQuant: A Rust library for quantitative finance tools.
+// Copyright (C) 2023 https://github.com/avhz
+// Dual licensed under Apache 2.0 and MIT.
+// See:
+//      - LICENSE-APACHE.md
+//      - LICENSE-MIT.md
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+//! Units of measure for the inputs of pricing models.
+//!
+//! [`Rate`], [`Volatility`], [`Price`], [`DividendYield`], and
+//! [`TimeFraction`] are thin wrappers around `f64`, so that the option and
+//! bond constructors cannot be passed a volatility where a rate is
+//! expected:
+//!
+//! ```compile_fail,E0308
+//! # use RustQuant::instruments::options::{BlackScholesMerton, TypeFlag};
+//! # use RustQuant::units::{Price, Rate, Volatility};
+//! # use time::macros::date;
+//! let volatility = Volatility::new(0.2).unwrap();
+//! let rate = Rate::new(0.05).unwrap();
+//!
+//! // Volatility and rate swapped: does not compile.
+//! let option = BlackScholesMerton::new(
+//!     rate,
+//!     Price::from(100.0),
+//!     Price::from(100.0),
+//!     rate,
+//!     volatility,
+//!     None,
+//!     date!(2025 - 01 - 01),
+//!     TypeFlag::Call,
+//! );
+//! ```
+//!
+//! The `new` constructors check the range of the value, and `From<f64>`
+//! wraps a value unchecked, so existing code only needs an `.into()`.
+//! Rates, volatilities, and dividend yields also parse from percentages
+//! and basis points:
+//!
+//! ```
+//! # use RustQuant::units::{Rate, Volatility};
+//! let rate: Rate = "25bp".parse().unwrap();
+//! let volatility: Volatility = "20%".parse().unwrap();
+//!
+//! assert_eq!(rate.value(), 0.0025);
+//! assert_eq!(volatility.value(), 0.2);
+//! ```
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPORTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+use crate::error::RustQuantError;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// STRUCTS, ENUMS, AND TRAITS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Interest rate, as a decimal (0.05 is 5%).
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Rate(f64);
+
+/// Volatility, annualised, as a decimal (0.2 is 20%).
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Volatility(f64);
+
+/// Price of an asset or instrument.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Price(f64);
+
+/// Continuous dividend yield, as a decimal (0.02 is 2%).
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct DividendYield(f64);
+
+/// Time, in years.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct TimeFraction(f64);
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// IMPLEMENTATIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Conversions, formatting, and arithmetic shared by the units.
+macro_rules! impl_unit {
+    ($unit:ident) => {
+        impl $unit {
+            /// Wraps `value` without checking its range.
+            #[must_use]
+            pub const fn new_unchecked(value: f64) -> Self {
+                Self(value)
+            }
+
+            /// The value, as an `f64`.
+            #[must_use]
+            pub const fn value(self) -> f64 {
+                self.0
+            }
+        }
+
+        impl From<f64> for $unit {
+            /// Wraps `value` without checking its range.
+            fn from(value: f64) -> Self {
+                Self(value)
+            }
+        }
+
+        impl From<$unit> for f64 {
+            fn from(value: $unit) -> Self {
+                value.0
+            }
+        }
+
+        impl fmt::Display for $unit {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(&self.0, f)
+            }
+        }
+
+        impl Add for $unit {
+            type Output = Self;
+
+            fn add(self, rhs: Self) -> Self {
+                Self(self.0 + rhs.0)
+            }
+        }
+
+        impl Sub for $unit {
+            type Output = Self;
+
+            fn sub(self, rhs: Self) -> Self {
+                Self(self.0 - rhs.0)
+            }
+        }
+
+        impl Neg for $unit {
+            type Output = Self;
+
+            fn neg(self) -> Self {
+                Self(-self.0)
+            }
+        }
+
+        impl Mul<f64> for $unit {
+            type Output = Self;
+
+            fn mul(self, rhs: f64) -> Self {
+                Self(self.0 * rhs)
+            }
+        }
+
+        impl Mul<$unit> for f64 {
+            type Output = $unit;
+
+            fn mul(self, rhs: $unit) -> $unit {
+                $unit(self * rhs.0)
+            }
+        }
+
+        impl Div<f64> for $unit {
+            type Output = Self;
+
+            fn div(self, rhs: f64) -> Self {
+                Self(self.0 / rhs)
+            }
+        }
+    };
+}
+
+impl_unit!(Rate);
+impl_unit!(Volatility);
+impl_unit!(Price);
+impl_unit!(DividendYield);
+impl_unit!(TimeFraction);
+
+impl Rate {
+    /// Rate of `value`.
+    ///
+    /// # Errors
+    /// `InvalidArgument` unless $|value| < 1$: use
+    /// [`Rate::new_unchecked`] for larger rates.
+    pub fn new(value: f64) -> Result<Self, RustQuantError> {
+        check_decimal("Rate", value).map(Self)
+    }
+
+    /// Rate of `percent` percent.
+    ///
+    /// # Errors
+    /// As [`Rate::new`].
+    pub fn from_percent(percent: f64) -> Result<Self, RustQuantError> {
+        Self::new(percent / 100.0)
+    }
+
+    /// Rate of `basis_points` basis points.
+    ///
+    /// # Errors
+    /// As [`Rate::new`].
+    pub fn from_basis_points(basis_points: f64) -> Result<Self, RustQuantError> {
+        Self::new(basis_points / 10_000.0)
+    }
+}
+
+impl Volatility {
+    /// Volatility of `value`.
+    ///
+    /// # Errors
+    /// `InvalidArgument` if `value` is negative or not finite.
+    pub fn new(value: f64) -> Result<Self, RustQuantError> {
+        if value.is_finite() && value >= 0.0 {
+            Ok(Self(value))
+        } else {
+            Err(RustQuantError::InvalidArgument(format!(
+                "Volatility must be finite and non-negative, got {value}."
+            )))
+        }
+    }
+
+    /// Volatility of `percent` percent.
+    ///
+    /// # Errors
+    /// As [`Volatility::new`].
+    pub fn from_percent(percent: f64) -> Result<Self, RustQuantError> {
+        Self::new(percent / 100.0)
+    }
+}
+
+impl Price {
+    /// Price of `value`.
+    ///
+    /// Prices may be negative (e.g. spreads), but must be finite.
+    ///
+    /// # Errors
+    /// `InvalidArgument` if `value` is not finite.
+    pub fn new(value: f64) -> Result<Self, RustQuantError> {
+        if value.is_finite() {
+            Ok(Self(value))
+        } else {
+            Err(RustQuantError::InvalidArgument(format!(
+                "Price must be finite, got {value}."
+            )))
+        }
+    }
+}
+
+impl DividendYield {
+    /// Dividend yield of `value`.
+    ///
+    /// # Errors
+    /// `InvalidArgument` unless $|value| < 1$: use
+    /// [`DividendYield::new_unchecked`] for larger yields.
+    pub fn new(value: f64) -> Result<Self, RustQuantError> {
+        check_decimal("Dividend yield", value).map(Self)
+    }
+
+    /// Dividend yield of `percent` percent.
+    ///
+    /// # Errors
+    /// As [`DividendYield::new`].
+    pub fn from_percent(percent: f64) -> Result<Self, RustQuantError> {
+        Self::new(percent / 100.0)
+    }
+}
+
+impl TimeFraction {
+    /// Time of `value` years.
+    ///
+    /// # Errors
+    /// `InvalidArgument` if `value` is negative or not finite.
+    pub fn new(value: f64) -> Result<Self, RustQuantError> {
+        if value.is_finite() && value >= 0.0 {
+            Ok(Self(value))
+        } else {
+            Err(RustQuantError::InvalidArgument(format!(
+                "Time fraction must be finite and non-negative, got {value}."
+            )))
+        }
+    }
+}
+
+/// Cost of carry $b = r - q$.
+impl Sub<DividendYield> for Rate {
+    type Output = Rate;
+
+    fn sub(self, rhs: DividendYield) -> Rate {
+        Rate(self.0 - rhs.0)
+    }
+}
+
+impl FromStr for Rate {
+    type Err = RustQuantError;
+
+    /// Parses a decimal (`"0.05"`), a percentage (`"5%"`), or basis points
+    /// (`"500bp"` or `"500bps"`).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(parse_decimal(s)?)
+    }
+}
+
+impl FromStr for Volatility {
+    type Err = RustQuantError;
+
+    /// Parses a decimal (`"0.2"`), a percentage (`"20%"`), or basis points.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(parse_decimal(s)?)
+    }
+}
+
+impl FromStr for DividendYield {
+    type Err = RustQuantError;
+
+    /// Parses a decimal (`"0.02"`), a percentage (`"2%"`), or basis points.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(parse_decimal(s)?)
+    }
+}
+
+impl FromStr for Price {
+    type Err = RustQuantError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(parse_number(s)?)
+    }
+}
+
+impl FromStr for TimeFraction {
+    type Err = RustQuantError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(parse_number(s)?)
+    }
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// FUNCTIONS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+/// Checks that the decimal `value` of `name` is less than one in magnitude.
+fn check_decimal(name: &str, value: f64) -> Result<f64, RustQuantError> {
+    if value.abs() < 1.0 {
+        Ok(value)
+    } else {
+        Err(RustQuantError::InvalidArgument(format!(
+            "{name} must be less than 100% in magnitude, got {value}."
+        )))
+    }
+}
+
+/// Parses a decimal, a percentage, or basis points.
+fn parse_decimal(s: &str) -> Result<f64, RustQuantError> {
+    let s = s.trim();
+
+    if let Some(percent) = s.strip_suffix('%') {
+        Ok(parse_number(percent)? / 100.0)
+    } else if let Some(basis_points) = s.strip_suffix("bps").or_else(|| s.strip_suffix("bp")) {
+        Ok(parse_number(basis_points)? / 10_000.0)
+    } else {
+        parse_number(s)
+    }
+}
+
+/// Parses a plain number.
+fn parse_number(s: &str) -> Result<f64, RustQuantError> {
+    s.trim()
+        .parse()
+        .map_err(|_| RustQuantError::InvalidArgument(format!("Cannot parse '{s}' as a number.")))
+}
+
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+// UNIT TESTS
+// ~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~
+
+#[cfg(test)]
+mod tests_units {
+    use super::*;
+
+    #[test]
+    fn test_range_checks() {
+        assert!(Rate::new(0.05).is_ok());
+        assert!(Rate::new(-0.005).is_ok());
+        assert!(Rate::new(1.5).is_err());
+        assert!(Rate::new(f64::NAN).is_err());
+        assert_eq!(Rate::new_unchecked(1.5).value(), 1.5);
+
+        assert!(Volatility::new(0.0).is_ok());
+        assert!(Volatility::new(-0.2).is_err());
+        assert!(Volatility::new(f64::INFINITY).is_err());
+
+        assert!(Price::new(-3.0).is_ok());
+        assert!(Price::new(f64::NAN).is_err());
+
+        assert!(DividendYield::new(1.0).is_err());
+        assert!(TimeFraction::new(-1.0).is_err());
+    }
+
+    #[test]
+    fn test_parsing() -> Result<(), RustQuantError> {
+        assert_eq!("5%".parse::<Rate>()?, Rate::new(0.05)?);
+        assert_eq!(" 25bp ".parse::<Rate>()?, Rate::new(0.0025)?);
+        assert_eq!("25bps".parse::<Rate>()?, Rate::from_basis_points(25.0)?);
+        assert_eq!("-0.5%".parse::<Rate>()?, Rate::new(-0.005)?);
+        assert_eq!(
+            "20%".parse::<Volatility>()?,
+            Volatility::from_percent(20.0)?
+        );
+        assert_eq!("1.5%".parse::<DividendYield>()?.value(), 0.015);
+        assert_eq!("101.25".parse::<Price>()?.value(), 101.25);
+        assert_eq!("0.5".parse::<TimeFraction>()?.value(), 0.5);
+
+        assert!("150%".parse::<Rate>().is_err());
+        assert!("-20%".parse::<Volatility>().is_err());
+        assert!("5 percent".parse::<Rate>().is_err());
+        assert!("10%".parse::<Price>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_parsing_round_trips() -> Result<(), RustQuantError> {
+        for value in [0.05, 0.0025, -0.0137, 0.1 + 0.2, 1.0 / 3.0] {
+            let rate = Rate::new(value)?;
+            assert_eq!(rate.to_string().parse::<Rate>()?, rate);
+        }
+        for value in [0.0, 0.2, 0.473_219_5] {
+            let volatility = Volatility::new(value)?;
+            assert_eq!(volatility.to_string().parse::<Volatility>()?, volatility);
+        }
+        let price = Price::new(99.875_123)?;
+        assert_eq!(price.to_string().parse::<Price>()?, price);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        let rate = Rate::from(0.05);
+        let dividend_yield = DividendYield::from(0.02);
+
+        assert_eq!((rate - dividend_yield).value(), 0.05 - 0.02);
+        assert_eq!((rate + rate).value(), 0.1);
+        assert_eq!((-rate).value(), -0.05);
+        assert_eq!((Price::from(100.0) * 0.5).value(), 50.0);
+        assert_eq!((2.0 * Volatility::from(0.1)).value(), 0.2);
+        assert_eq!((TimeFraction::from(1.0) / 4.0).value(), 0.25);
+        assert_eq!(f64::from(Price::from(42.0)), 42.0);
+    }
+}
@@ -0,0 +1,57 @@
+"""Reads the Arrow IPC files written by RustQuant with pyarrow.
+
+The files are written by the `arrow_ipc_fixtures` example:
+
+    pytest tests/python/test_arrow_ipc.py
+"""
+
+import datetime
+import pathlib
+import subprocess
+
+import pyarrow.feather as feather
+import pytest
+
+REPO = pathlib.Path(__file__).resolve().parents[2]
+
+
+@pytest.fixture(scope="module")
+def fixtures(tmp_path_factory):
+    out = tmp_path_factory.mktemp("arrow_ipc")
+    subprocess.run(
+        ["cargo", "run", "--quiet", "--example", "arrow_ipc_fixtures", "--", str(out)],
+        cwd=REPO,
+        check=True,
+    )
+    return out
+
+
+def test_paths(fixtures):
+    table = feather.read_table(fixtures / "paths.arrow")
+
+    assert table.num_rows == 101
+    assert table.column_names == ["time"] + [f"path_{j}" for j in range(5)]
+    assert table.column("time")[50].as_py() == pytest.approx(0.5)
+    assert table.column("path_3")[50].as_py() == pytest.approx(101.5)
+    assert table.column("path_4")[100].as_py() == pytest.approx(104.0)
+
+
+def test_monte_carlo_result(fixtures):
+    row = feather.read_table(fixtures / "mc_result.arrow").to_pylist()[0]
+
+    assert row["price"] == pytest.approx(10.45)
+    assert row["n_paths"] == 100_000
+    assert row["variance_reduction_factor"] is None
+    assert row["converged"] is True
+    assert row["trace_paths"] == [50_000, 100_000]
+    assert row["trace_estimates"] == pytest.approx([10.47, 10.45])
+
+
+def test_backtest(fixtures):
+    equity = feather.read_table(fixtures / "equity.arrow")
+    assert equity.column("equity").to_pylist() == [10_000.0, 10_012.5, 9_998.75]
+
+    fills = feather.read_table(fixtures / "fills.arrow").to_pylist()
+    assert [fill["side"] for fill in fills] == ["BID", "ASK"]
+    assert fills[0]["quantity"] == 10
+    assert fills[0]["timestamp"] == datetime.datetime(2024, 1, 2, 14, 30)